@@ -0,0 +1,8 @@
+CREATE TABLE recipe_view (
+	recipe_id INTEGER NOT NULL REFERENCES recipe ("recipe_id"),
+	viewed_on DATE NOT NULL,
+	view_count BIGINT NOT NULL CHECK ("view_count" > 0),
+	PRIMARY KEY (recipe_id, viewed_on)
+);
+
+CREATE INDEX recipe_view_viewed_on_idx ON recipe_view (viewed_on);
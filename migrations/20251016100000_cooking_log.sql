@@ -0,0 +1,9 @@
+CREATE TABLE cooking_log (
+	cooking_log_id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+	recipe_id INTEGER NOT NULL REFERENCES recipe ("recipe_id"),
+	cooked_on DATE NOT NULL,
+	note TEXT CHECK ("note" <> ''),
+	logged_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX cooking_log_recipe_id_cooked_on_idx ON cooking_log (recipe_id, cooked_on DESC);
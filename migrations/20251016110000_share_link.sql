@@ -0,0 +1,9 @@
+CREATE TABLE share_link (
+	share_link_id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+	recipe_id INTEGER NOT NULL REFERENCES recipe ("recipe_id"),
+	token TEXT NOT NULL UNIQUE,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+	revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX share_link_recipe_id_idx ON share_link (recipe_id);
@@ -0,0 +1,4 @@
+ALTER TABLE recipe
+	ADD COLUMN video_url TEXT,
+	ADD COLUMN video_title TEXT,
+	ADD COLUMN video_thumbnail_url TEXT;
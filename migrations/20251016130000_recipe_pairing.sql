@@ -0,0 +1,5 @@
+CREATE TYPE pairing_kind AS ENUM ('Wine', 'Beer', 'NonAlcoholic');
+
+ALTER TABLE recipe
+	ADD COLUMN pairing TEXT,
+	ADD COLUMN pairing_kind pairing_kind;
@@ -0,0 +1,3 @@
+ALTER TABLE recipe ADD COLUMN forked_from INTEGER REFERENCES recipe ("recipe_id");
+
+CREATE INDEX recipe_forked_from_idx ON recipe (forked_from);
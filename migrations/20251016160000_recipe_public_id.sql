@@ -0,0 +1 @@
+ALTER TABLE recipe ADD COLUMN public_id UUID NOT NULL UNIQUE DEFAULT gen_random_uuid();
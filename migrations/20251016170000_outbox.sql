@@ -0,0 +1,14 @@
+CREATE TYPE recipe_event_kind AS ENUM ('Created', 'Updated', 'Deleted');
+
+-- Events are written in the same transaction as the change they describe and relayed to
+-- subscribers afterwards. `recipe_id` has no foreign key as deleted recipes keep their events.
+CREATE TABLE outbox (
+	outbox_id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+	kind recipe_event_kind NOT NULL,
+	recipe_id INTEGER NOT NULL,
+	public_id UUID NOT NULL,
+	occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+	published_at TIMESTAMPTZ
+);
+
+CREATE INDEX outbox_unpublished_idx ON outbox (outbox_id) WHERE published_at IS NULL;
@@ -0,0 +1,23 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+-- UNACCENT itself is only stable, since it depends on the search path to find its dictionary.
+-- Index expressions have to be immutable, so searches go through this wrapper which pins the
+-- dictionary instead. The schema is looked up, as the extension may live outside of `public`.
+DO $$
+DECLARE
+	unaccent_schema TEXT := (
+		SELECT extnamespace::REGNAMESPACE::TEXT FROM pg_extension WHERE extname = 'unaccent'
+	);
+BEGIN
+	EXECUTE format(
+		'CREATE FUNCTION immutable_unaccent(TEXT) RETURNS TEXT
+			LANGUAGE SQL IMMUTABLE PARALLEL SAFE STRICT
+			RETURN %s.unaccent(%L::REGDICTIONARY, $1)',
+		unaccent_schema,
+		unaccent_schema || '.unaccent'
+	);
+END;
+$$;
+
+CREATE INDEX recipe_name_trgm_idx ON recipe USING GIN (immutable_unaccent(name) gin_trgm_ops);
+CREATE INDEX ingredient_name_trgm_idx ON ingredient USING GIN (immutable_unaccent(name) gin_trgm_ops);
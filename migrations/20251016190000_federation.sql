@@ -0,0 +1,22 @@
+CREATE TABLE remote_instance (
+	remote_instance_id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+	url TEXT NOT NULL UNIQUE CHECK ("url" <> ''),
+	-- Encrypted by the application with the configured federation key
+	token BYTEA,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+	last_synced_at TIMESTAMPTZ
+);
+
+-- Recipes copied from a remote instance. `recipe_id` is cleared when the copy is deleted locally,
+-- and `detached` is set once it is edited locally or removed from the remote, both of which keep
+-- later syncs from touching the recipe again.
+CREATE TABLE mirrored_recipe (
+	remote_instance_id INTEGER NOT NULL REFERENCES remote_instance ("remote_instance_id"),
+	remote_public_id UUID NOT NULL,
+	recipe_id INTEGER UNIQUE REFERENCES recipe ("recipe_id"),
+	fingerprint BIGINT NOT NULL,
+	detached BOOLEAN NOT NULL DEFAULT FALSE,
+	PRIMARY KEY (remote_instance_id, remote_public_id)
+);
+
+ALTER TABLE recipe ADD COLUMN federate BOOLEAN NOT NULL DEFAULT TRUE;
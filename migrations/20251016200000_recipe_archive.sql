@@ -0,0 +1,2 @@
+-- Archived recipes are kept but left out of listings and searches unless asked for
+ALTER TABLE recipe ADD COLUMN archived_at TIMESTAMPTZ;
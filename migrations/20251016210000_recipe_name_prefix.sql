@@ -0,0 +1,3 @@
+-- Serves prefix lookups for autocomplete, which the trigram index can't answer in name order
+CREATE INDEX recipe_name_prefix_idx ON recipe (LOWER(immutable_unaccent(name)) text_pattern_ops)
+    WHERE archived_at IS NULL;
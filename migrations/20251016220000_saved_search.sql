@@ -0,0 +1,6 @@
+CREATE TABLE saved_search (
+	saved_search_id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+	name TEXT NOT NULL CHECK ("name" <> ''),
+	criteria JSONB NOT NULL,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
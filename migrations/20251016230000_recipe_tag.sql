@@ -0,0 +1,8 @@
+-- Tags are stored lowercased, so `Vegetarian` and `vegetarian` are the same tag
+CREATE TABLE recipe_tag (
+	recipe_id INTEGER NOT NULL REFERENCES recipe ("recipe_id"),
+	name TEXT NOT NULL CHECK ("name" <> '' AND "name" = LOWER("name")),
+	PRIMARY KEY (recipe_id, name)
+);
+
+CREATE INDEX recipe_tag_name_idx ON recipe_tag (name);
@@ -0,0 +1,7 @@
+-- Copies of every recipe taken at a single point in time, in the format the importer reads
+CREATE TABLE snapshot (
+	snapshot_id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+	recipe_count INTEGER NOT NULL,
+	recipes JSONB NOT NULL
+);
@@ -0,0 +1,12 @@
+-- Who deleted which recipe, kept after the recipe itself is gone
+CREATE TABLE recipe_deletion (
+	deletion_id INTEGER GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+	recipe_id INTEGER NOT NULL,
+	recipe_name TEXT NOT NULL,
+	deleted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+	actor TEXT,
+	ip_address TEXT,
+	reason TEXT
+);
+
+CREATE INDEX recipe_deletion_deleted_at_idx ON recipe_deletion (deleted_at);
@@ -0,0 +1,14 @@
+-- Anonymous usage per day. Only counts are stored, nothing links them to who made the requests.
+CREATE TABLE route_usage (
+	route TEXT NOT NULL,
+	used_on DATE NOT NULL,
+	request_count BIGINT NOT NULL,
+	PRIMARY KEY (route, used_on)
+);
+
+CREATE TABLE search_term_usage (
+	term TEXT NOT NULL,
+	used_on DATE NOT NULL,
+	search_count BIGINT NOT NULL,
+	PRIMARY KEY (term, used_on)
+);
@@ -0,0 +1,62 @@
+-- When recipes were created and last changed. Recipes created before count as created now.
+ALTER TABLE recipe
+	ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+	ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+CREATE INDEX recipe_created_at_idx ON recipe (created_at);
+CREATE INDEX recipe_updated_at_idx ON recipe (updated_at);
+
+-- Updates setting updated_at themselves keep the value they set
+CREATE FUNCTION touch_recipe() RETURNS TRIGGER
+    LANGUAGE plpgsql
+AS $$
+BEGIN
+	IF NEW.updated_at = OLD.updated_at THEN
+		NEW.updated_at = NOW();
+	END IF;
+	RETURN NEW;
+END;
+$$;
+
+-- Changes to the ingredients and tags of a recipe count as changes to the recipe. Recipes are
+-- touched once per statement, rather than once for every ingredient or tag written.
+CREATE FUNCTION touch_parent_recipe() RETURNS TRIGGER
+    LANGUAGE plpgsql
+AS $$
+BEGIN
+	IF TG_OP IN ('INSERT', 'UPDATE') THEN
+		UPDATE recipe SET updated_at = NOW() WHERE recipe_id IN (SELECT recipe_id FROM new_rows);
+	END IF;
+	IF TG_OP IN ('UPDATE', 'DELETE') THEN
+		UPDATE recipe SET updated_at = NOW() WHERE recipe_id IN (SELECT recipe_id FROM old_rows);
+	END IF;
+	RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER recipe_touch BEFORE UPDATE ON recipe
+	FOR EACH ROW EXECUTE FUNCTION touch_recipe();
+
+CREATE TRIGGER ingredient_insert_touch_recipe AFTER INSERT ON ingredient
+	REFERENCING NEW TABLE AS new_rows
+	FOR EACH STATEMENT EXECUTE FUNCTION touch_parent_recipe();
+
+CREATE TRIGGER ingredient_update_touch_recipe AFTER UPDATE ON ingredient
+	REFERENCING OLD TABLE AS old_rows NEW TABLE AS new_rows
+	FOR EACH STATEMENT EXECUTE FUNCTION touch_parent_recipe();
+
+CREATE TRIGGER ingredient_delete_touch_recipe AFTER DELETE ON ingredient
+	REFERENCING OLD TABLE AS old_rows
+	FOR EACH STATEMENT EXECUTE FUNCTION touch_parent_recipe();
+
+CREATE TRIGGER recipe_tag_insert_touch_recipe AFTER INSERT ON recipe_tag
+	REFERENCING NEW TABLE AS new_rows
+	FOR EACH STATEMENT EXECUTE FUNCTION touch_parent_recipe();
+
+CREATE TRIGGER recipe_tag_update_touch_recipe AFTER UPDATE ON recipe_tag
+	REFERENCING OLD TABLE AS old_rows NEW TABLE AS new_rows
+	FOR EACH STATEMENT EXECUTE FUNCTION touch_parent_recipe();
+
+CREATE TRIGGER recipe_tag_delete_touch_recipe AFTER DELETE ON recipe_tag
+	REFERENCING OLD TABLE AS old_rows
+	FOR EACH STATEMENT EXECUTE FUNCTION touch_parent_recipe();
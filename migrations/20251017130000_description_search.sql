@@ -0,0 +1 @@
+CREATE INDEX recipe_description_trgm_idx ON recipe USING GIN (immutable_unaccent(description) gin_trgm_ops);
@@ -0,0 +1,22 @@
+-- Grams per milliliter of an ingredient, to convert between volume and mass. Names are lowercase.
+CREATE TABLE ingredient_density (
+	name TEXT PRIMARY KEY,
+	grams_per_milliliter REAL NOT NULL CHECK (grams_per_milliliter > 0)
+);
+
+INSERT INTO ingredient_density (name, grams_per_milliliter) VALUES
+	('water', 1.0),
+	('milk', 1.03),
+	('cream', 1.01),
+	('yogurt', 1.03),
+	('flour', 0.53),
+	('sugar', 0.85),
+	('brown sugar', 0.93),
+	('powdered sugar', 0.56),
+	('butter', 0.91),
+	('oil', 0.92),
+	('honey', 1.42),
+	('salt', 1.22),
+	('rice', 0.85),
+	('oats', 0.41),
+	('cocoa powder', 0.42);
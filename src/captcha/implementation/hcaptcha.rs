@@ -0,0 +1,69 @@
+use std::{sync::Arc, time::Duration};
+
+use eyre::Context;
+use reqwest::Client;
+use secrecy::{ExposeSecret, SecretBox};
+use serde::Deserialize;
+
+use crate::captcha::verifier::{CaptchaVerifier, VerifyCaptchaError};
+
+const VERIFY_URL: &str = "https://api.hcaptcha.com/siteverify";
+
+/// How long to wait on hCaptcha before failing the request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Verifies tokens of solved [hCaptcha](https://www.hcaptcha.com) challenges
+#[derive(Debug, Clone)]
+pub struct Hcaptcha {
+    client: Client,
+    secret: Arc<SecretBox<str>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SiteverifyResponse {
+    success: bool,
+    #[serde(rename = "error-codes", default)]
+    error_codes: Vec<String>,
+}
+
+impl Hcaptcha {
+    /// Creates a verifier using the secret key of an hCaptcha account
+    pub fn new(secret: SecretBox<str>) -> eyre::Result<Self> {
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .wrap_err("Failed to build HTTP client")?;
+
+        Ok(Self {
+            client,
+            secret: Arc::new(secret),
+        })
+    }
+}
+
+impl CaptchaVerifier for Hcaptcha {
+    async fn verify(&self, token: &str, remote_ip: Option<&str>) -> Result<(), VerifyCaptchaError> {
+        let mut form = vec![("secret", self.secret.expose_secret()), ("response", token)];
+        form.extend(remote_ip.map(|ip| ("remoteip", ip)));
+
+        let body = self
+            .client
+            .post(VERIFY_URL)
+            .form(&form)
+            .send()
+            .await
+            .wrap_err("Failed to reach hCaptcha")?
+            .error_for_status()
+            .wrap_err("hCaptcha returned an error")?
+            .json::<SiteverifyResponse>()
+            .await
+            .wrap_err("Failed to parse hCaptcha response")?;
+
+        if !body.success {
+            tracing::debug!(error_codes = ?body.error_codes, "CAPTCHA token was rejected");
+            return Err(VerifyCaptchaError::Rejected);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use std::{fmt, str::FromStr};
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum VerifyCaptchaError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The CAPTCHA was not solved")]
+    Rejected,
+}
+
+pub trait CaptchaVerifier: std::fmt::Debug + Clone + Send + Sync + 'static {
+    /// Checks the token a client got for solving a CAPTCHA. The address of the client is passed
+    /// along when known, which providers use as an extra signal.
+    fn verify(
+        &self,
+        token: &str,
+        remote_ip: Option<&str>,
+    ) -> impl Future<Output = Result<(), VerifyCaptchaError>> + Send;
+}
+
+/// Group of write endpoints which can be put behind a CAPTCHA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndpointGroup {
+    /// Creating, changing and deleting recipes, along with saved searches
+    Recipes,
+    /// Sharing recipes by email or link
+    Sharing,
+    /// Importing recipes from photos and generating them
+    Imports,
+    /// Uploading offline changes
+    Sync,
+    /// Every other write, like renaming ingredients, generating meal plans or taking snapshots
+    Other,
+}
+
+impl EndpointGroup {
+    /// Group of a route pattern like `/recipes/{recipe_id}/share`. Routes outside the named groups
+    /// fall in [`Other`](Self::Other), so new routes are gated until they are put in a group.
+    /// Routes reserved to admins give `None`, as they are authenticated with the admin token.
+    pub fn of(pattern: &str) -> Option<Self> {
+        let segments = pattern.trim_matches('/').split('/').collect::<Vec<_>>();
+
+        match segments.as_slice() {
+            ["federation", "instances", ..] => None,
+            ["recipes", "generate"] | ["recipes", "import", ..] => Some(Self::Imports),
+            ["recipes", _, "share" | "share-link" | "share-links", ..] => Some(Self::Sharing),
+            ["recipes", ..] | ["searches", ..] => Some(Self::Recipes),
+            ["sync"] => Some(Self::Sync),
+            _ => Some(Self::Other),
+        }
+    }
+}
+
+impl fmt::Display for EndpointGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EndpointGroup::Recipes => write!(f, "recipes"),
+            EndpointGroup::Sharing => write!(f, "sharing"),
+            EndpointGroup::Imports => write!(f, "imports"),
+            EndpointGroup::Sync => write!(f, "sync"),
+            EndpointGroup::Other => write!(f, "other"),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error(
+    "`{0}` is not an endpoint group, expected one of `recipes`, `sharing`, `imports`, `sync`, `other`"
+)]
+pub struct ParseEndpointGroupError(String);
+
+impl FromStr for EndpointGroup {
+    type Err = ParseEndpointGroupError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "recipes" => Ok(Self::Recipes),
+            "sharing" => Ok(Self::Sharing),
+            "imports" => Ok(Self::Imports),
+            "sync" => Ok(Self::Sync),
+            "other" => Ok(Self::Other),
+            _ => Err(ParseEndpointGroupError(value.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn it_groups_route_patterns() {
+        check!(EndpointGroup::of("/recipes") == Some(EndpointGroup::Recipes));
+        check!(EndpointGroup::of("/recipes/{recipe_id}/tags") == Some(EndpointGroup::Recipes));
+        check!(EndpointGroup::of("/searches/{saved_search_id}") == Some(EndpointGroup::Recipes));
+        check!(EndpointGroup::of("/recipes/{recipe_id}/share") == Some(EndpointGroup::Sharing));
+        check!(
+            EndpointGroup::of("/recipes/{recipe_id}/share-links/{share_link_id}")
+                == Some(EndpointGroup::Sharing)
+        );
+        check!(EndpointGroup::of("/recipes/import/image") == Some(EndpointGroup::Imports));
+        check!(EndpointGroup::of("/recipes/generate") == Some(EndpointGroup::Imports));
+        check!(EndpointGroup::of("/sync") == Some(EndpointGroup::Sync));
+    }
+
+    #[test]
+    fn it_puts_unknown_routes_in_other() {
+        check!(EndpointGroup::of("/admin/snapshots") == Some(EndpointGroup::Other));
+        check!(EndpointGroup::of("/meal-plans/generate") == Some(EndpointGroup::Other));
+        check!(EndpointGroup::of("/not-yet-written") == Some(EndpointGroup::Other));
+        check!(EndpointGroup::of("/federation/instances/{remote_instance_id}").is_none());
+    }
+}
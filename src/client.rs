@@ -0,0 +1,253 @@
+use std::{io::Read, path::PathBuf, time::Duration};
+
+use eyre::{Context, bail};
+use reqwest::{Client, RequestBuilder, StatusCode, Url};
+use secrecy::{ExposeSecret, SecretBox};
+
+use crate::{
+    core::{
+        import::draft_from_text,
+        recipe::{Ingredient, MealType, Pairing, format_cooking_time},
+    },
+    presentation::recipe::{NewRecipeDto, RecipeDto},
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+/// Configuration used to talk to a running instance
+pub struct ClientConfig {
+    /// Url the instance is reachable on, like `https://recipes.example.com`
+    pub server: String,
+    /// Token sent as bearer authentication, for instances behind an authenticating proxy
+    pub token: Option<SecretBox<str>>,
+}
+
+#[derive(Debug)]
+pub enum ClientCommand {
+    /// Lists all recipes
+    List,
+    /// Searches recipes by (part of) their name or an ingredient
+    Search {
+        recipe_name: Option<String>,
+        ingredient_name: Option<String>,
+    },
+    /// Shows a single recipe by its id or public id
+    Show { recipe_id: String },
+    /// Adds a recipe written as plain text, read from a file or stdin when missing
+    Add { file: Option<PathBuf> },
+}
+
+/// Runs a single command against the instance, printing the result to stdout
+pub async fn client(config: ClientConfig, command: ClientCommand) -> eyre::Result<()> {
+    let client = ApiClient::new(&config.server, config.token)?;
+
+    match command {
+        ClientCommand::List => {
+            let recipes = client
+                .send::<Vec<RecipeDto>>(client.get("recipes")?)
+                .await?;
+            print!("{}", format_recipe_list(&recipes));
+        }
+        ClientCommand::Search {
+            recipe_name,
+            ingredient_name,
+        } => {
+            let request = client.get("recipes/search")?.query(&[
+                ("recipe_name", recipe_name),
+                ("ingredient_name", ingredient_name),
+            ]);
+            let recipes = client.send::<Vec<RecipeDto>>(request).await?;
+            print!("{}", format_recipe_list(&recipes));
+        }
+        ClientCommand::Show { recipe_id } => {
+            let recipe = client
+                .send::<RecipeDto>(client.get(&format!("recipes/{recipe_id}"))?)
+                .await?;
+            print!("{}", format_recipe(recipe));
+        }
+        ClientCommand::Add { file } => {
+            let text = match file {
+                Some(file) => std::fs::read_to_string(&file)
+                    .wrap_err_with(|| format!("Failed to read {}", file.display()))?,
+                None => {
+                    let mut text = String::new();
+                    std::io::stdin()
+                        .read_to_string(&mut text)
+                        .wrap_err("Failed to read stdin")?;
+                    text
+                }
+            };
+
+            let draft = NewRecipeDto::from(draft_from_text(&text));
+            let recipe = client
+                .send::<RecipeDto>(client.post("recipes")?.json(&draft))
+                .await?;
+            println!("Added recipe {}: {}", recipe.recipe_id, recipe.name);
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Debug)]
+struct ApiClient {
+    client: Client,
+    base_url: Url,
+    token: Option<SecretBox<str>>,
+}
+
+impl ApiClient {
+    fn new(server: &str, token: Option<SecretBox<str>>) -> eyre::Result<Self> {
+        // Without the trailing slash joining would replace the last segment of the path
+        let base_url = format!("{}/", server.trim_end_matches('/'))
+            .parse()
+            .wrap_err("Failed to parse server url")?;
+
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .wrap_err("Failed to build HTTP client")?;
+
+        Ok(Self {
+            client,
+            base_url,
+            token,
+        })
+    }
+
+    fn get(&self, path: &str) -> eyre::Result<RequestBuilder> {
+        Ok(self.authenticate(self.client.get(self.url(path)?)))
+    }
+
+    fn post(&self, path: &str) -> eyre::Result<RequestBuilder> {
+        Ok(self.authenticate(self.client.post(self.url(path)?)))
+    }
+
+    fn url(&self, path: &str) -> eyre::Result<Url> {
+        self.base_url
+            .join(path)
+            .wrap_err_with(|| format!("Failed to build url for `{path}`"))
+    }
+
+    fn authenticate(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.token {
+            Some(token) => request.bearer_auth(token.expose_secret()),
+            None => request,
+        }
+    }
+
+    async fn send<T: serde::de::DeserializeOwned>(
+        &self,
+        request: RequestBuilder,
+    ) -> eyre::Result<T> {
+        let response = request
+            .send()
+            .await
+            .wrap_err("Failed to reach the server")?;
+
+        match response.status() {
+            status if status.is_success() => {
+                response.json().await.wrap_err("Failed to parse response")
+            }
+            StatusCode::NOT_FOUND => bail!("The recipe could not be found"),
+            status => {
+                let body = response.text().await.unwrap_or_default();
+                bail!("The server answered {status}: {body}")
+            }
+        }
+    }
+}
+
+fn format_recipe_list(recipes: &[RecipeDto]) -> String {
+    recipes
+        .iter()
+        .map(|recipe| {
+            format!(
+                "{:>5}  {}  ({})\n",
+                recipe.recipe_id,
+                recipe.name,
+                MealType::from(recipe.meal_type)
+            )
+        })
+        .collect()
+}
+
+fn format_recipe(recipe: RecipeDto) -> String {
+    let mut out = format!("{}\n", recipe.name);
+
+    out.push_str(&format!(
+        "\nMeal type: {}\n",
+        MealType::from(recipe.meal_type)
+    ));
+
+    if let Some(cooking_time) = recipe.cooking_time {
+        out.push_str(&format!(
+            "Cooking time: {}\n",
+            format_cooking_time(cooking_time)
+        ));
+    }
+
+    if let Some(pairing) = recipe.pairing {
+        out.push_str(&format!("Serve with: {}\n", Pairing::from(pairing)));
+    }
+
+    if !recipe.ingredients.is_empty() {
+        out.push_str("\nIngredients\n");
+
+        for ingredient in recipe.ingredients {
+            out.push_str(&format!("- {}\n", Ingredient::from(ingredient)));
+        }
+    }
+
+    if let Some(description) = &recipe.description {
+        out.push_str(&format!("\n{description}\n"));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::check;
+
+    use crate::presentation::recipe::{IngredientDto, MealType, QuantityType};
+
+    fn recipe() -> RecipeDto {
+        RecipeDto {
+            recipe_id: 7,
+            name: "Pancakes".to_string(),
+            description: Some("Whisk and fry.".to_string()),
+            ingredients: vec![IngredientDto {
+                name: "Flour".to_string(),
+                quantity_type: QuantityType::Gram,
+                quantity: 250.0,
+            }],
+            cooking_time: Some(Duration::from_secs(20 * 60)),
+            cooking_time_minutes: Some(20),
+            meal_type: MealType::Breakfast,
+            last_cooked_at: None,
+            video_url: None,
+            video_title: None,
+            video_thumbnail_url: None,
+            pairing: None,
+            forked_from: None,
+            public_id: uuid::Uuid::nil(),
+        }
+    }
+
+    #[test]
+    fn it_formats_a_recipe_for_the_terminal() {
+        check!(
+            format_recipe(recipe())
+                == "Pancakes\n\nMeal type: Breakfast\nCooking time: 20 min\n\nIngredients\n- 250 g Flour\n\nWhisk and fry.\n"
+        );
+    }
+
+    #[test]
+    fn it_lists_one_recipe_per_line() {
+        check!(format_recipe_list(&[recipe()]) == "    7  Pancakes  (Breakfast)\n");
+    }
+}
@@ -0,0 +1,259 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{Days, NaiveDate, Utc};
+use thiserror::Error;
+
+use crate::persistance::analytics::{AnalyticsRepository, UsageCountEntity, UsageEntity};
+
+/// Search terms searched for less often than this aren't reported, so a term only one person
+/// searched for doesn't point back at them
+pub const MIN_REPORTED_SEARCHES: i64 = 3;
+
+/// Longest search term which is counted, in characters. Longer terms are more likely to be
+/// something personal pasted into the search than a search term.
+const MAX_SEARCH_TERM_LENGTH: usize = 64;
+
+#[derive(Debug, Clone)]
+pub struct AnalyticsService<AR: AnalyticsRepository> {
+    repository: AR,
+    enabled: bool,
+    /// Usage recorded since the last flush, buffered like recipe views
+    pending: Arc<Mutex<PendingUsage>>,
+}
+
+#[derive(Debug, Default)]
+struct PendingUsage {
+    routes: HashMap<String, i64>,
+    search_terms: HashMap<String, i64>,
+}
+
+/// How often a route was requested, or a term searched for
+#[derive(Debug)]
+pub struct UsageCount {
+    pub key: String,
+    pub count: u64,
+}
+
+impl From<UsageCountEntity> for UsageCount {
+    fn from(value: UsageCountEntity) -> Self {
+        Self {
+            key: value.key,
+            count: value.count as u64,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Usage {
+    /// First day counted
+    pub since: NaiveDate,
+    /// Routes like `GET /recipes/{id}`, most requested first
+    pub routes: Vec<UsageCount>,
+    /// Normalized search terms, most searched first
+    pub search_terms: Vec<UsageCount>,
+}
+
+#[derive(Debug, Error)]
+pub enum FlushUsageError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::analytics::RecordUsageError> for FlushUsageError {
+    fn from(value: crate::persistance::analytics::RecordUsageError) -> Self {
+        match value {
+            crate::persistance::analytics::RecordUsageError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GetUsageError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::analytics::GetUsageError> for GetUsageError {
+    fn from(value: crate::persistance::analytics::GetUsageError) -> Self {
+        match value {
+            crate::persistance::analytics::GetUsageError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+/// Lowercases a search term and collapses its whitespace, so the same search typed differently is
+/// counted once. Gives `None` for terms which aren't counted.
+fn normalize_search_term(term: &str) -> Option<String> {
+    let term = term
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+
+    (!term.is_empty() && term.chars().count() <= MAX_SEARCH_TERM_LENGTH).then_some(term)
+}
+
+impl<AR: AnalyticsRepository> AnalyticsService<AR> {
+    /// Creates the service, recording nothing unless `enabled` is set. Recorded usage can be
+    /// reported either way.
+    pub fn new(repository: AR, enabled: bool) -> Self {
+        Self {
+            repository,
+            enabled,
+            pending: Arc::default(),
+        }
+    }
+
+    /// Counts a request to a route, like `GET /recipes/{id}`
+    pub fn record_route(&self, route: &str) {
+        if !self.enabled {
+            return;
+        }
+
+        *self
+            .pending
+            .lock()
+            .expect("Pending usage lock poisoned")
+            .routes
+            .entry(route.to_string())
+            .or_default() += 1;
+    }
+
+    /// Counts a search for a term
+    pub fn record_search(&self, term: &str) {
+        if !self.enabled {
+            return;
+        }
+
+        let Some(term) = normalize_search_term(term) else {
+            return;
+        };
+
+        *self
+            .pending
+            .lock()
+            .expect("Pending usage lock poisoned")
+            .search_terms
+            .entry(term)
+            .or_default() += 1;
+    }
+
+    /// Writes the buffered usage to the repository in a single batch. When writing fails, the
+    /// usage is put back into the buffer so the next flush can retry it.
+    pub async fn flush(&self) -> Result<(), FlushUsageError> {
+        let usage = std::mem::take(&mut *self.pending.lock().expect("Pending usage lock poisoned"));
+
+        if usage.routes.is_empty() && usage.search_terms.is_empty() {
+            return Ok(());
+        }
+
+        let routes = usage
+            .routes
+            .iter()
+            .map(|(route, count)| (route.clone(), *count))
+            .collect();
+        let search_terms = usage
+            .search_terms
+            .iter()
+            .map(|(term, count)| (term.clone(), *count))
+            .collect();
+
+        if let Err(error) = self.repository.record_usage(routes, search_terms).await {
+            let mut pending = self.pending.lock().expect("Pending usage lock poisoned");
+
+            for (route, count) in usage.routes {
+                *pending.routes.entry(route).or_default() += count;
+            }
+            for (term, count) in usage.search_terms {
+                *pending.search_terms.entry(term).or_default() += count;
+            }
+
+            return Err(error.into());
+        }
+
+        Ok(())
+    }
+
+    /// Usage over the last `days` days including today, at most `limit` routes and search terms
+    pub async fn usage(&self, days: u32, limit: u32) -> Result<Usage, GetUsageError> {
+        let since = Utc::now()
+            .date_naive()
+            .checked_sub_days(Days::new(days.saturating_sub(1) as u64))
+            .unwrap_or(NaiveDate::MIN);
+
+        let UsageEntity {
+            routes,
+            search_terms,
+        } = self
+            .repository
+            .get_usage(since, MIN_REPORTED_SEARCHES, limit as i64)
+            .await?;
+
+        Ok(Usage {
+            since,
+            routes: routes.into_iter().map(UsageCount::from).collect(),
+            search_terms: search_terms.into_iter().map(UsageCount::from).collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+
+    use crate::test_utils::MockRepository;
+
+    #[test]
+    fn it_normalizes_search_terms() {
+        check!(normalize_search_term("  Tomato   SOUP ") == Some("tomato soup".to_string()));
+        check!(normalize_search_term("   ").is_none());
+        check!(normalize_search_term(&"a".repeat(65)).is_none());
+    }
+
+    #[tokio::test]
+    async fn it_flushes_aggregated_usage() {
+        let repository = MockRepository::new();
+        repository.on_record_usage(Ok(()));
+        let svc = AnalyticsService::new(repository.clone(), true);
+
+        svc.record_route("GET /recipes");
+        svc.record_route("GET /recipes");
+        svc.record_search("Soup");
+        svc.record_search("soup ");
+
+        let_assert!(Ok(()) = svc.flush().await);
+        let calls = repository.calls_to("record_usage");
+        check!(calls[0].arguments == r#"([("GET /recipes", 2)], [("soup", 2)])"#);
+
+        // Nothing left to write
+        let_assert!(Ok(()) = svc.flush().await);
+        check!(repository.calls_to("record_usage").len() == 1);
+    }
+
+    #[tokio::test]
+    async fn it_records_nothing_when_disabled() {
+        let repository = MockRepository::new();
+        let svc = AnalyticsService::new(repository.clone(), false);
+
+        svc.record_route("GET /recipes");
+        svc.record_search("soup");
+
+        let_assert!(Ok(()) = svc.flush().await);
+        check!(repository.calls().is_empty());
+    }
+}
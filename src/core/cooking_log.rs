@@ -0,0 +1,111 @@
+use chrono::{Local, NaiveDate};
+use thiserror::Error;
+
+use crate::persistance::cooking_log::{
+    CookingLogEntity, CookingLogRepository, NewCookingLogEntity,
+};
+
+#[derive(Debug, Clone)]
+pub struct CookingLogService<CR: CookingLogRepository> {
+    repository: CR,
+}
+
+#[derive(Debug)]
+pub struct CookingLog {
+    pub cooking_log_id: i32,
+    pub recipe_id: i32,
+    pub cooked_on: NaiveDate,
+    pub note: Option<String>,
+}
+
+impl From<CookingLogEntity> for CookingLog {
+    fn from(value: CookingLogEntity) -> Self {
+        Self {
+            cooking_log_id: value.cooking_log_id,
+            recipe_id: value.recipe_id,
+            cooked_on: value.cooked_on,
+            note: value.note,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct NewCookingLog {
+    /// Day the recipe was cooked on, defaults to today
+    pub cooked_on: Option<NaiveDate>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum LogCookingError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::persistance::cooking_log::LogCookingError> for LogCookingError {
+    fn from(value: crate::persistance::cooking_log::LogCookingError) -> Self {
+        match value {
+            crate::persistance::cooking_log::LogCookingError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::persistance::cooking_log::LogCookingError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CookingHistoryError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::persistance::cooking_log::CookingHistoryError> for CookingHistoryError {
+    fn from(value: crate::persistance::cooking_log::CookingHistoryError) -> Self {
+        match value {
+            crate::persistance::cooking_log::CookingHistoryError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::persistance::cooking_log::CookingHistoryError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl<CR: CookingLogRepository> CookingLogService<CR> {
+    pub fn new(repository: CR) -> Self {
+        Self { repository }
+    }
+
+    pub async fn log_cooking(
+        &self,
+        recipe_id: i32,
+        dto: NewCookingLog,
+    ) -> Result<CookingLog, LogCookingError> {
+        let entity = NewCookingLogEntity {
+            cooked_on: dto.cooked_on.unwrap_or_else(|| Local::now().date_naive()),
+            note: dto.note.filter(|note| !note.trim().is_empty()),
+        };
+
+        let entity = self.repository.log_cooking(recipe_id, entity).await?;
+        Ok(entity.into())
+    }
+
+    pub async fn cooking_history(
+        &self,
+        recipe_id: i32,
+    ) -> Result<Vec<CookingLog>, CookingHistoryError> {
+        let entities = self.repository.cooking_history(recipe_id).await?;
+        Ok(entities.into_iter().map(CookingLog::from).collect())
+    }
+}
@@ -0,0 +1,150 @@
+use crate::core::recipe::{Ingredient, QuantityType, Recipe};
+
+/// Recipes which likely need cleaning up, like after importing a large collection
+#[derive(Debug, Default)]
+pub struct DataQualityReport {
+    pub no_ingredients: Vec<FlaggedRecipe>,
+    /// Recipes without a description, which holds the steps of a recipe
+    pub no_steps: Vec<FlaggedRecipe>,
+    pub no_cooking_time: Vec<FlaggedRecipe>,
+    pub large_quantities: Vec<LargeQuantity>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FlaggedRecipe {
+    pub recipe_id: i32,
+    pub name: String,
+}
+
+impl From<&Recipe> for FlaggedRecipe {
+    fn from(value: &Recipe) -> Self {
+        Self {
+            recipe_id: value.recipe_id,
+            name: value.name.clone(),
+        }
+    }
+}
+
+/// Ingredient with a quantity above [`quantity_limit`], often a unit mixup like grams entered as
+/// kilos
+#[derive(Debug)]
+pub struct LargeQuantity {
+    pub recipe: FlaggedRecipe,
+    pub ingredient: Ingredient,
+}
+
+/// Largest quantity of an ingredient which isn't flagged, generous enough for batch cooking
+fn quantity_limit(quantity_type: &QuantityType) -> f32 {
+    match quantity_type {
+        QuantityType::Count => 100.0,
+        QuantityType::Kilo | QuantityType::Liter => 10.0,
+        QuantityType::Gram | QuantityType::Milliliter => 10_000.0,
+    }
+}
+
+/// Flags the recipes with missing data or suspiciously large quantities, in the order of
+/// `recipes`. A recipe is listed under every issue it has.
+pub fn find_issues(recipes: Vec<Recipe>) -> DataQualityReport {
+    let mut report = DataQualityReport::default();
+
+    for recipe in recipes {
+        if recipe.ingredients.is_empty() {
+            report.no_ingredients.push((&recipe).into());
+        }
+        if recipe
+            .description
+            .as_deref()
+            .is_none_or(|description| description.trim().is_empty())
+        {
+            report.no_steps.push((&recipe).into());
+        }
+        if recipe.cooking_time.is_none_or(|time| time.is_zero()) {
+            report.no_cooking_time.push((&recipe).into());
+        }
+
+        let flagged = FlaggedRecipe::from(&recipe);
+        for ingredient in recipe.ingredients {
+            if ingredient.quantity > quantity_limit(&ingredient.quantity_type) {
+                report.large_quantities.push(LargeQuantity {
+                    recipe: flagged.clone(),
+                    ingredient,
+                });
+            }
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    use assert2::{check, let_assert};
+
+    use crate::core::recipe::MealType;
+
+    fn recipe(recipe_id: i32, description: Option<&str>, ingredients: Vec<Ingredient>) -> Recipe {
+        Recipe {
+            recipe_id,
+            name: format!("Recipe {recipe_id}"),
+            description: description.map(str::to_string),
+            ingredients,
+            cooking_time: Some(Duration::from_secs(60 * 30)),
+            meal_type: MealType::Dinner,
+            last_cooked_at: None,
+            video: None,
+            pairing: None,
+            forked_from: None,
+            public_id: uuid::Uuid::nil(),
+        }
+    }
+
+    fn ingredient(quantity: f32, quantity_type: QuantityType) -> Ingredient {
+        Ingredient {
+            name: "Flour".to_string(),
+            quantity_type,
+            quantity,
+        }
+    }
+
+    #[test]
+    fn it_flags_recipes_with_missing_data() {
+        let report = find_issues(vec![
+            recipe(1, Some("Mix and bake."), vec![]),
+            recipe(2, Some(" "), vec![ingredient(500.0, QuantityType::Gram)]),
+            Recipe {
+                cooking_time: None,
+                ..recipe(3, Some("Mix."), vec![ingredient(2.0, QuantityType::Count)])
+            },
+        ]);
+
+        let_assert!([no_ingredients] = report.no_ingredients.as_slice());
+        check!(no_ingredients.recipe_id == 1);
+        let_assert!([no_steps] = report.no_steps.as_slice());
+        check!(no_steps.recipe_id == 2);
+        let_assert!([no_cooking_time] = report.no_cooking_time.as_slice());
+        check!(no_cooking_time.recipe_id == 3);
+        check!(report.large_quantities.is_empty());
+    }
+
+    #[test]
+    fn it_flags_quantities_above_the_limit_of_their_unit() {
+        let report = find_issues(vec![recipe(
+            1,
+            Some("Mix and bake."),
+            vec![
+                ingredient(500.0, QuantityType::Kilo),
+                ingredient(500.0, QuantityType::Gram),
+                ingredient(250.0, QuantityType::Count),
+            ],
+        )]);
+
+        let_assert!([kilos, count] = report.large_quantities.as_slice());
+        check!(kilos.recipe.recipe_id == 1);
+        check!(kilos.ingredient.quantity_type == QuantityType::Kilo);
+        check!(count.ingredient.quantity == 250.0);
+    }
+}
@@ -0,0 +1,97 @@
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+use crate::persistance::deletion::{DeletionEntity, DeletionRepository, RecipeDeletionEntity};
+
+/// Longest reason accepted for deleting a recipe, in characters
+pub const MAX_REASON_LENGTH: usize = 500;
+
+#[derive(Debug, Clone)]
+pub struct DeletionService<DR: DeletionRepository> {
+    repository: DR,
+}
+
+/// Who is deleting a recipe and why, all of it optional
+#[derive(Debug, Clone, Default)]
+pub struct Deletion {
+    /// User reported by an authenticating proxy in front of the instance
+    pub actor: Option<String>,
+    pub ip_address: Option<String>,
+    pub reason: Option<String>,
+}
+
+impl From<Deletion> for DeletionEntity {
+    fn from(value: Deletion) -> Self {
+        Self {
+            actor: value.actor,
+            ip_address: value.ip_address,
+            reason: value.reason,
+        }
+    }
+}
+
+/// Recorded deletion of a recipe
+#[derive(Debug)]
+pub struct RecipeDeletion {
+    pub deletion_id: i32,
+    pub recipe_id: i32,
+    pub recipe_name: String,
+    pub deleted_at: DateTime<Utc>,
+    pub actor: Option<String>,
+    pub ip_address: Option<String>,
+    pub reason: Option<String>,
+}
+
+impl From<RecipeDeletionEntity> for RecipeDeletion {
+    fn from(value: RecipeDeletionEntity) -> Self {
+        Self {
+            deletion_id: value.deletion_id,
+            recipe_id: value.recipe_id,
+            recipe_name: value.recipe_name,
+            deleted_at: value.deleted_at,
+            actor: value.actor,
+            ip_address: value.ip_address,
+            reason: value.reason,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ListDeletionsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::deletion::ListDeletionsError> for ListDeletionsError {
+    fn from(value: crate::persistance::deletion::ListDeletionsError) -> Self {
+        match value {
+            crate::persistance::deletion::ListDeletionsError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+impl<DR: DeletionRepository> DeletionService<DR> {
+    pub fn new(repository: DR) -> Self {
+        Self { repository }
+    }
+
+    /// Lists the most recent recipe deletions, optionally only those of a single recipe
+    pub async fn list_deletions(
+        &self,
+        recipe_id: Option<i32>,
+        limit: u32,
+    ) -> Result<Vec<RecipeDeletion>, ListDeletionsError> {
+        let entities = self
+            .repository
+            .list_deletions(recipe_id, limit as i64)
+            .await?;
+
+        Ok(entities.into_iter().map(RecipeDeletion::from).collect())
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashSet;
+
+use crate::core::recipe::Recipe;
+
+/// Minimal similarity of the names of two recipes before they are flagged as duplicates
+const NAME_SIMILARITY_THRESHOLD: f32 = 0.5;
+
+/// Minimal overlap of the ingredients of two recipes before they are flagged as duplicates
+const INGREDIENT_OVERLAP_THRESHOLD: f32 = 0.6;
+
+/// Pair of recipes which look alike
+#[derive(Debug)]
+pub struct Duplicate {
+    pub recipes: [DuplicateRecipe; 2],
+    /// Similarity of the names between 0 and 1, based on shared trigrams
+    pub name_similarity: f32,
+    /// Share of the ingredients used by both recipes, between 0 and 1
+    pub ingredient_overlap: f32,
+}
+
+#[derive(Debug)]
+pub struct DuplicateRecipe {
+    pub recipe_id: i32,
+    pub name: String,
+}
+
+impl From<&Recipe> for DuplicateRecipe {
+    fn from(value: &Recipe) -> Self {
+        Self {
+            recipe_id: value.recipe_id,
+            name: value.name.clone(),
+        }
+    }
+}
+
+/// Flags every pair of recipes with both a similar name and mostly the same ingredients, the most
+/// alike pairs first
+pub fn find_duplicates(recipes: &[Recipe]) -> Vec<Duplicate> {
+    let mut duplicates = vec![];
+
+    for (index, first) in recipes.iter().enumerate() {
+        for second in &recipes[index + 1..] {
+            let name_similarity = name_similarity(&first.name, &second.name);
+            if name_similarity < NAME_SIMILARITY_THRESHOLD {
+                continue;
+            }
+
+            let ingredient_overlap = ingredient_overlap(first, second);
+            if ingredient_overlap < INGREDIENT_OVERLAP_THRESHOLD {
+                continue;
+            }
+
+            duplicates.push(Duplicate {
+                recipes: [first.into(), second.into()],
+                name_similarity,
+                ingredient_overlap,
+            });
+        }
+    }
+
+    duplicates.sort_by(|a, b| {
+        (b.name_similarity + b.ingredient_overlap)
+            .total_cmp(&(a.name_similarity + a.ingredient_overlap))
+    });
+
+    duplicates
+}
+
+/// Compares names by the trigrams of their words, like the `pg_trgm` extension does
+fn name_similarity(first: &str, second: &str) -> f32 {
+    jaccard(&trigrams(first), &trigrams(second))
+}
+
+fn trigrams(value: &str) -> HashSet<String> {
+    value
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .flat_map(|word| {
+            let padded = format!("  {word} ").chars().collect::<Vec<_>>();
+            padded
+                .windows(3)
+                .map(|window| window.iter().collect::<String>())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+fn ingredient_overlap(first: &Recipe, second: &Recipe) -> f32 {
+    let names = |recipe: &Recipe| {
+        recipe
+            .ingredients
+            .iter()
+            .map(|ingredient| ingredient.name.trim().to_lowercase())
+            .collect::<HashSet<_>>()
+    };
+
+    jaccard(&names(first), &names(second))
+}
+
+/// Size of the intersection over the size of the union, two empty sets are considered equal
+fn jaccard(first: &HashSet<String>, second: &HashSet<String>) -> f32 {
+    let union = first.union(second).count();
+    if union == 0 {
+        return 1.0;
+    }
+
+    first.intersection(second).count() as f32 / union as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::check;
+
+    use crate::core::recipe::{Ingredient, MealType, QuantityType};
+
+    fn recipe(recipe_id: i32, name: &str, ingredients: &[&str]) -> Recipe {
+        Recipe {
+            recipe_id,
+            name: name.to_string(),
+            description: None,
+            ingredients: ingredients
+                .iter()
+                .map(|name| Ingredient {
+                    name: name.to_string(),
+                    quantity_type: QuantityType::Count,
+                    quantity: 1.0,
+                })
+                .collect(),
+            cooking_time: None,
+            meal_type: MealType::Dinner,
+            last_cooked_at: None,
+            video: None,
+            pairing: None,
+            forked_from: None,
+            public_id: uuid::Uuid::nil(),
+        }
+    }
+
+    #[test]
+    fn it_flags_recipes_with_similar_names_and_ingredients() {
+        let duplicates = find_duplicates(&[
+            recipe(
+                1,
+                "Spaghetti Bolognese",
+                &["Spaghetti", "Minced beef", "Tomato"],
+            ),
+            recipe(2, "Pancakes", &["Flour", "Eggs", "Milk"]),
+            recipe(
+                3,
+                "spaghetti bolognaise",
+                &["spaghetti", "minced beef", "tomato", "Onion"],
+            ),
+        ]);
+
+        check!(duplicates.len() == 1);
+        check!(duplicates[0].recipes[0].recipe_id == 1);
+        check!(duplicates[0].recipes[1].recipe_id == 3);
+        check!(duplicates[0].ingredient_overlap == 0.75);
+    }
+
+    #[test]
+    fn it_ignores_similar_names_with_different_ingredients() {
+        let duplicates = find_duplicates(&[
+            recipe(1, "Tomato soup", &["Tomato", "Stock"]),
+            recipe(2, "Tomato salad", &["Tomato", "Mozzarella", "Basil"]),
+        ]);
+
+        check!(duplicates.is_empty());
+    }
+
+    #[test]
+    fn it_scores_identical_names_as_fully_similar() {
+        check!(name_similarity("Lasagne", "lasagne") == 1.0);
+        check!(name_similarity("Lasagne", "Pancakes") < NAME_SIMILARITY_THRESHOLD);
+    }
+}
@@ -0,0 +1,178 @@
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+use crate::persistance::outbox::{OutboxEventEntity, OutboxRepository};
+
+/// Number of events relayed per batch
+const RELAY_BATCH_SIZE: i64 = 100;
+
+/// Number of events a subscriber may fall behind before it misses events
+const SUBSCRIBER_CAPACITY: usize = 1024;
+
+/// Something that happened to a recipe, recorded in the same transaction as the change itself
+#[derive(Debug, Clone)]
+pub struct RecipeEvent {
+    pub event_id: i64,
+    pub kind: RecipeEventKind,
+    pub recipe_id: i32,
+    pub public_id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl From<OutboxEventEntity> for RecipeEvent {
+    fn from(value: OutboxEventEntity) -> Self {
+        Self {
+            event_id: value.outbox_id,
+            kind: value.kind.into(),
+            recipe_id: value.recipe_id,
+            public_id: value.public_id,
+            occurred_at: value.occurred_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecipeEventKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+impl From<crate::persistance::outbox::RecipeEventKind> for RecipeEventKind {
+    fn from(value: crate::persistance::outbox::RecipeEventKind) -> Self {
+        match value {
+            crate::persistance::outbox::RecipeEventKind::Created => Self::Created,
+            crate::persistance::outbox::RecipeEventKind::Updated => Self::Updated,
+            crate::persistance::outbox::RecipeEventKind::Deleted => Self::Deleted,
+        }
+    }
+}
+
+/// Relays events from the outbox to the subscribers in this process. Events are delivered at
+/// least once to the subscribers present at the time, events relayed while nobody is subscribed
+/// are dropped.
+#[derive(Debug, Clone)]
+pub struct OutboxRelay<OR: OutboxRepository> {
+    repository: OR,
+    sender: broadcast::Sender<RecipeEvent>,
+}
+
+#[derive(Debug, Error)]
+pub enum RelayEventsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::outbox::UnpublishedEventsError> for RelayEventsError {
+    fn from(value: crate::persistance::outbox::UnpublishedEventsError) -> Self {
+        match value {
+            crate::persistance::outbox::UnpublishedEventsError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+impl From<crate::persistance::outbox::MarkPublishedError> for RelayEventsError {
+    fn from(value: crate::persistance::outbox::MarkPublishedError) -> Self {
+        match value {
+            crate::persistance::outbox::MarkPublishedError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+impl<OR: OutboxRepository> OutboxRelay<OR> {
+    pub fn new(repository: OR) -> Self {
+        let (sender, _) = broadcast::channel(SUBSCRIBER_CAPACITY);
+        Self { repository, sender }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<RecipeEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Publishes all pending events to the subscribers in batches, returning the number of events
+    /// relayed
+    pub async fn relay(&self) -> Result<usize, RelayEventsError> {
+        let mut relayed = 0;
+
+        loop {
+            let events = self.repository.unpublished_events(RELAY_BATCH_SIZE).await?;
+            let batch_size = events.len();
+
+            if batch_size == 0 {
+                return Ok(relayed);
+            }
+
+            let mut outbox_ids = Vec::with_capacity(batch_size);
+
+            for event in events {
+                outbox_ids.push(event.outbox_id);
+                // Sending only fails without subscribers, in which case nobody is interested
+                let _ = self.sender.send(event.into());
+            }
+
+            self.repository.mark_published(outbox_ids).await?;
+            relayed += batch_size;
+
+            if batch_size < RELAY_BATCH_SIZE as usize {
+                return Ok(relayed);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+    use sqlx::PgPool;
+
+    use crate::persistance::{
+        implementation::postgres::Postgres,
+        recipe::{MealType, MutableRecipeEntity, RecipeRepository},
+    };
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_relays_pending_events_once(pool: PgPool) {
+        let repository = Postgres::new(pool);
+        let relay = OutboxRelay::new(repository.clone());
+        let mut events = relay.subscribe();
+
+        let_assert!(
+            Ok(recipe) = repository
+                .create_recipe(MutableRecipeEntity {
+                    name: "Soup".to_string(),
+                    description: None,
+                    ingredients: vec![],
+                    cooking_time: None,
+                    meal_type: MealType::Dinner,
+                    video_url: None,
+                    video_title: None,
+                    video_thumbnail_url: None,
+                    pairing: None,
+                    pairing_kind: None,
+                })
+                .await
+        );
+
+        let_assert!(Ok(1) = relay.relay().await);
+        let_assert!(Ok(event) = events.try_recv());
+        check!(event.kind == RecipeEventKind::Created);
+        check!(event.recipe_id == recipe.recipe_id);
+
+        let_assert!(Ok(0) = relay.relay().await);
+        check!(events.try_recv().is_err());
+    }
+}
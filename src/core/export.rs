@@ -0,0 +1,97 @@
+use thiserror::Error;
+
+use crate::{
+    core::recipe::Recipe,
+    persistance::{recipe::RecipeRepository, tag::TagRepository},
+};
+
+#[derive(Debug, Clone)]
+pub struct ExportService<RR: RecipeRepository, TR: TagRepository> {
+    recipes: RR,
+    tags: TR,
+}
+
+/// Everything stored about a recipe, for other recipe managers to import
+#[derive(Debug)]
+pub struct ExportedRecipe {
+    pub recipe: Recipe,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum ExportRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::persistance::recipe::GetRecipeError> for ExportRecipeError {
+    fn from(value: crate::persistance::recipe::GetRecipeError) -> Self {
+        match value {
+            crate::persistance::recipe::GetRecipeError::Unknown(report) => Self::Unknown(report),
+            crate::persistance::recipe::GetRecipeError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl From<crate::persistance::tag::RecipeTagsError> for ExportRecipeError {
+    fn from(value: crate::persistance::tag::RecipeTagsError) -> Self {
+        match value {
+            crate::persistance::tag::RecipeTagsError::Unknown(report) => Self::Unknown(report),
+            crate::persistance::tag::RecipeTagsError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl<RR: RecipeRepository, TR: TagRepository> ExportService<RR, TR> {
+    pub fn new(recipes: RR, tags: TR) -> Self {
+        Self { recipes, tags }
+    }
+
+    pub async fn export_recipe(&self, recipe_id: i32) -> Result<ExportedRecipe, ExportRecipeError> {
+        let recipe = self.recipes.get_recipe(recipe_id).await?.into();
+        let tags = self.tags.recipe_tags(recipe_id).await?;
+
+        Ok(ExportedRecipe { recipe, tags })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+
+    use crate::{
+        persistance::recipe::GetRecipeError,
+        test_utils::{MockRepository, recipe_entity},
+    };
+
+    #[tokio::test]
+    async fn it_exports_the_recipe_with_its_tags() {
+        let repository = MockRepository::new();
+        repository.on_get_recipe(Ok(recipe_entity(1, "Stew")));
+        repository.on_recipe_tags(Ok(vec!["winter".to_string()]));
+        let svc = ExportService::new(repository.clone(), repository.clone());
+
+        let_assert!(Ok(exported) = svc.export_recipe(1).await);
+        check!(exported.recipe.name == "Stew");
+        check!(exported.tags == vec!["winter".to_string()]);
+        check!(repository.calls_to("recipe_tags")[0].arguments == "(1,)");
+    }
+
+    #[tokio::test]
+    async fn it_returns_not_found_for_an_unknown_recipe() {
+        let repository = MockRepository::new();
+        repository.on_get_recipe(Err(GetRecipeError::NotFound));
+        let svc = ExportService::new(repository.clone(), repository.clone());
+
+        let_assert!(Err(ExportRecipeError::NotFound) = svc.export_recipe(1).await);
+        check!(repository.calls_to("recipe_tags").is_empty());
+    }
+}
@@ -0,0 +1,643 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use url::{Host, Url};
+use uuid::Uuid;
+
+use crate::{
+    core::recipe::Recipe,
+    federation::{
+        cipher::TokenCipher,
+        fetcher::{FetchRecipesError, RecipeFetcher},
+    },
+    persistance::federation::{
+        FederationRepository, MirrorSummaryEntity, RecipeOriginEntity, RemoteInstanceEntity,
+        RemoteRecipeEntity,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct FederationService<FR: FederationRepository, F: RecipeFetcher> {
+    repository: FR,
+    fetcher: F,
+    /// Missing when no key is configured, which rules out instances with a token
+    cipher: Option<TokenCipher>,
+}
+
+/// Instance recipes are mirrored from. The token is never handed out again once registered.
+#[derive(Debug)]
+pub struct RemoteInstance {
+    pub remote_instance_id: i32,
+    pub url: String,
+    pub has_token: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_synced_at: Option<DateTime<Utc>>,
+}
+
+impl From<RemoteInstanceEntity> for RemoteInstance {
+    fn from(value: RemoteInstanceEntity) -> Self {
+        Self {
+            remote_instance_id: value.remote_instance_id,
+            url: value.url,
+            has_token: value.token.is_some(),
+            created_at: value.created_at,
+            last_synced_at: value.last_synced_at,
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SyncSummary {
+    pub created: u64,
+    pub updated: u64,
+    pub skipped: u64,
+    pub detached: u64,
+}
+
+impl From<MirrorSummaryEntity> for SyncSummary {
+    fn from(value: MirrorSummaryEntity) -> Self {
+        Self {
+            created: value.created,
+            updated: value.updated,
+            skipped: value.skipped,
+            detached: value.detached,
+        }
+    }
+}
+
+/// Instance a recipe was mirrored from. A detached recipe is no longer kept in sync, because it
+/// was edited here or the instance stopped publishing it.
+#[derive(Debug)]
+pub struct RecipeOrigin {
+    pub remote_instance_id: i32,
+    pub url: String,
+    pub remote_public_id: Uuid,
+    pub detached: bool,
+}
+
+impl From<RecipeOriginEntity> for RecipeOrigin {
+    fn from(value: RecipeOriginEntity) -> Self {
+        Self {
+            remote_instance_id: value.remote_instance_id,
+            url: value.url,
+            remote_public_id: value.remote_public_id,
+            detached: value.detached,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RegisterInstanceError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("`{0}` is not an http or https url")]
+    InvalidUrl(String),
+    #[error("`{0}` points to a private or loopback address")]
+    PrivateAddress(String),
+    #[error("The instance is already registered")]
+    AlreadyRegistered,
+    #[error("Instances with a token need a federation key to be configured")]
+    TokensDisabled,
+}
+
+impl From<crate::persistance::federation::RegisterInstanceError> for RegisterInstanceError {
+    fn from(value: crate::persistance::federation::RegisterInstanceError) -> Self {
+        match value {
+            crate::persistance::federation::RegisterInstanceError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::persistance::federation::RegisterInstanceError::AlreadyRegistered => {
+                Self::AlreadyRegistered
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ListInstancesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::federation::ListInstancesError> for ListInstancesError {
+    fn from(value: crate::persistance::federation::ListInstancesError) -> Self {
+        match value {
+            crate::persistance::federation::ListInstancesError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RemoveInstanceError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The instance could not be found")]
+    NotFound,
+}
+
+impl From<crate::persistance::federation::RemoveInstanceError> for RemoveInstanceError {
+    fn from(value: crate::persistance::federation::RemoveInstanceError) -> Self {
+        match value {
+            crate::persistance::federation::RemoveInstanceError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::persistance::federation::RemoveInstanceError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum FederatedRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::federation::FederatedRecipesError> for FederatedRecipesError {
+    fn from(value: crate::persistance::federation::FederatedRecipesError) -> Self {
+        match value {
+            crate::persistance::federation::FederatedRecipesError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SyncInstanceError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The instance could not be found")]
+    NotFound,
+    #[error("The instance rejected the token")]
+    Unauthorized,
+}
+
+impl From<crate::persistance::federation::ListInstancesError> for SyncInstanceError {
+    fn from(value: crate::persistance::federation::ListInstancesError) -> Self {
+        match value {
+            crate::persistance::federation::ListInstancesError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+impl From<crate::persistance::federation::MirrorRecipesError> for SyncInstanceError {
+    fn from(value: crate::persistance::federation::MirrorRecipesError) -> Self {
+        match value {
+            crate::persistance::federation::MirrorRecipesError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::persistance::federation::MirrorRecipesError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl From<FetchRecipesError> for SyncInstanceError {
+    fn from(value: FetchRecipesError) -> Self {
+        match value {
+            FetchRecipesError::Unknown(report) => Self::Unknown(report),
+            FetchRecipesError::Unauthorized => Self::Unauthorized,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RecipeOriginError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::persistance::federation::RecipeOriginError> for RecipeOriginError {
+    fn from(value: crate::persistance::federation::RecipeOriginError) -> Self {
+        match value {
+            crate::persistance::federation::RecipeOriginError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::persistance::federation::RecipeOriginError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SetFederationOptOutError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::persistance::federation::SetFederationOptOutError> for SetFederationOptOutError {
+    fn from(value: crate::persistance::federation::SetFederationOptOutError) -> Self {
+        match value {
+            crate::persistance::federation::SetFederationOptOutError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::persistance::federation::SetFederationOptOutError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+/// Whether an address can be reached from the internet. Recipes are only fetched from public
+/// addresses, so remote instances can't be used to reach services on the network of the server.
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            // 100.64.0.0/10 is shared by carrier-grade NATs
+            let shared = a == 100 && (b & 0xc0) == 64;
+
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                || shared)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_address(mapped.into()),
+            None => {
+                !(ip.is_unspecified()
+                    || ip.is_loopback()
+                    || ip.is_multicast()
+                    || ip.is_unique_local()
+                    || ip.is_unicast_link_local())
+            }
+        },
+    }
+}
+
+impl<FR: FederationRepository, F: RecipeFetcher> FederationService<FR, F> {
+    pub fn new(repository: FR, fetcher: F, cipher: Option<TokenCipher>) -> Self {
+        Self {
+            repository,
+            fetcher,
+            cipher,
+        }
+    }
+
+    /// Registers an instance to mirror recipes from. Instances on the local network are refused,
+    /// names are checked again when they are resolved for a sync.
+    pub async fn register_instance(
+        &self,
+        url: String,
+        token: Option<String>,
+    ) -> Result<RemoteInstance, RegisterInstanceError> {
+        let Some(host) = Url::parse(&url)
+            .ok()
+            .filter(|parsed| matches!(parsed.scheme(), "http" | "https"))
+            .and_then(|parsed| parsed.host().map(|host| host.to_owned()))
+        else {
+            return Err(RegisterInstanceError::InvalidUrl(url));
+        };
+
+        let public = match host {
+            Host::Domain(domain) => domain != "localhost" && !domain.ends_with(".localhost"),
+            Host::Ipv4(ip) => is_public_address(ip.into()),
+            Host::Ipv6(ip) => is_public_address(ip.into()),
+        };
+        if !public {
+            return Err(RegisterInstanceError::PrivateAddress(url));
+        }
+
+        let token = match (token, &self.cipher) {
+            (Some(token), Some(cipher)) => Some(cipher.encrypt(&token)?),
+            (Some(_), None) => return Err(RegisterInstanceError::TokensDisabled),
+            (None, _) => None,
+        };
+
+        let url = url.trim_end_matches('/').to_string();
+        let entity = self.repository.register_instance(url, token).await?;
+        Ok(entity.into())
+    }
+
+    pub async fn list_instances(&self) -> Result<Vec<RemoteInstance>, ListInstancesError> {
+        let entities = self.repository.list_instances().await?;
+        Ok(entities.into_iter().map(RemoteInstance::from).collect())
+    }
+
+    pub async fn remove_instance(
+        &self,
+        remote_instance_id: i32,
+    ) -> Result<(), RemoveInstanceError> {
+        self.repository.remove_instance(remote_instance_id).await?;
+        Ok(())
+    }
+
+    /// Recipes this instance publishes to the instances mirroring it
+    pub async fn federated_recipes(&self) -> Result<Vec<Recipe>, FederatedRecipesError> {
+        let entities = self.repository.federated_recipes().await?;
+        Ok(entities.into_iter().map(Recipe::from).collect())
+    }
+
+    /// Fetches the recipes of a remote instance and mirrors them
+    pub async fn sync_instance(
+        &self,
+        remote_instance_id: i32,
+    ) -> Result<SyncSummary, SyncInstanceError> {
+        let instance = self
+            .repository
+            .list_instances()
+            .await?
+            .into_iter()
+            .find(|instance| instance.remote_instance_id == remote_instance_id)
+            .ok_or(SyncInstanceError::NotFound)?;
+
+        self.sync(instance).await
+    }
+
+    /// Syncs every registered instance, an instance failing to sync doesn't stop the others
+    pub async fn sync_all(&self) -> Result<(), ListInstancesError> {
+        for instance in self.repository.list_instances().await? {
+            let url = instance.url.clone();
+
+            match self.sync(instance).await {
+                Ok(summary) => tracing::info!(url, ?summary, "Synced remote instance"),
+                Err(error) => tracing::error!(url, ?error, "Failed to sync remote instance"),
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn sync(&self, instance: RemoteInstanceEntity) -> Result<SyncSummary, SyncInstanceError> {
+        let token = match (&instance.token, &self.cipher) {
+            (Some(sealed), Some(cipher)) => Some(cipher.decrypt(sealed)?),
+            (Some(_), None) => {
+                return Err(
+                    eyre::eyre!("The token can't be decrypted without the federation key").into(),
+                );
+            }
+            (None, _) => None,
+        };
+
+        let recipes = self
+            .fetcher
+            .fetch_recipes(&instance.url, token.as_deref())
+            .await?
+            .into_iter()
+            .map(|recipe| RemoteRecipeEntity {
+                remote_public_id: recipe.public_id,
+                recipe: recipe.into(),
+            })
+            .collect();
+
+        let summary = self
+            .repository
+            .mirror_recipes(instance.remote_instance_id, recipes)
+            .await?;
+
+        Ok(summary.into())
+    }
+
+    /// Finds the instance a recipe was mirrored from, `None` for recipes written here
+    pub async fn recipe_origin(
+        &self,
+        recipe_id: i32,
+    ) -> Result<Option<RecipeOrigin>, RecipeOriginError> {
+        let origin = self.repository.recipe_origin(recipe_id).await?;
+        Ok(origin.map(RecipeOrigin::from))
+    }
+
+    /// Keeps a recipe from being published to other instances, or publishes it again
+    pub async fn set_opt_out(
+        &self,
+        recipe_id: i32,
+        opt_out: bool,
+    ) -> Result<(), SetFederationOptOutError> {
+        self.repository
+            .set_federation_opt_out(recipe_id, opt_out)
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+    use chrono::Utc;
+    use secrecy::SecretBox;
+
+    use crate::{
+        core::recipe::MealType,
+        persistance::federation::{
+            ListInstancesError as ListInstancesEntityError, MirrorSummaryEntity,
+        },
+        test_utils::MockRepository,
+    };
+
+    /// Fetcher handing out a single recipe, or rejecting the token when there is none
+    #[derive(Debug, Clone)]
+    struct StubFetcher;
+
+    impl RecipeFetcher for StubFetcher {
+        async fn fetch_recipes(
+            &self,
+            _url: &str,
+            token: Option<&str>,
+        ) -> Result<Vec<Recipe>, FetchRecipesError> {
+            if token.is_none() {
+                return Err(FetchRecipesError::Unauthorized);
+            }
+
+            Ok(vec![Recipe {
+                recipe_id: 7,
+                name: "Soup".to_string(),
+                description: None,
+                ingredients: vec![],
+                cooking_time: None,
+                meal_type: MealType::Dinner,
+                last_cooked_at: None,
+                video: None,
+                pairing: None,
+                forked_from: None,
+                public_id: Uuid::from_u128(1),
+            }])
+        }
+    }
+
+    fn cipher() -> TokenCipher {
+        TokenCipher::new(&SecretBox::from("federation key"))
+    }
+
+    fn instance(remote_instance_id: i32, token: Option<&str>) -> RemoteInstanceEntity {
+        RemoteInstanceEntity {
+            remote_instance_id,
+            url: "https://recipes.example.com".to_string(),
+            token: token.map(|token| cipher().encrypt(token).expect("Failed to encrypt token")),
+            created_at: Utc::now(),
+            last_synced_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn it_rejects_instances_without_an_http_url() {
+        let repository = MockRepository::new();
+        let svc = FederationService::new(repository.clone(), StubFetcher, Some(cipher()));
+
+        let_assert!(
+            Err(RegisterInstanceError::InvalidUrl(_)) = svc
+                .register_instance("recipes.example.com".to_string(), None)
+                .await
+        );
+        check!(repository.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_rejects_instances_on_the_local_network() {
+        let repository = MockRepository::new();
+        let svc = FederationService::new(repository.clone(), StubFetcher, Some(cipher()));
+
+        for url in [
+            "http://localhost:8080",
+            "http://recipes.localhost",
+            "http://127.0.0.1",
+            "http://10.0.0.5",
+            "http://192.168.1.10:8080",
+            "http://169.254.169.254/latest",
+            "http://100.64.0.1",
+            "http://[::1]",
+            "http://[fd00::1]",
+            "http://[::ffff:127.0.0.1]",
+        ] {
+            let_assert!(
+                Err(RegisterInstanceError::PrivateAddress(_)) =
+                    svc.register_instance(url.to_string(), None).await,
+                "for {url}"
+            );
+        }
+        check!(repository.calls().is_empty());
+    }
+
+    fn ip(address: &str) -> IpAddr {
+        address.parse().expect("Invalid address")
+    }
+
+    #[test]
+    fn it_considers_internet_addresses_public() {
+        check!(is_public_address(ip("93.184.215.14")));
+        check!(is_public_address(ip("2606:2800:21f:cb07::1")));
+        check!(!is_public_address(ip("0.0.0.0")));
+        check!(!is_public_address(ip("fe80::1")));
+    }
+
+    #[tokio::test]
+    async fn it_stores_tokens_encrypted() {
+        let repository = MockRepository::new();
+        repository.on_register_instance(Ok(instance(1, None)));
+        let svc = FederationService::new(repository.clone(), StubFetcher, Some(cipher()));
+
+        let_assert!(
+            Ok(_) = svc
+                .register_instance(
+                    "https://recipes.example.com".to_string(),
+                    Some("secret".to_string())
+                )
+                .await
+        );
+        let plaintext = format!("{:?}", b"secret")
+            .trim_matches(['[', ']'])
+            .to_string();
+        let calls = repository.calls_to("register_instance");
+        check!(calls[0].arguments.contains("Some(["));
+        check!(!calls[0].arguments.contains(&plaintext));
+
+        let svc = FederationService::new(repository.clone(), StubFetcher, None);
+        let_assert!(
+            Err(RegisterInstanceError::TokensDisabled) = svc
+                .register_instance(
+                    "https://recipes.example.com".to_string(),
+                    Some("secret".to_string())
+                )
+                .await
+        );
+    }
+
+    #[tokio::test]
+    async fn it_mirrors_fetched_recipes_by_their_public_id() {
+        let repository = MockRepository::new();
+        repository
+            .on_list_instances(Ok(vec![instance(3, Some("secret"))]))
+            .on_mirror_recipes(Ok(MirrorSummaryEntity {
+                created: 1,
+                ..Default::default()
+            }));
+        let svc = FederationService::new(repository.clone(), StubFetcher, Some(cipher()));
+
+        let_assert!(Ok(summary) = svc.sync_instance(3).await);
+
+        check!(summary.created == 1);
+        let calls = repository.calls_to("mirror_recipes");
+        check!(calls.len() == 1);
+        check!(calls[0].arguments.starts_with("(3, "));
+        check!(
+            calls[0]
+                .arguments
+                .contains("remote_public_id: 00000000-0000-0000-0000-000000000001")
+        );
+    }
+
+    #[tokio::test]
+    async fn it_keeps_syncing_when_an_instance_fails() {
+        let repository = MockRepository::new();
+        repository
+            .on_list_instances(Ok(vec![instance(1, None), instance(2, Some("secret"))]))
+            .on_mirror_recipes(Ok(MirrorSummaryEntity::default()));
+        let svc = FederationService::new(repository.clone(), StubFetcher, Some(cipher()));
+
+        let_assert!(Ok(()) = svc.sync_all().await);
+
+        let calls = repository.calls_to("mirror_recipes");
+        check!(calls.len() == 1);
+        check!(calls[0].arguments.starts_with("(2, "));
+    }
+
+    #[tokio::test]
+    async fn it_fails_syncing_unknown_instances() {
+        let repository = MockRepository::new();
+        repository.on_list_instances(Ok(vec![]));
+        let svc = FederationService::new(repository.clone(), StubFetcher, Some(cipher()));
+
+        let_assert!(Err(SyncInstanceError::NotFound) = svc.sync_instance(3).await);
+
+        repository.on_list_instances(Err(ListInstancesEntityError::Unknown(eyre::eyre!("down"))));
+        let_assert!(Err(SyncInstanceError::Unknown(_)) = svc.sync_instance(3).await);
+    }
+}
@@ -0,0 +1,63 @@
+use thiserror::Error;
+
+use crate::{
+    core::{import::draft_from_text, recipe::NewRecipe},
+    suggest::suggester::{RecipeSuggester, SuggestRecipeError},
+};
+
+/// Longest prompt passed on to the suggester, keeps requests to paid APIs predictable
+pub const MAX_PROMPT_LENGTH: usize = 500;
+
+#[derive(Debug, Clone)]
+pub struct GenerateService<RS: RecipeSuggester> {
+    suggester: RS,
+}
+
+#[derive(Debug, Error)]
+pub enum GenerateRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The prompt must be between 1 and {MAX_PROMPT_LENGTH} characters")]
+    InvalidPrompt,
+    #[error("No recipe was generated for the prompt")]
+    NoRecipe,
+    #[error("Generating recipes is not configured on this instance")]
+    Disabled,
+}
+
+impl From<SuggestRecipeError> for GenerateRecipeError {
+    fn from(value: SuggestRecipeError) -> Self {
+        match value {
+            SuggestRecipeError::Unknown(report) => Self::Unknown(report),
+            SuggestRecipeError::Disabled => Self::Disabled,
+        }
+    }
+}
+
+impl<RS: RecipeSuggester> GenerateService<RS> {
+    pub fn new(suggester: RS) -> Self {
+        Self { suggester }
+    }
+
+    /// Generates a draft recipe from a prompt like `vegetarian dinner with leftover rice`. The
+    /// draft is not saved.
+    pub async fn generate_recipe(&self, prompt: &str) -> Result<NewRecipe, GenerateRecipeError> {
+        let prompt = prompt.trim();
+
+        if prompt.is_empty() || prompt.chars().count() > MAX_PROMPT_LENGTH {
+            return Err(GenerateRecipeError::InvalidPrompt);
+        }
+
+        let text = self.suggester.suggest(prompt).await?;
+
+        if text.trim().is_empty() {
+            return Err(GenerateRecipeError::NoRecipe);
+        }
+
+        Ok(draft_from_text(&text))
+    }
+}
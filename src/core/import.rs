@@ -0,0 +1,392 @@
+use thiserror::Error;
+
+use crate::{
+    core::{
+        recipe::{Ingredient, MealType, NewRecipe},
+        unit::{Quantity, Unit},
+    },
+    ocr::recognizer::{RecognizeTextError, TextRecognizer},
+};
+
+#[derive(Debug, Clone)]
+pub struct ImportService<TR: TextRecognizer> {
+    recognizer: TR,
+}
+
+#[derive(Debug, Error)]
+pub enum ImportImageError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("No text could be found in the image")]
+    NoText,
+    #[error("Importing images is not configured on this instance")]
+    Disabled,
+}
+
+impl From<RecognizeTextError> for ImportImageError {
+    fn from(value: RecognizeTextError) -> Self {
+        match value {
+            RecognizeTextError::Unknown(report) => Self::Unknown(report),
+            RecognizeTextError::Disabled => Self::Disabled,
+        }
+    }
+}
+
+/// Ingredient read from a line of text, in the unit it was written in
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedIngredient {
+    pub name: String,
+    pub quantity: Quantity,
+    /// Remarks on the ingredient, like `sifted` in `1 cup flour, sifted`
+    pub note: Option<String>,
+}
+
+/// Recipes have no place for notes, so they are kept in the name like they were written
+impl From<ParsedIngredient> for Ingredient {
+    fn from(value: ParsedIngredient) -> Self {
+        let (quantity_type, quantity) = value.quantity.to_stored();
+
+        let name = match value.note {
+            Some(note) => format!("{}, {note}", value.name),
+            None => value.name,
+        };
+
+        Self {
+            name,
+            quantity_type,
+            quantity,
+        }
+    }
+}
+
+/// Headings which commonly start the ingredients of a written recipe
+const INGREDIENT_HEADINGS: [&str; 2] = ["ingredients", "you will need"];
+
+/// Headings which commonly start the steps of a written recipe
+const STEP_HEADINGS: [&str; 6] = [
+    "method",
+    "directions",
+    "instructions",
+    "preparation",
+    "steps",
+    "how to make it",
+];
+
+/// Builds a best-effort draft from the text of a written recipe. The first line becomes the name,
+/// lines starting with a quantity become ingredients and everything else ends up in the
+/// description. Once a heading like `Method` starts the steps every line is kept in the
+/// description, so numbered steps aren't mistaken for ingredients.
+pub fn draft_from_text(text: &str) -> NewRecipe {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+
+    let name = lines.next().unwrap_or("Imported recipe").to_string();
+
+    let mut ingredients = vec![];
+    let mut description = vec![];
+    let mut in_steps = false;
+
+    for line in lines {
+        let heading = line.trim_end_matches(':').trim().to_lowercase();
+        if INGREDIENT_HEADINGS.contains(&heading.as_str()) {
+            in_steps = false;
+            continue;
+        }
+        if STEP_HEADINGS.contains(&heading.as_str()) {
+            in_steps = true;
+            continue;
+        }
+
+        match parse_ingredient(line).filter(|_| !in_steps) {
+            Some(ingredient) => ingredients.push(ingredient.into()),
+            None => description.push(line),
+        }
+    }
+
+    let lowercase = text.to_lowercase();
+    let meal_type = if lowercase.contains("breakfast") {
+        MealType::Breakfast
+    } else if lowercase.contains("lunch") {
+        MealType::Lunch
+    } else {
+        MealType::Dinner
+    };
+
+    NewRecipe {
+        name,
+        description: (!description.is_empty()).then(|| description.join("\n")),
+        ingredients,
+        cooking_time: None,
+        meal_type,
+        video_url: None,
+        pairing: None,
+    }
+}
+
+/// Parses lines like `500 g flour`, `- 2 eggs`, `250ml milk` or `2 1/2 cups all-purpose flour,
+/// sifted`. Lines which don't start with a quantity aren't ingredients.
+pub fn parse_ingredient(line: &str) -> Option<ParsedIngredient> {
+    let line = line.trim().trim_start_matches(['-', '*', '•']).trim_start();
+
+    let (amount, rest) = parse_amount(line)?;
+    let (unit, rest) = parse_unit(rest);
+    let rest = rest.strip_prefix("of ").unwrap_or(rest);
+
+    let (name, note) = split_note(rest);
+    if name.is_empty() {
+        return None;
+    }
+
+    Some(ParsedIngredient {
+        name,
+        quantity: Quantity::new(unit, amount),
+        note,
+    })
+}
+
+/// Reads the quantity at the start of a line, returning it with the rest of the line. Mixed
+/// numbers like `2 1/2` are added up and ranges like `2-3` keep their lower bound.
+fn parse_amount(line: &str) -> Option<(f32, &str)> {
+    let (mut amount, mut rest) = parse_number(line)?;
+
+    let fraction = parse_number(rest.trim_start())
+        .filter(|(fraction, _)| amount.fract() == 0.0 && *fraction < 1.0);
+    if let Some((fraction, after)) = fraction {
+        amount += fraction;
+        rest = after;
+    }
+
+    let upper = rest
+        .trim_start()
+        .strip_prefix(['-', '–'])
+        .and_then(|upper| parse_number(upper.trim_start()));
+    if let Some((_, after)) = upper {
+        rest = after;
+    }
+
+    (amount.is_finite() && amount > 0.0).then_some((amount, rest.trim_start()))
+}
+
+/// Reads a number like `2`, `1.5`, `1,5`, `1/2`, `½` or `2½` at the start of `value`
+fn parse_number(value: &str) -> Option<(f32, &str)> {
+    let end = value
+        .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | ',' | '/')))
+        .unwrap_or(value.len());
+    let (number, rest) = value.split_at(end);
+
+    let mut chars = rest.chars();
+    let (fraction, rest) = match chars.next().and_then(vulgar_fraction) {
+        Some(fraction) => (fraction, chars.as_str()),
+        None => (0.0, rest),
+    };
+
+    if number.is_empty() {
+        return (fraction > 0.0).then_some((fraction, rest));
+    }
+
+    Some((parse_quantity(number)? + fraction, rest))
+}
+
+/// Parses quantities like `2`, `1.5`, `1,5` or `1/2`
+fn parse_quantity(value: &str) -> Option<f32> {
+    let quantity = match value.split_once('/') {
+        Some((numerator, denominator)) => {
+            numerator.parse::<f32>().ok()? / denominator.parse::<f32>().ok()?
+        }
+        None => value.replace(',', ".").parse().ok()?,
+    };
+
+    (quantity.is_finite() && quantity > 0.0).then_some(quantity)
+}
+
+fn vulgar_fraction(c: char) -> Option<f32> {
+    match c {
+        '½' => Some(1.0 / 2.0),
+        '⅓' => Some(1.0 / 3.0),
+        '⅔' => Some(2.0 / 3.0),
+        '¼' => Some(1.0 / 4.0),
+        '¾' => Some(3.0 / 4.0),
+        '⅛' => Some(1.0 / 8.0),
+        _ => None,
+    }
+}
+
+/// Reads the unit at the start of `value`, returning it with the rest of the line. Without a known
+/// unit the quantity is a count of what follows.
+fn parse_unit(value: &str) -> (Unit, &str) {
+    let (word, rest) = split_word(value);
+
+    let unit = match word.to_lowercase().trim_end_matches('.') {
+        "g" | "gr" | "gram" | "grams" => Unit::Gram,
+        "kg" | "kilo" | "kilos" | "kilogram" | "kilograms" => Unit::Kilo,
+        "l" | "liter" | "liters" | "litre" | "litres" => Unit::Liter,
+        "ml" | "milliliter" | "milliliters" | "millilitre" | "millilitres" => Unit::Milliliter,
+        "oz" | "ounce" | "ounces" => Unit::Ounce,
+        "lb" | "lbs" | "pound" | "pounds" => Unit::Pound,
+        "cup" | "cups" => Unit::Cup,
+        "tbsp" | "tbs" | "tablespoon" | "tablespoons" => Unit::Tablespoon,
+        "tsp" | "teaspoon" | "teaspoons" => Unit::Teaspoon,
+        "fl" | "fluid" => {
+            // Fluid ounces are written as two words, like `fl oz` or `fluid ounces`
+            let (next, after) = split_word(rest);
+
+            return match next.to_lowercase().trim_end_matches('.') {
+                "oz" | "ounce" | "ounces" => (Unit::FluidOunce, after),
+                _ => (Unit::Count, value),
+            };
+        }
+        _ => return (Unit::Count, value),
+    };
+
+    (unit, rest)
+}
+
+fn split_word(value: &str) -> (&str, &str) {
+    let (word, rest) = value.split_once(char::is_whitespace).unwrap_or((value, ""));
+
+    (word, rest.trim_start())
+}
+
+/// Splits remarks in parentheses or after a comma off the name, like `finely chopped` in `onion
+/// (finely chopped)`
+fn split_note(value: &str) -> (String, Option<String>) {
+    let mut name = value.to_string();
+    let mut notes = vec![];
+
+    let parenthesized = name
+        .find('(')
+        .and_then(|open| Some((open, open + name[open..].find(')')?)));
+    if let Some((open, close)) = parenthesized {
+        notes.push(name[open + 1..close].trim().to_string());
+        name.replace_range(open..=close, " ");
+    }
+
+    if let Some((before, after)) = name.split_once(',') {
+        notes.push(after.trim().to_string());
+        name = before.to_string();
+    }
+
+    notes.retain(|note| !note.is_empty());
+
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    let note = (!notes.is_empty()).then(|| notes.join(", "));
+
+    (name, note)
+}
+
+impl<TR: TextRecognizer> ImportService<TR> {
+    pub fn new(recognizer: TR) -> Self {
+        Self { recognizer }
+    }
+
+    /// Reads a photo of a recipe, like a cookbook page, into a draft for the user to review. The
+    /// draft is not saved.
+    pub async fn import_image(&self, image: Vec<u8>) -> Result<NewRecipe, ImportImageError> {
+        let text = self.recognizer.recognize(image).await?;
+
+        if text.trim().is_empty() {
+            return Err(ImportImageError::NoText);
+        }
+
+        Ok(draft_from_text(&text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+
+    use crate::core::recipe::QuantityType;
+
+    #[test]
+    fn it_drafts_a_recipe_from_text() {
+        let draft = draft_from_text(
+            "Pancakes\n\nIngredients:\n- 250 g flour\n2 eggs\n500ml milk\n\nMethod\nWhisk everything together.\nFry for breakfast.",
+        );
+
+        check!(draft.name == "Pancakes");
+        check!(draft.ingredients.len() == 3);
+        check!(draft.ingredients[0].name == "flour");
+        check!(draft.ingredients[0].quantity == 250.0);
+        let_assert!(QuantityType::Gram = &draft.ingredients[0].quantity_type);
+        check!(draft.ingredients[1].name == "eggs");
+        let_assert!(QuantityType::Count = &draft.ingredients[1].quantity_type);
+        check!(draft.ingredients[2].name == "milk");
+        let_assert!(QuantityType::Milliliter = &draft.ingredients[2].quantity_type);
+        check!(
+            draft.description.as_deref() == Some("Whisk everything together.\nFry for breakfast.")
+        );
+        let_assert!(MealType::Breakfast = draft.meal_type);
+    }
+
+    #[test]
+    fn it_keeps_numbered_steps_in_the_description() {
+        let draft = draft_from_text(
+            "Tomato soup\nIngredients\n1 kg tomatoes\n1 onion, chopped\nInstructions:\n1. Fry the onion.\n2. Add the tomatoes.",
+        );
+
+        check!(draft.ingredients.len() == 2);
+        check!(draft.ingredients[1].name == "onion, chopped");
+        check!(draft.description.as_deref() == Some("1. Fry the onion.\n2. Add the tomatoes."));
+    }
+
+    #[test]
+    fn it_parses_fractional_quantities() {
+        let_assert!(Some(ingredient) = parse_ingredient("1/2 l of stock"));
+
+        check!(ingredient.quantity == Quantity::new(Unit::Liter, 0.5));
+        check!(ingredient.name == "stock");
+    }
+
+    #[test]
+    fn it_parses_mixed_numbers_and_notes() {
+        let_assert!(Some(ingredient) = parse_ingredient("2 1/2 cups all-purpose flour, sifted"));
+
+        check!(ingredient.quantity == Quantity::new(Unit::Cup, 2.5));
+        check!(ingredient.name == "all-purpose flour");
+        check!(ingredient.note.as_deref() == Some("sifted"));
+    }
+
+    #[test]
+    fn it_parses_imperial_units_and_vulgar_fractions() {
+        let_assert!(Some(butter) = parse_ingredient("1½ Tbsp. butter (softened)"));
+        check!(butter.quantity == Quantity::new(Unit::Tablespoon, 1.5));
+        check!(butter.name == "butter");
+        check!(butter.note.as_deref() == Some("softened"));
+
+        let_assert!(Some(cream) = parse_ingredient("¾ fl oz heavy cream"));
+        check!(cream.quantity == Quantity::new(Unit::FluidOunce, 0.75));
+        check!(cream.name == "heavy cream");
+        check!(cream.note.is_none());
+    }
+
+    #[test]
+    fn it_keeps_the_lower_bound_of_ranges() {
+        let_assert!(Some(garlic) = parse_ingredient("2-3 cloves garlic, minced"));
+
+        check!(garlic.quantity == Quantity::new(Unit::Count, 2.0));
+        check!(garlic.name == "cloves garlic");
+        check!(garlic.note.as_deref() == Some("minced"));
+    }
+
+    #[test]
+    fn it_keeps_lines_without_a_quantity_in_the_description() {
+        check!(parse_ingredient("Salt and pepper to taste").is_none());
+        check!(parse_ingredient("200").is_none());
+    }
+
+    #[test]
+    fn it_stores_parsed_ingredients_in_metric_with_the_note_in_the_name() {
+        let_assert!(Some(parsed) = parse_ingredient("2 cups milk, warm"));
+        let ingredient = Ingredient::from(parsed);
+
+        check!(ingredient.name == "milk, warm");
+        check!(ingredient.quantity == 473.18);
+        let_assert!(QuantityType::Milliliter = ingredient.quantity_type);
+    }
+}
@@ -0,0 +1,354 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::{
+    core::recipe::{Ingredient, MealType, normalize_name},
+    persistance::ingredient::{
+        IngredientDensityEntity, IngredientRecipeEntity, IngredientRepository,
+        IngredientUsageEntity,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct IngredientService<IR: IngredientRepository> {
+    repository: IR,
+}
+
+/// Ingredient name along with the number of recipes using it
+#[derive(Debug)]
+pub struct IngredientUsage {
+    pub name: String,
+    pub recipe_count: u64,
+}
+
+impl From<IngredientUsageEntity> for IngredientUsage {
+    fn from(value: IngredientUsageEntity) -> Self {
+        Self {
+            name: value.name,
+            recipe_count: value.recipe_count.try_into().unwrap_or_default(),
+        }
+    }
+}
+
+/// Recipe using an ingredient, with the ingredient as it appears in the recipe
+#[derive(Debug)]
+pub struct IngredientRecipe {
+    pub recipe_id: i32,
+    pub public_id: Uuid,
+    pub name: String,
+    pub meal_type: MealType,
+    pub ingredient: Ingredient,
+}
+
+impl From<IngredientRecipeEntity> for IngredientRecipe {
+    fn from(value: IngredientRecipeEntity) -> Self {
+        Self {
+            recipe_id: value.recipe_id,
+            public_id: value.public_id,
+            name: value.name,
+            meal_type: value.meal_type.into(),
+            ingredient: Ingredient {
+                name: value.ingredient_name,
+                quantity_type: value.quantity_type.into(),
+                quantity: value.quantity,
+            },
+        }
+    }
+}
+
+/// How many grams a milliliter of an ingredient weighs, to convert between volume and mass
+#[derive(Debug, Clone, PartialEq)]
+pub struct IngredientDensity {
+    /// Lowercase name of the ingredient
+    pub name: String,
+    pub grams_per_milliliter: f32,
+}
+
+impl From<IngredientDensityEntity> for IngredientDensity {
+    fn from(value: IngredientDensityEntity) -> Self {
+        Self {
+            name: value.name,
+            grams_per_milliliter: value.grams_per_milliliter,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ListIngredientsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::ingredient::ListIngredientsError> for ListIngredientsError {
+    fn from(value: crate::persistance::ingredient::ListIngredientsError) -> Self {
+        match value {
+            crate::persistance::ingredient::ListIngredientsError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum IngredientRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::ingredient::IngredientRecipesError> for IngredientRecipesError {
+    fn from(value: crate::persistance::ingredient::IngredientRecipesError) -> Self {
+        match value {
+            crate::persistance::ingredient::IngredientRecipesError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RenameIngredientError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("Ingredient names can't be empty")]
+    EmptyName,
+}
+
+impl From<crate::persistance::ingredient::RenameIngredientError> for RenameIngredientError {
+    fn from(value: crate::persistance::ingredient::RenameIngredientError) -> Self {
+        match value {
+            crate::persistance::ingredient::RenameIngredientError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ListDensitiesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::ingredient::ListDensitiesError> for ListDensitiesError {
+    fn from(value: crate::persistance::ingredient::ListDensitiesError) -> Self {
+        match value {
+            crate::persistance::ingredient::ListDensitiesError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SetDensityError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("Ingredient names can't be empty")]
+    EmptyName,
+    #[error("Densities have to be a positive number of grams per milliliter")]
+    InvalidDensity,
+}
+
+impl From<crate::persistance::ingredient::SetDensityError> for SetDensityError {
+    fn from(value: crate::persistance::ingredient::SetDensityError) -> Self {
+        match value {
+            crate::persistance::ingredient::SetDensityError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum DeleteDensityError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("No density is known for the ingredient")]
+    NotFound,
+}
+
+impl From<crate::persistance::ingredient::DeleteDensityError> for DeleteDensityError {
+    fn from(value: crate::persistance::ingredient::DeleteDensityError) -> Self {
+        match value {
+            crate::persistance::ingredient::DeleteDensityError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::persistance::ingredient::DeleteDensityError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl<IR: IngredientRepository> IngredientService<IR> {
+    pub fn new(repository: IR) -> Self {
+        Self { repository }
+    }
+
+    pub async fn list_ingredients(&self) -> Result<Vec<IngredientUsage>, ListIngredientsError> {
+        let entities = self.repository.list_ingredients().await?;
+        Ok(entities.into_iter().map(IngredientUsage::from).collect())
+    }
+
+    /// Lists the recipes using an ingredient, matched ignoring case
+    pub async fn ingredient_recipes(
+        &self,
+        name: &str,
+    ) -> Result<Vec<IngredientRecipe>, IngredientRecipesError> {
+        let entities = self
+            .repository
+            .ingredient_recipes(normalize_name(name))
+            .await?;
+
+        Ok(entities.into_iter().map(IngredientRecipe::from).collect())
+    }
+
+    /// Renames an ingredient in every recipe using it, like after importing recipes with a typo.
+    /// The old name is matched ignoring case. Returns the number of recipes which were changed.
+    pub async fn rename_ingredient(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<u64, RenameIngredientError> {
+        let from = normalize_name(from);
+        let to = normalize_name(to);
+
+        if from.is_empty() || to.is_empty() {
+            return Err(RenameIngredientError::EmptyName);
+        }
+
+        Ok(self.repository.rename_ingredient(from, to).await?)
+    }
+
+    pub async fn list_densities(&self) -> Result<Vec<IngredientDensity>, ListDensitiesError> {
+        let entities = self.repository.list_densities().await?;
+        Ok(entities.into_iter().map(IngredientDensity::from).collect())
+    }
+
+    /// Sets the density of an ingredient, stored under its lowercase name
+    pub async fn set_density(
+        &self,
+        name: &str,
+        grams_per_milliliter: f32,
+    ) -> Result<IngredientDensity, SetDensityError> {
+        let name = normalize_name(name).to_lowercase();
+
+        if name.is_empty() {
+            return Err(SetDensityError::EmptyName);
+        }
+
+        if !grams_per_milliliter.is_finite() || grams_per_milliliter <= 0.0 {
+            return Err(SetDensityError::InvalidDensity);
+        }
+
+        let entity = self
+            .repository
+            .set_density(name, grams_per_milliliter)
+            .await?;
+
+        Ok(entity.into())
+    }
+
+    pub async fn delete_density(&self, name: &str) -> Result<(), DeleteDensityError> {
+        let name = normalize_name(name).to_lowercase();
+        Ok(self.repository.delete_density(name).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+
+    use crate::test_utils::MockRepository;
+
+    #[tokio::test]
+    async fn it_looks_up_recipes_by_the_normalized_name() {
+        let repository = MockRepository::new();
+        repository.on_ingredient_recipes(Ok(vec![]));
+        let svc = IngredientService::new(repository.clone());
+
+        let_assert!(Ok(recipes) = svc.ingredient_recipes(" Cre\u{300}me  fraiche").await);
+
+        check!(recipes.is_empty());
+        check!(repository.calls_to("ingredient_recipes")[0].arguments == r#"("Crème fraiche",)"#);
+    }
+
+    #[tokio::test]
+    async fn it_renames_to_a_normalized_name() {
+        let repository = MockRepository::new();
+        repository.on_rename_ingredient(Ok(3));
+        let svc = IngredientService::new(repository.clone());
+
+        let_assert!(
+            Ok(3) = svc
+                .rename_ingredient("tomatoe", " Cre\u{300}me  fraiche ")
+                .await
+        );
+
+        let calls = repository.calls_to("rename_ingredient");
+        check!(calls.len() == 1);
+        check!(calls[0].arguments == r#"("tomatoe", "Crème fraiche")"#);
+    }
+
+    #[tokio::test]
+    async fn it_rejects_empty_names() {
+        let repository = MockRepository::new();
+        let svc = IngredientService::new(repository.clone());
+
+        let_assert!(
+            Err(RenameIngredientError::EmptyName) = svc.rename_ingredient("Tomato", " ").await
+        );
+        check!(repository.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_stores_densities_under_the_lowercase_name() {
+        let repository = MockRepository::new();
+        repository.on_set_density(Ok(IngredientDensityEntity {
+            name: "spelt flour".to_string(),
+            grams_per_milliliter: 0.55,
+        }));
+        let svc = IngredientService::new(repository.clone());
+
+        let_assert!(Ok(_) = svc.set_density(" Spelt  Flour", 0.55).await);
+
+        check!(repository.calls_to("set_density")[0].arguments == r#"("spelt flour", 0.55)"#);
+    }
+
+    #[tokio::test]
+    async fn it_rejects_densities_which_are_not_positive() {
+        let repository = MockRepository::new();
+        let svc = IngredientService::new(repository.clone());
+
+        for density in [0.0, -1.0, f32::NAN] {
+            let_assert!(
+                Err(SetDensityError::InvalidDensity) = svc.set_density("Flour", density).await
+            );
+        }
+        check!(repository.calls().is_empty());
+    }
+}
@@ -0,0 +1,138 @@
+use thiserror::Error;
+
+use crate::persistance::integrity::{IntegrityIssuesEntity, IntegrityRepository};
+
+#[derive(Debug, Clone)]
+pub struct IntegrityService<IR: IntegrityRepository> {
+    repository: IR,
+}
+
+/// Outcome of an integrity check
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// Ids of ingredients pointing to a recipe which doesn't exist
+    pub orphaned_ingredients: Vec<i32>,
+    /// Ids of recipes whose ingredients aren't numbered `0..n`
+    pub broken_ingredient_orders: Vec<i32>,
+    /// Whether the issues found were repaired
+    pub repaired: bool,
+}
+
+impl IntegrityReport {
+    pub fn is_clean(&self) -> bool {
+        self.orphaned_ingredients.is_empty() && self.broken_ingredient_orders.is_empty()
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CheckIntegrityError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::integrity::CheckIntegrityError> for CheckIntegrityError {
+    fn from(value: crate::persistance::integrity::CheckIntegrityError) -> Self {
+        match value {
+            crate::persistance::integrity::CheckIntegrityError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+impl From<crate::persistance::integrity::RepairIntegrityError> for CheckIntegrityError {
+    fn from(value: crate::persistance::integrity::RepairIntegrityError) -> Self {
+        match value {
+            crate::persistance::integrity::RepairIntegrityError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+impl<IR: IntegrityRepository> IntegrityService<IR> {
+    pub fn new(repository: IR) -> Self {
+        Self { repository }
+    }
+
+    /// Looks for rows breaking referential integrity, and repairs them when `repair` is set. The
+    /// report lists the issues which were found before repairing.
+    pub async fn check(&self, repair: bool) -> Result<IntegrityReport, CheckIntegrityError> {
+        let issues = self.repository.check_integrity().await?;
+
+        let mut report = IntegrityReport {
+            orphaned_ingredients: issues.orphaned_ingredients.clone(),
+            broken_ingredient_orders: issues.broken_ingredient_orders.clone(),
+            repaired: false,
+        };
+
+        if repair && issues != IntegrityIssuesEntity::default() {
+            self.repository.repair_integrity(issues).await?;
+            report.repaired = true;
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+
+    use crate::test_utils::MockRepository;
+
+    fn issues() -> IntegrityIssuesEntity {
+        IntegrityIssuesEntity {
+            orphaned_ingredients: vec![],
+            broken_ingredient_orders: vec![4],
+        }
+    }
+
+    #[tokio::test]
+    async fn it_only_repairs_on_request() {
+        let repository = MockRepository::new();
+        repository.on_check_integrity(Ok(issues()));
+        let svc = IntegrityService::new(repository.clone());
+
+        let_assert!(Ok(report) = svc.check(false).await);
+
+        check!(report.broken_ingredient_orders == [4]);
+        check!(!report.repaired);
+        check!(repository.calls_to("repair_integrity").is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_repairs_the_issues_found() {
+        let repository = MockRepository::new();
+        repository
+            .on_check_integrity(Ok(issues()))
+            .on_repair_integrity(Ok(()));
+        let svc = IntegrityService::new(repository.clone());
+
+        let_assert!(Ok(report) = svc.check(true).await);
+
+        check!(report.repaired);
+        let calls = repository.calls_to("repair_integrity");
+        check!(calls.len() == 1);
+        check!(calls[0].arguments.contains("broken_ingredient_orders: [4]"));
+    }
+
+    #[tokio::test]
+    async fn it_skips_repairing_a_clean_database() {
+        let repository = MockRepository::new();
+        repository.on_check_integrity(Ok(IntegrityIssuesEntity::default()));
+        let svc = IntegrityService::new(repository.clone());
+
+        let_assert!(Ok(report) = svc.check(true).await);
+
+        check!(report.is_clean());
+        check!(!report.repaired);
+        check!(repository.calls_to("repair_integrity").is_empty());
+    }
+}
@@ -0,0 +1,354 @@
+use std::{collections::HashMap, time::Duration};
+
+use chrono::{Datelike, Local, NaiveDate, Weekday};
+use rand::{Rng, seq::IndexedRandom};
+use thiserror::Error;
+
+use crate::{
+    core::{
+        recipe::{MealType, RecipeName},
+        tag::normalize_tags,
+    },
+    persistance::recipe::{RecipeEntity, RecipeRepository, SearchRecipesArguments, SearchWeights},
+};
+
+/// Longest plan generated at once
+pub const MAX_PLAN_DAYS: u32 = 28;
+
+#[derive(Debug, Clone)]
+pub struct MealPlanService<RR: RecipeRepository> {
+    repository: RR,
+}
+
+/// What a generated plan has to respect
+#[derive(Debug)]
+pub struct PlanConstraints {
+    /// First day of the plan, defaults to today
+    pub start: Option<NaiveDate>,
+    pub days: u32,
+    /// Meals to plan every day, in the order they are listed per day
+    pub meal_types: Vec<MealType>,
+    /// Upper bound for the cooking time of meals from Monday to Friday. Recipes without a cooking
+    /// time are then only planned in the weekend.
+    pub max_weekday_cooking_time: Option<Duration>,
+    /// Only recipes tagged with every one of these tags are planned, like `vegetarian`
+    pub tags: Vec<String>,
+    /// Number of days after a recipe was planned or last cooked before it is planned again
+    pub avoid_repeats_days: u32,
+}
+
+/// Meal in a generated plan, without a recipe when none fit the constraints
+#[derive(Debug)]
+pub struct PlannedMeal {
+    pub date: NaiveDate,
+    pub meal_type: MealType,
+    pub recipe: Option<RecipeName>,
+}
+
+/// What planning needs to know about a recipe
+#[derive(Debug)]
+struct Candidate {
+    recipe_id: i32,
+    name: String,
+    cooking_time: Option<Duration>,
+    last_cooked_at: Option<NaiveDate>,
+}
+
+impl From<RecipeEntity> for Candidate {
+    fn from(value: RecipeEntity) -> Self {
+        Self {
+            recipe_id: value.recipe_id,
+            name: value.name,
+            cooking_time: value.cooking_time,
+            last_cooked_at: value.last_cooked_at,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GeneratePlanError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("A plan covers between 1 and {MAX_PLAN_DAYS} days")]
+    InvalidDays,
+    #[error("At least one meal type is required")]
+    NoMealTypes,
+}
+
+impl From<crate::persistance::recipe::SearchRecipeError> for GeneratePlanError {
+    fn from(value: crate::persistance::recipe::SearchRecipeError) -> Self {
+        match value {
+            crate::persistance::recipe::SearchRecipeError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+/// Fills every meal of every day with a random recipe out of the candidates for its meal type,
+/// `candidates` holding the candidates per entry of `meal_types`
+fn plan(
+    meal_types: &[MealType],
+    candidates: &[Vec<Candidate>],
+    start: NaiveDate,
+    constraints: &PlanConstraints,
+    rng: &mut impl Rng,
+) -> Vec<PlannedMeal> {
+    // Day each recipe was last eaten on, planned meals included
+    let mut last_eaten = candidates
+        .iter()
+        .flatten()
+        .filter_map(|candidate| Some((candidate.recipe_id, candidate.last_cooked_at?)))
+        .collect::<HashMap<_, _>>();
+
+    let mut meals = vec![];
+
+    for date in start.iter_days().take(constraints.days as usize) {
+        let weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+
+        for (meal_type, candidates) in meal_types.iter().zip(candidates) {
+            let fitting = candidates
+                .iter()
+                .filter(|candidate| match constraints.max_weekday_cooking_time {
+                    Some(max) if !weekend => candidate.cooking_time.is_some_and(|time| time <= max),
+                    _ => true,
+                })
+                .filter(|candidate| {
+                    last_eaten.get(&candidate.recipe_id).is_none_or(|eaten| {
+                        (date - *eaten).num_days() > i64::from(constraints.avoid_repeats_days)
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            let recipe = fitting.choose(rng).map(|candidate| {
+                last_eaten.insert(candidate.recipe_id, date);
+
+                RecipeName {
+                    recipe_id: candidate.recipe_id,
+                    name: candidate.name.clone(),
+                }
+            });
+
+            meals.push(PlannedMeal {
+                date,
+                meal_type: *meal_type,
+                recipe,
+            });
+        }
+    }
+
+    meals
+}
+
+impl<RR: RecipeRepository> MealPlanService<RR> {
+    pub fn new(repository: RR) -> Self {
+        Self { repository }
+    }
+
+    /// Generates a plan out of the recipes which aren't archived. Meals no recipe fits are left
+    /// empty rather than breaking the constraints.
+    pub async fn generate(
+        &self,
+        constraints: PlanConstraints,
+    ) -> Result<Vec<PlannedMeal>, GeneratePlanError> {
+        if !(1..=MAX_PLAN_DAYS).contains(&constraints.days) {
+            return Err(GeneratePlanError::InvalidDays);
+        }
+
+        let mut meal_types = Vec::<MealType>::new();
+        for meal_type in &constraints.meal_types {
+            if !meal_types.contains(meal_type) {
+                meal_types.push(*meal_type);
+            }
+        }
+
+        if meal_types.is_empty() {
+            return Err(GeneratePlanError::NoMealTypes);
+        }
+
+        let tags = normalize_tags(&constraints.tags);
+        let mut candidates = vec![];
+
+        for meal_type in &meal_types {
+            let entities = self
+                .repository
+                .search_recipes(SearchRecipesArguments {
+                    recipe_name: None,
+                    ingredient_name: None,
+                    meal_type: Some((*meal_type).into()),
+                    include_ingredients: false,
+                    archived: false,
+                    tags: tags.clone(),
+                    sort: vec![],
+                    created_after: None,
+                    created_before: None,
+                    updated_after: None,
+                    keywords: vec![],
+                    weights: SearchWeights::default(),
+                })
+                .await?;
+
+            candidates.push(
+                entities
+                    .into_iter()
+                    .map(Candidate::from)
+                    .collect::<Vec<_>>(),
+            );
+        }
+
+        let start = constraints
+            .start
+            .unwrap_or_else(|| Local::now().date_naive());
+
+        Ok(plan(
+            &meal_types,
+            &candidates,
+            start,
+            &constraints,
+            &mut rand::rng(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+
+    use crate::test_utils::{MockRepository, recipe_entity};
+
+    fn candidate(recipe_id: i32, cooking_time_mins: Option<u64>) -> Candidate {
+        Candidate {
+            recipe_id,
+            name: format!("Recipe {recipe_id}"),
+            cooking_time: cooking_time_mins.map(|mins| Duration::from_secs(mins * 60)),
+            last_cooked_at: None,
+        }
+    }
+
+    fn constraints(days: u32) -> PlanConstraints {
+        PlanConstraints {
+            start: None,
+            days,
+            meal_types: vec![MealType::Dinner],
+            max_weekday_cooking_time: None,
+            tags: vec![],
+            avoid_repeats_days: 0,
+        }
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        // 2025-10-20 is a Monday
+        NaiveDate::from_ymd_opt(2025, 10, 19 + day).expect("Valid date")
+    }
+
+    fn recipe_ids(meals: &[PlannedMeal]) -> Vec<Option<i32>> {
+        meals
+            .iter()
+            .map(|meal| meal.recipe.as_ref().map(|recipe| recipe.recipe_id))
+            .collect()
+    }
+
+    #[test]
+    fn it_keeps_long_recipes_for_the_weekend() {
+        let constraints = PlanConstraints {
+            max_weekday_cooking_time: Some(Duration::from_secs(30 * 60)),
+            ..constraints(7)
+        };
+        let candidates = vec![vec![candidate(1, Some(20)), candidate(2, Some(90))]];
+
+        let meals = plan(
+            &[MealType::Dinner],
+            &candidates,
+            date(1),
+            &constraints,
+            &mut rand::rng(),
+        );
+
+        let_assert!([weekdays @ .., _, _] = meals.as_slice());
+        check!(recipe_ids(weekdays) == vec![Some(1); 5]);
+        check!(meals[5].date.weekday() == Weekday::Sat);
+    }
+
+    #[test]
+    fn it_avoids_repeats_within_the_given_days() {
+        let constraints = PlanConstraints {
+            avoid_repeats_days: 2,
+            ..constraints(9)
+        };
+        let candidates = vec![vec![
+            candidate(1, None),
+            candidate(2, None),
+            candidate(3, None),
+        ]];
+
+        let meals = plan(
+            &[MealType::Dinner],
+            &candidates,
+            date(1),
+            &constraints,
+            &mut rand::rng(),
+        );
+
+        for window in recipe_ids(&meals).windows(3) {
+            check!(window[0].is_some());
+            check!(window[0] != window[1] && window[0] != window[2] && window[1] != window[2]);
+        }
+    }
+
+    #[test]
+    fn it_leaves_meals_empty_when_nothing_fits() {
+        let constraints = PlanConstraints {
+            avoid_repeats_days: 7,
+            ..constraints(3)
+        };
+        let mut cooked = candidate(1, None);
+        cooked.last_cooked_at = Some(date(0));
+        let candidates = vec![vec![cooked, candidate(2, None)]];
+
+        let meals = plan(
+            &[MealType::Dinner],
+            &candidates,
+            date(1),
+            &constraints,
+            &mut rand::rng(),
+        );
+
+        check!(recipe_ids(&meals) == vec![Some(2), None, None]);
+    }
+
+    #[tokio::test]
+    async fn it_searches_candidates_per_meal_type() {
+        let repository = MockRepository::new();
+        repository.on_search_recipes(Ok(vec![recipe_entity(1, "Porridge")]));
+        repository.on_search_recipes(Ok(vec![recipe_entity(2, "Stew")]));
+        let svc = MealPlanService::new(repository.clone());
+
+        let constraints = PlanConstraints {
+            start: Some(date(1)),
+            meal_types: vec![MealType::Breakfast, MealType::Dinner, MealType::Breakfast],
+            tags: vec!["Vegetarian".to_string()],
+            ..constraints(2)
+        };
+        let_assert!(Ok(meals) = svc.generate(constraints).await);
+
+        let calls = repository.calls_to("search_recipes");
+        let_assert!([breakfast, dinner] = calls.as_slice());
+        check!(breakfast.arguments.contains("meal_type: Some(Breakfast)"));
+        check!(dinner.arguments.contains("meal_type: Some(Dinner)"));
+        check!(breakfast.arguments.contains(r#"tags: ["vegetarian"]"#));
+
+        check!(recipe_ids(&meals) == vec![Some(1), Some(2), Some(1), Some(2)]);
+        check!(meals[2].date == date(2));
+    }
+
+    #[tokio::test]
+    async fn it_rejects_plans_without_days() {
+        let svc = MealPlanService::new(MockRepository::new());
+
+        let_assert!(Err(GeneratePlanError::InvalidDays) = svc.generate(constraints(0)).await);
+    }
+}
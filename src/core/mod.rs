@@ -1 +1,27 @@
-pub(crate) mod recipe;
+pub mod analytics;
+pub mod cooking_log;
+pub mod data_quality;
+pub mod deletion;
+pub mod duplicate;
+pub mod event;
+pub mod export;
+pub mod federation;
+pub mod generate;
+pub mod import;
+pub mod ingredient;
+pub mod integrity;
+pub mod meal_plan;
+pub mod nextcloud;
+pub mod notification;
+pub mod qr_code;
+pub mod rate_limit;
+pub mod recipe;
+pub mod retention;
+pub mod saved_search;
+pub mod share;
+pub mod share_link;
+pub mod snapshot;
+pub mod sync;
+pub mod tag;
+pub mod timer;
+pub mod unit;
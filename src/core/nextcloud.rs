@@ -0,0 +1,207 @@
+use std::time::Duration;
+
+use crate::core::{
+    import::parse_ingredient,
+    recipe::{Ingredient, MealType, NewRecipe},
+    tag::normalize_tags,
+    unit::Unit,
+};
+
+/// Recipe as Nextcloud Cookbook stores it in the `recipe.json` of its folder
+#[derive(Debug, Default)]
+pub struct NextcloudRecipe {
+    pub name: String,
+    /// Short introduction of the recipe
+    pub description: Option<String>,
+    /// Ingredients written out, like `2 cups flour`
+    pub ingredients: Vec<String>,
+    pub instructions: Vec<String>,
+    pub prep_time: Option<Duration>,
+    pub cook_time: Option<Duration>,
+    pub total_time: Option<Duration>,
+    pub category: Option<String>,
+    pub keywords: Vec<String>,
+    /// Website the recipe was taken from
+    pub url: Option<String>,
+    /// Fields without a place in a recipe, like `nutrition` or the photo
+    pub unsupported: Vec<String>,
+}
+
+/// Recipe converted from Nextcloud Cookbook, along with what had to change to fit it
+#[derive(Debug)]
+pub struct NextcloudConversion {
+    pub draft: NewRecipe,
+    pub tags: Vec<String>,
+    /// Changes made to fit the recipe, like quantities converted to metric
+    pub conversions: Vec<String>,
+    /// Fields which were left out
+    pub skipped: Vec<String>,
+}
+
+/// Converts a Nextcloud Cookbook recipe. Nothing written in the recipe is dropped when it fits
+/// somewhere: instructions, ingredients without a quantity and the source url are kept in the
+/// description and categories which aren't a meal type become a tag.
+pub fn convert_nextcloud(recipe: NextcloudRecipe) -> NextcloudConversion {
+    let mut conversions = vec![];
+    let mut tags = recipe.keywords;
+
+    let mut ingredients = vec![];
+    let mut unparsed = vec![];
+
+    for line in recipe
+        .ingredients
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+    {
+        match parse_ingredient(line) {
+            Some(parsed) => {
+                let imperial = !matches!(
+                    parsed.quantity.unit,
+                    Unit::Count | Unit::Kilo | Unit::Gram | Unit::Liter | Unit::Milliliter
+                );
+                let ingredient = Ingredient::from(parsed);
+
+                if imperial {
+                    conversions.push(format!("`{line}` became `{ingredient}`"));
+                }
+
+                ingredients.push(ingredient);
+            }
+            None => {
+                conversions.push(format!(
+                    "`{line}` has no quantity and was added to the description"
+                ));
+                unparsed.push(line);
+            }
+        }
+    }
+
+    let mut sections = vec![];
+
+    if let Some(description) = recipe.description.filter(|d| !d.trim().is_empty()) {
+        sections.push(description.trim().to_string());
+    }
+    if !unparsed.is_empty() {
+        sections.push(format!("Also needed: {}", unparsed.join(", ")));
+    }
+
+    let instructions = recipe
+        .instructions
+        .iter()
+        .map(|step| step.trim())
+        .filter(|step| !step.is_empty())
+        .collect::<Vec<_>>();
+    if !instructions.is_empty() {
+        sections.push(instructions.join("\n"));
+    }
+
+    if let Some(url) = recipe.url.filter(|url| !url.trim().is_empty()) {
+        conversions.push("The source url was added to the description".to_string());
+        sections.push(format!("Source: {}", url.trim()));
+    }
+
+    let cooking_time = match (recipe.total_time, recipe.prep_time, recipe.cook_time) {
+        (Some(total), _, _) => Some(total),
+        (None, Some(prep), Some(cook)) => {
+            conversions.push("The preparation and cooking time were added up".to_string());
+            Some(prep + cook)
+        }
+        (None, prep, cook) => prep.or(cook),
+    }
+    .filter(|time| !time.is_zero());
+
+    let meal_type = match recipe.category.as_deref().map(str::trim) {
+        Some(category) if category.eq_ignore_ascii_case("breakfast") => MealType::Breakfast,
+        Some(category) if category.eq_ignore_ascii_case("lunch") => MealType::Lunch,
+        Some(category) if category.eq_ignore_ascii_case("dinner") || category.is_empty() => {
+            MealType::Dinner
+        }
+        Some(category) => {
+            conversions.push(format!(
+                "The category `{category}` is not a meal type, it became a tag and the recipe a dinner"
+            ));
+            tags.push(category.to_string());
+            MealType::Dinner
+        }
+        None => MealType::Dinner,
+    };
+
+    let tags = normalize_tags(&tags);
+
+    NextcloudConversion {
+        draft: NewRecipe {
+            name: recipe.name.trim().to_string(),
+            description: (!sections.is_empty()).then(|| sections.join("\n\n")),
+            ingredients,
+            cooking_time,
+            meal_type,
+            video_url: None,
+            pairing: None,
+        },
+        tags,
+        conversions,
+        skipped: recipe.unsupported,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+
+    use crate::core::recipe::QuantityType;
+
+    #[test]
+    fn it_converts_a_cookbook_recipe() {
+        let conversion = convert_nextcloud(NextcloudRecipe {
+            name: "Pancakes".to_string(),
+            description: Some("Fluffy and quick.".to_string()),
+            ingredients: vec![
+                "2 cups milk".to_string(),
+                "3 eggs".to_string(),
+                "Butter for frying".to_string(),
+            ],
+            instructions: vec!["Whisk everything.".to_string(), "Fry.".to_string()],
+            prep_time: Some(Duration::from_secs(5 * 60)),
+            cook_time: Some(Duration::from_secs(15 * 60)),
+            category: Some("Breakfast".to_string()),
+            keywords: vec!["Sweet".to_string()],
+            url: Some("https://example.com/pancakes".to_string()),
+            unsupported: vec!["nutrition".to_string()],
+            ..NextcloudRecipe::default()
+        });
+
+        let draft = conversion.draft;
+        check!(draft.name == "Pancakes");
+        let_assert!(MealType::Breakfast = draft.meal_type);
+        check!(draft.cooking_time == Some(Duration::from_secs(20 * 60)));
+        check!(draft.ingredients.len() == 2);
+        check!(draft.ingredients[0].quantity == 473.18);
+        let_assert!(QuantityType::Milliliter = &draft.ingredients[0].quantity_type);
+        check!(
+            draft.description.as_deref()
+                == Some(
+                    "Fluffy and quick.\n\nAlso needed: Butter for frying\n\nWhisk everything.\nFry.\n\nSource: https://example.com/pancakes"
+                )
+        );
+        check!(conversion.tags == vec!["sweet".to_string()]);
+        check!(conversion.conversions.len() == 4);
+        check!(conversion.conversions[0] == "`2 cups milk` became `473.18 ml milk`");
+        check!(conversion.skipped == vec!["nutrition".to_string()]);
+    }
+
+    #[test]
+    fn it_turns_categories_which_are_no_meal_type_into_tags() {
+        let conversion = convert_nextcloud(NextcloudRecipe {
+            name: "Brownies".to_string(),
+            category: Some("Dessert".to_string()),
+            ..NextcloudRecipe::default()
+        });
+
+        let_assert!(MealType::Dinner = conversion.draft.meal_type);
+        check!(conversion.tags == vec!["dessert".to_string()]);
+        check!(conversion.draft.description.is_none());
+    }
+}
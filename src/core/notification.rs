@@ -0,0 +1,144 @@
+use thiserror::Error;
+
+use crate::{
+    core::event::{RecipeEvent, RecipeEventKind},
+    notify::notifier::{Notification, Notifier, NotifyError, Priority},
+    persistance::recipe::{GetRecipeError, RecipeRepository},
+};
+
+/// Pushes recipe events to a push notification service
+#[derive(Debug, Clone)]
+pub struct NotificationService<RR: RecipeRepository, N: Notifier> {
+    repository: RR,
+    notifier: N,
+}
+
+#[derive(Debug, Error)]
+pub enum NotifyEventError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("Push notifications are not configured on this instance")]
+    Disabled,
+}
+
+impl From<NotifyError> for NotifyEventError {
+    fn from(value: NotifyError) -> Self {
+        match value {
+            NotifyError::Unknown(report) => Self::Unknown(report),
+            NotifyError::Disabled => Self::Disabled,
+        }
+    }
+}
+
+impl<RR: RecipeRepository, N: Notifier> NotificationService<RR, N> {
+    pub fn new(repository: RR, notifier: N) -> Self {
+        Self {
+            repository,
+            notifier,
+        }
+    }
+
+    /// Sends a notification for an event. Events for recipes which were deleted in the meantime
+    /// are skipped, the deletion is notified on its own.
+    pub async fn notify_event(&self, event: &RecipeEvent) -> Result<(), NotifyEventError> {
+        let name = match event.kind {
+            RecipeEventKind::Deleted => None,
+            RecipeEventKind::Created | RecipeEventKind::Updated => {
+                match self.repository.get_recipe(event.recipe_id).await {
+                    Ok(recipe) => Some(recipe.name),
+                    Err(GetRecipeError::NotFound) => return Ok(()),
+                    Err(GetRecipeError::Unknown(report)) => return Err(report.into()),
+                }
+            }
+        };
+
+        self.notifier
+            .notify(notification(event, name.as_deref()))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Deletions are the only change which can't be undone, so those stand out
+fn notification(event: &RecipeEvent, name: Option<&str>) -> Notification {
+    let name = name
+        .map(|name| format!("\"{name}\""))
+        .unwrap_or_else(|| format!("Recipe {}", event.public_id));
+
+    match event.kind {
+        RecipeEventKind::Created => Notification {
+            title: "New recipe".to_string(),
+            message: format!("{name} was added"),
+            priority: Priority::Default,
+        },
+        RecipeEventKind::Updated => Notification {
+            title: "Recipe updated".to_string(),
+            message: format!("{name} was changed"),
+            priority: Priority::Low,
+        },
+        RecipeEventKind::Deleted => Notification {
+            title: "Recipe deleted".to_string(),
+            message: format!("{name} was removed"),
+            priority: Priority::High,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+    use chrono::Utc;
+    use sqlx::PgPool;
+    use uuid::Uuid;
+
+    use crate::{
+        notify::implementation::ntfy::Ntfy, persistance::implementation::postgres::Postgres,
+    };
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    fn event(kind: RecipeEventKind) -> RecipeEvent {
+        RecipeEvent {
+            event_id: 1,
+            kind,
+            recipe_id: 1,
+            public_id: Uuid::nil(),
+            occurred_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn it_names_the_recipe_in_the_message() {
+        let notification = notification(&event(RecipeEventKind::Created), Some("Lasagne"));
+
+        check!(notification.title == "New recipe");
+        check!(notification.message == "\"Lasagne\" was added");
+        check!(notification.priority == Priority::Default);
+    }
+
+    #[test]
+    fn it_raises_the_priority_of_deletions() {
+        let notification = notification(&event(RecipeEventKind::Deleted), None);
+
+        check!(notification.message == format!("Recipe {} was removed", Uuid::nil()));
+        check!(notification.priority == Priority::High);
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_skips_events_of_deleted_recipes(pool: PgPool) {
+        let service = NotificationService::new(Postgres::new(pool), None::<Ntfy>);
+
+        let_assert!(Ok(()) = service.notify_event(&event(RecipeEventKind::Updated)).await);
+        let_assert!(
+            Err(NotifyEventError::Disabled) =
+                service.notify_event(&event(RecipeEventKind::Deleted)).await
+        );
+    }
+}
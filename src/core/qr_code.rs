@@ -0,0 +1,124 @@
+use eyre::Context;
+use qrcode::{Color, QrCode};
+use thiserror::Error;
+
+use crate::persistance::{recipe::RecipeRepository, share_link::ShareLinkRepository};
+
+/// Size of a single QR code module in pixels
+const MODULE_SIZE: usize = 8;
+/// Blank border around the code in modules, the QR specification asks for at least 4
+const QUIET_ZONE: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct QrCodeService<RR: RecipeRepository, SR: ShareLinkRepository> {
+    recipes: RR,
+    share_links: SR,
+    /// Url the instance is reachable on, without a trailing slash
+    public_url: String,
+}
+
+#[derive(Debug, Error)]
+pub enum RecipeQrCodeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+    #[error("The share link could not be found")]
+    ShareLinkNotFound,
+}
+
+impl From<crate::persistance::recipe::GetRecipeError> for RecipeQrCodeError {
+    fn from(value: crate::persistance::recipe::GetRecipeError) -> Self {
+        match value {
+            crate::persistance::recipe::GetRecipeError::Unknown(report) => Self::Unknown(report),
+            crate::persistance::recipe::GetRecipeError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl From<crate::persistance::share_link::ResolveShareLinkError> for RecipeQrCodeError {
+    fn from(value: crate::persistance::share_link::ResolveShareLinkError) -> Self {
+        match value {
+            crate::persistance::share_link::ResolveShareLinkError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::persistance::share_link::ResolveShareLinkError::NotFound => {
+                Self::ShareLinkNotFound
+            }
+        }
+    }
+}
+
+impl<RR: RecipeRepository, SR: ShareLinkRepository> QrCodeService<RR, SR> {
+    pub fn new(recipes: RR, share_links: SR, public_url: &str) -> Self {
+        Self {
+            recipes,
+            share_links,
+            public_url: public_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Renders a PNG QR code linking to the recipe, or to the share link when a token is given
+    pub async fn recipe_qr_code(
+        &self,
+        recipe_id: i32,
+        token: Option<&str>,
+    ) -> Result<Vec<u8>, RecipeQrCodeError> {
+        self.recipes.get_recipe(recipe_id).await?;
+
+        let url = match token {
+            Some(token) => {
+                if self.share_links.resolve_share_link(token).await? != recipe_id {
+                    return Err(RecipeQrCodeError::ShareLinkNotFound);
+                }
+
+                format!("{}/shared/{token}", self.public_url)
+            }
+            None => format!("{}/recipes/{recipe_id}", self.public_url),
+        };
+
+        Ok(render_png(&url)?)
+    }
+}
+
+fn render_png(data: &str) -> eyre::Result<Vec<u8>> {
+    let code = QrCode::new(data.as_bytes()).wrap_err("Failed to encode QR code")?;
+    let colors = code.to_colors();
+
+    let modules = code.width() + 2 * QUIET_ZONE;
+    let size = modules * MODULE_SIZE;
+
+    let mut pixels = vec![u8::MAX; size * size];
+
+    for (idx, color) in colors.iter().enumerate() {
+        if *color == Color::Light {
+            continue;
+        }
+
+        let x = (idx % code.width() + QUIET_ZONE) * MODULE_SIZE;
+        let y = (idx / code.width() + QUIET_ZONE) * MODULE_SIZE;
+
+        for row in y..y + MODULE_SIZE {
+            pixels[row * size + x..row * size + x + MODULE_SIZE].fill(0);
+        }
+    }
+
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, size as u32, size as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder
+        .write_header()
+        .wrap_err("Failed to write PNG header")?;
+    writer
+        .write_image_data(&pixels)
+        .wrap_err("Failed to write PNG data")?;
+    writer.finish().wrap_err("Failed to finish PNG")?;
+
+    Ok(png)
+}
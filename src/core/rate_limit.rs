@@ -0,0 +1,106 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Sliding window rate limiter, clones share the same window
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    limit: usize,
+    window: Duration,
+    hits: Arc<Mutex<VecDeque<Instant>>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Self {
+            limit: limit as usize,
+            window,
+            hits: Arc::default(),
+        }
+    }
+
+    /// Records a hit if the limit allows it, otherwise returns how long to wait before the next
+    /// hit would be allowed
+    pub fn try_acquire(&self) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut hits = self.hits.lock().expect("Rate limiter lock poisoned");
+
+        while hits
+            .front()
+            .is_some_and(|hit| now.duration_since(*hit) >= self.window)
+        {
+            hits.pop_front();
+        }
+
+        if hits.len() < self.limit {
+            hits.push_back(now);
+            return Ok(());
+        }
+
+        let oldest = hits.front().copied().unwrap_or(now);
+        Err(self.window.saturating_sub(now.duration_since(oldest)))
+    }
+
+    /// Gives back the most recent hit, for when what it was recorded for didn't happen after all
+    pub fn release(&self) {
+        self.hits
+            .lock()
+            .expect("Rate limiter lock poisoned")
+            .pop_back();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::{check, let_assert};
+
+    #[test]
+    fn it_allows_hits_up_to_the_limit() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+
+        check!(limiter.try_acquire().is_ok());
+        check!(limiter.try_acquire().is_ok());
+
+        let_assert!(Err(retry_after) = limiter.try_acquire());
+        check!(retry_after <= Duration::from_secs(60));
+        check!(retry_after > Duration::from_secs(58));
+    }
+
+    #[test]
+    fn it_shares_the_window_between_clones() {
+        let limiter = RateLimiter::new(1, Duration::from_secs(60));
+        let clone = limiter.clone();
+
+        check!(limiter.try_acquire().is_ok());
+        check!(clone.try_acquire().is_err());
+    }
+
+    #[test]
+    fn it_allows_hits_again_once_the_window_passed() {
+        let limiter = RateLimiter::new(1, Duration::from_millis(10));
+
+        check!(limiter.try_acquire().is_ok());
+        std::thread::sleep(Duration::from_millis(20));
+        check!(limiter.try_acquire().is_ok());
+    }
+
+    #[test]
+    fn it_allows_a_hit_again_once_one_is_released() {
+        let limiter = RateLimiter::new(1, Duration::from_secs(60));
+
+        check!(limiter.try_acquire().is_ok());
+        limiter.release();
+        check!(limiter.try_acquire().is_ok());
+        check!(limiter.try_acquire().is_err());
+    }
+
+    #[test]
+    fn it_never_allows_hits_with_a_zero_limit() {
+        let limiter = RateLimiter::new(0, Duration::from_secs(60));
+
+        check!(limiter.try_acquire().is_err());
+    }
+}
@@ -1,24 +1,138 @@
-use std::time::Duration;
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use chrono::{DateTime, NaiveDate, Utc};
 
-use crate::persistance::recipe::{
-    IngredientEntity, MutableIngredientEntity, MutableRecipeEntity, RecipeEntity, RecipeRepository,
-    SearchRecipesArguments,
+use crate::{
+    core::{
+        data_quality::{DataQualityReport, find_issues},
+        deletion::{Deletion, MAX_REASON_LENGTH},
+        duplicate::{Duplicate, find_duplicates},
+        tag::normalize_tags,
+        timer::{Timer, suggest_timers},
+    },
+    persistance::recipe::{
+        IngredientEntity, IngredientMatchEntity, MutableIngredientEntity, MutableRecipeEntity,
+        NameKind, PopularRecipeEntity, RandomRecipeArguments, RecipeEntity, RecipeForkEntity,
+        RecipeMetadataEntity, RecipeNameEntity, RecipeRepository, SearchRecipesArguments,
+    },
+    video::resolver::{ResolveVideoError, VideoResolver},
 };
 use thiserror::Error;
+use unicode_normalization::UnicodeNormalization;
+use uuid::Uuid;
+
+/// Number of names suggested per searched field when a search finds nothing
+const SUGGESTION_LIMIT: i64 = 5;
 
 #[derive(Debug, Clone)]
-pub(crate) struct RecipeService<RR: RecipeRepository> {
+pub struct RecipeService<RR: RecipeRepository, VR: VideoResolver> {
     repository: RR,
+    video_resolver: VR,
+    /// Views recorded since the last flush, keyed by recipe id. Views are buffered so that popular
+    /// recipes don't turn into hot rows when every request would update the same counter.
+    pending_views: Arc<Mutex<HashMap<i32, i64>>>,
+    search_weights: SearchWeights,
+}
+
+#[derive(Debug)]
+pub struct Recipe {
+    pub recipe_id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub ingredients: Vec<Ingredient>,
+    pub cooking_time: Option<Duration>,
+    pub meal_type: MealType,
+    pub last_cooked_at: Option<NaiveDate>,
+    pub video: Option<Video>,
+    pub pairing: Option<Pairing>,
+    /// Recipe this recipe was forked from, ignored when updating a recipe
+    pub forked_from: Option<i32>,
+    /// Random id to expose to external clients instead of the sequential `recipe_id`, ignored when
+    /// updating a recipe
+    pub public_id: Uuid,
 }
 
+/// Video accompanying a recipe, the title and thumbnail are looked up when the recipe is saved
 #[derive(Debug)]
-pub(crate) struct Recipe {
-    pub(crate) recipe_id: i32,
-    pub(crate) name: String,
-    pub(crate) description: Option<String>,
-    pub(crate) ingredients: Vec<Ingredient>,
-    pub(crate) cooking_time: Option<Duration>,
-    pub(crate) meal_type: MealType,
+pub struct Video {
+    pub url: String,
+    pub title: Option<String>,
+    pub thumbnail_url: Option<String>,
+}
+
+/// Beverage to serve with a recipe, like `Chianti` or `Elderflower lemonade`
+#[derive(Debug, PartialEq, Eq)]
+pub struct Pairing {
+    pub kind: Option<PairingKind>,
+    pub description: String,
+}
+
+/// Formats a pairing for people to read, like `Wine: Chianti`
+impl fmt::Display for Pairing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            Some(kind) => write!(f, "{kind}: {}", self.description),
+            None => write!(f, "{}", self.description),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PairingKind {
+    Wine,
+    Beer,
+    NonAlcoholic,
+}
+
+impl fmt::Display for PairingKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PairingKind::Wine => write!(f, "Wine"),
+            PairingKind::Beer => write!(f, "Beer"),
+            PairingKind::NonAlcoholic => write!(f, "Non-alcoholic"),
+        }
+    }
+}
+
+impl From<crate::persistance::recipe::PairingKind> for PairingKind {
+    fn from(value: crate::persistance::recipe::PairingKind) -> Self {
+        match value {
+            crate::persistance::recipe::PairingKind::Wine => Self::Wine,
+            crate::persistance::recipe::PairingKind::Beer => Self::Beer,
+            crate::persistance::recipe::PairingKind::NonAlcoholic => Self::NonAlcoholic,
+        }
+    }
+}
+
+impl From<PairingKind> for crate::persistance::recipe::PairingKind {
+    fn from(value: PairingKind) -> Self {
+        match value {
+            PairingKind::Wine => Self::Wine,
+            PairingKind::Beer => Self::Beer,
+            PairingKind::NonAlcoholic => Self::NonAlcoholic,
+        }
+    }
+}
+
+/// Splits a pairing into the description and kind columns of the recipe table
+fn pairing_columns(
+    pairing: Option<Pairing>,
+) -> (
+    Option<String>,
+    Option<crate::persistance::recipe::PairingKind>,
+) {
+    match pairing {
+        Some(pairing) => (
+            Some(pairing.description),
+            pairing.kind.map(PairingKind::into),
+        ),
+        None => (None, None),
+    }
 }
 
 impl From<RecipeEntity> for Recipe {
@@ -34,12 +148,30 @@ impl From<RecipeEntity> for Recipe {
                 .collect(),
             cooking_time: value.cooking_time,
             meal_type: value.meal_type.into(),
+            last_cooked_at: value.last_cooked_at,
+            video: value.video_url.map(|url| Video {
+                url,
+                title: value.video_title,
+                thumbnail_url: value.video_thumbnail_url,
+            }),
+            pairing: value.pairing.map(|description| Pairing {
+                kind: value.pairing_kind.map(PairingKind::from),
+                description,
+            }),
+            forked_from: value.forked_from,
+            public_id: value.public_id,
         }
     }
 }
 
 impl From<Recipe> for MutableRecipeEntity {
     fn from(value: Recipe) -> Self {
+        let (video_url, video_title, video_thumbnail_url) = match value.video {
+            Some(video) => (Some(video.url), video.title, video.thumbnail_url),
+            None => (None, None, None),
+        };
+        let (pairing, pairing_kind) = pairing_columns(value.pairing);
+
         Self {
             name: value.name,
             description: value.description,
@@ -50,15 +182,116 @@ impl From<Recipe> for MutableRecipeEntity {
                 .collect(),
             cooking_time: value.cooking_time,
             meal_type: value.meal_type.into(),
+            video_url,
+            video_title,
+            video_thumbnail_url,
+            pairing,
+            pairing_kind,
+        }
+    }
+}
+
+/// Fork of a recipe together with the forks made of it in turn
+#[derive(Debug)]
+pub struct RecipeFork {
+    pub recipe_id: i32,
+    pub name: String,
+    pub forks: Vec<RecipeFork>,
+}
+
+/// Builds the tree of forks below `recipe_id` out of the flat list of forks
+fn fork_tree(recipe_id: i32, forks: &mut HashMap<i32, Vec<RecipeForkEntity>>) -> Vec<RecipeFork> {
+    forks
+        .remove(&recipe_id)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|fork| RecipeFork {
+            recipe_id: fork.recipe_id,
+            forks: fork_tree(fork.recipe_id, forks),
+            name: fork.name,
+        })
+        .collect()
+}
+
+/// Field of a recipe which can be taken from the source recipe when merging two recipes, also used
+/// to point out the fields a sync conflict is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeField {
+    Name,
+    Description,
+    Ingredients,
+    CookingTime,
+    MealType,
+    Video,
+    Pairing,
+}
+
+/// Just enough of a recipe to pick it from a list
+#[derive(Debug)]
+pub struct RecipeName {
+    pub recipe_id: i32,
+    pub name: String,
+}
+
+impl From<RecipeNameEntity> for RecipeName {
+    fn from(value: RecipeNameEntity) -> Self {
+        Self {
+            recipe_id: value.recipe_id,
+            name: value.name,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PopularRecipe {
+    pub recipe: Recipe,
+    pub views: i64,
+}
+
+impl From<PopularRecipeEntity> for PopularRecipe {
+    fn from(value: PopularRecipeEntity) -> Self {
+        Self {
+            recipe: value.recipe.into(),
+            views: value.views,
         }
     }
 }
 
+/// Recipe matched against a list of available ingredients
 #[derive(Debug)]
-pub(crate) struct Ingredient {
-    pub(crate) name: String,
-    pub(crate) quantity_type: QuantityType,
-    pub(crate) quantity: f32,
+pub struct IngredientMatch {
+    pub recipe: Recipe,
+    /// Number of ingredients of the recipe which are available
+    pub matched: i64,
+    /// Number of ingredients of the recipe which are still missing
+    pub missing: i64,
+}
+
+impl From<IngredientMatchEntity> for IngredientMatch {
+    fn from(value: IngredientMatchEntity) -> Self {
+        Self {
+            recipe: value.recipe.into(),
+            matched: value.matched,
+            missing: value.missing,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Ingredient {
+    pub name: String,
+    pub quantity_type: QuantityType,
+    pub quantity: f32,
+}
+
+/// Formats an ingredient for people to read, like `500 g Flour` or `3 Eggs`
+impl fmt::Display for Ingredient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.quantity_type.unit() {
+            Some(unit) => write!(f, "{} {} {}", self.quantity, unit, self.name),
+            None => write!(f, "{} {}", self.quantity, self.name),
+        }
+    }
 }
 
 impl From<IngredientEntity> for Ingredient {
@@ -82,23 +315,141 @@ impl From<Ingredient> for MutableIngredientEntity {
 }
 
 #[derive(Debug)]
-pub(crate) struct NewRecipe {
-    pub(crate) name: String,
-    pub(crate) description: Option<String>,
-    pub(crate) ingredients: Vec<Ingredient>,
-    pub(crate) cooking_time: Option<Duration>,
-    pub(crate) meal_type: MealType,
+pub struct NewRecipe {
+    pub name: String,
+    pub description: Option<String>,
+    pub ingredients: Vec<Ingredient>,
+    pub cooking_time: Option<Duration>,
+    pub meal_type: MealType,
+    pub video_url: Option<String>,
+    pub pairing: Option<Pairing>,
+}
+
+#[derive(Debug)]
+pub struct SearchCriteria {
+    /// Matches the name or the description, recipes matching by name come first
+    pub recipe_name: Option<String>,
+    pub ingredient_name: Option<String>,
+    pub meal_type: Option<MealType>,
+    pub include_ingredients: bool,
+    /// Searches the archived recipes instead of the others
+    pub archived: bool,
+    /// Looks for names close to `recipe_name` and `ingredient_name` when nothing matches
+    pub suggest: bool,
+    /// Only recipes tagged with every one of these tags are found
+    pub tags: Vec<String>,
+    /// Orders the recipes by each key in turn, later repeats of a key are ignored
+    pub sort: Vec<SearchSort>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    /// Only recipes changed after this moment, changes to ingredients and tags included
+    pub updated_after: Option<DateTime<Utc>>,
+    /// Words to look for in the name, description, ingredients and tags at once, any of them
+    /// matching is enough
+    pub keywords: Option<String>,
+}
+
+/// Ordering of search results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchSort {
+    /// Shortest cooking time first
+    CookingTime,
+    /// Most recently cooked first
+    LastCooked,
+}
+
+impl From<SearchSort> for crate::persistance::recipe::SearchSort {
+    fn from(value: SearchSort) -> Self {
+        match value {
+            SearchSort::CookingTime => Self::CookingTime,
+            SearchSort::LastCooked => Self::LastCooked,
+        }
+    }
+}
+
+/// What a keyword matching each field adds to the rank of a recipe in a keyword search
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchWeights {
+    pub name: f32,
+    pub ingredient: f32,
+    pub tag: f32,
+    pub description: f32,
+}
+
+impl Default for SearchWeights {
+    fn default() -> Self {
+        Self {
+            name: 4.0,
+            ingredient: 2.0,
+            tag: 2.0,
+            description: 1.0,
+        }
+    }
+}
+
+impl From<SearchWeights> for crate::persistance::recipe::SearchWeights {
+    fn from(value: SearchWeights) -> Self {
+        Self {
+            name: value.name,
+            ingredient: value.ingredient,
+            tag: value.tag,
+            description: value.description,
+        }
+    }
+}
+
+/// Recipes found by a search, along with corrections to try when none were found
+#[derive(Debug)]
+pub struct SearchResults {
+    pub recipes: Vec<Recipe>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// Name which exists and is close to a name searched for, like `Pancakes` for `Pancaeks`
+#[derive(Debug, PartialEq, Eq)]
+pub enum Suggestion {
+    RecipeName(String),
+    IngredientName(String),
+}
+
+#[derive(Debug)]
+pub struct RandomCriteria {
+    pub meal_type: Option<MealType>,
+    pub max_cooking_time: Option<Duration>,
+    /// Only recipes tagged with every one of these tags are picked
+    pub tags: Vec<String>,
+}
+
+/// Changes to the metadata of a recipe, fields which are `None` are left as they are
+#[derive(Debug, Default)]
+pub struct MetadataChanges {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub cooking_time: Option<Duration>,
+    pub meal_type: Option<MealType>,
+    /// Replaces the tags of the recipe
+    pub tags: Option<Vec<String>>,
 }
 
 #[derive(Debug)]
-pub(crate) struct SearchCriteria {
-    pub(crate) recipe_name: Option<String>,
-    pub(crate) ingredient_name: Option<String>,
-    pub(crate) meal_type: Option<MealType>,
+pub struct MetadataUpdate {
+    pub recipe_id: i32,
+    pub changes: MetadataChanges,
+}
+
+/// What became of a single metadata update
+#[derive(Debug, PartialEq, Eq)]
+pub enum MetadataOutcome {
+    Updated,
+    NotFound,
+    /// The changes are invalid, like an empty name
+    Rejected(String),
 }
 
 impl From<NewRecipe> for MutableRecipeEntity {
     fn from(value: NewRecipe) -> Self {
+        let (pairing, pairing_kind) = pairing_columns(value.pairing);
+
         Self {
             name: value.name,
             description: value.description,
@@ -109,12 +460,17 @@ impl From<NewRecipe> for MutableRecipeEntity {
                 .collect(),
             cooking_time: value.cooking_time,
             meal_type: value.meal_type.into(),
+            video_url: value.video_url,
+            video_title: None,
+            video_thumbnail_url: None,
+            pairing,
+            pairing_kind,
         }
     }
 }
 
-#[derive(Debug)]
-pub(crate) enum QuantityType {
+#[derive(Debug, PartialEq, Eq)]
+pub enum QuantityType {
     Count,
     Kilo,
     Gram,
@@ -122,6 +478,19 @@ pub(crate) enum QuantityType {
     Milliliter,
 }
 
+impl QuantityType {
+    /// Abbreviated unit, [`None`] for plain counts
+    pub fn unit(&self) -> Option<&'static str> {
+        match self {
+            QuantityType::Count => None,
+            QuantityType::Kilo => Some("kg"),
+            QuantityType::Gram => Some("g"),
+            QuantityType::Liter => Some("l"),
+            QuantityType::Milliliter => Some("ml"),
+        }
+    }
+}
+
 impl From<crate::persistance::recipe::QuantityType> for QuantityType {
     fn from(value: crate::persistance::recipe::QuantityType) -> Self {
         match value {
@@ -146,13 +515,34 @@ impl From<QuantityType> for crate::persistance::recipe::QuantityType {
     }
 }
 
-#[derive(Debug)]
-pub(crate) enum MealType {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MealType {
     Breakfast,
     Lunch,
     Dinner,
 }
 
+impl fmt::Display for MealType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MealType::Breakfast => write!(f, "Breakfast"),
+            MealType::Lunch => write!(f, "Lunch"),
+            MealType::Dinner => write!(f, "Dinner"),
+        }
+    }
+}
+
+/// Formats a cooking time for people to read, like `1 h 30 min`
+pub fn format_cooking_time(cooking_time: Duration) -> String {
+    let minutes = cooking_time.as_secs().div_ceil(60);
+
+    match (minutes / 60, minutes % 60) {
+        (0, minutes) => format!("{minutes} min"),
+        (hours, 0) => format!("{hours} h"),
+        (hours, minutes) => format!("{hours} h {minutes} min"),
+    }
+}
+
 impl From<crate::persistance::recipe::MealType> for MealType {
     fn from(value: crate::persistance::recipe::MealType) -> Self {
         match value {
@@ -174,7 +564,7 @@ impl From<MealType> for crate::persistance::recipe::MealType {
 }
 
 #[derive(Debug, Error)]
-pub(crate) enum ListRecipeError {
+pub enum ListRecipeError {
     #[error("An unknown error occured: {0:}")]
     Unknown(
         #[from]
@@ -184,7 +574,7 @@ pub(crate) enum ListRecipeError {
 }
 
 #[derive(Debug, Error)]
-pub(crate) enum SearchRecipeError {
+pub enum SearchRecipeError {
     #[error("An unknown error occured: {0:}")]
     Unknown(
         #[from]
@@ -201,6 +591,14 @@ impl From<crate::persistance::recipe::SearchRecipeError> for SearchRecipeError {
     }
 }
 
+impl From<crate::persistance::recipe::SimilarNamesError> for SearchRecipeError {
+    fn from(value: crate::persistance::recipe::SimilarNamesError) -> Self {
+        match value {
+            crate::persistance::recipe::SimilarNamesError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
 impl From<crate::persistance::recipe::ListRecipeError> for ListRecipeError {
     fn from(value: crate::persistance::recipe::ListRecipeError) -> Self {
         match value {
@@ -210,13 +608,62 @@ impl From<crate::persistance::recipe::ListRecipeError> for ListRecipeError {
 }
 
 #[derive(Debug, Error)]
-pub(crate) enum CreateRecipeError {
+pub enum GetRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::recipe::GetRecipesError> for GetRecipesError {
+    fn from(value: crate::persistance::recipe::GetRecipesError) -> Self {
+        match value {
+            crate::persistance::recipe::GetRecipesError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+/// Longest recipe and ingredient name accepted, in characters
+pub const MAX_NAME_LENGTH: usize = 200;
+/// Longest description accepted, in characters
+pub const MAX_DESCRIPTION_LENGTH: usize = 20_000;
+
+/// Field of a recipe which was rejected, like a name which is too long
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// Path to the field, like `ingredients[1].name`
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` {}", self.field, self.message)
+    }
+}
+
+fn describe_field_errors(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(FieldError::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Debug, Error)]
+pub enum CreateRecipeError {
     #[error("An unknown error occured: {0:}")]
     Unknown(
         #[from]
         #[source]
         eyre::Report,
     ),
+    #[error("`{0}` is not a valid video url")]
+    InvalidVideoUrl(String),
+    #[error("Invalid fields: {}", describe_field_errors(.0))]
+    InvalidFields(Vec<FieldError>),
 }
 
 impl From<crate::persistance::recipe::CreateRecipeError> for CreateRecipeError {
@@ -228,7 +675,31 @@ impl From<crate::persistance::recipe::CreateRecipeError> for CreateRecipeError {
 }
 
 #[derive(Debug, Error)]
-pub(crate) enum UpdateRecipeError {
+pub enum ImportRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("`{0}` is not a valid video url")]
+    InvalidVideoUrl(String),
+    #[error("Invalid fields: {}", describe_field_errors(.0))]
+    InvalidFields(Vec<FieldError>),
+}
+
+impl From<crate::persistance::recipe::ImportRecipesError> for ImportRecipesError {
+    fn from(value: crate::persistance::recipe::ImportRecipesError) -> Self {
+        match value {
+            crate::persistance::recipe::ImportRecipesError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum UpdateRecipeError {
     #[error("An unknown error occured: {0:}")]
     Unknown(
         #[from]
@@ -237,6 +708,10 @@ pub(crate) enum UpdateRecipeError {
     ),
     #[error("The recipe could not be found")]
     NotFound,
+    #[error("`{0}` is not a valid video url")]
+    InvalidVideoUrl(String),
+    #[error("Invalid fields: {}", describe_field_errors(.0))]
+    InvalidFields(Vec<FieldError>),
 }
 
 impl From<crate::persistance::recipe::UpdateRecipeError> for UpdateRecipeError {
@@ -249,7 +724,27 @@ impl From<crate::persistance::recipe::UpdateRecipeError> for UpdateRecipeError {
 }
 
 #[derive(Debug, Error)]
-pub(crate) enum DeleteRecipeError {
+pub enum UpdateMetadataError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::recipe::UpdateMetadataError> for UpdateMetadataError {
+    fn from(value: crate::persistance::recipe::UpdateMetadataError) -> Self {
+        match value {
+            crate::persistance::recipe::UpdateMetadataError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum DeleteRecipeError {
     #[error("An unknown error occured: {0:}")]
     Unknown(
         #[from]
@@ -258,6 +753,8 @@ pub(crate) enum DeleteRecipeError {
     ),
     #[error("The recipe could not be found")]
     NotFound,
+    #[error("Invalid fields: {}", describe_field_errors(.0))]
+    InvalidFields(Vec<FieldError>),
 }
 
 impl From<crate::persistance::recipe::DeleteRecipeError> for DeleteRecipeError {
@@ -269,46 +766,1320 @@ impl From<crate::persistance::recipe::DeleteRecipeError> for DeleteRecipeError {
     }
 }
 
-impl<RR: RecipeRepository> RecipeService<RR> {
-    pub(crate) fn new(repository: RR) -> Self {
-        Self { repository }
-    }
+#[derive(Debug, Error)]
+pub enum SetArchivedError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
 
-    pub(crate) async fn list_recipes(&self) -> Result<Vec<Recipe>, ListRecipeError> {
-        let entity = self.repository.list_recipes().await?;
-        Ok(entity.into_iter().map(Recipe::from).collect())
+impl From<crate::persistance::recipe::SetArchivedError> for SetArchivedError {
+    fn from(value: crate::persistance::recipe::SetArchivedError) -> Self {
+        match value {
+            crate::persistance::recipe::SetArchivedError::Unknown(report) => Self::Unknown(report),
+            crate::persistance::recipe::SetArchivedError::NotFound => Self::NotFound,
+        }
     }
+}
 
-    pub(crate) async fn create_recipe(&self, dto: NewRecipe) -> Result<Recipe, CreateRecipeError> {
-        let entity = self.repository.create_recipe(dto.into()).await?;
-        Ok(entity.into())
+#[derive(Debug, Error)]
+pub enum SearchByIngredientsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::recipe::SearchByIngredientsError> for SearchByIngredientsError {
+    fn from(value: crate::persistance::recipe::SearchByIngredientsError) -> Self {
+        match value {
+            crate::persistance::recipe::SearchByIngredientsError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
     }
+}
 
-    pub(crate) async fn update_recipe(&self, dto: Recipe) -> Result<Recipe, UpdateRecipeError> {
-        let entity = self
-            .repository
-            .update_recipe(dto.recipe_id, dto.into())
-            .await?;
+#[derive(Debug, Error)]
+pub enum RandomRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("No recipe matched the given criteria")]
+    NotFound,
+}
 
-        Ok(entity.into())
+impl From<crate::persistance::recipe::RandomRecipeError> for RandomRecipeError {
+    fn from(value: crate::persistance::recipe::RandomRecipeError) -> Self {
+        match value {
+            crate::persistance::recipe::RandomRecipeError::Unknown(report) => Self::Unknown(report),
+            crate::persistance::recipe::RandomRecipeError::NotFound => Self::NotFound,
+        }
     }
+}
 
-    pub(crate) async fn delete_recipe(&self, recipe_id: i32) -> Result<(), DeleteRecipeError> {
-        self.repository.delete_recipe(recipe_id).await?;
-        Ok(())
+#[derive(Debug, Error)]
+pub enum GetRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::persistance::recipe::GetRecipeError> for GetRecipeError {
+    fn from(value: crate::persistance::recipe::GetRecipeError) -> Self {
+        match value {
+            crate::persistance::recipe::GetRecipeError::Unknown(report) => Self::Unknown(report),
+            crate::persistance::recipe::GetRecipeError::NotFound => Self::NotFound,
+        }
     }
+}
 
-    pub(crate) async fn search_recipes(
-        &self,
-        criteria: SearchCriteria,
-    ) -> Result<Vec<Recipe>, SearchRecipeError> {
-        let args = SearchRecipesArguments {
-            recipe_name: criteria.recipe_name,
-            ingredient_name: criteria.ingredient_name,
-            meal_type: criteria.meal_type.map(|mt| mt.into()),
-        };
+#[derive(Debug, Error)]
+pub enum ForkRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
 
-        let entities = self.repository.search_recipes(args).await?;
-        Ok(entities.into_iter().map(Recipe::from).collect())
+impl From<crate::persistance::recipe::ForkRecipeError> for ForkRecipeError {
+    fn from(value: crate::persistance::recipe::ForkRecipeError) -> Self {
+        match value {
+            crate::persistance::recipe::ForkRecipeError::Unknown(report) => Self::Unknown(report),
+            crate::persistance::recipe::ForkRecipeError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ListForksError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::persistance::recipe::ListForksError> for ListForksError {
+    fn from(value: crate::persistance::recipe::ListForksError) -> Self {
+        match value {
+            crate::persistance::recipe::ListForksError::Unknown(report) => Self::Unknown(report),
+            crate::persistance::recipe::ListForksError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum FindDuplicatesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::recipe::ListRecipeError> for FindDuplicatesError {
+    fn from(value: crate::persistance::recipe::ListRecipeError) -> Self {
+        match value {
+            crate::persistance::recipe::ListRecipeError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum DataQualityError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::recipe::ListRecipeError> for DataQualityError {
+    fn from(value: crate::persistance::recipe::ListRecipeError) -> Self {
+        match value {
+            crate::persistance::recipe::ListRecipeError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum MergeRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+    #[error("A recipe can't be merged into itself")]
+    SameRecipe,
+}
+
+impl From<crate::persistance::recipe::GetRecipeError> for MergeRecipesError {
+    fn from(value: crate::persistance::recipe::GetRecipeError) -> Self {
+        match value {
+            crate::persistance::recipe::GetRecipeError::Unknown(report) => Self::Unknown(report),
+            crate::persistance::recipe::GetRecipeError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl From<crate::persistance::recipe::MergeRecipesError> for MergeRecipesError {
+    fn from(value: crate::persistance::recipe::MergeRecipesError) -> Self {
+        match value {
+            crate::persistance::recipe::MergeRecipesError::Unknown(report) => Self::Unknown(report),
+            crate::persistance::recipe::MergeRecipesError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ResolvePublicIdError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::persistance::recipe::ResolvePublicIdError> for ResolvePublicIdError {
+    fn from(value: crate::persistance::recipe::ResolvePublicIdError) -> Self {
+        match value {
+            crate::persistance::recipe::ResolvePublicIdError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::persistance::recipe::ResolvePublicIdError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum FlushViewsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::recipe::RecordViewsError> for FlushViewsError {
+    fn from(value: crate::persistance::recipe::RecordViewsError) -> Self {
+        match value {
+            crate::persistance::recipe::RecordViewsError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AutocompleteError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::recipe::AutocompleteError> for AutocompleteError {
+    fn from(value: crate::persistance::recipe::AutocompleteError) -> Self {
+        match value {
+            crate::persistance::recipe::AutocompleteError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PopularRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::recipe::PopularRecipesError> for PopularRecipesError {
+    fn from(value: crate::persistance::recipe::PopularRecipesError) -> Self {
+        match value {
+            crate::persistance::recipe::PopularRecipesError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+fn is_valid_video_url(url: &str) -> bool {
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+/// Normalizes names to their composed unicode form with single spaces, so `Crème` is stored the
+/// same regardless of how it was typed. Accent-insensitive search relies on composed characters.
+pub(crate) fn normalize_name(name: &str) -> String {
+    name.nfc()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Splits a keyword search into its distinct words, in their composed unicode form
+fn split_keywords(keywords: &str) -> Vec<String> {
+    keywords
+        .nfc()
+        .collect::<String>()
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Normalizes descriptions to their composed unicode form without surrounding whitespace. Line
+/// breaks inside are kept, as they separate the steps.
+pub(crate) fn normalize_description(description: &str) -> String {
+    description.nfc().collect::<String>().trim().to_string()
+}
+
+/// Normalizes the names and description of a recipe, dropping descriptions which are left empty
+fn normalize_fields(entity: &mut MutableRecipeEntity) {
+    entity.name = normalize_name(&entity.name);
+    entity.description = entity
+        .description
+        .as_deref()
+        .map(normalize_description)
+        .filter(|description| !description.is_empty());
+
+    for ingredient in &mut entity.ingredients {
+        ingredient.name = normalize_name(&ingredient.name);
+    }
+}
+
+/// Rejects a value longer than `max` characters, or holding a NUL character which Postgres can't
+/// store in text
+fn check_length(field: String, value: &str, max: usize) -> Option<FieldError> {
+    if value.contains('\0') {
+        Some(FieldError {
+            field,
+            message: "can't contain NUL characters".to_string(),
+        })
+    } else if value.chars().count() > max {
+        Some(FieldError {
+            field,
+            message: format!("can be at most {max} characters long"),
+        })
+    } else {
+        None
+    }
+}
+
+/// Rejects a normalized name which is left empty, or which [`check_length`] rejects
+fn check_name(field: String, name: &str) -> Option<FieldError> {
+    if name.is_empty() {
+        Some(FieldError {
+            field,
+            message: "can't be empty".to_string(),
+        })
+    } else {
+        check_length(field, name, MAX_NAME_LENGTH)
+    }
+}
+
+/// Checks the fields of a normalized recipe, with `prefix` in front of every field path to tell
+/// recipes apart when validating many at once
+fn field_errors(entity: &MutableRecipeEntity, prefix: &str) -> Vec<FieldError> {
+    let mut errors = Vec::new();
+
+    errors.extend(check_name(format!("{prefix}name"), &entity.name));
+
+    if let Some(description) = &entity.description {
+        errors.extend(check_length(
+            format!("{prefix}description"),
+            description,
+            MAX_DESCRIPTION_LENGTH,
+        ));
+    }
+
+    for (index, ingredient) in entity.ingredients.iter().enumerate() {
+        errors.extend(check_name(
+            format!("{prefix}ingredients[{index}].name"),
+            &ingredient.name,
+        ));
+    }
+
+    errors
+}
+
+impl<RR: RecipeRepository, VR: VideoResolver> RecipeService<RR, VR> {
+    pub fn new(repository: RR, video_resolver: VR) -> Self {
+        Self {
+            repository,
+            video_resolver,
+            pending_views: Arc::default(),
+            search_weights: SearchWeights::default(),
+        }
+    }
+
+    /// Ranks keyword searches with these weights instead of the defaults
+    pub fn with_search_weights(self, search_weights: SearchWeights) -> Self {
+        Self {
+            search_weights,
+            ..self
+        }
+    }
+
+    /// Looks up the title and thumbnail of the video of a recipe. Failing lookups only drop the
+    /// metadata, a recipe should still save when the provider is unreachable.
+    async fn resolve_video_metadata(&self, entity: &mut MutableRecipeEntity) {
+        let Some(url) = &entity.video_url else {
+            return;
+        };
+
+        let metadata = match self.video_resolver.resolve(url).await {
+            Ok(metadata) => metadata,
+            Err(ResolveVideoError::Disabled) => Default::default(),
+            Err(error) => {
+                tracing::warn!(?error, url, "Failed to resolve video metadata");
+                Default::default()
+            }
+        };
+
+        entity.video_title = metadata.title;
+        entity.video_thumbnail_url = metadata.thumbnail_url;
+    }
+
+    /// Lists all recipes which are archived or not, depending on `archived`. The ingredients are
+    /// left empty unless `include_ingredients` is set.
+    pub async fn list_recipes(
+        &self,
+        include_ingredients: bool,
+        archived: bool,
+    ) -> Result<Vec<Recipe>, ListRecipeError> {
+        let entity = self
+            .repository
+            .list_recipes(include_ingredients, archived)
+            .await?;
+        Ok(entity.into_iter().map(Recipe::from).collect())
+    }
+
+    /// Gets a single recipe, counting it as a view. Views are only persisted once
+    /// [`RecipeService::flush_views`] runs.
+    pub async fn get_recipe(&self, recipe_id: i32) -> Result<Recipe, GetRecipeError> {
+        let entity = self.repository.get_recipe(recipe_id).await?;
+
+        *self
+            .pending_views
+            .lock()
+            .expect("Pending views lock poisoned")
+            .entry(recipe_id)
+            .or_default() += 1;
+
+        Ok(entity.into())
+    }
+
+    /// Gets multiple recipes at once, in the order of the given ids. Unlike
+    /// [`RecipeService::get_recipe`] this doesn't count as a view.
+    pub async fn get_recipes(
+        &self,
+        recipe_ids: Vec<i32>,
+        include_ingredients: bool,
+    ) -> Result<Vec<Recipe>, GetRecipesError> {
+        let entities = self
+            .repository
+            .get_recipes(recipe_ids, include_ingredients)
+            .await?;
+
+        Ok(entities.into_iter().map(Recipe::from).collect())
+    }
+
+    /// Looks up the id of the recipe with the given public id
+    pub async fn resolve_public_id(&self, public_id: Uuid) -> Result<i32, ResolvePublicIdError> {
+        Ok(self.repository.resolve_public_id(public_id).await?)
+    }
+
+    /// Suggests timers based on the durations mentioned in the description of a recipe
+    pub async fn recipe_timers(&self, recipe_id: i32) -> Result<Vec<Timer>, GetRecipeError> {
+        let entity = self.repository.get_recipe(recipe_id).await?;
+
+        Ok(entity
+            .description
+            .as_deref()
+            .map(suggest_timers)
+            .unwrap_or_default())
+    }
+
+    pub async fn create_recipe(&self, dto: NewRecipe) -> Result<Recipe, CreateRecipeError> {
+        if let Some(url) = dto
+            .video_url
+            .as_ref()
+            .filter(|url| !is_valid_video_url(url))
+        {
+            return Err(CreateRecipeError::InvalidVideoUrl(url.clone()));
+        }
+
+        let mut entity = MutableRecipeEntity::from(dto);
+        normalize_fields(&mut entity);
+
+        let errors = field_errors(&entity, "");
+        if !errors.is_empty() {
+            return Err(CreateRecipeError::InvalidFields(errors));
+        }
+
+        self.resolve_video_metadata(&mut entity).await;
+
+        let entity = self.repository.create_recipe(entity).await?;
+        Ok(entity.into())
+    }
+
+    /// Creates many recipes in one go, returning their ids in the order they were given. Unlike
+    /// [`Self::create_recipe`] no video metadata is looked up, as that would take a request per
+    /// recipe.
+    pub async fn import_recipes(
+        &self,
+        dtos: Vec<NewRecipe>,
+    ) -> Result<Vec<i32>, ImportRecipesError> {
+        let entities = dtos
+            .into_iter()
+            .enumerate()
+            .map(|(index, dto)| {
+                if let Some(url) = dto
+                    .video_url
+                    .as_ref()
+                    .filter(|url| !is_valid_video_url(url))
+                {
+                    return Err(ImportRecipesError::InvalidVideoUrl(url.clone()));
+                }
+
+                let mut entity = MutableRecipeEntity::from(dto);
+                normalize_fields(&mut entity);
+
+                let errors = field_errors(&entity, &format!("[{index}]."));
+                if !errors.is_empty() {
+                    return Err(ImportRecipesError::InvalidFields(errors));
+                }
+
+                Ok(entity)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(self.repository.import_recipes(entities).await?)
+    }
+
+    pub async fn update_recipe(&self, dto: Recipe) -> Result<Recipe, UpdateRecipeError> {
+        if let Some(video) = dto
+            .video
+            .as_ref()
+            .filter(|video| !is_valid_video_url(&video.url))
+        {
+            return Err(UpdateRecipeError::InvalidVideoUrl(video.url.clone()));
+        }
+
+        let recipe_id = dto.recipe_id;
+        let mut entity = MutableRecipeEntity::from(dto);
+        normalize_fields(&mut entity);
+
+        let errors = field_errors(&entity, "");
+        if !errors.is_empty() {
+            return Err(UpdateRecipeError::InvalidFields(errors));
+        }
+
+        self.resolve_video_metadata(&mut entity).await;
+
+        let entity = self.repository.update_recipe(recipe_id, entity).await?;
+
+        Ok(entity.into())
+    }
+
+    /// Applies metadata changes to many recipes in a single transaction, with an outcome per
+    /// update in the order they were given. Invalid changes are rejected up front so they can't
+    /// fail the others, changes to recipes which don't exist are reported as not found.
+    pub async fn update_metadata(
+        &self,
+        updates: Vec<MetadataUpdate>,
+    ) -> Result<Vec<MetadataOutcome>, UpdateMetadataError> {
+        let mut outcomes = Vec::with_capacity(updates.len());
+        let mut entities = Vec::with_capacity(updates.len());
+
+        for update in updates {
+            let name = update.changes.name.as_deref().map(normalize_name);
+            let description = update
+                .changes
+                .description
+                .as_deref()
+                .map(normalize_description);
+
+            let name_error = name
+                .as_deref()
+                .and_then(|name| check_name("name".to_string(), name));
+            let rejection = if name_error.is_none() && description.as_deref() == Some("") {
+                Some("The description can't be empty, leave it out to keep it".to_string())
+            } else {
+                name_error
+                    .or_else(|| {
+                        description.as_deref().and_then(|description| {
+                            check_length(
+                                "description".to_string(),
+                                description,
+                                MAX_DESCRIPTION_LENGTH,
+                            )
+                        })
+                    })
+                    .map(|error| format!("The {error}"))
+            };
+
+            let rejected = rejection.is_some();
+            outcomes.push(rejection.map(MetadataOutcome::Rejected));
+
+            if !rejected {
+                entities.push(RecipeMetadataEntity {
+                    recipe_id: update.recipe_id,
+                    name,
+                    description,
+                    cooking_time: update.changes.cooking_time,
+                    meal_type: update.changes.meal_type.map(|mt| mt.into()),
+                    tags: update.changes.tags.as_deref().map(normalize_tags),
+                });
+            }
+        }
+
+        let recipe_ids = entities
+            .iter()
+            .map(|entity| entity.recipe_id)
+            .collect::<Vec<_>>();
+        let updated: HashSet<i32> = if entities.is_empty() {
+            HashSet::new()
+        } else {
+            self.repository
+                .update_metadata(entities)
+                .await?
+                .into_iter()
+                .collect()
+        };
+
+        let mut recipe_ids = recipe_ids.into_iter();
+        Ok(outcomes
+            .into_iter()
+            .map(|outcome| {
+                outcome.unwrap_or_else(|| {
+                    if recipe_ids.next().is_some_and(|id| updated.contains(&id)) {
+                        MetadataOutcome::Updated
+                    } else {
+                        MetadataOutcome::NotFound
+                    }
+                })
+            })
+            .collect())
+    }
+
+    /// Archives or unarchives a recipe, which keeps it out of listings and searches without
+    /// deleting it
+    pub async fn set_archived(
+        &self,
+        recipe_id: i32,
+        archived: bool,
+    ) -> Result<(), SetArchivedError> {
+        self.repository.set_archived(recipe_id, archived).await?;
+        Ok(())
+    }
+
+    /// Deletes a recipe, recording who deleted it and why. An empty reason is left out. Returns
+    /// the recipe as it was right before it was deleted.
+    pub async fn delete_recipe(
+        &self,
+        recipe_id: i32,
+        mut deletion: Deletion,
+    ) -> Result<Recipe, DeleteRecipeError> {
+        deletion.reason = deletion
+            .reason
+            .as_deref()
+            .map(normalize_description)
+            .filter(|reason| !reason.is_empty());
+
+        if let Some(error) = deletion
+            .reason
+            .as_deref()
+            .and_then(|reason| check_length("reason".to_string(), reason, MAX_REASON_LENGTH))
+        {
+            return Err(DeleteRecipeError::InvalidFields(vec![error]));
+        }
+
+        let entity = self
+            .repository
+            .delete_recipe(recipe_id, deletion.into())
+            .await?;
+        Ok(entity.into())
+    }
+
+    /// Creates an editable copy of a recipe which keeps track of the recipe it was forked from
+    pub async fn fork_recipe(&self, recipe_id: i32) -> Result<Recipe, ForkRecipeError> {
+        let entity = self.repository.fork_recipe(recipe_id).await?;
+        Ok(entity.into())
+    }
+
+    /// Gets the tree of forks made of a recipe, including forks of forks
+    pub async fn recipe_forks(&self, recipe_id: i32) -> Result<Vec<RecipeFork>, ListForksError> {
+        let mut forks = HashMap::<i32, Vec<RecipeForkEntity>>::new();
+
+        for fork in self.repository.list_forks(recipe_id).await? {
+            forks.entry(fork.forked_from).or_default().push(fork);
+        }
+
+        Ok(fork_tree(recipe_id, &mut forks))
+    }
+
+    /// Flags pairs of recipes which are likely the same recipe entered twice
+    pub async fn find_duplicates(&self) -> Result<Vec<Duplicate>, FindDuplicatesError> {
+        let recipes = self
+            .repository
+            .list_recipes(true, false)
+            .await?
+            .into_iter()
+            .map(Recipe::from)
+            .collect::<Vec<_>>();
+
+        Ok(find_duplicates(&recipes))
+    }
+
+    /// Reports the recipes which are not archived and have missing data or suspiciously large
+    /// quantities
+    pub async fn data_quality_report(&self) -> Result<DataQualityReport, DataQualityError> {
+        let recipes = self
+            .repository
+            .list_recipes(true, false)
+            .await?
+            .into_iter()
+            .map(Recipe::from)
+            .collect::<Vec<_>>();
+
+        Ok(find_issues(recipes))
+    }
+
+    /// Merges the source recipe into the target recipe. The target keeps its own fields except
+    /// for the given `fields`, which are taken from the source. The source is deleted afterwards.
+    pub async fn merge_recipes(
+        &self,
+        target_id: i32,
+        source_id: i32,
+        fields: &[MergeField],
+    ) -> Result<Recipe, MergeRecipesError> {
+        if target_id == source_id {
+            return Err(MergeRecipesError::SameRecipe);
+        }
+
+        let mut merged = Recipe::from(self.repository.get_recipe(target_id).await?);
+        let source = Recipe::from(self.repository.get_recipe(source_id).await?);
+
+        if fields.contains(&MergeField::Name) {
+            merged.name = source.name;
+        }
+        if fields.contains(&MergeField::Description) {
+            merged.description = source.description;
+        }
+        if fields.contains(&MergeField::Ingredients) {
+            merged.ingredients = source.ingredients;
+        }
+        if fields.contains(&MergeField::CookingTime) {
+            merged.cooking_time = source.cooking_time;
+        }
+        if fields.contains(&MergeField::MealType) {
+            merged.meal_type = source.meal_type;
+        }
+        if fields.contains(&MergeField::Video) {
+            merged.video = source.video;
+        }
+        if fields.contains(&MergeField::Pairing) {
+            merged.pairing = source.pairing;
+        }
+
+        let entity = self
+            .repository
+            .merge_recipes(target_id, source_id, merged.into())
+            .await?;
+
+        Ok(entity.into())
+    }
+
+    pub async fn search_recipes(
+        &self,
+        criteria: SearchCriteria,
+    ) -> Result<SearchResults, SearchRecipeError> {
+        let args = SearchRecipesArguments {
+            recipe_name: criteria.recipe_name.as_deref().map(normalize_name),
+            ingredient_name: criteria.ingredient_name.as_deref().map(normalize_name),
+            meal_type: criteria.meal_type.map(|mt| mt.into()),
+            include_ingredients: criteria.include_ingredients,
+            archived: criteria.archived,
+            tags: normalize_tags(&criteria.tags),
+            sort: criteria
+                .sort
+                .iter()
+                .enumerate()
+                .filter(|(index, sort)| !criteria.sort[..*index].contains(sort))
+                .map(|(_, sort)| (*sort).into())
+                .collect(),
+            created_after: criteria.created_after,
+            created_before: criteria.created_before,
+            updated_after: criteria.updated_after,
+            keywords: criteria
+                .keywords
+                .as_deref()
+                .map(split_keywords)
+                .unwrap_or_default(),
+            weights: self.search_weights.into(),
+        };
+
+        let recipe_name = args.recipe_name.clone();
+        let ingredient_name = args.ingredient_name.clone();
+
+        let entities = self.repository.search_recipes(args).await?;
+        let recipes = entities.into_iter().map(Recipe::from).collect::<Vec<_>>();
+
+        let mut suggestions = vec![];
+        if criteria.suggest && recipes.is_empty() {
+            for (term, kind) in [
+                (recipe_name, NameKind::Recipe),
+                (ingredient_name, NameKind::Ingredient),
+            ] {
+                let Some(term) = term.filter(|term| !term.is_empty()) else {
+                    continue;
+                };
+
+                let names = self
+                    .repository
+                    .similar_names(term, kind, criteria.archived, SUGGESTION_LIMIT)
+                    .await?;
+
+                suggestions.extend(names.into_iter().map(|name| match kind {
+                    NameKind::Recipe => Suggestion::RecipeName(name),
+                    NameKind::Ingredient => Suggestion::IngredientName(name),
+                }));
+            }
+        }
+
+        Ok(SearchResults {
+            recipes,
+            suggestions,
+        })
+    }
+
+    pub async fn random_recipe(
+        &self,
+        criteria: RandomCriteria,
+    ) -> Result<Recipe, RandomRecipeError> {
+        let args = RandomRecipeArguments {
+            meal_type: criteria.meal_type.map(|mt| mt.into()),
+            max_cooking_time: criteria.max_cooking_time,
+            tags: normalize_tags(&criteria.tags),
+        };
+
+        let entity = self.repository.random_recipe(args).await?;
+        Ok(entity.into())
+    }
+
+    /// Writes all buffered views to the repository in a single batch. When writing fails, the
+    /// views are put back into the buffer so the next flush can retry them.
+    pub async fn flush_views(&self) -> Result<(), FlushViewsError> {
+        let views = std::mem::take(
+            &mut *self
+                .pending_views
+                .lock()
+                .expect("Pending views lock poisoned"),
+        );
+
+        if views.is_empty() {
+            return Ok(());
+        }
+
+        let batch = views.iter().map(|(id, count)| (*id, *count)).collect();
+
+        if let Err(error) = self.repository.record_views(batch).await {
+            let mut pending = self
+                .pending_views
+                .lock()
+                .expect("Pending views lock poisoned");
+
+            for (recipe_id, count) in views {
+                *pending.entry(recipe_id).or_default() += count;
+            }
+
+            return Err(error.into());
+        }
+
+        Ok(())
+    }
+
+    /// Ranks recipes by how much of their ingredients are covered by the given ingredients, best
+    /// covered first
+    pub async fn search_by_ingredients(
+        &self,
+        ingredient_names: Vec<String>,
+        limit: u32,
+    ) -> Result<Vec<IngredientMatch>, SearchByIngredientsError> {
+        let entities = self
+            .repository
+            .search_by_ingredients(ingredient_names, limit as i64)
+            .await?;
+
+        Ok(entities.into_iter().map(IngredientMatch::from).collect())
+    }
+
+    /// Recipes with a name starting with `query`, ignoring case and accents. An empty query
+    /// matches nothing rather than every recipe.
+    pub async fn autocomplete(
+        &self,
+        query: &str,
+        limit: u32,
+    ) -> Result<Vec<RecipeName>, AutocompleteError> {
+        let prefix = normalize_name(query);
+        if prefix.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let entities = self.repository.autocomplete(prefix, limit as i64).await?;
+        Ok(entities.into_iter().map(RecipeName::from).collect())
+    }
+
+    pub async fn popular_recipes(
+        &self,
+        window_days: u32,
+        limit: u32,
+    ) -> Result<Vec<PopularRecipe>, PopularRecipesError> {
+        let entities = self
+            .repository
+            .popular_recipes(i32::try_from(window_days).unwrap_or(i32::MAX), limit as i64)
+            .await?;
+
+        Ok(entities.into_iter().map(PopularRecipe::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+
+    use crate::{
+        test_utils::{MockRepository, recipe_entity},
+        video::implementation::oembed::Oembed,
+    };
+
+    fn new_recipe(name: &str, video_url: Option<&str>) -> NewRecipe {
+        NewRecipe {
+            name: name.to_string(),
+            description: None,
+            ingredients: vec![],
+            cooking_time: None,
+            meal_type: MealType::Dinner,
+            video_url: video_url.map(str::to_string),
+            pairing: None,
+        }
+    }
+
+    #[test]
+    fn it_normalizes_names_to_composed_characters() {
+        check!(normalize_name("Cre\u{300}me bru\u{302}le\u{301}e") == "Crème brûlée");
+        check!(normalize_name("  Spaghetti \t bolognese ") == "Spaghetti bolognese");
+    }
+
+    #[tokio::test]
+    async fn it_imports_recipes_with_normalized_names() {
+        let repository = MockRepository::new();
+        repository.on_import_recipes(Ok(vec![4, 5]));
+        let svc = RecipeService::new(repository.clone(), None::<Oembed>);
+
+        let recipes = vec![new_recipe(" Soup ", None), new_recipe("Stew", None)];
+        let_assert!(Ok(recipe_ids) = svc.import_recipes(recipes).await);
+
+        check!(recipe_ids == [4, 5]);
+        let calls = repository.calls_to("import_recipes");
+        check!(calls.len() == 1);
+        check!(calls[0].arguments.contains(r#"name: "Soup""#));
+    }
+
+    #[tokio::test]
+    async fn it_rejects_imports_with_invalid_video_urls() {
+        let repository = MockRepository::new();
+        let svc = RecipeService::new(repository.clone(), None::<Oembed>);
+
+        let recipes = vec![
+            new_recipe("Soup", None),
+            new_recipe("Stew", Some("ftp://example.com/stew")),
+        ];
+        let_assert!(
+            Err(ImportRecipesError::InvalidVideoUrl(url)) = svc.import_recipes(recipes).await
+        );
+
+        check!(url == "ftp://example.com/stew");
+        check!(repository.calls().is_empty());
+    }
+
+    #[test]
+    fn it_trims_descriptions_but_keeps_their_lines() {
+        check!(normalize_description("  Boil.\n\nSimmer.\n") == "Boil.\n\nSimmer.");
+        check!(normalize_description("Pure\u{301}e") == "Purée");
+    }
+
+    #[tokio::test]
+    async fn it_rejects_recipes_with_overly_long_fields() {
+        let repository = MockRepository::new();
+        let svc = RecipeService::new(repository.clone(), None::<Oembed>);
+
+        let mut recipe = new_recipe(&"a".repeat(MAX_NAME_LENGTH + 1), None);
+        recipe.ingredients = vec![
+            Ingredient {
+                name: "Salt".to_string(),
+                quantity_type: QuantityType::Gram,
+                quantity: 5.0,
+            },
+            Ingredient {
+                name: "b".repeat(MAX_NAME_LENGTH + 1),
+                quantity_type: QuantityType::Gram,
+                quantity: 5.0,
+            },
+        ];
+        let_assert!(
+            Err(CreateRecipeError::InvalidFields(errors)) = svc.create_recipe(recipe).await
+        );
+
+        let fields = errors
+            .iter()
+            .map(|error| error.field.as_str())
+            .collect::<Vec<_>>();
+        check!(fields == ["name", "ingredients[1].name"]);
+        check!(repository.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_rejects_blank_names_and_nul_characters() {
+        let repository = MockRepository::new();
+        let svc = RecipeService::new(repository.clone(), None::<Oembed>);
+
+        let mut recipe = new_recipe("   ", None);
+        recipe.description = Some("Boil\0".to_string());
+        recipe.ingredients = vec![Ingredient {
+            name: " \t ".to_string(),
+            quantity_type: QuantityType::Gram,
+            quantity: 5.0,
+        }];
+        let_assert!(
+            Err(CreateRecipeError::InvalidFields(errors)) = svc.create_recipe(recipe).await
+        );
+
+        let fields = errors
+            .iter()
+            .map(|error| (error.field.as_str(), error.message.as_str()))
+            .collect::<Vec<_>>();
+        check!(
+            fields
+                == [
+                    ("name", "can't be empty"),
+                    ("description", "can't contain NUL characters"),
+                    ("ingredients[0].name", "can't be empty"),
+                ]
+        );
+        check!(repository.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_counts_characters_after_normalizing() {
+        let repository = MockRepository::new();
+        repository.on_import_recipes(Ok(vec![1]));
+        let svc = RecipeService::new(repository.clone(), None::<Oembed>);
+
+        // Every `e` with a combining accent composes into a single character
+        let name = "e\u{301}".repeat(MAX_NAME_LENGTH);
+        let mut recipe = new_recipe(&format!("  {name}  "), None);
+        recipe.description = Some(" \n ".to_string());
+        let_assert!(Ok(_) = svc.import_recipes(vec![recipe]).await);
+
+        let calls = repository.calls_to("import_recipes");
+        check!(calls[0].arguments.contains("description: None"));
+    }
+
+    #[tokio::test]
+    async fn it_points_at_the_recipe_when_rejecting_imports() {
+        let repository = MockRepository::new();
+        let svc = RecipeService::new(repository.clone(), None::<Oembed>);
+
+        let mut recipe = new_recipe("Stew", None);
+        recipe.description = Some("c".repeat(MAX_DESCRIPTION_LENGTH + 1));
+        let recipes = vec![new_recipe("Soup", None), recipe];
+        let_assert!(
+            Err(ImportRecipesError::InvalidFields(errors)) = svc.import_recipes(recipes).await
+        );
+
+        let_assert!([error] = errors.as_slice());
+        check!(error.field == "[1].description");
+    }
+
+    #[tokio::test]
+    async fn it_records_deletions_with_a_trimmed_reason() {
+        let repository = MockRepository::new();
+        repository.on_delete_recipe(Ok(recipe_entity(3, "Soup")));
+        let svc = RecipeService::new(repository.clone(), None::<Oembed>);
+
+        let deletion = Deletion {
+            actor: Some("sam".to_string()),
+            ip_address: None,
+            reason: Some("  Entered twice\n".to_string()),
+        };
+        let_assert!(Ok(recipe) = svc.delete_recipe(3, deletion).await);
+        check!(recipe.name == "Soup");
+
+        let calls = repository.calls_to("delete_recipe");
+        check!(
+            calls[0]
+                .arguments
+                .contains(r#"reason: Some("Entered twice")"#)
+        );
+    }
+
+    #[tokio::test]
+    async fn it_rejects_overly_long_deletion_reasons() {
+        let repository = MockRepository::new();
+        let svc = RecipeService::new(repository.clone(), None::<Oembed>);
+
+        let deletion = Deletion {
+            reason: Some("r".repeat(MAX_REASON_LENGTH + 1)),
+            ..Deletion::default()
+        };
+        let_assert!(
+            Err(DeleteRecipeError::InvalidFields(errors)) = svc.delete_recipe(3, deletion).await
+        );
+
+        check!(errors[0].field == "reason");
+        check!(repository.calls().is_empty());
+    }
+
+    fn metadata_update(recipe_id: i32, name: Option<&str>) -> MetadataUpdate {
+        MetadataUpdate {
+            recipe_id,
+            changes: MetadataChanges {
+                name: name.map(str::to_string),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn it_reports_an_outcome_per_metadata_update() {
+        let repository = MockRepository::new();
+        repository.on_update_metadata(Ok(vec![1]));
+        let svc = RecipeService::new(repository.clone(), None::<Oembed>);
+
+        let updates = vec![
+            metadata_update(1, Some(" Soup ")),
+            metadata_update(2, Some("  ")),
+            metadata_update(3, None),
+        ];
+        let_assert!(Ok(outcomes) = svc.update_metadata(updates).await);
+
+        let_assert!(
+            [
+                MetadataOutcome::Updated,
+                MetadataOutcome::Rejected(_),
+                MetadataOutcome::NotFound
+            ] = outcomes.as_slice()
+        );
+        let calls = repository.calls_to("update_metadata");
+        check!(calls.len() == 1);
+        check!(calls[0].arguments.contains(r#"name: Some("Soup")"#));
+        check!(!calls[0].arguments.contains("recipe_id: 2"));
+    }
+
+    #[tokio::test]
+    async fn it_normalizes_the_tags_of_a_metadata_update() {
+        let repository = MockRepository::new();
+        repository.on_update_metadata(Ok(vec![1]));
+        let svc = RecipeService::new(repository.clone(), None::<Oembed>);
+
+        let update = MetadataUpdate {
+            recipe_id: 1,
+            changes: MetadataChanges {
+                tags: Some(vec![
+                    " Quick ".to_string(),
+                    "quick".to_string(),
+                    "".to_string(),
+                ]),
+                ..Default::default()
+            },
+        };
+        let_assert!(Ok(_) = svc.update_metadata(vec![update]).await);
+
+        let calls = repository.calls_to("update_metadata");
+        check!(calls.len() == 1);
+        check!(calls[0].arguments.contains(r#"tags: Some(["quick"])"#));
+    }
+
+    #[tokio::test]
+    async fn it_skips_the_repository_when_every_metadata_update_is_rejected() {
+        let repository = MockRepository::new();
+        let svc = RecipeService::new(repository.clone(), None::<Oembed>);
+
+        let_assert!(
+            Ok(outcomes) = svc
+                .update_metadata(vec![metadata_update(1, Some(""))])
+                .await
+        );
+
+        let_assert!([MetadataOutcome::Rejected(_)] = outcomes.as_slice());
+        check!(repository.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_does_not_autocomplete_an_empty_query() {
+        let repository = MockRepository::new();
+        let svc = RecipeService::new(repository.clone(), None::<Oembed>);
+
+        let_assert!(Ok(recipes) = svc.autocomplete("  ", 10).await);
+
+        check!(recipes.is_empty());
+        check!(repository.calls().is_empty());
+    }
+
+    fn search_criteria(recipe_name: &str) -> SearchCriteria {
+        SearchCriteria {
+            recipe_name: Some(recipe_name.to_string()),
+            ingredient_name: None,
+            meal_type: None,
+            include_ingredients: false,
+            archived: false,
+            suggest: true,
+            tags: vec![],
+            sort: vec![],
+            created_after: None,
+            created_before: None,
+            updated_after: None,
+            keywords: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn it_suggests_similar_names_when_nothing_matches() {
+        let repository = MockRepository::new();
+        repository
+            .on_search_recipes(Ok(vec![]))
+            .on_similar_names(Ok(vec!["Pancakes".to_string()]));
+        let svc = RecipeService::new(repository.clone(), None::<Oembed>);
+
+        let_assert!(Ok(results) = svc.search_recipes(search_criteria("Pancaeks")).await);
+
+        check!(results.recipes.is_empty());
+        check!(results.suggestions == [Suggestion::RecipeName("Pancakes".to_string())]);
+        check!(
+            repository.calls_to("similar_names")[0].arguments
+                == r#"("Pancaeks", Recipe, false, 5)"#
+        );
+    }
+
+    #[tokio::test]
+    async fn it_only_suggests_names_on_request() {
+        let repository = MockRepository::new();
+        repository.on_search_recipes(Ok(vec![]));
+        let svc = RecipeService::new(repository.clone(), None::<Oembed>);
+
+        let criteria = SearchCriteria {
+            suggest: false,
+            ..search_criteria("Pancaeks")
+        };
+        let_assert!(Ok(results) = svc.search_recipes(criteria).await);
+
+        check!(results.suggestions.is_empty());
+        check!(repository.calls_to("similar_names").is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_drops_repeated_sort_keys() {
+        let repository = MockRepository::new();
+        repository
+            .on_search_recipes(Ok(vec![]))
+            .on_similar_names(Ok(vec![]));
+        let svc = RecipeService::new(repository.clone(), None::<Oembed>);
+
+        let criteria = SearchCriteria {
+            sort: vec![
+                SearchSort::LastCooked,
+                SearchSort::CookingTime,
+                SearchSort::LastCooked,
+            ],
+            ..search_criteria("Pancakes")
+        };
+        let_assert!(Ok(_) = svc.search_recipes(criteria).await);
+
+        check!(
+            repository.calls_to("search_recipes")[0]
+                .arguments
+                .contains("sort: [LastCooked, CookingTime]")
+        );
+    }
+
+    #[tokio::test]
+    async fn it_splits_keywords_into_distinct_words() {
+        let repository = MockRepository::new();
+        repository.on_search_recipes(Ok(vec![]));
+        let svc = RecipeService::new(repository.clone(), None::<Oembed>);
+
+        let criteria = SearchCriteria {
+            recipe_name: None,
+            keywords: Some("  Tomato basil\ttomato ".to_string()),
+            ..search_criteria("")
+        };
+        let_assert!(Ok(_) = svc.search_recipes(criteria).await);
+
+        check!(
+            repository.calls_to("search_recipes")[0]
+                .arguments
+                .contains(r#"keywords: ["basil", "tomato"]"#)
+        );
+    }
+
+    #[tokio::test]
+    async fn it_ranks_with_the_configured_weights() {
+        let repository = MockRepository::new();
+        repository
+            .on_search_recipes(Ok(vec![]))
+            .on_similar_names(Ok(vec![]));
+        let weights = SearchWeights {
+            ingredient: 8.0,
+            ..SearchWeights::default()
+        };
+        let svc =
+            RecipeService::new(repository.clone(), None::<Oembed>).with_search_weights(weights);
+
+        let_assert!(Ok(_) = svc.search_recipes(search_criteria("Pancakes")).await);
+
+        check!(repository.calls_to("search_recipes")[0].arguments.contains(
+            "weights: SearchWeights { name: 4.0, ingredient: 8.0, tag: 2.0, description: 1.0 }"
+        ));
     }
 }
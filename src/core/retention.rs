@@ -0,0 +1,241 @@
+use std::time::Duration;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use thiserror::Error;
+
+use crate::persistance::retention::{
+    ExpiredCountsEntity, RetentionCutoffsEntity, RetentionRepository,
+};
+
+/// How long rows are kept, per kind of row. Kinds without a duration are kept forever.
+#[derive(Debug, Clone, Default)]
+pub struct RetentionPolicy {
+    /// Recorded recipe deletions, by when the recipe was deleted
+    pub deletions: Option<Duration>,
+    /// Share links, by when they were revoked. Links which were never revoked are kept.
+    pub revoked_share_links: Option<Duration>,
+    /// Export snapshots, by when they were taken
+    pub snapshots: Option<Duration>,
+}
+
+impl RetentionPolicy {
+    /// Whether every kind of row is kept forever
+    pub fn keeps_everything(&self) -> bool {
+        self.deletions.is_none() && self.revoked_share_links.is_none() && self.snapshots.is_none()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RetentionService<RR: RetentionRepository> {
+    repository: RR,
+    policy: RetentionPolicy,
+    dry_run: bool,
+}
+
+/// Rows of one kind which are older than its retention rule
+#[derive(Debug, Clone)]
+pub struct ExpiredRows {
+    /// Rows from before this moment are expired
+    pub cutoff: DateTime<Utc>,
+    pub count: u64,
+}
+
+/// Outcome of applying the retention policy, missing kinds have no rule
+#[derive(Debug)]
+pub struct RetentionReport {
+    /// Whether the expired rows were only counted, and not deleted
+    pub dry_run: bool,
+    pub deletions: Option<ExpiredRows>,
+    pub revoked_share_links: Option<ExpiredRows>,
+    pub snapshots: Option<ExpiredRows>,
+}
+
+impl RetentionReport {
+    fn new(dry_run: bool, cutoffs: RetentionCutoffsEntity, counts: ExpiredCountsEntity) -> Self {
+        let expired = |cutoff: Option<DateTime<Utc>>, count: i64| {
+            cutoff.map(|cutoff| ExpiredRows {
+                cutoff,
+                count: count as u64,
+            })
+        };
+
+        Self {
+            dry_run,
+            deletions: expired(cutoffs.deletions, counts.deletions),
+            revoked_share_links: expired(cutoffs.revoked_share_links, counts.revoked_share_links),
+            snapshots: expired(cutoffs.snapshots, counts.snapshots),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PreviewRetentionError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::retention::CountExpiredError> for PreviewRetentionError {
+    fn from(value: crate::persistance::retention::CountExpiredError) -> Self {
+        match value {
+            crate::persistance::retention::CountExpiredError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ApplyRetentionError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::retention::CountExpiredError> for ApplyRetentionError {
+    fn from(value: crate::persistance::retention::CountExpiredError) -> Self {
+        match value {
+            crate::persistance::retention::CountExpiredError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+impl From<crate::persistance::retention::PurgeExpiredError> for ApplyRetentionError {
+    fn from(value: crate::persistance::retention::PurgeExpiredError) -> Self {
+        match value {
+            crate::persistance::retention::PurgeExpiredError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+impl<RR: RetentionRepository> RetentionService<RR> {
+    /// Creates a service enforcing the policy. With `dry_run` set, [`Self::apply`] only reports
+    /// what it would delete.
+    pub fn new(repository: RR, policy: RetentionPolicy, dry_run: bool) -> Self {
+        Self {
+            repository,
+            policy,
+            dry_run,
+        }
+    }
+
+    /// How long the service keeps rows
+    pub fn policy(&self) -> &RetentionPolicy {
+        &self.policy
+    }
+
+    /// Reports which rows the policy would delete right now, without deleting them
+    pub async fn preview(&self) -> Result<RetentionReport, PreviewRetentionError> {
+        let cutoffs = self.cutoffs(Utc::now());
+        let counts = self.repository.count_expired(cutoffs.clone()).await?;
+
+        Ok(RetentionReport::new(true, cutoffs, counts))
+    }
+
+    /// Deletes the rows which are older than the policy allows, or only counts them on a dry run
+    pub async fn apply(&self) -> Result<RetentionReport, ApplyRetentionError> {
+        let cutoffs = self.cutoffs(Utc::now());
+
+        let counts = if self.dry_run {
+            self.repository.count_expired(cutoffs.clone()).await?
+        } else {
+            self.repository.purge_expired(cutoffs.clone()).await?
+        };
+
+        Ok(RetentionReport::new(self.dry_run, cutoffs, counts))
+    }
+
+    fn cutoffs(&self, now: DateTime<Utc>) -> RetentionCutoffsEntity {
+        // Durations too long to subtract from now keep everything
+        let cutoff = |retention: Option<Duration>| {
+            retention
+                .and_then(|retention| TimeDelta::from_std(retention).ok())
+                .and_then(|retention| now.checked_sub_signed(retention))
+        };
+
+        RetentionCutoffsEntity {
+            deletions: cutoff(self.policy.deletions),
+            revoked_share_links: cutoff(self.policy.revoked_share_links),
+            snapshots: cutoff(self.policy.snapshots),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+
+    use crate::test_utils::MockRepository;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn policy() -> RetentionPolicy {
+        RetentionPolicy {
+            deletions: Some(365 * DAY),
+            revoked_share_links: Some(30 * DAY),
+            snapshots: None,
+        }
+    }
+
+    #[test]
+    fn it_computes_cutoffs_from_the_policy() {
+        let svc = RetentionService::new(MockRepository::new(), policy(), false);
+        let now = Utc::now();
+
+        let cutoffs = svc.cutoffs(now);
+
+        check!(cutoffs.deletions == Some(now - TimeDelta::days(365)));
+        check!(cutoffs.revoked_share_links == Some(now - TimeDelta::days(30)));
+        check!(cutoffs.snapshots.is_none());
+    }
+
+    #[tokio::test]
+    async fn it_only_counts_expired_rows_on_a_dry_run() {
+        let repository = MockRepository::new();
+        repository.on_count_expired(Ok(ExpiredCountsEntity {
+            deletions: 4,
+            revoked_share_links: 2,
+            snapshots: 0,
+        }));
+        let svc = RetentionService::new(repository.clone(), policy(), true);
+
+        let_assert!(Ok(report) = svc.apply().await);
+        check!(report.dry_run);
+        let_assert!(Some(deletions) = report.deletions);
+        check!(deletions.count == 4);
+        let_assert!(Some(revoked_share_links) = report.revoked_share_links);
+        check!(revoked_share_links.count == 2);
+        check!(report.snapshots.is_none());
+
+        check!(repository.calls_to("purge_expired").is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_purges_expired_rows() {
+        let repository = MockRepository::new();
+        repository.on_purge_expired(Ok(ExpiredCountsEntity {
+            deletions: 1,
+            revoked_share_links: 0,
+            snapshots: 0,
+        }));
+        let svc = RetentionService::new(repository.clone(), policy(), false);
+
+        let_assert!(Ok(report) = svc.apply().await);
+        check!(!report.dry_run);
+        let_assert!(Some(deletions) = report.deletions);
+        check!(deletions.count == 1);
+        check!(repository.calls_to("purge_expired").len() == 1);
+    }
+}
@@ -0,0 +1,286 @@
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+use crate::{
+    core::recipe::{MealType, Recipe, RecipeService, SearchCriteria, normalize_name},
+    persistance::{
+        recipe::RecipeRepository,
+        saved_search::{SavedCriteriaEntity, SavedSearchEntity, SavedSearchRepository},
+    },
+    video::resolver::VideoResolver,
+};
+
+/// Stores named search criteria, so recurring searches like "quick vegetarian dinners" can be run
+/// again without entering them every time
+#[derive(Debug, Clone)]
+pub struct SavedSearchService<R: SavedSearchRepository + RecipeRepository, VR: VideoResolver> {
+    repository: R,
+    recipes: RecipeService<R, VR>,
+}
+
+/// The criteria of [`SearchCriteria`] worth saving, how to present the results is up to the run
+#[derive(Debug, Default)]
+pub struct SavedCriteria {
+    pub recipe_name: Option<String>,
+    pub ingredient_name: Option<String>,
+    pub meal_type: Option<MealType>,
+    pub archived: bool,
+    pub tags: Vec<String>,
+}
+
+impl From<SavedCriteriaEntity> for SavedCriteria {
+    fn from(value: SavedCriteriaEntity) -> Self {
+        Self {
+            recipe_name: value.recipe_name,
+            ingredient_name: value.ingredient_name,
+            meal_type: value.meal_type.map(MealType::from),
+            archived: value.archived,
+            tags: value.tags,
+        }
+    }
+}
+
+impl From<SavedCriteria> for SavedCriteriaEntity {
+    fn from(value: SavedCriteria) -> Self {
+        Self {
+            recipe_name: value.recipe_name,
+            ingredient_name: value.ingredient_name,
+            meal_type: value.meal_type.map(Into::into),
+            archived: value.archived,
+            tags: value.tags,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SavedSearch {
+    pub saved_search_id: i32,
+    pub name: String,
+    pub criteria: SavedCriteria,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<SavedSearchEntity> for SavedSearch {
+    fn from(value: SavedSearchEntity) -> Self {
+        Self {
+            saved_search_id: value.saved_search_id,
+            name: value.name,
+            criteria: value.criteria.into(),
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SaveSearchError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The name of a saved search can't be empty")]
+    EmptyName,
+}
+
+impl From<crate::persistance::saved_search::SaveSearchError> for SaveSearchError {
+    fn from(value: crate::persistance::saved_search::SaveSearchError) -> Self {
+        match value {
+            crate::persistance::saved_search::SaveSearchError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ListSavedSearchesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::saved_search::ListSavedSearchesError> for ListSavedSearchesError {
+    fn from(value: crate::persistance::saved_search::ListSavedSearchesError) -> Self {
+        match value {
+            crate::persistance::saved_search::ListSavedSearchesError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RunSavedSearchError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The saved search could not be found")]
+    NotFound,
+}
+
+impl From<crate::persistance::saved_search::GetSavedSearchError> for RunSavedSearchError {
+    fn from(value: crate::persistance::saved_search::GetSavedSearchError) -> Self {
+        match value {
+            crate::persistance::saved_search::GetSavedSearchError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::persistance::saved_search::GetSavedSearchError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl From<crate::core::recipe::SearchRecipeError> for RunSavedSearchError {
+    fn from(value: crate::core::recipe::SearchRecipeError) -> Self {
+        match value {
+            crate::core::recipe::SearchRecipeError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum DeleteSavedSearchError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The saved search could not be found")]
+    NotFound,
+}
+
+impl From<crate::persistance::saved_search::DeleteSavedSearchError> for DeleteSavedSearchError {
+    fn from(value: crate::persistance::saved_search::DeleteSavedSearchError) -> Self {
+        match value {
+            crate::persistance::saved_search::DeleteSavedSearchError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::persistance::saved_search::DeleteSavedSearchError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl<R: SavedSearchRepository + RecipeRepository, VR: VideoResolver> SavedSearchService<R, VR> {
+    pub fn new(repository: R, recipes: RecipeService<R, VR>) -> Self {
+        Self {
+            repository,
+            recipes,
+        }
+    }
+
+    pub async fn save_search(
+        &self,
+        name: &str,
+        criteria: SavedCriteria,
+    ) -> Result<SavedSearch, SaveSearchError> {
+        let name = normalize_name(name);
+        if name.is_empty() {
+            return Err(SaveSearchError::EmptyName);
+        }
+
+        let entity = self.repository.save_search(name, criteria.into()).await?;
+        Ok(entity.into())
+    }
+
+    pub async fn list_saved_searches(&self) -> Result<Vec<SavedSearch>, ListSavedSearchesError> {
+        let entities = self.repository.list_saved_searches().await?;
+        Ok(entities.into_iter().map(SavedSearch::from).collect())
+    }
+
+    /// Searches recipes with the criteria of a saved search, as they are matched right now
+    pub async fn run_saved_search(
+        &self,
+        saved_search_id: i32,
+        include_ingredients: bool,
+    ) -> Result<Vec<Recipe>, RunSavedSearchError> {
+        let saved = self.repository.get_saved_search(saved_search_id).await?;
+        let criteria = SavedCriteria::from(saved.criteria);
+
+        let results = self
+            .recipes
+            .search_recipes(SearchCriteria {
+                recipe_name: criteria.recipe_name,
+                ingredient_name: criteria.ingredient_name,
+                meal_type: criteria.meal_type,
+                include_ingredients,
+                archived: criteria.archived,
+                suggest: false,
+                tags: criteria.tags,
+                sort: vec![],
+                created_after: None,
+                created_before: None,
+                updated_after: None,
+                keywords: None,
+            })
+            .await?;
+
+        Ok(results.recipes)
+    }
+
+    pub async fn delete_saved_search(
+        &self,
+        saved_search_id: i32,
+    ) -> Result<(), DeleteSavedSearchError> {
+        Ok(self.repository.delete_saved_search(saved_search_id).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+
+    use crate::{test_utils::MockRepository, video::implementation::oembed::Oembed};
+
+    fn service(repository: &MockRepository) -> SavedSearchService<MockRepository, Option<Oembed>> {
+        SavedSearchService::new(
+            repository.clone(),
+            RecipeService::new(repository.clone(), None),
+        )
+    }
+
+    #[tokio::test]
+    async fn it_runs_the_saved_criteria() {
+        let repository = MockRepository::new();
+        repository
+            .on_get_saved_search(Ok(SavedSearchEntity {
+                saved_search_id: 1,
+                name: "Quick dinners".to_string(),
+                criteria: SavedCriteriaEntity {
+                    recipe_name: Some("pasta".to_string()),
+                    meal_type: Some(crate::persistance::recipe::MealType::Dinner),
+                    ..Default::default()
+                },
+                created_at: Utc::now(),
+            }))
+            .on_search_recipes(Ok(vec![]));
+
+        let_assert!(Ok(recipes) = service(&repository).run_saved_search(1, false).await);
+
+        check!(recipes.is_empty());
+        let calls = repository.calls_to("search_recipes");
+        check!(calls.len() == 1);
+        check!(calls[0].arguments.contains(r#"recipe_name: Some("pasta")"#));
+        check!(calls[0].arguments.contains("meal_type: Some(Dinner)"));
+    }
+
+    #[tokio::test]
+    async fn it_rejects_saving_without_a_name() {
+        let repository = MockRepository::new();
+
+        let_assert!(
+            Err(SaveSearchError::EmptyName) = service(&repository)
+                .save_search(" ", SavedCriteria::default())
+                .await
+        );
+        check!(repository.calls().is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+use std::time::Duration;
+
+use maud::html;
+use thiserror::Error;
+
+use crate::{
+    core::{
+        rate_limit::RateLimiter,
+        recipe::{Recipe, format_cooking_time},
+    },
+    mail::mailer::{Email, Mailer, SendMailError},
+    persistance::recipe::RecipeRepository,
+};
+
+#[derive(Debug, Clone)]
+pub struct ShareService<RR: RecipeRepository, M: Mailer> {
+    repository: RR,
+    mailer: M,
+    limiter: RateLimiter,
+}
+
+#[derive(Debug)]
+pub struct ShareByEmail {
+    pub to: String,
+    /// Personal message shown above the recipe
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum ShareRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+    #[error("`{0}` is not a valid email address")]
+    InvalidRecipient(String),
+    #[error("Too many recipes have been shared recently, try again later")]
+    RateLimited { retry_after: Duration },
+    #[error("Sharing by email is not configured on this instance")]
+    Disabled,
+}
+
+impl From<crate::persistance::recipe::GetRecipeError> for ShareRecipeError {
+    fn from(value: crate::persistance::recipe::GetRecipeError) -> Self {
+        match value {
+            crate::persistance::recipe::GetRecipeError::Unknown(report) => Self::Unknown(report),
+            crate::persistance::recipe::GetRecipeError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl From<SendMailError> for ShareRecipeError {
+    fn from(value: SendMailError) -> Self {
+        match value {
+            SendMailError::Unknown(report) => Self::Unknown(report),
+            SendMailError::InvalidRecipient(to) => Self::InvalidRecipient(to),
+            SendMailError::Disabled => Self::Disabled,
+        }
+    }
+}
+
+impl<RR: RecipeRepository, M: Mailer> ShareService<RR, M> {
+    /// Creates the service, allowing at most `limit` emails to be sent per `window` across the
+    /// whole instance
+    pub fn new(repository: RR, mailer: M, limit: u32, window: Duration) -> Self {
+        Self {
+            repository,
+            mailer,
+            limiter: RateLimiter::new(limit, window),
+        }
+    }
+
+    pub async fn share_by_email(
+        &self,
+        recipe_id: i32,
+        share: ShareByEmail,
+    ) -> Result<(), ShareRecipeError> {
+        // Only a rough check, the mailer does the actual address validation
+        if !share
+            .to
+            .split_once('@')
+            .is_some_and(|(user, domain)| !user.is_empty() && !domain.is_empty())
+        {
+            return Err(ShareRecipeError::InvalidRecipient(share.to));
+        }
+
+        // Checked before taking a slot, so requests to an instance without mail don't count
+        if !self.mailer.is_enabled() {
+            return Err(ShareRecipeError::Disabled);
+        }
+
+        let recipe: Recipe = self.repository.get_recipe(recipe_id).await?.into();
+
+        self.limiter
+            .try_acquire()
+            .map_err(|retry_after| ShareRecipeError::RateLimited { retry_after })?;
+
+        match self.mailer.send(compose_email(&recipe, share)).await {
+            // Rejected before anything was sent, so the slot is free for the next email
+            Err(error @ (SendMailError::InvalidRecipient(_) | SendMailError::Disabled)) => {
+                self.limiter.release();
+                Err(error.into())
+            }
+            result => Ok(result?),
+        }
+    }
+}
+
+fn compose_email(recipe: &Recipe, share: ShareByEmail) -> Email {
+    let mut text_body = String::new();
+
+    if let Some(message) = &share.message {
+        text_body.push_str(&format!("{message}\n\n"));
+    }
+
+    text_body.push_str(&format!("{}\n", recipe.name));
+
+    if let Some(description) = &recipe.description {
+        text_body.push_str(&format!("\n{description}\n"));
+    }
+
+    text_body.push_str(&format!("\nMeal type: {}\n", recipe.meal_type));
+
+    if let Some(cooking_time) = recipe.cooking_time {
+        text_body.push_str(&format!(
+            "Cooking time: {}\n",
+            format_cooking_time(cooking_time)
+        ));
+    }
+
+    if let Some(pairing) = &recipe.pairing {
+        text_body.push_str(&format!("Serve with: {pairing}\n"));
+    }
+
+    if !recipe.ingredients.is_empty() {
+        text_body.push_str("\nIngredients\n");
+
+        for ingredient in &recipe.ingredients {
+            text_body.push_str(&format!("- {ingredient}\n"));
+        }
+    }
+
+    let html_body = html! {
+        body style="font-family: sans-serif; max-width: 40em;" {
+            @if let Some(message) = &share.message {
+                p style="font-style: italic;" { (message) }
+            }
+            h1 { (recipe.name) }
+            @if let Some(description) = &recipe.description {
+                p { (description) }
+            }
+            p {
+                strong { "Meal type: " } (recipe.meal_type)
+                @if let Some(cooking_time) = recipe.cooking_time {
+                    br;
+                    strong { "Cooking time: " } (format_cooking_time(cooking_time))
+                }
+                @if let Some(pairing) = &recipe.pairing {
+                    br;
+                    strong { "Serve with: " } (pairing)
+                }
+            }
+            @if !recipe.ingredients.is_empty() {
+                h2 { "Ingredients" }
+                ul {
+                    @for ingredient in &recipe.ingredients {
+                        li { (ingredient) }
+                    }
+                }
+            }
+        }
+    };
+
+    Email {
+        to: share.to,
+        subject: format!("Recipe: {}", recipe.name),
+        text_body,
+        html_body: html_body.into_string(),
+    }
+}
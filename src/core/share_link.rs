@@ -0,0 +1,188 @@
+use chrono::{DateTime, Utc};
+use rand::{Rng, distr::Alphanumeric};
+use thiserror::Error;
+
+use crate::{
+    core::recipe::Recipe,
+    persistance::{
+        recipe::RecipeRepository,
+        share_link::{ShareLinkEntity, ShareLinkRepository},
+    },
+};
+
+/// Length of generated tokens, 43 alphanumeric characters carry just over 256 bits of entropy
+const TOKEN_LENGTH: usize = 43;
+
+#[derive(Debug, Clone)]
+pub struct ShareLinkService<SR: ShareLinkRepository, RR: RecipeRepository> {
+    share_links: SR,
+    recipes: RR,
+}
+
+#[derive(Debug)]
+pub struct ShareLink {
+    pub share_link_id: i32,
+    pub recipe_id: i32,
+    pub token: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ShareLinkEntity> for ShareLink {
+    fn from(value: ShareLinkEntity) -> Self {
+        Self {
+            share_link_id: value.share_link_id,
+            recipe_id: value.recipe_id,
+            token: value.token,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CreateShareLinkError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::persistance::share_link::CreateShareLinkError> for CreateShareLinkError {
+    fn from(value: crate::persistance::share_link::CreateShareLinkError) -> Self {
+        match value {
+            crate::persistance::share_link::CreateShareLinkError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::persistance::share_link::CreateShareLinkError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ListShareLinksError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::persistance::share_link::ListShareLinksError> for ListShareLinksError {
+    fn from(value: crate::persistance::share_link::ListShareLinksError) -> Self {
+        match value {
+            crate::persistance::share_link::ListShareLinksError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::persistance::share_link::ListShareLinksError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RevokeShareLinkError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The share link could not be found")]
+    NotFound,
+}
+
+impl From<crate::persistance::share_link::RevokeShareLinkError> for RevokeShareLinkError {
+    fn from(value: crate::persistance::share_link::RevokeShareLinkError) -> Self {
+        match value {
+            crate::persistance::share_link::RevokeShareLinkError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::persistance::share_link::RevokeShareLinkError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum GetSharedRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The shared recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::persistance::share_link::ResolveShareLinkError> for GetSharedRecipeError {
+    fn from(value: crate::persistance::share_link::ResolveShareLinkError) -> Self {
+        match value {
+            crate::persistance::share_link::ResolveShareLinkError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::persistance::share_link::ResolveShareLinkError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl From<crate::persistance::recipe::GetRecipeError> for GetSharedRecipeError {
+    fn from(value: crate::persistance::recipe::GetRecipeError) -> Self {
+        match value {
+            crate::persistance::recipe::GetRecipeError::Unknown(report) => Self::Unknown(report),
+            crate::persistance::recipe::GetRecipeError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl<SR: ShareLinkRepository, RR: RecipeRepository> ShareLinkService<SR, RR> {
+    pub fn new(share_links: SR, recipes: RR) -> Self {
+        Self {
+            share_links,
+            recipes,
+        }
+    }
+
+    pub async fn create_share_link(
+        &self,
+        recipe_id: i32,
+    ) -> Result<ShareLink, CreateShareLinkError> {
+        let token = rand::rng()
+            .sample_iter(Alphanumeric)
+            .take(TOKEN_LENGTH)
+            .map(char::from)
+            .collect();
+
+        let entity = self.share_links.create_share_link(recipe_id, token).await?;
+        Ok(entity.into())
+    }
+
+    pub async fn list_share_links(
+        &self,
+        recipe_id: i32,
+    ) -> Result<Vec<ShareLink>, ListShareLinksError> {
+        let entities = self.share_links.list_share_links(recipe_id).await?;
+        Ok(entities.into_iter().map(ShareLink::from).collect())
+    }
+
+    pub async fn revoke_share_link(
+        &self,
+        recipe_id: i32,
+        share_link_id: i32,
+    ) -> Result<(), RevokeShareLinkError> {
+        self.share_links
+            .revoke_share_link(recipe_id, share_link_id)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_shared_recipe(&self, token: &str) -> Result<Recipe, GetSharedRecipeError> {
+        let recipe_id = self.share_links.resolve_share_link(token).await?;
+        let entity = self.recipes.get_recipe(recipe_id).await?;
+        Ok(entity.into())
+    }
+}
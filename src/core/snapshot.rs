@@ -0,0 +1,194 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+use crate::{
+    core::recipe::{Ingredient, NewRecipe, Pairing, PairingKind},
+    persistance::snapshot::{SnapshotEntity, SnapshotRecipeEntity, SnapshotRepository},
+};
+
+#[derive(Debug, Clone)]
+pub struct SnapshotService<SR: SnapshotRepository> {
+    repository: SR,
+}
+
+/// Copy of all recipes taken at a single point in time
+#[derive(Debug)]
+pub struct Snapshot {
+    pub snapshot_id: i32,
+    pub created_at: DateTime<Utc>,
+    pub recipe_count: u64,
+}
+
+impl From<SnapshotEntity> for Snapshot {
+    fn from(value: SnapshotEntity) -> Self {
+        Self {
+            snapshot_id: value.snapshot_id,
+            created_at: value.created_at,
+            recipe_count: value.recipe_count.try_into().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<SnapshotRecipeEntity> for NewRecipe {
+    fn from(value: SnapshotRecipeEntity) -> Self {
+        let pairing_kind = value.pairing_kind.map(PairingKind::from);
+
+        Self {
+            name: value.name,
+            description: value.description,
+            ingredients: value
+                .ingredients
+                .into_iter()
+                .map(|ingredient| Ingredient {
+                    name: ingredient.name,
+                    quantity_type: ingredient.quantity_type.into(),
+                    quantity: ingredient.quantity,
+                })
+                .collect(),
+            cooking_time: value
+                .cooking_time_secs
+                .map(|value| Duration::from_secs(value as u64)),
+            meal_type: value.meal_type.into(),
+            video_url: value.video_url,
+            pairing: value.pairing.map(|description| Pairing {
+                kind: pairing_kind,
+                description,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CreateSnapshotError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::snapshot::CreateSnapshotError> for CreateSnapshotError {
+    fn from(value: crate::persistance::snapshot::CreateSnapshotError) -> Self {
+        match value {
+            crate::persistance::snapshot::CreateSnapshotError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ListSnapshotsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::snapshot::ListSnapshotsError> for ListSnapshotsError {
+    fn from(value: crate::persistance::snapshot::ListSnapshotsError) -> Self {
+        match value {
+            crate::persistance::snapshot::ListSnapshotsError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SnapshotRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The snapshot could not be found")]
+    NotFound,
+}
+
+impl From<crate::persistance::snapshot::SnapshotRecipesError> for SnapshotRecipesError {
+    fn from(value: crate::persistance::snapshot::SnapshotRecipesError) -> Self {
+        match value {
+            crate::persistance::snapshot::SnapshotRecipesError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::persistance::snapshot::SnapshotRecipesError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl<SR: SnapshotRepository> SnapshotService<SR> {
+    pub fn new(repository: SR) -> Self {
+        Self { repository }
+    }
+
+    /// Takes a consistent copy of all recipes, archived ones included
+    pub async fn create_snapshot(&self) -> Result<Snapshot, CreateSnapshotError> {
+        Ok(self.repository.create_snapshot().await?.into())
+    }
+
+    pub async fn list_snapshots(&self) -> Result<Vec<Snapshot>, ListSnapshotsError> {
+        let entities = self.repository.list_snapshots().await?;
+        Ok(entities.into_iter().map(Snapshot::from).collect())
+    }
+
+    /// The recipes of a snapshot, in the format accepted when importing recipes
+    pub async fn snapshot_recipes(
+        &self,
+        snapshot_id: i32,
+    ) -> Result<Vec<NewRecipe>, SnapshotRecipesError> {
+        let entities = self.repository.snapshot_recipes(snapshot_id).await?;
+        Ok(entities.into_iter().map(NewRecipe::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+
+    use crate::{
+        core::recipe::{MealType, QuantityType},
+        persistance::recipe::MutableIngredientEntity,
+        test_utils::MockRepository,
+    };
+
+    #[tokio::test]
+    async fn it_returns_snapshot_recipes_as_new_recipes() {
+        let repository = MockRepository::new();
+        repository.on_snapshot_recipes(Ok(vec![SnapshotRecipeEntity {
+            name: "Risotto".to_string(),
+            description: None,
+            ingredients: vec![MutableIngredientEntity {
+                name: "Rice".to_string(),
+                quantity_type: crate::persistance::recipe::QuantityType::Gram,
+                quantity: 300.0,
+            }],
+            cooking_time_secs: Some(1800),
+            meal_type: crate::persistance::recipe::MealType::Dinner,
+            video_url: None,
+            pairing: Some("Soave".to_string()),
+            pairing_kind: Some(crate::persistance::recipe::PairingKind::Wine),
+        }]));
+        let svc = SnapshotService::new(repository.clone());
+
+        let_assert!(Ok(recipes) = svc.snapshot_recipes(3).await);
+        let_assert!([recipe] = recipes.as_slice());
+        check!(recipe.name == "Risotto");
+        check!(recipe.ingredients[0].quantity_type == QuantityType::Gram);
+        check!(recipe.cooking_time == Some(Duration::from_secs(1800)));
+        check!(recipe.meal_type == MealType::Dinner);
+        let_assert!(Some(pairing) = &recipe.pairing);
+        check!(pairing.to_string() == "Wine: Soave");
+
+        let calls = repository.calls_to("snapshot_recipes");
+        check!(calls[0].arguments == "(3,)");
+    }
+}
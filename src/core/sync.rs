@@ -0,0 +1,724 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::{
+    core::{
+        deletion::Deletion,
+        event::RecipeEventKind,
+        recipe::{
+            CreateRecipeError, DeleteRecipeError, MergeField, NewRecipe, Recipe, RecipeService,
+            ResolvePublicIdError, UpdateRecipeError, Video, normalize_description, normalize_name,
+        },
+    },
+    persistance::{outbox::OutboxRepository, recipe::RecipeRepository},
+    video::resolver::VideoResolver,
+};
+
+/// Most events a single pull goes through, clients page through the rest with the cursor
+pub const MAX_SYNC_EVENTS: i64 = 1000;
+
+/// Hands out the changes made to recipes since a cursor, and applies changes made by clients
+/// while offline. The cursor is the id of the last recipe event a client has seen.
+#[derive(Debug, Clone)]
+pub struct SyncService<R: RecipeRepository + OutboxRepository, VR: VideoResolver> {
+    repository: R,
+    recipes: RecipeService<R, VR>,
+}
+
+/// Latest state of a recipe changed since the cursor
+#[derive(Debug)]
+pub enum RecipeChange {
+    Created(Recipe),
+    Updated(Recipe),
+    /// Tombstone of a recipe deleted since the cursor, which might have been created since the
+    /// cursor as well
+    Deleted {
+        public_id: Uuid,
+        deleted_at: DateTime<Utc>,
+    },
+    /// Recipe which is archived now. Restoring it hands it out as updated again.
+    Archived {
+        public_id: Uuid,
+        archived_at: DateTime<Utc>,
+    },
+}
+
+#[derive(Debug)]
+pub struct ChangeSet {
+    /// One change per recipe, in the order the recipes last changed
+    pub changes: Vec<RecipeChange>,
+    /// Cursor to pass to the next pull
+    pub cursor: i64,
+    /// Whether more changes are waiting past the returned cursor
+    pub has_more: bool,
+}
+
+/// Change a client made while offline. Updates and deletes carry the cursor of the pull the
+/// client's copy of the recipe is from, changes made here after that cursor make it a conflict.
+/// Without a base cursor the change is applied as is.
+#[derive(Debug)]
+pub enum Upload {
+    Create(NewRecipe),
+    Update {
+        public_id: Uuid,
+        base_cursor: Option<i64>,
+        recipe: NewRecipe,
+    },
+    Delete {
+        public_id: Uuid,
+        base_cursor: Option<i64>,
+    },
+}
+
+/// How to settle an upload of a recipe which changed here since the client last pulled it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictStrategy {
+    /// Keep the recipe as it is here and drop the upload
+    ServerWins,
+    /// Apply the upload over the changes made here
+    ClientWins,
+    /// Leave the recipe alone and hand both versions back to the client
+    #[default]
+    Manual,
+}
+
+/// Recipe changed both here and by the client
+#[derive(Debug)]
+pub struct Conflict {
+    pub server: Recipe,
+    /// Version of the client, `None` when the client deleted the recipe
+    pub client: Option<NewRecipe>,
+    /// Fields which differ between both versions
+    pub fields: Vec<MergeField>,
+}
+
+/// What became of a single upload
+#[derive(Debug)]
+pub enum UploadOutcome {
+    Applied {
+        public_id: Uuid,
+    },
+    NotFound,
+    /// The change is invalid, like a recipe with an invalid video url
+    Rejected(String),
+    /// The recipe changed here as well and is left as it is for the client to resolve
+    Conflict(Box<Conflict>),
+    /// The recipe changed here as well and the upload was dropped in favour of that change
+    Discarded {
+        public_id: Uuid,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum ChangesSinceError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::outbox::EventsSinceError> for ChangesSinceError {
+    fn from(value: crate::persistance::outbox::EventsSinceError) -> Self {
+        match value {
+            crate::persistance::outbox::EventsSinceError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl From<crate::core::recipe::GetRecipesError> for ChangesSinceError {
+    fn from(value: crate::core::recipe::GetRecipesError) -> Self {
+        match value {
+            crate::core::recipe::GetRecipesError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum UploadChangesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::outbox::LatestEventIdError> for UploadChangesError {
+    fn from(value: crate::persistance::outbox::LatestEventIdError) -> Self {
+        match value {
+            crate::persistance::outbox::LatestEventIdError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+impl From<crate::core::recipe::GetRecipesError> for UploadChangesError {
+    fn from(value: crate::core::recipe::GetRecipesError) -> Self {
+        match value {
+            crate::core::recipe::GetRecipesError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+/// Lists the fields in which the client's version of a recipe differs from the one here. Names
+/// and descriptions are compared the way they are stored, so whitespace alone is no difference.
+fn conflicting_fields(server: &Recipe, client: &NewRecipe) -> Vec<MergeField> {
+    let same_ingredients = server.ingredients.len() == client.ingredients.len()
+        && server
+            .ingredients
+            .iter()
+            .zip(&client.ingredients)
+            .all(|(server, client)| {
+                server.name == normalize_name(&client.name)
+                    && server.quantity_type == client.quantity_type
+                    && server.quantity == client.quantity
+            });
+
+    [
+        (
+            MergeField::Name,
+            server.name == normalize_name(&client.name),
+        ),
+        (
+            MergeField::Description,
+            server.description
+                == client
+                    .description
+                    .as_deref()
+                    .map(normalize_description)
+                    .filter(|description| !description.is_empty()),
+        ),
+        (MergeField::Ingredients, same_ingredients),
+        (
+            MergeField::CookingTime,
+            server.cooking_time == client.cooking_time,
+        ),
+        (MergeField::MealType, server.meal_type == client.meal_type),
+        (
+            MergeField::Video,
+            server.video.as_ref().map(|video| &video.url) == client.video_url.as_ref(),
+        ),
+        (MergeField::Pairing, server.pairing == client.pairing),
+    ]
+    .into_iter()
+    .filter(|(_, same)| !same)
+    .map(|(field, _)| field)
+    .collect()
+}
+
+/// Latest event of a recipe within a page of events
+struct LatestEvent {
+    outbox_id: i64,
+    recipe_id: i32,
+    kind: RecipeEventKind,
+    occurred_at: DateTime<Utc>,
+    archived_at: Option<DateTime<Utc>>,
+    created: bool,
+}
+
+impl<R: RecipeRepository + OutboxRepository, VR: VideoResolver> SyncService<R, VR> {
+    pub fn new(repository: R, recipes: RecipeService<R, VR>) -> Self {
+        Self {
+            repository,
+            recipes,
+        }
+    }
+
+    /// Collapses the events after the cursor into the latest state of every changed recipe.
+    /// Recipes are read as they are now, so a recipe changed again past the end of the page is
+    /// handed out in its newest state and once more on the next pull.
+    pub async fn changes_since(
+        &self,
+        cursor: i64,
+        limit: i64,
+    ) -> Result<ChangeSet, ChangesSinceError> {
+        let limit = limit.clamp(1, MAX_SYNC_EVENTS);
+        let events = self.repository.events_since(cursor, limit).await?;
+
+        let has_more = events.len() as i64 == limit;
+        let next_cursor = events.last().map_or(cursor, |event| event.outbox_id);
+
+        let mut latest = HashMap::<Uuid, LatestEvent>::new();
+
+        for event in events {
+            let kind = RecipeEventKind::from(event.kind);
+            let created = kind == RecipeEventKind::Created
+                || latest
+                    .get(&event.public_id)
+                    .is_some_and(|latest| latest.created);
+
+            latest.insert(
+                event.public_id,
+                LatestEvent {
+                    outbox_id: event.outbox_id,
+                    recipe_id: event.recipe_id,
+                    kind,
+                    occurred_at: event.occurred_at,
+                    archived_at: event.archived_at,
+                    created,
+                },
+            );
+        }
+
+        let mut latest = latest.into_iter().collect::<Vec<_>>();
+        latest.sort_by_key(|(_, event)| event.outbox_id);
+
+        let recipe_ids = latest
+            .iter()
+            .filter(|(_, event)| event.kind != RecipeEventKind::Deleted)
+            .map(|(_, event)| event.recipe_id)
+            .collect();
+
+        let mut recipes = self
+            .recipes
+            .get_recipes(recipe_ids, true)
+            .await?
+            .into_iter()
+            .map(|recipe| (recipe.recipe_id, recipe))
+            .collect::<HashMap<_, _>>();
+
+        let changes = latest
+            .into_iter()
+            .map(|(public_id, event)| {
+                let recipe = recipes
+                    .remove(&event.recipe_id)
+                    .filter(|_| event.kind != RecipeEventKind::Deleted);
+
+                match (recipe, event.archived_at) {
+                    (Some(_), Some(archived_at)) => RecipeChange::Archived {
+                        public_id,
+                        archived_at,
+                    },
+                    (Some(recipe), None) if event.created => RecipeChange::Created(recipe),
+                    (Some(recipe), None) => RecipeChange::Updated(recipe),
+                    // Gone already when deleted after the events of this page were recorded
+                    (None, _) => RecipeChange::Deleted {
+                        public_id,
+                        deleted_at: event.occurred_at,
+                    },
+                }
+            })
+            .collect();
+
+        Ok(ChangeSet {
+            changes,
+            cursor: next_cursor,
+            has_more,
+        })
+    }
+
+    /// Applies the uploads in order, each on its own. Invalid uploads, conflicts and uploads of
+    /// recipes which no longer exist are reported back instead of failing the others, while a
+    /// failing database stops the batch at the upload it failed on.
+    pub async fn upload_changes(
+        &self,
+        uploads: Vec<Upload>,
+        strategy: ConflictStrategy,
+    ) -> Result<Vec<UploadOutcome>, UploadChangesError> {
+        let mut outcomes = Vec::with_capacity(uploads.len());
+
+        for upload in uploads {
+            outcomes.push(self.upload(upload, strategy).await?);
+        }
+
+        Ok(outcomes)
+    }
+
+    async fn upload(
+        &self,
+        upload: Upload,
+        strategy: ConflictStrategy,
+    ) -> Result<UploadOutcome, UploadChangesError> {
+        match upload {
+            Upload::Create(recipe) => match self.recipes.create_recipe(recipe).await {
+                Ok(recipe) => Ok(UploadOutcome::Applied {
+                    public_id: recipe.public_id,
+                }),
+                Err(
+                    error @ (CreateRecipeError::InvalidVideoUrl(_)
+                    | CreateRecipeError::InvalidFields(_)),
+                ) => Ok(UploadOutcome::Rejected(error.to_string())),
+                Err(CreateRecipeError::Unknown(report)) => Err(report.into()),
+            },
+            Upload::Update {
+                public_id,
+                base_cursor,
+                recipe,
+            } => {
+                let Some(recipe_id) = self.resolve(public_id).await? else {
+                    return Ok(UploadOutcome::NotFound);
+                };
+
+                if let Some(server) = self.changed_since(recipe_id, base_cursor).await? {
+                    let fields = conflicting_fields(&server, &recipe);
+
+                    // Both sides made the same change, which leaves nothing to resolve
+                    if fields.is_empty() {
+                        return Ok(UploadOutcome::Applied { public_id });
+                    }
+
+                    match strategy {
+                        ConflictStrategy::ClientWins => {}
+                        ConflictStrategy::ServerWins => {
+                            return Ok(UploadOutcome::Discarded { public_id });
+                        }
+                        ConflictStrategy::Manual => {
+                            return Ok(UploadOutcome::Conflict(Box::new(Conflict {
+                                server,
+                                client: Some(recipe),
+                                fields,
+                            })));
+                        }
+                    }
+                }
+
+                let recipe = Recipe {
+                    recipe_id,
+                    name: recipe.name,
+                    description: recipe.description,
+                    ingredients: recipe.ingredients,
+                    cooking_time: recipe.cooking_time,
+                    meal_type: recipe.meal_type,
+                    last_cooked_at: None,
+                    video: recipe.video_url.map(|url| Video {
+                        url,
+                        title: None,
+                        thumbnail_url: None,
+                    }),
+                    pairing: recipe.pairing,
+                    forked_from: None,
+                    public_id,
+                };
+
+                match self.recipes.update_recipe(recipe).await {
+                    Ok(_) => Ok(UploadOutcome::Applied { public_id }),
+                    Err(UpdateRecipeError::NotFound) => Ok(UploadOutcome::NotFound),
+                    Err(
+                        error @ (UpdateRecipeError::InvalidVideoUrl(_)
+                        | UpdateRecipeError::InvalidFields(_)),
+                    ) => Ok(UploadOutcome::Rejected(error.to_string())),
+                    Err(UpdateRecipeError::Unknown(report)) => Err(report.into()),
+                }
+            }
+            Upload::Delete {
+                public_id,
+                base_cursor,
+            } => {
+                let Some(recipe_id) = self.resolve(public_id).await? else {
+                    return Ok(UploadOutcome::NotFound);
+                };
+
+                if let Some(server) = self.changed_since(recipe_id, base_cursor).await? {
+                    match strategy {
+                        ConflictStrategy::ClientWins => {}
+                        ConflictStrategy::ServerWins => {
+                            return Ok(UploadOutcome::Discarded { public_id });
+                        }
+                        ConflictStrategy::Manual => {
+                            return Ok(UploadOutcome::Conflict(Box::new(Conflict {
+                                server,
+                                client: None,
+                                fields: vec![],
+                            })));
+                        }
+                    }
+                }
+
+                match self
+                    .recipes
+                    .delete_recipe(recipe_id, Deletion::default())
+                    .await
+                {
+                    Ok(_) => Ok(UploadOutcome::Applied { public_id }),
+                    Err(DeleteRecipeError::NotFound) => Ok(UploadOutcome::NotFound),
+                    Err(error @ DeleteRecipeError::InvalidFields(_)) => {
+                        Ok(UploadOutcome::Rejected(error.to_string()))
+                    }
+                    Err(DeleteRecipeError::Unknown(report)) => Err(report.into()),
+                }
+            }
+        }
+    }
+
+    /// Gets the recipe as it is here when it changed after the base cursor
+    async fn changed_since(
+        &self,
+        recipe_id: i32,
+        base_cursor: Option<i64>,
+    ) -> Result<Option<Recipe>, UploadChangesError> {
+        let Some(base_cursor) = base_cursor else {
+            return Ok(None);
+        };
+
+        let latest = self.repository.latest_event_id(recipe_id).await?;
+        if latest.is_none_or(|outbox_id| outbox_id <= base_cursor) {
+            return Ok(None);
+        }
+
+        Ok(self.recipes.get_recipes(vec![recipe_id], true).await?.pop())
+    }
+
+    async fn resolve(&self, public_id: Uuid) -> Result<Option<i32>, UploadChangesError> {
+        match self.recipes.resolve_public_id(public_id).await {
+            Ok(recipe_id) => Ok(Some(recipe_id)),
+            Err(ResolvePublicIdError::NotFound) => Ok(None),
+            Err(ResolvePublicIdError::Unknown(report)) => Err(report.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+    use chrono::Utc;
+
+    use crate::{
+        core::recipe::MealType,
+        persistance::{
+            outbox::{OutboxEventEntity, RecipeEventKind as RecipeEventKindEntity},
+            recipe::{MealType as MealTypeEntity, RecipeEntity},
+        },
+        test_utils::MockRepository,
+        video::implementation::oembed::Oembed,
+    };
+
+    fn service(repository: &MockRepository) -> SyncService<MockRepository, Option<Oembed>> {
+        SyncService::new(
+            repository.clone(),
+            RecipeService::new(repository.clone(), None),
+        )
+    }
+
+    fn event(outbox_id: i64, kind: RecipeEventKindEntity, recipe_id: i32) -> OutboxEventEntity {
+        OutboxEventEntity {
+            outbox_id,
+            kind,
+            recipe_id,
+            public_id: Uuid::from_u128(recipe_id as u128),
+            occurred_at: Utc::now(),
+            archived_at: None,
+        }
+    }
+
+    fn recipe(recipe_id: i32) -> RecipeEntity {
+        RecipeEntity {
+            recipe_id,
+            name: "Soup".to_string(),
+            description: None,
+            ingredients: vec![],
+            cooking_time: None,
+            meal_type: MealTypeEntity::Dinner,
+            video_url: None,
+            video_title: None,
+            video_thumbnail_url: None,
+            pairing: None,
+            pairing_kind: None,
+            forked_from: None,
+            public_id: Uuid::from_u128(recipe_id as u128),
+            last_cooked_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn it_collapses_events_into_one_change_per_recipe() {
+        let repository = MockRepository::new();
+        repository
+            .on_events_since(Ok(vec![
+                event(4, RecipeEventKindEntity::Created, 1),
+                event(5, RecipeEventKindEntity::Updated, 2),
+                event(6, RecipeEventKindEntity::Updated, 1),
+                event(7, RecipeEventKindEntity::Deleted, 3),
+            ]))
+            .on_get_recipes(Ok(vec![recipe(2), recipe(1)]));
+
+        let_assert!(Ok(change_set) = service(&repository).changes_since(3, 100).await);
+
+        check!(change_set.cursor == 7);
+        check!(!change_set.has_more);
+        let_assert!(
+            [
+                RecipeChange::Updated(updated),
+                RecipeChange::Created(created),
+                RecipeChange::Deleted { public_id, .. },
+            ] = change_set.changes.as_slice()
+        );
+        check!(updated.recipe_id == 2);
+        check!(created.recipe_id == 1);
+        check!(*public_id == Uuid::from_u128(3));
+    }
+
+    #[tokio::test]
+    async fn it_hands_out_archived_recipes_as_archived() {
+        let repository = MockRepository::new();
+        let archived_at = Utc::now();
+        repository
+            .on_events_since(Ok(vec![
+                OutboxEventEntity {
+                    archived_at: Some(archived_at),
+                    ..event(4, RecipeEventKindEntity::Updated, 1)
+                },
+                event(5, RecipeEventKindEntity::Updated, 2),
+            ]))
+            .on_get_recipes(Ok(vec![recipe(1), recipe(2)]));
+
+        let_assert!(Ok(change_set) = service(&repository).changes_since(3, 100).await);
+
+        let_assert!(
+            [
+                RecipeChange::Archived {
+                    public_id,
+                    archived_at: archived,
+                },
+                RecipeChange::Updated(updated),
+            ] = change_set.changes.as_slice()
+        );
+        check!(*public_id == Uuid::from_u128(1));
+        check!(*archived == archived_at);
+        check!(updated.recipe_id == 2);
+    }
+
+    #[tokio::test]
+    async fn it_keeps_the_cursor_when_nothing_changed() {
+        let repository = MockRepository::new();
+        repository
+            .on_events_since(Ok(vec![]))
+            .on_get_recipes(Ok(vec![]));
+
+        let_assert!(Ok(change_set) = service(&repository).changes_since(12, 100).await);
+
+        check!(change_set.cursor == 12);
+        check!(change_set.changes.is_empty());
+    }
+
+    fn update(base_cursor: Option<i64>, name: &str) -> Upload {
+        Upload::Update {
+            public_id: Uuid::from_u128(1),
+            base_cursor,
+            recipe: NewRecipe {
+                name: name.to_string(),
+                description: None,
+                ingredients: vec![],
+                cooking_time: None,
+                meal_type: MealType::Dinner,
+                video_url: None,
+                pairing: None,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn it_reports_uploads_of_unknown_recipes() {
+        let repository = MockRepository::new();
+        repository.on_resolve_public_id(Err(
+            crate::persistance::recipe::ResolvePublicIdError::NotFound,
+        ));
+
+        let_assert!(
+            Ok(outcomes) = service(&repository)
+                .upload_changes(vec![update(None, "Soup")], ConflictStrategy::Manual)
+                .await
+        );
+
+        let_assert!([UploadOutcome::NotFound] = outcomes.as_slice());
+        check!(repository.calls_to("update_recipe").is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_hands_back_both_versions_of_a_conflicting_upload() {
+        let repository = MockRepository::new();
+        repository
+            .on_resolve_public_id(Ok(1))
+            .on_latest_event_id(Ok(Some(9)))
+            .on_get_recipes(Ok(vec![recipe(1)]));
+
+        let_assert!(
+            Ok(outcomes) = service(&repository)
+                .upload_changes(
+                    vec![update(Some(5), "Tomato soup")],
+                    ConflictStrategy::Manual
+                )
+                .await
+        );
+
+        let_assert!([UploadOutcome::Conflict(conflict)] = outcomes.as_slice());
+        check!(conflict.server.name == "Soup");
+        let_assert!(Some(client) = &conflict.client);
+        check!(client.name == "Tomato soup");
+        check!(conflict.fields == [MergeField::Name]);
+        check!(repository.calls_to("update_recipe").is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_drops_conflicting_uploads_when_the_server_wins() {
+        let repository = MockRepository::new();
+        repository
+            .on_resolve_public_id(Ok(1))
+            .on_latest_event_id(Ok(Some(9)))
+            .on_get_recipes(Ok(vec![recipe(1)]));
+
+        let_assert!(
+            Ok(outcomes) = service(&repository)
+                .upload_changes(
+                    vec![update(Some(5), "Tomato soup")],
+                    ConflictStrategy::ServerWins
+                )
+                .await
+        );
+
+        let_assert!([UploadOutcome::Discarded { public_id }] = outcomes.as_slice());
+        check!(*public_id == Uuid::from_u128(1));
+        check!(repository.calls_to("update_recipe").is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_skips_uploads_matching_the_change_made_here() {
+        let repository = MockRepository::new();
+        repository
+            .on_resolve_public_id(Ok(1))
+            .on_latest_event_id(Ok(Some(9)))
+            .on_get_recipes(Ok(vec![recipe(1)]));
+
+        let mut upload = update(Some(5), " Soup ");
+        if let Upload::Update { recipe, .. } = &mut upload {
+            recipe.description = Some("  \n".to_string());
+        }
+
+        let_assert!(
+            Ok(outcomes) = service(&repository)
+                .upload_changes(vec![upload], ConflictStrategy::Manual)
+                .await
+        );
+
+        let_assert!([UploadOutcome::Applied { .. }] = outcomes.as_slice());
+        check!(repository.calls_to("update_recipe").is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_applies_uploads_of_recipes_unchanged_since_the_base_cursor() {
+        let repository = MockRepository::new();
+        repository
+            .on_resolve_public_id(Ok(1))
+            .on_latest_event_id(Ok(Some(5)))
+            .on_update_recipe(Ok(recipe(1)));
+
+        let_assert!(
+            Ok(outcomes) = service(&repository)
+                .upload_changes(
+                    vec![update(Some(5), "Tomato soup")],
+                    ConflictStrategy::Manual
+                )
+                .await
+        );
+
+        let_assert!([UploadOutcome::Applied { .. }] = outcomes.as_slice());
+        check!(repository.calls_to("update_recipe").len() == 1);
+    }
+}
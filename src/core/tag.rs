@@ -0,0 +1,173 @@
+use std::collections::BTreeSet;
+
+use thiserror::Error;
+
+use crate::{
+    core::recipe::normalize_name,
+    persistance::tag::{TagRepository, TagUsageEntity},
+};
+
+#[derive(Debug, Clone)]
+pub struct TagService<TR: TagRepository> {
+    repository: TR,
+}
+
+/// Tag along with the number of recipes tagged with it
+#[derive(Debug)]
+pub struct TagUsage {
+    pub name: String,
+    pub recipe_count: u64,
+}
+
+impl From<TagUsageEntity> for TagUsage {
+    fn from(value: TagUsageEntity) -> Self {
+        Self {
+            name: value.name,
+            recipe_count: value.recipe_count.try_into().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ListTagsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::persistance::tag::ListTagsError> for ListTagsError {
+    fn from(value: crate::persistance::tag::ListTagsError) -> Self {
+        match value {
+            crate::persistance::tag::ListTagsError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RecipeTagsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::persistance::tag::RecipeTagsError> for RecipeTagsError {
+    fn from(value: crate::persistance::tag::RecipeTagsError) -> Self {
+        match value {
+            crate::persistance::tag::RecipeTagsError::Unknown(report) => Self::Unknown(report),
+            crate::persistance::tag::RecipeTagsError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SetRecipeTagsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+    #[error("Tags can't be empty")]
+    EmptyTag,
+}
+
+impl From<crate::persistance::tag::SetRecipeTagsError> for SetRecipeTagsError {
+    fn from(value: crate::persistance::tag::SetRecipeTagsError) -> Self {
+        match value {
+            crate::persistance::tag::SetRecipeTagsError::Unknown(report) => Self::Unknown(report),
+            crate::persistance::tag::SetRecipeTagsError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+/// Normalizes a tag like a name and lowercases it, so `Quick  Meals` and `quick meals` are the
+/// same tag
+pub(crate) fn normalize_tag(tag: &str) -> String {
+    normalize_name(tag).to_lowercase()
+}
+
+/// Normalizes tags, leaving out empty and duplicate tags
+pub(crate) fn normalize_tags(tags: &[String]) -> Vec<String> {
+    tags.iter()
+        .map(|tag| normalize_tag(tag))
+        .filter(|tag| !tag.is_empty())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+impl<TR: TagRepository> TagService<TR> {
+    pub fn new(repository: TR) -> Self {
+        Self { repository }
+    }
+
+    /// Lists the tags in use by recipes which are not archived, with their number of recipes
+    pub async fn list_tags(&self) -> Result<Vec<TagUsage>, ListTagsError> {
+        let entities = self.repository.list_tags().await?;
+        Ok(entities.into_iter().map(TagUsage::from).collect())
+    }
+
+    pub async fn recipe_tags(&self, recipe_id: i32) -> Result<Vec<String>, RecipeTagsError> {
+        Ok(self.repository.recipe_tags(recipe_id).await?)
+    }
+
+    /// Replaces the tags of a recipe. Tags are normalized and duplicates are dropped, returns the
+    /// tags the recipe ended up with.
+    pub async fn set_recipe_tags(
+        &self,
+        recipe_id: i32,
+        tags: &[String],
+    ) -> Result<Vec<String>, SetRecipeTagsError> {
+        if tags.iter().any(|tag| normalize_tag(tag).is_empty()) {
+            return Err(SetRecipeTagsError::EmptyTag);
+        }
+
+        Ok(self
+            .repository
+            .set_recipe_tags(recipe_id, normalize_tags(tags))
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+
+    use crate::test_utils::MockRepository;
+
+    #[tokio::test]
+    async fn it_sets_normalized_distinct_tags() {
+        let repository = MockRepository::new();
+        repository.on_set_recipe_tags(Ok(vec!["quick meals".to_string(), "winter".to_string()]));
+        let svc = TagService::new(repository.clone());
+
+        let tags = ["Winter", " Quick  Meals", "winter"].map(String::from);
+        let_assert!(Ok(_) = svc.set_recipe_tags(4, &tags).await);
+
+        let calls = repository.calls_to("set_recipe_tags");
+        check!(calls.len() == 1);
+        check!(calls[0].arguments == r#"(4, ["quick meals", "winter"])"#);
+    }
+
+    #[tokio::test]
+    async fn it_rejects_empty_tags() {
+        let repository = MockRepository::new();
+        let svc = TagService::new(repository.clone());
+
+        let tags = ["winter", " "].map(String::from);
+        let_assert!(Err(SetRecipeTagsError::EmptyTag) = svc.set_recipe_tags(4, &tags).await);
+        check!(repository.calls().is_empty());
+    }
+}
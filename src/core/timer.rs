@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+/// Timer suggested from a sentence of a recipe, like `Simmer for 20 minutes`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timer {
+    pub name: String,
+    pub duration: Duration,
+}
+
+/// Suggests timers for every sentence of the text which mentions a duration. For ranges like
+/// `10-15 minutes` the upper bound is used, so a timer never goes off too early.
+pub fn suggest_timers(text: &str) -> Vec<Timer> {
+    text.split(['.', '!', '?', '\n'])
+        .map(str::trim)
+        .filter_map(|sentence| {
+            find_duration(sentence).map(|duration| Timer {
+                name: sentence.to_string(),
+                duration,
+            })
+        })
+        .collect()
+}
+
+/// Finds the first `<amount> <unit>` pair in a sentence, the unit may also be attached to the
+/// amount like `20min`
+fn find_duration(sentence: &str) -> Option<Duration> {
+    let words = sentence
+        .split(|c: char| c.is_whitespace() || c == ',' || c == ';' || c == '(' || c == ')')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+
+    words.iter().enumerate().find_map(|(index, word)| {
+        let split = word
+            .find(|c: char| !c.is_ascii_digit() && c != '-' && c != '–')
+            .unwrap_or(word.len());
+        let (amount, unit) = word.split_at(split);
+
+        let amount = parse_amount(amount)?;
+
+        let unit = match unit {
+            "" => match words.get(index + 1).copied() {
+                // Ranges written out, like `10 to 15 minutes`
+                Some("to") => {
+                    return parse_amount(words.get(index + 2)?)
+                        .zip(unit_secs(words.get(index + 3)?))
+                        .and_then(|(amount, unit)| amount.checked_mul(unit))
+                        .map(Duration::from_secs);
+                }
+                next => next?,
+            },
+            unit => unit,
+        };
+
+        amount
+            .checked_mul(unit_secs(unit)?)
+            .map(Duration::from_secs)
+    })
+}
+
+/// Parses an amount like `20` or a range like `10-15`, returning the upper bound
+fn parse_amount(amount: &str) -> Option<u64> {
+    let upper = amount.rsplit(['-', '–']).next()?;
+    upper.parse().ok().filter(|amount| *amount > 0)
+}
+
+fn unit_secs(unit: &str) -> Option<u64> {
+    match unit.to_lowercase().as_str() {
+        "s" | "sec" | "secs" | "second" | "seconds" => Some(1),
+        "m" | "min" | "mins" | "minute" | "minutes" => Some(60),
+        "h" | "hr" | "hrs" | "hour" | "hours" => Some(3600),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn it_suggests_a_timer_per_sentence_with_a_duration() {
+        let timers = suggest_timers(
+            "Fry the onions for 5 minutes. Add the tomatoes and stir! Simmer for 1 hour",
+        );
+
+        check!(
+            timers
+                == vec![
+                    Timer {
+                        name: "Fry the onions for 5 minutes".to_string(),
+                        duration: Duration::from_secs(300),
+                    },
+                    Timer {
+                        name: "Simmer for 1 hour".to_string(),
+                        duration: Duration::from_secs(3600),
+                    },
+                ]
+        );
+    }
+
+    #[test]
+    fn it_uses_the_upper_bound_of_ranges() {
+        let timers = suggest_timers("Bake for 10-15 mins. Rest for 5 to 10 minutes.");
+
+        check!(timers.len() == 2);
+        check!(timers[0].duration == Duration::from_secs(900));
+        check!(timers[1].duration == Duration::from_secs(600));
+    }
+
+    #[test]
+    fn it_understands_attached_units() {
+        let timers = suggest_timers("Boil the pasta (12min)");
+
+        check!(timers.len() == 1);
+        check!(timers[0].duration == Duration::from_secs(720));
+    }
+
+    #[test]
+    fn it_ignores_numbers_without_a_time_unit() {
+        check!(suggest_timers("Crack 3 eggs into a bowl. Preheat to 200 degrees").is_empty());
+    }
+
+    #[test]
+    fn it_skips_durations_too_long_to_represent() {
+        check!(suggest_timers("Age for 18446744073709551615 hours").is_empty());
+        check!(suggest_timers("Age for 10 to 18446744073709551615 days").is_empty());
+
+        let timers = suggest_timers("Wait 18446744073709551615 days or 2 hours");
+        check!(timers.len() == 1);
+        check!(timers[0].duration == Duration::from_secs(7200));
+    }
+}
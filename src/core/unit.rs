@@ -0,0 +1,200 @@
+use crate::core::recipe::QuantityType;
+
+const GRAMS_PER_OUNCE: f32 = 28.349_523;
+const GRAMS_PER_POUND: f32 = 453.592_37;
+const MILLILITERS_PER_FLUID_OUNCE: f32 = 29.573_53;
+const MILLILITERS_PER_CUP: f32 = 236.588_24;
+const MILLILITERS_PER_TABLESPOON: f32 = 14.786_765;
+const MILLILITERS_PER_TEASPOON: f32 = 4.928_922;
+
+/// System of measurement quantities are shown in. Recipes are always stored in metric.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnitSystem {
+    #[default]
+    Metric,
+    Imperial,
+}
+
+/// Unit of a quantity, including the imperial units recipes are never stored in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Count,
+    Kilo,
+    Gram,
+    Liter,
+    Milliliter,
+    Ounce,
+    Pound,
+    FluidOunce,
+    Cup,
+    Tablespoon,
+    Teaspoon,
+}
+
+impl From<&QuantityType> for Unit {
+    fn from(value: &QuantityType) -> Self {
+        match value {
+            QuantityType::Count => Self::Count,
+            QuantityType::Kilo => Self::Kilo,
+            QuantityType::Gram => Self::Gram,
+            QuantityType::Liter => Self::Liter,
+            QuantityType::Milliliter => Self::Milliliter,
+        }
+    }
+}
+
+/// Amount of something in a [`Unit`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantity {
+    pub unit: Unit,
+    pub amount: f32,
+}
+
+impl Quantity {
+    pub fn new(unit: Unit, amount: f32) -> Self {
+        Self { unit, amount }
+    }
+
+    /// Converts the quantity to the given system. Weights become ounces, or pounds from a pound
+    /// up, while volumes become fluid ounces, or cups from a quarter cup up. Converted amounts are
+    /// rounded to two decimals, quantities already in the system are left alone.
+    pub fn to_system(self, system: UnitSystem) -> Self {
+        match (system, self.unit) {
+            (_, Unit::Count)
+            | (UnitSystem::Metric, Unit::Kilo | Unit::Gram | Unit::Liter | Unit::Milliliter)
+            | (
+                UnitSystem::Imperial,
+                Unit::Ounce
+                | Unit::Pound
+                | Unit::FluidOunce
+                | Unit::Cup
+                | Unit::Tablespoon
+                | Unit::Teaspoon,
+            ) => self,
+            (UnitSystem::Metric, Unit::Ounce | Unit::Pound) => {
+                Self::new(Unit::Gram, round(self.grams()))
+            }
+            (
+                UnitSystem::Metric,
+                Unit::FluidOunce | Unit::Cup | Unit::Tablespoon | Unit::Teaspoon,
+            ) => Self::new(Unit::Milliliter, round(self.milliliters())),
+            (UnitSystem::Imperial, Unit::Kilo | Unit::Gram) => {
+                let grams = self.grams();
+
+                if grams >= GRAMS_PER_POUND {
+                    Self::new(Unit::Pound, round(grams / GRAMS_PER_POUND))
+                } else {
+                    Self::new(Unit::Ounce, round(grams / GRAMS_PER_OUNCE))
+                }
+            }
+            (UnitSystem::Imperial, Unit::Liter | Unit::Milliliter) => {
+                let milliliters = self.milliliters();
+
+                if milliliters >= MILLILITERS_PER_CUP / 4.0 {
+                    Self::new(Unit::Cup, round(milliliters / MILLILITERS_PER_CUP))
+                } else {
+                    Self::new(
+                        Unit::FluidOunce,
+                        round(milliliters / MILLILITERS_PER_FLUID_OUNCE),
+                    )
+                }
+            }
+        }
+    }
+
+    /// Converts the quantity to the metric type recipes are stored with
+    pub fn to_stored(self) -> (QuantityType, f32) {
+        let metric = self.to_system(UnitSystem::Metric);
+
+        let quantity_type = match metric.unit {
+            Unit::Count => QuantityType::Count,
+            Unit::Kilo => QuantityType::Kilo,
+            Unit::Gram => QuantityType::Gram,
+            Unit::Liter => QuantityType::Liter,
+            Unit::Milliliter => QuantityType::Milliliter,
+            Unit::Ounce
+            | Unit::Pound
+            | Unit::FluidOunce
+            | Unit::Cup
+            | Unit::Tablespoon
+            | Unit::Teaspoon => {
+                unreachable!("Metric quantities never have an imperial unit")
+            }
+        };
+
+        (quantity_type, metric.amount)
+    }
+
+    fn grams(&self) -> f32 {
+        match self.unit {
+            Unit::Kilo => self.amount * 1000.0,
+            Unit::Ounce => self.amount * GRAMS_PER_OUNCE,
+            Unit::Pound => self.amount * GRAMS_PER_POUND,
+            _ => self.amount,
+        }
+    }
+
+    fn milliliters(&self) -> f32 {
+        match self.unit {
+            Unit::Liter => self.amount * 1000.0,
+            Unit::FluidOunce => self.amount * MILLILITERS_PER_FLUID_OUNCE,
+            Unit::Cup => self.amount * MILLILITERS_PER_CUP,
+            Unit::Tablespoon => self.amount * MILLILITERS_PER_TABLESPOON,
+            Unit::Teaspoon => self.amount * MILLILITERS_PER_TEASPOON,
+            _ => self.amount,
+        }
+    }
+}
+
+fn round(amount: f32) -> f32 {
+    (amount * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+
+    #[test]
+    fn it_converts_weights_to_ounces_and_pounds() {
+        check!(
+            Quantity::new(Unit::Gram, 100.0).to_system(UnitSystem::Imperial)
+                == Quantity::new(Unit::Ounce, 3.53)
+        );
+        check!(
+            Quantity::new(Unit::Kilo, 1.0).to_system(UnitSystem::Imperial)
+                == Quantity::new(Unit::Pound, 2.2)
+        );
+    }
+
+    #[test]
+    fn it_converts_volumes_to_fluid_ounces_and_cups() {
+        check!(
+            Quantity::new(Unit::Milliliter, 30.0).to_system(UnitSystem::Imperial)
+                == Quantity::new(Unit::FluidOunce, 1.01)
+        );
+        check!(
+            Quantity::new(Unit::Liter, 0.5).to_system(UnitSystem::Imperial)
+                == Quantity::new(Unit::Cup, 2.11)
+        );
+    }
+
+    #[test]
+    fn it_leaves_counts_and_matching_systems_alone() {
+        let eggs = Quantity::new(Unit::Count, 3.0);
+        check!(eggs.to_system(UnitSystem::Imperial) == eggs);
+
+        let flour = Quantity::new(Unit::Kilo, 1.5);
+        check!(flour.to_system(UnitSystem::Metric) == flour);
+    }
+
+    #[test]
+    fn it_stores_imperial_quantities_as_metric() {
+        let_assert!((QuantityType::Milliliter, amount) = Quantity::new(Unit::Cup, 2.0).to_stored());
+        check!(amount == 473.18);
+
+        let_assert!((QuantityType::Gram, amount) = Quantity::new(Unit::Ounce, 4.0).to_stored());
+        check!(amount == 113.4);
+    }
+}
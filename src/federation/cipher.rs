@@ -0,0 +1,103 @@
+use std::{fmt, sync::Arc};
+
+use eyre::{OptionExt, eyre};
+use ring::aead::{Aad, CHACHA20_POLY1305, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use secrecy::{ExposeSecret, SecretBox};
+use sha2::{Digest, Sha256};
+
+/// Encrypts the tokens of remote instances before they are stored, so a copy of the database
+/// doesn't hand out access to the instances mirrored from. Tokens are sealed with
+/// ChaCha20-Poly1305 under a random nonce, which is stored in front of the ciphertext.
+#[derive(Clone)]
+pub struct TokenCipher {
+    key: Arc<LessSafeKey>,
+}
+
+impl fmt::Debug for TokenCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TokenCipher").finish_non_exhaustive()
+    }
+}
+
+impl TokenCipher {
+    /// Derives the key from the configured secret, which should be long and random
+    pub fn new(secret: &SecretBox<str>) -> Self {
+        let digest = Sha256::digest(secret.expose_secret().as_bytes());
+        let key = UnboundKey::new(&CHACHA20_POLY1305, &digest)
+            .expect("SHA-256 digests are as long as ChaCha20-Poly1305 keys");
+
+        Self {
+            key: Arc::new(LessSafeKey::new(key)),
+        }
+    }
+
+    pub fn encrypt(&self, token: &str) -> eyre::Result<Vec<u8>> {
+        let nonce = rand::random::<[u8; NONCE_LEN]>();
+
+        let mut ciphertext = token.as_bytes().to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut ciphertext,
+            )
+            .map_err(|_| eyre!("Failed to encrypt token"))?;
+
+        Ok([nonce.as_slice(), &ciphertext].concat())
+    }
+
+    pub fn decrypt(&self, sealed: &[u8]) -> eyre::Result<String> {
+        let (nonce, ciphertext) = sealed
+            .split_first_chunk::<NONCE_LEN>()
+            .ok_or_eyre("Encrypted token is too short")?;
+
+        let mut ciphertext = ciphertext.to_vec();
+        let token = self
+            .key
+            .open_in_place(
+                Nonce::assume_unique_for_key(*nonce),
+                Aad::empty(),
+                &mut ciphertext,
+            )
+            .map_err(|_| eyre!("Failed to decrypt token, was the key changed?"))?;
+
+        Ok(String::from_utf8(token.to_vec())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+
+    #[test]
+    fn it_decrypts_what_it_encrypted() {
+        let cipher = TokenCipher::new(&SecretBox::from("correct horse battery staple"));
+
+        let_assert!(Ok(sealed) = cipher.encrypt("secret"));
+        check!(!sealed.windows(6).any(|window| window == b"secret"));
+        check!(Some(&sealed) != cipher.encrypt("secret").ok().as_ref());
+
+        let_assert!(Ok(token) = cipher.decrypt(&sealed));
+        check!(token == "secret");
+    }
+
+    #[test]
+    fn it_refuses_tokens_sealed_with_another_key() {
+        let sealed = TokenCipher::new(&SecretBox::from("one"))
+            .encrypt("secret")
+            .expect("Failed to encrypt token");
+
+        check!(
+            TokenCipher::new(&SecretBox::from("other"))
+                .decrypt(&sealed)
+                .is_err()
+        );
+        check!(
+            TokenCipher::new(&SecretBox::from("one"))
+                .decrypt(&sealed[..4])
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,25 @@
+use thiserror::Error;
+
+use crate::core::recipe::Recipe;
+
+#[derive(Debug, Error)]
+pub enum FetchRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The remote instance rejected the token")]
+    Unauthorized,
+}
+
+pub trait RecipeFetcher: std::fmt::Debug + Clone + Send + Sync + 'static {
+    /// Fetches the recipes a remote instance publishes, `url` being the url the instance is
+    /// reachable on
+    fn fetch_recipes(
+        &self,
+        url: &str,
+        token: Option<&str>,
+    ) -> impl Future<Output = Result<Vec<Recipe>, FetchRecipesError>> + Send;
+}
@@ -0,0 +1,143 @@
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use eyre::Context;
+use reqwest::{
+    Client, StatusCode,
+    dns::{Addrs, Name, Resolve, Resolving},
+    redirect::Policy,
+};
+use url::Host;
+
+use crate::{
+    core::{federation::is_public_address, recipe::Recipe},
+    federation::fetcher::{FetchRecipesError, RecipeFetcher},
+    presentation::recipe::RecipeDto,
+};
+
+/// Remote instances send every recipe they publish at once, which takes a while for big ones
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Largest list of recipes read from a remote instance, a bigger response is refused instead
+/// of being read into memory
+const MAX_RESPONSE_SIZE: usize = 32 * 1024 * 1024;
+
+/// Followed redirects, each of which has to stay on a public address too
+const MAX_REDIRECTS: usize = 5;
+
+/// Resolves names like the system does, leaving out addresses which aren't public. Checking
+/// when connecting rather than when registering keeps names which later resolve to the local
+/// network from being fetched.
+#[derive(Debug)]
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_address(addr.ip()))
+                .collect::<Vec<SocketAddr>>();
+
+            if addrs.is_empty() {
+                return Err(format!("`{}` has no public address", name.as_str()).into());
+            }
+
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Fetches recipes from the `/federation/recipes` route of other gecko-recipes instances, as
+/// long as they are on a public address
+#[derive(Debug, Clone)]
+pub struct Http {
+    client: Client,
+}
+
+impl Http {
+    pub fn new() -> eyre::Result<Self> {
+        // Addresses in urls aren't resolved, so redirects to them are checked here
+        let redirects = Policy::custom(|attempt| {
+            let public = match attempt.url().host() {
+                Some(Host::Ipv4(ip)) => is_public_address(ip.into()),
+                Some(Host::Ipv6(ip)) => is_public_address(ip.into()),
+                Some(Host::Domain(_)) => true,
+                None => false,
+            };
+
+            if !public {
+                attempt.error("Redirected to a private address")
+            } else if attempt.previous().len() >= MAX_REDIRECTS {
+                attempt.error("Too many redirects")
+            } else {
+                attempt.follow()
+            }
+        });
+
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .dns_resolver(Arc::new(PublicResolver))
+            .redirect(redirects)
+            .build()
+            .wrap_err("Failed to build HTTP client")?;
+
+        Ok(Self { client })
+    }
+}
+
+impl RecipeFetcher for Http {
+    async fn fetch_recipes(
+        &self,
+        url: &str,
+        token: Option<&str>,
+    ) -> Result<Vec<Recipe>, FetchRecipesError> {
+        let mut request = self
+            .client
+            .get(format!("{}/federation/recipes", url.trim_end_matches('/')));
+
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .wrap_err("Failed to reach remote instance")?;
+
+        if matches!(
+            response.status(),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+        ) {
+            return Err(FetchRecipesError::Unauthorized);
+        }
+
+        let mut response = response
+            .error_for_status()
+            .wrap_err("Remote instance returned an error")?;
+
+        let too_large = || eyre::eyre!("Remote instance sent more than {MAX_RESPONSE_SIZE} bytes");
+        if response
+            .content_length()
+            .is_some_and(|length| length > MAX_RESPONSE_SIZE as u64)
+        {
+            return Err(too_large().into());
+        }
+
+        let mut body = vec![];
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .wrap_err("Failed to read recipes of remote instance")?
+        {
+            if body.len() + chunk.len() > MAX_RESPONSE_SIZE {
+                return Err(too_large().into());
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        let recipes = serde_json::from_slice::<Vec<RecipeDto>>(&body)
+            .wrap_err("Failed to parse recipes of remote instance")?;
+
+        Ok(recipes.into_iter().map(Recipe::from).collect())
+    }
+}
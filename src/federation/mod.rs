@@ -0,0 +1,3 @@
+pub mod cipher;
+pub mod fetcher;
+pub mod implementation;
@@ -14,56 +14,1053 @@
 //! Dependencies flow inward: presentation → core ← persistence, ensuring the core
 //! business logic remains independent of external concerns like databases or web frameworks.
 
-use actix_web::{App, HttpServer, web::Data};
-use eyre::Context;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+pub use client::{ClientCommand, ClientConfig, client};
+
+use actix_web::{
+    App, HttpServer,
+    http::KeepAlive,
+    middleware::from_fn,
+    web::{self, Data, ServiceConfig},
+};
+use captcha::{implementation::hcaptcha::Hcaptcha, verifier::EndpointGroup};
+use eyre::{Context, OptionExt};
+use federation::{cipher::TokenCipher, implementation::http::Http};
+use mail::implementation::smtp::Smtp;
+use notify::implementation::{gotify::Gotify, ntfy::Ntfy};
+use ocr::implementation::tesseract::Tesseract;
 use persistance::implementation::postgres::Postgres;
 use secrecy::{ExposeSecret, SecretBox};
-use sqlx::PgPool;
+use sqlx::{Connection, PgConnection, PgPool, postgres::PgPoolOptions};
+use suggest::implementation::openai::OpenAi;
+use video::implementation::oembed::Oembed;
+
+use crate::{
+    core::{
+        integrity::{IntegrityReport, IntegrityService},
+        nextcloud::{NextcloudConversion, NextcloudRecipe, convert_nextcloud},
+        notification::NotifyEventError,
+        recipe::{NewRecipe, SearchWeights},
+        retention::RetentionPolicy,
+    },
+    persistance::{
+        Repository,
+        circuit_breaker::{Breaker, CircuitBreaker},
+        metrics::Metered,
+    },
+    presentation::{
+        admin::AdminToken,
+        analytics::record_usage,
+        captcha::{CaptchaGate, require_captcha},
+        circuit_breaker::unavailable_while_open,
+        import::NextcloudImportDto,
+        metrics::{Metrics, record_route_latency},
+        normalize_path::normalize_path,
+        recipe::{NewRecipeDto, TrustedProxy},
+        recipe_id::public_id_resolver,
+    },
+};
 
+/// Anti-bot checks for public instances, abstracted behind a verifier interface.
+pub mod captcha;
+/// Command line client for a running instance.
+mod client;
 /// Core business logic and domain models for recipes and ingredients.
-mod core;
+pub mod core;
+/// Recipes of other instances, fetched behind a fetcher interface.
+pub mod federation;
+/// Outgoing email, abstracted behind a mailer interface.
+pub mod mail;
+/// Push notifications, abstracted behind a notifier interface.
+pub mod notify;
+/// Text recognition for imported photos, abstracted behind a recognizer interface.
+pub mod ocr;
 /// Data persistence layer with repository pattern and database implementations.
-mod persistance;
+pub mod persistance;
 /// HTTP request handlers and API endpoint definitions.
 mod presentation;
+/// Recipe generation by language models, abstracted behind a suggester interface.
+pub mod suggest;
+/// Scripted repositories for tests which run without a database.
+#[cfg(any(test, feature = "test_utils"))]
+pub mod test_utils;
+/// Video metadata lookups, abstracted behind a resolver interface.
+pub mod video;
+
+pub(crate) type RecipeService<R = Postgres> = crate::core::recipe::RecipeService<R, Option<Oembed>>;
+pub(crate) type CookingLogService<R = Postgres> = crate::core::cooking_log::CookingLogService<R>;
+pub(crate) type IngredientService<R = Postgres> = crate::core::ingredient::IngredientService<R>;
+pub(crate) type TagService<R = Postgres> = crate::core::tag::TagService<R>;
+pub(crate) type MealPlanService<R = Postgres> = crate::core::meal_plan::MealPlanService<R>;
+pub(crate) type DeletionService<R = Postgres> = crate::core::deletion::DeletionService<R>;
+pub(crate) type SnapshotService<R = Postgres> = crate::core::snapshot::SnapshotService<R>;
+pub(crate) type RetentionService<R = Postgres> = crate::core::retention::RetentionService<R>;
+pub(crate) type AnalyticsService<R = Postgres> = crate::core::analytics::AnalyticsService<R>;
+pub(crate) type ShareService<R = Postgres> = crate::core::share::ShareService<R, Option<Smtp>>;
+pub(crate) type ShareLinkService<R = Postgres> = crate::core::share_link::ShareLinkService<R, R>;
+pub(crate) type QrCodeService<R = Postgres> = crate::core::qr_code::QrCodeService<R, R>;
+pub(crate) type ExportService<R = Postgres> = crate::core::export::ExportService<R, R>;
+pub(crate) type SavedSearchService<R = Postgres> =
+    crate::core::saved_search::SavedSearchService<R, Option<Oembed>>;
+pub(crate) type SyncService<R = Postgres> = crate::core::sync::SyncService<R, Option<Oembed>>;
+pub(crate) type ImportService = crate::core::import::ImportService<Option<Tesseract>>;
+pub(crate) type GenerateService = crate::core::generate::GenerateService<Option<OpenAi>>;
+pub(crate) type FederationService<R = Postgres> =
+    crate::core::federation::FederationService<R, Http>;
+pub(crate) type OutboxRelay<R = Postgres> = crate::core::event::OutboxRelay<R>;
+pub(crate) type NtfyService<R = Postgres> =
+    crate::core::notification::NotificationService<R, Option<Ntfy>>;
+pub(crate) type GotifyService<R = Postgres> =
+    crate::core::notification::NotificationService<R, Option<Gotify>>;
+
+/// How often buffered recipe views are written to the database
+const VIEW_FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often buffered usage analytics are written to the database
+const ANALYTICS_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often the outbox is checked for events to relay
+const EVENT_RELAY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the retention policy is applied
+const RETENTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Delay before the second attempt to connect to the database, doubled after every failed attempt
+const CONNECT_RETRY_DELAY: Duration = Duration::from_millis(250);
 
-pub(crate) type RecipeService = crate::core::recipe::RecipeService<Postgres>;
+/// Longest delay between two attempts to connect to the database
+const MAX_CONNECT_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// How long a request waits for a database connection before failing
+const DATABASE_ACQUIRE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Number of database failures in a row after which requests fail right away
+const CIRCUIT_BREAKER_THRESHOLD: u32 = 5;
+
+/// How long requests fail right away once the database keeps failing
+const CIRCUIT_BREAKER_COOLDOWN: Duration = Duration::from_secs(10);
 
 #[derive(Debug)]
 /// Configuration used to start the server
 pub struct Config {
     /// Url used to connect to the database instance
     pub database_url: SecretBox<str>,
+    /// How long to keep retrying when the database can't be reached at startup, like when it is
+    /// still starting up next to the server. Zero gives up after the first attempt.
+    pub wait_for_db: Duration,
     /// Host to bind to
     pub host: String,
     /// Port to bind to
     pub port: u16,
+    /// Number of worker threads handling requests, one per physical CPU core when missing
+    pub workers: Option<usize>,
+    /// How long idle connections are kept open for the next request. Zero closes connections
+    /// after every response.
+    pub keep_alive: Duration,
+    /// Maximum number of connections waiting to be accepted
+    pub backlog: u32,
+    /// Maximum number of open connections per worker, further connections wait until one closes
+    pub max_connections: usize,
+    /// Url the instance is reachable on, used for links leaving the API like QR codes. Defaults
+    /// to `http://{host}:{port}`
+    pub public_url: Option<String>,
+    /// Url used to connect to the SMTP server, mail is disabled when missing
+    pub smtp_url: Option<SecretBox<str>>,
+    /// Sender address for outgoing mail, required when `smtp_url` is set
+    pub mail_from: Option<String>,
+    /// Maximum number of recipes shared by email per hour, across the whole instance
+    pub share_email_limit: u32,
+    /// Directory with a static frontend to serve next to the API
+    pub serve_frontend: Option<PathBuf>,
+    /// oEmbed endpoint used to look up video titles and thumbnails, skipped when missing
+    pub oembed_url: Option<String>,
+    /// Path to the `tesseract` binary used to import recipes from photos, disabled when missing
+    pub tesseract_path: Option<PathBuf>,
+    /// Base url of an OpenAI compatible API used to generate recipes, disabled when missing
+    pub llm_url: Option<String>,
+    /// Key used to authenticate with the LLM API
+    pub llm_api_key: Option<SecretBox<str>>,
+    /// Model used to generate recipes
+    pub llm_model: String,
+    /// Url of the ntfy topic recipe events are pushed to, disabled when missing
+    pub ntfy_url: Option<String>,
+    /// Access token for protected ntfy topics
+    pub ntfy_token: Option<SecretBox<str>>,
+    /// Url of the Gotify server recipe events are pushed to, disabled when missing
+    pub gotify_url: Option<String>,
+    /// Application token used to publish to Gotify, required when `gotify_url` is set
+    pub gotify_token: Option<SecretBox<str>>,
+    /// How often the recipes of registered remote instances are mirrored. Zero only syncs when
+    /// asked to through the API.
+    pub federation_interval: Duration,
+    /// Secret the tokens of remote instances are encrypted with, instances can only be registered
+    /// with a token when set
+    pub federation_key: Option<SecretBox<str>>,
+    /// Repository calls taking at least this long are logged as a warning along with their
+    /// arguments, disabled when missing
+    pub slow_query_threshold: Option<Duration>,
+    /// Serve the recipe tools over the Model Context Protocol on stdin and stdout instead of
+    /// starting the HTTP server
+    pub mcp_stdio: bool,
+    /// Requests come through a reverse proxy which sets `X-Forwarded-For` and `Remote-User`. Only
+    /// then are those headers believed, anyone can send them on a direct connection.
+    pub trusted_proxy: bool,
+    /// Token admins send as bearer authentication to manage remote instances, nobody is an admin
+    /// when missing
+    pub admin_token: Option<SecretBox<str>>,
+    /// The instance is open to anyone on the internet, which puts the writes of `captcha_groups`
+    /// behind a CAPTCHA
+    pub public_instance: bool,
+    /// hCaptcha secret key used to verify solved CAPTCHAs, required for public instances
+    pub captcha_secret: Option<SecretBox<str>>,
+    /// Endpoint groups whose writes need a solved CAPTCHA on public instances
+    pub captcha_groups: Vec<EndpointGroup>,
+    /// How long audit records, revoked share links and snapshots are kept before they are deleted
+    /// in the background
+    pub retention: RetentionPolicy,
+    /// Only log what the retention policy would delete, without deleting anything
+    pub retention_dry_run: bool,
+    /// Count requests per route and searched terms, without anything about who made them
+    pub analytics: bool,
+    /// What a keyword matching the name, ingredients, tags or description adds to the rank of a
+    /// recipe in keyword searches
+    pub search_weights: SearchWeights,
+}
+
+impl Config {
+    /// Creates a config with every integration disabled, using the same defaults as the binary
+    pub fn new(database_url: SecretBox<str>) -> Self {
+        Self {
+            database_url,
+            wait_for_db: Duration::from_secs(30),
+            host: "127.0.0.1".to_string(),
+            port: 8080,
+            workers: None,
+            keep_alive: Duration::from_secs(5),
+            backlog: 2048,
+            max_connections: 25_000,
+            public_url: None,
+            smtp_url: None,
+            mail_from: None,
+            share_email_limit: 20,
+            serve_frontend: None,
+            oembed_url: None,
+            tesseract_path: None,
+            llm_url: None,
+            llm_api_key: None,
+            llm_model: "gpt-4o-mini".to_string(),
+            ntfy_url: None,
+            ntfy_token: None,
+            gotify_url: None,
+            gotify_token: None,
+            federation_interval: Duration::from_secs(15 * 60),
+            federation_key: None,
+            slow_query_threshold: None,
+            mcp_stdio: false,
+            trusted_proxy: false,
+            admin_token: None,
+            public_instance: false,
+            captcha_secret: None,
+            captcha_groups: vec![
+                EndpointGroup::Recipes,
+                EndpointGroup::Sharing,
+                EndpointGroup::Imports,
+                EndpointGroup::Sync,
+                EndpointGroup::Other,
+            ],
+            retention: RetentionPolicy::default(),
+            retention_dry_run: false,
+            analytics: false,
+            search_weights: SearchWeights::default(),
+        }
+    }
 }
 
+/// Repository the services use, timed per method and guarded against database outages
+type Guarded<R> = CircuitBreaker<Metered<R>>;
+
+type NotificationServices<R> = (NtfyService<Guarded<R>>, GotifyService<Guarded<R>>);
+
+/// Gecko Recipes wired up to its database and integrations. Can be served on its own with
+/// [`Server::run`], or mounted into an existing Actix Web application with [`Server::configure`].
+#[derive(Debug, Clone)]
+pub struct Server<R: Repository = Postgres> {
+    recipe_service: RecipeService<Guarded<R>>,
+    cooking_log_service: CookingLogService<Guarded<R>>,
+    ingredient_service: IngredientService<Guarded<R>>,
+    tag_service: TagService<Guarded<R>>,
+    meal_plan_service: MealPlanService<Guarded<R>>,
+    snapshot_service: SnapshotService<Guarded<R>>,
+    deletion_service: DeletionService<Guarded<R>>,
+    retention_service: RetentionService<Guarded<R>>,
+    analytics_service: AnalyticsService<Guarded<R>>,
+    share_service: ShareService<Guarded<R>>,
+    share_link_service: ShareLinkService<Guarded<R>>,
+    sync_service: SyncService<Guarded<R>>,
+    saved_search_service: SavedSearchService<Guarded<R>>,
+    qr_code_service: QrCodeService<Guarded<R>>,
+    export_service: ExportService<Guarded<R>>,
+    import_service: ImportService,
+    generate_service: GenerateService,
+    outbox_relay: OutboxRelay<Guarded<R>>,
+    federation_service: FederationService<Guarded<R>>,
+    federation_interval: Duration,
+    /// Missing when neither ntfy nor Gotify is configured
+    notification_services: Option<NotificationServices<R>>,
+    breaker: Breaker,
+    metrics: Metrics,
+    host: String,
+    port: u16,
+    workers: Option<usize>,
+    keep_alive: Duration,
+    backlog: u32,
+    max_connections: usize,
+    serve_frontend: Option<PathBuf>,
+    mcp_stdio: bool,
+    trusted_proxy: bool,
+    admin_token: Option<AdminToken>,
+    /// Missing unless the instance is public
+    captcha_gate: Option<CaptchaGate<Hcaptcha>>,
+}
+
+impl Server {
+    /// Connects to the database and sets up the integrations enabled in the config
+    pub async fn new(config: Config) -> eyre::Result<Self> {
+        let pg_pool = connect_to_database(&config.database_url, config.wait_for_db).await?;
+
+        Self::with_repository(Postgres::new(pg_pool), config)
+    }
+}
+
+impl<R: Repository> Server<R> {
+    /// Sets up the integrations enabled in the config on top of another repository than Postgres.
+    /// The `database_url` of the config is not used.
+    pub fn with_repository(repository: R, config: Config) -> eyre::Result<Self> {
+        let metrics = Metrics::default();
+        let breaker = Breaker::new(CIRCUIT_BREAKER_THRESHOLD, CIRCUIT_BREAKER_COOLDOWN);
+        let repository = CircuitBreaker::new(
+            Metered::new(
+                repository,
+                metrics.queries.clone(),
+                config.slow_query_threshold,
+            ),
+            breaker.clone(),
+        );
+
+        let mailer = match &config.smtp_url {
+            Some(smtp_url) => {
+                let mail_from = config
+                    .mail_from
+                    .as_deref()
+                    .ok_or_eyre("A sender address is required when SMTP is configured")?;
+
+                Some(Smtp::new(smtp_url.expose_secret(), mail_from)?)
+            }
+            None => None,
+        };
+
+        let video_resolver = config.oembed_url.as_deref().map(Oembed::new).transpose()?;
+
+        let recipe_service = RecipeService::new(repository.clone(), video_resolver)
+            .with_search_weights(config.search_weights);
+        let outbox_relay = OutboxRelay::new(repository.clone());
+        let cooking_log_service = CookingLogService::new(repository.clone());
+        let ingredient_service = IngredientService::new(repository.clone());
+        let tag_service = TagService::new(repository.clone());
+        let meal_plan_service = MealPlanService::new(repository.clone());
+        let snapshot_service = SnapshotService::new(repository.clone());
+        let deletion_service = DeletionService::new(repository.clone());
+        let retention_service = RetentionService::new(
+            repository.clone(),
+            config.retention,
+            config.retention_dry_run,
+        );
+        let analytics_service = AnalyticsService::new(repository.clone(), config.analytics);
+        let public_url = config
+            .public_url
+            .clone()
+            .unwrap_or_else(|| format!("http://{}:{}", config.host, config.port));
+
+        let share_link_service = ShareLinkService::new(repository.clone(), repository.clone());
+        let sync_service = SyncService::new(repository.clone(), recipe_service.clone());
+        let saved_search_service =
+            SavedSearchService::new(repository.clone(), recipe_service.clone());
+        let qr_code_service =
+            QrCodeService::new(repository.clone(), repository.clone(), &public_url);
+        let export_service = ExportService::new(repository.clone(), repository.clone());
+        let share_service = ShareService::new(
+            repository.clone(),
+            mailer,
+            config.share_email_limit,
+            Duration::from_secs(60 * 60),
+        );
+
+        let import_service = ImportService::new(config.tesseract_path.clone().map(Tesseract::new));
+
+        let suggester = match &config.llm_url {
+            Some(llm_url) => Some(OpenAi::new(
+                llm_url,
+                config.llm_api_key,
+                config.llm_model.clone(),
+            )?),
+            None => None,
+        };
+        let generate_service = GenerateService::new(suggester);
+
+        let federation_service = FederationService::new(
+            repository.clone(),
+            Http::new()?,
+            config.federation_key.as_ref().map(TokenCipher::new),
+        );
+
+        let ntfy = config
+            .ntfy_url
+            .as_deref()
+            .map(|ntfy_url| Ntfy::new(ntfy_url, config.ntfy_token))
+            .transpose()?;
+
+        let gotify = match &config.gotify_url {
+            Some(gotify_url) => {
+                let token = config
+                    .gotify_token
+                    .ok_or_eyre("An application token is required when Gotify is configured")?;
+
+                Some(Gotify::new(gotify_url, token)?)
+            }
+            None => None,
+        };
+
+        let captcha_gate = if config.public_instance {
+            let secret = config
+                .captcha_secret
+                .ok_or_eyre("A CAPTCHA secret is required for public instances")?;
+
+            Some(CaptchaGate {
+                verifier: Hcaptcha::new(secret)?,
+                groups: config.captcha_groups,
+            })
+        } else {
+            None
+        };
+
+        let notification_services = (ntfy.is_some() || gotify.is_some()).then(|| {
+            (
+                NtfyService::new(repository.clone(), ntfy),
+                GotifyService::new(repository.clone(), gotify),
+            )
+        });
+
+        Ok(Self {
+            recipe_service,
+            cooking_log_service,
+            ingredient_service,
+            tag_service,
+            meal_plan_service,
+            snapshot_service,
+            deletion_service,
+            retention_service,
+            analytics_service,
+            share_service,
+            share_link_service,
+            sync_service,
+            saved_search_service,
+            qr_code_service,
+            export_service,
+            import_service,
+            generate_service,
+            outbox_relay,
+            federation_service,
+            federation_interval: config.federation_interval,
+            notification_services,
+            breaker,
+            metrics,
+            host: config.host,
+            port: config.port,
+            workers: config.workers,
+            keep_alive: config.keep_alive,
+            backlog: config.backlog,
+            max_connections: config.max_connections,
+            serve_frontend: config.serve_frontend,
+            mcp_stdio: config.mcp_stdio,
+            trusted_proxy: config.trusted_proxy,
+            admin_token: config.admin_token.map(|token| AdminToken(Arc::new(token))),
+            captcha_gate,
+        })
+    }
+
+    /// Registers the API routes and the services they use. To mount the API under a path prefix
+    /// use a scope like `web::scope("/recipes").configure(|cfg| server.configure(cfg))`, and
+    /// include the prefix in `public_url` so links leaving the API point to the right place.
+    ///
+    /// The API answers every request under the prefix it is mounted at, so routes of your own
+    /// sharing that prefix need to be registered before it.
+    pub fn configure(&self, cfg: &mut ServiceConfig) {
+        self.configure_api(cfg, None);
+    }
+
+    /// Registers the API like [`Server::configure`], serving the frontend in `frontend` for the
+    /// requests matching no route
+    fn configure_api(&self, cfg: &mut ServiceConfig, frontend: Option<&Path>) {
+        // A scope without prefix is the only way to put middleware around routes from a
+        // `ServiceConfig`. It answers every request, so anything handling the requests it has no
+        // route for needs to be registered inside it, after the routes.
+        let mut api = web::scope("")
+            .wrap(from_fn(require_captcha::<Hcaptcha>))
+            .configure(Self::configure_routes);
+        if let Some(dir) = frontend {
+            api = api.service(crate::presentation::frontend::frontend(dir));
+        }
+        cfg.service(api);
+
+        if let Some(gate) = &self.captcha_gate {
+            cfg.app_data(Data::new(gate.clone()));
+        }
+        if self.trusted_proxy {
+            cfg.app_data(Data::new(TrustedProxy));
+        }
+        if let Some(token) = &self.admin_token {
+            cfg.app_data(Data::new(token.clone()));
+        }
+
+        cfg.app_data(public_id_resolver(self.recipe_service.clone()))
+            .app_data(Data::new(self.recipe_service.clone()))
+            .app_data(Data::new(self.cooking_log_service.clone()))
+            .app_data(Data::new(self.ingredient_service.clone()))
+            .app_data(Data::new(self.tag_service.clone()))
+            .app_data(Data::new(self.meal_plan_service.clone()))
+            .app_data(Data::new(self.snapshot_service.clone()))
+            .app_data(Data::new(self.deletion_service.clone()))
+            .app_data(Data::new(self.retention_service.clone()))
+            .app_data(Data::new(self.analytics_service.clone()))
+            .app_data(Data::new(self.share_service.clone()))
+            .app_data(Data::new(self.share_link_service.clone()))
+            .app_data(Data::new(self.sync_service.clone()))
+            .app_data(Data::new(self.saved_search_service.clone()))
+            .app_data(Data::new(self.qr_code_service.clone()))
+            .app_data(Data::new(self.export_service.clone()))
+            .app_data(Data::new(self.import_service.clone()))
+            .app_data(Data::new(self.generate_service.clone()))
+            .app_data(Data::new(self.federation_service.clone()))
+            .app_data(Data::new(self.breaker.clone()))
+            .app_data(Data::new(self.metrics.clone()));
+    }
+
+    /// Registers the routes of every module
+    fn configure_routes(cfg: &mut ServiceConfig) {
+        // Before the recipe routes, which would otherwise match `/recipes/duplicates` as a recipe
+        crate::presentation::duplicate::configure::<Guarded<R>>(cfg);
+        crate::presentation::recipe::configure::<Guarded<R>>(cfg);
+        crate::presentation::cooking_log::configure::<Guarded<R>>(cfg);
+        crate::presentation::ingredient::configure::<Guarded<R>>(cfg);
+        crate::presentation::tag::configure::<Guarded<R>>(cfg);
+        crate::presentation::meal_plan::configure::<Guarded<R>>(cfg);
+        crate::presentation::share::configure::<Guarded<R>>(cfg);
+        crate::presentation::share_link::configure::<Guarded<R>>(cfg);
+        crate::presentation::sync::configure::<Guarded<R>>(cfg);
+        crate::presentation::saved_search::configure::<Guarded<R>>(cfg);
+        crate::presentation::qr_code::configure::<Guarded<R>>(cfg);
+        crate::presentation::print::configure::<Guarded<R>>(cfg);
+        crate::presentation::export::configure::<Guarded<R>>(cfg);
+        crate::presentation::timer::configure::<Guarded<R>>(cfg);
+        crate::presentation::fork::configure::<Guarded<R>>(cfg);
+        crate::presentation::federation::configure::<Guarded<R>>(cfg);
+        crate::presentation::data_quality::configure::<Guarded<R>>(cfg);
+        crate::presentation::snapshot::configure::<Guarded<R>>(cfg);
+        crate::presentation::deletion::configure::<Guarded<R>>(cfg);
+        crate::presentation::retention::configure::<Guarded<R>>(cfg);
+        crate::presentation::analytics::configure::<Guarded<R>>(cfg);
+        crate::presentation::metrics::configure(cfg);
+
+        crate::presentation::import::configure(cfg);
+
+        cfg.service(crate::presentation::generate::generate_recipe);
+    }
+
+    /// Starts flushing recipe views and usage analytics, relaying recipe events, pushing
+    /// notifications, mirroring remote instances and applying the retention policy in the
+    /// background. Needs to be called once, from within a Tokio runtime.
+    pub fn spawn_background_tasks(&self) {
+        let view_flusher = self.recipe_service.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(VIEW_FLUSH_INTERVAL);
+
+            loop {
+                interval.tick().await;
+
+                if let Err(error) = view_flusher.flush_views().await {
+                    tracing::error!(?error, "Failed to flush recipe views");
+                }
+            }
+        });
+
+        let usage_flusher = self.analytics_service.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(ANALYTICS_FLUSH_INTERVAL);
+
+            loop {
+                interval.tick().await;
+
+                if let Err(error) = usage_flusher.flush().await {
+                    tracing::error!(?error, "Failed to flush usage analytics");
+                }
+            }
+        });
+
+        let mut events = self.outbox_relay.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => tracing::debug!(
+                        event_id = event.event_id,
+                        kind = ?event.kind,
+                        recipe_id = event.recipe_id,
+                        public_id = %event.public_id,
+                        occurred_at = %event.occurred_at,
+                        "Recipe event"
+                    ),
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(missed)) => {
+                        tracing::warn!(missed, "Missed recipe events")
+                    }
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        if let Some((ntfy_service, gotify_service)) = self.notification_services.clone() {
+            let mut events = self.outbox_relay.subscribe();
+            tokio::spawn(async move {
+                loop {
+                    match events.recv().await {
+                        Ok(event) => {
+                            for result in [
+                                ntfy_service.notify_event(&event).await,
+                                gotify_service.notify_event(&event).await,
+                            ] {
+                                match result {
+                                    Ok(()) | Err(NotifyEventError::Disabled) => {}
+                                    Err(error) => {
+                                        tracing::error!(?error, "Failed to push recipe event")
+                                    }
+                                }
+                            }
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(missed)) => {
+                            tracing::warn!(missed, "Missed recipe events for push notifications")
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                    }
+                }
+            });
+        }
+
+        if !self.federation_interval.is_zero() {
+            let federation_service = self.federation_service.clone();
+            let federation_interval = self.federation_interval;
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(federation_interval);
+
+                loop {
+                    interval.tick().await;
+
+                    if let Err(error) = federation_service.sync_all().await {
+                        tracing::error!(?error, "Failed to sync remote instances");
+                    }
+                }
+            });
+        }
+
+        if !self.retention_service.policy().keeps_everything() {
+            let retention_service = self.retention_service.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(RETENTION_INTERVAL);
+
+                loop {
+                    interval.tick().await;
+
+                    match retention_service.apply().await {
+                        Ok(report) => tracing::info!(
+                            dry_run = report.dry_run,
+                            deletions = report.deletions.map(|expired| expired.count),
+                            revoked_share_links =
+                                report.revoked_share_links.map(|expired| expired.count),
+                            snapshots = report.snapshots.map(|expired| expired.count),
+                            "Applied retention policy"
+                        ),
+                        Err(error) => tracing::error!(?error, "Failed to apply retention policy"),
+                    }
+                }
+            });
+        }
+
+        let event_relay = self.outbox_relay.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(EVENT_RELAY_INTERVAL);
+
+            loop {
+                interval.tick().await;
+
+                if let Err(error) = event_relay.relay().await {
+                    tracing::error!(?error, "Failed to relay recipe events");
+                }
+            }
+        });
+    }
+
+    /// Writes the buffered recipe views and usage analytics to the database, should be called on
+    /// shutdown
+    pub async fn flush_views(&self) -> eyre::Result<()> {
+        self.recipe_service
+            .flush_views()
+            .await
+            .wrap_err("Failed to flush recipe views")?;
+
+        self.analytics_service
+            .flush()
+            .await
+            .wrap_err("Failed to flush usage analytics")
+    }
+
+    /// Serves the API on the configured host and port until it is shut down, or serves the
+    /// recipe tools over MCP on stdin and stdout when `mcp_stdio` is set
+    pub async fn run(self) -> eyre::Result<()> {
+        if self.mcp_stdio {
+            crate::presentation::mcp::serve_stdio(self.recipe_service.clone()).await?;
+            return self.flush_views().await;
+        }
+
+        self.spawn_background_tasks();
+
+        let keep_alive = if self.keep_alive.is_zero() {
+            KeepAlive::Disabled
+        } else {
+            KeepAlive::Timeout(self.keep_alive)
+        };
+
+        let server = self.clone();
+        let mut http_server = HttpServer::new(move || {
+            App::new()
+                .wrap(from_fn(unavailable_while_open))
+                .wrap(from_fn(record_route_latency))
+                .wrap(from_fn(record_usage::<Guarded<R>>))
+                // Outermost, so every other middleware sees the normalized path
+                .wrap(from_fn(normalize_path))
+                .configure(|cfg| server.configure_api(cfg, server.serve_frontend.as_deref()))
+        })
+        .keep_alive(keep_alive)
+        .max_connections(self.max_connections)
+        // Needs to be set before binding, it applies to the sockets created by `bind`
+        .backlog(self.backlog);
+
+        if let Some(workers) = self.workers {
+            http_server = http_server.workers(workers);
+        }
+
+        http_server
+            .bind((self.host.as_str(), self.port))
+            .wrap_err("Failed to bind server")?
+            .run()
+            .await?;
+
+        self.flush_views().await
+    }
+}
+
+/// Starts the server as configured, logging to stdout
 pub async fn server(config: Config) -> eyre::Result<()> {
-    tracing_subscriber::fmt::init();
+    if config.mcp_stdio {
+        // Stdout carries the protocol messages
+        tracing_subscriber::fmt()
+            .with_writer(std::io::stderr)
+            .init();
+    } else {
+        tracing_subscriber::fmt::init();
+    }
+
+    Server::new(config).await?.run().await
+}
+
+/// Imports a JSON array of recipes, in the format `POST /recipes` takes, straight into the
+/// database. Returns the number of imported recipes.
+pub async fn import(
+    database_url: SecretBox<str>,
+    wait_for_db: Duration,
+    path: &Path,
+) -> eyre::Result<usize> {
+    let recipes = std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+    let recipes = serde_json::from_str::<Vec<NewRecipeDto>>(&recipes)
+        .wrap_err_with(|| format!("Failed to parse recipes in {}", path.display()))?;
+
+    let pg_pool = connect_to_database(&database_url, wait_for_db).await?;
+
+    let recipe_ids = RecipeService::new(Postgres::new(pg_pool), None)
+        .import_recipes(recipes.into_iter().map(NewRecipe::from).collect())
+        .await
+        .wrap_err("Failed to import recipes")?;
+
+    Ok(recipe_ids.len())
+}
+
+/// Reads a Nextcloud Cookbook folder, which holds a folder with a `recipe.json` per recipe, and
+/// converts its recipes. Folders without a `recipe.json` are ignored.
+pub fn read_nextcloud_cookbook(path: &Path) -> eyre::Result<Vec<NextcloudConversion>> {
+    let mut folders = std::fs::read_dir(path)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+    folders.sort();
+
+    let mut conversions = vec![];
+
+    for folder in folders {
+        let file = folder.join("recipe.json");
+        if !file.is_file() {
+            continue;
+        }
+
+        let recipe = std::fs::read_to_string(&file)
+            .wrap_err_with(|| format!("Failed to read {}", file.display()))?;
+        let recipe = serde_json::from_str::<NextcloudImportDto>(&recipe)
+            .wrap_err_with(|| format!("Failed to parse recipe in {}", file.display()))?;
+
+        let mut recipe = NextcloudRecipe::from(recipe);
+
+        // Cookbook keeps the photo next to the `recipe.json`, recipes have no place for it
+        if folder.join("full.jpg").is_file() {
+            recipe.unsupported.push("photo".to_string());
+        }
+
+        conversions.push(convert_nextcloud(recipe));
+    }
+
+    Ok(conversions)
+}
+
+/// Imports recipes read by [`read_nextcloud_cookbook`] along with their tags, in one go like
+/// [`import`]. Returns the ids of the new recipes.
+pub async fn import_nextcloud(
+    database_url: SecretBox<str>,
+    wait_for_db: Duration,
+    conversions: Vec<NextcloudConversion>,
+) -> eyre::Result<Vec<i32>> {
+    let pg_pool = connect_to_database(&database_url, wait_for_db).await?;
+    let repository = Postgres::new(pg_pool);
+
+    let (drafts, tags): (Vec<_>, Vec<_>) = conversions
+        .into_iter()
+        .map(|conversion| (conversion.draft, conversion.tags))
+        .unzip();
+
+    let recipe_ids = RecipeService::new(repository.clone(), None)
+        .import_recipes(drafts)
+        .await
+        .wrap_err("Failed to import recipes")?;
+
+    let tag_service = TagService::new(repository);
+
+    for (recipe_id, tags) in recipe_ids.iter().zip(tags) {
+        if tags.is_empty() {
+            continue;
+        }
+
+        tag_service
+            .set_recipe_tags(*recipe_id, &tags)
+            .await
+            .wrap_err_with(|| format!("Failed to tag recipe {recipe_id}"))?;
+    }
+
+    Ok(recipe_ids)
+}
 
-    let pg_pool = PgPool::connect(config.database_url.expose_secret())
+/// Checks the database for rows breaking referential integrity, like ingredients with gaps in their
+/// order, and repairs them when `repair` is set.
+pub async fn check(
+    database_url: SecretBox<str>,
+    wait_for_db: Duration,
+    repair: bool,
+) -> eyre::Result<IntegrityReport> {
+    let pg_pool = connect_to_database(&database_url, wait_for_db).await?;
+
+    IntegrityService::new(Postgres::new(pg_pool))
+        .check(repair)
+        .await
+        .wrap_err("Failed to check database integrity")
+}
+
+/// Connects to the database, retrying with exponential backoff until `wait` has passed
+async fn connect_to_database(
+    database_url: &SecretBox<str>,
+    wait: Duration,
+) -> eyre::Result<PgPool> {
+    let deadline = tokio::time::Instant::now() + wait;
+    let mut delay = CONNECT_RETRY_DELAY;
+
+    // Probing with a single connection, as the pool keeps retrying on its own for half a minute
+    loop {
+        let error = match PgConnection::connect(database_url.expose_secret()).await {
+            Ok(connection) => {
+                let _ = connection.close().await;
+                break;
+            }
+            // An invalid url won't become valid by waiting for it
+            Err(error @ sqlx::Error::Configuration(_)) => error,
+            Err(error) if tokio::time::Instant::now() < deadline => {
+                let retry_in = delay.min(deadline - tokio::time::Instant::now());
+                tracing::warn!(?error, ?retry_in, "Failed to connect to database, retrying");
+
+                tokio::time::sleep(retry_in).await;
+                delay = (delay * 2).min(MAX_CONNECT_RETRY_DELAY);
+                continue;
+            }
+            Err(error) => error,
+        };
+
+        return Err(error).wrap_err("Failed to connect to database instance");
+    }
+
+    PgPoolOptions::new()
+        .acquire_timeout(DATABASE_ACQUIRE_TIMEOUT)
+        .connect(database_url.expose_secret())
         .await
-        .wrap_err("Failed to connect to database instance")?;
-
-    let postgres = Postgres::new(pg_pool);
-
-    let recipe_service = RecipeService::new(postgres);
-
-    HttpServer::new(move || {
-        App::new()
-            .service(crate::presentation::recipe::list_recipes)
-            .service(crate::presentation::recipe::search_recipes)
-            .service(crate::presentation::recipe::create_recipe)
-            .service(crate::presentation::recipe::update_recipe)
-            .service(crate::presentation::recipe::delete_recipe)
-            .app_data(Data::new(recipe_service.clone()))
-    })
-    .bind((config.host.as_str(), config.port))
-    .wrap_err("Failed to bind server")?
-    .run()
-    .await?;
-
-    Ok(())
+        .wrap_err("Failed to connect to database instance")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{
+        http::{StatusCode, header},
+        test,
+    };
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_can_be_mounted_under_a_prefix(pool: PgPool) {
+        let server =
+            Server::with_repository(Postgres::new(pool), Config::new(SecretBox::from("unused")))
+                .expect("Failed to set up server");
+
+        let app = test::init_service(
+            App::new().service(web::scope("/recipes-api").configure(|cfg| server.configure(cfg))),
+        )
+        .await;
+
+        let request = test::TestRequest::get()
+            .uri("/recipes-api/recipes")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert2::check!(response.status() == StatusCode::OK);
+
+        let request = test::TestRequest::get().uri("/recipes").to_request();
+        let response = test::call_service(&app, request).await;
+        assert2::check!(response.status() == StatusCode::NOT_FOUND);
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_guards_a_mounted_public_instance_with_a_captcha(pool: PgPool) {
+        let config = Config {
+            public_instance: true,
+            captcha_secret: Some(SecretBox::from("secret")),
+            ..Config::new(SecretBox::from("unused"))
+        };
+        let server =
+            Server::with_repository(Postgres::new(pool), config).expect("Failed to set up server");
+
+        let app = test::init_service(
+            App::new().service(web::scope("/recipes-api").configure(|cfg| server.configure(cfg))),
+        )
+        .await;
+
+        let request = test::TestRequest::post()
+            .uri("/recipes-api/recipes")
+            .set_json(
+                serde_json::json!({ "name": "Toast", "ingredients": [], "meal_type": "Breakfast" }),
+            )
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert2::check!(response.status() == StatusCode::FORBIDDEN);
+
+        // Sync uploads write recipes as well
+        let request = test::TestRequest::post()
+            .uri("/recipes-api/sync")
+            .set_json(serde_json::json!({ "changes": [] }))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert2::check!(response.status() == StatusCode::FORBIDDEN);
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_serves_the_frontend_for_paths_without_a_route(pool: PgPool) {
+        let dir = std::env::temp_dir().join(format!("gecko-recipes-server-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("Failed to create frontend dir");
+        std::fs::write(dir.join("index.html"), "<html>app</html>").expect("Failed to write index");
+
+        let server =
+            Server::with_repository(Postgres::new(pool), Config::new(SecretBox::from("unused")))
+                .expect("Failed to set up server");
+        let app =
+            test::init_service(App::new().configure(|cfg| server.configure_api(cfg, Some(&dir))))
+                .await;
+
+        let request = test::TestRequest::get()
+            .uri("/meal-plan/week")
+            .insert_header((header::ACCEPT, "text/html"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert2::check!(response.status() == StatusCode::OK);
+        assert2::check!(test::read_body(response).await == "<html>app</html>");
+
+        let request = test::TestRequest::get().uri("/recipes").to_request();
+        let response = test::call_service(&app, request).await;
+        assert2::check!(response.status() == StatusCode::OK);
+        assert2::check!(test::read_body(response).await.starts_with(b"["));
+
+        let request = test::TestRequest::get()
+            .uri("/recipes/1/unknown")
+            .insert_header((header::ACCEPT, "application/json"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert2::check!(response.status() == StatusCode::NOT_FOUND);
+
+        let request = test::TestRequest::post()
+            .uri("/meal-plan/week")
+            .insert_header((header::ACCEPT, "text/html"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert2::check!(response.status() == StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn it_keeps_retrying_until_the_wait_is_over() {
+        // Nothing listens on port 1, so every attempt is refused right away
+        let database_url = SecretBox::from("postgres://postgres@127.0.0.1:1/gecko");
+        let started = std::time::Instant::now();
+
+        let result = connect_to_database(&database_url, Duration::from_millis(600)).await;
+
+        assert2::check!(result.is_err());
+        assert2::check!(started.elapsed() >= Duration::from_millis(600));
+        assert2::check!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn it_does_not_retry_invalid_urls() {
+        let database_url = SecretBox::from("not a database url");
+        let started = std::time::Instant::now();
+
+        let result = connect_to_database(&database_url, Duration::from_secs(30)).await;
+
+        assert2::check!(result.is_err());
+        assert2::check!(started.elapsed() < Duration::from_secs(1));
+    }
 }
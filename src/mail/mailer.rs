@@ -0,0 +1,48 @@
+use thiserror::Error;
+
+#[derive(Debug)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub text_body: String,
+    pub html_body: String,
+}
+
+#[derive(Debug, Error)]
+pub enum SendMailError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("`{0}` is not a valid email address")]
+    InvalidRecipient(String),
+    #[error("Sending mail is not configured on this instance")]
+    Disabled,
+}
+
+pub trait Mailer: std::fmt::Debug + Clone + Send + Sync + 'static {
+    fn send(&self, email: Email) -> impl Future<Output = Result<(), SendMailError>> + Send;
+
+    /// Whether emails are sent at all, when not every email is rejected with
+    /// [`SendMailError::Disabled`]
+    fn is_enabled(&self) -> bool {
+        true
+    }
+}
+
+/// Allows mail to be optional, an unconfigured mailer rejects every email with
+/// [`SendMailError::Disabled`]
+impl<M: Mailer> Mailer for Option<M> {
+    async fn send(&self, email: Email) -> Result<(), SendMailError> {
+        match self {
+            Some(mailer) => mailer.send(email).await,
+            None => Err(SendMailError::Disabled),
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.as_ref().is_some_and(Mailer::is_enabled)
+    }
+}
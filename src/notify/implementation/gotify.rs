@@ -0,0 +1,75 @@
+use std::{sync::Arc, time::Duration};
+
+use eyre::Context;
+use reqwest::{Client, Url};
+use secrecy::{ExposeSecret, SecretBox};
+use serde::Serialize;
+
+use crate::notify::notifier::{Notification, Notifier, NotifyError, Priority};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Publishes notifications as messages of a [Gotify](https://gotify.net) application
+#[derive(Debug, Clone)]
+pub struct Gotify {
+    client: Client,
+    endpoint: Url,
+    token: Arc<SecretBox<str>>,
+}
+
+#[derive(Debug, Serialize)]
+struct MessageRequest {
+    title: String,
+    message: String,
+    priority: u8,
+}
+
+impl Gotify {
+    /// Creates a notifier for a server like `https://gotify.example.com`, authenticated with the
+    /// token of an application
+    pub fn new(base_url: &str, token: SecretBox<str>) -> eyre::Result<Self> {
+        let endpoint = format!("{}/message", base_url.trim_end_matches('/'))
+            .parse()
+            .wrap_err("Failed to parse Gotify url")?;
+
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .wrap_err("Failed to build HTTP client")?;
+
+        Ok(Self {
+            client,
+            endpoint,
+            token: Arc::new(token),
+        })
+    }
+}
+
+/// Gotify uses priorities from 0 to 10, clients only make a sound from 4 and alert from 8
+fn priority(priority: Priority) -> u8 {
+    match priority {
+        Priority::Low => 2,
+        Priority::Default => 5,
+        Priority::High => 8,
+    }
+}
+
+impl Notifier for Gotify {
+    async fn notify(&self, notification: Notification) -> Result<(), NotifyError> {
+        self.client
+            .post(self.endpoint.clone())
+            .header("X-Gotify-Key", self.token.expose_secret())
+            .json(&MessageRequest {
+                title: notification.title,
+                message: notification.message,
+                priority: priority(notification.priority),
+            })
+            .send()
+            .await
+            .wrap_err("Failed to reach Gotify server")?
+            .error_for_status()
+            .wrap_err("Gotify server returned an error")?;
+
+        Ok(())
+    }
+}
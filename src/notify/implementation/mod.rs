@@ -0,0 +1,2 @@
+pub mod gotify;
+pub mod ntfy;
@@ -0,0 +1,68 @@
+use std::{sync::Arc, time::Duration};
+
+use eyre::Context;
+use reqwest::{Client, Url};
+use secrecy::{ExposeSecret, SecretBox};
+
+use crate::notify::notifier::{Notification, Notifier, NotifyError, Priority};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Publishes notifications to a topic on a [ntfy](https://ntfy.sh) server
+#[derive(Debug, Clone)]
+pub struct Ntfy {
+    client: Client,
+    topic_url: Url,
+    token: Option<Arc<SecretBox<str>>>,
+}
+
+impl Ntfy {
+    /// Creates a notifier for a topic url like `https://ntfy.sh/my-recipes`
+    pub fn new(topic_url: &str, token: Option<SecretBox<str>>) -> eyre::Result<Self> {
+        let topic_url = topic_url.parse().wrap_err("Failed to parse ntfy url")?;
+
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .wrap_err("Failed to build HTTP client")?;
+
+        Ok(Self {
+            client,
+            topic_url,
+            token: token.map(Arc::new),
+        })
+    }
+}
+
+/// ntfy uses priorities from 1 (min) to 5 (max), with 3 as the default
+fn priority(priority: Priority) -> u8 {
+    match priority {
+        Priority::Low => 2,
+        Priority::Default => 3,
+        Priority::High => 4,
+    }
+}
+
+impl Notifier for Ntfy {
+    async fn notify(&self, notification: Notification) -> Result<(), NotifyError> {
+        let mut request = self
+            .client
+            .post(self.topic_url.clone())
+            .header("Title", notification.title)
+            .header("Priority", priority(notification.priority).to_string())
+            .body(notification.message);
+
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token.expose_secret());
+        }
+
+        request
+            .send()
+            .await
+            .wrap_err("Failed to reach ntfy server")?
+            .error_for_status()
+            .wrap_err("ntfy server returned an error")?;
+
+        Ok(())
+    }
+}
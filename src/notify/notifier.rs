@@ -0,0 +1,46 @@
+use thiserror::Error;
+
+#[derive(Debug)]
+pub struct Notification {
+    pub title: String,
+    pub message: String,
+    pub priority: Priority,
+}
+
+/// How urgent a notification is, mapped onto the priority scale of each push service
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    Low,
+    Default,
+    High,
+}
+
+#[derive(Debug, Error)]
+pub enum NotifyError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("Push notifications are not configured on this instance")]
+    Disabled,
+}
+
+pub trait Notifier: std::fmt::Debug + Clone + Send + Sync + 'static {
+    fn notify(
+        &self,
+        notification: Notification,
+    ) -> impl Future<Output = Result<(), NotifyError>> + Send;
+}
+
+/// Allows notifications to be optional, an unconfigured notifier rejects every notification with
+/// [`NotifyError::Disabled`]
+impl<N: Notifier> Notifier for Option<N> {
+    async fn notify(&self, notification: Notification) -> Result<(), NotifyError> {
+        match self {
+            Some(notifier) => notifier.notify(notification).await,
+            None => Err(NotifyError::Disabled),
+        }
+    }
+}
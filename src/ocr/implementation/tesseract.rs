@@ -0,0 +1,121 @@
+use std::{path::PathBuf, process::Stdio, time::Duration};
+
+use eyre::{Context, eyre};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    process::Command,
+};
+
+use crate::ocr::recognizer::{RecognizeTextError, TextRecognizer};
+
+/// Longest tesseract may take to read an image, large photos take a few seconds
+const RECOGNIZE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Recognizes text by running the `tesseract` command line tool
+#[derive(Debug, Clone)]
+pub struct Tesseract {
+    binary: PathBuf,
+    timeout: Duration,
+}
+
+impl Tesseract {
+    pub fn new(binary: PathBuf) -> Self {
+        Self {
+            binary,
+            timeout: RECOGNIZE_TIMEOUT,
+        }
+    }
+}
+
+impl TextRecognizer for Tesseract {
+    async fn recognize(&self, image: Vec<u8>) -> Result<String, RecognizeTextError> {
+        let mut child = Command::new(&self.binary)
+            .args(["stdin", "stdout"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .wrap_err("Failed to start tesseract")?;
+
+        let mut stdin = child.stdin.take().expect("Stdin should be piped");
+
+        // Writing on a separate task avoids deadlocking when tesseract fills its output pipes
+        // before it has read the whole image
+        let writer = tokio::spawn(async move {
+            stdin.write_all(&image).await?;
+            stdin.shutdown().await
+        });
+
+        let mut stdout = child.stdout.take().expect("Stdout should be piped");
+        let mut stderr = child.stderr.take().expect("Stderr should be piped");
+
+        let mut output = vec![];
+        let mut errors = vec![];
+        let finished = tokio::time::timeout(self.timeout, async {
+            tokio::try_join!(
+                child.wait(),
+                stdout.read_to_end(&mut output),
+                stderr.read_to_end(&mut errors),
+            )
+        })
+        .await;
+
+        let status = match finished {
+            Ok(result) => result.wrap_err("Failed to wait for tesseract")?.0,
+            Err(_) => {
+                child.kill().await.wrap_err("Failed to kill tesseract")?;
+                writer.abort();
+
+                return Err(RecognizeTextError::Unknown(eyre!(
+                    "Tesseract did not finish within {:?}",
+                    self.timeout
+                )));
+            }
+        };
+
+        let written = writer.await.wrap_err("Failed to join image writer")?;
+
+        if !status.success() {
+            let stderr = String::from_utf8_lossy(&errors);
+            return Err(RecognizeTextError::Unknown(eyre!(
+                "Tesseract exited with {}: {}",
+                status,
+                stderr.trim()
+            )));
+        }
+
+        written.wrap_err("Failed to pass the image to tesseract")?;
+
+        Ok(String::from_utf8(output).wrap_err("Tesseract returned invalid UTF-8")?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::{os::unix::fs::PermissionsExt, time::Instant};
+
+    use assert2::{check, let_assert};
+
+    #[tokio::test]
+    async fn it_kills_tesseract_when_it_takes_too_long() {
+        let binary = std::env::temp_dir().join(format!("slow-tesseract-{}", std::process::id()));
+        std::fs::write(&binary, "#!/bin/sh\nsleep 30\n").expect("Failed to write script");
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755))
+            .expect("Failed to make script executable");
+
+        let tesseract = Tesseract {
+            binary: binary.clone(),
+            timeout: Duration::from_millis(200),
+        };
+
+        let started = Instant::now();
+        let result = tesseract.recognize(vec![0; 16]).await;
+        let _ = std::fs::remove_file(&binary);
+
+        let_assert!(Err(RecognizeTextError::Unknown(_)) = result);
+        check!(started.elapsed() < Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,32 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum RecognizeTextError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("Text recognition is not configured on this instance")]
+    Disabled,
+}
+
+pub trait TextRecognizer: std::fmt::Debug + Clone + Send + Sync + 'static {
+    /// Extracts the text from an encoded image, like a PNG or JPEG
+    fn recognize(
+        &self,
+        image: Vec<u8>,
+    ) -> impl Future<Output = Result<String, RecognizeTextError>> + Send;
+}
+
+/// Allows text recognition to be optional, an unconfigured recognizer fails every image with
+/// [`RecognizeTextError::Disabled`]
+impl<R: TextRecognizer> TextRecognizer for Option<R> {
+    async fn recognize(&self, image: Vec<u8>) -> Result<String, RecognizeTextError> {
+        match self {
+            Some(recognizer) => recognizer.recognize(image).await,
+            None => Err(RecognizeTextError::Disabled),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use chrono::NaiveDate;
+use thiserror::Error;
+
+/// How often a route was requested, or a term searched for
+#[derive(Debug)]
+pub struct UsageCountEntity {
+    pub key: String,
+    pub count: i64,
+}
+
+#[derive(Debug)]
+pub struct UsageEntity {
+    pub routes: Vec<UsageCountEntity>,
+    pub search_terms: Vec<UsageCountEntity>,
+}
+
+#[derive(Debug, Error)]
+pub enum RecordUsageError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum GetUsageError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+pub trait AnalyticsRepository: std::fmt::Debug + Clone + Send + Sync + 'static {
+    /// Adds the given `(route, requests)` and `(term, searches)` pairs to today's counts
+    fn record_usage(
+        &self,
+        routes: Vec<(String, i64)>,
+        search_terms: Vec<(String, i64)>,
+    ) -> impl Future<Output = Result<(), RecordUsageError>> + Send;
+
+    /// Totals since the given day, most used first. Search terms searched for less than
+    /// `min_searches` times in total are left out.
+    fn get_usage(
+        &self,
+        since: NaiveDate,
+        min_searches: i64,
+        limit: i64,
+    ) -> impl Future<Output = Result<UsageEntity, GetUsageError>> + Send;
+}
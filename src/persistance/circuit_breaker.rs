@@ -0,0 +1,499 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use chrono::NaiveDate;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::persistance::{
+    analytics::{AnalyticsRepository, GetUsageError, RecordUsageError, UsageEntity},
+    cooking_log::{
+        CookingHistoryError, CookingLogEntity, CookingLogRepository, LogCookingError,
+        NewCookingLogEntity,
+    },
+    deletion::{DeletionEntity, DeletionRepository, ListDeletionsError, RecipeDeletionEntity},
+    federation::{
+        FederatedRecipesError, FederationRepository, ListInstancesError, MirrorRecipesError,
+        MirrorSummaryEntity, RecipeOriginEntity, RecipeOriginError, RegisterInstanceError,
+        RemoteInstanceEntity, RemoteRecipeEntity, RemoveInstanceError, SetFederationOptOutError,
+    },
+    ingredient::{
+        DeleteDensityError, IngredientDensityEntity, IngredientRecipeEntity,
+        IngredientRecipesError, IngredientRepository, IngredientUsageEntity, ListDensitiesError,
+        ListIngredientsError, RenameIngredientError, SetDensityError,
+    },
+    outbox::{
+        EventsSinceError, LatestEventIdError, MarkPublishedError, OutboxEventEntity,
+        OutboxRepository, UnpublishedEventsError,
+    },
+    recipe::{
+        AutocompleteError, CreateRecipeError, DeleteRecipeError, ForkRecipeError, GetRecipeError,
+        GetRecipesError, ImportRecipesError, IngredientMatchEntity, ListForksError,
+        ListRecipeError, MergeRecipesError, MutableRecipeEntity, NameKind, PopularRecipeEntity,
+        PopularRecipesError, RandomRecipeArguments, RandomRecipeError, RecipeEntity,
+        RecipeForkEntity, RecipeMetadataEntity, RecipeNameEntity, RecipeRepository,
+        RecordViewsError, ResolvePublicIdError, SearchByIngredientsError, SearchRecipeError,
+        SearchRecipesArguments, SetArchivedError, SimilarNamesError, UpdateMetadataError,
+        UpdateRecipeError,
+    },
+    retention::{
+        CountExpiredError, ExpiredCountsEntity, PurgeExpiredError, RetentionCutoffsEntity,
+        RetentionRepository,
+    },
+    saved_search::{
+        DeleteSavedSearchError, GetSavedSearchError, ListSavedSearchesError, SaveSearchError,
+        SavedCriteriaEntity, SavedSearchEntity, SavedSearchRepository,
+    },
+    share_link::{
+        CreateShareLinkError, ListShareLinksError, ResolveShareLinkError, RevokeShareLinkError,
+        ShareLinkEntity, ShareLinkRepository,
+    },
+    snapshot::{
+        CreateSnapshotError, ListSnapshotsError, SnapshotEntity, SnapshotRecipeEntity,
+        SnapshotRecipesError, SnapshotRepository,
+    },
+    tag::{ListTagsError, RecipeTagsError, SetRecipeTagsError, TagRepository, TagUsageEntity},
+};
+
+/// Returned as the unknown error of every repository call made while the circuit is open
+#[derive(Debug, Error)]
+#[error("The database is unavailable, retry in {retry_after:?}")]
+pub struct CircuitOpen {
+    pub retry_after: Duration,
+}
+
+/// Keeps track of failing repository calls. After `threshold` calls in a row failed to reach the
+/// database the circuit opens, and calls fail right away for `cooldown` instead of waiting
+/// on a database which is down. Once the cooldown is over calls go through again, the first
+/// success closes the circuit while the first failure opens it for another cooldown. Clones share
+/// their state.
+#[derive(Debug, Clone)]
+pub struct Breaker {
+    threshold: u32,
+    cooldown: Duration,
+    state: Arc<Mutex<BreakerState>>,
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl Breaker {
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            state: Arc::default(),
+        }
+    }
+
+    /// Time left before calls go through again, `None` while the circuit is closed
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.state()
+            .open_until
+            .map(|open_until| open_until.saturating_duration_since(Instant::now()))
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    fn record_success(&self) {
+        *self.state() = BreakerState::default();
+    }
+
+    fn record_failure(&self) {
+        let mut state = self.state();
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+
+        if state.consecutive_failures >= self.threshold {
+            state.open_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, BreakerState> {
+        self.state.lock().expect("Breaker state lock poisoned")
+    }
+}
+
+/// Repository error which can tell whether the database couldn't be reached, as opposed to the
+/// database answering that something doesn't exist or rejecting a query
+trait RepositoryError: From<eyre::Report> {
+    fn is_connection_failure(&self) -> bool;
+}
+
+macro_rules! repository_errors {
+    ($($error:ident),*) => {
+        $(
+            impl RepositoryError for $error {
+                fn is_connection_failure(&self) -> bool {
+                    matches!(self, Self::Unknown(report) if is_connection_failure(report))
+                }
+            }
+        )*
+    };
+}
+
+/// Whether the error was caused by failing to reach the database. Queries the database rejected,
+/// like a broken constraint, can be caused by a request on purpose and don't count.
+fn is_connection_failure(report: &eyre::Report) -> bool {
+    report
+        .chain()
+        .filter_map(|error| error.downcast_ref::<sqlx::Error>())
+        .any(|error| {
+            matches!(
+                error,
+                sqlx::Error::Io(_)
+                    | sqlx::Error::Tls(_)
+                    | sqlx::Error::PoolTimedOut
+                    | sqlx::Error::PoolClosed
+            )
+        })
+}
+
+repository_errors!(
+    ListRecipeError,
+    GetRecipeError,
+    ResolvePublicIdError,
+    GetRecipesError,
+    CreateRecipeError,
+    ImportRecipesError,
+    UpdateRecipeError,
+    UpdateMetadataError,
+    DeleteRecipeError,
+    ForkRecipeError,
+    ListForksError,
+    MergeRecipesError,
+    SearchRecipeError,
+    SearchByIngredientsError,
+    SimilarNamesError,
+    AutocompleteError,
+    SetArchivedError,
+    RandomRecipeError,
+    RecordViewsError,
+    PopularRecipesError,
+    LogCookingError,
+    CookingHistoryError,
+    CreateShareLinkError,
+    ListShareLinksError,
+    RevokeShareLinkError,
+    ResolveShareLinkError,
+    UnpublishedEventsError,
+    EventsSinceError,
+    LatestEventIdError,
+    MarkPublishedError,
+    RegisterInstanceError,
+    ListInstancesError,
+    RemoveInstanceError,
+    FederatedRecipesError,
+    MirrorRecipesError,
+    RecipeOriginError,
+    SetFederationOptOutError,
+    ListIngredientsError,
+    IngredientRecipesError,
+    RenameIngredientError,
+    ListDensitiesError,
+    SetDensityError,
+    DeleteDensityError,
+    SaveSearchError,
+    ListSavedSearchesError,
+    GetSavedSearchError,
+    DeleteSavedSearchError,
+    ListTagsError,
+    RecipeTagsError,
+    SetRecipeTagsError,
+    CreateSnapshotError,
+    ListSnapshotsError,
+    SnapshotRecipesError,
+    ListDeletionsError,
+    CountExpiredError,
+    PurgeExpiredError,
+    RecordUsageError,
+    GetUsageError
+);
+
+/// Repository which guards every call to another repository with a [`Breaker`]
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker<R> {
+    repository: R,
+    breaker: Breaker,
+}
+
+impl<R> CircuitBreaker<R> {
+    pub fn new(repository: R, breaker: Breaker) -> Self {
+        Self {
+            repository,
+            breaker,
+        }
+    }
+
+    async fn guard<T, E: RepositoryError>(
+        &self,
+        call: impl Future<Output = Result<T, E>>,
+    ) -> Result<T, E> {
+        if let Some(retry_after) = self.breaker.retry_after() {
+            return Err(eyre::Report::new(CircuitOpen { retry_after }).into());
+        }
+
+        let result = call.await;
+
+        match &result {
+            Err(error) if error.is_connection_failure() => self.breaker.record_failure(),
+            _ => self.breaker.record_success(),
+        }
+
+        result
+    }
+}
+
+/// Implements a repository trait for [`CircuitBreaker`] by guarding the calls to the wrapped
+/// repository
+macro_rules! guard_repository {
+    ($trait:ident { $(fn $method:ident($($arg:ident: $ty:ty),*) -> $ret:ty;)* }) => {
+        impl<R: $trait> $trait for CircuitBreaker<R> {
+            $(
+                async fn $method(&self, $($arg: $ty),*) -> $ret {
+                    self.guard(self.repository.$method($($arg),*)).await
+                }
+            )*
+        }
+    };
+}
+
+guard_repository!(RecipeRepository {
+    fn list_recipes(include_ingredients: bool, archived: bool)
+        -> Result<Vec<RecipeEntity>, ListRecipeError>;
+    fn get_recipe(recipe_id: i32) -> Result<RecipeEntity, GetRecipeError>;
+    fn resolve_public_id(public_id: Uuid) -> Result<i32, ResolvePublicIdError>;
+    fn get_recipes(recipe_ids: Vec<i32>, include_ingredients: bool)
+        -> Result<Vec<RecipeEntity>, GetRecipesError>;
+    fn create_recipe(entity: MutableRecipeEntity) -> Result<RecipeEntity, CreateRecipeError>;
+    fn import_recipes(entities: Vec<MutableRecipeEntity>) -> Result<Vec<i32>, ImportRecipesError>;
+    fn update_recipe(recipe_id: i32, entity: MutableRecipeEntity)
+        -> Result<RecipeEntity, UpdateRecipeError>;
+    fn update_metadata(changes: Vec<RecipeMetadataEntity>)
+        -> Result<Vec<i32>, UpdateMetadataError>;
+    fn delete_recipe(recipe_id: i32, deletion: DeletionEntity)
+        -> Result<RecipeEntity, DeleteRecipeError>;
+    fn fork_recipe(recipe_id: i32) -> Result<RecipeEntity, ForkRecipeError>;
+    fn list_forks(recipe_id: i32) -> Result<Vec<RecipeForkEntity>, ListForksError>;
+    fn merge_recipes(target_id: i32, source_id: i32, entity: MutableRecipeEntity)
+        -> Result<RecipeEntity, MergeRecipesError>;
+    fn search_recipes(args: SearchRecipesArguments)
+        -> Result<Vec<RecipeEntity>, SearchRecipeError>;
+    fn similar_names(term: String, kind: NameKind, archived: bool, limit: i64)
+        -> Result<Vec<String>, SimilarNamesError>;
+    fn autocomplete(prefix: String, limit: i64)
+        -> Result<Vec<RecipeNameEntity>, AutocompleteError>;
+    fn set_archived(recipe_id: i32, archived: bool) -> Result<(), SetArchivedError>;
+    fn search_by_ingredients(ingredient_names: Vec<String>, limit: i64)
+        -> Result<Vec<IngredientMatchEntity>, SearchByIngredientsError>;
+    fn random_recipe(args: RandomRecipeArguments) -> Result<RecipeEntity, RandomRecipeError>;
+    fn record_views(views: Vec<(i32, i64)>) -> Result<(), RecordViewsError>;
+    fn popular_recipes(window_days: i32, limit: i64)
+        -> Result<Vec<PopularRecipeEntity>, PopularRecipesError>;
+});
+
+guard_repository!(CookingLogRepository {
+    fn log_cooking(recipe_id: i32, entity: NewCookingLogEntity)
+        -> Result<CookingLogEntity, LogCookingError>;
+    fn cooking_history(recipe_id: i32) -> Result<Vec<CookingLogEntity>, CookingHistoryError>;
+});
+
+guard_repository!(ShareLinkRepository {
+    fn create_share_link(recipe_id: i32, token: String)
+        -> Result<ShareLinkEntity, CreateShareLinkError>;
+    fn list_share_links(recipe_id: i32) -> Result<Vec<ShareLinkEntity>, ListShareLinksError>;
+    fn revoke_share_link(recipe_id: i32, share_link_id: i32) -> Result<(), RevokeShareLinkError>;
+    fn resolve_share_link(token: &str) -> Result<i32, ResolveShareLinkError>;
+});
+
+guard_repository!(FederationRepository {
+    fn register_instance(url: String, token: Option<Vec<u8>>)
+        -> Result<RemoteInstanceEntity, RegisterInstanceError>;
+    fn list_instances() -> Result<Vec<RemoteInstanceEntity>, ListInstancesError>;
+    fn remove_instance(remote_instance_id: i32) -> Result<(), RemoveInstanceError>;
+    fn federated_recipes() -> Result<Vec<RecipeEntity>, FederatedRecipesError>;
+    fn mirror_recipes(remote_instance_id: i32, recipes: Vec<RemoteRecipeEntity>)
+        -> Result<MirrorSummaryEntity, MirrorRecipesError>;
+    fn recipe_origin(recipe_id: i32) -> Result<Option<RecipeOriginEntity>, RecipeOriginError>;
+    fn set_federation_opt_out(recipe_id: i32, opt_out: bool)
+        -> Result<(), SetFederationOptOutError>;
+});
+
+guard_repository!(OutboxRepository {
+    fn unpublished_events(limit: i64) -> Result<Vec<OutboxEventEntity>, UnpublishedEventsError>;
+    fn events_since(outbox_id: i64, limit: i64)
+        -> Result<Vec<OutboxEventEntity>, EventsSinceError>;
+    fn latest_event_id(recipe_id: i32) -> Result<Option<i64>, LatestEventIdError>;
+    fn mark_published(outbox_ids: Vec<i64>) -> Result<(), MarkPublishedError>;
+});
+
+guard_repository!(IngredientRepository {
+    fn list_ingredients() -> Result<Vec<IngredientUsageEntity>, ListIngredientsError>;
+    fn ingredient_recipes(name: String)
+        -> Result<Vec<IngredientRecipeEntity>, IngredientRecipesError>;
+    fn rename_ingredient(from: String, to: String) -> Result<u64, RenameIngredientError>;
+    fn list_densities() -> Result<Vec<IngredientDensityEntity>, ListDensitiesError>;
+    fn set_density(name: String, grams_per_milliliter: f32)
+        -> Result<IngredientDensityEntity, SetDensityError>;
+    fn delete_density(name: String) -> Result<(), DeleteDensityError>;
+});
+
+guard_repository!(SavedSearchRepository {
+    fn save_search(name: String, criteria: SavedCriteriaEntity)
+        -> Result<SavedSearchEntity, SaveSearchError>;
+    fn list_saved_searches() -> Result<Vec<SavedSearchEntity>, ListSavedSearchesError>;
+    fn get_saved_search(saved_search_id: i32) -> Result<SavedSearchEntity, GetSavedSearchError>;
+    fn delete_saved_search(saved_search_id: i32) -> Result<(), DeleteSavedSearchError>;
+});
+
+guard_repository!(TagRepository {
+    fn list_tags() -> Result<Vec<TagUsageEntity>, ListTagsError>;
+    fn recipe_tags(recipe_id: i32) -> Result<Vec<String>, RecipeTagsError>;
+    fn set_recipe_tags(recipe_id: i32, tags: Vec<String>)
+        -> Result<Vec<String>, SetRecipeTagsError>;
+});
+
+guard_repository!(SnapshotRepository {
+    fn create_snapshot() -> Result<SnapshotEntity, CreateSnapshotError>;
+    fn list_snapshots() -> Result<Vec<SnapshotEntity>, ListSnapshotsError>;
+    fn snapshot_recipes(snapshot_id: i32)
+        -> Result<Vec<SnapshotRecipeEntity>, SnapshotRecipesError>;
+});
+
+guard_repository!(DeletionRepository {
+    fn list_deletions(recipe_id: Option<i32>, limit: i64)
+        -> Result<Vec<RecipeDeletionEntity>, ListDeletionsError>;
+});
+
+guard_repository!(RetentionRepository {
+    fn count_expired(cutoffs: RetentionCutoffsEntity)
+        -> Result<ExpiredCountsEntity, CountExpiredError>;
+    fn purge_expired(cutoffs: RetentionCutoffsEntity)
+        -> Result<ExpiredCountsEntity, PurgeExpiredError>;
+});
+
+guard_repository!(AnalyticsRepository {
+    fn record_usage(routes: Vec<(String, i64)>, search_terms: Vec<(String, i64)>)
+        -> Result<(), RecordUsageError>;
+    fn get_usage(since: NaiveDate, min_searches: i64, limit: i64)
+        -> Result<UsageEntity, GetUsageError>;
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+
+    use crate::test_utils::{MockRepository, recipe_entity};
+
+    fn circuit_breaker(cooldown: Duration) -> (MockRepository, CircuitBreaker<MockRepository>) {
+        let repository = MockRepository::new();
+        let circuit_breaker = CircuitBreaker::new(repository.clone(), Breaker::new(2, cooldown));
+        (repository, circuit_breaker)
+    }
+
+    fn unknown() -> DeleteRecipeError {
+        DeleteRecipeError::Unknown(
+            eyre::Report::new(sqlx::Error::PoolTimedOut).wrap_err("Failed to delete recipe"),
+        )
+    }
+
+    #[tokio::test]
+    async fn it_fails_fast_once_the_threshold_is_reached() {
+        let (repository, circuit_breaker) = circuit_breaker(Duration::from_secs(60));
+        repository
+            .on_delete_recipe(Err(unknown()))
+            .on_delete_recipe(Err(unknown()));
+
+        let_assert!(
+            Err(DeleteRecipeError::Unknown(_)) = circuit_breaker
+                .delete_recipe(1, DeletionEntity::default())
+                .await
+        );
+        check!(circuit_breaker.breaker.retry_after().is_none());
+        let_assert!(
+            Err(DeleteRecipeError::Unknown(_)) = circuit_breaker
+                .delete_recipe(1, DeletionEntity::default())
+                .await
+        );
+        check!(circuit_breaker.breaker.retry_after().is_some());
+
+        let_assert!(
+            Err(DeleteRecipeError::Unknown(report)) = circuit_breaker
+                .delete_recipe(1, DeletionEntity::default())
+                .await
+        );
+        check!(report.downcast_ref::<CircuitOpen>().is_some());
+        check!(repository.calls_to("delete_recipe").len() == 2);
+    }
+
+    #[tokio::test]
+    async fn it_does_not_count_missing_recipes_as_failures() {
+        let (repository, circuit_breaker) = circuit_breaker(Duration::from_secs(60));
+        repository
+            .on_delete_recipe(Err(unknown()))
+            .on_delete_recipe(Err(DeleteRecipeError::NotFound))
+            .on_delete_recipe(Err(unknown()));
+
+        for _ in 0..3 {
+            let _ = circuit_breaker
+                .delete_recipe(1, DeletionEntity::default())
+                .await;
+        }
+
+        check!(circuit_breaker.breaker.retry_after().is_none());
+    }
+
+    #[tokio::test]
+    async fn it_does_not_count_rejected_queries_as_failures() {
+        let (repository, circuit_breaker) = circuit_breaker(Duration::from_secs(60));
+        for _ in 0..3 {
+            repository.on_delete_recipe(Err(DeleteRecipeError::Unknown(
+                eyre::Report::new(sqlx::Error::RowNotFound).wrap_err("Failed to delete recipe"),
+            )));
+        }
+
+        for _ in 0..3 {
+            let _ = circuit_breaker
+                .delete_recipe(1, DeletionEntity::default())
+                .await;
+        }
+
+        check!(circuit_breaker.breaker.retry_after().is_none());
+    }
+
+    #[tokio::test]
+    async fn it_closes_after_a_successful_call_past_the_cooldown() {
+        let (repository, circuit_breaker) = circuit_breaker(Duration::from_millis(50));
+        repository
+            .on_delete_recipe(Err(unknown()))
+            .on_delete_recipe(Err(unknown()))
+            .on_delete_recipe(Ok(recipe_entity(1, "Soup")))
+            .on_delete_recipe(Err(unknown()));
+
+        let _ = circuit_breaker
+            .delete_recipe(1, DeletionEntity::default())
+            .await;
+        let _ = circuit_breaker
+            .delete_recipe(1, DeletionEntity::default())
+            .await;
+        check!(circuit_breaker.breaker.retry_after().is_some());
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        let_assert!(
+            Ok(_) = circuit_breaker
+                .delete_recipe(1, DeletionEntity::default())
+                .await
+        );
+
+        // A single failure no longer opens the circuit
+        let _ = circuit_breaker
+            .delete_recipe(1, DeletionEntity::default())
+            .await;
+        check!(circuit_breaker.breaker.retry_after().is_none());
+    }
+}
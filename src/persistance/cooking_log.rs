@@ -0,0 +1,54 @@
+use chrono::NaiveDate;
+use thiserror::Error;
+
+#[derive(Debug)]
+pub struct CookingLogEntity {
+    pub cooking_log_id: i32,
+    pub recipe_id: i32,
+    pub cooked_on: NaiveDate,
+    pub note: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct NewCookingLogEntity {
+    pub cooked_on: NaiveDate,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum LogCookingError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+#[derive(Debug, Error)]
+pub enum CookingHistoryError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+pub trait CookingLogRepository: std::fmt::Debug + Clone + Send + Sync + 'static {
+    fn log_cooking(
+        &self,
+        recipe_id: i32,
+        entity: NewCookingLogEntity,
+    ) -> impl Future<Output = Result<CookingLogEntity, LogCookingError>> + Send;
+
+    /// Lists every time the recipe was cooked, most recent first.
+    fn cooking_history(
+        &self,
+        recipe_id: i32,
+    ) -> impl Future<Output = Result<Vec<CookingLogEntity>, CookingHistoryError>> + Send;
+}
@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+/// Who deleted a recipe and why, recorded along with the deletion
+#[derive(Debug, Clone, Default)]
+pub struct DeletionEntity {
+    pub actor: Option<String>,
+    pub ip_address: Option<String>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct RecipeDeletionEntity {
+    pub deletion_id: i32,
+    pub recipe_id: i32,
+    pub recipe_name: String,
+    pub deleted_at: DateTime<Utc>,
+    pub actor: Option<String>,
+    pub ip_address: Option<String>,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum ListDeletionsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+pub trait DeletionRepository: std::fmt::Debug + Clone + Send + Sync + 'static {
+    /// Lists recorded deletions, most recent first. Only deletions of `recipe_id` are listed when
+    /// given.
+    fn list_deletions(
+        &self,
+        recipe_id: Option<i32>,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<RecipeDeletionEntity>, ListDeletionsError>> + Send;
+}
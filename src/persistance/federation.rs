@@ -0,0 +1,167 @@
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::persistance::recipe::{MutableRecipeEntity, RecipeEntity};
+
+#[derive(Debug)]
+pub struct RemoteInstanceEntity {
+    pub remote_instance_id: i32,
+    pub url: String,
+    /// Encrypted, only the federation service can read it
+    pub token: Option<Vec<u8>>,
+    pub created_at: DateTime<Utc>,
+    pub last_synced_at: Option<DateTime<Utc>>,
+}
+
+/// Recipe as published by a remote instance, identified by its public id over there
+#[derive(Debug)]
+pub struct RemoteRecipeEntity {
+    pub remote_public_id: Uuid,
+    pub recipe: MutableRecipeEntity,
+}
+
+/// What a sync did to the local copies of the recipes of a remote instance
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct MirrorSummaryEntity {
+    pub created: u64,
+    pub updated: u64,
+    /// Recipes left alone, as they were detached or deleted locally or exist here already
+    pub skipped: u64,
+    /// Local copies detached because the recipe is no longer published by the remote instance
+    pub detached: u64,
+}
+
+/// Instance a recipe was mirrored from
+#[derive(Debug)]
+pub struct RecipeOriginEntity {
+    pub remote_instance_id: i32,
+    pub url: String,
+    pub remote_public_id: Uuid,
+    pub detached: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum RegisterInstanceError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The instance is already registered")]
+    AlreadyRegistered,
+}
+
+#[derive(Debug, Error)]
+pub enum ListInstancesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum RemoveInstanceError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The instance could not be found")]
+    NotFound,
+}
+
+#[derive(Debug, Error)]
+pub enum FederatedRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum MirrorRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The instance could not be found")]
+    NotFound,
+}
+
+#[derive(Debug, Error)]
+pub enum RecipeOriginError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+#[derive(Debug, Error)]
+pub enum SetFederationOptOutError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+pub trait FederationRepository: std::fmt::Debug + Clone + Send + Sync + 'static {
+    fn register_instance(
+        &self,
+        url: String,
+        token: Option<Vec<u8>>,
+    ) -> impl Future<Output = Result<RemoteInstanceEntity, RegisterInstanceError>> + Send;
+
+    fn list_instances(
+        &self,
+    ) -> impl Future<Output = Result<Vec<RemoteInstanceEntity>, ListInstancesError>> + Send;
+
+    /// Stops mirroring an instance. Recipes mirrored so far are kept as local recipes.
+    fn remove_instance(
+        &self,
+        remote_instance_id: i32,
+    ) -> impl Future<Output = Result<(), RemoveInstanceError>> + Send;
+
+    /// Lists the recipes published to other instances with their ingredients, which are the
+    /// recipes written on this instance that didn't opt out
+    fn federated_recipes(
+        &self,
+    ) -> impl Future<Output = Result<Vec<RecipeEntity>, FederatedRecipesError>> + Send;
+
+    /// Brings the local copies of the recipes of a remote instance in line with the recipes it
+    /// publishes, in a single transaction. New recipes are created and changed ones updated,
+    /// while copies of recipes the instance stopped publishing are detached rather than deleted.
+    fn mirror_recipes(
+        &self,
+        remote_instance_id: i32,
+        recipes: Vec<RemoteRecipeEntity>,
+    ) -> impl Future<Output = Result<MirrorSummaryEntity, MirrorRecipesError>> + Send;
+
+    /// Finds the instance a recipe was mirrored from, `None` for recipes written on this instance
+    fn recipe_origin(
+        &self,
+        recipe_id: i32,
+    ) -> impl Future<Output = Result<Option<RecipeOriginEntity>, RecipeOriginError>> + Send;
+
+    fn set_federation_opt_out(
+        &self,
+        recipe_id: i32,
+        opt_out: bool,
+    ) -> impl Future<Output = Result<(), SetFederationOptOutError>> + Send;
+}
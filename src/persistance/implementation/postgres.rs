@@ -2,26 +2,104 @@ use std::time::Duration;
 
 use eyre::Context;
 use sqlx::{PgPool, PgTransaction, QueryBuilder, types::Json};
-
-use crate::persistance::recipe::{
-    CreateRecipeError, DeleteRecipeError, IngredientEntity, ListRecipeError, MealType,
-    MutableIngredientEntity, MutableRecipeEntity, RecipeEntity, RecipeRepository,
-    SearchRecipeError, SearchRecipesArguments, UpdateRecipeError,
+use uuid::Uuid;
+
+use crate::persistance::{
+    deletion::DeletionEntity,
+    outbox::RecipeEventKind,
+    recipe::{
+        AutocompleteError, CreateRecipeError, DeleteRecipeError, ForkRecipeError, GetRecipeError,
+        GetRecipesError, ImportRecipesError, IngredientEntity, IngredientMatchEntity,
+        ListForksError, ListRecipeError, MealType, MergeRecipesError, MutableIngredientEntity,
+        MutableRecipeEntity, NameKind, PairingKind, PopularRecipeEntity, PopularRecipesError,
+        QuantityType, RandomRecipeArguments, RandomRecipeError, RecipeEntity, RecipeForkEntity,
+        RecipeMetadataEntity, RecipeNameEntity, RecipeRepository, RecordViewsError,
+        ResolvePublicIdError, SearchByIngredientsError, SearchRecipeError, SearchRecipesArguments,
+        SetArchivedError, SimilarNamesError, UpdateMetadataError, UpdateRecipeError,
+    },
 };
 
+mod analytics;
+mod cooking_log;
+mod deletion;
+mod federation;
+mod ingredient;
+mod integrity;
+mod outbox;
+mod retention;
+mod saved_search;
+mod share_link;
+mod snapshot;
+mod tag;
+
 #[derive(Debug, Clone)]
-pub(crate) struct Postgres {
+pub struct Postgres {
     pool: PgPool,
 }
 
 impl Postgres {
-    pub(crate) fn new(pool: PgPool) -> Self {
+    pub fn new(pool: PgPool) -> Self {
         Self { pool }
     }
 }
 
 impl RecipeRepository for Postgres {
-    async fn list_recipes(&self) -> Result<Vec<RecipeEntity>, ListRecipeError> {
+    async fn list_recipes(
+        &self,
+        include_ingredients: bool,
+        archived: bool,
+    ) -> Result<Vec<RecipeEntity>, ListRecipeError> {
+        if !include_ingredients {
+            let data = sqlx::query!(
+                r#"
+                    SELECT
+                        r.recipe_id,
+                        r.name,
+                        description,
+                        cooking_time_secs,
+                        video_url,
+                        video_title,
+                        video_thumbnail_url,
+                        pairing,
+                        pairing_kind AS "pairing_kind: PairingKind",
+                        forked_from,
+                        public_id,
+                        meal_type AS "meal_type: MealType",
+                        (
+                            SELECT MAX(cl.cooked_on) FROM cooking_log cl WHERE cl.recipe_id = r.recipe_id
+                        ) AS last_cooked_at
+                    FROM recipe r
+                    WHERE (r.archived_at IS NOT NULL) = $1
+                "#,
+                archived
+            )
+            .fetch_all(&self.pool)
+            .await
+            .wrap_err("Failed to get recipes")?;
+
+            return Ok(data
+                .into_iter()
+                .map(|row| RecipeEntity {
+                    recipe_id: row.recipe_id,
+                    name: row.name,
+                    description: row.description,
+                    ingredients: Vec::new(),
+                    cooking_time: row
+                        .cooking_time_secs
+                        .map(|value| Duration::from_secs(value as u64)),
+                    meal_type: row.meal_type,
+                    video_url: row.video_url,
+                    video_title: row.video_title,
+                    video_thumbnail_url: row.video_thumbnail_url,
+                    pairing: row.pairing,
+                    pairing_kind: row.pairing_kind,
+                    forked_from: row.forked_from,
+                    public_id: row.public_id,
+                    last_cooked_at: row.last_cooked_at,
+                })
+                .collect());
+        }
+
         let data = sqlx::query!(
             r#"
                 WITH ingredients_json AS (
@@ -37,11 +115,156 @@ impl RecipeRepository for Postgres {
                     r.name,
                     description,
                     cooking_time_secs,
+                    video_url,
+                    video_title,
+                    video_thumbnail_url,
+                    pairing,
+                    pairing_kind AS "pairing_kind: PairingKind",
+                    forked_from,
+                    public_id,
                     ig.ingredients AS "ingredients: Json<Vec<IngredientEntity>>",
-                    meal_type AS "meal_type: MealType"
+                    meal_type AS "meal_type: MealType",
+                    (
+                        SELECT MAX(cl.cooked_on) FROM cooking_log cl WHERE cl.recipe_id = r.recipe_id
+                    ) AS last_cooked_at
                     FROM recipe r
                 LEFT JOIN ingredients_grouped ig ON ig.recipe_id = r.recipe_id
-            "#
+                WHERE (r.archived_at IS NOT NULL) = $1
+            "#,
+            archived
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to get recipes")?;
+
+        Ok(data
+            .into_iter()
+            .map(|row| RecipeEntity {
+                recipe_id: row.recipe_id,
+                name: row.name,
+                description: row.description,
+                ingredients: row
+                    .ingredients
+                    .map(|ingredient| ingredient.0)
+                    .unwrap_or_default(),
+                cooking_time: row
+                    .cooking_time_secs
+                    .map(|value| Duration::from_secs(value as u64)),
+                meal_type: row.meal_type,
+                video_url: row.video_url,
+                video_title: row.video_title,
+                video_thumbnail_url: row.video_thumbnail_url,
+                pairing: row.pairing,
+                pairing_kind: row.pairing_kind,
+                forked_from: row.forked_from,
+                public_id: row.public_id,
+                last_cooked_at: row.last_cooked_at,
+            })
+            .collect())
+    }
+
+    async fn get_recipe(&self, recipe_id: i32) -> Result<RecipeEntity, GetRecipeError> {
+        let row = sqlx::query!(
+            r#"
+                SELECT
+                    r.recipe_id,
+                    r.name,
+                    description,
+                    cooking_time_secs,
+                    video_url,
+                    video_title,
+                    video_thumbnail_url,
+                    pairing,
+                    pairing_kind AS "pairing_kind: PairingKind",
+                    forked_from,
+                    public_id,
+                    (
+                        SELECT JSON_AGG(ROW_TO_JSON(i) ORDER BY i.ingredient_order)
+                        FROM ingredient i
+                        WHERE i.recipe_id = r.recipe_id
+                    ) AS "ingredients: Json<Vec<IngredientEntity>>",
+                    meal_type AS "meal_type: MealType",
+                    (
+                        SELECT MAX(cl.cooked_on) FROM cooking_log cl WHERE cl.recipe_id = r.recipe_id
+                    ) AS last_cooked_at
+                    FROM recipe r
+                WHERE r.recipe_id = $1
+            "#,
+            recipe_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .wrap_err("Failed to get recipe")?
+        .ok_or(GetRecipeError::NotFound)?;
+
+        Ok(RecipeEntity {
+            recipe_id: row.recipe_id,
+            name: row.name,
+            description: row.description,
+            ingredients: row
+                .ingredients
+                .map(|ingredient| ingredient.0)
+                .unwrap_or_default(),
+            cooking_time: row
+                .cooking_time_secs
+                .map(|value| Duration::from_secs(value as u64)),
+            meal_type: row.meal_type,
+            video_url: row.video_url,
+            video_title: row.video_title,
+            video_thumbnail_url: row.video_thumbnail_url,
+            pairing: row.pairing,
+            pairing_kind: row.pairing_kind,
+            forked_from: row.forked_from,
+            public_id: row.public_id,
+            last_cooked_at: row.last_cooked_at,
+        })
+    }
+
+    async fn resolve_public_id(&self, public_id: Uuid) -> Result<i32, ResolvePublicIdError> {
+        sqlx::query_scalar!(
+            "SELECT recipe_id FROM recipe WHERE public_id = $1",
+            public_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .wrap_err("Failed to resolve public id")?
+        .ok_or(ResolvePublicIdError::NotFound)
+    }
+
+    async fn get_recipes(
+        &self,
+        recipe_ids: Vec<i32>,
+        include_ingredients: bool,
+    ) -> Result<Vec<RecipeEntity>, GetRecipesError> {
+        let data = sqlx::query!(
+            r#"
+                SELECT
+                    r.recipe_id,
+                    r.name,
+                    description,
+                    cooking_time_secs,
+                    video_url,
+                    video_title,
+                    video_thumbnail_url,
+                    pairing,
+                    pairing_kind AS "pairing_kind: PairingKind",
+                    forked_from,
+                    public_id,
+                    CASE WHEN $2 THEN (
+                        SELECT JSON_AGG(ROW_TO_JSON(i) ORDER BY i.ingredient_order)
+                        FROM ingredient i
+                        WHERE i.recipe_id = r.recipe_id
+                    ) END AS "ingredients: Json<Vec<IngredientEntity>>",
+                    meal_type AS "meal_type: MealType",
+                    (
+                        SELECT MAX(cl.cooked_on) FROM cooking_log cl WHERE cl.recipe_id = r.recipe_id
+                    ) AS last_cooked_at
+                    FROM recipe r
+                WHERE r.recipe_id = ANY($1)
+                ORDER BY ARRAY_POSITION($1, r.recipe_id)
+            "#,
+            &recipe_ids,
+            include_ingredients,
         )
         .fetch_all(&self.pool)
         .await
@@ -61,6 +284,14 @@ impl RecipeRepository for Postgres {
                     .cooking_time_secs
                     .map(|value| Duration::from_secs(value as u64)),
                 meal_type: row.meal_type,
+                video_url: row.video_url,
+                video_title: row.video_title,
+                video_thumbnail_url: row.video_thumbnail_url,
+                pairing: row.pairing,
+                pairing_kind: row.pairing_kind,
+                forked_from: row.forked_from,
+                public_id: row.public_id,
+                last_cooked_at: row.last_cooked_at,
             })
             .collect())
     }
@@ -81,20 +312,49 @@ impl RecipeRepository for Postgres {
                     name,
                     description,
                     cooking_time_secs,
-                    meal_type
-                ) VALUES ($1, $2, $3, $4)
-                RETURNING recipe_id, name, description, cooking_time_secs, meal_type AS "meal_type: MealType"
+                    meal_type,
+                    video_url,
+                    video_title,
+                    video_thumbnail_url,
+                    pairing,
+                    pairing_kind
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                RETURNING
+                    recipe_id,
+                    name,
+                    description,
+                    cooking_time_secs,
+                    meal_type AS "meal_type: MealType",
+                    video_url,
+                    video_title,
+                    video_thumbnail_url,
+                    pairing,
+                    pairing_kind AS "pairing_kind: PairingKind",
+                    forked_from,
+                    public_id
             "#,
             entity.name,
             entity.description,
             entity.cooking_time.map(|time| time.as_secs() as i64),
-            &entity.meal_type as &MealType
-        ).fetch_one(&mut *tx).await.wrap_err("Failed to insert recipe")?;
+            &entity.meal_type as &MealType,
+            entity.video_url,
+            entity.video_title,
+            entity.video_thumbnail_url,
+            entity.pairing,
+            entity.pairing_kind as Option<PairingKind>,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .wrap_err("Failed to insert recipe")?;
 
         let ingredients = create_ingredients(&mut tx, result.recipe_id, &entity.ingredients)
             .await
             .wrap_err("Failed to create ingredients")?;
 
+        outbox::record_event(&mut tx, RecipeEventKind::Created, result.recipe_id)
+            .await
+            .wrap_err("Failed to record event")?;
+
         tx.commit().await.wrap_err("Failed to commit transaction")?;
 
         Ok(RecipeEntity {
@@ -106,9 +366,235 @@ impl RecipeRepository for Postgres {
                 .cooking_time_secs
                 .map(|time| Duration::from_secs(time as u64)),
             meal_type: result.meal_type,
+            video_url: result.video_url,
+            video_title: result.video_title,
+            video_thumbnail_url: result.video_thumbnail_url,
+            pairing: result.pairing,
+            pairing_kind: result.pairing_kind,
+            forked_from: result.forked_from,
+            public_id: result.public_id,
+            last_cooked_at: None,
         })
     }
 
+    async fn import_recipes(
+        &self,
+        entities: Vec<MutableRecipeEntity>,
+    ) -> Result<Vec<i32>, ImportRecipesError> {
+        if entities.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut recipes = String::new();
+        let mut ingredients = String::new();
+
+        for (import_index, entity) in entities.iter().enumerate() {
+            let cooking_time = entity.cooking_time.map(|time| time.as_secs().to_string());
+
+            push_copy_row(
+                &mut recipes,
+                &[
+                    Some(&import_index.to_string()),
+                    Some(&entity.name),
+                    entity.description.as_deref(),
+                    cooking_time.as_deref(),
+                    Some(entity.meal_type.as_label()),
+                    entity.video_url.as_deref(),
+                    entity.video_title.as_deref(),
+                    entity.video_thumbnail_url.as_deref(),
+                    entity.pairing.as_deref(),
+                    entity.pairing_kind.as_ref().map(PairingKind::as_label),
+                ],
+            );
+
+            for (ingredient_order, ingredient) in entity.ingredients.iter().enumerate() {
+                push_copy_row(
+                    &mut ingredients,
+                    &[
+                        Some(&import_index.to_string()),
+                        Some(&ingredient_order.to_string()),
+                        Some(&ingredient.name),
+                        Some(&ingredient.quantity.to_string()),
+                        Some(ingredient.quantity_type.as_label()),
+                    ],
+                );
+            }
+        }
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .wrap_err("Failed to open transaction")?;
+
+        // `COPY` can't write the identity columns of the real tables, so the rows are staged in
+        // temporary tables and moved over with their ids reserved up front
+        sqlx::query(
+            r#"
+                CREATE TEMPORARY TABLE recipe_import (
+                    import_index INTEGER NOT NULL,
+                    name TEXT NOT NULL,
+                    description TEXT,
+                    cooking_time_secs BIGINT,
+                    meal_type meal_type NOT NULL,
+                    video_url TEXT,
+                    video_title TEXT,
+                    video_thumbnail_url TEXT,
+                    pairing TEXT,
+                    pairing_kind pairing_kind,
+                    recipe_id INTEGER
+                ) ON COMMIT DROP
+            "#,
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to create recipe import table")?;
+
+        sqlx::query(
+            r#"
+                CREATE TEMPORARY TABLE ingredient_import (
+                    import_index INTEGER NOT NULL,
+                    ingredient_order INTEGER NOT NULL,
+                    name TEXT NOT NULL,
+                    quantity REAL NOT NULL,
+                    quantity_type quantity_type NOT NULL
+                ) ON COMMIT DROP
+            "#,
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to create ingredient import table")?;
+
+        let mut copy = tx
+            .copy_in_raw(
+                r#"
+                    COPY recipe_import (
+                        import_index,
+                        name,
+                        description,
+                        cooking_time_secs,
+                        meal_type,
+                        video_url,
+                        video_title,
+                        video_thumbnail_url,
+                        pairing,
+                        pairing_kind
+                    ) FROM STDIN
+                "#,
+            )
+            .await
+            .wrap_err("Failed to start copying recipes")?;
+        copy.send(recipes.into_bytes())
+            .await
+            .wrap_err("Failed to copy recipes")?;
+        copy.finish().await.wrap_err("Failed to copy recipes")?;
+
+        let mut copy = tx
+            .copy_in_raw(
+                r#"
+                    COPY ingredient_import (
+                        import_index,
+                        ingredient_order,
+                        name,
+                        quantity,
+                        quantity_type
+                    ) FROM STDIN
+                "#,
+            )
+            .await
+            .wrap_err("Failed to start copying ingredients")?;
+        copy.send(ingredients.into_bytes())
+            .await
+            .wrap_err("Failed to copy ingredients")?;
+        copy.finish().await.wrap_err("Failed to copy ingredients")?;
+
+        sqlx::query(
+            r#"
+                UPDATE recipe_import ri
+                SET recipe_id = reserved.recipe_id
+                FROM (
+                    SELECT import_index, NEXTVAL(PG_GET_SERIAL_SEQUENCE('recipe', 'recipe_id')) AS recipe_id
+                    FROM (SELECT import_index FROM recipe_import ORDER BY import_index) ordered
+                ) reserved
+                WHERE reserved.import_index = ri.import_index
+            "#,
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to reserve recipe ids")?;
+
+        sqlx::query(
+            r#"
+                INSERT INTO recipe (
+                    recipe_id,
+                    name,
+                    description,
+                    cooking_time_secs,
+                    meal_type,
+                    video_url,
+                    video_title,
+                    video_thumbnail_url,
+                    pairing,
+                    pairing_kind
+                ) OVERRIDING SYSTEM VALUE
+                SELECT
+                    recipe_id,
+                    name,
+                    description,
+                    cooking_time_secs,
+                    meal_type,
+                    video_url,
+                    video_title,
+                    video_thumbnail_url,
+                    pairing,
+                    pairing_kind
+                FROM recipe_import
+            "#,
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to insert recipes")?;
+
+        sqlx::query(
+            r#"
+                INSERT INTO ingredient (recipe_id, ingredient_order, name, quantity, quantity_type)
+                SELECT ri.recipe_id, ii.ingredient_order, ii.name, ii.quantity, ii.quantity_type
+                FROM ingredient_import ii
+                JOIN recipe_import ri USING (import_index)
+            "#,
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to insert ingredients")?;
+
+        outbox::lock_outbox(&mut tx)
+            .await
+            .wrap_err("Failed to lock outbox")?;
+
+        sqlx::query(
+            r#"
+                INSERT INTO outbox (kind, recipe_id, public_id)
+                SELECT 'Created', r.recipe_id, r.public_id
+                FROM recipe_import ri
+                JOIN recipe r USING (recipe_id)
+                ORDER BY ri.import_index
+            "#,
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to record events")?;
+
+        let recipe_ids =
+            sqlx::query_scalar("SELECT recipe_id FROM recipe_import ORDER BY import_index")
+                .fetch_all(&mut *tx)
+                .await
+                .wrap_err("Failed to fetch imported recipe ids")?;
+
+        tx.commit().await.wrap_err("Failed to commit transaction")?;
+
+        Ok(recipe_ids)
+    }
+
     async fn update_recipe(
         &self,
         recipe_id: i32,
@@ -126,15 +612,40 @@ impl RecipeRepository for Postgres {
                     name = $1,
                     description = $2,
                     cooking_time_secs = $3,
-                    meal_type = $4
+                    meal_type = $4,
+                    video_url = $6,
+                    video_title = $7,
+                    video_thumbnail_url = $8,
+                    pairing = $9,
+                    pairing_kind = $10
                 WHERE recipe_id = $5
-                RETURNING recipe_id, name, description, cooking_time_secs, meal_type AS "meal_type: MealType"
+                RETURNING
+                    recipe_id,
+                    name,
+                    description,
+                    cooking_time_secs,
+                    video_url,
+                    video_title,
+                    video_thumbnail_url,
+                    pairing,
+                    pairing_kind AS "pairing_kind: PairingKind",
+                    forked_from,
+                    public_id,
+                    meal_type AS "meal_type: MealType",
+                    (
+                        SELECT MAX(cl.cooked_on) FROM cooking_log cl WHERE cl.recipe_id = recipe.recipe_id
+                    ) AS last_cooked_at
             "#,
             entity.name,
             entity.description,
             entity.cooking_time.map(|time| time.as_secs() as i64),
             &entity.meal_type as &MealType,
-            recipe_id
+            recipe_id,
+            entity.video_url,
+            entity.video_title,
+            entity.video_thumbnail_url,
+            entity.pairing,
+            entity.pairing_kind as Option<PairingKind>,
         ).fetch_one(&mut *tx).await.map_err(|error| {
             match error {
                 sqlx::Error::RowNotFound => UpdateRecipeError::NotFound,
@@ -151,6 +662,19 @@ impl RecipeRepository for Postgres {
             .await
             .wrap_err("Failed to create ingredients")?;
 
+        // Local edits to a mirrored recipe win over later changes on the remote instance
+        sqlx::query!(
+            "UPDATE mirrored_recipe SET detached = TRUE WHERE recipe_id = $1",
+            recipe_id
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to detach mirrored recipe")?;
+
+        outbox::record_event(&mut tx, RecipeEventKind::Updated, result.recipe_id)
+            .await
+            .wrap_err("Failed to record event")?;
+
         tx.commit().await.wrap_err("Failed to commit transaction")?;
 
         Ok(RecipeEntity {
@@ -162,43 +686,390 @@ impl RecipeRepository for Postgres {
                 .cooking_time_secs
                 .map(|time| Duration::from_secs(time as u64)),
             meal_type: result.meal_type,
+            video_url: result.video_url,
+            video_title: result.video_title,
+            video_thumbnail_url: result.video_thumbnail_url,
+            pairing: result.pairing,
+            pairing_kind: result.pairing_kind,
+            forked_from: result.forked_from,
+            public_id: result.public_id,
+            last_cooked_at: result.last_cooked_at,
         })
     }
 
-    async fn delete_recipe(&self, recipe_id: i32) -> Result<(), DeleteRecipeError> {
+    async fn update_metadata(
+        &self,
+        changes: Vec<RecipeMetadataEntity>,
+    ) -> Result<Vec<i32>, UpdateMetadataError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .wrap_err("Failed to open transaction")?;
+
+        let mut updated = Vec::with_capacity(changes.len());
+
+        for change in changes {
+            let result = sqlx::query!(
+                r#"
+                    UPDATE recipe SET
+                        name = COALESCE($2, name),
+                        description = COALESCE($3, description),
+                        cooking_time_secs = COALESCE($4, cooking_time_secs),
+                        meal_type = COALESCE($5, meal_type)
+                    WHERE recipe_id = $1
+                "#,
+                change.recipe_id,
+                change.name,
+                change.description,
+                change.cooking_time.map(|time| time.as_secs() as i64),
+                change.meal_type as Option<MealType>,
+            )
+            .execute(&mut *tx)
+            .await
+            .wrap_err("Failed to update recipe metadata")?;
+
+            if result.rows_affected() == 0 {
+                continue;
+            }
+
+            if let Some(tags) = &change.tags {
+                sqlx::query!(
+                    "DELETE FROM recipe_tag WHERE recipe_id = $1",
+                    change.recipe_id
+                )
+                .execute(&mut *tx)
+                .await
+                .wrap_err("Failed to delete recipe tags")?;
+
+                sqlx::query!(
+                    r#"
+                        INSERT INTO recipe_tag (recipe_id, name)
+                        SELECT $1, name FROM UNNEST($2::TEXT[]) AS name
+                        ON CONFLICT DO NOTHING
+                    "#,
+                    change.recipe_id,
+                    tags
+                )
+                .execute(&mut *tx)
+                .await
+                .wrap_err("Failed to insert recipe tags")?;
+            }
+
+            // Local edits to a mirrored recipe win over later changes on the remote instance
+            sqlx::query!(
+                "UPDATE mirrored_recipe SET detached = TRUE WHERE recipe_id = $1",
+                change.recipe_id
+            )
+            .execute(&mut *tx)
+            .await
+            .wrap_err("Failed to detach mirrored recipe")?;
+
+            outbox::record_event(&mut tx, RecipeEventKind::Updated, change.recipe_id)
+                .await
+                .wrap_err("Failed to record event")?;
+
+            updated.push(change.recipe_id);
+        }
+
+        tx.commit().await.wrap_err("Failed to commit transaction")?;
+
+        Ok(updated)
+    }
+
+    async fn delete_recipe(
+        &self,
+        recipe_id: i32,
+        deletion: DeletionEntity,
+    ) -> Result<RecipeEntity, DeleteRecipeError> {
         let mut tx = self
             .pool
             .begin()
             .await
             .wrap_err("Failed to start transaction")?;
 
-        // First delete all ingredients for this recipe
-        sqlx::query!("DELETE FROM ingredient WHERE recipe_id = $1", recipe_id)
+        // First delete all ingredients for this recipe, keeping them to return with the recipe
+        let ingredients = sqlx::query_as!(
+            IngredientEntity,
+            r#"
+                WITH deleted AS (
+                    DELETE FROM ingredient WHERE recipe_id = $1
+                    RETURNING ingredient_id, recipe_id, ingredient_order, name, quantity, quantity_type
+                )
+                SELECT
+                    ingredient_id AS "ingredient_id!",
+                    recipe_id AS "recipe_id!",
+                    ingredient_order AS "ingredient_order!",
+                    name AS "name!",
+                    quantity AS "quantity!",
+                    quantity_type AS "quantity_type!: QuantityType"
+                FROM deleted
+                ORDER BY ingredient_order
+            "#,
+            recipe_id
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .wrap_err("Failed to delete ingredients")?;
+
+        sqlx::query!("DELETE FROM recipe_view WHERE recipe_id = $1", recipe_id)
             .execute(&mut *tx)
             .await
-            .wrap_err("Failed to delete ingredients")?;
+            .wrap_err("Failed to delete recipe views")?;
 
-        // Then delete the recipe
-        let result = sqlx::query!("DELETE FROM recipe WHERE recipe_id = $1", recipe_id)
+        let last_cooked_at = sqlx::query_scalar!(
+            r#"
+                WITH deleted AS (
+                    DELETE FROM cooking_log WHERE recipe_id = $1 RETURNING cooked_on
+                )
+                SELECT MAX(cooked_on) FROM deleted
+            "#,
+            recipe_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .wrap_err("Failed to delete cooking log")?;
+
+        sqlx::query!("DELETE FROM share_link WHERE recipe_id = $1", recipe_id)
+            .execute(&mut *tx)
+            .await
+            .wrap_err("Failed to delete share links")?;
+
+        sqlx::query!("DELETE FROM recipe_tag WHERE recipe_id = $1", recipe_id)
             .execute(&mut *tx)
             .await
-            .wrap_err("Failed to delete recipe")?;
+            .wrap_err("Failed to delete recipe tags")?;
+
+        // The mirror row is kept so later syncs don't bring the deleted recipe back
+        sqlx::query!(
+            "UPDATE mirrored_recipe SET recipe_id = NULL WHERE recipe_id = $1",
+            recipe_id
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to release mirrored recipe")?;
+
+        // Keep the lineage of forks intact by moving them up a level
+        sqlx::query!(
+            r#"
+                UPDATE recipe
+                SET forked_from = (SELECT forked_from FROM recipe WHERE recipe_id = $1)
+                WHERE forked_from = $1
+            "#,
+            recipe_id
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to move forks")?;
+
+        outbox::record_event(&mut tx, RecipeEventKind::Deleted, recipe_id)
+            .await
+            .wrap_err("Failed to record event")?;
+
+        // Then delete the recipe
+        let row = sqlx::query!(
+            r#"
+                DELETE FROM recipe
+                WHERE recipe_id = $1
+                RETURNING
+                    recipe_id,
+                    name,
+                    description,
+                    cooking_time_secs,
+                    video_url,
+                    video_title,
+                    video_thumbnail_url,
+                    pairing,
+                    pairing_kind AS "pairing_kind: PairingKind",
+                    forked_from,
+                    public_id,
+                    meal_type AS "meal_type: MealType"
+            "#,
+            recipe_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .wrap_err("Failed to delete recipe")?;
 
-        if result.rows_affected() > 0 {
-            tx.commit().await.wrap_err("Failed to commit transaction")?;
-            Ok(())
-        } else {
+        let Some(row) = row else {
             tx.rollback()
                 .await
                 .wrap_err("Failed to rollback transaction")?;
-            Err(DeleteRecipeError::NotFound)
-        }
+            return Err(DeleteRecipeError::NotFound);
+        };
+
+        sqlx::query!(
+            r#"
+                INSERT INTO recipe_deletion (recipe_id, recipe_name, actor, ip_address, reason)
+                VALUES ($1, $2, $3, $4, $5)
+            "#,
+            recipe_id,
+            row.name,
+            deletion.actor,
+            deletion.ip_address,
+            deletion.reason,
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to record deletion")?;
+
+        tx.commit().await.wrap_err("Failed to commit transaction")?;
+
+        Ok(RecipeEntity {
+            recipe_id: row.recipe_id,
+            name: row.name,
+            description: row.description,
+            ingredients,
+            cooking_time: row
+                .cooking_time_secs
+                .map(|value| Duration::from_secs(value as u64)),
+            meal_type: row.meal_type,
+            video_url: row.video_url,
+            video_title: row.video_title,
+            video_thumbnail_url: row.video_thumbnail_url,
+            pairing: row.pairing,
+            pairing_kind: row.pairing_kind,
+            forked_from: row.forked_from,
+            public_id: row.public_id,
+            last_cooked_at,
+        })
     }
 
     async fn search_recipes(
         &self,
         args: SearchRecipesArguments,
     ) -> Result<Vec<RecipeEntity>, SearchRecipeError> {
+        // Every position in the sort keys gets an ORDER BY expression per key, so the keys apply
+        // in the order they were given
+        let sort = args
+            .sort
+            .iter()
+            .map(|sort| sort.as_str().to_string())
+            .collect::<Vec<_>>();
+
+        if !args.include_ingredients {
+            let data = sqlx::query!(
+                r#"
+                    SELECT
+                        r.recipe_id,
+                        r.name,
+                        description,
+                        cooking_time_secs,
+                        video_url,
+                        video_title,
+                        video_thumbnail_url,
+                        pairing,
+                        pairing_kind AS "pairing_kind: PairingKind",
+                        forked_from,
+                        public_id,
+                        meal_type AS "meal_type: MealType",
+                        lc.cooked_on AS last_cooked_at
+                    FROM recipe r
+                    LEFT JOIN LATERAL (
+                        SELECT MAX(cl.cooked_on) AS cooked_on FROM cooking_log cl WHERE cl.recipe_id = r.recipe_id
+                    ) lc ON TRUE
+                    LEFT JOIN LATERAL (
+                        -- Every keyword adds the weight of each field it matches, so the fields saying the most
+                        -- about a recipe rank it highest
+                        SELECT
+                            COALESCE(BOOL_OR(m.name OR m.ingredient OR m.tag OR m.description), FALSE) AS matched,
+                            COALESCE(SUM(
+                                CASE WHEN m.name THEN $11::REAL ELSE 0 END +
+                                CASE WHEN m.ingredient THEN $12::REAL ELSE 0 END +
+                                CASE WHEN m.tag THEN $13::REAL ELSE 0 END +
+                                CASE WHEN m.description THEN $14::REAL ELSE 0 END
+                            ), 0) AS score
+                        FROM UNNEST($10::TEXT[]) k
+                        CROSS JOIN LATERAL (
+                            SELECT
+                                IMMUTABLE_UNACCENT(r.name) ILIKE '%' || IMMUTABLE_UNACCENT(k) || '%' AS name,
+                                EXISTS (
+                                    SELECT 1 FROM ingredient i3
+                                    WHERE i3.recipe_id = r.recipe_id
+                                    AND IMMUTABLE_UNACCENT(i3.name) ILIKE '%' || IMMUTABLE_UNACCENT(k) || '%'
+                                ) AS ingredient,
+                                EXISTS (
+                                    SELECT 1 FROM recipe_tag t2
+                                    WHERE t2.recipe_id = r.recipe_id AND t2.name ILIKE '%' || k || '%'
+                                ) AS tag,
+                                COALESCE(
+                                    IMMUTABLE_UNACCENT(r.description) ILIKE '%' || IMMUTABLE_UNACCENT(k) || '%',
+                                    FALSE
+                                ) AS description
+                        ) m
+                    ) kw ON TRUE
+                    WHERE
+                        ($1::TEXT IS NULL OR
+                            IMMUTABLE_UNACCENT(r.name) ILIKE '%' || IMMUTABLE_UNACCENT($1) || '%' OR
+                            IMMUTABLE_UNACCENT(r.description) ILIKE '%' || IMMUTABLE_UNACCENT($1) || '%'
+                        ) AND
+                        ($2::TEXT IS NULL OR EXISTS (
+                            SELECT 1 FROM ingredient i2
+                            WHERE i2.recipe_id = r.recipe_id
+                            AND IMMUTABLE_UNACCENT(i2.name) ILIKE '%' || IMMUTABLE_UNACCENT($2) || '%'
+                        )) AND
+                        ($3::meal_type IS NULL OR r.meal_type = $3::meal_type) AND
+                        (r.archived_at IS NOT NULL) = $4 AND
+                        (CARDINALITY($5::TEXT[]) = 0 OR (
+                            SELECT COUNT(*) FROM recipe_tag t
+                            WHERE t.recipe_id = r.recipe_id AND t.name = ANY($5)
+                        ) = CARDINALITY($5::TEXT[])) AND
+                        ($7::TIMESTAMPTZ IS NULL OR r.created_at > $7) AND
+                        ($8::TIMESTAMPTZ IS NULL OR r.created_at < $8) AND
+                        ($9::TIMESTAMPTZ IS NULL OR r.updated_at > $9) AND
+                        (CARDINALITY($10::TEXT[]) = 0 OR kw.matched)
+                    ORDER BY
+                        CASE WHEN ($6::TEXT[])[1] = 'cooking_time' THEN r.cooking_time_secs END ASC NULLS LAST,
+                        CASE WHEN ($6::TEXT[])[1] = 'last_cooked' THEN lc.cooked_on END DESC NULLS LAST,
+                        CASE WHEN ($6::TEXT[])[2] = 'cooking_time' THEN r.cooking_time_secs END ASC NULLS LAST,
+                        CASE WHEN ($6::TEXT[])[2] = 'last_cooked' THEN lc.cooked_on END DESC NULLS LAST,
+                        kw.score DESC,
+                        -- Name matches rank above recipes only matching on their description
+                        IMMUTABLE_UNACCENT(r.name) ILIKE '%' || IMMUTABLE_UNACCENT($1) || '%' DESC NULLS LAST,
+                        r.recipe_id
+                "#,
+                args.recipe_name,
+                args.ingredient_name,
+                args.meal_type.as_ref() as Option<&MealType>,
+                args.archived,
+                &args.tags,
+                &sort,
+                args.created_after,
+                args.created_before,
+                args.updated_after,
+                &args.keywords,
+                args.weights.name,
+                args.weights.ingredient,
+                args.weights.tag,
+                args.weights.description,
+            )
+            .fetch_all(&self.pool)
+            .await
+            .wrap_err("Failed to query for recipes")?;
+
+            return Ok(data
+                .into_iter()
+                .map(|row| RecipeEntity {
+                    recipe_id: row.recipe_id,
+                    name: row.name,
+                    description: row.description,
+                    ingredients: Vec::new(),
+                    cooking_time: row
+                        .cooking_time_secs
+                        .map(|value| Duration::from_secs(value as u64)),
+                    meal_type: row.meal_type,
+                    video_url: row.video_url,
+                    video_title: row.video_title,
+                    video_thumbnail_url: row.video_thumbnail_url,
+                    pairing: row.pairing,
+                    pairing_kind: row.pairing_kind,
+                    forked_from: row.forked_from,
+                    public_id: row.public_id,
+                    last_cooked_at: row.last_cooked_at,
+                })
+                .collect());
+        }
+
         let data = sqlx::query!(
             r#"
                 WITH ingredients_json AS (
@@ -214,22 +1085,95 @@ impl RecipeRepository for Postgres {
                     r.name,
                     description,
                     cooking_time_secs,
+                    video_url,
+                    video_title,
+                    video_thumbnail_url,
+                    pairing,
+                    pairing_kind AS "pairing_kind: PairingKind",
+                    forked_from,
+                    public_id,
                     ig.ingredients AS "ingredients: Json<Vec<IngredientEntity>>",
-                    meal_type AS "meal_type: MealType"
-                    FROM recipe r
+                    meal_type AS "meal_type: MealType",
+                    lc.cooked_on AS last_cooked_at
+                FROM recipe r
                 LEFT JOIN ingredients_grouped ig ON ig.recipe_id = r.recipe_id
+                LEFT JOIN LATERAL (
+                    SELECT MAX(cl.cooked_on) AS cooked_on FROM cooking_log cl WHERE cl.recipe_id = r.recipe_id
+                ) lc ON TRUE
+                LEFT JOIN LATERAL (
+                    -- Every keyword adds the weight of each field it matches, so the fields saying the most
+                    -- about a recipe rank it highest
+                    SELECT
+                        COALESCE(BOOL_OR(m.name OR m.ingredient OR m.tag OR m.description), FALSE) AS matched,
+                        COALESCE(SUM(
+                            CASE WHEN m.name THEN $11::REAL ELSE 0 END +
+                            CASE WHEN m.ingredient THEN $12::REAL ELSE 0 END +
+                            CASE WHEN m.tag THEN $13::REAL ELSE 0 END +
+                            CASE WHEN m.description THEN $14::REAL ELSE 0 END
+                        ), 0) AS score
+                    FROM UNNEST($10::TEXT[]) k
+                    CROSS JOIN LATERAL (
+                        SELECT
+                            IMMUTABLE_UNACCENT(r.name) ILIKE '%' || IMMUTABLE_UNACCENT(k) || '%' AS name,
+                            EXISTS (
+                                SELECT 1 FROM ingredient i3
+                                WHERE i3.recipe_id = r.recipe_id
+                                AND IMMUTABLE_UNACCENT(i3.name) ILIKE '%' || IMMUTABLE_UNACCENT(k) || '%'
+                            ) AS ingredient,
+                            EXISTS (
+                                SELECT 1 FROM recipe_tag t2
+                                WHERE t2.recipe_id = r.recipe_id AND t2.name ILIKE '%' || k || '%'
+                            ) AS tag,
+                            COALESCE(
+                                IMMUTABLE_UNACCENT(r.description) ILIKE '%' || IMMUTABLE_UNACCENT(k) || '%',
+                                FALSE
+                            ) AS description
+                    ) m
+                ) kw ON TRUE
                 WHERE
-                    ($1::TEXT IS NULL OR r.name ILIKE '%' || $1 || '%') AND
+                    ($1::TEXT IS NULL OR
+                        IMMUTABLE_UNACCENT(r.name) ILIKE '%' || IMMUTABLE_UNACCENT($1) || '%' OR
+                        IMMUTABLE_UNACCENT(r.description) ILIKE '%' || IMMUTABLE_UNACCENT($1) || '%'
+                    ) AND
                     ($2::TEXT IS NULL OR EXISTS (
                         SELECT 1 FROM ingredient i2
                         WHERE i2.recipe_id = r.recipe_id
-                        AND i2.name ILIKE '%' || $2 || '%'
+                        AND IMMUTABLE_UNACCENT(i2.name) ILIKE '%' || IMMUTABLE_UNACCENT($2) || '%'
                     )) AND
-                    ($3::meal_type IS NULL OR r.meal_type = $3::meal_type)
+                    ($3::meal_type IS NULL OR r.meal_type = $3::meal_type) AND
+                    (r.archived_at IS NOT NULL) = $4 AND
+                    (CARDINALITY($5::TEXT[]) = 0 OR (
+                        SELECT COUNT(*) FROM recipe_tag t
+                        WHERE t.recipe_id = r.recipe_id AND t.name = ANY($5)
+                    ) = CARDINALITY($5::TEXT[])) AND
+                    ($7::TIMESTAMPTZ IS NULL OR r.created_at > $7) AND
+                    ($8::TIMESTAMPTZ IS NULL OR r.created_at < $8) AND
+                    ($9::TIMESTAMPTZ IS NULL OR r.updated_at > $9) AND
+                    (CARDINALITY($10::TEXT[]) = 0 OR kw.matched)
+                ORDER BY
+                    CASE WHEN ($6::TEXT[])[1] = 'cooking_time' THEN r.cooking_time_secs END ASC NULLS LAST,
+                    CASE WHEN ($6::TEXT[])[1] = 'last_cooked' THEN lc.cooked_on END DESC NULLS LAST,
+                    CASE WHEN ($6::TEXT[])[2] = 'cooking_time' THEN r.cooking_time_secs END ASC NULLS LAST,
+                    CASE WHEN ($6::TEXT[])[2] = 'last_cooked' THEN lc.cooked_on END DESC NULLS LAST,
+                    kw.score DESC,
+                    -- Name matches rank above recipes only matching on their description
+                    IMMUTABLE_UNACCENT(r.name) ILIKE '%' || IMMUTABLE_UNACCENT($1) || '%' DESC NULLS LAST,
+                    r.recipe_id
             "#,
             args.recipe_name,
             args.ingredient_name,
             args.meal_type.as_ref() as Option<&MealType>,
+            args.archived,
+            &args.tags,
+            &sort,
+            args.created_after,
+            args.created_before,
+            args.updated_after,
+            &args.keywords,
+            args.weights.name,
+            args.weights.ingredient,
+            args.weights.tag,
+            args.weights.description,
         )
         .fetch_all(&self.pool)
         .await
@@ -249,6 +1193,717 @@ impl RecipeRepository for Postgres {
                     .cooking_time_secs
                     .map(|value| Duration::from_secs(value as u64)),
                 meal_type: row.meal_type,
+                video_url: row.video_url,
+                video_title: row.video_title,
+                video_thumbnail_url: row.video_thumbnail_url,
+                pairing: row.pairing,
+                pairing_kind: row.pairing_kind,
+                forked_from: row.forked_from,
+                public_id: row.public_id,
+                last_cooked_at: row.last_cooked_at,
+            })
+            .collect())
+    }
+
+    async fn similar_names(
+        &self,
+        term: String,
+        kind: NameKind,
+        archived: bool,
+        limit: i64,
+    ) -> Result<Vec<String>, SimilarNamesError> {
+        // `%` only matches names above the similarity threshold of pg_trgm, and can use the
+        // trigram indexes
+        let data = match kind {
+            NameKind::Recipe => sqlx::query_scalar!(
+                r#"
+                    SELECT r.name
+                    FROM recipe r
+                    WHERE
+                        IMMUTABLE_UNACCENT(r.name) % IMMUTABLE_UNACCENT($1) AND
+                        (r.archived_at IS NOT NULL) = $2
+                    GROUP BY r.name
+                    ORDER BY SIMILARITY(IMMUTABLE_UNACCENT(r.name), IMMUTABLE_UNACCENT($1)) DESC, r.name
+                    LIMIT $3
+                "#,
+                term,
+                archived,
+                limit
+            )
+            .fetch_all(&self.pool)
+            .await
+            .wrap_err("Failed to find similar recipe names")?,
+            NameKind::Ingredient => sqlx::query_scalar!(
+                r#"
+                    SELECT i.name
+                    FROM ingredient i
+                    JOIN recipe r ON r.recipe_id = i.recipe_id
+                    WHERE
+                        IMMUTABLE_UNACCENT(i.name) % IMMUTABLE_UNACCENT($1) AND
+                        (r.archived_at IS NOT NULL) = $2
+                    GROUP BY i.name
+                    ORDER BY SIMILARITY(IMMUTABLE_UNACCENT(i.name), IMMUTABLE_UNACCENT($1)) DESC, i.name
+                    LIMIT $3
+                "#,
+                term,
+                archived,
+                limit
+            )
+            .fetch_all(&self.pool)
+            .await
+            .wrap_err("Failed to find similar ingredient names")?,
+        };
+
+        Ok(data)
+    }
+
+    async fn autocomplete(
+        &self,
+        prefix: String,
+        limit: i64,
+    ) -> Result<Vec<RecipeNameEntity>, AutocompleteError> {
+        // Wildcards in the prefix are matched literally
+        let prefix = escape_like(&prefix);
+
+        let data = sqlx::query_as!(
+            RecipeNameEntity,
+            r#"
+                SELECT recipe_id, name
+                FROM recipe
+                WHERE
+                    LOWER(IMMUTABLE_UNACCENT(name)) LIKE LOWER(IMMUTABLE_UNACCENT($1)) || '%' AND
+                    archived_at IS NULL
+                ORDER BY LOWER(IMMUTABLE_UNACCENT(name)), recipe_id
+                LIMIT $2
+            "#,
+            prefix,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to autocomplete recipe names")?;
+
+        Ok(data)
+    }
+
+    async fn set_archived(&self, recipe_id: i32, archived: bool) -> Result<(), SetArchivedError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .wrap_err("Failed to start transaction")?;
+
+        let result = sqlx::query!(
+            r#"
+                UPDATE recipe
+                SET archived_at = CASE WHEN $2 THEN COALESCE(archived_at, NOW()) END
+                WHERE recipe_id = $1
+            "#,
+            recipe_id,
+            archived
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to archive recipe")?;
+
+        if result.rows_affected() == 0 {
+            return Err(SetArchivedError::NotFound);
+        }
+
+        // Sync clients find out about the archive through the event
+        outbox::record_event(&mut tx, RecipeEventKind::Updated, recipe_id)
+            .await
+            .wrap_err("Failed to record event")?;
+
+        tx.commit().await.wrap_err("Failed to commit transaction")?;
+
+        Ok(())
+    }
+
+    async fn search_by_ingredients(
+        &self,
+        ingredient_names: Vec<String>,
+        limit: i64,
+    ) -> Result<Vec<IngredientMatchEntity>, SearchByIngredientsError> {
+        // Wildcards in the names are matched literally
+        let ingredient_names = ingredient_names
+            .iter()
+            .map(|name| escape_like(name))
+            .collect::<Vec<_>>();
+
+        let data = sqlx::query!(
+            r#"
+                WITH coverage AS (
+                    SELECT
+                        i.recipe_id,
+                        COUNT(*) FILTER (WHERE EXISTS (
+                            SELECT 1 FROM UNNEST($1::TEXT[]) AS have (name)
+                            WHERE i.name ILIKE '%' || have.name || '%'
+                        )) AS matched,
+                        COUNT(*) AS total
+                    FROM ingredient i
+                    GROUP BY i.recipe_id
+                )
+
+                SELECT
+                    r.recipe_id,
+                    r.name,
+                    description,
+                    cooking_time_secs,
+                    video_url,
+                    video_title,
+                    video_thumbnail_url,
+                    pairing,
+                    pairing_kind AS "pairing_kind: PairingKind",
+                    forked_from,
+                    public_id,
+                    (
+                        SELECT JSON_AGG(ROW_TO_JSON(i) ORDER BY i.ingredient_order)
+                        FROM ingredient i
+                        WHERE i.recipe_id = r.recipe_id
+                    ) AS "ingredients: Json<Vec<IngredientEntity>>",
+                    meal_type AS "meal_type: MealType",
+                    (
+                        SELECT MAX(cl.cooked_on) FROM cooking_log cl WHERE cl.recipe_id = r.recipe_id
+                    ) AS last_cooked_at,
+                    c.matched AS "matched!",
+                    c.total - c.matched AS "missing!"
+                    FROM recipe r
+                JOIN coverage c ON c.recipe_id = r.recipe_id
+                WHERE c.matched > 0 AND r.archived_at IS NULL
+                ORDER BY c.matched::FLOAT / c.total DESC, c.total - c.matched, r.recipe_id
+                LIMIT $2
+            "#,
+            &ingredient_names,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to search recipes by ingredients")?;
+
+        Ok(data
+            .into_iter()
+            .map(|row| IngredientMatchEntity {
+                recipe: RecipeEntity {
+                    recipe_id: row.recipe_id,
+                    name: row.name,
+                    description: row.description,
+                    ingredients: row
+                        .ingredients
+                        .map(|ingredient| ingredient.0)
+                        .unwrap_or_default(),
+                    cooking_time: row
+                        .cooking_time_secs
+                        .map(|value| Duration::from_secs(value as u64)),
+                    meal_type: row.meal_type,
+                    video_url: row.video_url,
+                    video_title: row.video_title,
+                    video_thumbnail_url: row.video_thumbnail_url,
+                    pairing: row.pairing,
+                    pairing_kind: row.pairing_kind,
+                    forked_from: row.forked_from,
+                    public_id: row.public_id,
+                    last_cooked_at: row.last_cooked_at,
+                },
+                matched: row.matched,
+                missing: row.missing,
+            })
+            .collect())
+    }
+
+    async fn random_recipe(
+        &self,
+        args: RandomRecipeArguments,
+    ) -> Result<RecipeEntity, RandomRecipeError> {
+        // Picking a random offset into the matching ids keeps the filters usable, which
+        // `TABLESAMPLE` would not, and avoids sorting the whole table by `RANDOM()`
+        let row = sqlx::query!(
+            r#"
+                WITH candidates AS (
+                    SELECT recipe_id FROM recipe
+                    WHERE
+                        archived_at IS NULL AND
+                        ($1::meal_type IS NULL OR meal_type = $1::meal_type) AND
+                        ($2::BIGINT IS NULL OR cooking_time_secs <= $2) AND
+                        (CARDINALITY($3::TEXT[]) = 0 OR (
+                            SELECT COUNT(*) FROM recipe_tag t
+                            WHERE t.recipe_id = recipe.recipe_id AND t.name = ANY($3)
+                        ) = CARDINALITY($3::TEXT[]))
+                ), picked AS (
+                    SELECT recipe_id FROM candidates
+                    ORDER BY recipe_id
+                    OFFSET FLOOR(RANDOM() * (SELECT COUNT(*) FROM candidates))
+                    LIMIT 1
+                )
+
+                SELECT
+                    r.recipe_id,
+                    r.name,
+                    description,
+                    cooking_time_secs,
+                    video_url,
+                    video_title,
+                    video_thumbnail_url,
+                    pairing,
+                    pairing_kind AS "pairing_kind: PairingKind",
+                    forked_from,
+                    public_id,
+                    (
+                        SELECT JSON_AGG(ROW_TO_JSON(i) ORDER BY i.ingredient_order)
+                        FROM ingredient i
+                        WHERE i.recipe_id = r.recipe_id
+                    ) AS "ingredients: Json<Vec<IngredientEntity>>",
+                    meal_type AS "meal_type: MealType",
+                    (
+                        SELECT MAX(cl.cooked_on) FROM cooking_log cl WHERE cl.recipe_id = r.recipe_id
+                    ) AS last_cooked_at
+                    FROM recipe r
+                JOIN picked p ON p.recipe_id = r.recipe_id
+            "#,
+            args.meal_type.as_ref() as Option<&MealType>,
+            // A bound past what fits the column lets every cooking time through
+            args.max_cooking_time
+                .map(|time| i64::try_from(time.as_secs()).unwrap_or(i64::MAX)),
+            &args.tags,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .wrap_err("Failed to query for a random recipe")?
+        .ok_or(RandomRecipeError::NotFound)?;
+
+        Ok(RecipeEntity {
+            recipe_id: row.recipe_id,
+            name: row.name,
+            description: row.description,
+            ingredients: row
+                .ingredients
+                .map(|ingredient| ingredient.0)
+                .unwrap_or_default(),
+            cooking_time: row
+                .cooking_time_secs
+                .map(|value| Duration::from_secs(value as u64)),
+            meal_type: row.meal_type,
+            video_url: row.video_url,
+            video_title: row.video_title,
+            video_thumbnail_url: row.video_thumbnail_url,
+            pairing: row.pairing,
+            pairing_kind: row.pairing_kind,
+            forked_from: row.forked_from,
+            public_id: row.public_id,
+            last_cooked_at: row.last_cooked_at,
+        })
+    }
+
+    async fn record_views(&self, views: Vec<(i32, i64)>) -> Result<(), RecordViewsError> {
+        let (recipe_ids, view_counts): (Vec<i32>, Vec<i64>) = views.into_iter().unzip();
+
+        sqlx::query!(
+            r#"
+                INSERT INTO recipe_view (recipe_id, viewed_on, view_count)
+                SELECT v.recipe_id, CURRENT_DATE, v.view_count
+                FROM UNNEST($1::INTEGER[], $2::BIGINT[]) AS v (recipe_id, view_count)
+                JOIN recipe r ON r.recipe_id = v.recipe_id
+                ON CONFLICT (recipe_id, viewed_on)
+                DO UPDATE SET view_count = recipe_view.view_count + EXCLUDED.view_count
+            "#,
+            &recipe_ids,
+            &view_counts,
+        )
+        .execute(&self.pool)
+        .await
+        .wrap_err("Failed to record recipe views")?;
+
+        Ok(())
+    }
+
+    async fn fork_recipe(&self, recipe_id: i32) -> Result<RecipeEntity, ForkRecipeError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .wrap_err("Failed to open transaction")?;
+
+        let fork_id = sqlx::query_scalar!(
+            r#"
+                INSERT INTO recipe (
+                    name,
+                    description,
+                    cooking_time_secs,
+                    meal_type,
+                    video_url,
+                    video_title,
+                    video_thumbnail_url,
+                    pairing,
+                    pairing_kind,
+                    forked_from
+                )
+                SELECT
+                    name,
+                    description,
+                    cooking_time_secs,
+                    meal_type,
+                    video_url,
+                    video_title,
+                    video_thumbnail_url,
+                    pairing,
+                    pairing_kind,
+                    recipe_id
+                FROM recipe
+                WHERE recipe_id = $1
+                RETURNING recipe_id
+            "#,
+            recipe_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .wrap_err("Failed to copy recipe")?
+        .ok_or(ForkRecipeError::NotFound)?;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO ingredient (recipe_id, ingredient_order, name, quantity, quantity_type)
+                SELECT $2, ingredient_order, name, quantity, quantity_type
+                FROM ingredient
+                WHERE recipe_id = $1
+            "#,
+            recipe_id,
+            fork_id
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to copy ingredients")?;
+
+        outbox::record_event(&mut tx, RecipeEventKind::Created, fork_id)
+            .await
+            .wrap_err("Failed to record event")?;
+
+        tx.commit().await.wrap_err("Failed to commit transaction")?;
+
+        self.get_recipe(fork_id).await.map_err(|error| match error {
+            GetRecipeError::Unknown(report) => ForkRecipeError::Unknown(report),
+            GetRecipeError::NotFound => ForkRecipeError::NotFound,
+        })
+    }
+
+    async fn list_forks(&self, recipe_id: i32) -> Result<Vec<RecipeForkEntity>, ListForksError> {
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM recipe WHERE recipe_id = $1) AS "exists!""#,
+            recipe_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .wrap_err("Failed to check recipe")?;
+
+        if !exists {
+            return Err(ListForksError::NotFound);
+        }
+
+        let forks = sqlx::query_as!(
+            RecipeForkEntity,
+            r#"
+                WITH RECURSIVE fork AS (
+                    SELECT recipe_id, name, forked_from
+                    FROM recipe
+                    WHERE forked_from = $1
+                    UNION ALL
+                    SELECT r.recipe_id, r.name, r.forked_from
+                    FROM recipe r
+                    JOIN fork f ON r.forked_from = f.recipe_id
+                )
+                SELECT
+                    recipe_id AS "recipe_id!",
+                    name AS "name!",
+                    forked_from AS "forked_from!"
+                FROM fork
+                ORDER BY recipe_id
+            "#,
+            recipe_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to list forks")?;
+
+        Ok(forks)
+    }
+
+    async fn merge_recipes(
+        &self,
+        target_id: i32,
+        source_id: i32,
+        entity: MutableRecipeEntity,
+    ) -> Result<RecipeEntity, MergeRecipesError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .wrap_err("Failed to open transaction")?;
+
+        let result = sqlx::query!(
+            r#"
+                UPDATE recipe SET
+                    name = $1,
+                    description = $2,
+                    cooking_time_secs = $3,
+                    meal_type = $4,
+                    video_url = $6,
+                    video_title = $7,
+                    video_thumbnail_url = $8,
+                    pairing = $9,
+                    pairing_kind = $10
+                WHERE recipe_id = $5
+            "#,
+            entity.name,
+            entity.description,
+            entity.cooking_time.map(|time| time.as_secs() as i64),
+            &entity.meal_type as &MealType,
+            target_id,
+            entity.video_url,
+            entity.video_title,
+            entity.video_thumbnail_url,
+            entity.pairing,
+            entity.pairing_kind as Option<PairingKind>,
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to update target recipe")?;
+
+        if result.rows_affected() == 0 {
+            return Err(MergeRecipesError::NotFound);
+        }
+
+        sqlx::query!(
+            "DELETE FROM ingredient WHERE recipe_id = ANY($1)",
+            &[target_id, source_id]
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to clear out old ingredients")?;
+
+        create_ingredients(&mut tx, target_id, &entity.ingredients)
+            .await
+            .wrap_err("Failed to create ingredients")?;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO recipe_view (recipe_id, viewed_on, view_count)
+                SELECT $1, viewed_on, view_count
+                FROM recipe_view
+                WHERE recipe_id = $2
+                ON CONFLICT (recipe_id, viewed_on)
+                DO UPDATE SET view_count = recipe_view.view_count + EXCLUDED.view_count
+            "#,
+            target_id,
+            source_id
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to move recipe views")?;
+
+        sqlx::query!("DELETE FROM recipe_view WHERE recipe_id = $1", source_id)
+            .execute(&mut *tx)
+            .await
+            .wrap_err("Failed to delete recipe views")?;
+
+        sqlx::query!(
+            "UPDATE cooking_log SET recipe_id = $1 WHERE recipe_id = $2",
+            target_id,
+            source_id
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to move cooking log")?;
+
+        sqlx::query!(
+            "UPDATE share_link SET recipe_id = $1 WHERE recipe_id = $2",
+            target_id,
+            source_id
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to move share links")?;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO recipe_tag (recipe_id, name)
+                SELECT $1, name FROM recipe_tag WHERE recipe_id = $2
+                ON CONFLICT DO NOTHING
+            "#,
+            target_id,
+            source_id
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to copy recipe tags")?;
+
+        sqlx::query!("DELETE FROM recipe_tag WHERE recipe_id = $1", source_id)
+            .execute(&mut *tx)
+            .await
+            .wrap_err("Failed to delete recipe tags")?;
+
+        sqlx::query!(
+            "UPDATE mirrored_recipe SET detached = TRUE WHERE recipe_id = $1",
+            target_id
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to detach mirrored recipe")?;
+
+        sqlx::query!(
+            "UPDATE mirrored_recipe SET recipe_id = NULL WHERE recipe_id = $1",
+            source_id
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to release mirrored recipe")?;
+
+        // When the target descends from the source it takes the place of the source, otherwise
+        // moving the forks of the source to the target would turn the lineage into a cycle
+        sqlx::query!(
+            r#"
+                UPDATE recipe
+                SET forked_from = (SELECT forked_from FROM recipe WHERE recipe_id = $2)
+                WHERE recipe_id = $1
+                AND $2 IN (
+                    WITH RECURSIVE ancestor AS (
+                        SELECT forked_from FROM recipe WHERE recipe_id = $1
+                        UNION
+                        SELECT r.forked_from
+                        FROM recipe r
+                        JOIN ancestor a ON r.recipe_id = a.forked_from
+                    )
+                    SELECT forked_from FROM ancestor
+                )
+            "#,
+            target_id,
+            source_id
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to move target recipe")?;
+
+        sqlx::query!(
+            "UPDATE recipe SET forked_from = $1 WHERE forked_from = $2",
+            target_id,
+            source_id
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to move forks")?;
+
+        outbox::record_event(&mut tx, RecipeEventKind::Updated, target_id)
+            .await
+            .wrap_err("Failed to record event")?;
+        outbox::record_event(&mut tx, RecipeEventKind::Deleted, source_id)
+            .await
+            .wrap_err("Failed to record event")?;
+
+        let source_name = sqlx::query_scalar!(
+            "DELETE FROM recipe WHERE recipe_id = $1 RETURNING name",
+            source_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .wrap_err("Failed to delete source recipe")?
+        .ok_or(MergeRecipesError::NotFound)?;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO recipe_deletion (recipe_id, recipe_name, reason)
+                VALUES ($1, $2, 'Merged into recipe ' || $3::INTEGER)
+            "#,
+            source_id,
+            source_name,
+            target_id,
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to record deletion")?;
+
+        tx.commit().await.wrap_err("Failed to commit transaction")?;
+
+        self.get_recipe(target_id)
+            .await
+            .map_err(|error| match error {
+                GetRecipeError::Unknown(report) => MergeRecipesError::Unknown(report),
+                GetRecipeError::NotFound => MergeRecipesError::NotFound,
+            })
+    }
+
+    async fn popular_recipes(
+        &self,
+        window_days: i32,
+        limit: i64,
+    ) -> Result<Vec<PopularRecipeEntity>, PopularRecipesError> {
+        let data = sqlx::query!(
+            r#"
+                WITH views AS (
+                    SELECT rv.recipe_id, SUM(rv.view_count)::BIGINT AS views
+                    FROM recipe_view rv
+                    JOIN recipe r ON r.recipe_id = rv.recipe_id
+                    WHERE rv.viewed_on > CURRENT_DATE - $1::INTEGER AND r.archived_at IS NULL
+                    GROUP BY rv.recipe_id
+                    ORDER BY views DESC, rv.recipe_id
+                    LIMIT $2
+                )
+
+                SELECT
+                    r.recipe_id,
+                    r.name,
+                    description,
+                    cooking_time_secs,
+                    video_url,
+                    video_title,
+                    video_thumbnail_url,
+                    pairing,
+                    pairing_kind AS "pairing_kind: PairingKind",
+                    forked_from,
+                    public_id,
+                    (
+                        SELECT JSON_AGG(ROW_TO_JSON(i) ORDER BY i.ingredient_order)
+                        FROM ingredient i
+                        WHERE i.recipe_id = r.recipe_id
+                    ) AS "ingredients: Json<Vec<IngredientEntity>>",
+                    meal_type AS "meal_type: MealType",
+                    (
+                        SELECT MAX(cl.cooked_on) FROM cooking_log cl WHERE cl.recipe_id = r.recipe_id
+                    ) AS last_cooked_at,
+                    v.views AS "views!"
+                    FROM recipe r
+                JOIN views v ON v.recipe_id = r.recipe_id
+                ORDER BY v.views DESC, r.recipe_id
+            "#,
+            window_days,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to query for popular recipes")?;
+
+        Ok(data
+            .into_iter()
+            .map(|row| PopularRecipeEntity {
+                recipe: RecipeEntity {
+                    recipe_id: row.recipe_id,
+                    name: row.name,
+                    description: row.description,
+                    ingredients: row
+                        .ingredients
+                        .map(|ingredient| ingredient.0)
+                        .unwrap_or_default(),
+                    cooking_time: row
+                        .cooking_time_secs
+                        .map(|value| Duration::from_secs(value as u64)),
+                    meal_type: row.meal_type,
+                    video_url: row.video_url,
+                    video_title: row.video_title,
+                    video_thumbnail_url: row.video_thumbnail_url,
+                    pairing: row.pairing,
+                    pairing_kind: row.pairing_kind,
+                    forked_from: row.forked_from,
+                    public_id: row.public_id,
+                    last_cooked_at: row.last_cooked_at,
+                },
+                views: row.views,
             })
             .collect())
     }
@@ -291,10 +1946,46 @@ async fn create_ingredients(
         .await
 }
 
+/// Escapes the wildcards of `LIKE` patterns, so the value is matched literally
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Appends a row in the text format of `COPY`, missing values being written as `NULL`
+fn push_copy_row(buffer: &mut String, values: &[Option<&str>]) {
+    for (idx, value) in values.iter().enumerate() {
+        if idx > 0 {
+            buffer.push('\t');
+        }
+
+        match value {
+            Some(value) => {
+                for char in value.chars() {
+                    match char {
+                        '\\' => buffer.push_str("\\\\"),
+                        '\t' => buffer.push_str("\\t"),
+                        '\n' => buffer.push_str("\\n"),
+                        '\r' => buffer.push_str("\\r"),
+                        char => buffer.push(char),
+                    }
+                }
+            }
+            None => buffer.push_str("\\N"),
+        }
+    }
+
+    buffer.push('\n');
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::persistance::recipe::{MealType, QuantityType};
+    use crate::persistance::recipe::{
+        MealType, PairingKind, QuantityType, SearchSort, SearchWeights,
+    };
     use assert2::{check, let_assert};
 
     static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
@@ -321,6 +2012,81 @@ mod tests {
             ],
             cooking_time: Some(Duration::from_secs(3600)),
             meal_type,
+            video_url: None,
+            video_title: None,
+            video_thumbnail_url: None,
+            pairing: None,
+            pairing_kind: None,
+        }
+    }
+
+    /// Dinner without ingredients or cooking time, shared with the tests of the other
+    /// repositories
+    pub(crate) fn create_minimal_recipe(name: &str) -> MutableRecipeEntity {
+        MutableRecipeEntity {
+            name: name.to_string(),
+            description: None,
+            ingredients: vec![],
+            cooking_time: None,
+            meal_type: MealType::Dinner,
+            video_url: None,
+            video_title: None,
+            video_thumbnail_url: None,
+            pairing: None,
+            pairing_kind: None,
+        }
+    }
+
+    mod contract {
+        use super::*;
+
+        crate::recipe_repository_contract!(
+            #[sqlx::test(migrator = "super::MIGRATOR")]
+            |pool: PgPool| Postgres::new(pool)
+        );
+    }
+
+    mod as_label {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_matches_the_labels_of_the_postgres_enums(pool: PgPool) {
+            let_assert!(
+                Ok(labels) = sqlx::query_scalar!(
+                    r#"
+                        SELECT ARRAY[
+                            enum_range(NULL::quantity_type)::TEXT,
+                            enum_range(NULL::pairing_kind)::TEXT,
+                            enum_range(NULL::meal_type)::TEXT
+                        ] AS "labels!"
+                    "#
+                )
+                .fetch_one(&pool)
+                .await
+            );
+
+            let quantity_types = [
+                QuantityType::Count,
+                QuantityType::Kilo,
+                QuantityType::Gram,
+                QuantityType::Liter,
+                QuantityType::Milliliter,
+            ]
+            .map(|value| value.as_label());
+            let pairing_kinds = [
+                PairingKind::Wine,
+                PairingKind::Beer,
+                PairingKind::NonAlcoholic,
+            ]
+            .map(|value| value.as_label());
+            let meal_types = [MealType::Breakfast, MealType::Lunch, MealType::Dinner]
+                .map(|value| value.as_label());
+
+            check!(
+                labels
+                    == [quantity_types.as_slice(), &pairing_kinds, &meal_types]
+                        .map(|labels| format!("{{{}}}", labels.join(",")))
+            );
         }
     }
 
@@ -331,7 +2097,7 @@ mod tests {
         async fn it_returns_empty_list_when_no_recipes_exist(pool: PgPool) {
             let repository = Postgres::new(pool);
 
-            let result = repository.list_recipes().await;
+            let result = repository.list_recipes(true, false).await;
 
             let_assert!(Ok(recipes) = result);
             check!(recipes.is_empty());
@@ -347,7 +2113,7 @@ mod tests {
             let_assert!(Ok(_) = repository.create_recipe(recipe1).await);
             let_assert!(Ok(_) = repository.create_recipe(recipe2).await);
 
-            let result = repository.list_recipes().await;
+            let result = repository.list_recipes(true, false).await;
 
             let_assert!(Ok(recipes) = result);
             check!(recipes.len() == 2);
@@ -362,7 +2128,7 @@ mod tests {
             let recipe = create_test_recipe("Test Recipe", MealType::Lunch);
             let_assert!(Ok(_) = repository.create_recipe(recipe).await);
 
-            let result = repository.list_recipes().await;
+            let result = repository.list_recipes(true, false).await;
 
             let_assert!(Ok(recipes) = result);
             check!(recipes.len() == 1);
@@ -373,6 +2139,298 @@ mod tests {
             check!(recipe.ingredients[0].name == "Ingredient 1");
             check!(recipe.ingredients[1].name == "Ingredient 2");
         }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_skips_ingredients_when_not_included(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe = create_test_recipe("Test Recipe", MealType::Lunch);
+            let_assert!(Ok(_) = repository.create_recipe(recipe).await);
+
+            let_assert!(Ok(recipes) = repository.list_recipes(false, false).await);
+            check!(recipes.len() == 1);
+            check!(recipes[0].name == "Test Recipe");
+            check!(recipes[0].ingredients.is_empty());
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_lists_archived_recipes_apart(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let pancakes = create_test_recipe("Pancakes", MealType::Breakfast);
+            let_assert!(Ok(pancakes) = repository.create_recipe(pancakes).await);
+            let pasta = create_test_recipe("Pasta", MealType::Dinner);
+            let_assert!(Ok(_) = repository.create_recipe(pasta).await);
+            let_assert!(Ok(()) = repository.set_archived(pancakes.recipe_id, true).await);
+
+            let_assert!(Ok(recipes) = repository.list_recipes(true, false).await);
+            check!(recipes.len() == 1);
+            check!(recipes[0].name == "Pasta");
+
+            let_assert!(Ok(archived) = repository.list_recipes(false, true).await);
+            check!(archived.len() == 1);
+            check!(archived[0].name == "Pancakes");
+        }
+    }
+
+    mod similar_names {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_suggests_names_close_to_a_typo(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            for name in ["Pancakes", "Pan-fried salmon", "Lasagna"] {
+                let_assert!(
+                    Ok(_) = repository
+                        .create_recipe(create_test_recipe(name, MealType::Dinner))
+                        .await
+                );
+            }
+
+            let_assert!(
+                Ok(names) = repository
+                    .similar_names("Pancaeks".to_string(), NameKind::Recipe, false, 5)
+                    .await
+            );
+            check!(names.first().map(String::as_str) == Some("Pancakes"));
+            check!(!names.contains(&"Lasagna".to_string()));
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_suggests_each_ingredient_name_once(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            for name in ["Stew", "Soup"] {
+                let_assert!(
+                    Ok(_) = repository
+                        .create_recipe(create_test_recipe(name, MealType::Dinner))
+                        .await
+                );
+            }
+
+            let_assert!(
+                Ok(names) = repository
+                    .similar_names("Ingredeint 1".to_string(), NameKind::Ingredient, false, 5)
+                    .await
+            );
+            check!(names == ["Ingredient 1", "Ingredient 2"]);
+        }
+    }
+
+    mod autocomplete {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_matches_names_by_prefix_ignoring_case_and_accents(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            for name in ["Crème brûlée", "Cremini risotto", "Ice cream", "Crepes"] {
+                let_assert!(
+                    Ok(_) = repository
+                        .create_recipe(create_test_recipe(name, MealType::Dinner))
+                        .await
+                );
+            }
+
+            let_assert!(Ok(recipes) = repository.autocomplete("cre".to_string(), 10).await);
+            let names = recipes
+                .iter()
+                .map(|recipe| recipe.name.as_str())
+                .collect::<Vec<_>>();
+            check!(names == ["Crème brûlée", "Cremini risotto", "Crepes"]);
+
+            let_assert!(Ok(recipes) = repository.autocomplete("cre".to_string(), 1).await);
+            check!(recipes.len() == 1);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_matches_wildcards_literally(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe = create_test_recipe("Pancakes", MealType::Breakfast);
+            let_assert!(Ok(_) = repository.create_recipe(recipe).await);
+
+            let_assert!(Ok(recipes) = repository.autocomplete("%cakes".to_string(), 10).await);
+            check!(recipes.is_empty());
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_leaves_out_archived_recipes(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe = create_test_recipe("Pancakes", MealType::Breakfast);
+            let_assert!(Ok(recipe) = repository.create_recipe(recipe).await);
+            let_assert!(Ok(()) = repository.set_archived(recipe.recipe_id, true).await);
+
+            let_assert!(Ok(recipes) = repository.autocomplete("Pan".to_string(), 10).await);
+            check!(recipes.is_empty());
+        }
+    }
+
+    mod set_archived {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_unarchives_recipes(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe = create_test_recipe("Pancakes", MealType::Breakfast);
+            let_assert!(Ok(recipe) = repository.create_recipe(recipe).await);
+            let_assert!(Ok(()) = repository.set_archived(recipe.recipe_id, true).await);
+            let_assert!(Ok(()) = repository.set_archived(recipe.recipe_id, false).await);
+
+            let_assert!(Ok(recipes) = repository.list_recipes(false, false).await);
+            check!(recipes.len() == 1);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_returns_not_found_error_for_nonexistent_recipe(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let result = repository.set_archived(999, true).await;
+
+            let_assert!(Err(SetArchivedError::NotFound) = result);
+        }
+    }
+
+    mod get_recipes {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_returns_recipes_in_the_requested_order(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(first) = repository
+                    .create_recipe(create_test_recipe("First", MealType::Lunch))
+                    .await
+            );
+            let_assert!(
+                Ok(second) = repository
+                    .create_recipe(create_test_recipe("Second", MealType::Dinner))
+                    .await
+            );
+
+            let_assert!(
+                Ok(recipes) = repository
+                    .get_recipes(vec![second.recipe_id, 99999, first.recipe_id], true)
+                    .await
+            );
+
+            check!(recipes.len() == 2);
+            check!(recipes[0].name == "Second");
+            check!(recipes[1].name == "First");
+            check!(recipes[0].ingredients.len() == 2);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_skips_ingredients_when_not_included(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(recipe) = repository
+                    .create_recipe(create_test_recipe("First", MealType::Lunch))
+                    .await
+            );
+
+            let_assert!(Ok(recipes) = repository.get_recipes(vec![recipe.recipe_id], false).await);
+
+            check!(recipes.len() == 1);
+            check!(recipes[0].ingredients.is_empty());
+        }
+    }
+
+    mod search_by_ingredients {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_ranks_recipes_by_ingredient_coverage(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let full = MutableRecipeEntity {
+                ingredients: vec![
+                    create_test_ingredient("Rice", 200.0, QuantityType::Gram),
+                    create_test_ingredient("Eggs", 2.0, QuantityType::Count),
+                ],
+                ..create_test_recipe("Fried Rice", MealType::Dinner)
+            };
+            let partial = MutableRecipeEntity {
+                ingredients: vec![
+                    create_test_ingredient("Basmati rice", 200.0, QuantityType::Gram),
+                    create_test_ingredient("Chicken", 300.0, QuantityType::Gram),
+                    create_test_ingredient("Curry paste", 2.0, QuantityType::Count),
+                ],
+                ..create_test_recipe("Curry", MealType::Dinner)
+            };
+            let unrelated = create_test_recipe("Unrelated", MealType::Lunch);
+
+            let_assert!(Ok(_) = repository.create_recipe(partial).await);
+            let_assert!(Ok(_) = repository.create_recipe(full).await);
+            let_assert!(Ok(_) = repository.create_recipe(unrelated).await);
+
+            let_assert!(
+                Ok(matches) = repository
+                    .search_by_ingredients(vec!["rice".to_string(), "egg".to_string()], 10)
+                    .await
+            );
+
+            check!(matches.len() == 2);
+            check!(matches[0].recipe.name == "Fried Rice");
+            check!(matches[0].matched == 2);
+            check!(matches[0].missing == 0);
+            check!(matches[0].recipe.ingredients.len() == 2);
+            check!(matches[1].recipe.name == "Curry");
+            check!(matches[1].matched == 1);
+            check!(matches[1].missing == 2);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_matches_wildcards_literally(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe = create_test_recipe("Pancakes", MealType::Breakfast);
+            let_assert!(Ok(_) = repository.create_recipe(recipe).await);
+
+            for wildcard in ["%", "_", "Ingredient\\ 1"] {
+                let_assert!(
+                    Ok(matches) = repository
+                        .search_by_ingredients(vec![wildcard.to_string()], 10)
+                        .await
+                );
+                check!(matches.is_empty(), "for {wildcard}");
+            }
+        }
+    }
+
+    mod get_recipe {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_returns_the_recipe_with_its_ingredients(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe = create_test_recipe("Pancakes", MealType::Breakfast);
+            let_assert!(Ok(created) = repository.create_recipe(recipe).await);
+
+            let result = repository.get_recipe(created.recipe_id).await;
+
+            let_assert!(Ok(recipe) = result);
+            check!(recipe.recipe_id == created.recipe_id);
+            check!(recipe.name == "Pancakes");
+            check!(recipe.ingredients.len() == 2);
+            check!(recipe.ingredients[0].name == "Ingredient 1");
+            check!(recipe.ingredients[1].name == "Ingredient 2");
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_returns_not_found_error_for_nonexistent_recipe(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let result = repository.get_recipe(99999).await;
+
+            let_assert!(Err(GetRecipeError::NotFound) = result);
+        }
     }
 
     mod create_recipe {
@@ -424,6 +2482,11 @@ mod tests {
                 ingredients: vec![],
                 cooking_time: None,
                 meal_type: MealType::Lunch,
+                video_url: None,
+                video_title: None,
+                video_thumbnail_url: None,
+                pairing: None,
+                pairing_kind: None,
             };
 
             let result = repository.create_recipe(recipe).await;
@@ -435,6 +2498,56 @@ mod tests {
             let_assert!(None = created_recipe.cooking_time);
         }
 
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_stores_the_video_and_its_metadata(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe = MutableRecipeEntity {
+                video_url: Some("https://video.example.com/soup".to_string()),
+                video_title: Some("Making soup".to_string()),
+                video_thumbnail_url: Some("https://video.example.com/soup.jpg".to_string()),
+                ..create_test_recipe("Soup", MealType::Dinner)
+            };
+
+            let_assert!(Ok(created) = repository.create_recipe(recipe).await);
+            let_assert!(Ok(fetched) = repository.get_recipe(created.recipe_id).await);
+
+            check!(fetched.video_url.as_deref() == Some("https://video.example.com/soup"));
+            check!(fetched.video_title.as_deref() == Some("Making soup"));
+            check!(
+                fetched.video_thumbnail_url.as_deref()
+                    == Some("https://video.example.com/soup.jpg")
+            );
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_stores_the_pairing(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe = MutableRecipeEntity {
+                pairing: Some("Chianti".to_string()),
+                pairing_kind: Some(PairingKind::Wine),
+                ..create_test_recipe("Lasagne", MealType::Dinner)
+            };
+
+            let_assert!(Ok(created) = repository.create_recipe(recipe).await);
+            let_assert!(Ok(fetched) = repository.get_recipe(created.recipe_id).await);
+
+            check!(fetched.pairing.as_deref() == Some("Chianti"));
+            let_assert!(Some(PairingKind::Wine) = fetched.pairing_kind);
+
+            let update = MutableRecipeEntity {
+                pairing: Some("Sparkling water".to_string()),
+                pairing_kind: None,
+                ..create_test_recipe("Lasagne", MealType::Dinner)
+            };
+
+            let_assert!(Ok(updated) = repository.update_recipe(created.recipe_id, update).await);
+
+            check!(updated.pairing.as_deref() == Some("Sparkling water"));
+            check!(updated.pairing_kind.is_none());
+        }
+
         #[sqlx::test(migrator = "super::MIGRATOR")]
         async fn it_preserves_ingredient_order(pool: PgPool) {
             let repository = Postgres::new(pool);
@@ -449,6 +2562,11 @@ mod tests {
                 ],
                 cooking_time: None,
                 meal_type: MealType::Lunch,
+                video_url: None,
+                video_title: None,
+                video_thumbnail_url: None,
+                pairing: None,
+                pairing_kind: None,
             };
 
             let result = repository.create_recipe(recipe).await;
@@ -483,6 +2601,11 @@ mod tests {
                 )],
                 cooking_time: Some(Duration::from_secs(1800)),
                 meal_type: MealType::Dinner,
+                video_url: None,
+                video_title: None,
+                video_thumbnail_url: None,
+                pairing: None,
+                pairing_kind: None,
             };
 
             let result = repository
@@ -520,6 +2643,11 @@ mod tests {
                 ],
                 cooking_time: None,
                 meal_type: MealType::Lunch,
+                video_url: None,
+                video_title: None,
+                video_thumbnail_url: None,
+                pairing: None,
+                pairing_kind: None,
             };
 
             let result = repository
@@ -556,6 +2684,11 @@ mod tests {
                 ingredients: vec![],
                 cooking_time: None,
                 meal_type: MealType::Dinner,
+                video_url: None,
+                video_title: None,
+                video_thumbnail_url: None,
+                pairing: None,
+                pairing_kind: None,
             };
 
             let result = repository
@@ -567,6 +2700,92 @@ mod tests {
         }
     }
 
+    mod update_metadata {
+        use super::*;
+        use crate::persistance::{outbox::OutboxRepository, tag::TagRepository};
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_only_changes_the_given_fields(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe = create_test_recipe("Pancakes", MealType::Breakfast);
+            let_assert!(Ok(created) = repository.create_recipe(recipe).await);
+
+            let changes = vec![RecipeMetadataEntity {
+                recipe_id: created.recipe_id,
+                name: None,
+                description: None,
+                cooking_time: None,
+                meal_type: Some(MealType::Dinner),
+                tags: None,
+            }];
+            let_assert!(Ok(updated) = repository.update_metadata(changes).await);
+            check!(updated == [created.recipe_id]);
+
+            let_assert!(Ok(recipe) = repository.get_recipe(created.recipe_id).await);
+            check!(recipe.name == "Pancakes");
+            check!(matches!(recipe.meal_type, MealType::Dinner));
+            check!(recipe.cooking_time == Some(Duration::from_secs(3600)));
+            check!(recipe.ingredients.len() == 2);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_skips_recipes_which_do_not_exist(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe = create_test_recipe("Pancakes", MealType::Breakfast);
+            let_assert!(Ok(created) = repository.create_recipe(recipe).await);
+
+            let changes = [99999, created.recipe_id]
+                .into_iter()
+                .map(|recipe_id| RecipeMetadataEntity {
+                    recipe_id,
+                    name: Some("Crêpes".to_string()),
+                    description: None,
+                    cooking_time: None,
+                    meal_type: None,
+                    tags: None,
+                })
+                .collect();
+            let_assert!(Ok(updated) = repository.update_metadata(changes).await);
+            check!(updated == [created.recipe_id]);
+
+            let_assert!(Ok(events) = repository.events_since(0, 10).await);
+            check!(events.len() == 2);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_replaces_the_tags_when_given(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe = create_test_recipe("Pancakes", MealType::Breakfast);
+            let_assert!(Ok(created) = repository.create_recipe(recipe).await);
+            let_assert!(
+                Ok(_) = repository
+                    .set_recipe_tags(created.recipe_id, vec!["sweet".to_string()])
+                    .await
+            );
+
+            let change = |tags| RecipeMetadataEntity {
+                recipe_id: created.recipe_id,
+                name: None,
+                description: None,
+                cooking_time: None,
+                meal_type: None,
+                tags,
+            };
+
+            let_assert!(Ok(_) = repository.update_metadata(vec![change(None)]).await);
+            let_assert!(Ok(tags) = repository.recipe_tags(created.recipe_id).await);
+            check!(tags == ["sweet"]);
+
+            let tags = vec!["quick".to_string(), "breakfast".to_string()];
+            let_assert!(Ok(_) = repository.update_metadata(vec![change(Some(tags))]).await);
+            let_assert!(Ok(tags) = repository.recipe_tags(created.recipe_id).await);
+            check!(tags == ["breakfast", "quick"]);
+        }
+    }
+
     mod delete_recipe {
         use super::*;
 
@@ -577,10 +2796,14 @@ mod tests {
             let recipe = create_test_recipe("To Delete", MealType::Breakfast);
             let_assert!(Ok(created) = repository.create_recipe(recipe).await);
 
-            let result = repository.delete_recipe(created.recipe_id).await;
+            let result = repository
+                .delete_recipe(created.recipe_id, DeletionEntity::default())
+                .await;
 
-            let_assert!(Ok(()) = result);
-            let_assert!(Ok(list_result) = repository.list_recipes().await);
+            let_assert!(Ok(deleted) = result);
+            check!(deleted.recipe_id == created.recipe_id);
+            check!(deleted.name == "To Delete");
+            let_assert!(Ok(list_result) = repository.list_recipes(true, false).await);
             check!(list_result.is_empty());
         }
 
@@ -601,46 +2824,197 @@ mod tests {
 
             check!(ingredient_count == 2);
 
-            let result = repository.delete_recipe(created.recipe_id).await;
-            let_assert!(Ok(()) = result);
+            let result = repository
+                .delete_recipe(created.recipe_id, DeletionEntity::default())
+                .await;
+            let_assert!(Ok(_) = result);
+
+            let ingredient_count_after = sqlx::query_scalar!(
+                "SELECT COUNT(*) as \"count!\" FROM ingredient WHERE recipe_id = $1",
+                created.recipe_id
+            )
+            .fetch_one(&repository.pool)
+            .await
+            .expect("Failed to fetch count");
+
+            check!(ingredient_count_after == 0);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_returns_not_found_error_for_nonexistent_recipe(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let result = repository
+                .delete_recipe(99999, DeletionEntity::default())
+                .await;
+
+            let_assert!(Err(DeleteRecipeError::NotFound) = result);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_does_not_affect_other_recipes_when_deleting(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe1 = create_test_recipe("Keep This", MealType::Breakfast);
+            let recipe2 = create_test_recipe("Delete This", MealType::Lunch);
+
+            let_assert!(Ok(created1) = repository.create_recipe(recipe1).await);
+            let_assert!(Ok(created2) = repository.create_recipe(recipe2).await);
+
+            let result = repository
+                .delete_recipe(created2.recipe_id, DeletionEntity::default())
+                .await;
+            let_assert!(Ok(_) = result);
+
+            let_assert!(Ok(remaining_recipes) = repository.list_recipes(true, false).await);
+            check!(remaining_recipes.len() == 1);
+            check!(remaining_recipes[0].recipe_id == created1.recipe_id);
+            check!(remaining_recipes[0].name == "Keep This");
+        }
+    }
+
+    mod fork_recipe {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_copies_the_recipe_and_its_ingredients(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe = create_test_recipe("Pizza", MealType::Dinner);
+            let_assert!(Ok(created) = repository.create_recipe(recipe).await);
+
+            let_assert!(Ok(fork) = repository.fork_recipe(created.recipe_id).await);
+
+            check!(fork.recipe_id != created.recipe_id);
+            check!(fork.name == "Pizza");
+            check!(fork.forked_from == Some(created.recipe_id));
+            check!(fork.ingredients.len() == 2);
+            check!(fork.ingredients[0].name == "Ingredient 1");
+            check!(fork.ingredients[1].name == "Ingredient 2");
+        }
 
-            let ingredient_count_after = sqlx::query_scalar!(
-                "SELECT COUNT(*) as \"count!\" FROM ingredient WHERE recipe_id = $1",
-                created.recipe_id
-            )
-            .fetch_one(&repository.pool)
-            .await
-            .expect("Failed to fetch count");
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_returns_not_found_for_missing_recipe(pool: PgPool) {
+            let repository = Postgres::new(pool);
 
-            check!(ingredient_count_after == 0);
+            let_assert!(Err(ForkRecipeError::NotFound) = repository.fork_recipe(42).await);
         }
 
         #[sqlx::test(migrator = "super::MIGRATOR")]
-        async fn it_returns_not_found_error_for_nonexistent_recipe(pool: PgPool) {
+        async fn it_lists_forks_of_forks(pool: PgPool) {
             let repository = Postgres::new(pool);
 
-            let result = repository.delete_recipe(99999).await;
+            let recipe = create_test_recipe("Pizza", MealType::Dinner);
+            let_assert!(Ok(base) = repository.create_recipe(recipe).await);
+            let_assert!(Ok(fork) = repository.fork_recipe(base.recipe_id).await);
+            let_assert!(Ok(nested) = repository.fork_recipe(fork.recipe_id).await);
 
-            let_assert!(Err(DeleteRecipeError::NotFound) = result);
+            let_assert!(Ok(forks) = repository.list_forks(base.recipe_id).await);
+
+            check!(forks.len() == 2);
+            check!(forks[0].recipe_id == fork.recipe_id);
+            check!(forks[0].forked_from == base.recipe_id);
+            check!(forks[1].recipe_id == nested.recipe_id);
+            check!(forks[1].forked_from == fork.recipe_id);
         }
 
         #[sqlx::test(migrator = "super::MIGRATOR")]
-        async fn it_does_not_affect_other_recipes_when_deleting(pool: PgPool) {
+        async fn it_moves_forks_up_when_deleting_a_fork(pool: PgPool) {
             let repository = Postgres::new(pool);
 
-            let recipe1 = create_test_recipe("Keep This", MealType::Breakfast);
-            let recipe2 = create_test_recipe("Delete This", MealType::Lunch);
+            let recipe = create_test_recipe("Pizza", MealType::Dinner);
+            let_assert!(Ok(base) = repository.create_recipe(recipe).await);
+            let_assert!(Ok(fork) = repository.fork_recipe(base.recipe_id).await);
+            let_assert!(Ok(nested) = repository.fork_recipe(fork.recipe_id).await);
+
+            let_assert!(
+                Ok(_) = repository
+                    .delete_recipe(fork.recipe_id, DeletionEntity::default())
+                    .await
+            );
+
+            let_assert!(Ok(forks) = repository.list_forks(base.recipe_id).await);
+            check!(forks.len() == 1);
+            check!(forks[0].recipe_id == nested.recipe_id);
+            check!(forks[0].forked_from == base.recipe_id);
+        }
+    }
 
-            let_assert!(Ok(created1) = repository.create_recipe(recipe1).await);
-            let_assert!(Ok(created2) = repository.create_recipe(recipe2).await);
+    mod merge_recipes {
+        use super::*;
 
-            let result = repository.delete_recipe(created2.recipe_id).await;
-            let_assert!(Ok(()) = result);
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_moves_history_and_forks_to_the_target(pool: PgPool) {
+            let repository = Postgres::new(pool);
 
-            let_assert!(Ok(remaining_recipes) = repository.list_recipes().await);
-            check!(remaining_recipes.len() == 1);
-            check!(remaining_recipes[0].recipe_id == created1.recipe_id);
-            check!(remaining_recipes[0].name == "Keep This");
+            let_assert!(
+                Ok(target) = repository
+                    .create_recipe(create_test_recipe("Lasagne", MealType::Dinner))
+                    .await
+            );
+            let_assert!(
+                Ok(source) = repository
+                    .create_recipe(create_test_recipe("Lasagna", MealType::Dinner))
+                    .await
+            );
+            let_assert!(Ok(fork) = repository.fork_recipe(source.recipe_id).await);
+
+            sqlx::query!(
+                "INSERT INTO cooking_log (recipe_id, cooked_on) VALUES ($1, CURRENT_DATE)",
+                source.recipe_id
+            )
+            .execute(&repository.pool)
+            .await
+            .expect("Failed to log cooking");
+
+            let_assert!(
+                Ok(merged) = repository
+                    .merge_recipes(
+                        target.recipe_id,
+                        source.recipe_id,
+                        create_test_recipe("Lasagne", MealType::Dinner),
+                    )
+                    .await
+            );
+
+            check!(merged.recipe_id == target.recipe_id);
+            check!(merged.last_cooked_at.is_some());
+            check!(merged.ingredients.len() == 2);
+
+            let_assert!(
+                Err(GetRecipeError::NotFound) = repository.get_recipe(source.recipe_id).await
+            );
+            let_assert!(Ok(forks) = repository.list_forks(target.recipe_id).await);
+            check!(forks.len() == 1);
+            check!(forks[0].recipe_id == fork.recipe_id);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_takes_the_place_of_the_source_when_merging_into_a_fork(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(source) = repository
+                    .create_recipe(create_test_recipe("Pizza", MealType::Dinner))
+                    .await
+            );
+            let_assert!(Ok(fork) = repository.fork_recipe(source.recipe_id).await);
+            let_assert!(Ok(target) = repository.fork_recipe(fork.recipe_id).await);
+
+            let_assert!(
+                Ok(merged) = repository
+                    .merge_recipes(
+                        target.recipe_id,
+                        source.recipe_id,
+                        create_test_recipe("Pizza", MealType::Dinner),
+                    )
+                    .await
+            );
+
+            check!(merged.forked_from.is_none());
+            let_assert!(Ok(forks) = repository.list_forks(target.recipe_id).await);
+            check!(forks.len() == 1);
+            check!(forks[0].recipe_id == fork.recipe_id);
         }
     }
 
@@ -655,6 +3029,15 @@ mod tests {
                 recipe_name: Some("Nonexistent Recipe".to_string()),
                 ingredient_name: None,
                 meal_type: None,
+                include_ingredients: true,
+                archived: false,
+                tags: vec![],
+                sort: vec![],
+                created_after: None,
+                created_before: None,
+                updated_after: None,
+                keywords: vec![],
+                weights: SearchWeights::default(),
             };
 
             let result = repository.search_recipes(args).await;
@@ -663,6 +3046,104 @@ mod tests {
             check!(recipes.is_empty());
         }
 
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_ignores_accents(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe = MutableRecipeEntity {
+                ingredients: vec![create_test_ingredient(
+                    "Crème fraîche",
+                    200.0,
+                    QuantityType::Gram,
+                )],
+                ..create_test_recipe("Crème brûlée", MealType::Dinner)
+            };
+            let_assert!(Ok(_) = repository.create_recipe(recipe).await);
+
+            for include_ingredients in [true, false] {
+                let args = SearchRecipesArguments {
+                    recipe_name: Some("creme brulee".to_string()),
+                    ingredient_name: Some("CREME FRAICHE".to_string()),
+                    meal_type: None,
+                    include_ingredients,
+                    archived: false,
+                    tags: vec![],
+                    sort: vec![],
+                    created_after: None,
+                    created_before: None,
+                    updated_after: None,
+                    keywords: vec![],
+                    weights: SearchWeights::default(),
+                };
+
+                let_assert!(Ok(recipes) = repository.search_recipes(args).await);
+                check!(recipes.len() == 1);
+                check!(recipes[0].name == "Crème brûlée");
+            }
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_applies_the_sort_keys_in_order(pool: PgPool) {
+            let repository = Postgres::new(pool.clone());
+
+            let mut recipe_ids = vec![];
+            for (name, minutes) in [("Stew", Some(90)), ("Soup", None), ("Salad", Some(10))] {
+                let recipe = MutableRecipeEntity {
+                    cooking_time: minutes.map(|minutes| Duration::from_secs(minutes * 60)),
+                    ..create_test_recipe(name, MealType::Dinner)
+                };
+                let_assert!(Ok(recipe) = repository.create_recipe(recipe).await);
+                recipe_ids.push(recipe.recipe_id);
+            }
+            let [stew, soup, salad] = recipe_ids[..] else {
+                unreachable!()
+            };
+
+            sqlx::query!(
+                r#"
+                    INSERT INTO cooking_log (recipe_id, cooked_on)
+                    VALUES ($1, '2025-01-10'), ($2, '2025-03-01'), ($3, '2025-03-01')
+                "#,
+                stew,
+                soup,
+                salad
+            )
+            .execute(&pool)
+            .await
+            .expect("Failed to log cooking");
+
+            for (sort, expected) in [
+                (vec![SearchSort::CookingTime], [salad, stew, soup]),
+                (vec![SearchSort::LastCooked], [soup, salad, stew]),
+                (
+                    vec![SearchSort::LastCooked, SearchSort::CookingTime],
+                    [salad, soup, stew],
+                ),
+            ] {
+                let args = SearchRecipesArguments {
+                    recipe_name: None,
+                    ingredient_name: None,
+                    meal_type: None,
+                    include_ingredients: false,
+                    archived: false,
+                    tags: vec![],
+                    sort,
+                    created_after: None,
+                    created_before: None,
+                    updated_after: None,
+                    keywords: vec![],
+                    weights: SearchWeights::default(),
+                };
+
+                let_assert!(Ok(recipes) = repository.search_recipes(args).await);
+                let ids = recipes
+                    .iter()
+                    .map(|recipe| recipe.recipe_id)
+                    .collect::<Vec<_>>();
+                check!(ids == expected);
+            }
+        }
+
         #[sqlx::test(migrator = "super::MIGRATOR")]
         async fn it_finds_recipe_by_exact_name(pool: PgPool) {
             let repository = Postgres::new(pool);
@@ -677,6 +3158,15 @@ mod tests {
                 recipe_name: Some("Pancakes".to_string()),
                 ingredient_name: None,
                 meal_type: None,
+                include_ingredients: true,
+                archived: false,
+                tags: vec![],
+                sort: vec![],
+                created_after: None,
+                created_before: None,
+                updated_after: None,
+                keywords: vec![],
+                weights: SearchWeights::default(),
             };
 
             let result = repository.search_recipes(args).await;
@@ -687,6 +3177,86 @@ mod tests {
             check!(matches!(recipes[0].meal_type, MealType::Breakfast));
         }
 
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_filters_by_when_recipes_were_created_and_changed(pool: PgPool) {
+            let repository = Postgres::new(pool.clone());
+
+            let_assert!(
+                Ok(old) = repository
+                    .create_recipe(create_test_recipe("Stew", MealType::Dinner))
+                    .await
+            );
+            let_assert!(
+                Ok(_) = repository
+                    .create_recipe(create_test_recipe("Soup", MealType::Dinner))
+                    .await
+            );
+            sqlx::query!(
+                r#"
+                    UPDATE recipe
+                    SET
+                        created_at = NOW() - INTERVAL '2 days',
+                        updated_at = NOW() - INTERVAL '2 days'
+                    WHERE recipe_id = $1
+                "#,
+                old.recipe_id
+            )
+            .execute(&pool)
+            .await
+            .expect("Failed to backdate recipe");
+
+            let day_ago = chrono::Utc::now() - chrono::Duration::days(1);
+            let search = |created_after, created_before, updated_after| SearchRecipesArguments {
+                recipe_name: None,
+                ingredient_name: None,
+                meal_type: None,
+                include_ingredients: false,
+                archived: false,
+                tags: vec![],
+                sort: vec![],
+                created_after,
+                created_before,
+                updated_after,
+                keywords: vec![],
+                weights: SearchWeights::default(),
+            };
+
+            let_assert!(
+                Ok(recipes) = repository
+                    .search_recipes(search(Some(day_ago), None, None))
+                    .await
+            );
+            let_assert!([recipe] = recipes.as_slice());
+            check!(recipe.name == "Soup");
+
+            let_assert!(
+                Ok(recipes) = repository
+                    .search_recipes(search(None, Some(day_ago), None))
+                    .await
+            );
+            let_assert!([recipe] = recipes.as_slice());
+            check!(recipe.name == "Stew");
+
+            // Changing an ingredient counts as changing the recipe
+            sqlx::query!(
+                r#"
+                    INSERT INTO ingredient (recipe_id, ingredient_order, name, quantity, quantity_type)
+                    VALUES ($1, 0, 'Beef', 500, 'Gram')
+                "#,
+                old.recipe_id
+            )
+            .execute(&pool)
+            .await
+            .expect("Failed to insert ingredient");
+
+            let_assert!(
+                Ok(recipes) = repository
+                    .search_recipes(search(None, None, Some(day_ago)))
+                    .await
+            );
+            check!(recipes.len() == 2);
+        }
+
         #[sqlx::test(migrator = "super::MIGRATOR")]
         async fn it_finds_recipe_by_partial_name(pool: PgPool) {
             let repository = Postgres::new(pool);
@@ -703,6 +3273,15 @@ mod tests {
                 recipe_name: Some("Pancake".to_string()),
                 ingredient_name: None,
                 meal_type: None,
+                include_ingredients: true,
+                archived: false,
+                tags: vec![],
+                sort: vec![],
+                created_after: None,
+                created_before: None,
+                updated_after: None,
+                keywords: vec![],
+                weights: SearchWeights::default(),
             };
 
             let result = repository.search_recipes(args).await;
@@ -726,6 +3305,11 @@ mod tests {
                 ],
                 cooking_time: None,
                 meal_type: MealType::Lunch,
+                video_url: None,
+                video_title: None,
+                video_thumbnail_url: None,
+                pairing: None,
+                pairing_kind: None,
             };
 
             let recipe_without_flour = create_test_recipe("Salad", MealType::Lunch);
@@ -737,6 +3321,15 @@ mod tests {
                 recipe_name: None,
                 ingredient_name: Some("Flour".to_string()),
                 meal_type: None,
+                include_ingredients: true,
+                archived: false,
+                tags: vec![],
+                sort: vec![],
+                created_after: None,
+                created_before: None,
+                updated_after: None,
+                keywords: vec![],
+                weights: SearchWeights::default(),
             };
 
             let result = repository.search_recipes(args).await;
@@ -759,6 +3352,11 @@ mod tests {
                 ],
                 cooking_time: None,
                 meal_type: MealType::Dinner,
+                video_url: None,
+                video_title: None,
+                video_thumbnail_url: None,
+                pairing: None,
+                pairing_kind: None,
             };
 
             let recipe_with_milk = MutableRecipeEntity {
@@ -770,6 +3368,11 @@ mod tests {
                 ],
                 cooking_time: None,
                 meal_type: MealType::Breakfast,
+                video_url: None,
+                video_title: None,
+                video_thumbnail_url: None,
+                pairing: None,
+                pairing_kind: None,
             };
 
             let recipe_without_chocolate = create_test_recipe("Vanilla Pudding", MealType::Dinner);
@@ -782,6 +3385,15 @@ mod tests {
                 recipe_name: None,
                 ingredient_name: Some("Chocolate".to_string()),
                 meal_type: None,
+                include_ingredients: true,
+                archived: false,
+                tags: vec![],
+                sort: vec![],
+                created_after: None,
+                created_before: None,
+                updated_after: None,
+                keywords: vec![],
+                weights: SearchWeights::default(),
             };
 
             let result = repository.search_recipes(args).await;
@@ -808,6 +3420,15 @@ mod tests {
                 recipe_name: None,
                 ingredient_name: None,
                 meal_type: Some(MealType::Breakfast),
+                include_ingredients: true,
+                archived: false,
+                tags: vec![],
+                sort: vec![],
+                created_after: None,
+                created_before: None,
+                updated_after: None,
+                keywords: vec![],
+                weights: SearchWeights::default(),
             };
 
             let result = repository.search_recipes(args).await;
@@ -831,6 +3452,11 @@ mod tests {
                 ],
                 cooking_time: None,
                 meal_type: MealType::Breakfast,
+                video_url: None,
+                video_title: None,
+                video_thumbnail_url: None,
+                pairing: None,
+                pairing_kind: None,
             };
 
             let non_matching_name = MutableRecipeEntity {
@@ -839,6 +3465,11 @@ mod tests {
                 ingredients: vec![create_test_ingredient("Flour", 500.0, QuantityType::Gram)],
                 cooking_time: None,
                 meal_type: MealType::Breakfast,
+                video_url: None,
+                video_title: None,
+                video_thumbnail_url: None,
+                pairing: None,
+                pairing_kind: None,
             };
 
             let non_matching_meal_type = MutableRecipeEntity {
@@ -847,6 +3478,11 @@ mod tests {
                 ingredients: vec![create_test_ingredient("Flour", 100.0, QuantityType::Gram)],
                 cooking_time: None,
                 meal_type: MealType::Dinner,
+                video_url: None,
+                video_title: None,
+                video_thumbnail_url: None,
+                pairing: None,
+                pairing_kind: None,
             };
 
             let_assert!(Ok(_) = repository.create_recipe(matching_recipe).await);
@@ -857,6 +3493,15 @@ mod tests {
                 recipe_name: Some("Pancake".to_string()),
                 ingredient_name: Some("Flour".to_string()),
                 meal_type: Some(MealType::Breakfast),
+                include_ingredients: true,
+                archived: false,
+                tags: vec![],
+                sort: vec![],
+                created_after: None,
+                created_before: None,
+                updated_after: None,
+                keywords: vec![],
+                weights: SearchWeights::default(),
             };
 
             let result = repository.search_recipes(args).await;
@@ -882,6 +3527,15 @@ mod tests {
                 recipe_name: None,
                 ingredient_name: None,
                 meal_type: None,
+                include_ingredients: true,
+                archived: false,
+                tags: vec![],
+                sort: vec![],
+                created_after: None,
+                created_before: None,
+                updated_after: None,
+                keywords: vec![],
+                weights: SearchWeights::default(),
             };
 
             let result = repository.search_recipes(args).await;
@@ -890,6 +3544,33 @@ mod tests {
             check!(recipes.len() == 3);
         }
 
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_still_filters_on_ingredients_when_not_including_them(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe = create_test_recipe("Recipe 1", MealType::Breakfast);
+            let_assert!(Ok(_) = repository.create_recipe(recipe).await);
+
+            let args = SearchRecipesArguments {
+                recipe_name: None,
+                ingredient_name: Some("Ingredient 2".to_string()),
+                meal_type: None,
+                include_ingredients: false,
+                archived: false,
+                tags: vec![],
+                sort: vec![],
+                created_after: None,
+                created_before: None,
+                updated_after: None,
+                keywords: vec![],
+                weights: SearchWeights::default(),
+            };
+
+            let_assert!(Ok(recipes) = repository.search_recipes(args).await);
+            check!(recipes.len() == 1);
+            check!(recipes[0].ingredients.is_empty());
+        }
+
         #[sqlx::test(migrator = "super::MIGRATOR")]
         async fn it_search_is_case_insensitive(pool: PgPool) {
             let repository = Postgres::new(pool);
@@ -904,6 +3585,11 @@ mod tests {
                 )],
                 cooking_time: None,
                 meal_type: MealType::Lunch,
+                video_url: None,
+                video_title: None,
+                video_thumbnail_url: None,
+                pairing: None,
+                pairing_kind: None,
             };
 
             let_assert!(Ok(_) = repository.create_recipe(recipe).await);
@@ -913,6 +3599,15 @@ mod tests {
                 recipe_name: Some("uppercase".to_string()),
                 ingredient_name: None,
                 meal_type: None,
+                include_ingredients: true,
+                archived: false,
+                tags: vec![],
+                sort: vec![],
+                created_after: None,
+                created_before: None,
+                updated_after: None,
+                keywords: vec![],
+                weights: SearchWeights::default(),
             };
 
             let result = repository.search_recipes(args).await;
@@ -924,11 +3619,502 @@ mod tests {
                 recipe_name: None,
                 ingredient_name: Some("uppercase ingredient".to_string()),
                 meal_type: None,
+                include_ingredients: true,
+                archived: false,
+                tags: vec![],
+                sort: vec![],
+                created_after: None,
+                created_before: None,
+                updated_after: None,
+                keywords: vec![],
+                weights: SearchWeights::default(),
             };
 
             let result = repository.search_recipes(args).await;
             let_assert!(Ok(recipes) = result);
             check!(recipes.len() == 1);
         }
+
+        /// Plans the statement `search_recipes` prepared, by searching on a single connection and
+        /// explaining the prepared statement left behind on it. The tables are filled first, as
+        /// the planner would always prefer scanning them whole when nearly empty.
+        async fn explain(
+            pool: &PgPool,
+            recipe_name: Option<&str>,
+            ingredient_name: Option<&str>,
+        ) -> String {
+            let pool = sqlx::postgres::PgPoolOptions::new()
+                .max_connections(1)
+                .connect_with(pool.connect_options().as_ref().clone())
+                .await
+                .expect("Failed to connect");
+            let repository = Postgres::new(pool.clone());
+
+            sqlx::raw_sql(
+                r#"
+                    INSERT INTO recipe (name, meal_type)
+                    SELECT 'Recipe ' || n, 'Dinner' FROM GENERATE_SERIES(1, 1000) n;
+
+                    INSERT INTO ingredient (recipe_id, ingredient_order, name, quantity, quantity_type)
+                    SELECT recipe_id, 0, 'Ingredient ' || recipe_id, 1, 'Count' FROM recipe;
+
+                    ANALYZE recipe, ingredient;
+                "#,
+            )
+            .execute(&pool)
+            .await
+            .expect("Failed to fill tables");
+
+            let args = SearchRecipesArguments {
+                recipe_name: recipe_name.map(str::to_string),
+                ingredient_name: ingredient_name.map(str::to_string),
+                meal_type: None,
+                include_ingredients: false,
+                archived: false,
+                tags: vec![],
+                sort: vec![],
+                created_after: None,
+                created_before: None,
+                updated_after: None,
+                keywords: vec![],
+                weights: SearchWeights::default(),
+            };
+            let_assert!(Ok(_) = repository.search_recipes(args).await);
+
+            let mut conn = pool.acquire().await.expect("Failed to acquire connection");
+
+            let statement: String = sqlx::query_scalar(
+                "SELECT name FROM pg_prepared_statements WHERE statement LIKE $1",
+            )
+            .bind("%FROM recipe r%kw.score DESC%")
+            .fetch_one(&mut *conn)
+            .await
+            .expect("Failed to find the search statement");
+
+            let literal = |value: Option<&str>| {
+                value.map_or("NULL".to_string(), |value| format!("'{value}'"))
+            };
+            let plan: Vec<String> = sqlx::query_scalar(&format!(
+                "EXPLAIN EXECUTE {statement}({}, {}, NULL, FALSE, '{{}}', '{{}}', NULL, NULL, NULL, '{{}}', 1, 1, 1, 1)",
+                literal(recipe_name),
+                literal(ingredient_name),
+            ))
+            .fetch_all(&mut *conn)
+            .await
+            .expect("Failed to explain search");
+
+            plan.join("\n")
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_uses_the_trigram_index_on_recipe_names(pool: PgPool) {
+            let plan = explain(&pool, Some("pasta"), None).await;
+
+            check!(plan.contains("recipe_name_trgm_idx"), "{plan}");
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_uses_the_trigram_index_on_ingredient_names(pool: PgPool) {
+            let plan = explain(&pool, None, Some("tomato")).await;
+
+            check!(plan.contains("ingredient_name_trgm_idx"), "{plan}");
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_searches_archived_recipes_apart(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe = create_test_recipe("Pancakes", MealType::Breakfast);
+            let_assert!(Ok(recipe) = repository.create_recipe(recipe).await);
+            let_assert!(Ok(()) = repository.set_archived(recipe.recipe_id, true).await);
+
+            for archived in [false, true] {
+                let args = SearchRecipesArguments {
+                    recipe_name: Some("Pancakes".to_string()),
+                    ingredient_name: None,
+                    meal_type: None,
+                    include_ingredients: false,
+                    archived,
+                    tags: vec![],
+                    sort: vec![],
+                    created_after: None,
+                    created_before: None,
+                    updated_after: None,
+                    keywords: vec![],
+                    weights: SearchWeights::default(),
+                };
+
+                let_assert!(Ok(recipes) = repository.search_recipes(args).await);
+                check!(recipes.len() == usize::from(archived));
+            }
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_ranks_name_matches_above_description_matches(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let mut soup = create_test_recipe("Tomato soup", MealType::Dinner);
+            soup.description = Some("Serve with basil pesto".to_string());
+            let_assert!(Ok(soup) = repository.create_recipe(soup).await);
+            let_assert!(
+                Ok(pesto) = repository
+                    .create_recipe(create_test_recipe("Pesto", MealType::Dinner))
+                    .await
+            );
+            let_assert!(
+                Ok(_) = repository
+                    .create_recipe(create_test_recipe("Pancakes", MealType::Breakfast))
+                    .await
+            );
+
+            for include_ingredients in [false, true] {
+                let args = SearchRecipesArguments {
+                    recipe_name: Some("pesto".to_string()),
+                    ingredient_name: None,
+                    meal_type: None,
+                    include_ingredients,
+                    archived: false,
+                    tags: vec![],
+                    sort: vec![],
+                    created_after: None,
+                    created_before: None,
+                    updated_after: None,
+                    keywords: vec![],
+                    weights: SearchWeights::default(),
+                };
+
+                let_assert!(Ok(recipes) = repository.search_recipes(args).await);
+                let ids = recipes.iter().map(|r| r.recipe_id).collect::<Vec<_>>();
+                check!(ids == vec![pesto.recipe_id, soup.recipe_id]);
+            }
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_ranks_keyword_matches_by_field(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let mut soup = create_test_recipe("Tomato soup", MealType::Dinner);
+            soup.description = Some("Serve with basil".to_string());
+            let_assert!(Ok(soup) = repository.create_recipe(soup).await);
+            let_assert!(
+                Ok(pesto) = repository
+                    .create_recipe(create_test_recipe("Basil pesto", MealType::Dinner))
+                    .await
+            );
+            let mut salad = create_test_recipe("Caprese", MealType::Lunch);
+            salad.ingredients[0].name = "Basil".to_string();
+            let_assert!(Ok(salad) = repository.create_recipe(salad).await);
+            let_assert!(
+                Ok(_) = repository
+                    .create_recipe(create_test_recipe("Pancakes", MealType::Breakfast))
+                    .await
+            );
+
+            let weights = SearchWeights {
+                name: 4.0,
+                ingredient: 2.0,
+                tag: 2.0,
+                description: 1.0,
+            };
+
+            for include_ingredients in [false, true] {
+                let args = SearchRecipesArguments {
+                    recipe_name: None,
+                    ingredient_name: None,
+                    meal_type: None,
+                    include_ingredients,
+                    archived: false,
+                    tags: vec![],
+                    sort: vec![],
+                    created_after: None,
+                    created_before: None,
+                    updated_after: None,
+                    keywords: vec!["basil".to_string(), "tomato".to_string()],
+                    weights,
+                };
+
+                let_assert!(Ok(recipes) = repository.search_recipes(args).await);
+                let ids = recipes.iter().map(|r| r.recipe_id).collect::<Vec<_>>();
+                check!(ids == vec![soup.recipe_id, pesto.recipe_id, salad.recipe_id]);
+            }
+
+            // Favouring ingredients puts the salad first, while the name and description of the
+            // soup still outweigh the name of the pesto
+            let args = SearchRecipesArguments {
+                recipe_name: None,
+                ingredient_name: None,
+                meal_type: None,
+                include_ingredients: false,
+                archived: false,
+                tags: vec![],
+                sort: vec![],
+                created_after: None,
+                created_before: None,
+                updated_after: None,
+                keywords: vec!["basil".to_string(), "tomato".to_string()],
+                weights: SearchWeights {
+                    ingredient: 8.0,
+                    ..weights
+                },
+            };
+
+            let_assert!(Ok(recipes) = repository.search_recipes(args).await);
+            let ids = recipes.iter().map(|r| r.recipe_id).collect::<Vec<_>>();
+            check!(ids == vec![salad.recipe_id, soup.recipe_id, pesto.recipe_id]);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_still_finds_fields_weighted_zero(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let mut soup = create_test_recipe("Tomato soup", MealType::Dinner);
+            soup.description = Some("Serve with basil".to_string());
+            let_assert!(Ok(soup) = repository.create_recipe(soup).await);
+
+            let args = SearchRecipesArguments {
+                recipe_name: None,
+                ingredient_name: None,
+                meal_type: None,
+                include_ingredients: false,
+                archived: false,
+                tags: vec![],
+                sort: vec![],
+                created_after: None,
+                created_before: None,
+                updated_after: None,
+                keywords: vec!["basil".to_string()],
+                weights: SearchWeights::default(),
+            };
+
+            let_assert!(Ok(recipes) = repository.search_recipes(args).await);
+            let_assert!([recipe] = recipes.as_slice());
+            check!(recipe.recipe_id == soup.recipe_id);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_combines_keywords_with_the_other_filters(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(_) = repository
+                    .create_recipe(create_test_recipe("Basil pesto", MealType::Dinner))
+                    .await
+            );
+            let_assert!(
+                Ok(omelette) = repository
+                    .create_recipe(create_test_recipe("Basil omelette", MealType::Breakfast))
+                    .await
+            );
+
+            let args = SearchRecipesArguments {
+                recipe_name: None,
+                ingredient_name: None,
+                meal_type: Some(MealType::Breakfast),
+                include_ingredients: false,
+                archived: false,
+                tags: vec![],
+                sort: vec![],
+                created_after: None,
+                created_before: None,
+                updated_after: None,
+                keywords: vec!["basil".to_string()],
+                weights: SearchWeights::default(),
+            };
+
+            let_assert!(Ok(recipes) = repository.search_recipes(args).await);
+            let_assert!([recipe] = recipes.as_slice());
+            check!(recipe.recipe_id == omelette.recipe_id);
+        }
+    }
+
+    mod random_recipe {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_returns_not_found_when_no_recipes_exist(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let args = RandomRecipeArguments {
+                meal_type: None,
+                max_cooking_time: None,
+                tags: vec![],
+            };
+
+            let result = repository.random_recipe(args).await;
+
+            let_assert!(Err(RandomRecipeError::NotFound) = result);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_returns_a_recipe_with_its_ingredients(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe = create_test_recipe("Only Recipe", MealType::Lunch);
+            let_assert!(Ok(created) = repository.create_recipe(recipe).await);
+
+            let args = RandomRecipeArguments {
+                meal_type: None,
+                max_cooking_time: None,
+                tags: vec![],
+            };
+
+            let result = repository.random_recipe(args).await;
+
+            let_assert!(Ok(recipe) = result);
+            check!(recipe.recipe_id == created.recipe_id);
+            check!(recipe.ingredients.len() == 2);
+            check!(recipe.ingredients[0].name == "Ingredient 1");
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_only_picks_recipes_matching_the_filters(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let quick_dinner = MutableRecipeEntity {
+                cooking_time: Some(Duration::from_secs(900)),
+                ..create_test_recipe("Quick Dinner", MealType::Dinner)
+            };
+            let slow_dinner = create_test_recipe("Slow Dinner", MealType::Dinner);
+            let quick_lunch = MutableRecipeEntity {
+                cooking_time: Some(Duration::from_secs(600)),
+                ..create_test_recipe("Quick Lunch", MealType::Lunch)
+            };
+
+            let_assert!(Ok(_) = repository.create_recipe(quick_dinner).await);
+            let_assert!(Ok(_) = repository.create_recipe(slow_dinner).await);
+            let_assert!(Ok(_) = repository.create_recipe(quick_lunch).await);
+
+            for _ in 0..10 {
+                let args = RandomRecipeArguments {
+                    meal_type: Some(MealType::Dinner),
+                    max_cooking_time: Some(Duration::from_secs(1800)),
+                    tags: vec![],
+                };
+
+                let result = repository.random_recipe(args).await;
+
+                let_assert!(Ok(recipe) = result);
+                check!(recipe.name == "Quick Dinner");
+            }
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_excludes_recipes_without_cooking_time_when_filtering_on_it(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe = MutableRecipeEntity {
+                cooking_time: None,
+                ..create_test_recipe("Untimed", MealType::Dinner)
+            };
+            let_assert!(Ok(_) = repository.create_recipe(recipe).await);
+
+            let args = RandomRecipeArguments {
+                meal_type: None,
+                max_cooking_time: Some(Duration::from_secs(3600)),
+                tags: vec![],
+            };
+
+            let result = repository.random_recipe(args).await;
+
+            let_assert!(Err(RandomRecipeError::NotFound) = result);
+        }
+    }
+
+    mod popular_recipes {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_returns_empty_list_when_nothing_was_viewed(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let recipe = create_test_recipe("Unseen", MealType::Lunch);
+            let_assert!(Ok(_) = repository.create_recipe(recipe).await);
+
+            let result = repository.popular_recipes(30, 10).await;
+
+            let_assert!(Ok(recipes) = result);
+            check!(recipes.is_empty());
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_orders_recipes_by_accumulated_views(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(pancakes) = repository
+                    .create_recipe(create_test_recipe("Pancakes", MealType::Breakfast))
+                    .await
+            );
+            let_assert!(
+                Ok(pasta) = repository
+                    .create_recipe(create_test_recipe("Pasta", MealType::Dinner))
+                    .await
+            );
+
+            let_assert!(
+                Ok(()) = repository
+                    .record_views(vec![(pancakes.recipe_id, 2), (pasta.recipe_id, 3)])
+                    .await
+            );
+            let_assert!(Ok(()) = repository.record_views(vec![(pancakes.recipe_id, 4)]).await);
+
+            let result = repository.popular_recipes(30, 10).await;
+
+            let_assert!(Ok(recipes) = result);
+            check!(recipes.len() == 2);
+            check!(recipes[0].recipe.name == "Pancakes");
+            check!(recipes[0].views == 6);
+            check!(recipes[0].recipe.ingredients.len() == 2);
+            check!(recipes[1].recipe.name == "Pasta");
+            check!(recipes[1].views == 3);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_ignores_views_outside_of_the_window(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(recipe) = repository
+                    .create_recipe(create_test_recipe("Old Favourite", MealType::Dinner))
+                    .await
+            );
+
+            sqlx::query!(
+                "INSERT INTO recipe_view (recipe_id, viewed_on, view_count) VALUES ($1, CURRENT_DATE - 40, 10)",
+                recipe.recipe_id
+            )
+            .execute(&repository.pool)
+            .await
+            .expect("Failed to insert view");
+
+            let_assert!(Ok(recipes) = repository.popular_recipes(30, 10).await);
+            check!(recipes.is_empty());
+
+            let_assert!(Ok(recipes) = repository.popular_recipes(60, 10).await);
+            check!(recipes.len() == 1);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_drops_views_for_deleted_recipes(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(recipe) = repository
+                    .create_recipe(create_test_recipe("Gone", MealType::Dinner))
+                    .await
+            );
+            let_assert!(Ok(()) = repository.record_views(vec![(recipe.recipe_id, 1)]).await);
+            let_assert!(
+                Ok(_) = repository
+                    .delete_recipe(recipe.recipe_id, DeletionEntity::default())
+                    .await
+            );
+
+            let result = repository.record_views(vec![(recipe.recipe_id, 1)]).await;
+
+            let_assert!(Ok(()) = result);
+            let_assert!(Ok(recipes) = repository.popular_recipes(30, 10).await);
+            check!(recipes.is_empty());
+        }
     }
 }
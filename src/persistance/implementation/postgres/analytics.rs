@@ -0,0 +1,142 @@
+use chrono::NaiveDate;
+use eyre::Context;
+
+use super::Postgres;
+use crate::persistance::analytics::{
+    AnalyticsRepository, GetUsageError, RecordUsageError, UsageCountEntity, UsageEntity,
+};
+
+impl AnalyticsRepository for Postgres {
+    async fn record_usage(
+        &self,
+        routes: Vec<(String, i64)>,
+        search_terms: Vec<(String, i64)>,
+    ) -> Result<(), RecordUsageError> {
+        let (routes, request_counts): (Vec<String>, Vec<i64>) = routes.into_iter().unzip();
+        let (terms, search_counts): (Vec<String>, Vec<i64>) = search_terms.into_iter().unzip();
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .wrap_err("Failed to open transaction")?;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO route_usage (route, used_on, request_count)
+                SELECT u.route, CURRENT_DATE, u.request_count
+                FROM UNNEST($1::TEXT[], $2::BIGINT[]) AS u (route, request_count)
+                ON CONFLICT (route, used_on)
+                DO UPDATE SET request_count = route_usage.request_count + EXCLUDED.request_count
+            "#,
+            &routes,
+            &request_counts,
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to record route usage")?;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO search_term_usage (term, used_on, search_count)
+                SELECT u.term, CURRENT_DATE, u.search_count
+                FROM UNNEST($1::TEXT[], $2::BIGINT[]) AS u (term, search_count)
+                ON CONFLICT (term, used_on)
+                DO UPDATE SET search_count = search_term_usage.search_count + EXCLUDED.search_count
+            "#,
+            &terms,
+            &search_counts,
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to record search term usage")?;
+
+        tx.commit().await.wrap_err("Failed to commit transaction")?;
+
+        Ok(())
+    }
+
+    async fn get_usage(
+        &self,
+        since: NaiveDate,
+        min_searches: i64,
+        limit: i64,
+    ) -> Result<UsageEntity, GetUsageError> {
+        let routes = sqlx::query_as!(
+            UsageCountEntity,
+            r#"
+                SELECT route AS key, SUM(request_count)::BIGINT AS "count!"
+                FROM route_usage
+                WHERE used_on >= $1
+                GROUP BY route
+                ORDER BY 2 DESC, route
+                LIMIT $2
+            "#,
+            since,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to get route usage")?;
+
+        let search_terms = sqlx::query_as!(
+            UsageCountEntity,
+            r#"
+                SELECT term AS key, SUM(search_count)::BIGINT AS "count!"
+                FROM search_term_usage
+                WHERE used_on >= $1
+                GROUP BY term
+                HAVING SUM(search_count) >= $2::BIGINT
+                ORDER BY 2 DESC, term
+                LIMIT $3
+            "#,
+            since,
+            min_searches,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to get search term usage")?;
+
+        Ok(UsageEntity {
+            routes,
+            search_terms,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert2::{check, let_assert};
+    use chrono::Utc;
+    use sqlx::PgPool;
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_adds_up_usage_and_leaves_out_rare_search_terms(pool: PgPool) {
+        let repository = Postgres::new(pool);
+
+        for _ in 0..2 {
+            let_assert!(
+                Ok(()) = repository
+                    .record_usage(
+                        vec![("GET /recipes".to_string(), 3)],
+                        vec![("soup".to_string(), 2), ("grandma's stew".to_string(), 1)],
+                    )
+                    .await
+            );
+        }
+
+        let today = Utc::now().date_naive();
+        let_assert!(Ok(usage) = repository.get_usage(today, 3, 10).await);
+
+        let_assert!([route] = usage.routes.as_slice());
+        check!(route.key == "GET /recipes");
+        check!(route.count == 6);
+        let_assert!([term] = usage.search_terms.as_slice());
+        check!(term.key == "soup");
+        check!(term.count == 4);
+    }
+}
@@ -0,0 +1,204 @@
+use eyre::Context;
+
+use super::Postgres;
+use crate::persistance::cooking_log::{
+    CookingHistoryError, CookingLogEntity, CookingLogRepository, LogCookingError,
+    NewCookingLogEntity,
+};
+
+impl CookingLogRepository for Postgres {
+    async fn log_cooking(
+        &self,
+        recipe_id: i32,
+        entity: NewCookingLogEntity,
+    ) -> Result<CookingLogEntity, LogCookingError> {
+        // Selecting from `recipe` lets a missing recipe show up as an empty result rather than a
+        // foreign key violation
+        let row = sqlx::query_as!(
+            CookingLogEntity,
+            r#"
+                INSERT INTO cooking_log (recipe_id, cooked_on, note)
+                SELECT recipe_id, $2, $3 FROM recipe WHERE recipe_id = $1
+                RETURNING cooking_log_id, recipe_id, cooked_on, note
+            "#,
+            recipe_id,
+            entity.cooked_on,
+            entity.note,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .wrap_err("Failed to insert cooking log")?
+        .ok_or(LogCookingError::NotFound)?;
+
+        Ok(row)
+    }
+
+    async fn cooking_history(
+        &self,
+        recipe_id: i32,
+    ) -> Result<Vec<CookingLogEntity>, CookingHistoryError> {
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM recipe WHERE recipe_id = $1) AS "exists!""#,
+            recipe_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .wrap_err("Failed to check if recipe exists")?;
+
+        if !exists {
+            return Err(CookingHistoryError::NotFound);
+        }
+
+        let data = sqlx::query_as!(
+            CookingLogEntity,
+            r#"
+                SELECT cooking_log_id, recipe_id, cooked_on, note
+                FROM cooking_log
+                WHERE recipe_id = $1
+                ORDER BY cooked_on DESC, cooking_log_id DESC
+            "#,
+            recipe_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to get cooking history")?;
+
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistance::{
+        implementation::postgres::tests::create_minimal_recipe, recipe::RecipeRepository,
+    };
+    use assert2::{check, let_assert};
+    use chrono::NaiveDate;
+    use sqlx::PgPool;
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).expect("Invalid test date")
+    }
+
+    mod log_cooking {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_logs_cooking_for_existing_recipe(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(recipe) = repository
+                    .create_recipe(create_minimal_recipe("Stew"))
+                    .await
+            );
+
+            let result = repository
+                .log_cooking(
+                    recipe.recipe_id,
+                    NewCookingLogEntity {
+                        cooked_on: date(2025, 3, 14),
+                        note: Some("Added extra paprika".to_string()),
+                    },
+                )
+                .await;
+
+            let_assert!(Ok(log) = result);
+            check!(log.cooking_log_id > 0);
+            check!(log.recipe_id == recipe.recipe_id);
+            check!(log.cooked_on == date(2025, 3, 14));
+            check!(log.note == Some("Added extra paprika".to_string()));
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_returns_not_found_error_for_nonexistent_recipe(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let result = repository
+                .log_cooking(
+                    99999,
+                    NewCookingLogEntity {
+                        cooked_on: date(2025, 3, 14),
+                        note: None,
+                    },
+                )
+                .await;
+
+            let_assert!(Err(LogCookingError::NotFound) = result);
+        }
+    }
+
+    mod cooking_history {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_lists_history_most_recent_first(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(recipe) = repository
+                    .create_recipe(create_minimal_recipe("Stew"))
+                    .await
+            );
+            let_assert!(
+                Ok(other) = repository
+                    .create_recipe(create_minimal_recipe("Soup"))
+                    .await
+            );
+
+            for (recipe_id, cooked_on) in [
+                (recipe.recipe_id, date(2025, 1, 1)),
+                (recipe.recipe_id, date(2025, 2, 1)),
+                (other.recipe_id, date(2025, 3, 1)),
+            ] {
+                let entity = NewCookingLogEntity {
+                    cooked_on,
+                    note: None,
+                };
+                let_assert!(Ok(_) = repository.log_cooking(recipe_id, entity).await);
+            }
+
+            let result = repository.cooking_history(recipe.recipe_id).await;
+
+            let_assert!(Ok(history) = result);
+            check!(history.len() == 2);
+            check!(history[0].cooked_on == date(2025, 2, 1));
+            check!(history[1].cooked_on == date(2025, 1, 1));
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_exposes_the_last_cooked_date_on_recipes(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(recipe) = repository
+                    .create_recipe(create_minimal_recipe("Stew"))
+                    .await
+            );
+            check!(recipe.last_cooked_at == None);
+
+            for cooked_on in [date(2025, 2, 1), date(2025, 1, 1)] {
+                let entity = NewCookingLogEntity {
+                    cooked_on,
+                    note: None,
+                };
+                let_assert!(Ok(_) = repository.log_cooking(recipe.recipe_id, entity).await);
+            }
+
+            let_assert!(Ok(recipes) = repository.list_recipes(true, false).await);
+            check!(recipes[0].last_cooked_at == Some(date(2025, 2, 1)));
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_returns_not_found_error_for_nonexistent_recipe(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let result = repository.cooking_history(99999).await;
+
+            let_assert!(Err(CookingHistoryError::NotFound) = result);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use eyre::Context;
+
+use super::Postgres;
+use crate::persistance::deletion::{DeletionRepository, ListDeletionsError, RecipeDeletionEntity};
+
+impl DeletionRepository for Postgres {
+    async fn list_deletions(
+        &self,
+        recipe_id: Option<i32>,
+        limit: i64,
+    ) -> Result<Vec<RecipeDeletionEntity>, ListDeletionsError> {
+        let data = sqlx::query_as!(
+            RecipeDeletionEntity,
+            r#"
+                SELECT deletion_id, recipe_id, recipe_name, deleted_at, actor, ip_address, reason
+                FROM recipe_deletion
+                WHERE $1::INTEGER IS NULL OR recipe_id = $1
+                ORDER BY deleted_at DESC, deletion_id DESC
+                LIMIT $2
+            "#,
+            recipe_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to list deletions")?;
+
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistance::{
+        deletion::DeletionEntity, implementation::postgres::tests::create_minimal_recipe,
+        recipe::RecipeRepository,
+    };
+    use assert2::{check, let_assert};
+    use sqlx::PgPool;
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_records_who_deleted_a_recipe(pool: PgPool) {
+        let repository = Postgres::new(pool);
+
+        let_assert!(
+            Ok(stew) = repository
+                .create_recipe(create_minimal_recipe("Stew"))
+                .await
+        );
+        let_assert!(
+            Ok(soup) = repository
+                .create_recipe(create_minimal_recipe("Soup"))
+                .await
+        );
+
+        let deletion = DeletionEntity {
+            actor: Some("sam".to_string()),
+            ip_address: Some("192.0.2.4".to_string()),
+            reason: Some("Entered twice".to_string()),
+        };
+        let_assert!(Ok(_) = repository.delete_recipe(stew.recipe_id, deletion).await);
+        let_assert!(
+            Ok(_) = repository
+                .delete_recipe(soup.recipe_id, DeletionEntity::default())
+                .await
+        );
+
+        let_assert!(Ok(deletions) = repository.list_deletions(None, 10).await);
+        let_assert!([latest, first] = deletions.as_slice());
+        check!(latest.recipe_name == "Soup");
+        check!(latest.actor.is_none());
+        check!(first.recipe_id == stew.recipe_id);
+        check!(first.recipe_name == "Stew");
+        check!(first.actor.as_deref() == Some("sam"));
+        check!(first.ip_address.as_deref() == Some("192.0.2.4"));
+        check!(first.reason.as_deref() == Some("Entered twice"));
+
+        let_assert!(Ok(deletions) = repository.list_deletions(Some(stew.recipe_id), 10).await);
+        check!(deletions.len() == 1);
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_records_nothing_when_the_recipe_does_not_exist(pool: PgPool) {
+        let repository = Postgres::new(pool);
+
+        let_assert!(
+            Err(_) = repository
+                .delete_recipe(42, DeletionEntity::default())
+                .await
+        );
+
+        let_assert!(Ok(deletions) = repository.list_deletions(None, 10).await);
+        check!(deletions.is_empty());
+    }
+}
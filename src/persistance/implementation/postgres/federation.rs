@@ -0,0 +1,649 @@
+use eyre::Context;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::{Postgres, create_ingredients, outbox::record_event};
+use crate::persistance::{
+    federation::{
+        FederatedRecipesError, FederationRepository, ListInstancesError, MirrorRecipesError,
+        MirrorSummaryEntity, RecipeOriginEntity, RecipeOriginError, RegisterInstanceError,
+        RemoteInstanceEntity, RemoteRecipeEntity, RemoveInstanceError, SetFederationOptOutError,
+    },
+    outbox::RecipeEventKind,
+    recipe::{
+        GetRecipesError, MealType, MutableRecipeEntity, PairingKind, RecipeEntity, RecipeRepository,
+    },
+};
+
+/// Hash of everything a remote recipe consists of, so syncs only update the local copies of
+/// recipes which changed on the remote. The recipe is written out as JSON with sorted keys before
+/// hashing, which keeps the hash the same across builds and Rust versions.
+fn fingerprint(recipe: &MutableRecipeEntity) -> i64 {
+    let canonical = serde_json::json!({
+        "name": recipe.name,
+        "description": recipe.description,
+        "ingredients": recipe.ingredients,
+        "cooking_time": recipe.cooking_time.map(|time| time.as_secs()),
+        "meal_type": recipe.meal_type,
+        "video_url": recipe.video_url,
+        "video_title": recipe.video_title,
+        "video_thumbnail_url": recipe.video_thumbnail_url,
+        "pairing": recipe.pairing,
+        "pairing_kind": recipe.pairing_kind,
+    });
+
+    let digest = Sha256::digest(canonical.to_string());
+    i64::from_be_bytes(
+        digest[..8]
+            .try_into()
+            .expect("SHA-256 digests are 32 bytes"),
+    )
+}
+
+impl FederationRepository for Postgres {
+    async fn register_instance(
+        &self,
+        url: String,
+        token: Option<Vec<u8>>,
+    ) -> Result<RemoteInstanceEntity, RegisterInstanceError> {
+        let row = sqlx::query_as!(
+            RemoteInstanceEntity,
+            r#"
+                INSERT INTO remote_instance (url, token)
+                VALUES ($1, $2)
+                ON CONFLICT (url) DO NOTHING
+                RETURNING remote_instance_id, url, token, created_at, last_synced_at
+            "#,
+            url,
+            token,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .wrap_err("Failed to insert remote instance")?
+        .ok_or(RegisterInstanceError::AlreadyRegistered)?;
+
+        Ok(row)
+    }
+
+    async fn list_instances(&self) -> Result<Vec<RemoteInstanceEntity>, ListInstancesError> {
+        let rows = sqlx::query_as!(
+            RemoteInstanceEntity,
+            r#"
+                SELECT remote_instance_id, url, token, created_at, last_synced_at
+                FROM remote_instance
+                ORDER BY remote_instance_id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to get remote instances")?;
+
+        Ok(rows)
+    }
+
+    async fn remove_instance(&self, remote_instance_id: i32) -> Result<(), RemoveInstanceError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .wrap_err("Failed to open transaction")?;
+
+        sqlx::query!(
+            "DELETE FROM mirrored_recipe WHERE remote_instance_id = $1",
+            remote_instance_id
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to delete mirrored recipes")?;
+
+        let result = sqlx::query!(
+            "DELETE FROM remote_instance WHERE remote_instance_id = $1",
+            remote_instance_id
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to delete remote instance")?;
+
+        if result.rows_affected() == 0 {
+            return Err(RemoveInstanceError::NotFound);
+        }
+
+        tx.commit().await.wrap_err("Failed to commit transaction")?;
+        Ok(())
+    }
+
+    async fn federated_recipes(&self) -> Result<Vec<RecipeEntity>, FederatedRecipesError> {
+        // Mirrored recipes are never published again, which would otherwise make two instances
+        // mirroring each other copy the same recipes back and forth forever
+        let recipe_ids = sqlx::query_scalar!(
+            r#"
+                SELECT recipe_id
+                FROM recipe r
+                WHERE federate
+                AND NOT EXISTS (SELECT 1 FROM mirrored_recipe mr WHERE mr.recipe_id = r.recipe_id)
+                ORDER BY recipe_id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to get federated recipe ids")?;
+
+        self.get_recipes(recipe_ids, true)
+            .await
+            .map_err(|error| match error {
+                GetRecipesError::Unknown(report) => FederatedRecipesError::Unknown(report),
+            })
+    }
+
+    async fn mirror_recipes(
+        &self,
+        remote_instance_id: i32,
+        recipes: Vec<RemoteRecipeEntity>,
+    ) -> Result<MirrorSummaryEntity, MirrorRecipesError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .wrap_err("Failed to open transaction")?;
+
+        // Locking the instance keeps two syncs of the same instance from creating a recipe twice
+        sqlx::query_scalar!(
+            "SELECT remote_instance_id FROM remote_instance WHERE remote_instance_id = $1 FOR UPDATE",
+            remote_instance_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .wrap_err("Failed to lock remote instance")?
+        .ok_or(MirrorRecipesError::NotFound)?;
+
+        let mut summary = MirrorSummaryEntity::default();
+
+        for RemoteRecipeEntity {
+            remote_public_id,
+            recipe,
+        } in &recipes
+        {
+            let fingerprint = fingerprint(recipe);
+
+            let mirrored = sqlx::query!(
+                r#"
+                    SELECT recipe_id, fingerprint, detached
+                    FROM mirrored_recipe
+                    WHERE remote_instance_id = $1 AND remote_public_id = $2
+                "#,
+                remote_instance_id,
+                remote_public_id
+            )
+            .fetch_optional(&mut *tx)
+            .await
+            .wrap_err("Failed to get mirrored recipe")?;
+
+            match mirrored {
+                Some(mirrored) => {
+                    let Some(recipe_id) = mirrored.recipe_id.filter(|_| !mirrored.detached) else {
+                        summary.skipped += 1;
+                        continue;
+                    };
+
+                    if mirrored.fingerprint == fingerprint {
+                        continue;
+                    }
+
+                    update_mirror(&mut tx, recipe_id, recipe).await?;
+
+                    sqlx::query!(
+                        r#"
+                            UPDATE mirrored_recipe SET fingerprint = $3
+                            WHERE remote_instance_id = $1 AND remote_public_id = $2
+                        "#,
+                        remote_instance_id,
+                        remote_public_id,
+                        fingerprint
+                    )
+                    .execute(&mut *tx)
+                    .await
+                    .wrap_err("Failed to update mirrored recipe")?;
+
+                    summary.updated += 1;
+                }
+                None => {
+                    // A recipe with the same public id was written here, or copied here some
+                    // other way, so it is not ours to overwrite
+                    let exists = sqlx::query_scalar!(
+                        r#"SELECT EXISTS (SELECT 1 FROM recipe WHERE public_id = $1) AS "exists!""#,
+                        remote_public_id
+                    )
+                    .fetch_one(&mut *tx)
+                    .await
+                    .wrap_err("Failed to check for a conflicting recipe")?;
+
+                    if exists {
+                        summary.skipped += 1;
+                        continue;
+                    }
+
+                    let recipe_id = insert_mirror(&mut tx, recipe).await?;
+
+                    sqlx::query!(
+                        r#"
+                            INSERT INTO mirrored_recipe (
+                                remote_instance_id,
+                                remote_public_id,
+                                recipe_id,
+                                fingerprint
+                            ) VALUES ($1, $2, $3, $4)
+                        "#,
+                        remote_instance_id,
+                        remote_public_id,
+                        recipe_id,
+                        fingerprint
+                    )
+                    .execute(&mut *tx)
+                    .await
+                    .wrap_err("Failed to insert mirrored recipe")?;
+
+                    summary.created += 1;
+                }
+            }
+        }
+
+        let published = recipes
+            .iter()
+            .map(|recipe| recipe.remote_public_id)
+            .collect::<Vec<Uuid>>();
+
+        let detached = sqlx::query!(
+            r#"
+                UPDATE mirrored_recipe SET detached = TRUE
+                WHERE remote_instance_id = $1
+                AND NOT (remote_public_id = ANY($2))
+                AND recipe_id IS NOT NULL
+                AND NOT detached
+            "#,
+            remote_instance_id,
+            &published
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to detach removed recipes")?;
+        summary.detached = detached.rows_affected();
+
+        sqlx::query!(
+            "UPDATE remote_instance SET last_synced_at = NOW() WHERE remote_instance_id = $1",
+            remote_instance_id
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to update last sync")?;
+
+        tx.commit().await.wrap_err("Failed to commit transaction")?;
+
+        Ok(summary)
+    }
+
+    async fn recipe_origin(
+        &self,
+        recipe_id: i32,
+    ) -> Result<Option<RecipeOriginEntity>, RecipeOriginError> {
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM recipe WHERE recipe_id = $1) AS "exists!""#,
+            recipe_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .wrap_err("Failed to check if recipe exists")?;
+
+        if !exists {
+            return Err(RecipeOriginError::NotFound);
+        }
+
+        let origin = sqlx::query_as!(
+            RecipeOriginEntity,
+            r#"
+                SELECT mr.remote_instance_id, ri.url, mr.remote_public_id, mr.detached
+                FROM mirrored_recipe mr
+                JOIN remote_instance ri ON ri.remote_instance_id = mr.remote_instance_id
+                WHERE mr.recipe_id = $1
+            "#,
+            recipe_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .wrap_err("Failed to get recipe origin")?;
+
+        Ok(origin)
+    }
+
+    async fn set_federation_opt_out(
+        &self,
+        recipe_id: i32,
+        opt_out: bool,
+    ) -> Result<(), SetFederationOptOutError> {
+        let result = sqlx::query!(
+            "UPDATE recipe SET federate = $2 WHERE recipe_id = $1",
+            recipe_id,
+            !opt_out
+        )
+        .execute(&self.pool)
+        .await
+        .wrap_err("Failed to update federation opt-out")?;
+
+        if result.rows_affected() == 0 {
+            return Err(SetFederationOptOutError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+async fn insert_mirror(
+    tx: &mut sqlx::PgTransaction<'_>,
+    recipe: &MutableRecipeEntity,
+) -> eyre::Result<i32> {
+    let recipe_id = sqlx::query_scalar!(
+        r#"
+            INSERT INTO recipe (
+                name,
+                description,
+                cooking_time_secs,
+                meal_type,
+                video_url,
+                video_title,
+                video_thumbnail_url,
+                pairing,
+                pairing_kind
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING recipe_id
+        "#,
+        recipe.name,
+        recipe.description,
+        recipe.cooking_time.map(|time| time.as_secs() as i64),
+        &recipe.meal_type as &MealType,
+        recipe.video_url,
+        recipe.video_title,
+        recipe.video_thumbnail_url,
+        recipe.pairing,
+        recipe.pairing_kind.as_ref() as Option<&PairingKind>,
+    )
+    .fetch_one(&mut **tx)
+    .await
+    .wrap_err("Failed to insert mirrored recipe")?;
+
+    create_ingredients(tx, recipe_id, &recipe.ingredients)
+        .await
+        .wrap_err("Failed to create ingredients")?;
+
+    record_event(tx, RecipeEventKind::Created, recipe_id)
+        .await
+        .wrap_err("Failed to record event")?;
+
+    Ok(recipe_id)
+}
+
+async fn update_mirror(
+    tx: &mut sqlx::PgTransaction<'_>,
+    recipe_id: i32,
+    recipe: &MutableRecipeEntity,
+) -> eyre::Result<()> {
+    sqlx::query!(
+        r#"
+            UPDATE recipe SET
+                name = $2,
+                description = $3,
+                cooking_time_secs = $4,
+                meal_type = $5,
+                video_url = $6,
+                video_title = $7,
+                video_thumbnail_url = $8,
+                pairing = $9,
+                pairing_kind = $10
+            WHERE recipe_id = $1
+        "#,
+        recipe_id,
+        recipe.name,
+        recipe.description,
+        recipe.cooking_time.map(|time| time.as_secs() as i64),
+        &recipe.meal_type as &MealType,
+        recipe.video_url,
+        recipe.video_title,
+        recipe.video_thumbnail_url,
+        recipe.pairing,
+        recipe.pairing_kind.as_ref() as Option<&PairingKind>,
+    )
+    .execute(&mut **tx)
+    .await
+    .wrap_err("Failed to update mirrored recipe")?;
+
+    sqlx::query!("DELETE FROM ingredient WHERE recipe_id = $1", recipe_id)
+        .execute(&mut **tx)
+        .await
+        .wrap_err("Failed to clear out old ingredients")?;
+
+    create_ingredients(tx, recipe_id, &recipe.ingredients)
+        .await
+        .wrap_err("Failed to create ingredients")?;
+
+    record_event(tx, RecipeEventKind::Updated, recipe_id)
+        .await
+        .wrap_err("Failed to record event")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistance::{
+        deletion::DeletionEntity,
+        implementation::postgres::tests::create_minimal_recipe,
+        recipe::{MealType, MutableRecipeEntity, RecipeRepository},
+    };
+    use assert2::{check, let_assert};
+    use sqlx::PgPool;
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    fn remote(id: u128, name: &str) -> RemoteRecipeEntity {
+        RemoteRecipeEntity {
+            remote_public_id: Uuid::from_u128(id),
+            recipe: create_minimal_recipe(name),
+        }
+    }
+
+    async fn register(repository: &Postgres) -> i32 {
+        let_assert!(
+            Ok(instance) = repository
+                .register_instance("https://recipes.example.com".to_string(), None)
+                .await
+        );
+        instance.remote_instance_id
+    }
+
+    mod fingerprint {
+        use super::*;
+
+        #[test]
+        fn it_hashes_the_same_recipe_the_same_across_builds() {
+            check!(fingerprint(&create_minimal_recipe("Soup")) == -7582845790925402308);
+        }
+
+        #[test]
+        fn it_changes_with_the_recipe() {
+            let soup = fingerprint(&create_minimal_recipe("Soup"));
+
+            check!(fingerprint(&create_minimal_recipe("Stew")) != soup);
+            check!(
+                fingerprint(&MutableRecipeEntity {
+                    meal_type: MealType::Lunch,
+                    ..create_minimal_recipe("Soup")
+                }) != soup
+            );
+        }
+    }
+
+    mod register_instance {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_refuses_to_register_an_instance_twice(pool: PgPool) {
+            let repository = Postgres::new(pool);
+            register(&repository).await;
+
+            let result = repository
+                .register_instance("https://recipes.example.com".to_string(), None)
+                .await;
+
+            let_assert!(Err(RegisterInstanceError::AlreadyRegistered) = result);
+        }
+    }
+
+    mod mirror_recipes {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_creates_and_updates_local_copies(pool: PgPool) {
+            let repository = Postgres::new(pool);
+            let instance_id = register(&repository).await;
+
+            let result = repository
+                .mirror_recipes(instance_id, vec![remote(1, "Soup"), remote(2, "Stew")])
+                .await;
+            let_assert!(Ok(summary) = result);
+            check!(summary.created == 2);
+
+            let result = repository
+                .mirror_recipes(instance_id, vec![remote(1, "Soup"), remote(2, "Hot pot")])
+                .await;
+            let_assert!(Ok(summary) = result);
+            check!(
+                summary
+                    == MirrorSummaryEntity {
+                        updated: 1,
+                        ..Default::default()
+                    }
+            );
+
+            let_assert!(Ok(recipes) = repository.list_recipes(false, false).await);
+            let mut names = recipes
+                .into_iter()
+                .map(|recipe| recipe.name)
+                .collect::<Vec<_>>();
+            names.sort();
+            check!(names == ["Hot pot", "Soup"]);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_leaves_locally_edited_and_deleted_copies_alone(pool: PgPool) {
+            let repository = Postgres::new(pool);
+            let instance_id = register(&repository).await;
+
+            let result = repository
+                .mirror_recipes(instance_id, vec![remote(1, "Soup"), remote(2, "Stew")])
+                .await;
+            let_assert!(Ok(_) = result);
+
+            let_assert!(Ok(recipes) = repository.list_recipes(false, false).await);
+            let_assert!(Some(soup) = recipes.iter().find(|recipe| recipe.name == "Soup"));
+            let_assert!(Some(stew) = recipes.iter().find(|recipe| recipe.name == "Stew"));
+
+            let result = repository
+                .update_recipe(soup.recipe_id, create_minimal_recipe("Our soup"))
+                .await;
+            let_assert!(Ok(_) = result);
+            let_assert!(
+                Ok(_) = repository
+                    .delete_recipe(stew.recipe_id, DeletionEntity::default())
+                    .await
+            );
+
+            let result = repository
+                .mirror_recipes(instance_id, vec![remote(1, "Soup 2"), remote(2, "Stew 2")])
+                .await;
+            let_assert!(Ok(summary) = result);
+            check!(summary.skipped == 2);
+
+            let_assert!(Ok(recipes) = repository.list_recipes(false, false).await);
+            check!(recipes.len() == 1);
+            check!(recipes[0].name == "Our soup");
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_skips_recipes_which_exist_here_already(pool: PgPool) {
+            let repository = Postgres::new(pool);
+            let instance_id = register(&repository).await;
+            let_assert!(
+                Ok(local) = repository
+                    .create_recipe(create_minimal_recipe("Soup"))
+                    .await
+            );
+
+            let result = repository
+                .mirror_recipes(
+                    instance_id,
+                    vec![RemoteRecipeEntity {
+                        remote_public_id: local.public_id,
+                        recipe: create_minimal_recipe("Their soup"),
+                    }],
+                )
+                .await;
+            let_assert!(Ok(summary) = result);
+            check!(summary.skipped == 1);
+            check!(summary.created == 0);
+
+            let_assert!(Ok(None) = repository.recipe_origin(local.recipe_id).await);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_detaches_recipes_no_longer_published(pool: PgPool) {
+            let repository = Postgres::new(pool);
+            let instance_id = register(&repository).await;
+
+            let result = repository
+                .mirror_recipes(instance_id, vec![remote(1, "Soup")])
+                .await;
+            let_assert!(Ok(_) = result);
+
+            let_assert!(Ok(summary) = repository.mirror_recipes(instance_id, vec![]).await);
+            check!(summary.detached == 1);
+
+            let_assert!(Ok(recipes) = repository.list_recipes(false, false).await);
+            let_assert!(Ok(Some(origin)) = repository.recipe_origin(recipes[0].recipe_id).await);
+            check!(origin.detached);
+            check!(origin.remote_public_id == Uuid::from_u128(1));
+        }
+    }
+
+    mod federated_recipes {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_publishes_local_recipes_which_did_not_opt_out(pool: PgPool) {
+            let repository = Postgres::new(pool);
+            let instance_id = register(&repository).await;
+
+            let_assert!(
+                Ok(soup) = repository
+                    .create_recipe(create_minimal_recipe("Soup"))
+                    .await
+            );
+            let_assert!(
+                Ok(stew) = repository
+                    .create_recipe(create_minimal_recipe("Stew"))
+                    .await
+            );
+            let_assert!(
+                Ok(()) = repository
+                    .set_federation_opt_out(stew.recipe_id, true)
+                    .await
+            );
+            let result = repository
+                .mirror_recipes(instance_id, vec![remote(1, "Hot pot")])
+                .await;
+            let_assert!(Ok(_) = result);
+
+            let_assert!(Ok(recipes) = repository.federated_recipes().await);
+            check!(recipes.len() == 1);
+            check!(recipes[0].recipe_id == soup.recipe_id);
+        }
+    }
+}
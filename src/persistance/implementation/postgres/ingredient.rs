@@ -0,0 +1,357 @@
+use std::collections::BTreeSet;
+
+use eyre::Context;
+
+use super::{Postgres, outbox};
+use crate::persistance::{
+    ingredient::{
+        DeleteDensityError, IngredientDensityEntity, IngredientRecipeEntity,
+        IngredientRecipesError, IngredientRepository, IngredientUsageEntity, ListDensitiesError,
+        ListIngredientsError, RenameIngredientError, SetDensityError,
+    },
+    outbox::RecipeEventKind,
+    recipe::{MealType, QuantityType},
+};
+
+impl IngredientRepository for Postgres {
+    async fn list_ingredients(&self) -> Result<Vec<IngredientUsageEntity>, ListIngredientsError> {
+        let data = sqlx::query_as!(
+            IngredientUsageEntity,
+            r#"
+                SELECT i.name, COUNT(DISTINCT i.recipe_id) AS "recipe_count!"
+                FROM ingredient i
+                JOIN recipe r ON r.recipe_id = i.recipe_id
+                WHERE r.archived_at IS NULL
+                GROUP BY i.name
+                ORDER BY i.name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to list ingredients")?;
+
+        Ok(data)
+    }
+
+    async fn ingredient_recipes(
+        &self,
+        name: String,
+    ) -> Result<Vec<IngredientRecipeEntity>, IngredientRecipesError> {
+        let data = sqlx::query_as!(
+            IngredientRecipeEntity,
+            r#"
+                SELECT
+                    r.recipe_id,
+                    r.public_id,
+                    r.name,
+                    r.meal_type AS "meal_type: MealType",
+                    i.name AS ingredient_name,
+                    i.quantity_type AS "quantity_type: QuantityType",
+                    i.quantity
+                FROM ingredient i
+                JOIN recipe r ON r.recipe_id = i.recipe_id
+                WHERE LOWER(i.name) = LOWER($1) AND r.archived_at IS NULL
+                ORDER BY r.name, r.recipe_id, i.ingredient_order
+            "#,
+            name
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to list recipes by ingredient")?;
+
+        Ok(data)
+    }
+
+    async fn rename_ingredient(
+        &self,
+        from: String,
+        to: String,
+    ) -> Result<u64, RenameIngredientError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .wrap_err("Failed to open transaction")?;
+
+        let recipe_ids = sqlx::query_scalar!(
+            "UPDATE ingredient SET name = $2 WHERE LOWER(name) = LOWER($1) RETURNING recipe_id",
+            from,
+            to,
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .wrap_err("Failed to rename ingredient")?
+        .into_iter()
+        .collect::<BTreeSet<_>>();
+
+        let recipe_ids = recipe_ids.into_iter().collect::<Vec<_>>();
+
+        // Local edits to a mirrored recipe win over later changes on the remote instance
+        sqlx::query!(
+            "UPDATE mirrored_recipe SET detached = TRUE WHERE recipe_id = ANY($1)",
+            &recipe_ids
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to detach mirrored recipes")?;
+
+        for recipe_id in &recipe_ids {
+            outbox::record_event(&mut tx, RecipeEventKind::Updated, *recipe_id)
+                .await
+                .wrap_err("Failed to record event")?;
+        }
+
+        tx.commit().await.wrap_err("Failed to commit transaction")?;
+
+        Ok(recipe_ids.len() as u64)
+    }
+
+    async fn list_densities(&self) -> Result<Vec<IngredientDensityEntity>, ListDensitiesError> {
+        let data = sqlx::query_as!(
+            IngredientDensityEntity,
+            "SELECT name, grams_per_milliliter FROM ingredient_density ORDER BY name"
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to list ingredient densities")?;
+
+        Ok(data)
+    }
+
+    async fn set_density(
+        &self,
+        name: String,
+        grams_per_milliliter: f32,
+    ) -> Result<IngredientDensityEntity, SetDensityError> {
+        let data = sqlx::query_as!(
+            IngredientDensityEntity,
+            r#"
+                INSERT INTO ingredient_density (name, grams_per_milliliter)
+                VALUES ($1, $2)
+                ON CONFLICT (name) DO UPDATE SET grams_per_milliliter = EXCLUDED.grams_per_milliliter
+                RETURNING name, grams_per_milliliter
+            "#,
+            name,
+            grams_per_milliliter,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .wrap_err("Failed to set ingredient density")?;
+
+        Ok(data)
+    }
+
+    async fn delete_density(&self, name: String) -> Result<(), DeleteDensityError> {
+        let result = sqlx::query!("DELETE FROM ingredient_density WHERE name = $1", name)
+            .execute(&self.pool)
+            .await
+            .wrap_err("Failed to delete ingredient density")?;
+
+        if result.rows_affected() == 0 {
+            return Err(DeleteDensityError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistance::{
+        implementation::postgres::tests::create_minimal_recipe,
+        outbox::OutboxRepository,
+        recipe::{MutableIngredientEntity, MutableRecipeEntity, QuantityType, RecipeRepository},
+    };
+    use assert2::{check, let_assert};
+    use sqlx::PgPool;
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    fn create_test_recipe(name: &str, ingredients: &[&str]) -> MutableRecipeEntity {
+        MutableRecipeEntity {
+            ingredients: ingredients
+                .iter()
+                .map(|ingredient| MutableIngredientEntity {
+                    name: ingredient.to_string(),
+                    quantity_type: QuantityType::Count,
+                    quantity: 1.0,
+                })
+                .collect(),
+            ..create_minimal_recipe(name)
+        }
+    }
+
+    mod list_ingredients {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_counts_the_recipes_using_each_ingredient(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(_) = repository
+                    .create_recipe(create_test_recipe("Stew", &["Onion", "Tomato"]))
+                    .await
+            );
+            let_assert!(
+                Ok(_) = repository
+                    .create_recipe(create_test_recipe("Soup", &["Tomato"]))
+                    .await
+            );
+            let_assert!(
+                Ok(archived) = repository
+                    .create_recipe(create_test_recipe("Salad", &["Lettuce", "Tomato"]))
+                    .await
+            );
+            let_assert!(Ok(()) = repository.set_archived(archived.recipe_id, true).await);
+
+            let_assert!(Ok(ingredients) = repository.list_ingredients().await);
+            let_assert!([onion, tomato] = ingredients.as_slice());
+            check!(onion.name == "Onion");
+            check!(onion.recipe_count == 1);
+            check!(tomato.name == "Tomato");
+            check!(tomato.recipe_count == 2);
+        }
+    }
+
+    mod ingredient_recipes {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_lists_the_recipes_using_an_ingredient_by_name(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(stew) = repository
+                    .create_recipe(create_test_recipe("Stew", &["Onion", "Tomato"]))
+                    .await
+            );
+            let_assert!(
+                Ok(soup) = repository
+                    .create_recipe(create_test_recipe("Soup", &["tomato"]))
+                    .await
+            );
+            let_assert!(
+                Ok(_) = repository
+                    .create_recipe(create_test_recipe("Salad", &["Lettuce"]))
+                    .await
+            );
+
+            let_assert!(Ok(recipes) = repository.ingredient_recipes("TOMATO".to_string()).await);
+            let_assert!([first, second] = recipes.as_slice());
+            check!(first.recipe_id == soup.recipe_id);
+            check!(first.ingredient_name == "tomato");
+            check!(second.recipe_id == stew.recipe_id);
+            check!(second.public_id == stew.public_id);
+        }
+    }
+
+    mod rename_ingredient {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_renames_the_ingredient_in_every_recipe(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(stew) = repository
+                    .create_recipe(create_test_recipe("Stew", &["Tomatoe", "Onion"]))
+                    .await
+            );
+            let_assert!(
+                Ok(soup) = repository
+                    .create_recipe(create_test_recipe("Soup", &["tomatoe"]))
+                    .await
+            );
+            let_assert!(
+                Ok(salad) = repository
+                    .create_recipe(create_test_recipe("Salad", &["Lettuce"]))
+                    .await
+            );
+
+            let_assert!(Ok(before) = repository.latest_event_id(soup.recipe_id).await);
+
+            let_assert!(
+                Ok(touched) = repository
+                    .rename_ingredient("Tomatoe".to_string(), "Tomato".to_string())
+                    .await
+            );
+            check!(touched == 2);
+
+            for (recipe_id, expected) in [
+                (stew.recipe_id, vec!["Tomato", "Onion"]),
+                (soup.recipe_id, vec!["Tomato"]),
+                (salad.recipe_id, vec!["Lettuce"]),
+            ] {
+                let_assert!(Ok(recipe) = repository.get_recipe(recipe_id).await);
+                let names = recipe
+                    .ingredients
+                    .iter()
+                    .map(|ingredient| ingredient.name.as_str())
+                    .collect::<Vec<_>>();
+                check!(names == expected);
+            }
+
+            let_assert!(Ok(after) = repository.latest_event_id(soup.recipe_id).await);
+            check!(after > before);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_touches_nothing_for_an_unknown_ingredient(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(touched) = repository
+                    .rename_ingredient("Saffron".to_string(), "Safran".to_string())
+                    .await
+            );
+            check!(touched == 0);
+        }
+    }
+
+    mod densities {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_comes_with_common_densities(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(Ok(densities) = repository.list_densities().await);
+            let_assert!(Some(flour) = densities.iter().find(|density| density.name == "flour"));
+            check!(flour.grams_per_milliliter == 0.53);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_replaces_the_density_of_an_ingredient(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(Ok(_) = repository.set_density("spelt flour".to_string(), 0.5).await);
+            let_assert!(
+                Ok(density) = repository
+                    .set_density("spelt flour".to_string(), 0.55)
+                    .await
+            );
+            check!(density.grams_per_milliliter == 0.55);
+
+            let_assert!(Ok(densities) = repository.list_densities().await);
+            let spelt = densities
+                .iter()
+                .filter(|density| density.name == "spelt flour")
+                .collect::<Vec<_>>();
+            let_assert!([spelt] = spelt.as_slice());
+            check!(spelt.grams_per_milliliter == 0.55);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_deletes_a_density(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(Ok(()) = repository.delete_density("flour".to_string()).await);
+            let_assert!(
+                Err(DeleteDensityError::NotFound) =
+                    repository.delete_density("flour".to_string()).await
+            );
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use eyre::Context;
+
+use super::Postgres;
+use crate::persistance::integrity::{
+    CheckIntegrityError, IntegrityIssuesEntity, IntegrityRepository, RepairIntegrityError,
+};
+
+impl IntegrityRepository for Postgres {
+    async fn check_integrity(&self) -> Result<IntegrityIssuesEntity, CheckIntegrityError> {
+        let orphaned_ingredients = sqlx::query_scalar!(
+            r#"
+                SELECT i.ingredient_id
+                FROM ingredient i
+                LEFT JOIN recipe r ON r.recipe_id = i.recipe_id
+                WHERE r.recipe_id IS NULL
+                ORDER BY i.ingredient_id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to look for orphaned ingredients")?;
+
+        let broken_ingredient_orders = sqlx::query_scalar!(
+            r#"
+                SELECT recipe_id
+                FROM ingredient
+                GROUP BY recipe_id
+                HAVING
+                    MIN(ingredient_order) <> 0 OR
+                    MAX(ingredient_order) <> COUNT(*) - 1 OR
+                    COUNT(DISTINCT ingredient_order) <> COUNT(*)
+                ORDER BY recipe_id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to look for broken ingredient orders")?;
+
+        Ok(IntegrityIssuesEntity {
+            orphaned_ingredients,
+            broken_ingredient_orders,
+        })
+    }
+
+    async fn repair_integrity(
+        &self,
+        issues: IntegrityIssuesEntity,
+    ) -> Result<(), RepairIntegrityError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .wrap_err("Failed to open transaction")?;
+
+        sqlx::query!(
+            "DELETE FROM ingredient WHERE ingredient_id = ANY($1)",
+            &issues.orphaned_ingredients
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to delete orphaned ingredients")?;
+
+        // Ties are broken by insertion order, which is the best guess left for duplicated orders
+        sqlx::query!(
+            r#"
+                UPDATE ingredient i SET ingredient_order = o.position
+                FROM (
+                    SELECT
+                        ingredient_id,
+                        (ROW_NUMBER() OVER (
+                            PARTITION BY recipe_id ORDER BY ingredient_order, ingredient_id
+                        ) - 1)::INTEGER AS position
+                    FROM ingredient
+                    WHERE recipe_id = ANY($1)
+                ) o
+                WHERE i.ingredient_id = o.ingredient_id
+            "#,
+            &issues.broken_ingredient_orders
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to renumber ingredients")?;
+
+        tx.commit().await.wrap_err("Failed to commit transaction")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistance::recipe::RecipeRepository;
+    use assert2::{check, let_assert};
+    use sqlx::PgPool;
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    async fn insert_recipe(pool: &PgPool, orders: &[i32]) -> i32 {
+        let recipe_id = sqlx::query_scalar!(
+            "INSERT INTO recipe (name, meal_type) VALUES ('Stew', 'Dinner') RETURNING recipe_id"
+        )
+        .fetch_one(pool)
+        .await
+        .expect("Failed to insert recipe");
+
+        for (idx, order) in orders.iter().enumerate() {
+            sqlx::query!(
+                r#"
+                    INSERT INTO ingredient (recipe_id, ingredient_order, name, quantity, quantity_type)
+                    VALUES ($1, $2, $3, 1, 'Count')
+                "#,
+                recipe_id,
+                order,
+                format!("Ingredient {idx}")
+            )
+            .execute(pool)
+            .await
+            .expect("Failed to insert ingredient");
+        }
+
+        recipe_id
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_finds_recipes_with_broken_ingredient_orders(pool: PgPool) {
+        let repository = Postgres::new(pool.clone());
+
+        let _ = insert_recipe(&pool, &[0, 1, 2]).await;
+        let gap = insert_recipe(&pool, &[0, 2]).await;
+        let duplicate = insert_recipe(&pool, &[0, 1, 1]).await;
+
+        let_assert!(Ok(issues) = repository.check_integrity().await);
+        check!(issues.orphaned_ingredients.is_empty());
+        check!(issues.broken_ingredient_orders == [gap, duplicate]);
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_renumbers_broken_ingredient_orders(pool: PgPool) {
+        let repository = Postgres::new(pool.clone());
+
+        let recipe_id = insert_recipe(&pool, &[3, 1, 1]).await;
+
+        let_assert!(Ok(issues) = repository.check_integrity().await);
+        let_assert!(Ok(()) = repository.repair_integrity(issues).await);
+
+        let_assert!(Ok(issues) = repository.check_integrity().await);
+        check!(issues == IntegrityIssuesEntity::default());
+
+        let_assert!(Ok(recipe) = repository.get_recipe(recipe_id).await);
+        let names = recipe
+            .ingredients
+            .iter()
+            .map(|ingredient| ingredient.name.as_str())
+            .collect::<Vec<_>>();
+        check!(names == ["Ingredient 1", "Ingredient 2", "Ingredient 0"]);
+    }
+}
@@ -0,0 +1,316 @@
+use eyre::Context;
+use sqlx::PgTransaction;
+
+use super::Postgres;
+use crate::persistance::outbox::{
+    EventsSinceError, LatestEventIdError, MarkPublishedError, OutboxEventEntity, OutboxRepository,
+    RecipeEventKind, UnpublishedEventsError,
+};
+
+/// Key of the advisory lock taken by [`lock_outbox`]
+const OUTBOX_LOCK: i64 = 0x6f75_7462_6f78;
+
+/// Makes transactions writing events commit in the order of their outbox ids, by holding a lock
+/// from before the ids are handed out until the transaction ends. Otherwise a transaction could
+/// commit after one with higher ids, and clients whose cursor already passed those would never
+/// see its events. Has to be taken before writing to the outbox.
+pub(super) async fn lock_outbox(transaction: &mut PgTransaction<'_>) -> Result<(), sqlx::Error> {
+    sqlx::query_scalar!(
+        "SELECT 1 AS locked FROM pg_advisory_xact_lock($1)",
+        OUTBOX_LOCK
+    )
+    .fetch_one(&mut **transaction)
+    .await?;
+
+    Ok(())
+}
+
+/// Writes an event for the given recipe to the outbox. Has to run inside the transaction of the
+/// change, and before the recipe is deleted for [`RecipeEventKind::Deleted`].
+pub(super) async fn record_event(
+    transaction: &mut PgTransaction<'_>,
+    kind: RecipeEventKind,
+    recipe_id: i32,
+) -> Result<(), sqlx::Error> {
+    lock_outbox(transaction).await?;
+
+    sqlx::query!(
+        r#"
+            INSERT INTO outbox (kind, recipe_id, public_id)
+            SELECT $1, recipe_id, public_id FROM recipe WHERE recipe_id = $2
+        "#,
+        kind as RecipeEventKind,
+        recipe_id
+    )
+    .execute(&mut **transaction)
+    .await?;
+
+    Ok(())
+}
+
+impl OutboxRepository for Postgres {
+    async fn unpublished_events(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<OutboxEventEntity>, UnpublishedEventsError> {
+        let events = sqlx::query_as!(
+            OutboxEventEntity,
+            r#"
+                SELECT
+                    o.outbox_id,
+                    o.kind AS "kind: RecipeEventKind",
+                    o.recipe_id,
+                    o.public_id,
+                    o.occurred_at,
+                    r.archived_at
+                FROM outbox o
+                LEFT JOIN recipe r USING (recipe_id)
+                WHERE o.published_at IS NULL
+                ORDER BY o.outbox_id
+                LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to get unpublished events")?;
+
+        Ok(events)
+    }
+
+    async fn events_since(
+        &self,
+        outbox_id: i64,
+        limit: i64,
+    ) -> Result<Vec<OutboxEventEntity>, EventsSinceError> {
+        let events = sqlx::query_as!(
+            OutboxEventEntity,
+            r#"
+                SELECT
+                    o.outbox_id,
+                    o.kind AS "kind: RecipeEventKind",
+                    o.recipe_id,
+                    o.public_id,
+                    o.occurred_at,
+                    r.archived_at
+                FROM outbox o
+                LEFT JOIN recipe r USING (recipe_id)
+                WHERE o.outbox_id > $1
+                ORDER BY o.outbox_id
+                LIMIT $2
+            "#,
+            outbox_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to get events")?;
+
+        Ok(events)
+    }
+
+    async fn latest_event_id(&self, recipe_id: i32) -> Result<Option<i64>, LatestEventIdError> {
+        let outbox_id = sqlx::query_scalar!(
+            "SELECT MAX(outbox_id) FROM outbox WHERE recipe_id = $1",
+            recipe_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .wrap_err("Failed to get latest event")?;
+
+        Ok(outbox_id)
+    }
+
+    async fn mark_published(&self, outbox_ids: Vec<i64>) -> Result<(), MarkPublishedError> {
+        sqlx::query!(
+            "UPDATE outbox SET published_at = NOW() WHERE outbox_id = ANY($1)",
+            &outbox_ids
+        )
+        .execute(&self.pool)
+        .await
+        .wrap_err("Failed to mark events as published")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+    use sqlx::PgPool;
+
+    use crate::persistance::{
+        deletion::DeletionEntity,
+        recipe::{MealType, MutableRecipeEntity, RecipeRepository},
+    };
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    fn recipe(name: &str) -> MutableRecipeEntity {
+        MutableRecipeEntity {
+            name: name.to_string(),
+            description: None,
+            ingredients: vec![],
+            cooking_time: None,
+            meal_type: MealType::Dinner,
+            video_url: None,
+            video_title: None,
+            video_thumbnail_url: None,
+            pairing: None,
+            pairing_kind: None,
+        }
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_records_an_event_for_every_change(pool: PgPool) {
+        let repository = Postgres::new(pool);
+
+        let_assert!(Ok(created) = repository.create_recipe(recipe("Soup")).await);
+        let_assert!(
+            Ok(_) = repository
+                .update_recipe(created.recipe_id, recipe("Tomato soup"))
+                .await
+        );
+        let_assert!(
+            Ok(_) = repository
+                .delete_recipe(created.recipe_id, DeletionEntity::default())
+                .await
+        );
+
+        let_assert!(Ok(events) = repository.unpublished_events(10).await);
+
+        check!(events.len() == 3);
+        let_assert!(RecipeEventKind::Created = events[0].kind);
+        let_assert!(RecipeEventKind::Updated = events[1].kind);
+        let_assert!(RecipeEventKind::Deleted = events[2].kind);
+        check!(
+            events
+                .iter()
+                .all(|event| event.recipe_id == created.recipe_id)
+        );
+        check!(
+            events
+                .iter()
+                .all(|event| event.public_id == created.public_id)
+        );
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_does_not_record_events_for_failed_changes(pool: PgPool) {
+        let repository = Postgres::new(pool);
+
+        let_assert!(Err(_) = repository.update_recipe(42, recipe("Soup")).await);
+        let_assert!(
+            Err(_) = repository
+                .delete_recipe(42, DeletionEntity::default())
+                .await
+        );
+
+        let_assert!(Ok(events) = repository.unpublished_events(10).await);
+        check!(events.is_empty());
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_skips_published_events(pool: PgPool) {
+        let repository = Postgres::new(pool);
+
+        let_assert!(Ok(_) = repository.create_recipe(recipe("Soup")).await);
+        let_assert!(Ok(_) = repository.create_recipe(recipe("Stew")).await);
+
+        let_assert!(Ok(events) = repository.unpublished_events(1).await);
+        check!(events.len() == 1);
+
+        let_assert!(
+            Ok(()) = repository
+                .mark_published(events.iter().map(|event| event.outbox_id).collect())
+                .await
+        );
+
+        let_assert!(Ok(remaining) = repository.unpublished_events(10).await);
+        check!(remaining.len() == 1);
+        check!(remaining[0].outbox_id > events[0].outbox_id);
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_finds_the_latest_event_of_a_recipe(pool: PgPool) {
+        let repository = Postgres::new(pool);
+
+        let_assert!(Ok(soup) = repository.create_recipe(recipe("Soup")).await);
+        let_assert!(Ok(_) = repository.create_recipe(recipe("Stew")).await);
+        let_assert!(
+            Ok(_) = repository
+                .update_recipe(soup.recipe_id, recipe("Soup"))
+                .await
+        );
+
+        let_assert!(Ok(events) = repository.events_since(0, 10).await);
+        let_assert!(Ok(Some(outbox_id)) = repository.latest_event_id(soup.recipe_id).await);
+        check!(outbox_id == events[2].outbox_id);
+
+        let_assert!(Ok(None) = repository.latest_event_id(42).await);
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_lists_events_after_a_cursor_including_published_ones(pool: PgPool) {
+        let repository = Postgres::new(pool);
+
+        let_assert!(Ok(_) = repository.create_recipe(recipe("Soup")).await);
+        let_assert!(Ok(_) = repository.create_recipe(recipe("Stew")).await);
+        let_assert!(Ok(events) = repository.unpublished_events(10).await);
+        let_assert!(
+            Ok(()) = repository
+                .mark_published(events.iter().map(|event| event.outbox_id).collect())
+                .await
+        );
+
+        let_assert!(Ok(all) = repository.events_since(0, 10).await);
+        check!(all.len() == 2);
+
+        let_assert!(Ok(later) = repository.events_since(all[0].outbox_id, 10).await);
+        check!(later.len() == 1);
+        check!(later[0].outbox_id == all[1].outbox_id);
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_records_archiving_as_an_update(pool: PgPool) {
+        let repository = Postgres::new(pool);
+
+        let_assert!(Ok(soup) = repository.create_recipe(recipe("Soup")).await);
+        let_assert!(Ok(()) = repository.set_archived(soup.recipe_id, true).await);
+
+        let_assert!(Ok(events) = repository.events_since(0, 10).await);
+        check!(events.len() == 2);
+        let_assert!(RecipeEventKind::Updated = events[1].kind);
+        check!(events.iter().all(|event| event.archived_at.is_some()));
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_makes_events_visible_in_the_order_of_their_ids(pool: PgPool) {
+        let repository = Postgres::new(pool.clone());
+        let_assert!(Ok(soup) = repository.create_recipe(recipe("Soup")).await);
+
+        let mut tx = pool.begin().await.expect("Transaction");
+        let_assert!(Ok(()) = record_event(&mut tx, RecipeEventKind::Updated, soup.recipe_id).await);
+
+        // Events of a later transaction have to wait on the open one, even if it finishes first
+        let stew = tokio::spawn({
+            let repository = repository.clone();
+            async move { repository.create_recipe(recipe("Stew")).await }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        check!(!stew.is_finished());
+
+        tx.commit().await.expect("Commit");
+        let_assert!(Ok(Ok(_)) = stew.await);
+
+        let_assert!(Ok(events) = repository.events_since(0, 10).await);
+        let_assert!([created, updated, stew] = events.as_slice());
+        check!(created.recipe_id == soup.recipe_id);
+        check!(updated.recipe_id == soup.recipe_id);
+        check!(stew.recipe_id != soup.recipe_id);
+        check!(created.outbox_id < updated.outbox_id);
+        check!(updated.outbox_id < stew.outbox_id);
+    }
+}
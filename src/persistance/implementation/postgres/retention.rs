@@ -0,0 +1,130 @@
+use eyre::Context;
+
+use super::Postgres;
+use crate::persistance::retention::{
+    CountExpiredError, ExpiredCountsEntity, PurgeExpiredError, RetentionCutoffsEntity,
+    RetentionRepository,
+};
+
+impl RetentionRepository for Postgres {
+    async fn count_expired(
+        &self,
+        cutoffs: RetentionCutoffsEntity,
+    ) -> Result<ExpiredCountsEntity, CountExpiredError> {
+        let counts = sqlx::query_as!(
+            ExpiredCountsEntity,
+            r#"
+                SELECT
+                    (
+                        SELECT COUNT(*) FROM recipe_deletion WHERE deleted_at < $1
+                    ) AS "deletions!",
+                    (
+                        SELECT COUNT(*) FROM share_link WHERE revoked_at < $2
+                    ) AS "revoked_share_links!",
+                    (
+                        SELECT COUNT(*) FROM snapshot WHERE created_at < $3
+                    ) AS "snapshots!"
+            "#,
+            cutoffs.deletions,
+            cutoffs.revoked_share_links,
+            cutoffs.snapshots,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .wrap_err("Failed to count expired rows")?;
+
+        Ok(counts)
+    }
+
+    async fn purge_expired(
+        &self,
+        cutoffs: RetentionCutoffsEntity,
+    ) -> Result<ExpiredCountsEntity, PurgeExpiredError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .wrap_err("Failed to open transaction")?;
+
+        // Comparing with a missing cutoff matches nothing, which keeps those rows
+        let deletions = sqlx::query!(
+            "DELETE FROM recipe_deletion WHERE deleted_at < $1",
+            cutoffs.deletions
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to purge deletions")?
+        .rows_affected();
+
+        let revoked_share_links = sqlx::query!(
+            "DELETE FROM share_link WHERE revoked_at < $1",
+            cutoffs.revoked_share_links
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to purge share links")?
+        .rows_affected();
+
+        let snapshots = sqlx::query!(
+            "DELETE FROM snapshot WHERE created_at < $1",
+            cutoffs.snapshots
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to purge snapshots")?
+        .rows_affected();
+
+        tx.commit().await.wrap_err("Failed to commit transaction")?;
+
+        Ok(ExpiredCountsEntity {
+            deletions: deletions as i64,
+            revoked_share_links: revoked_share_links as i64,
+            snapshots: snapshots as i64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistance::snapshot::SnapshotRepository;
+    use assert2::{check, let_assert};
+    use chrono::{Duration, Utc};
+    use sqlx::PgPool;
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_purges_only_rows_before_the_cutoffs(pool: PgPool) {
+        sqlx::query!(
+            r#"
+                INSERT INTO recipe_deletion (recipe_id, recipe_name, deleted_at)
+                VALUES (1, 'Stew', NOW() - INTERVAL '2 years'), (2, 'Soup', NOW())
+            "#
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to insert deletions");
+
+        let repository = Postgres::new(pool);
+        let_assert!(Ok(_) = repository.create_snapshot().await);
+
+        let cutoffs = RetentionCutoffsEntity {
+            deletions: Some(Utc::now() - Duration::days(365)),
+            revoked_share_links: Some(Utc::now()),
+            snapshots: None,
+        };
+
+        let_assert!(Ok(expired) = repository.count_expired(cutoffs.clone()).await);
+        check!(expired.deletions == 1);
+        check!(expired.snapshots == 0);
+
+        let_assert!(Ok(purged) = repository.purge_expired(cutoffs.clone()).await);
+        check!(purged.deletions == 1);
+        check!(purged.revoked_share_links == 0);
+        check!(purged.snapshots == 0);
+
+        let_assert!(Ok(expired) = repository.count_expired(cutoffs).await);
+        check!(expired.deletions == 0);
+    }
+}
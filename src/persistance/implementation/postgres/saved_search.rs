@@ -0,0 +1,160 @@
+use eyre::Context;
+use sqlx::types::Json;
+
+use super::Postgres;
+use crate::persistance::saved_search::{
+    DeleteSavedSearchError, GetSavedSearchError, ListSavedSearchesError, SaveSearchError,
+    SavedCriteriaEntity, SavedSearchEntity, SavedSearchRepository,
+};
+
+impl SavedSearchRepository for Postgres {
+    async fn save_search(
+        &self,
+        name: String,
+        criteria: SavedCriteriaEntity,
+    ) -> Result<SavedSearchEntity, SaveSearchError> {
+        let row = sqlx::query!(
+            r#"
+                INSERT INTO saved_search (name, criteria)
+                VALUES ($1, $2)
+                RETURNING
+                    saved_search_id,
+                    name,
+                    criteria AS "criteria: Json<SavedCriteriaEntity>",
+                    created_at
+            "#,
+            name,
+            Json(criteria) as _,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .wrap_err("Failed to insert saved search")?;
+
+        Ok(SavedSearchEntity {
+            saved_search_id: row.saved_search_id,
+            name: row.name,
+            criteria: row.criteria.0,
+            created_at: row.created_at,
+        })
+    }
+
+    async fn list_saved_searches(&self) -> Result<Vec<SavedSearchEntity>, ListSavedSearchesError> {
+        let data = sqlx::query!(
+            r#"
+                SELECT
+                    saved_search_id,
+                    name,
+                    criteria AS "criteria: Json<SavedCriteriaEntity>",
+                    created_at
+                FROM saved_search
+                ORDER BY name, saved_search_id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to list saved searches")?;
+
+        Ok(data
+            .into_iter()
+            .map(|row| SavedSearchEntity {
+                saved_search_id: row.saved_search_id,
+                name: row.name,
+                criteria: row.criteria.0,
+                created_at: row.created_at,
+            })
+            .collect())
+    }
+
+    async fn get_saved_search(
+        &self,
+        saved_search_id: i32,
+    ) -> Result<SavedSearchEntity, GetSavedSearchError> {
+        let row = sqlx::query!(
+            r#"
+                SELECT
+                    saved_search_id,
+                    name,
+                    criteria AS "criteria: Json<SavedCriteriaEntity>",
+                    created_at
+                FROM saved_search
+                WHERE saved_search_id = $1
+            "#,
+            saved_search_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .wrap_err("Failed to get saved search")?
+        .ok_or(GetSavedSearchError::NotFound)?;
+
+        Ok(SavedSearchEntity {
+            saved_search_id: row.saved_search_id,
+            name: row.name,
+            criteria: row.criteria.0,
+            created_at: row.created_at,
+        })
+    }
+
+    async fn delete_saved_search(
+        &self,
+        saved_search_id: i32,
+    ) -> Result<(), DeleteSavedSearchError> {
+        let result = sqlx::query!(
+            "DELETE FROM saved_search WHERE saved_search_id = $1",
+            saved_search_id
+        )
+        .execute(&self.pool)
+        .await
+        .wrap_err("Failed to delete saved search")?;
+
+        if result.rows_affected() == 0 {
+            return Err(DeleteSavedSearchError::NotFound);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistance::recipe::MealType;
+    use assert2::{check, let_assert};
+    use sqlx::PgPool;
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    mod save_search {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_stores_the_criteria(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let criteria = SavedCriteriaEntity {
+                recipe_name: Some("soup".to_string()),
+                meal_type: Some(MealType::Dinner),
+                ..Default::default()
+            };
+            let_assert!(Ok(saved) = repository.save_search("Soups".to_string(), criteria).await);
+
+            let_assert!(Ok(found) = repository.get_saved_search(saved.saved_search_id).await);
+            check!(found.name == "Soups");
+            check!(found.criteria.recipe_name.as_deref() == Some("soup"));
+            check!(found.criteria.ingredient_name.is_none());
+            let_assert!(Some(MealType::Dinner) = found.criteria.meal_type);
+        }
+    }
+
+    mod delete_saved_search {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_returns_not_found_error_for_nonexistent_search(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Err(DeleteSavedSearchError::NotFound) = repository.delete_saved_search(999).await
+            );
+        }
+    }
+}
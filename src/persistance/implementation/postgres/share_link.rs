@@ -0,0 +1,217 @@
+use eyre::Context;
+
+use super::Postgres;
+use crate::persistance::share_link::{
+    CreateShareLinkError, ListShareLinksError, ResolveShareLinkError, RevokeShareLinkError,
+    ShareLinkEntity, ShareLinkRepository,
+};
+
+impl ShareLinkRepository for Postgres {
+    async fn create_share_link(
+        &self,
+        recipe_id: i32,
+        token: String,
+    ) -> Result<ShareLinkEntity, CreateShareLinkError> {
+        let row = sqlx::query_as!(
+            ShareLinkEntity,
+            r#"
+                INSERT INTO share_link (recipe_id, token)
+                SELECT recipe_id, $2 FROM recipe WHERE recipe_id = $1
+                RETURNING share_link_id, recipe_id, token, created_at
+            "#,
+            recipe_id,
+            token,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .wrap_err("Failed to insert share link")?
+        .ok_or(CreateShareLinkError::NotFound)?;
+
+        Ok(row)
+    }
+
+    async fn list_share_links(
+        &self,
+        recipe_id: i32,
+    ) -> Result<Vec<ShareLinkEntity>, ListShareLinksError> {
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM recipe WHERE recipe_id = $1) AS "exists!""#,
+            recipe_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .wrap_err("Failed to check if recipe exists")?;
+
+        if !exists {
+            return Err(ListShareLinksError::NotFound);
+        }
+
+        let data = sqlx::query_as!(
+            ShareLinkEntity,
+            r#"
+                SELECT share_link_id, recipe_id, token, created_at
+                FROM share_link
+                WHERE recipe_id = $1 AND revoked_at IS NULL
+                ORDER BY created_at DESC, share_link_id DESC
+            "#,
+            recipe_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to get share links")?;
+
+        Ok(data)
+    }
+
+    async fn revoke_share_link(
+        &self,
+        recipe_id: i32,
+        share_link_id: i32,
+    ) -> Result<(), RevokeShareLinkError> {
+        let result = sqlx::query!(
+            r#"
+                UPDATE share_link SET revoked_at = NOW()
+                WHERE share_link_id = $1 AND recipe_id = $2 AND revoked_at IS NULL
+            "#,
+            share_link_id,
+            recipe_id
+        )
+        .execute(&self.pool)
+        .await
+        .wrap_err("Failed to revoke share link")?;
+
+        if result.rows_affected() == 0 {
+            return Err(RevokeShareLinkError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn resolve_share_link(&self, token: &str) -> Result<i32, ResolveShareLinkError> {
+        let recipe_id = sqlx::query_scalar!(
+            "SELECT recipe_id FROM share_link WHERE token = $1 AND revoked_at IS NULL",
+            token
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .wrap_err("Failed to resolve share link")?
+        .ok_or(ResolveShareLinkError::NotFound)?;
+
+        Ok(recipe_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistance::{
+        implementation::postgres::tests::create_minimal_recipe, recipe::RecipeRepository,
+    };
+    use assert2::{check, let_assert};
+    use sqlx::PgPool;
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    mod create_share_link {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_creates_a_link_which_resolves_to_the_recipe(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(recipe) = repository
+                    .create_recipe(create_minimal_recipe("Stew"))
+                    .await
+            );
+
+            let result = repository
+                .create_share_link(recipe.recipe_id, "secret-token".to_string())
+                .await;
+
+            let_assert!(Ok(link) = result);
+            check!(link.recipe_id == recipe.recipe_id);
+            check!(link.token == "secret-token");
+
+            let_assert!(Ok(recipe_id) = repository.resolve_share_link("secret-token").await);
+            check!(recipe_id == recipe.recipe_id);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_returns_not_found_error_for_nonexistent_recipe(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let result = repository
+                .create_share_link(99999, "secret-token".to_string())
+                .await;
+
+            let_assert!(Err(CreateShareLinkError::NotFound) = result);
+        }
+    }
+
+    mod revoke_share_link {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_stops_resolving_and_listing_revoked_links(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(recipe) = repository
+                    .create_recipe(create_minimal_recipe("Stew"))
+                    .await
+            );
+            let_assert!(
+                Ok(revoked) = repository
+                    .create_share_link(recipe.recipe_id, "revoked".to_string())
+                    .await
+            );
+            let_assert!(
+                Ok(kept) = repository
+                    .create_share_link(recipe.recipe_id, "kept".to_string())
+                    .await
+            );
+
+            let result = repository
+                .revoke_share_link(recipe.recipe_id, revoked.share_link_id)
+                .await;
+
+            let_assert!(Ok(()) = result);
+            let_assert!(
+                Err(ResolveShareLinkError::NotFound) =
+                    repository.resolve_share_link("revoked").await
+            );
+
+            let_assert!(Ok(links) = repository.list_share_links(recipe.recipe_id).await);
+            check!(links.len() == 1);
+            check!(links[0].share_link_id == kept.share_link_id);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_returns_not_found_error_for_a_link_of_another_recipe(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(recipe) = repository
+                    .create_recipe(create_minimal_recipe("Stew"))
+                    .await
+            );
+            let_assert!(
+                Ok(other) = repository
+                    .create_recipe(create_minimal_recipe("Soup"))
+                    .await
+            );
+            let_assert!(
+                Ok(link) = repository
+                    .create_share_link(recipe.recipe_id, "token".to_string())
+                    .await
+            );
+
+            let result = repository
+                .revoke_share_link(other.recipe_id, link.share_link_id)
+                .await;
+
+            let_assert!(Err(RevokeShareLinkError::NotFound) = result);
+        }
+    }
+}
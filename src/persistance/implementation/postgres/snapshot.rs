@@ -0,0 +1,203 @@
+use eyre::Context;
+use sqlx::types::Json;
+
+use super::Postgres;
+use crate::persistance::{
+    recipe::{MealType, MutableIngredientEntity, PairingKind},
+    snapshot::{
+        CreateSnapshotError, ListSnapshotsError, SnapshotEntity, SnapshotRecipeEntity,
+        SnapshotRecipesError, SnapshotRepository,
+    },
+};
+
+impl SnapshotRepository for Postgres {
+    async fn create_snapshot(&self) -> Result<SnapshotEntity, CreateSnapshotError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .wrap_err("Failed to open transaction")?;
+
+        sqlx::query!("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ")
+            .execute(&mut *tx)
+            .await
+            .wrap_err("Failed to set isolation level")?;
+
+        let recipes = sqlx::query!(
+            r#"
+                SELECT
+                    r.name,
+                    r.description,
+                    r.cooking_time_secs,
+                    r.meal_type AS "meal_type: MealType",
+                    r.video_url,
+                    r.pairing,
+                    r.pairing_kind AS "pairing_kind: PairingKind",
+                    COALESCE(
+                        (
+                            SELECT JSON_AGG(
+                                JSON_BUILD_OBJECT(
+                                    'name', i.name,
+                                    'quantity_type', i.quantity_type,
+                                    'quantity', i.quantity
+                                )
+                                ORDER BY i.ingredient_order
+                            )
+                            FROM ingredient i
+                            WHERE i.recipe_id = r.recipe_id
+                        ),
+                        '[]'
+                    ) AS "ingredients!: Json<Vec<MutableIngredientEntity>>"
+                FROM recipe r
+                ORDER BY r.recipe_id
+            "#
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .wrap_err("Failed to read recipes")?
+        .into_iter()
+        .map(|row| SnapshotRecipeEntity {
+            name: row.name,
+            description: row.description,
+            ingredients: row.ingredients.0,
+            cooking_time_secs: row.cooking_time_secs,
+            meal_type: row.meal_type,
+            video_url: row.video_url,
+            pairing: row.pairing,
+            pairing_kind: row.pairing_kind,
+        })
+        .collect::<Vec<_>>();
+
+        let snapshot = sqlx::query_as!(
+            SnapshotEntity,
+            r#"
+                INSERT INTO snapshot (recipe_count, recipes)
+                VALUES ($1, $2)
+                RETURNING snapshot_id, created_at, recipe_count
+            "#,
+            recipes.len() as i32,
+            Json(recipes) as _,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .wrap_err("Failed to insert snapshot")?;
+
+        tx.commit().await.wrap_err("Failed to commit transaction")?;
+
+        Ok(snapshot)
+    }
+
+    async fn list_snapshots(&self) -> Result<Vec<SnapshotEntity>, ListSnapshotsError> {
+        let data = sqlx::query_as!(
+            SnapshotEntity,
+            r#"
+                SELECT snapshot_id, created_at, recipe_count
+                FROM snapshot
+                ORDER BY created_at DESC, snapshot_id DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to list snapshots")?;
+
+        Ok(data)
+    }
+
+    async fn snapshot_recipes(
+        &self,
+        snapshot_id: i32,
+    ) -> Result<Vec<SnapshotRecipeEntity>, SnapshotRecipesError> {
+        let recipes = sqlx::query_scalar!(
+            r#"
+                SELECT recipes AS "recipes: Json<Vec<SnapshotRecipeEntity>>"
+                FROM snapshot
+                WHERE snapshot_id = $1
+            "#,
+            snapshot_id
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .wrap_err("Failed to get snapshot")?
+        .ok_or(SnapshotRecipesError::NotFound)?;
+
+        Ok(recipes.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistance::{
+        deletion::DeletionEntity,
+        implementation::postgres::tests::create_minimal_recipe,
+        recipe::{MutableRecipeEntity, QuantityType, RecipeRepository},
+    };
+    use assert2::{check, let_assert};
+    use sqlx::PgPool;
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    fn create_test_recipe(name: &str) -> MutableRecipeEntity {
+        MutableRecipeEntity {
+            description: Some("Stir.".to_string()),
+            ingredients: vec![
+                MutableIngredientEntity {
+                    name: "Flour".to_string(),
+                    quantity_type: QuantityType::Gram,
+                    quantity: 250.0,
+                },
+                MutableIngredientEntity {
+                    name: "Eggs".to_string(),
+                    quantity_type: QuantityType::Count,
+                    quantity: 2.0,
+                },
+            ],
+            meal_type: MealType::Breakfast,
+            ..create_minimal_recipe(name)
+        }
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_keeps_the_recipes_as_they_were(pool: PgPool) {
+        let repository = Postgres::new(pool);
+
+        let_assert!(
+            Ok(pancakes) = repository
+                .create_recipe(create_test_recipe("Pancakes"))
+                .await
+        );
+        let_assert!(
+            Ok(waffles) = repository
+                .create_recipe(create_test_recipe("Waffles"))
+                .await
+        );
+        let_assert!(Ok(()) = repository.set_archived(waffles.recipe_id, true).await);
+
+        let_assert!(Ok(snapshot) = repository.create_snapshot().await);
+        check!(snapshot.recipe_count == 2);
+
+        let_assert!(
+            Ok(_) = repository
+                .delete_recipe(pancakes.recipe_id, DeletionEntity::default())
+                .await
+        );
+
+        let_assert!(Ok(recipes) = repository.snapshot_recipes(snapshot.snapshot_id).await);
+        let_assert!([first, second] = recipes.as_slice());
+        check!(first.name == "Pancakes");
+        check!(first.ingredients.len() == 2);
+        check!(first.ingredients[1].name == "Eggs");
+        check!(second.name == "Waffles");
+
+        let_assert!(Ok(snapshots) = repository.list_snapshots().await);
+        let_assert!([listed] = snapshots.as_slice());
+        check!(listed.snapshot_id == snapshot.snapshot_id);
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_returns_not_found_for_an_unknown_snapshot(pool: PgPool) {
+        let repository = Postgres::new(pool);
+
+        let_assert!(Err(SnapshotRecipesError::NotFound) = repository.snapshot_recipes(1).await);
+    }
+}
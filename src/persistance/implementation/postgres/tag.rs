@@ -0,0 +1,344 @@
+use eyre::Context;
+
+use super::Postgres;
+use crate::persistance::tag::{
+    ListTagsError, RecipeTagsError, SetRecipeTagsError, TagRepository, TagUsageEntity,
+};
+
+impl TagRepository for Postgres {
+    async fn list_tags(&self) -> Result<Vec<TagUsageEntity>, ListTagsError> {
+        let data = sqlx::query_as!(
+            TagUsageEntity,
+            r#"
+                SELECT t.name, COUNT(*) AS "recipe_count!"
+                FROM recipe_tag t
+                JOIN recipe r ON r.recipe_id = t.recipe_id
+                WHERE r.archived_at IS NULL
+                GROUP BY t.name
+                ORDER BY t.name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to list tags")?;
+
+        Ok(data)
+    }
+
+    async fn recipe_tags(&self, recipe_id: i32) -> Result<Vec<String>, RecipeTagsError> {
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM recipe WHERE recipe_id = $1) AS "exists!""#,
+            recipe_id
+        )
+        .fetch_one(&self.pool)
+        .await
+        .wrap_err("Failed to check if recipe exists")?;
+
+        if !exists {
+            return Err(RecipeTagsError::NotFound);
+        }
+
+        let data = sqlx::query_scalar!(
+            "SELECT name FROM recipe_tag WHERE recipe_id = $1 ORDER BY name",
+            recipe_id
+        )
+        .fetch_all(&self.pool)
+        .await
+        .wrap_err("Failed to get recipe tags")?;
+
+        Ok(data)
+    }
+
+    async fn set_recipe_tags(
+        &self,
+        recipe_id: i32,
+        tags: Vec<String>,
+    ) -> Result<Vec<String>, SetRecipeTagsError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .wrap_err("Failed to open transaction")?;
+
+        // Locks the recipe so concurrent updates of its tags don't interleave
+        let exists = sqlx::query_scalar!(
+            "SELECT recipe_id FROM recipe WHERE recipe_id = $1 FOR UPDATE",
+            recipe_id
+        )
+        .fetch_optional(&mut *tx)
+        .await
+        .wrap_err("Failed to lock recipe")?
+        .is_some();
+
+        if !exists {
+            return Err(SetRecipeTagsError::NotFound);
+        }
+
+        sqlx::query!("DELETE FROM recipe_tag WHERE recipe_id = $1", recipe_id)
+            .execute(&mut *tx)
+            .await
+            .wrap_err("Failed to delete recipe tags")?;
+
+        sqlx::query!(
+            r#"
+                INSERT INTO recipe_tag (recipe_id, name)
+                SELECT $1, name FROM UNNEST($2::TEXT[]) AS name
+                ON CONFLICT DO NOTHING
+            "#,
+            recipe_id,
+            &tags
+        )
+        .execute(&mut *tx)
+        .await
+        .wrap_err("Failed to insert recipe tags")?;
+
+        tx.commit().await.wrap_err("Failed to commit transaction")?;
+
+        let mut tags = tags;
+        tags.sort();
+        tags.dedup();
+
+        Ok(tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistance::{
+        implementation::postgres::tests::create_minimal_recipe,
+        recipe::{RandomRecipeArguments, RecipeRepository, SearchRecipesArguments, SearchWeights},
+    };
+    use assert2::{check, let_assert};
+    use sqlx::PgPool;
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|tag| tag.to_string()).collect()
+    }
+
+    mod list_tags {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_counts_the_recipes_with_each_tag(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(stew) = repository
+                    .create_recipe(create_minimal_recipe("Stew"))
+                    .await
+            );
+            let_assert!(
+                Ok(soup) = repository
+                    .create_recipe(create_minimal_recipe("Soup"))
+                    .await
+            );
+            let_assert!(
+                Ok(salad) = repository
+                    .create_recipe(create_minimal_recipe("Salad"))
+                    .await
+            );
+
+            for (recipe, recipe_tags) in [
+                (&stew, tags(&["hearty", "winter"])),
+                (&soup, tags(&["winter"])),
+                (&salad, tags(&["summer", "winter"])),
+            ] {
+                let_assert!(
+                    Ok(_) = repository
+                        .set_recipe_tags(recipe.recipe_id, recipe_tags)
+                        .await
+                );
+            }
+            let_assert!(Ok(()) = repository.set_archived(salad.recipe_id, true).await);
+
+            let_assert!(Ok(tags) = repository.list_tags().await);
+            let_assert!([hearty, winter] = tags.as_slice());
+            check!(hearty.name == "hearty");
+            check!(hearty.recipe_count == 1);
+            check!(winter.name == "winter");
+            check!(winter.recipe_count == 2);
+        }
+    }
+
+    mod set_recipe_tags {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_replaces_the_tags_of_the_recipe(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(stew) = repository
+                    .create_recipe(create_minimal_recipe("Stew"))
+                    .await
+            );
+
+            let_assert!(
+                Ok(_) = repository
+                    .set_recipe_tags(stew.recipe_id, tags(&["winter", "hearty"]))
+                    .await
+            );
+            let_assert!(
+                Ok(set) = repository
+                    .set_recipe_tags(stew.recipe_id, tags(&["quick", "hearty"]))
+                    .await
+            );
+            check!(set == tags(&["hearty", "quick"]));
+
+            let_assert!(Ok(stored) = repository.recipe_tags(stew.recipe_id).await);
+            check!(stored == tags(&["hearty", "quick"]));
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_returns_not_found_for_an_unknown_recipe(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Err(SetRecipeTagsError::NotFound) =
+                    repository.set_recipe_tags(1, tags(&["winter"])).await
+            );
+        }
+    }
+
+    mod search_recipes {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_only_finds_recipes_with_every_tag(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(stew) = repository
+                    .create_recipe(create_minimal_recipe("Stew"))
+                    .await
+            );
+            let_assert!(
+                Ok(soup) = repository
+                    .create_recipe(create_minimal_recipe("Soup"))
+                    .await
+            );
+
+            let_assert!(
+                Ok(_) = repository
+                    .set_recipe_tags(stew.recipe_id, tags(&["hearty", "winter"]))
+                    .await
+            );
+            let_assert!(
+                Ok(_) = repository
+                    .set_recipe_tags(soup.recipe_id, tags(&["winter"]))
+                    .await
+            );
+
+            let_assert!(
+                Ok(recipes) = repository
+                    .search_recipes(SearchRecipesArguments {
+                        recipe_name: None,
+                        ingredient_name: None,
+                        meal_type: None,
+                        include_ingredients: false,
+                        archived: false,
+                        tags: tags(&["winter", "hearty"]),
+                        sort: vec![],
+                        created_after: None,
+                        created_before: None,
+                        updated_after: None,
+                        keywords: vec![],
+                        weights: SearchWeights::default(),
+                    })
+                    .await
+            );
+            let_assert!([recipe] = recipes.as_slice());
+            check!(recipe.recipe_id == stew.recipe_id);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_finds_recipes_by_tag_keywords(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(stew) = repository
+                    .create_recipe(create_minimal_recipe("Stew"))
+                    .await
+            );
+            let_assert!(
+                Ok(_) = repository
+                    .create_recipe(create_minimal_recipe("Soup"))
+                    .await
+            );
+
+            let_assert!(
+                Ok(_) = repository
+                    .set_recipe_tags(stew.recipe_id, tags(&["winter"]))
+                    .await
+            );
+
+            let_assert!(
+                Ok(recipes) = repository
+                    .search_recipes(SearchRecipesArguments {
+                        recipe_name: None,
+                        ingredient_name: None,
+                        meal_type: None,
+                        include_ingredients: false,
+                        archived: false,
+                        tags: vec![],
+                        sort: vec![],
+                        created_after: None,
+                        created_before: None,
+                        updated_after: None,
+                        keywords: tags(&["winter"]),
+                        weights: SearchWeights::default(),
+                    })
+                    .await
+            );
+            let_assert!([recipe] = recipes.as_slice());
+            check!(recipe.recipe_id == stew.recipe_id);
+        }
+    }
+
+    mod random_recipe {
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_only_picks_recipes_with_every_tag(pool: PgPool) {
+            let repository = Postgres::new(pool);
+
+            let_assert!(
+                Ok(stew) = repository
+                    .create_recipe(create_minimal_recipe("Stew"))
+                    .await
+            );
+            let_assert!(
+                Ok(soup) = repository
+                    .create_recipe(create_minimal_recipe("Soup"))
+                    .await
+            );
+
+            let_assert!(
+                Ok(_) = repository
+                    .set_recipe_tags(stew.recipe_id, tags(&["hearty", "winter"]))
+                    .await
+            );
+            let_assert!(
+                Ok(_) = repository
+                    .set_recipe_tags(soup.recipe_id, tags(&["winter"]))
+                    .await
+            );
+
+            for _ in 0..10 {
+                let_assert!(
+                    Ok(recipe) = repository
+                        .random_recipe(RandomRecipeArguments {
+                            meal_type: None,
+                            max_cooking_time: None,
+                            tags: tags(&["winter", "hearty"]),
+                        })
+                        .await
+                );
+                check!(recipe.recipe_id == stew.recipe_id);
+            }
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::persistance::recipe::{MealType, QuantityType};
+
+#[derive(Debug)]
+pub struct IngredientUsageEntity {
+    pub name: String,
+    pub recipe_count: i64,
+}
+
+/// Recipe using an ingredient, along with how much of it
+#[derive(Debug)]
+pub struct IngredientRecipeEntity {
+    pub recipe_id: i32,
+    pub public_id: Uuid,
+    pub name: String,
+    pub meal_type: MealType,
+    pub ingredient_name: String,
+    pub quantity_type: QuantityType,
+    pub quantity: f32,
+}
+
+/// Grams per milliliter of an ingredient, the name is lowercase
+#[derive(Debug)]
+pub struct IngredientDensityEntity {
+    pub name: String,
+    pub grams_per_milliliter: f32,
+}
+
+#[derive(Debug, Error)]
+pub enum ListIngredientsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum IngredientRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum RenameIngredientError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum ListDensitiesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum SetDensityError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum DeleteDensityError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("No density is known for the ingredient")]
+    NotFound,
+}
+
+pub trait IngredientRepository: std::fmt::Debug + Clone + Send + Sync + 'static {
+    /// Lists the distinct ingredient names of recipes which are not archived, ordered by name,
+    /// with the number of recipes using each of them.
+    fn list_ingredients(
+        &self,
+    ) -> impl Future<Output = Result<Vec<IngredientUsageEntity>, ListIngredientsError>> + Send;
+
+    /// Lists the recipes which are not archived using an ingredient, matched ignoring case,
+    /// ordered by recipe name. A recipe using the ingredient twice is listed twice.
+    fn ingredient_recipes(
+        &self,
+        name: String,
+    ) -> impl Future<Output = Result<Vec<IngredientRecipeEntity>, IngredientRecipesError>> + Send;
+
+    /// Renames every ingredient called `from`, ignoring case, to `to` in a single transaction.
+    /// Returns the number of recipes which had the ingredient.
+    fn rename_ingredient(
+        &self,
+        from: String,
+        to: String,
+    ) -> impl Future<Output = Result<u64, RenameIngredientError>> + Send;
+
+    /// Lists the known ingredient densities, ordered by name.
+    fn list_densities(
+        &self,
+    ) -> impl Future<Output = Result<Vec<IngredientDensityEntity>, ListDensitiesError>> + Send;
+
+    /// Sets the density of an ingredient, replacing the density it had. Names are expected to
+    /// be lowercase.
+    fn set_density(
+        &self,
+        name: String,
+        grams_per_milliliter: f32,
+    ) -> impl Future<Output = Result<IngredientDensityEntity, SetDensityError>> + Send;
+
+    /// Forgets the density of an ingredient.
+    fn delete_density(
+        &self,
+        name: String,
+    ) -> impl Future<Output = Result<(), DeleteDensityError>> + Send;
+}
@@ -0,0 +1,45 @@
+use thiserror::Error;
+
+/// Rows breaking the invariants the rest of the application relies on
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct IntegrityIssuesEntity {
+    /// Ids of ingredients pointing to a recipe which doesn't exist
+    pub orphaned_ingredients: Vec<i32>,
+    /// Ids of recipes whose `ingredient_order` values aren't exactly `0..n`
+    pub broken_ingredient_orders: Vec<i32>,
+}
+
+#[derive(Debug, Error)]
+pub enum CheckIntegrityError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum RepairIntegrityError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+/// Maintenance checks run from the command line, which is why it isn't part of
+/// [`super::Repository`]
+pub trait IntegrityRepository: std::fmt::Debug + Clone + Send + Sync + 'static {
+    fn check_integrity(
+        &self,
+    ) -> impl Future<Output = Result<IntegrityIssuesEntity, CheckIntegrityError>> + Send;
+
+    /// Deletes the orphaned ingredients and renumbers the ingredients of the recipes with a broken
+    /// order, keeping their relative order, in a single transaction.
+    fn repair_integrity(
+        &self,
+        issues: IntegrityIssuesEntity,
+    ) -> impl Future<Output = Result<(), RepairIntegrityError>> + Send;
+}
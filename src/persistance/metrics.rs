@@ -0,0 +1,524 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::persistance::{
+    analytics::{AnalyticsRepository, GetUsageError, RecordUsageError, UsageEntity},
+    cooking_log::{
+        CookingHistoryError, CookingLogEntity, CookingLogRepository, LogCookingError,
+        NewCookingLogEntity,
+    },
+    deletion::{DeletionEntity, DeletionRepository, ListDeletionsError, RecipeDeletionEntity},
+    federation::{
+        FederatedRecipesError, FederationRepository, ListInstancesError, MirrorRecipesError,
+        MirrorSummaryEntity, RecipeOriginEntity, RecipeOriginError, RegisterInstanceError,
+        RemoteInstanceEntity, RemoteRecipeEntity, RemoveInstanceError, SetFederationOptOutError,
+    },
+    ingredient::{
+        DeleteDensityError, IngredientDensityEntity, IngredientRecipeEntity,
+        IngredientRecipesError, IngredientRepository, IngredientUsageEntity, ListDensitiesError,
+        ListIngredientsError, RenameIngredientError, SetDensityError,
+    },
+    outbox::{
+        EventsSinceError, LatestEventIdError, MarkPublishedError, OutboxEventEntity,
+        OutboxRepository, UnpublishedEventsError,
+    },
+    recipe::{
+        AutocompleteError, CreateRecipeError, DeleteRecipeError, ForkRecipeError, GetRecipeError,
+        GetRecipesError, ImportRecipesError, IngredientMatchEntity, ListForksError,
+        ListRecipeError, MergeRecipesError, MutableRecipeEntity, NameKind, PopularRecipeEntity,
+        PopularRecipesError, RandomRecipeArguments, RandomRecipeError, RecipeEntity,
+        RecipeForkEntity, RecipeMetadataEntity, RecipeNameEntity, RecipeRepository,
+        RecordViewsError, ResolvePublicIdError, SearchByIngredientsError, SearchRecipeError,
+        SearchRecipesArguments, SetArchivedError, SimilarNamesError, UpdateMetadataError,
+        UpdateRecipeError,
+    },
+    retention::{
+        CountExpiredError, ExpiredCountsEntity, PurgeExpiredError, RetentionCutoffsEntity,
+        RetentionRepository,
+    },
+    saved_search::{
+        DeleteSavedSearchError, GetSavedSearchError, ListSavedSearchesError, SaveSearchError,
+        SavedCriteriaEntity, SavedSearchEntity, SavedSearchRepository,
+    },
+    share_link::{
+        CreateShareLinkError, ListShareLinksError, ResolveShareLinkError, RevokeShareLinkError,
+        ShareLinkEntity, ShareLinkRepository,
+    },
+    snapshot::{
+        CreateSnapshotError, ListSnapshotsError, SnapshotEntity, SnapshotRecipeEntity,
+        SnapshotRecipesError, SnapshotRepository,
+    },
+    tag::{ListTagsError, RecipeTagsError, SetRecipeTagsError, TagRepository, TagUsageEntity},
+};
+
+/// Number of most recent calls per operation the percentiles are taken from
+const LATENCY_WINDOW: usize = 1024;
+
+/// Characters of a text argument kept for the log of a slow call
+const MAX_LOGGED_CHARS: usize = 100;
+
+/// Latency percentiles of a single operation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencySummary {
+    /// Operation the latencies were recorded for, like `get_recipe` or `GET /recipes/{id}`
+    pub operation: String,
+    /// Number of calls recorded since startup
+    pub count: u64,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub p99: Duration,
+}
+
+/// Keeps the latencies of the most recent calls per operation. Clones share their latencies.
+#[derive(Debug, Clone, Default)]
+pub struct Latencies {
+    operations: Arc<Mutex<HashMap<String, OperationLatencies>>>,
+}
+
+#[derive(Debug, Default)]
+struct OperationLatencies {
+    count: u64,
+    recent: VecDeque<Duration>,
+}
+
+impl Latencies {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, operation: &str, latency: Duration) {
+        let mut operations = self.operations();
+        let latencies = operations.entry(operation.to_string()).or_default();
+
+        latencies.count += 1;
+        if latencies.recent.len() == LATENCY_WINDOW {
+            latencies.recent.pop_front();
+        }
+        latencies.recent.push_back(latency);
+    }
+
+    /// Percentiles of every operation recorded so far, ordered by operation
+    pub fn summaries(&self) -> Vec<LatencySummary> {
+        let mut summaries = self
+            .operations()
+            .iter()
+            .map(|(operation, latencies)| {
+                let mut recent = Vec::from(latencies.recent.clone());
+                recent.sort_unstable();
+
+                LatencySummary {
+                    operation: operation.clone(),
+                    count: latencies.count,
+                    p50: percentile(&recent, 50),
+                    p95: percentile(&recent, 95),
+                    p99: percentile(&recent, 99),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        summaries.sort_by(|a, b| a.operation.cmp(&b.operation));
+        summaries
+    }
+
+    fn operations(&self) -> std::sync::MutexGuard<'_, HashMap<String, OperationLatencies>> {
+        self.operations.lock().expect("Latencies lock poisoned")
+    }
+}
+
+/// Nearest-rank percentile of sorted latencies
+fn percentile(sorted: &[Duration], percentile: usize) -> Duration {
+    let rank = (sorted.len() * percentile).div_ceil(100);
+
+    sorted
+        .get(rank.saturating_sub(1))
+        .copied()
+        .unwrap_or_default()
+}
+
+/// How an argument shows up in the log of a slow call. The arguments are moved into the call, so
+/// this is taken up front and kept cheap: values are copied, text is cut short, and collections and
+/// entities, which can be large or hold personal data, only show their size or kind. It is only
+/// formatted once the call turned out to be slow.
+trait LogArgument {
+    type Logged: fmt::Debug;
+
+    fn logged(&self) -> Self::Logged;
+}
+
+macro_rules! log_values {
+    ($($ty:ty),*) => {
+        $(
+            impl LogArgument for $ty {
+                type Logged = Self;
+
+                fn logged(&self) -> Self {
+                    *self
+                }
+            }
+        )*
+    };
+}
+
+log_values!(bool, i32, i64, f32, Option<i32>, Uuid, NaiveDate, NameKind);
+
+impl LogArgument for String {
+    type Logged = String;
+
+    fn logged(&self) -> String {
+        self.chars().take(MAX_LOGGED_CHARS).collect()
+    }
+}
+
+/// Collection logged by its number of items
+struct Items(usize);
+
+impl fmt::Debug for Items {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{} items]", self.0)
+    }
+}
+
+impl<T> LogArgument for Vec<T> {
+    type Logged = Items;
+
+    fn logged(&self) -> Items {
+        Items(self.len())
+    }
+}
+
+/// Entity logged by its name only
+struct Omitted(&'static str);
+
+impl fmt::Debug for Omitted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {{ .. }}", self.0)
+    }
+}
+
+macro_rules! log_omitted {
+    ($($ty:ident),*) => {
+        $(
+            impl LogArgument for $ty {
+                type Logged = Omitted;
+
+                fn logged(&self) -> Omitted {
+                    Omitted(stringify!($ty))
+                }
+            }
+        )*
+    };
+}
+
+log_omitted!(
+    MutableRecipeEntity,
+    DeletionEntity,
+    SearchRecipesArguments,
+    RandomRecipeArguments,
+    NewCookingLogEntity,
+    SavedCriteriaEntity,
+    RetentionCutoffsEntity
+);
+
+/// Logged in place of secrets, like tokens
+struct Redacted;
+
+impl fmt::Debug for Redacted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+/// Takes what is logged of an argument, arguments marked with `#[redact]` are left out
+macro_rules! logged_argument {
+    (redact $arg:ident) => {{
+        let _ = &$arg;
+        Redacted
+    }};
+    ($arg:ident) => {
+        LogArgument::logged(&$arg)
+    };
+}
+
+/// Repository which records how long every call to another repository takes, per method. Calls
+/// taking at least `slow_threshold` are logged as a warning along with their arguments.
+#[derive(Debug, Clone)]
+pub struct Metered<R> {
+    repository: R,
+    latencies: Latencies,
+    slow_threshold: Option<Duration>,
+}
+
+impl<R> Metered<R> {
+    pub fn new(repository: R, latencies: Latencies, slow_threshold: Option<Duration>) -> Self {
+        Self {
+            repository,
+            latencies,
+            slow_threshold,
+        }
+    }
+
+    fn record(
+        &self,
+        operation: &'static str,
+        arguments: Option<&dyn fmt::Debug>,
+        elapsed: Duration,
+    ) {
+        self.latencies.record(operation, elapsed);
+
+        let (Some(threshold), Some(arguments)) = (self.slow_threshold, arguments) else {
+            return;
+        };
+
+        if elapsed >= threshold {
+            tracing::warn!(
+                operation,
+                arguments = format!("{arguments:?}"),
+                ?elapsed,
+                ?threshold,
+                "Slow repository call"
+            );
+        }
+    }
+}
+
+/// Implements a repository trait for [`Metered`] by timing the calls to the wrapped repository
+macro_rules! meter_repository {
+    (
+        $trait:ident {
+            $(fn $method:ident($($(#[$redact:ident])? $arg:ident: $ty:ty),*) -> $ret:ty;)*
+        }
+    ) => {
+        impl<R: $trait> $trait for Metered<R> {
+            $(
+                async fn $method(&self, $($arg: $ty),*) -> $ret {
+                    // Taken up front, the arguments are moved into the call
+                    let arguments = self
+                        .slow_threshold
+                        .map(|_| ($(logged_argument!($($redact)? $arg),)*));
+
+                    let started = Instant::now();
+                    let result = self.repository.$method($($arg),*).await;
+                    self.record(
+                        stringify!($method),
+                        arguments.as_ref().map(|arguments| arguments as &dyn fmt::Debug),
+                        started.elapsed(),
+                    );
+
+                    result
+                }
+            )*
+        }
+    };
+}
+
+meter_repository!(RecipeRepository {
+    fn list_recipes(include_ingredients: bool, archived: bool)
+        -> Result<Vec<RecipeEntity>, ListRecipeError>;
+    fn get_recipe(recipe_id: i32) -> Result<RecipeEntity, GetRecipeError>;
+    fn resolve_public_id(public_id: Uuid) -> Result<i32, ResolvePublicIdError>;
+    fn get_recipes(recipe_ids: Vec<i32>, include_ingredients: bool)
+        -> Result<Vec<RecipeEntity>, GetRecipesError>;
+    fn create_recipe(entity: MutableRecipeEntity) -> Result<RecipeEntity, CreateRecipeError>;
+    fn import_recipes(entities: Vec<MutableRecipeEntity>) -> Result<Vec<i32>, ImportRecipesError>;
+    fn update_recipe(recipe_id: i32, entity: MutableRecipeEntity)
+        -> Result<RecipeEntity, UpdateRecipeError>;
+    fn update_metadata(changes: Vec<RecipeMetadataEntity>)
+        -> Result<Vec<i32>, UpdateMetadataError>;
+    fn delete_recipe(recipe_id: i32, deletion: DeletionEntity)
+        -> Result<RecipeEntity, DeleteRecipeError>;
+    fn fork_recipe(recipe_id: i32) -> Result<RecipeEntity, ForkRecipeError>;
+    fn list_forks(recipe_id: i32) -> Result<Vec<RecipeForkEntity>, ListForksError>;
+    fn merge_recipes(target_id: i32, source_id: i32, entity: MutableRecipeEntity)
+        -> Result<RecipeEntity, MergeRecipesError>;
+    fn search_recipes(args: SearchRecipesArguments)
+        -> Result<Vec<RecipeEntity>, SearchRecipeError>;
+    fn similar_names(term: String, kind: NameKind, archived: bool, limit: i64)
+        -> Result<Vec<String>, SimilarNamesError>;
+    fn autocomplete(prefix: String, limit: i64)
+        -> Result<Vec<RecipeNameEntity>, AutocompleteError>;
+    fn set_archived(recipe_id: i32, archived: bool) -> Result<(), SetArchivedError>;
+    fn search_by_ingredients(ingredient_names: Vec<String>, limit: i64)
+        -> Result<Vec<IngredientMatchEntity>, SearchByIngredientsError>;
+    fn random_recipe(args: RandomRecipeArguments) -> Result<RecipeEntity, RandomRecipeError>;
+    fn record_views(views: Vec<(i32, i64)>) -> Result<(), RecordViewsError>;
+    fn popular_recipes(window_days: i32, limit: i64)
+        -> Result<Vec<PopularRecipeEntity>, PopularRecipesError>;
+});
+
+meter_repository!(CookingLogRepository {
+    fn log_cooking(recipe_id: i32, entity: NewCookingLogEntity)
+        -> Result<CookingLogEntity, LogCookingError>;
+    fn cooking_history(recipe_id: i32) -> Result<Vec<CookingLogEntity>, CookingHistoryError>;
+});
+
+meter_repository!(ShareLinkRepository {
+    fn create_share_link(recipe_id: i32, #[redact] token: String)
+        -> Result<ShareLinkEntity, CreateShareLinkError>;
+    fn list_share_links(recipe_id: i32) -> Result<Vec<ShareLinkEntity>, ListShareLinksError>;
+    fn revoke_share_link(recipe_id: i32, share_link_id: i32) -> Result<(), RevokeShareLinkError>;
+    fn resolve_share_link(#[redact] token: &str) -> Result<i32, ResolveShareLinkError>;
+});
+
+meter_repository!(FederationRepository {
+    fn register_instance(url: String, #[redact] token: Option<Vec<u8>>)
+        -> Result<RemoteInstanceEntity, RegisterInstanceError>;
+    fn list_instances() -> Result<Vec<RemoteInstanceEntity>, ListInstancesError>;
+    fn remove_instance(remote_instance_id: i32) -> Result<(), RemoveInstanceError>;
+    fn federated_recipes() -> Result<Vec<RecipeEntity>, FederatedRecipesError>;
+    fn mirror_recipes(remote_instance_id: i32, recipes: Vec<RemoteRecipeEntity>)
+        -> Result<MirrorSummaryEntity, MirrorRecipesError>;
+    fn recipe_origin(recipe_id: i32) -> Result<Option<RecipeOriginEntity>, RecipeOriginError>;
+    fn set_federation_opt_out(recipe_id: i32, opt_out: bool)
+        -> Result<(), SetFederationOptOutError>;
+});
+
+meter_repository!(OutboxRepository {
+    fn unpublished_events(limit: i64) -> Result<Vec<OutboxEventEntity>, UnpublishedEventsError>;
+    fn events_since(outbox_id: i64, limit: i64)
+        -> Result<Vec<OutboxEventEntity>, EventsSinceError>;
+    fn latest_event_id(recipe_id: i32) -> Result<Option<i64>, LatestEventIdError>;
+    fn mark_published(outbox_ids: Vec<i64>) -> Result<(), MarkPublishedError>;
+});
+
+meter_repository!(IngredientRepository {
+    fn list_ingredients() -> Result<Vec<IngredientUsageEntity>, ListIngredientsError>;
+    fn ingredient_recipes(name: String)
+        -> Result<Vec<IngredientRecipeEntity>, IngredientRecipesError>;
+    fn rename_ingredient(from: String, to: String) -> Result<u64, RenameIngredientError>;
+    fn list_densities() -> Result<Vec<IngredientDensityEntity>, ListDensitiesError>;
+    fn set_density(name: String, grams_per_milliliter: f32)
+        -> Result<IngredientDensityEntity, SetDensityError>;
+    fn delete_density(name: String) -> Result<(), DeleteDensityError>;
+});
+
+meter_repository!(SavedSearchRepository {
+    fn save_search(name: String, criteria: SavedCriteriaEntity)
+        -> Result<SavedSearchEntity, SaveSearchError>;
+    fn list_saved_searches() -> Result<Vec<SavedSearchEntity>, ListSavedSearchesError>;
+    fn get_saved_search(saved_search_id: i32) -> Result<SavedSearchEntity, GetSavedSearchError>;
+    fn delete_saved_search(saved_search_id: i32) -> Result<(), DeleteSavedSearchError>;
+});
+
+meter_repository!(TagRepository {
+    fn list_tags() -> Result<Vec<TagUsageEntity>, ListTagsError>;
+    fn recipe_tags(recipe_id: i32) -> Result<Vec<String>, RecipeTagsError>;
+    fn set_recipe_tags(recipe_id: i32, tags: Vec<String>)
+        -> Result<Vec<String>, SetRecipeTagsError>;
+});
+
+meter_repository!(SnapshotRepository {
+    fn create_snapshot() -> Result<SnapshotEntity, CreateSnapshotError>;
+    fn list_snapshots() -> Result<Vec<SnapshotEntity>, ListSnapshotsError>;
+    fn snapshot_recipes(snapshot_id: i32)
+        -> Result<Vec<SnapshotRecipeEntity>, SnapshotRecipesError>;
+});
+
+meter_repository!(DeletionRepository {
+    fn list_deletions(recipe_id: Option<i32>, limit: i64)
+        -> Result<Vec<RecipeDeletionEntity>, ListDeletionsError>;
+});
+
+meter_repository!(RetentionRepository {
+    fn count_expired(cutoffs: RetentionCutoffsEntity)
+        -> Result<ExpiredCountsEntity, CountExpiredError>;
+    fn purge_expired(cutoffs: RetentionCutoffsEntity)
+        -> Result<ExpiredCountsEntity, PurgeExpiredError>;
+});
+
+meter_repository!(AnalyticsRepository {
+    fn record_usage(routes: Vec<(String, i64)>, search_terms: Vec<(String, i64)>)
+        -> Result<(), RecordUsageError>;
+    fn get_usage(since: NaiveDate, min_searches: i64, limit: i64)
+        -> Result<UsageEntity, GetUsageError>;
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+
+    use crate::test_utils::{MockRepository, recipe_entity};
+
+    #[test]
+    fn it_takes_percentiles_from_the_recorded_latencies() {
+        let latencies = Latencies::new();
+        for millis in (1..=100).rev() {
+            latencies.record("get_recipe", Duration::from_millis(millis));
+        }
+
+        let summaries = latencies.summaries();
+        let_assert!([summary] = summaries.as_slice());
+        check!(summary.operation == "get_recipe");
+        check!(summary.count == 100);
+        check!(summary.p50 == Duration::from_millis(50));
+        check!(summary.p95 == Duration::from_millis(95));
+        check!(summary.p99 == Duration::from_millis(99));
+    }
+
+    #[test]
+    fn it_only_keeps_the_most_recent_latencies() {
+        let latencies = Latencies::new();
+        latencies.record("get_recipe", Duration::from_secs(10));
+        for _ in 0..LATENCY_WINDOW {
+            latencies.record("get_recipe", Duration::from_millis(1));
+        }
+
+        let summaries = latencies.summaries();
+        let_assert!([summary] = summaries.as_slice());
+        check!(summary.count == LATENCY_WINDOW as u64 + 1);
+        check!(summary.p99 == Duration::from_millis(1));
+    }
+
+    #[test]
+    fn it_logs_arguments_without_secrets_or_contents() {
+        let token = Some("secret".to_string());
+        let recipe_ids = vec![1, 2, 3];
+        let name = "a".repeat(MAX_LOGGED_CHARS + 50);
+        let deletion = DeletionEntity::default();
+
+        let logged = (
+            logged_argument!(redact token),
+            logged_argument!(recipe_ids),
+            logged_argument!(name),
+            logged_argument!(deletion),
+        );
+
+        check!(
+            format!("{logged:?}")
+                == format!(
+                    r#"(<redacted>, [3 items], "{}", DeletionEntity {{ .. }})"#,
+                    "a".repeat(MAX_LOGGED_CHARS)
+                )
+        );
+    }
+
+    #[tokio::test]
+    async fn it_records_calls_per_method_whatever_their_outcome() {
+        let repository = MockRepository::new();
+        repository
+            .on_delete_recipe(Ok(recipe_entity(1, "Soup")))
+            .on_delete_recipe(Err(DeleteRecipeError::NotFound))
+            .on_resolve_share_link(Ok(1));
+
+        let latencies = Latencies::new();
+        let metered = Metered::new(repository, latencies.clone(), None);
+        let _ = metered.delete_recipe(1, DeletionEntity::default()).await;
+        let _ = metered.delete_recipe(2, DeletionEntity::default()).await;
+        let _ = metered.resolve_share_link("token").await;
+
+        let summaries = latencies.summaries();
+        let_assert!([delete, resolve] = summaries.as_slice());
+        check!(delete.operation == "delete_recipe");
+        check!(delete.count == 2);
+        check!(resolve.operation == "resolve_share_link");
+        check!(resolve.count == 1);
+    }
+}
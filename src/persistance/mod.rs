@@ -1,2 +1,49 @@
-pub(crate) mod implementation;
-pub(crate) mod recipe;
+pub mod analytics;
+pub mod circuit_breaker;
+pub mod cooking_log;
+pub mod deletion;
+pub mod federation;
+pub mod implementation;
+pub mod ingredient;
+pub mod integrity;
+pub mod metrics;
+pub mod outbox;
+pub mod recipe;
+pub mod retention;
+pub mod saved_search;
+pub mod share_link;
+pub mod snapshot;
+pub mod tag;
+
+/// All repositories the API is served from, implemented for every type implementing each of them
+pub trait Repository:
+    recipe::RecipeRepository
+    + cooking_log::CookingLogRepository
+    + share_link::ShareLinkRepository
+    + outbox::OutboxRepository
+    + federation::FederationRepository
+    + ingredient::IngredientRepository
+    + saved_search::SavedSearchRepository
+    + snapshot::SnapshotRepository
+    + deletion::DeletionRepository
+    + retention::RetentionRepository
+    + analytics::AnalyticsRepository
+    + tag::TagRepository
+{
+}
+
+impl<T> Repository for T where
+    T: recipe::RecipeRepository
+        + cooking_log::CookingLogRepository
+        + share_link::ShareLinkRepository
+        + outbox::OutboxRepository
+        + federation::FederationRepository
+        + ingredient::IngredientRepository
+        + saved_search::SavedSearchRepository
+        + snapshot::SnapshotRepository
+        + deletion::DeletionRepository
+        + retention::RetentionRepository
+        + analytics::AnalyticsRepository
+        + tag::TagRepository
+{
+}
@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+use sqlx::Type;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub struct OutboxEventEntity {
+    pub outbox_id: i64,
+    pub kind: RecipeEventKind,
+    pub recipe_id: i32,
+    pub public_id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+    /// When the recipe was archived, as it is now. `None` for recipes which aren't archived or
+    /// were deleted.
+    pub archived_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, Type)]
+#[sqlx(type_name = "recipe_event_kind")]
+pub enum RecipeEventKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+#[derive(Debug, Error)]
+pub enum UnpublishedEventsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum EventsSinceError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum LatestEventIdError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum MarkPublishedError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+/// Read side of the outbox. Events are written by the repositories themselves, inside the
+/// transaction of the change they describe.
+pub trait OutboxRepository: std::fmt::Debug + Clone + Send + Sync + 'static {
+    /// Lists the oldest events which have not been published yet, oldest first
+    fn unpublished_events(
+        &self,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<OutboxEventEntity>, UnpublishedEventsError>> + Send;
+
+    /// Lists the events recorded after the given outbox id whether published or not, oldest
+    /// first. Events are never removed and become visible in the order of their ids, which makes
+    /// the outbox id usable as a change cursor.
+    fn events_since(
+        &self,
+        outbox_id: i64,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<OutboxEventEntity>, EventsSinceError>> + Send;
+
+    /// Finds the id of the last event recorded for a recipe, `None` when there is none
+    fn latest_event_id(
+        &self,
+        recipe_id: i32,
+    ) -> impl Future<Output = Result<Option<i64>, LatestEventIdError>> + Send;
+
+    fn mark_published(
+        &self,
+        outbox_ids: Vec<i64>,
+    ) -> impl Future<Output = Result<(), MarkPublishedError>> + Send;
+}
@@ -1,55 +1,171 @@
 use std::time::Duration;
 
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, Type};
 use thiserror::Error;
+use uuid::Uuid;
+
+use crate::persistance::deletion::DeletionEntity;
 
 #[derive(Debug)]
-pub(crate) struct RecipeEntity {
-    pub(crate) recipe_id: i32,
-    pub(crate) name: String,
-    pub(crate) description: Option<String>,
-    pub(crate) ingredients: Vec<IngredientEntity>,
-    pub(crate) cooking_time: Option<Duration>,
-    pub(crate) meal_type: MealType,
+pub struct RecipeEntity {
+    pub recipe_id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub ingredients: Vec<IngredientEntity>,
+    pub cooking_time: Option<Duration>,
+    pub meal_type: MealType,
+    pub last_cooked_at: Option<NaiveDate>,
+    pub video_url: Option<String>,
+    pub video_title: Option<String>,
+    pub video_thumbnail_url: Option<String>,
+    pub pairing: Option<String>,
+    pub pairing_kind: Option<PairingKind>,
+    pub forked_from: Option<i32>,
+    pub public_id: Uuid,
 }
 
 #[derive(Debug, FromRow, Serialize, Deserialize)]
-pub(crate) struct IngredientEntity {
-    pub(crate) ingredient_id: i32,
-    pub(crate) recipe_id: i32,
-    pub(crate) ingredient_order: i32,
-    pub(crate) name: String,
-    pub(crate) quantity_type: QuantityType,
-    pub(crate) quantity: f32,
+pub struct IngredientEntity {
+    pub ingredient_id: i32,
+    pub recipe_id: i32,
+    pub ingredient_order: i32,
+    pub name: String,
+    pub quantity_type: QuantityType,
+    pub quantity: f32,
 }
 
 #[derive(Debug)]
-pub(crate) struct MutableRecipeEntity {
-    pub(crate) name: String,
-    pub(crate) description: Option<String>,
-    pub(crate) ingredients: Vec<MutableIngredientEntity>,
-    pub(crate) cooking_time: Option<Duration>,
-    pub(crate) meal_type: MealType,
+pub struct MutableRecipeEntity {
+    pub name: String,
+    pub description: Option<String>,
+    pub ingredients: Vec<MutableIngredientEntity>,
+    pub cooking_time: Option<Duration>,
+    pub meal_type: MealType,
+    pub video_url: Option<String>,
+    pub video_title: Option<String>,
+    pub video_thumbnail_url: Option<String>,
+    pub pairing: Option<String>,
+    pub pairing_kind: Option<PairingKind>,
 }
 
 #[derive(Debug, FromRow, Serialize, Deserialize)]
-pub(crate) struct MutableIngredientEntity {
-    pub(crate) name: String,
-    pub(crate) quantity_type: QuantityType,
-    pub(crate) quantity: f32,
+pub struct MutableIngredientEntity {
+    pub name: String,
+    pub quantity_type: QuantityType,
+    pub quantity: f32,
+}
+
+/// Changes to the metadata of a single recipe, fields which are `None` are left as they are
+#[derive(Debug)]
+pub struct RecipeMetadataEntity {
+    pub recipe_id: i32,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub cooking_time: Option<Duration>,
+    pub meal_type: Option<MealType>,
+    /// Replaces the tags of the recipe, tags are expected to be lowercase and distinct
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Debug)]
+pub struct SearchRecipesArguments {
+    /// Matches the name or the description, recipes matching by name come first
+    pub recipe_name: Option<String>,
+    pub ingredient_name: Option<String>,
+    pub meal_type: Option<MealType>,
+    pub include_ingredients: bool,
+    /// Searches the archived recipes instead of the others
+    pub archived: bool,
+    /// Only recipes tagged with every one of these tags are found
+    pub tags: Vec<String>,
+    /// Orders the recipes by each key in turn, and by id after that. Keys are expected to be
+    /// distinct.
+    pub sort: Vec<SearchSort>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    /// Only recipes changed after this moment, changes to ingredients and tags included
+    pub updated_after: Option<DateTime<Utc>>,
+    /// Recipes matching any of these words in their name, description, ingredients or tags,
+    /// ranked by where they match. Comes after the sort keys when ordering.
+    pub keywords: Vec<String>,
+    pub weights: SearchWeights,
+}
+
+/// What a keyword matching each field adds to the rank of a recipe. Fields with a weight of zero
+/// still match, they just don't move the recipe up.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SearchWeights {
+    pub name: f32,
+    pub ingredient: f32,
+    pub tag: f32,
+    pub description: f32,
+}
+
+/// Ordering of search results
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchSort {
+    /// Shortest cooking time first, recipes without a cooking time last
+    CookingTime,
+    /// Most recently cooked first, recipes which were never cooked last
+    LastCooked,
+}
+
+impl SearchSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchSort::CookingTime => "cooking_time",
+            SearchSort::LastCooked => "last_cooked",
+        }
+    }
+}
+
+/// Which names [`RecipeRepository::similar_names`] looks through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameKind {
+    Recipe,
+    Ingredient,
+}
+
+#[derive(Debug)]
+pub struct RecipeNameEntity {
+    pub recipe_id: i32,
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub struct PopularRecipeEntity {
+    pub recipe: RecipeEntity,
+    pub views: i64,
 }
 
 #[derive(Debug)]
-pub(crate) struct SearchRecipesArguments {
-    pub(crate) recipe_name: Option<String>,
-    pub(crate) ingredient_name: Option<String>,
-    pub(crate) meal_type: Option<MealType>,
+pub struct IngredientMatchEntity {
+    pub recipe: RecipeEntity,
+    pub matched: i64,
+    pub missing: i64,
+}
+
+/// Recipe forked from another recipe, somewhere below the recipe the forks were listed for
+#[derive(Debug)]
+pub struct RecipeForkEntity {
+    pub recipe_id: i32,
+    pub name: String,
+    pub forked_from: i32,
+}
+
+#[derive(Debug)]
+pub struct RandomRecipeArguments {
+    pub meal_type: Option<MealType>,
+    pub max_cooking_time: Option<Duration>,
+    /// Only recipes tagged with every one of these tags are picked
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Type, Serialize, Deserialize)]
 #[sqlx(type_name = "quantity_type")]
-pub(crate) enum QuantityType {
+pub enum QuantityType {
     Count,
     Kilo,
     Gram,
@@ -57,36 +173,201 @@ pub(crate) enum QuantityType {
     Milliliter,
 }
 
+impl QuantityType {
+    /// Label of the variant in the Postgres enum, for queries which can't bind it like `COPY`
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            QuantityType::Count => "Count",
+            QuantityType::Kilo => "Kilo",
+            QuantityType::Gram => "Gram",
+            QuantityType::Liter => "Liter",
+            QuantityType::Milliliter => "Milliliter",
+        }
+    }
+}
+
+#[derive(Debug, Type, Serialize, Deserialize)]
+#[sqlx(type_name = "pairing_kind")]
+pub enum PairingKind {
+    Wine,
+    Beer,
+    NonAlcoholic,
+}
+
+impl PairingKind {
+    /// Label of the variant in the Postgres enum, for queries which can't bind it like `COPY`
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            PairingKind::Wine => "Wine",
+            PairingKind::Beer => "Beer",
+            PairingKind::NonAlcoholic => "NonAlcoholic",
+        }
+    }
+}
+
 #[derive(Debug, Type, Serialize, Deserialize)]
 #[sqlx(type_name = "meal_type")]
-pub(crate) enum MealType {
+pub enum MealType {
     Breakfast,
     Lunch,
     Dinner,
 }
 
+impl MealType {
+    /// Label of the variant in the Postgres enum, for queries which can't bind it like `COPY`
+    pub fn as_label(&self) -> &'static str {
+        match self {
+            MealType::Breakfast => "Breakfast",
+            MealType::Lunch => "Lunch",
+            MealType::Dinner => "Dinner",
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ListRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum GetRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum CreateRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum ImportRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum UpdateRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+#[derive(Debug, Error)]
+pub enum UpdateMetadataError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum DeleteRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+#[derive(Debug, Error)]
+pub enum SearchRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum SearchByIngredientsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum ForkRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+#[derive(Debug, Error)]
+pub enum ListForksError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
 #[derive(Debug, Error)]
-pub(crate) enum ListRecipeError {
+pub enum MergeRecipesError {
     #[error("An unknown error occured: {0:}")]
     Unknown(
         #[from]
         #[source]
         eyre::Report,
     ),
+    #[error("The recipe could not be found")]
+    NotFound,
 }
 
 #[derive(Debug, Error)]
-pub(crate) enum CreateRecipeError {
+pub enum RandomRecipeError {
     #[error("An unknown error occured: {0:}")]
     Unknown(
         #[from]
         #[source]
         eyre::Report,
     ),
+    #[error("No recipe matched the given criteria")]
+    NotFound,
 }
 
 #[derive(Debug, Error)]
-pub(crate) enum UpdateRecipeError {
+pub enum GetRecipeError {
     #[error("An unknown error occured: {0:}")]
     Unknown(
         #[from]
@@ -98,7 +379,7 @@ pub(crate) enum UpdateRecipeError {
 }
 
 #[derive(Debug, Error)]
-pub(crate) enum DeleteRecipeError {
+pub enum ResolvePublicIdError {
     #[error("An unknown error occured: {0:}")]
     Unknown(
         #[from]
@@ -110,7 +391,17 @@ pub(crate) enum DeleteRecipeError {
 }
 
 #[derive(Debug, Error)]
-pub(crate) enum SearchRecipeError {
+pub enum SimilarNamesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum AutocompleteError {
     #[error("An unknown error occured: {0:}")]
     Unknown(
         #[from]
@@ -119,24 +410,179 @@ pub(crate) enum SearchRecipeError {
     ),
 }
 
-pub(crate) trait RecipeRepository: std::fmt::Debug + Clone + Send + Sync + 'static {
-    async fn list_recipes(&self) -> Result<Vec<RecipeEntity>, ListRecipeError>;
+#[derive(Debug, Error)]
+pub enum SetArchivedError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
 
-    async fn create_recipe(
+#[derive(Debug, Error)]
+pub enum RecordViewsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum PopularRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+pub trait RecipeRepository: std::fmt::Debug + Clone + Send + Sync + 'static {
+    /// Lists all recipes which are archived or not, depending on `archived`. The ingredients are
+    /// left empty when `include_ingredients` is false, which skips aggregating them altogether.
+    fn list_recipes(
+        &self,
+        include_ingredients: bool,
+        archived: bool,
+    ) -> impl Future<Output = Result<Vec<RecipeEntity>, ListRecipeError>> + Send;
+
+    fn get_recipe(
+        &self,
+        recipe_id: i32,
+    ) -> impl Future<Output = Result<RecipeEntity, GetRecipeError>> + Send;
+
+    /// Looks up the id of the recipe with the given public id
+    fn resolve_public_id(
+        &self,
+        public_id: Uuid,
+    ) -> impl Future<Output = Result<i32, ResolvePublicIdError>> + Send;
+
+    /// Gets the recipes with the given ids in the order they were requested. Ids which don't
+    /// exist are skipped.
+    fn get_recipes(
+        &self,
+        recipe_ids: Vec<i32>,
+        include_ingredients: bool,
+    ) -> impl Future<Output = Result<Vec<RecipeEntity>, GetRecipesError>> + Send;
+
+    fn create_recipe(
         &self,
         entity: MutableRecipeEntity,
-    ) -> Result<RecipeEntity, CreateRecipeError>;
+    ) -> impl Future<Output = Result<RecipeEntity, CreateRecipeError>> + Send;
 
-    async fn update_recipe(
+    /// Creates all given recipes at once, which is a lot faster than creating them one by one for
+    /// large imports. Returns the ids of the new recipes in the order the entities were given.
+    fn import_recipes(
+        &self,
+        entities: Vec<MutableRecipeEntity>,
+    ) -> impl Future<Output = Result<Vec<i32>, ImportRecipesError>> + Send;
+
+    fn update_recipe(
         &self,
         recipe_id: i32,
         entity: MutableRecipeEntity,
-    ) -> Result<RecipeEntity, UpdateRecipeError>;
+    ) -> impl Future<Output = Result<RecipeEntity, UpdateRecipeError>> + Send;
 
-    async fn delete_recipe(&self, recipe_id: i32) -> Result<(), DeleteRecipeError>;
+    /// Applies the metadata changes of many recipes at once, in a single transaction. Returns the
+    /// ids of the recipes which were updated, changes to recipes which don't exist are skipped.
+    fn update_metadata(
+        &self,
+        changes: Vec<RecipeMetadataEntity>,
+    ) -> impl Future<Output = Result<Vec<i32>, UpdateMetadataError>> + Send;
 
-    async fn search_recipes(
+    /// Deletes a recipe, its forks are moved up to the recipe it was forked from itself. The
+    /// deletion is recorded along with who deleted it. Returns the recipe as it was right before
+    /// it was deleted.
+    fn delete_recipe(
+        &self,
+        recipe_id: i32,
+        deletion: DeletionEntity,
+    ) -> impl Future<Output = Result<RecipeEntity, DeleteRecipeError>> + Send;
+
+    /// Copies a recipe with its ingredients into a new recipe which records where it was forked
+    /// from. Views and the cooking log are not copied.
+    fn fork_recipe(
+        &self,
+        recipe_id: i32,
+    ) -> impl Future<Output = Result<RecipeEntity, ForkRecipeError>> + Send;
+
+    /// Lists all forks of a recipe, including forks of forks, ordered by recipe id
+    fn list_forks(
+        &self,
+        recipe_id: i32,
+    ) -> impl Future<Output = Result<Vec<RecipeForkEntity>, ListForksError>> + Send;
+
+    /// Saves `entity` as the target recipe and folds the source recipe into it. The views, cooking
+    /// log, share links, tags and forks of the source are moved to the target before the source is
+    /// deleted.
+    fn merge_recipes(
+        &self,
+        target_id: i32,
+        source_id: i32,
+        entity: MutableRecipeEntity,
+    ) -> impl Future<Output = Result<RecipeEntity, MergeRecipesError>> + Send;
+
+    fn search_recipes(
         &self,
         args: SearchRecipesArguments,
-    ) -> Result<Vec<RecipeEntity>, SearchRecipeError>;
+    ) -> impl Future<Output = Result<Vec<RecipeEntity>, SearchRecipeError>> + Send;
+
+    /// Lists distinct recipe or ingredient names which look like `term` by trigram similarity,
+    /// most similar first. Only names of recipes in the archive are considered when `archived`
+    /// is set, otherwise only those of the other recipes.
+    fn similar_names(
+        &self,
+        term: String,
+        kind: NameKind,
+        archived: bool,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<String>, SimilarNamesError>> + Send;
+
+    /// Lists recipes which are not archived with a name starting with `prefix`, ignoring case and
+    /// accents, ordered by name.
+    fn autocomplete(
+        &self,
+        prefix: String,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<RecipeNameEntity>, AutocompleteError>> + Send;
+
+    /// Archives or unarchives a recipe. Archived recipes are left out of listings, searches,
+    /// random picks and popular recipes, but can still be fetched by id.
+    fn set_archived(
+        &self,
+        recipe_id: i32,
+        archived: bool,
+    ) -> impl Future<Output = Result<(), SetArchivedError>> + Send;
+
+    /// Ranks recipes by how many of their ingredients partially match one of the given names,
+    /// recipes without any matching ingredient and archived recipes are left out.
+    fn search_by_ingredients(
+        &self,
+        ingredient_names: Vec<String>,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<IngredientMatchEntity>, SearchByIngredientsError>> + Send;
+
+    fn random_recipe(
+        &self,
+        args: RandomRecipeArguments,
+    ) -> impl Future<Output = Result<RecipeEntity, RandomRecipeError>> + Send;
+
+    /// Adds the given `(recipe_id, views)` pairs to today's view counts. Views for recipes which
+    /// no longer exist are dropped.
+    fn record_views(
+        &self,
+        views: Vec<(i32, i64)>,
+    ) -> impl Future<Output = Result<(), RecordViewsError>> + Send;
+
+    /// Lists the most viewed recipes over the last `window_days` days, most viewed first.
+    fn popular_recipes(
+        &self,
+        window_days: i32,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<PopularRecipeEntity>, PopularRecipesError>> + Send;
 }
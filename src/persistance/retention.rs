@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+/// Moments before which rows are expired, per kind of row. Kinds without a cutoff are kept.
+#[derive(Debug, Clone, Default)]
+pub struct RetentionCutoffsEntity {
+    /// Recorded recipe deletions
+    pub deletions: Option<DateTime<Utc>>,
+    /// Share links, by when they were revoked
+    pub revoked_share_links: Option<DateTime<Utc>>,
+    pub snapshots: Option<DateTime<Utc>>,
+}
+
+/// Number of expired rows, per kind of row
+#[derive(Debug, Default)]
+pub struct ExpiredCountsEntity {
+    pub deletions: i64,
+    pub revoked_share_links: i64,
+    pub snapshots: i64,
+}
+
+#[derive(Debug, Error)]
+pub enum CountExpiredError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum PurgeExpiredError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+pub trait RetentionRepository: std::fmt::Debug + Clone + Send + Sync + 'static {
+    /// Counts the rows which [`Self::purge_expired`] would delete
+    fn count_expired(
+        &self,
+        cutoffs: RetentionCutoffsEntity,
+    ) -> impl Future<Output = Result<ExpiredCountsEntity, CountExpiredError>> + Send;
+
+    /// Deletes the expired rows in a single transaction, returning how many were deleted
+    fn purge_expired(
+        &self,
+        cutoffs: RetentionCutoffsEntity,
+    ) -> impl Future<Output = Result<ExpiredCountsEntity, PurgeExpiredError>> + Send;
+}
@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::persistance::recipe::MealType;
+
+/// Search criteria as stored with a saved search, fields missing from older criteria are left out
+/// of the search
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SavedCriteriaEntity {
+    #[serde(default)]
+    pub recipe_name: Option<String>,
+    #[serde(default)]
+    pub ingredient_name: Option<String>,
+    #[serde(default)]
+    pub meal_type: Option<MealType>,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct SavedSearchEntity {
+    pub saved_search_id: i32,
+    pub name: String,
+    pub criteria: SavedCriteriaEntity,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Error)]
+pub enum SaveSearchError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum ListSavedSearchesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum GetSavedSearchError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The saved search could not be found")]
+    NotFound,
+}
+
+#[derive(Debug, Error)]
+pub enum DeleteSavedSearchError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The saved search could not be found")]
+    NotFound,
+}
+
+pub trait SavedSearchRepository: std::fmt::Debug + Clone + Send + Sync + 'static {
+    fn save_search(
+        &self,
+        name: String,
+        criteria: SavedCriteriaEntity,
+    ) -> impl Future<Output = Result<SavedSearchEntity, SaveSearchError>> + Send;
+
+    /// Lists every saved search, ordered by name.
+    fn list_saved_searches(
+        &self,
+    ) -> impl Future<Output = Result<Vec<SavedSearchEntity>, ListSavedSearchesError>> + Send;
+
+    fn get_saved_search(
+        &self,
+        saved_search_id: i32,
+    ) -> impl Future<Output = Result<SavedSearchEntity, GetSavedSearchError>> + Send;
+
+    fn delete_saved_search(
+        &self,
+        saved_search_id: i32,
+    ) -> impl Future<Output = Result<(), DeleteSavedSearchError>> + Send;
+}
@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+#[derive(Debug)]
+pub struct ShareLinkEntity {
+    pub share_link_id: i32,
+    pub recipe_id: i32,
+    pub token: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Error)]
+pub enum CreateShareLinkError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+#[derive(Debug, Error)]
+pub enum ListShareLinksError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+#[derive(Debug, Error)]
+pub enum RevokeShareLinkError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The share link could not be found")]
+    NotFound,
+}
+
+#[derive(Debug, Error)]
+pub enum ResolveShareLinkError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The share link could not be found")]
+    NotFound,
+}
+
+pub trait ShareLinkRepository: std::fmt::Debug + Clone + Send + Sync + 'static {
+    fn create_share_link(
+        &self,
+        recipe_id: i32,
+        token: String,
+    ) -> impl Future<Output = Result<ShareLinkEntity, CreateShareLinkError>> + Send;
+
+    /// Lists the share links of a recipe which have not been revoked, newest first.
+    fn list_share_links(
+        &self,
+        recipe_id: i32,
+    ) -> impl Future<Output = Result<Vec<ShareLinkEntity>, ListShareLinksError>> + Send;
+
+    fn revoke_share_link(
+        &self,
+        recipe_id: i32,
+        share_link_id: i32,
+    ) -> impl Future<Output = Result<(), RevokeShareLinkError>> + Send;
+
+    /// Finds the recipe id an active share link points to.
+    fn resolve_share_link(
+        &self,
+        token: &str,
+    ) -> impl Future<Output = Result<i32, ResolveShareLinkError>> + Send;
+}
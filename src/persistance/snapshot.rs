@@ -0,0 +1,76 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::persistance::recipe::{MealType, MutableIngredientEntity, PairingKind};
+
+/// Recipe as stored in a snapshot, holding what's needed to import it again
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SnapshotRecipeEntity {
+    pub name: String,
+    pub description: Option<String>,
+    pub ingredients: Vec<MutableIngredientEntity>,
+    pub cooking_time_secs: Option<i64>,
+    pub meal_type: MealType,
+    pub video_url: Option<String>,
+    pub pairing: Option<String>,
+    pub pairing_kind: Option<PairingKind>,
+}
+
+#[derive(Debug)]
+pub struct SnapshotEntity {
+    pub snapshot_id: i32,
+    pub created_at: DateTime<Utc>,
+    pub recipe_count: i32,
+}
+
+#[derive(Debug, Error)]
+pub enum CreateSnapshotError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum ListSnapshotsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum SnapshotRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The snapshot could not be found")]
+    NotFound,
+}
+
+pub trait SnapshotRepository: std::fmt::Debug + Clone + Send + Sync + 'static {
+    /// Copies every recipe, archived ones included, into a new snapshot. The recipes are read in a
+    /// single repeatable read transaction, so changes made meanwhile are either fully part of the
+    /// snapshot or not at all.
+    fn create_snapshot(
+        &self,
+    ) -> impl Future<Output = Result<SnapshotEntity, CreateSnapshotError>> + Send;
+
+    /// Lists the snapshots, most recent first
+    fn list_snapshots(
+        &self,
+    ) -> impl Future<Output = Result<Vec<SnapshotEntity>, ListSnapshotsError>> + Send;
+
+    fn snapshot_recipes(
+        &self,
+        snapshot_id: i32,
+    ) -> impl Future<Output = Result<Vec<SnapshotRecipeEntity>, SnapshotRecipesError>> + Send;
+}
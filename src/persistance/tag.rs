@@ -0,0 +1,61 @@
+use thiserror::Error;
+
+#[derive(Debug)]
+pub struct TagUsageEntity {
+    pub name: String,
+    pub recipe_count: i64,
+}
+
+#[derive(Debug, Error)]
+pub enum ListTagsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+#[derive(Debug, Error)]
+pub enum RecipeTagsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+#[derive(Debug, Error)]
+pub enum SetRecipeTagsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+pub trait TagRepository: std::fmt::Debug + Clone + Send + Sync + 'static {
+    /// Lists every tag of a recipe which is not archived, ordered by name, with the number of
+    /// recipes tagged with it.
+    fn list_tags(&self) -> impl Future<Output = Result<Vec<TagUsageEntity>, ListTagsError>> + Send;
+
+    /// Lists the tags of a recipe, ordered by name.
+    fn recipe_tags(
+        &self,
+        recipe_id: i32,
+    ) -> impl Future<Output = Result<Vec<String>, RecipeTagsError>> + Send;
+
+    /// Replaces the tags of a recipe, tags are expected to be lowercase and distinct. Returns the
+    /// tags of the recipe ordered by name.
+    fn set_recipe_tags(
+        &self,
+        recipe_id: i32,
+        tags: Vec<String>,
+    ) -> impl Future<Output = Result<Vec<String>, SetRecipeTagsError>> + Send;
+}
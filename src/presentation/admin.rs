@@ -0,0 +1,127 @@
+use std::{
+    future::{Ready, ready},
+    sync::Arc,
+};
+
+use actix_web::{
+    FromRequest, HttpRequest, ResponseError,
+    dev::Payload,
+    http::{StatusCode, header},
+    web::Data,
+};
+use secrecy::{ExposeSecret, SecretBox};
+use thiserror::Error;
+
+/// Token admins send as bearer authentication, registered as app data when one is configured
+#[derive(Debug, Clone)]
+pub(crate) struct AdminToken(pub(crate) Arc<SecretBox<str>>);
+
+/// Extractor for routes only admins may use. Nobody is an admin when no [`AdminToken`] is
+/// registered.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Admin;
+
+#[derive(Debug, Error)]
+pub(crate) enum AdminError {
+    #[error("Set an admin token to use this route")]
+    NotConfigured,
+    #[error("Send the admin token as bearer authentication")]
+    Unauthorized,
+}
+
+impl ResponseError for AdminError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AdminError::NotConfigured => StatusCode::FORBIDDEN,
+            AdminError::Unauthorized => StatusCode::UNAUTHORIZED,
+        }
+    }
+}
+
+/// Compares every byte, so the time taken doesn't tell how much of a guess was right
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+impl FromRequest for Admin {
+    type Error = AdminError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let Some(AdminToken(token)) = req.app_data::<Data<AdminToken>>().map(Data::get_ref) else {
+            return ready(Err(AdminError::NotConfigured));
+        };
+
+        let sent = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+
+        ready(match sent {
+            Some(sent) if constant_time_eq(sent.as_bytes(), token.expose_secret().as_bytes()) => {
+                Ok(Admin)
+            }
+            _ => Err(AdminError::Unauthorized),
+        })
+    }
+}
+
+/// Token the tests register, send as `Authorization: Bearer letmein`
+#[cfg(test)]
+pub(crate) fn test_admin_token() -> Data<AdminToken> {
+    Data::new(AdminToken(Arc::new(SecretBox::from("letmein"))))
+}
+
+#[cfg(test)]
+pub(crate) const TEST_ADMIN_AUTHORIZATION: (header::HeaderName, &str) =
+    (header::AUTHORIZATION, "Bearer letmein");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{App, HttpResponse, test, web};
+    use assert2::check;
+
+    async fn ok(_: Admin) -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    #[actix_web::test]
+    async fn it_only_lets_admins_through() {
+        let app = test::init_service(
+            App::new()
+                .app_data(test_admin_token())
+                .route("/admin", web::get().to(ok)),
+        )
+        .await;
+
+        for (authorization, status) in [
+            (None, StatusCode::UNAUTHORIZED),
+            (Some("Bearer wrong"), StatusCode::UNAUTHORIZED),
+            (Some("letmein"), StatusCode::UNAUTHORIZED),
+            (Some("Bearer letmein"), StatusCode::OK),
+        ] {
+            let mut request = test::TestRequest::get().uri("/admin");
+            if let Some(authorization) = authorization {
+                request = request.insert_header((header::AUTHORIZATION, authorization));
+            }
+
+            let response = test::call_service(&app, request.to_request()).await;
+            check!(response.status() == status, "for {authorization:?}");
+        }
+    }
+
+    #[actix_web::test]
+    async fn it_turns_everyone_away_without_an_admin_token() {
+        let app = test::init_service(App::new().route("/admin", web::get().to(ok))).await;
+
+        let request = test::TestRequest::get()
+            .uri("/admin")
+            .insert_header((header::AUTHORIZATION, "Bearer "))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        check!(response.status() == StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,246 @@
+use actix_web::{
+    Error, ResponseError,
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::{Method, StatusCode},
+    middleware::Next,
+    web::{self, Data, Json, Query, ServiceConfig},
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    AnalyticsService,
+    core::analytics::{Usage, UsageCount},
+    persistance::Repository,
+    presentation::admin::Admin,
+};
+
+const DEFAULT_ANALYTICS_DAYS: u32 = 30;
+const MAX_ANALYTICS_DAYS: u32 = 366;
+const DEFAULT_ANALYTICS_LIMIT: u32 = 50;
+const MAX_ANALYTICS_LIMIT: u32 = 500;
+
+/// Route whose query parameters are counted as search terms
+const SEARCH_ROUTE: &str = "/recipes/search";
+
+/// Query parameters of [`SEARCH_ROUTE`] holding search terms, anything else is ignored
+#[derive(Debug, Deserialize)]
+struct SearchTerms {
+    recipe_name: Option<String>,
+    ingredient_name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct RouteUsageDto {
+    pub(crate) route: String,
+    pub(crate) requests: u64,
+}
+
+impl From<UsageCount> for RouteUsageDto {
+    fn from(value: UsageCount) -> Self {
+        Self {
+            route: value.key,
+            requests: value.count,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SearchTermUsageDto {
+    pub(crate) term: String,
+    pub(crate) searches: u64,
+}
+
+impl From<UsageCount> for SearchTermUsageDto {
+    fn from(value: UsageCount) -> Self {
+        Self {
+            term: value.key,
+            searches: value.count,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct UsageDto {
+    pub(crate) since: NaiveDate,
+    pub(crate) routes: Vec<RouteUsageDto>,
+    pub(crate) search_terms: Vec<SearchTermUsageDto>,
+}
+
+impl From<Usage> for UsageDto {
+    fn from(value: Usage) -> Self {
+        Self {
+            since: value.since,
+            routes: value.routes.into_iter().map(RouteUsageDto::from).collect(),
+            search_terms: value
+                .search_terms
+                .into_iter()
+                .map(SearchTermUsageDto::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct AnalyticsQuery {
+    /// Number of days to report on, including today
+    days: Option<u32>,
+    limit: Option<u32>,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum GetUsageError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::core::analytics::GetUsageError> for GetUsageError {
+    fn from(value: crate::core::analytics::GetUsageError) -> Self {
+        match value {
+            crate::core::analytics::GetUsageError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl ResponseError for GetUsageError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            GetUsageError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Middleware counting successful requests per route, and the terms searched for on
+/// [`SEARCH_ROUTE`], in the registered [`AnalyticsService`]. Nothing about who made the request
+/// is kept.
+pub(crate) async fn record_usage<R: Repository>(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let svc = req.app_data::<Data<AnalyticsService<R>>>().cloned();
+    let res = next.call(req).await?;
+
+    let Some(svc) = svc else {
+        return Ok(res);
+    };
+
+    if !res.status().is_success() {
+        return Ok(res);
+    }
+
+    if let Some(pattern) = res.request().match_pattern() {
+        svc.record_route(&format!("{} {pattern}", res.request().method()));
+
+        if pattern == SEARCH_ROUTE && res.request().method() == Method::GET {
+            // Malformed queries were already rejected by the handler
+            let terms = Query::<SearchTerms>::from_query(res.request().query_string())
+                .map(|Query(terms)| [terms.recipe_name, terms.ingredient_name])
+                .unwrap_or_default();
+
+            for term in terms.into_iter().flatten() {
+                svc.record_search(&term);
+            }
+        }
+    }
+
+    Ok(res)
+}
+
+/// Requests per route and the most common search terms over the last days. Search terms only a
+/// few people searched for are left out.
+pub(crate) async fn get_usage<R: Repository>(
+    _: Admin,
+    svc: Data<AnalyticsService<R>>,
+    Query(query): Query<AnalyticsQuery>,
+) -> Result<Json<UsageDto>, GetUsageError> {
+    let days = query
+        .days
+        .unwrap_or(DEFAULT_ANALYTICS_DAYS)
+        .clamp(1, MAX_ANALYTICS_DAYS);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_ANALYTICS_LIMIT)
+        .min(MAX_ANALYTICS_LIMIT);
+
+    Ok(Json(svc.usage(days, limit).await?.into()))
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route("/admin/analytics", web::get().to(get_usage::<R>));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{App, HttpResponse, middleware::from_fn, test};
+    use assert2::check;
+
+    use crate::{presentation::admin::test_admin_token, test_utils::MockRepository};
+
+    async fn ok() -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    #[actix_web::test]
+    async fn it_counts_routes_and_search_terms() {
+        let repository = MockRepository::new();
+        repository.on_record_usage(Ok(()));
+        let svc = AnalyticsService::new(repository.clone(), true);
+
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(record_usage::<MockRepository>))
+                .app_data(Data::new(svc.clone()))
+                .route(SEARCH_ROUTE, web::get().to(ok)),
+        )
+        .await;
+
+        for uri in [
+            "/recipes/search?recipe_name=Soup&ingredient_name=leek",
+            "/unknown?recipe_name=Stew",
+        ] {
+            let request = test::TestRequest::get().uri(uri).to_request();
+            test::call_service(&app, request).await;
+        }
+
+        svc.flush().await.expect("Failed to flush usage");
+        let calls = repository.calls_to("record_usage");
+        check!(
+            calls[0]
+                .arguments
+                .starts_with(r#"([("GET /recipes/search", 1)], ["#)
+        );
+        check!(calls[0].arguments.contains(r#"("soup", 1)"#));
+        check!(calls[0].arguments.contains(r#"("leek", 1)"#));
+        check!(!calls[0].arguments.contains("stew"));
+    }
+
+    #[actix_web::test]
+    async fn it_reports_usage_only_to_admins() {
+        let repository = MockRepository::new();
+        let svc = AnalyticsService::new(repository.clone(), true);
+
+        let app = test::init_service(
+            App::new()
+                .configure(configure::<MockRepository>)
+                .app_data(Data::new(svc))
+                .app_data(test_admin_token()),
+        )
+        .await;
+
+        let request = test::TestRequest::get()
+            .uri("/admin/analytics")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+
+        check!(response.status() == StatusCode::UNAUTHORIZED);
+        check!(repository.calls().is_empty());
+    }
+}
@@ -0,0 +1,238 @@
+use actix_web::{
+    Error, ResponseError,
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::{Method, StatusCode},
+    middleware::Next,
+    web::Data,
+};
+use thiserror::Error;
+
+use crate::captcha::verifier::{CaptchaVerifier, EndpointGroup, VerifyCaptchaError};
+
+/// Header carrying the token of a solved CAPTCHA
+pub(crate) const CAPTCHA_TOKEN_HEADER: &str = "X-Captcha-Token";
+
+/// Endpoint groups whose writes need a solved CAPTCHA, registered only on public instances
+#[derive(Debug, Clone)]
+pub(crate) struct CaptchaGate<V: CaptchaVerifier> {
+    pub(crate) verifier: V,
+    pub(crate) groups: Vec<EndpointGroup>,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum CaptchaError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("A solved CAPTCHA is required, send its token in the `{CAPTCHA_TOKEN_HEADER}` header")]
+    Missing,
+    #[error("The CAPTCHA was not solved")]
+    Rejected,
+}
+
+impl From<VerifyCaptchaError> for CaptchaError {
+    fn from(value: VerifyCaptchaError) -> Self {
+        match value {
+            VerifyCaptchaError::Unknown(report) => Self::Unknown(report),
+            VerifyCaptchaError::Rejected => Self::Rejected,
+        }
+    }
+}
+
+impl ResponseError for CaptchaError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            CaptchaError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            CaptchaError::Missing | CaptchaError::Rejected => StatusCode::FORBIDDEN,
+        }
+    }
+}
+
+/// Route pattern of a request, without the prefix of the scope the API is mounted in. Requests
+/// which don't match a route give `None`.
+fn route_pattern(req: &ServiceRequest) -> Option<String> {
+    let pattern = req.match_pattern()?;
+    let mounted_at = req
+        .path()
+        .strip_suffix(req.match_info().unprocessed())
+        .unwrap_or_default();
+
+    Some(
+        pattern
+            .strip_prefix(mounted_at)
+            .map(str::to_string)
+            .unwrap_or(pattern),
+    )
+}
+
+/// Middleware requiring a solved CAPTCHA for writes to the endpoint groups of the registered
+/// [`CaptchaGate`]. Writes are grouped by the route they match, and those matching no route at
+/// all count as [`EndpointGroup::Other`]. Reads, and every request when no gate is registered,
+/// pass through untouched.
+pub(crate) async fn require_captcha<V: CaptchaVerifier>(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let gate = req.app_data::<Data<CaptchaGate<V>>>().cloned();
+
+    let is_write = !matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+    let gated = gate.as_ref().filter(|gate| {
+        let group = match route_pattern(&req) {
+            Some(pattern) => EndpointGroup::of(&pattern),
+            None => Some(EndpointGroup::Other),
+        };
+
+        is_write && group.is_some_and(|group| gate.groups.contains(&group))
+    });
+
+    if let Some(gate) = gated
+        && let Err(error) = check_captcha(gate, &req).await
+    {
+        return Ok(req.error_response(error).map_into_right_body());
+    }
+
+    next.call(req)
+        .await
+        .map(ServiceResponse::map_into_left_body)
+}
+
+async fn check_captcha<V: CaptchaVerifier>(
+    gate: &CaptchaGate<V>,
+    req: &ServiceRequest,
+) -> Result<(), CaptchaError> {
+    let token = req
+        .headers()
+        .get(CAPTCHA_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|token| !token.is_empty())
+        .ok_or(CaptchaError::Missing)?;
+    let remote_ip = req.peer_addr().map(|addr| addr.ip().to_string());
+
+    gate.verifier.verify(token, remote_ip.as_deref()).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{App, HttpResponse, middleware::from_fn, test, web};
+    use assert2::check;
+
+    /// Accepts the token `solved` and rejects everything else
+    #[derive(Debug, Clone)]
+    struct StubVerifier;
+
+    impl CaptchaVerifier for StubVerifier {
+        async fn verify(&self, token: &str, _: Option<&str>) -> Result<(), VerifyCaptchaError> {
+            match token {
+                "solved" => Ok(()),
+                _ => Err(VerifyCaptchaError::Rejected),
+            }
+        }
+    }
+
+    async fn ok() -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    macro_rules! setup_app {
+        () => {
+            test::init_service(
+                App::new()
+                    .wrap(from_fn(require_captcha::<StubVerifier>))
+                    .app_data(Data::new(CaptchaGate {
+                        verifier: StubVerifier,
+                        groups: vec![EndpointGroup::Sharing, EndpointGroup::Other],
+                    }))
+                    .route("/recipes/{recipe_id}/share", web::post().to(ok))
+                    .route("/meal-plans/generate", web::post().to(ok))
+                    .route("/federation/instances", web::post().to(ok))
+                    .route("/recipes", web::post().to(ok))
+                    .route("/recipes", web::get().to(ok)),
+            )
+            .await
+        };
+    }
+
+    #[actix_web::test]
+    async fn it_requires_a_solved_captcha_for_gated_groups() {
+        let app = setup_app!();
+
+        for (token, status) in [
+            (None, StatusCode::FORBIDDEN),
+            (Some("guessed"), StatusCode::FORBIDDEN),
+            (Some("solved"), StatusCode::OK),
+        ] {
+            let mut request = test::TestRequest::post().uri("/recipes/1/share");
+            if let Some(token) = token {
+                request = request.insert_header((CAPTCHA_TOKEN_HEADER, token));
+            }
+
+            let response = test::call_service(&app, request.to_request()).await;
+            check!(response.status() == status);
+        }
+    }
+
+    #[actix_web::test]
+    async fn it_leaves_reads_and_other_groups_alone() {
+        let app = setup_app!();
+
+        for request in [
+            test::TestRequest::post().uri("/recipes"),
+            test::TestRequest::get().uri("/recipes"),
+        ] {
+            let response = test::call_service(&app, request.to_request()).await;
+            check!(response.status() == StatusCode::OK);
+        }
+    }
+
+    #[actix_web::test]
+    async fn it_requires_a_solved_captcha_for_unknown_writes() {
+        let app = setup_app!();
+
+        for uri in ["/meal-plans/generate", "/not-a-route"] {
+            let request = test::TestRequest::post().uri(uri).to_request();
+            let response = test::call_service(&app, request).await;
+            check!(response.status() == StatusCode::FORBIDDEN, "for {uri}");
+        }
+
+        let request = test::TestRequest::post()
+            .uri("/federation/instances")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        check!(response.status() == StatusCode::OK);
+    }
+
+    #[actix_web::test]
+    async fn it_groups_routes_of_an_api_mounted_in_a_scope() {
+        let app = test::init_service(
+            App::new()
+                .app_data(Data::new(CaptchaGate {
+                    verifier: StubVerifier,
+                    groups: vec![EndpointGroup::Sharing],
+                }))
+                .service(
+                    web::scope("/api")
+                        .wrap(from_fn(require_captcha::<StubVerifier>))
+                        .route("/recipes/{recipe_id}/share", web::post().to(ok))
+                        .route("/recipes", web::post().to(ok)),
+                ),
+        )
+        .await;
+
+        let request = test::TestRequest::post()
+            .uri("/api/recipes/1/share")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        check!(response.status() == StatusCode::FORBIDDEN);
+
+        let request = test::TestRequest::post().uri("/api/recipes").to_request();
+        let response = test::call_service(&app, request).await;
+        check!(response.status() == StatusCode::OK);
+    }
+}
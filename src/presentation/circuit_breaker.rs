@@ -0,0 +1,105 @@
+use actix_web::{
+    Error,
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::{
+        StatusCode,
+        header::{HeaderValue, RETRY_AFTER},
+    },
+    middleware::Next,
+    web::Data,
+};
+
+use crate::persistance::circuit_breaker::Breaker;
+
+/// Middleware turning internal server errors into `503 Service Unavailable` while the registered
+/// [`Breaker`] is open, telling clients when to retry. Responses for anything but internal server
+/// errors, like static files, are left alone.
+pub(crate) async fn unavailable_while_open(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let breaker = req.app_data::<Data<Breaker>>().cloned();
+    let mut res = next.call(req).await?;
+
+    if res.status() != StatusCode::INTERNAL_SERVER_ERROR {
+        return Ok(res);
+    }
+
+    if let Some(retry_after) = breaker.and_then(|breaker| breaker.retry_after()) {
+        let retry_after = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+
+        let response = res.response_mut();
+        *response.status_mut() = StatusCode::SERVICE_UNAVAILABLE;
+        response
+            .headers_mut()
+            .insert(RETRY_AFTER, HeaderValue::from(retry_after));
+    }
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use actix_web::{App, HttpResponse, middleware::from_fn, test, web};
+    use assert2::check;
+
+    use crate::{
+        persistance::{
+            circuit_breaker::CircuitBreaker,
+            deletion::DeletionEntity,
+            recipe::{DeleteRecipeError, RecipeRepository},
+        },
+        test_utils::MockRepository,
+    };
+
+    async fn failing() -> HttpResponse {
+        HttpResponse::InternalServerError().finish()
+    }
+
+    #[actix_web::test]
+    async fn it_answers_service_unavailable_while_the_circuit_is_open() {
+        let breaker = Breaker::new(1, Duration::from_secs(30));
+
+        let repository = MockRepository::new();
+        repository.on_delete_recipe(Err(DeleteRecipeError::Unknown(eyre::Report::new(
+            sqlx::Error::PoolTimedOut,
+        ))));
+        let _ = CircuitBreaker::new(repository, breaker.clone())
+            .delete_recipe(1, DeletionEntity::default())
+            .await;
+
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(unavailable_while_open))
+                .app_data(Data::new(breaker))
+                .route("/", web::get().to(failing)),
+        )
+        .await;
+
+        let response = test::call_service(&app, test::TestRequest::get().to_request()).await;
+
+        check!(response.status() == StatusCode::SERVICE_UNAVAILABLE);
+        check!(response.headers().get(RETRY_AFTER).unwrap() == "30");
+    }
+
+    #[actix_web::test]
+    async fn it_leaves_errors_alone_while_the_circuit_is_closed() {
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(unavailable_while_open))
+                .app_data(Data::new(Breaker::new(1, Duration::from_secs(30))))
+                .route("/", web::get().to(failing)),
+        )
+        .await;
+
+        let response = test::call_service(&app, test::TestRequest::get().to_request()).await;
+
+        check!(response.status() == StatusCode::INTERNAL_SERVER_ERROR);
+        check!(response.headers().get(RETRY_AFTER).is_none());
+    }
+}
@@ -0,0 +1,140 @@
+use actix_web::{
+    HttpResponse, ResponseError,
+    http::{StatusCode, header::ContentType},
+    web::{self, Data, Json, ServiceConfig},
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    CookingLogService,
+    core::cooking_log::{CookingLog, NewCookingLog},
+    persistance::Repository,
+    presentation::{json_body::JsonBody, recipe_id::RecipeId},
+};
+
+#[derive(Debug, Serialize)]
+pub(crate) struct CookingLogDto {
+    pub(crate) cooking_log_id: i32,
+    pub(crate) recipe_id: i32,
+    pub(crate) cooked_on: NaiveDate,
+    pub(crate) note: Option<String>,
+}
+
+impl From<CookingLog> for CookingLogDto {
+    fn from(value: CookingLog) -> Self {
+        Self {
+            cooking_log_id: value.cooking_log_id,
+            recipe_id: value.recipe_id,
+            cooked_on: value.cooked_on,
+            note: value.note,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct NewCookingLogDto {
+    pub(crate) cooked_on: Option<NaiveDate>,
+    pub(crate) note: Option<String>,
+}
+
+impl From<NewCookingLogDto> for NewCookingLog {
+    fn from(value: NewCookingLogDto) -> Self {
+        Self {
+            cooked_on: value.cooked_on,
+            note: value.note,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum LogCookingError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::cooking_log::LogCookingError> for LogCookingError {
+    fn from(value: crate::core::cooking_log::LogCookingError) -> Self {
+        match value {
+            crate::core::cooking_log::LogCookingError::Unknown(report) => Self::Unknown(report),
+            crate::core::cooking_log::LogCookingError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for LogCookingError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            LogCookingError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            LogCookingError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum CookingHistoryError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::cooking_log::CookingHistoryError> for CookingHistoryError {
+    fn from(value: crate::core::cooking_log::CookingHistoryError) -> Self {
+        match value {
+            crate::core::cooking_log::CookingHistoryError::Unknown(report) => Self::Unknown(report),
+            crate::core::cooking_log::CookingHistoryError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for CookingHistoryError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            CookingHistoryError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            CookingHistoryError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+pub(crate) async fn log_cooking<R: Repository>(
+    svc: Data<CookingLogService<R>>,
+    RecipeId(recipe_id): RecipeId,
+    JsonBody(data): JsonBody<NewCookingLogDto>,
+) -> Result<HttpResponse, LogCookingError> {
+    let log = svc.log_cooking(recipe_id, data.into()).await?;
+
+    Ok(HttpResponse::Created()
+        .content_type(ContentType::json())
+        .json(CookingLogDto::from(log)))
+}
+
+pub(crate) async fn cooking_history<R: Repository>(
+    svc: Data<CookingLogService<R>>,
+    RecipeId(recipe_id): RecipeId,
+) -> Result<Json<Vec<CookingLogDto>>, CookingHistoryError> {
+    let history = svc.cooking_history(recipe_id).await?;
+    Ok(Json(history.into_iter().map(CookingLogDto::from).collect()))
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route(
+        "/recipes/{recipe_id}/cooked",
+        web::post().to(log_cooking::<R>),
+    )
+    .route(
+        "/recipes/{recipe_id}/history",
+        web::get().to(cooking_history::<R>),
+    );
+}
@@ -0,0 +1,179 @@
+use actix_web::{
+    ResponseError,
+    http::StatusCode,
+    web::{self, Data, Json, ServiceConfig},
+};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    RecipeService,
+    core::data_quality::{DataQualityReport, FlaggedRecipe, LargeQuantity},
+    persistance::Repository,
+    presentation::{admin::Admin, recipe::IngredientDto},
+};
+
+#[derive(Debug, Serialize)]
+pub(crate) struct FlaggedRecipeDto {
+    pub(crate) recipe_id: i32,
+    pub(crate) name: String,
+}
+
+impl From<FlaggedRecipe> for FlaggedRecipeDto {
+    fn from(value: FlaggedRecipe) -> Self {
+        Self {
+            recipe_id: value.recipe_id,
+            name: value.name,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct LargeQuantityDto {
+    pub(crate) recipe_id: i32,
+    pub(crate) name: String,
+    pub(crate) ingredient: IngredientDto,
+}
+
+impl From<LargeQuantity> for LargeQuantityDto {
+    fn from(value: LargeQuantity) -> Self {
+        Self {
+            recipe_id: value.recipe.recipe_id,
+            name: value.recipe.name,
+            ingredient: value.ingredient.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct DataQualityReportDto {
+    pub(crate) no_ingredients: Vec<FlaggedRecipeDto>,
+    pub(crate) no_steps: Vec<FlaggedRecipeDto>,
+    pub(crate) no_cooking_time: Vec<FlaggedRecipeDto>,
+    pub(crate) large_quantities: Vec<LargeQuantityDto>,
+}
+
+impl From<DataQualityReport> for DataQualityReportDto {
+    fn from(value: DataQualityReport) -> Self {
+        let recipes = |recipes: Vec<FlaggedRecipe>| {
+            recipes
+                .into_iter()
+                .map(FlaggedRecipeDto::from)
+                .collect::<Vec<_>>()
+        };
+
+        Self {
+            no_ingredients: recipes(value.no_ingredients),
+            no_steps: recipes(value.no_steps),
+            no_cooking_time: recipes(value.no_cooking_time),
+            large_quantities: value
+                .large_quantities
+                .into_iter()
+                .map(LargeQuantityDto::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum DataQualityError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::core::recipe::DataQualityError> for DataQualityError {
+    fn from(value: crate::core::recipe::DataQualityError) -> Self {
+        match value {
+            crate::core::recipe::DataQualityError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl ResponseError for DataQualityError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            DataQualityError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Recipes with missing data or suspiciously large quantities, to clean up imported collections
+pub(crate) async fn data_quality<R: Repository>(
+    _: Admin,
+    svc: Data<RecipeService<R>>,
+) -> Result<Json<DataQualityReportDto>, DataQualityError> {
+    let report = svc.data_quality_report().await?;
+    Ok(Json(report.into()))
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route("/admin/data-quality", web::get().to(data_quality::<R>));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{App, test};
+    use sqlx::PgPool;
+
+    use crate::{
+        Postgres,
+        presentation::admin::{TEST_ADMIN_AUTHORIZATION, test_admin_token},
+    };
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_should_report_recipes_with_missing_data(pool: PgPool) {
+        let recipe_id = sqlx::query_scalar!(
+            "INSERT INTO recipe (name, description, meal_type) VALUES ('Toast', 'Toast the bread.', 'Breakfast') RETURNING recipe_id"
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to insert recipe");
+
+        let app = test::init_service(
+            App::new()
+                .configure(configure::<Postgres>)
+                .app_data(Data::new(RecipeService::new(Postgres::new(pool), None)))
+                .app_data(test_admin_token()),
+        )
+        .await;
+
+        let request = test::TestRequest::get()
+            .uri("/admin/data-quality")
+            .insert_header(TEST_ADMIN_AUTHORIZATION)
+            .to_request();
+        let body = test::call_and_read_body(&app, request).await;
+
+        let recipe = format!(r#"[{{"recipe_id":{recipe_id},"name":"Toast"}}]"#);
+        assert2::check!(
+            body == format!(
+                r#"{{"no_ingredients":{recipe},"no_steps":[],"no_cooking_time":{recipe},"large_quantities":[]}}"#
+            )
+        );
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_should_return_401_without_the_admin_token(pool: PgPool) {
+        let app = test::init_service(
+            App::new()
+                .configure(configure::<Postgres>)
+                .app_data(Data::new(RecipeService::new(Postgres::new(pool), None)))
+                .app_data(test_admin_token()),
+        )
+        .await;
+
+        let request = test::TestRequest::get()
+            .uri("/admin/data-quality")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+
+        assert2::check!(response.status() == StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,181 @@
+use actix_web::{
+    ResponseError,
+    http::StatusCode,
+    web::{self, Data, Json, Query, ServiceConfig},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    DeletionService, core::deletion::RecipeDeletion, persistance::Repository,
+    presentation::admin::Admin,
+};
+
+const DEFAULT_DELETIONS_LIMIT: u32 = 100;
+const MAX_DELETIONS_LIMIT: u32 = 1000;
+
+#[derive(Debug, Serialize)]
+pub(crate) struct RecipeDeletionDto {
+    pub(crate) deletion_id: i32,
+    pub(crate) recipe_id: i32,
+    pub(crate) recipe_name: String,
+    pub(crate) deleted_at: DateTime<Utc>,
+    pub(crate) actor: Option<String>,
+    pub(crate) ip_address: Option<String>,
+    pub(crate) reason: Option<String>,
+}
+
+impl From<RecipeDeletion> for RecipeDeletionDto {
+    fn from(value: RecipeDeletion) -> Self {
+        Self {
+            deletion_id: value.deletion_id,
+            recipe_id: value.recipe_id,
+            recipe_name: value.recipe_name,
+            deleted_at: value.deleted_at,
+            actor: value.actor,
+            ip_address: value.ip_address,
+            reason: value.reason,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DeletionsQuery {
+    /// Only lists the deletions of this recipe
+    recipe_id: Option<i32>,
+    limit: Option<u32>,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ListDeletionsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::core::deletion::ListDeletionsError> for ListDeletionsError {
+    fn from(value: crate::core::deletion::ListDeletionsError) -> Self {
+        match value {
+            crate::core::deletion::ListDeletionsError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl ResponseError for ListDeletionsError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ListDeletionsError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Recipes which were deleted, most recent first, with who deleted them, from where and why. The
+/// limit is capped at [`MAX_DELETIONS_LIMIT`].
+pub(crate) async fn list_deletions<R: Repository>(
+    _: Admin,
+    svc: Data<DeletionService<R>>,
+    Query(query): Query<DeletionsQuery>,
+) -> Result<Json<Vec<RecipeDeletionDto>>, ListDeletionsError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_DELETIONS_LIMIT)
+        .min(MAX_DELETIONS_LIMIT);
+    let deletions = svc.list_deletions(query.recipe_id, limit).await?;
+
+    Ok(Json(
+        deletions.into_iter().map(RecipeDeletionDto::from).collect(),
+    ))
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route("/admin/deletions", web::get().to(list_deletions::<R>));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{App, test};
+    use assert2::check;
+    use sqlx::PgPool;
+
+    use crate::{
+        Postgres, RecipeService,
+        presentation::{
+            admin::{TEST_ADMIN_AUTHORIZATION, test_admin_token},
+            recipe::{REMOTE_USER_HEADER, TrustedProxy},
+        },
+    };
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    async fn delete_toast(pool: PgPool, trusted_proxy: bool) -> serde_json::Value {
+        let recipe_id = sqlx::query_scalar!(
+            "INSERT INTO recipe (name, meal_type) VALUES ('Toast', 'Breakfast') RETURNING recipe_id"
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to insert recipe");
+
+        let postgres = Postgres::new(pool);
+        let app = test::init_service(
+            App::new()
+                .configure(configure::<Postgres>)
+                .configure(crate::presentation::recipe::configure::<Postgres>)
+                .configure(|cfg| {
+                    if trusted_proxy {
+                        cfg.app_data(Data::new(TrustedProxy));
+                    }
+                })
+                .app_data(Data::new(RecipeService::new(postgres.clone(), None)))
+                .app_data(Data::new(DeletionService::new(postgres)))
+                .app_data(test_admin_token()),
+        )
+        .await;
+
+        let request = test::TestRequest::delete()
+            .uri(&format!("/recipes/{recipe_id}?reason=Entered%20twice"))
+            .insert_header((REMOTE_USER_HEADER, "sam"))
+            .insert_header(("X-Forwarded-For", "192.0.2.4"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        check!(response.status() == StatusCode::OK);
+
+        let request = test::TestRequest::get()
+            .uri("/admin/deletions")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        check!(response.status() == StatusCode::UNAUTHORIZED);
+
+        let request = test::TestRequest::get()
+            .uri("/admin/deletions")
+            .insert_header(TEST_ADMIN_AUTHORIZATION)
+            .to_request();
+        let deletions: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+
+        check!(deletions[0]["recipe_id"] == recipe_id);
+        check!(deletions[0]["recipe_name"] == "Toast");
+        check!(deletions[0]["reason"] == "Entered twice");
+        deletions
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_should_list_who_deleted_a_recipe(pool: PgPool) {
+        let deletions = delete_toast(pool, true).await;
+
+        check!(deletions[0]["actor"] == "sam");
+        check!(deletions[0]["ip_address"] == "192.0.2.4");
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_should_ignore_the_user_without_a_trusted_proxy(pool: PgPool) {
+        let deletions = delete_toast(pool, false).await;
+
+        check!(deletions[0]["actor"].is_null());
+        check!(deletions[0]["ip_address"].is_null());
+    }
+}
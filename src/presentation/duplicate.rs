@@ -0,0 +1,256 @@
+use actix_web::{
+    self, ResponseError,
+    http::StatusCode,
+    web::{self, Data, Json, ServiceConfig},
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    RecipeService,
+    core::{
+        duplicate::{Duplicate, DuplicateRecipe},
+        recipe::MergeField,
+    },
+    persistance::Repository,
+    presentation::{json_body::JsonBody, recipe::RecipeDto},
+};
+
+#[derive(Debug, Serialize)]
+pub(crate) struct DuplicateDto {
+    pub(crate) recipes: [DuplicateRecipeDto; 2],
+    pub(crate) name_similarity: f32,
+    pub(crate) ingredient_overlap: f32,
+}
+
+impl From<Duplicate> for DuplicateDto {
+    fn from(value: Duplicate) -> Self {
+        Self {
+            recipes: value.recipes.map(DuplicateRecipeDto::from),
+            name_similarity: value.name_similarity,
+            ingredient_overlap: value.ingredient_overlap,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct DuplicateRecipeDto {
+    pub(crate) recipe_id: i32,
+    pub(crate) name: String,
+}
+
+impl From<DuplicateRecipe> for DuplicateRecipeDto {
+    fn from(value: DuplicateRecipe) -> Self {
+        Self {
+            recipe_id: value.recipe_id,
+            name: value.name,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct MergeRecipesDto {
+    /// Recipe which is kept
+    pub(crate) target: i32,
+    /// Recipe which is folded into the target and deleted
+    pub(crate) source: i32,
+    /// Fields to take from the source instead of the target
+    #[serde(default)]
+    pub(crate) take_from_source: Vec<MergeFieldDto>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum MergeFieldDto {
+    Name,
+    Description,
+    Ingredients,
+    CookingTime,
+    MealType,
+    Video,
+    Pairing,
+}
+
+impl From<MergeFieldDto> for MergeField {
+    fn from(value: MergeFieldDto) -> Self {
+        match value {
+            MergeFieldDto::Name => Self::Name,
+            MergeFieldDto::Description => Self::Description,
+            MergeFieldDto::Ingredients => Self::Ingredients,
+            MergeFieldDto::CookingTime => Self::CookingTime,
+            MergeFieldDto::MealType => Self::MealType,
+            MergeFieldDto::Video => Self::Video,
+            MergeFieldDto::Pairing => Self::Pairing,
+        }
+    }
+}
+
+impl From<MergeField> for MergeFieldDto {
+    fn from(value: MergeField) -> Self {
+        match value {
+            MergeField::Name => Self::Name,
+            MergeField::Description => Self::Description,
+            MergeField::Ingredients => Self::Ingredients,
+            MergeField::CookingTime => Self::CookingTime,
+            MergeField::MealType => Self::MealType,
+            MergeField::Video => Self::Video,
+            MergeField::Pairing => Self::Pairing,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum FindDuplicatesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::core::recipe::FindDuplicatesError> for FindDuplicatesError {
+    fn from(value: crate::core::recipe::FindDuplicatesError) -> Self {
+        match value {
+            crate::core::recipe::FindDuplicatesError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl ResponseError for FindDuplicatesError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            FindDuplicatesError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum MergeRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+    #[error("A recipe can't be merged into itself")]
+    SameRecipe,
+}
+
+impl From<crate::core::recipe::MergeRecipesError> for MergeRecipesError {
+    fn from(value: crate::core::recipe::MergeRecipesError) -> Self {
+        match value {
+            crate::core::recipe::MergeRecipesError::Unknown(report) => Self::Unknown(report),
+            crate::core::recipe::MergeRecipesError::NotFound => Self::NotFound,
+            crate::core::recipe::MergeRecipesError::SameRecipe => Self::SameRecipe,
+        }
+    }
+}
+
+impl ResponseError for MergeRecipesError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            MergeRecipesError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            MergeRecipesError::NotFound => StatusCode::NOT_FOUND,
+            MergeRecipesError::SameRecipe => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+pub(crate) async fn find_duplicates<R: Repository>(
+    svc: Data<RecipeService<R>>,
+) -> Result<Json<Vec<DuplicateDto>>, FindDuplicatesError> {
+    let duplicates = svc.find_duplicates().await?;
+    Ok(Json(
+        duplicates.into_iter().map(DuplicateDto::from).collect(),
+    ))
+}
+
+pub(crate) async fn merge_recipes<R: Repository>(
+    svc: Data<RecipeService<R>>,
+    JsonBody(data): JsonBody<MergeRecipesDto>,
+) -> Result<Json<RecipeDto>, MergeRecipesError> {
+    let fields = data
+        .take_from_source
+        .into_iter()
+        .map(MergeField::from)
+        .collect::<Vec<_>>();
+
+    let recipe = svc.merge_recipes(data.target, data.source, &fields).await?;
+
+    Ok(Json(recipe.into()))
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route("/recipes/duplicates", web::get().to(find_duplicates::<R>))
+        .route("/recipes/merge", web::post().to(merge_recipes::<R>));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{App, http::header::ContentType, test};
+    use sqlx::PgPool;
+
+    use crate::Postgres;
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_should_merge_recipes_taking_the_chosen_fields(pool: PgPool) {
+        let target = sqlx::query_scalar!(
+            "INSERT INTO recipe (name, description, meal_type) VALUES ('Lasagne', 'Layer everything.', 'Dinner') RETURNING recipe_id"
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to insert recipe");
+        let source = sqlx::query_scalar!(
+            "INSERT INTO recipe (name, description, meal_type) VALUES ('Lasagna', 'Layer and bake.', 'Lunch') RETURNING recipe_id"
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to insert recipe");
+
+        let app = test::init_service(
+            App::new()
+                .configure(configure::<Postgres>)
+                .app_data(Data::new(RecipeService::new(Postgres::new(pool), None))),
+        )
+        .await;
+
+        let request = test::TestRequest::post()
+            .uri("/recipes/merge")
+            .insert_header(ContentType::json())
+            .set_payload(format!(
+                r#"{{"target":{target},"source":{source},"take_from_source":["description"]}}"#
+            ))
+            .to_request();
+        let merged: RecipeDto = test::call_and_read_body_json(&app, request).await;
+
+        assert2::check!(merged.recipe_id == target);
+        assert2::check!(merged.name == "Lasagne");
+        assert2::check!(merged.description.as_deref() == Some("Layer and bake."));
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_should_return_400_when_merging_a_recipe_into_itself(pool: PgPool) {
+        let app = test::init_service(
+            App::new()
+                .configure(configure::<Postgres>)
+                .app_data(Data::new(RecipeService::new(Postgres::new(pool), None))),
+        )
+        .await;
+
+        let request = test::TestRequest::post()
+            .uri("/recipes/merge")
+            .insert_header(ContentType::json())
+            .set_payload(r#"{"target":1,"source":1}"#)
+            .to_request();
+        let response = test::call_service(&app, request).await;
+
+        assert2::check!(response.status() == StatusCode::BAD_REQUEST);
+    }
+}
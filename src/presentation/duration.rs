@@ -0,0 +1,168 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+/// Formats a duration as an ISO-8601 duration like `PT1H30M`, dropping sub-second precision
+pub(crate) fn to_iso8601(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    if secs == 0 {
+        return "PT0S".to_string();
+    }
+
+    let mut out = "PT".to_string();
+
+    if hours > 0 {
+        out.push_str(&format!("{hours}H"));
+    }
+    if minutes > 0 {
+        out.push_str(&format!("{minutes}M"));
+    }
+    if seconds > 0 {
+        out.push_str(&format!("{seconds}S"));
+    }
+
+    out
+}
+
+/// Parses either an ISO-8601 duration like `PT1H30M` or a short form like `1h 30m`
+pub(crate) fn parse(value: &str) -> Option<Duration> {
+    let value = value.trim();
+
+    match value.strip_prefix('P') {
+        Some(iso) => parse_iso8601(iso),
+        None => parse_short(value),
+    }
+}
+
+/// Parses the part of an ISO-8601 duration after the `P`, only days and time components are
+/// supported as months and years don't have a fixed length
+fn parse_iso8601(value: &str) -> Option<Duration> {
+    let (date, time) = match value.split_once('T') {
+        Some((date, time)) if !time.is_empty() => (date, Some(time)),
+        Some(_) => return None,
+        None => (value, None),
+    };
+
+    let mut secs = 0;
+
+    if !date.is_empty() {
+        secs += parse_components(date, &[('D', 86400)])?;
+    }
+    if let Some(time) = time {
+        secs += parse_components(time, &[('H', 3600), ('M', 60), ('S', 1)])?;
+    }
+
+    (!date.is_empty() || time.is_some()).then_some(Duration::from_secs(secs))
+}
+
+/// Parses durations like `1h 30m`, `90m` or `45s`
+fn parse_short(value: &str) -> Option<Duration> {
+    let compact = value
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_uppercase();
+
+    if compact.is_empty() {
+        return None;
+    }
+
+    parse_components(&compact, &[('H', 3600), ('M', 60), ('S', 1)]).map(Duration::from_secs)
+}
+
+/// Parses a sequence of `<number><unit>` pairs, where the units must appear in the given order
+fn parse_components(value: &str, units: &[(char, u64)]) -> Option<u64> {
+    let mut secs = 0u64;
+    let mut remaining = value;
+    let mut units = units.iter();
+
+    while !remaining.is_empty() {
+        let digits = remaining.find(|c: char| !c.is_ascii_digit())?;
+        let amount = remaining[..digits].parse::<u64>().ok()?;
+        let unit = remaining[digits..].chars().next()?;
+
+        let (_, factor) = units.find(|(u, _)| *u == unit)?;
+        secs = secs.checked_add(amount.checked_mul(*factor)?)?;
+
+        remaining = &remaining[digits + unit.len_utf8()..];
+    }
+
+    Some(secs)
+}
+
+/// Serde module for optional durations, written as ISO-8601 strings. Besides the formats
+/// understood by [`parse`] the `{ "secs": .., "nanos": .. }` shape of older clients is accepted.
+pub(crate) mod option {
+    use super::*;
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum DurationInput {
+        Text(String),
+        Legacy { secs: u64, nanos: u32 },
+    }
+
+    pub(crate) fn serialize<S: Serializer>(
+        value: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(duration) => serializer.serialize_str(&to_iso8601(*duration)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        match Option::<DurationInput>::deserialize(deserializer)? {
+            Some(DurationInput::Text(text)) => parse(&text)
+                .map(Some)
+                .ok_or_else(|| D::Error::custom(format!("invalid duration `{text}`"))),
+            Some(DurationInput::Legacy { secs, nanos }) => Ok(Some(Duration::new(secs, nanos))),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::check;
+
+    #[test]
+    fn it_formats_iso8601_durations() {
+        check!(to_iso8601(Duration::from_secs(5400)) == "PT1H30M");
+        check!(to_iso8601(Duration::from_secs(3600)) == "PT1H");
+        check!(to_iso8601(Duration::from_secs(45)) == "PT45S");
+        check!(to_iso8601(Duration::ZERO) == "PT0S");
+    }
+
+    #[test]
+    fn it_parses_iso8601_durations() {
+        check!(parse("PT1H30M") == Some(Duration::from_secs(5400)));
+        check!(parse("PT90M") == Some(Duration::from_secs(5400)));
+        check!(parse("P1DT2H") == Some(Duration::from_secs(93600)));
+        check!(parse("PT0S") == Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn it_parses_short_durations() {
+        check!(parse("1h 30m") == Some(Duration::from_secs(5400)));
+        check!(parse("90m") == Some(Duration::from_secs(5400)));
+        check!(parse("2h") == Some(Duration::from_secs(7200)));
+    }
+
+    #[test]
+    fn it_rejects_invalid_durations() {
+        check!(parse("") == None);
+        check!(parse("P") == None);
+        check!(parse("PT") == None);
+        check!(parse("30m 1h") == None);
+        check!(parse("1.5h") == None);
+        check!(parse("an hour") == None);
+    }
+}
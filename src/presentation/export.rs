@@ -0,0 +1,342 @@
+use actix_web::{
+    HttpResponse, ResponseError,
+    http::{
+        StatusCode,
+        header::{ContentDisposition, ContentType, DispositionParam, DispositionType},
+    },
+    web::{self, Data, ServiceConfig},
+};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    ExportService,
+    core::export::ExportedRecipe,
+    persistance::Repository,
+    presentation::{duration::to_iso8601, recipe_id::RecipeId},
+};
+
+/// Recipe in the `recipe.json` format of Nextcloud Cookbook, which follows schema.org
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NextcloudRecipeDto {
+    #[serde(rename = "@context")]
+    pub(crate) context: &'static str,
+    #[serde(rename = "@type")]
+    pub(crate) kind: &'static str,
+    pub(crate) name: String,
+    /// Holds the pairing, the steps end up in `recipe_instructions`
+    pub(crate) description: String,
+    /// Video accompanying the recipe
+    pub(crate) url: String,
+    /// Thumbnail of the video
+    pub(crate) image: String,
+    pub(crate) cook_time: Option<String>,
+    pub(crate) total_time: Option<String>,
+    pub(crate) recipe_category: String,
+    /// Tags separated by commas
+    pub(crate) keywords: String,
+    pub(crate) recipe_yield: u32,
+    pub(crate) recipe_ingredient: Vec<String>,
+    pub(crate) recipe_instructions: Vec<String>,
+}
+
+impl From<ExportedRecipe> for NextcloudRecipeDto {
+    fn from(value: ExportedRecipe) -> Self {
+        let recipe = value.recipe;
+        let cooking_time = recipe.cooking_time.map(to_iso8601);
+
+        Self {
+            context: "http://schema.org",
+            kind: "Recipe",
+            name: recipe.name,
+            description: recipe
+                .pairing
+                .map(|pairing| format!("Serve with: {pairing}"))
+                .unwrap_or_default(),
+            url: recipe
+                .video
+                .as_ref()
+                .map(|video| video.url.clone())
+                .unwrap_or_default(),
+            image: recipe
+                .video
+                .and_then(|video| video.thumbnail_url)
+                .unwrap_or_default(),
+            cook_time: cooking_time.clone(),
+            total_time: cooking_time,
+            recipe_category: recipe.meal_type.to_string(),
+            keywords: value.tags.join(","),
+            recipe_yield: 1,
+            recipe_ingredient: recipe.ingredients.iter().map(ToString::to_string).collect(),
+            recipe_instructions: recipe
+                .description
+                .iter()
+                .flat_map(|description| description.lines())
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+/// Recipe in the `recipe.json` format of Tandoor exports
+#[derive(Debug, Serialize)]
+pub(crate) struct TandoorRecipeDto {
+    pub(crate) name: String,
+    /// Holds the pairing, the steps end up in `steps`
+    pub(crate) description: Option<String>,
+    /// Tags, along with the meal type
+    pub(crate) keywords: Vec<TandoorNameDto>,
+    pub(crate) steps: Vec<TandoorStepDto>,
+    /// Cooking time in minutes
+    pub(crate) working_time: u64,
+    pub(crate) waiting_time: u64,
+    pub(crate) internal: bool,
+    pub(crate) servings: u32,
+    pub(crate) servings_text: String,
+    pub(crate) source_url: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct TandoorNameDto {
+    pub(crate) name: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct TandoorStepDto {
+    pub(crate) name: String,
+    pub(crate) instruction: String,
+    pub(crate) ingredients: Vec<TandoorIngredientDto>,
+    pub(crate) time: u64,
+    pub(crate) order: u32,
+    pub(crate) show_as_header: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct TandoorIngredientDto {
+    pub(crate) food: TandoorNameDto,
+    /// Missing for counted ingredients
+    pub(crate) unit: Option<TandoorNameDto>,
+    pub(crate) amount: f32,
+    pub(crate) note: String,
+    pub(crate) order: usize,
+    pub(crate) is_header: bool,
+    pub(crate) no_amount: bool,
+}
+
+/// Recipes are written as a single step holding every ingredient, as the steps aren't stored
+/// separately
+impl From<ExportedRecipe> for TandoorRecipeDto {
+    fn from(value: ExportedRecipe) -> Self {
+        let recipe = value.recipe;
+
+        let ingredients = recipe
+            .ingredients
+            .into_iter()
+            .enumerate()
+            .map(|(order, ingredient)| TandoorIngredientDto {
+                unit: ingredient.quantity_type.unit().map(|unit| TandoorNameDto {
+                    name: unit.to_string(),
+                }),
+                food: TandoorNameDto {
+                    name: ingredient.name,
+                },
+                amount: ingredient.quantity,
+                note: String::new(),
+                order,
+                is_header: false,
+                no_amount: false,
+            })
+            .collect();
+
+        let keywords = value
+            .tags
+            .into_iter()
+            .chain([recipe.meal_type.to_string().to_lowercase()])
+            .map(|name| TandoorNameDto { name })
+            .collect();
+
+        Self {
+            name: recipe.name,
+            description: recipe
+                .pairing
+                .map(|pairing| format!("Serve with: {pairing}")),
+            keywords,
+            steps: vec![TandoorStepDto {
+                name: String::new(),
+                instruction: recipe.description.unwrap_or_default(),
+                ingredients,
+                time: 0,
+                order: 0,
+                show_as_header: false,
+            }],
+            working_time: recipe
+                .cooking_time
+                .map(|cooking_time| cooking_time.as_secs().div_ceil(60))
+                .unwrap_or_default(),
+            waiting_time: 0,
+            internal: true,
+            servings: 1,
+            servings_text: String::new(),
+            source_url: recipe.video.map(|video| video.url),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ExportRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::export::ExportRecipeError> for ExportRecipeError {
+    fn from(value: crate::core::export::ExportRecipeError) -> Self {
+        match value {
+            crate::core::export::ExportRecipeError::Unknown(report) => Self::Unknown(report),
+            crate::core::export::ExportRecipeError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for ExportRecipeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ExportRecipeError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ExportRecipeError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+/// Both Nextcloud Cookbook and Tandoor expect the recipe in a file called `recipe.json`
+fn recipe_json(body: impl Serialize) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(ContentType::json())
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename("recipe.json".to_string())],
+        })
+        .json(body)
+}
+
+/// Downloads the recipe as Nextcloud Cookbook `recipe.json`
+pub(crate) async fn export_nextcloud<R: Repository>(
+    svc: Data<ExportService<R>>,
+    RecipeId(recipe_id): RecipeId,
+) -> Result<HttpResponse, ExportRecipeError> {
+    let exported = svc.export_recipe(recipe_id).await?;
+    Ok(recipe_json(NextcloudRecipeDto::from(exported)))
+}
+
+/// Downloads the recipe as Tandoor `recipe.json`
+pub(crate) async fn export_tandoor<R: Repository>(
+    svc: Data<ExportService<R>>,
+    RecipeId(recipe_id): RecipeId,
+) -> Result<HttpResponse, ExportRecipeError> {
+    let exported = svc.export_recipe(recipe_id).await?;
+    Ok(recipe_json(TandoorRecipeDto::from(exported)))
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route(
+        "/recipes/{recipe_id}/export/nextcloud",
+        web::get().to(export_nextcloud::<R>),
+    )
+    .route(
+        "/recipes/{recipe_id}/export/tandoor",
+        web::get().to(export_tandoor::<R>),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use actix_web::{App, test};
+    use assert2::check;
+
+    use crate::{
+        persistance::recipe::{IngredientEntity, QuantityType, RecipeEntity},
+        test_utils::{MockRepository, recipe_entity},
+    };
+
+    fn stew() -> RecipeEntity {
+        RecipeEntity {
+            description: Some("Brown the beef.\n\nSimmer for two hours.".to_string()),
+            ingredients: vec![
+                IngredientEntity {
+                    ingredient_id: 1,
+                    recipe_id: 1,
+                    ingredient_order: 0,
+                    name: "Beef".to_string(),
+                    quantity_type: QuantityType::Gram,
+                    quantity: 500.0,
+                },
+                IngredientEntity {
+                    ingredient_id: 2,
+                    recipe_id: 1,
+                    ingredient_order: 1,
+                    name: "Onions".to_string(),
+                    quantity_type: QuantityType::Count,
+                    quantity: 2.0,
+                },
+            ],
+            cooking_time: Some(Duration::from_secs(2 * 60 * 60)),
+            ..recipe_entity(1, "Stew")
+        }
+    }
+
+    async fn export(format: &str) -> serde_json::Value {
+        let repository = MockRepository::new();
+        repository.on_get_recipe(Ok(stew()));
+        repository.on_recipe_tags(Ok(vec!["winter".to_string()]));
+
+        let app = test::init_service(App::new().configure(configure::<MockRepository>).app_data(
+            Data::new(ExportService::new(repository.clone(), repository.clone())),
+        ))
+        .await;
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/recipes/1/export/{format}"))
+            .to_request();
+        test::call_and_read_body_json(&app, request).await
+    }
+
+    #[actix_web::test]
+    async fn it_exports_to_nextcloud_cookbook() {
+        let recipe = export("nextcloud").await;
+
+        check!(recipe["@type"] == "Recipe");
+        check!(recipe["cookTime"] == "PT2H");
+        check!(recipe["recipeCategory"] == "Dinner");
+        check!(recipe["keywords"] == "winter");
+        check!(recipe["recipeIngredient"] == serde_json::json!(["500 g Beef", "2 Onions"]));
+        check!(
+            recipe["recipeInstructions"]
+                == serde_json::json!(["Brown the beef.", "Simmer for two hours."])
+        );
+    }
+
+    #[actix_web::test]
+    async fn it_exports_to_tandoor() {
+        let recipe = export("tandoor").await;
+
+        check!(recipe["working_time"] == 120);
+        check!(
+            recipe["keywords"] == serde_json::json!([{ "name": "winter" }, { "name": "dinner" }])
+        );
+        check!(recipe["steps"][0]["instruction"] == "Brown the beef.\n\nSimmer for two hours.");
+        check!(recipe["steps"][0]["ingredients"][0]["food"]["name"] == "Beef");
+        check!(recipe["steps"][0]["ingredients"][0]["unit"]["name"] == "g");
+        check!(recipe["steps"][0]["ingredients"][1]["unit"].is_null());
+    }
+}
@@ -0,0 +1,415 @@
+use actix_web::{
+    HttpResponse, ResponseError,
+    http::{StatusCode, header::ContentType},
+    web::{self, Data, Json, Path, ServiceConfig},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::{
+    FederationService,
+    core::federation::{RecipeOrigin, RemoteInstance, SyncSummary},
+    persistance::Repository,
+    presentation::{admin::Admin, json_body::JsonBody, recipe::RecipeDto, recipe_id::RecipeId},
+};
+
+#[derive(Debug, Serialize)]
+pub(crate) struct RemoteInstanceDto {
+    pub(crate) remote_instance_id: i32,
+    pub(crate) url: String,
+    /// Whether a token is sent along, the token itself is never returned
+    pub(crate) has_token: bool,
+    pub(crate) created_at: DateTime<Utc>,
+    pub(crate) last_synced_at: Option<DateTime<Utc>>,
+}
+
+impl From<RemoteInstance> for RemoteInstanceDto {
+    fn from(value: RemoteInstance) -> Self {
+        Self {
+            remote_instance_id: value.remote_instance_id,
+            url: value.url,
+            has_token: value.has_token,
+            created_at: value.created_at,
+            last_synced_at: value.last_synced_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RegisterInstanceDto {
+    /// Url the remote instance is reachable on, like `https://recipes.example.com`
+    pub(crate) url: String,
+    /// Sent as bearer authentication, for instances behind an authenticating proxy
+    #[serde(default)]
+    pub(crate) token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SyncSummaryDto {
+    pub(crate) created: u64,
+    pub(crate) updated: u64,
+    pub(crate) skipped: u64,
+    pub(crate) detached: u64,
+}
+
+impl From<SyncSummary> for SyncSummaryDto {
+    fn from(value: SyncSummary) -> Self {
+        Self {
+            created: value.created,
+            updated: value.updated,
+            skipped: value.skipped,
+            detached: value.detached,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct RecipeOriginDto {
+    pub(crate) remote_instance_id: i32,
+    pub(crate) url: String,
+    /// Public id of the recipe on the remote instance
+    pub(crate) remote_public_id: Uuid,
+    /// Set once the recipe is no longer kept in sync with the remote instance
+    pub(crate) detached: bool,
+}
+
+impl From<RecipeOrigin> for RecipeOriginDto {
+    fn from(value: RecipeOrigin) -> Self {
+        Self {
+            remote_instance_id: value.remote_instance_id,
+            url: value.url,
+            remote_public_id: value.remote_public_id,
+            detached: value.detached,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct FederationOptOutDto {
+    pub(crate) opt_out: bool,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum RegisterInstanceError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("`{0}` is not an http or https url")]
+    InvalidUrl(String),
+    #[error("`{0}` points to a private or loopback address")]
+    PrivateAddress(String),
+    #[error("The instance is already registered")]
+    AlreadyRegistered,
+    #[error("Instances with a token need a federation key to be configured")]
+    TokensDisabled,
+}
+
+impl From<crate::core::federation::RegisterInstanceError> for RegisterInstanceError {
+    fn from(value: crate::core::federation::RegisterInstanceError) -> Self {
+        match value {
+            crate::core::federation::RegisterInstanceError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::core::federation::RegisterInstanceError::InvalidUrl(url) => {
+                Self::InvalidUrl(url)
+            }
+            crate::core::federation::RegisterInstanceError::PrivateAddress(url) => {
+                Self::PrivateAddress(url)
+            }
+            crate::core::federation::RegisterInstanceError::AlreadyRegistered => {
+                Self::AlreadyRegistered
+            }
+            crate::core::federation::RegisterInstanceError::TokensDisabled => Self::TokensDisabled,
+        }
+    }
+}
+
+impl ResponseError for RegisterInstanceError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RegisterInstanceError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            RegisterInstanceError::InvalidUrl(_) | RegisterInstanceError::PrivateAddress(_) => {
+                StatusCode::BAD_REQUEST
+            }
+            RegisterInstanceError::AlreadyRegistered => StatusCode::CONFLICT,
+            RegisterInstanceError::TokensDisabled => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ListInstancesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::core::federation::ListInstancesError> for ListInstancesError {
+    fn from(value: crate::core::federation::ListInstancesError) -> Self {
+        match value {
+            crate::core::federation::ListInstancesError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl ResponseError for ListInstancesError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ListInstancesError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum RemoveInstanceError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The instance could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::federation::RemoveInstanceError> for RemoveInstanceError {
+    fn from(value: crate::core::federation::RemoveInstanceError) -> Self {
+        match value {
+            crate::core::federation::RemoveInstanceError::Unknown(report) => Self::Unknown(report),
+            crate::core::federation::RemoveInstanceError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for RemoveInstanceError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RemoveInstanceError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            RemoveInstanceError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum FederatedRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::core::federation::FederatedRecipesError> for FederatedRecipesError {
+    fn from(value: crate::core::federation::FederatedRecipesError) -> Self {
+        match value {
+            crate::core::federation::FederatedRecipesError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+impl ResponseError for FederatedRecipesError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            FederatedRecipesError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum SyncInstanceError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The instance could not be found")]
+    NotFound,
+    #[error("The instance rejected the token")]
+    Unauthorized,
+}
+
+impl From<crate::core::federation::SyncInstanceError> for SyncInstanceError {
+    fn from(value: crate::core::federation::SyncInstanceError) -> Self {
+        match value {
+            crate::core::federation::SyncInstanceError::Unknown(report) => Self::Unknown(report),
+            crate::core::federation::SyncInstanceError::NotFound => Self::NotFound,
+            crate::core::federation::SyncInstanceError::Unauthorized => Self::Unauthorized,
+        }
+    }
+}
+
+impl ResponseError for SyncInstanceError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SyncInstanceError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SyncInstanceError::NotFound => StatusCode::NOT_FOUND,
+            SyncInstanceError::Unauthorized => StatusCode::BAD_GATEWAY,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum RecipeOriginError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::federation::RecipeOriginError> for RecipeOriginError {
+    fn from(value: crate::core::federation::RecipeOriginError) -> Self {
+        match value {
+            crate::core::federation::RecipeOriginError::Unknown(report) => Self::Unknown(report),
+            crate::core::federation::RecipeOriginError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for RecipeOriginError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RecipeOriginError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            RecipeOriginError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum SetFederationOptOutError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::federation::SetFederationOptOutError> for SetFederationOptOutError {
+    fn from(value: crate::core::federation::SetFederationOptOutError) -> Self {
+        match value {
+            crate::core::federation::SetFederationOptOutError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::core::federation::SetFederationOptOutError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for SetFederationOptOutError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SetFederationOptOutError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SetFederationOptOutError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+/// Recipes published to the instances mirroring this one, in the format of `GET /recipes`
+pub(crate) async fn federated_recipes<R: Repository>(
+    svc: Data<FederationService<R>>,
+) -> Result<Json<Vec<RecipeDto>>, FederatedRecipesError> {
+    let recipes = svc.federated_recipes().await?;
+    Ok(Json(recipes.into_iter().map(RecipeDto::from).collect()))
+}
+
+pub(crate) async fn list_instances<R: Repository>(
+    _: Admin,
+    svc: Data<FederationService<R>>,
+) -> Result<Json<Vec<RemoteInstanceDto>>, ListInstancesError> {
+    let instances = svc.list_instances().await?;
+    Ok(Json(
+        instances.into_iter().map(RemoteInstanceDto::from).collect(),
+    ))
+}
+
+pub(crate) async fn register_instance<R: Repository>(
+    _: Admin,
+    svc: Data<FederationService<R>>,
+    JsonBody(data): JsonBody<RegisterInstanceDto>,
+) -> Result<HttpResponse, RegisterInstanceError> {
+    let instance = svc.register_instance(data.url, data.token).await?;
+
+    Ok(HttpResponse::Created()
+        .content_type(ContentType::json())
+        .json(RemoteInstanceDto::from(instance)))
+}
+
+pub(crate) async fn remove_instance<R: Repository>(
+    _: Admin,
+    svc: Data<FederationService<R>>,
+    path: Path<i32>,
+) -> Result<HttpResponse, RemoveInstanceError> {
+    svc.remove_instance(path.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Syncs an instance right away instead of waiting on the next background sync
+pub(crate) async fn sync_instance<R: Repository>(
+    _: Admin,
+    svc: Data<FederationService<R>>,
+    path: Path<i32>,
+) -> Result<Json<SyncSummaryDto>, SyncInstanceError> {
+    let summary = svc.sync_instance(path.into_inner()).await?;
+    Ok(Json(summary.into()))
+}
+
+/// Instance the recipe was mirrored from, `null` for recipes written on this instance
+pub(crate) async fn recipe_origin<R: Repository>(
+    svc: Data<FederationService<R>>,
+    RecipeId(recipe_id): RecipeId,
+) -> Result<Json<Option<RecipeOriginDto>>, RecipeOriginError> {
+    let origin = svc.recipe_origin(recipe_id).await?;
+    Ok(Json(origin.map(RecipeOriginDto::from)))
+}
+
+pub(crate) async fn set_federation_opt_out<R: Repository>(
+    svc: Data<FederationService<R>>,
+    RecipeId(recipe_id): RecipeId,
+    JsonBody(data): JsonBody<FederationOptOutDto>,
+) -> Result<HttpResponse, SetFederationOptOutError> {
+    svc.set_opt_out(recipe_id, data.opt_out).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route("/federation/recipes", web::get().to(federated_recipes::<R>))
+        .route("/federation/instances", web::get().to(list_instances::<R>))
+        .route(
+            "/federation/instances",
+            web::post().to(register_instance::<R>),
+        )
+        .route(
+            "/federation/instances/{remote_instance_id}",
+            web::delete().to(remove_instance::<R>),
+        )
+        .route(
+            "/federation/instances/{remote_instance_id}/sync",
+            web::post().to(sync_instance::<R>),
+        )
+        .route(
+            "/recipes/{recipe_id}/origin",
+            web::get().to(recipe_origin::<R>),
+        )
+        .route(
+            "/recipes/{recipe_id}/federation",
+            web::put().to(set_federation_opt_out::<R>),
+        );
+}
@@ -0,0 +1,193 @@
+use actix_web::{
+    HttpResponse, ResponseError,
+    http::{StatusCode, header::ContentType},
+    web::{self, Data, Json, ServiceConfig},
+};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    RecipeService,
+    core::recipe::RecipeFork,
+    persistance::Repository,
+    presentation::{recipe::RecipeDto, recipe_id::RecipeId, unit_system::Units},
+};
+
+#[derive(Debug, Serialize)]
+pub(crate) struct RecipeForkDto {
+    pub(crate) recipe_id: i32,
+    pub(crate) name: String,
+    pub(crate) forks: Vec<RecipeForkDto>,
+}
+
+impl From<RecipeFork> for RecipeForkDto {
+    fn from(value: RecipeFork) -> Self {
+        Self {
+            recipe_id: value.recipe_id,
+            name: value.name,
+            forks: value.forks.into_iter().map(RecipeForkDto::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ForkRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::recipe::ForkRecipeError> for ForkRecipeError {
+    fn from(value: crate::core::recipe::ForkRecipeError) -> Self {
+        match value {
+            crate::core::recipe::ForkRecipeError::Unknown(report) => Self::Unknown(report),
+            crate::core::recipe::ForkRecipeError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for ForkRecipeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ForkRecipeError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ForkRecipeError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ListForksError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::recipe::ListForksError> for ListForksError {
+    fn from(value: crate::core::recipe::ListForksError) -> Self {
+        match value {
+            crate::core::recipe::ListForksError::Unknown(report) => Self::Unknown(report),
+            crate::core::recipe::ListForksError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for ListForksError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ListForksError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ListForksError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+pub(crate) async fn fork_recipe<R: Repository>(
+    svc: Data<RecipeService<R>>,
+    RecipeId(recipe_id): RecipeId,
+    Units(units): Units,
+) -> Result<HttpResponse, ForkRecipeError> {
+    let recipe = svc.fork_recipe(recipe_id).await?;
+
+    Ok(HttpResponse::Created()
+        .content_type(ContentType::json())
+        .json(RecipeDto::from(recipe).into_system(units)))
+}
+
+pub(crate) async fn recipe_forks<R: Repository>(
+    svc: Data<RecipeService<R>>,
+    RecipeId(recipe_id): RecipeId,
+) -> Result<Json<Vec<RecipeForkDto>>, ListForksError> {
+    let forks = svc.recipe_forks(recipe_id).await?;
+    Ok(Json(forks.into_iter().map(RecipeForkDto::from).collect()))
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route(
+        "/recipes/{recipe_id}/fork",
+        web::post().to(fork_recipe::<R>),
+    )
+    .route(
+        "/recipes/{recipe_id}/forks",
+        web::get().to(recipe_forks::<R>),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{App, test};
+    use sqlx::PgPool;
+
+    use crate::Postgres;
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_should_fork_a_recipe_and_list_the_fork_tree(pool: PgPool) {
+        let recipe_id = sqlx::query_scalar!(
+            "INSERT INTO recipe (name, meal_type) VALUES ('Pizza', 'Dinner') RETURNING recipe_id"
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to insert recipe");
+
+        let app = test::init_service(
+            App::new()
+                .configure(configure::<Postgres>)
+                .app_data(Data::new(RecipeService::new(Postgres::new(pool), None))),
+        )
+        .await;
+
+        let request = test::TestRequest::post()
+            .uri(&format!("/recipes/{recipe_id}/fork"))
+            .to_request();
+        let fork: RecipeDto = test::call_and_read_body_json(&app, request).await;
+
+        assert2::check!(fork.name == "Pizza");
+        assert2::check!(fork.forked_from == Some(recipe_id));
+
+        let request = test::TestRequest::post()
+            .uri(&format!("/recipes/{}/fork", fork.recipe_id))
+            .to_request();
+        let nested: RecipeDto = test::call_and_read_body_json(&app, request).await;
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/recipes/{recipe_id}/forks"))
+            .to_request();
+        let body = test::call_and_read_body(&app, request).await;
+
+        assert2::check!(
+            body == format!(
+                r#"[{{"recipe_id":{},"name":"Pizza","forks":[{{"recipe_id":{},"name":"Pizza","forks":[]}}]}}]"#,
+                fork.recipe_id, nested.recipe_id
+            )
+        );
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_should_return_404_when_forking_a_missing_recipe(pool: PgPool) {
+        let app = test::init_service(
+            App::new()
+                .configure(configure::<Postgres>)
+                .app_data(Data::new(RecipeService::new(Postgres::new(pool), None))),
+        )
+        .await;
+
+        let request = test::TestRequest::post()
+            .uri("/recipes/42/fork")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+
+        assert2::check!(response.status() == StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,123 @@
+use std::path::Path;
+
+use actix_files::{Files, NamedFile};
+use actix_web::{
+    HttpRequest, HttpResponse,
+    dev::{ServiceRequest, ServiceResponse, fn_service},
+    guard,
+    http::{
+        Method,
+        header::{Accept, Header},
+    },
+    mime,
+};
+
+/// Whether `request` is a browser navigation, only those fall back to the frontend so unknown
+/// API paths and non-`GET` requests keep returning a plain `404`
+fn is_page_request(request: &HttpRequest) -> bool {
+    request.method() == Method::GET
+        && Accept::parse(request)
+            .is_ok_and(|accept| accept.iter().any(|item| item.item == mime::TEXT_HTML))
+}
+
+/// Serves the static frontend in `dir`. Paths which don't match a file are answered with
+/// `index.html` when a browser asks for a page, so routing of a single page application keeps
+/// working on reload.
+///
+/// Other methods than `GET` and `HEAD` don't match, so they end up at the default service of
+/// wherever this is registered. This has to be registered after all API services since it matches
+/// every path.
+pub(crate) fn frontend(dir: &Path) -> Files {
+    let index = dir.join("index.html");
+
+    Files::new("/", dir)
+        .guard(guard::Any(guard::Get()).or(guard::Head()))
+        .index_file("index.html")
+        .default_handler(fn_service(move |request: ServiceRequest| {
+            let index = index.clone();
+
+            async move {
+                let (request, _) = request.into_parts();
+
+                if !is_page_request(&request) {
+                    return Ok(ServiceResponse::new(
+                        request,
+                        HttpResponse::NotFound().finish(),
+                    ));
+                }
+
+                let response = NamedFile::open_async(index).await?.into_response(&request);
+
+                Ok(ServiceResponse::new(request, response))
+            }
+        }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{
+        App,
+        http::{StatusCode, header},
+        test,
+    };
+    use assert2::check;
+
+    fn create_frontend_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "gecko-recipes-frontend-{name}-{}",
+            std::process::id()
+        ));
+
+        std::fs::create_dir_all(dir.join("assets")).expect("Failed to create frontend dir");
+        std::fs::write(dir.join("index.html"), "<html>app</html>").expect("Failed to write index");
+        std::fs::write(dir.join("assets/app.js"), "console.log('app')")
+            .expect("Failed to write asset");
+
+        dir
+    }
+
+    #[actix_web::test]
+    async fn it_serves_files_and_falls_back_to_the_index() {
+        let dir = create_frontend_dir("fallback");
+        let app = test::init_service(App::new().service(frontend(&dir))).await;
+
+        let request = test::TestRequest::get().uri("/assets/app.js").to_request();
+        let response = test::call_service(&app, request).await;
+        check!(response.status() == StatusCode::OK);
+        check!(test::read_body(response).await == "console.log('app')");
+
+        let request = test::TestRequest::get()
+            .uri("/meal-plan/week")
+            .insert_header((header::ACCEPT, "text/html,application/xhtml+xml,*/*;q=0.8"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        check!(response.status() == StatusCode::OK);
+        check!(test::read_body(response).await == "<html>app</html>");
+    }
+
+    #[actix_web::test]
+    async fn it_only_falls_back_for_page_requests() {
+        let dir = create_frontend_dir("api");
+        let app = test::init_service(App::new().service(frontend(&dir))).await;
+
+        let request = test::TestRequest::get()
+            .uri("/recipes/1/unknown")
+            .insert_header((header::ACCEPT, "application/json"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        check!(response.status() == StatusCode::NOT_FOUND);
+
+        let request = test::TestRequest::get().uri("/meal-plan/week").to_request();
+        let response = test::call_service(&app, request).await;
+        check!(response.status() == StatusCode::NOT_FOUND);
+
+        let request = test::TestRequest::post()
+            .uri("/meal-plan/week")
+            .insert_header((header::ACCEPT, "text/html"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        check!(response.status() == StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,115 @@
+use actix_web::{
+    ResponseError,
+    http::StatusCode,
+    post,
+    web::{Data, Json},
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    GenerateService,
+    presentation::{json_body::JsonBody, recipe::NewRecipeDto},
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct GenerateRecipeDto {
+    /// What to cook, like `vegetarian dinner with leftover rice`
+    pub(crate) prompt: String,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum GenerateRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error(
+        "The prompt must be between 1 and {} characters",
+        crate::core::generate::MAX_PROMPT_LENGTH
+    )]
+    InvalidPrompt,
+    #[error("No recipe was generated for the prompt")]
+    NoRecipe,
+    #[error("Generating recipes is not configured on this instance")]
+    Disabled,
+}
+
+impl From<crate::core::generate::GenerateRecipeError> for GenerateRecipeError {
+    fn from(value: crate::core::generate::GenerateRecipeError) -> Self {
+        match value {
+            crate::core::generate::GenerateRecipeError::Unknown(report) => Self::Unknown(report),
+            crate::core::generate::GenerateRecipeError::InvalidPrompt => Self::InvalidPrompt,
+            crate::core::generate::GenerateRecipeError::NoRecipe => Self::NoRecipe,
+            crate::core::generate::GenerateRecipeError::Disabled => Self::Disabled,
+        }
+    }
+}
+
+impl ResponseError for GenerateRecipeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            GenerateRecipeError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            GenerateRecipeError::InvalidPrompt => StatusCode::BAD_REQUEST,
+            GenerateRecipeError::NoRecipe => StatusCode::BAD_GATEWAY,
+            GenerateRecipeError::Disabled => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
+
+/// Generates a draft recipe from a prompt, the draft is returned for review and not saved
+#[post("/recipes/generate")]
+pub(crate) async fn generate_recipe(
+    svc: Data<GenerateService>,
+    JsonBody(data): JsonBody<GenerateRecipeDto>,
+) -> Result<Json<NewRecipeDto>, GenerateRecipeError> {
+    let draft = svc.generate_recipe(&data.prompt).await?;
+    Ok(Json(draft.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{App, http::header::ContentType, test};
+
+    #[actix_web::test]
+    async fn it_should_return_503_when_not_configured() {
+        let app = test::init_service(
+            App::new()
+                .service(generate_recipe)
+                .app_data(Data::new(GenerateService::new(None))),
+        )
+        .await;
+
+        let request = test::TestRequest::post()
+            .uri("/recipes/generate")
+            .insert_header(ContentType::json())
+            .set_payload(r#"{"prompt":"vegetarian dinner with leftover rice"}"#)
+            .to_request();
+        let response = test::call_service(&app, request).await;
+
+        assert2::check!(response.status() == StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[actix_web::test]
+    async fn it_should_return_400_for_an_empty_prompt() {
+        let app = test::init_service(
+            App::new()
+                .service(generate_recipe)
+                .app_data(Data::new(GenerateService::new(None))),
+        )
+        .await;
+
+        let request = test::TestRequest::post()
+            .uri("/recipes/generate")
+            .insert_header(ContentType::json())
+            .set_payload(r#"{"prompt":"  "}"#)
+            .to_request();
+        let response = test::call_service(&app, request).await;
+
+        assert2::check!(response.status() == StatusCode::BAD_REQUEST);
+    }
+}
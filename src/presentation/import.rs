@@ -0,0 +1,425 @@
+use actix_web::{
+    ResponseError,
+    http::StatusCode,
+    post,
+    web::{self, Bytes, Data, Json, PayloadConfig, ServiceConfig},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::{
+    ImportService,
+    core::{import::ParsedIngredient, nextcloud::NextcloudRecipe},
+    presentation::{
+        duration,
+        json_body::JsonBody,
+        recipe::{IngredientDto, NewRecipeDto},
+    },
+};
+
+/// Largest photo accepted for imports, phone cameras easily produce images of several megabytes
+pub(crate) const MAX_IMAGE_SIZE: usize = 10 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub(crate) enum ImportImageError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The request did not contain an image")]
+    MissingImage,
+    #[error("No text could be found in the image")]
+    NoText,
+    #[error("Importing images is not configured on this instance")]
+    Disabled,
+}
+
+impl From<crate::core::import::ImportImageError> for ImportImageError {
+    fn from(value: crate::core::import::ImportImageError) -> Self {
+        match value {
+            crate::core::import::ImportImageError::Unknown(report) => Self::Unknown(report),
+            crate::core::import::ImportImageError::NoText => Self::NoText,
+            crate::core::import::ImportImageError::Disabled => Self::Disabled,
+        }
+    }
+}
+
+impl ResponseError for ImportImageError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ImportImageError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ImportImageError::MissingImage => StatusCode::BAD_REQUEST,
+            ImportImageError::NoText => StatusCode::UNPROCESSABLE_ENTITY,
+            ImportImageError::Disabled => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
+
+/// Reads a photo of a recipe into a draft, the draft is returned for review and not saved
+pub(crate) async fn import_image(
+    svc: Data<ImportService>,
+    body: Bytes,
+) -> Result<Json<NewRecipeDto>, ImportImageError> {
+    if body.is_empty() {
+        return Err(ImportImageError::MissingImage);
+    }
+
+    let draft = svc.import_image(body.to_vec()).await?;
+    Ok(Json(draft.into()))
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ImportTextError {
+    #[error("The request did not contain any text")]
+    MissingText,
+}
+
+impl ResponseError for ImportTextError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ImportTextError::MissingText => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+/// Splits a pasted recipe into a draft, the draft is returned for review and not saved
+#[post("/recipes/import/text")]
+pub(crate) async fn import_text(body: String) -> Result<Json<NewRecipeDto>, ImportTextError> {
+    if body.trim().is_empty() {
+        return Err(ImportTextError::MissingText);
+    }
+
+    let draft = crate::core::import::draft_from_text(&body);
+    Ok(Json(draft.into()))
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ParseIngredientsDto {
+    /// Pasted ingredients, one per line
+    pub(crate) text: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ParsedIngredientDto {
+    #[serde(flatten)]
+    pub(crate) ingredient: IngredientDto,
+    /// Remarks on the ingredient, like `sifted`
+    pub(crate) note: Option<String>,
+}
+
+impl From<ParsedIngredient> for ParsedIngredientDto {
+    fn from(value: ParsedIngredient) -> Self {
+        Self {
+            ingredient: IngredientDto {
+                name: value.name,
+                quantity_type: value.quantity.unit.into(),
+                quantity: value.quantity.amount,
+            },
+            note: value.note,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ParsedIngredientsDto {
+    pub(crate) ingredients: Vec<ParsedIngredientDto>,
+    /// Lines which could not be read as an ingredient, usually because they have no quantity
+    pub(crate) unparsed: Vec<String>,
+}
+
+/// Turns pasted lines into ingredients in the unit they were written in, nothing is saved
+#[post("/recipes/parse-ingredients")]
+pub(crate) async fn parse_ingredients(
+    JsonBody(data): JsonBody<ParseIngredientsDto>,
+) -> Json<ParsedIngredientsDto> {
+    let mut ingredients = vec![];
+    let mut unparsed = vec![];
+
+    for line in data
+        .text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+    {
+        match crate::core::import::parse_ingredient(line) {
+            Some(ingredient) => ingredients.push(ingredient.into()),
+            None => unparsed.push(line.to_string()),
+        }
+    }
+
+    Json(ParsedIngredientsDto {
+        ingredients,
+        unparsed,
+    })
+}
+
+/// Fields of a Nextcloud Cookbook recipe which only describe the file itself
+const NEXTCLOUD_METADATA: [&str; 9] = [
+    "@context",
+    "@type",
+    "id",
+    "dateCreated",
+    "dateModified",
+    "datePublished",
+    "printImage",
+    "imageUrl",
+    "imagePlaceholderUrl",
+];
+
+/// `recipe.json` of a Nextcloud Cookbook recipe, which follows schema.org. Fields without a place
+/// in a recipe end up in `other`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NextcloudImportDto {
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    #[serde(default)]
+    pub(crate) recipe_ingredient: Vec<String>,
+    #[serde(default)]
+    pub(crate) recipe_instructions: Vec<NextcloudInstructionDto>,
+    pub(crate) prep_time: Option<String>,
+    pub(crate) cook_time: Option<String>,
+    pub(crate) total_time: Option<String>,
+    pub(crate) recipe_category: Option<String>,
+    /// Separated by commas
+    pub(crate) keywords: Option<String>,
+    pub(crate) url: Option<String>,
+    #[serde(flatten)]
+    pub(crate) other: Map<String, Value>,
+}
+
+/// Instructions are plain text in Cookbook, but schema.org also allows `HowToStep` objects
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum NextcloudInstructionDto {
+    Text(String),
+    Step { text: String },
+}
+
+/// Whether a field holds anything besides empty values and schema.org annotations like `@type`
+fn has_content(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(_) | Value::Number(_) => true,
+        Value::String(value) => !value.trim().is_empty(),
+        Value::Array(values) => values.iter().any(has_content),
+        Value::Object(fields) => fields
+            .iter()
+            .any(|(key, value)| !key.starts_with('@') && has_content(value)),
+    }
+}
+
+impl From<NextcloudImportDto> for NextcloudRecipe {
+    fn from(value: NextcloudImportDto) -> Self {
+        let mut unsupported = value
+            .other
+            .iter()
+            .filter(|(key, value)| {
+                !NEXTCLOUD_METADATA.contains(&key.as_str()) && has_content(value)
+            })
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+
+        let mut parse_time = |field: &str, time: Option<String>| {
+            let time = time.filter(|time| !time.trim().is_empty())?;
+            let parsed = duration::parse(&time);
+
+            if parsed.is_none() {
+                unsupported.push(field.to_string());
+            }
+
+            parsed
+        };
+
+        let prep_time = parse_time("prepTime", value.prep_time);
+        let cook_time = parse_time("cookTime", value.cook_time);
+        let total_time = parse_time("totalTime", value.total_time);
+
+        Self {
+            name: value.name,
+            description: value.description,
+            ingredients: value.recipe_ingredient,
+            instructions: value
+                .recipe_instructions
+                .into_iter()
+                .map(|instruction| match instruction {
+                    NextcloudInstructionDto::Text(text)
+                    | NextcloudInstructionDto::Step { text } => text,
+                })
+                .collect(),
+            prep_time,
+            cook_time,
+            total_time,
+            category: value.recipe_category,
+            keywords: value
+                .keywords
+                .iter()
+                .flat_map(|keywords| keywords.split(','))
+                .map(str::to_string)
+                .collect(),
+            url: value.url,
+            unsupported,
+        }
+    }
+}
+
+/// Only the image import accepts payloads up to [`MAX_IMAGE_SIZE`], the other routes keep the
+/// default limit
+pub(crate) fn configure(cfg: &mut ServiceConfig) {
+    cfg.service(
+        web::resource("/recipes/import/image")
+            .app_data(PayloadConfig::new(MAX_IMAGE_SIZE))
+            .route(web::post().to(import_image)),
+    )
+    .service(import_text)
+    .service(parse_ingredients);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{App, test};
+    use assert2::check;
+
+    #[actix_web::test]
+    async fn it_should_return_503_when_not_configured() {
+        let app = test::init_service(
+            App::new()
+                .configure(configure)
+                .app_data(Data::new(ImportService::new(None))),
+        )
+        .await;
+
+        let request = test::TestRequest::post()
+            .uri("/recipes/import/image")
+            .set_payload(vec![0x89, b'P', b'N', b'G'])
+            .to_request();
+        let response = test::call_service(&app, request).await;
+
+        check!(response.status() == StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[actix_web::test]
+    async fn it_only_accepts_large_payloads_for_images() {
+        let app = test::init_service(
+            App::new()
+                .configure(configure)
+                .app_data(Data::new(ImportService::new(None))),
+        )
+        .await;
+
+        let request = test::TestRequest::post()
+            .uri("/recipes/import/image")
+            .set_payload(vec![0; 1024 * 1024])
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        check!(response.status() == StatusCode::SERVICE_UNAVAILABLE);
+
+        let request = test::TestRequest::post()
+            .uri("/recipes/import/image")
+            .set_payload(vec![0; MAX_IMAGE_SIZE + 1])
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        check!(response.status() == StatusCode::PAYLOAD_TOO_LARGE);
+
+        let request = test::TestRequest::post()
+            .uri("/recipes/import/text")
+            .set_payload("a".repeat(1024 * 1024))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        check!(response.status() == StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[actix_web::test]
+    async fn it_drafts_a_recipe_from_pasted_text() {
+        let app = test::init_service(App::new().service(import_text)).await;
+
+        let request = test::TestRequest::post()
+            .uri("/recipes/import/text")
+            .set_payload(
+                "Pancakes\n\nIngredients\n2 cups flour\n2 eggs\n\nMethod\n1. Whisk.\n2. Fry.",
+            )
+            .to_request();
+        let draft: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+
+        check!(draft["name"] == "Pancakes");
+        check!(draft["ingredients"][0]["name"] == "flour");
+        check!(draft["ingredients"][0]["quantity_type"] == "Milliliter");
+        check!(draft["ingredients"].as_array().map(Vec::len) == Some(2));
+        check!(draft["description"] == "1. Whisk.\n2. Fry.");
+    }
+
+    #[actix_web::test]
+    async fn it_rejects_empty_text() {
+        let app = test::init_service(App::new().service(import_text)).await;
+
+        let request = test::TestRequest::post()
+            .uri("/recipes/import/text")
+            .set_payload("  \n")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+
+        check!(response.status() == StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_web::test]
+    async fn it_reports_nextcloud_fields_without_a_place_as_unsupported() {
+        let dto = serde_json::from_value::<NextcloudImportDto>(serde_json::json!({
+            "@context": "http://schema.org",
+            "@type": "Recipe",
+            "id": "1234",
+            "name": "Stew",
+            "recipeIngredient": ["500 g beef"],
+            "recipeInstructions": ["Brown the beef.", { "@type": "HowToStep", "text": "Simmer." }],
+            "cookTime": "PT2H0M0S",
+            "prepTime": "soon",
+            "keywords": "winter,hearty",
+            "image": "https://example.com/stew.jpg",
+            "recipeYield": 4,
+            "tool": [],
+            "nutrition": { "@type": "NutritionInformation", "calories": "450 kcal" },
+        }))
+        .expect("Valid recipe");
+
+        let recipe = NextcloudRecipe::from(dto);
+
+        check!(recipe.instructions == vec!["Brown the beef.", "Simmer."]);
+        check!(recipe.cook_time == Some(std::time::Duration::from_secs(2 * 60 * 60)));
+        check!(recipe.prep_time.is_none());
+        check!(recipe.keywords == vec!["winter", "hearty"]);
+        check!(recipe.unsupported == vec!["image", "nutrition", "recipeYield", "prepTime"]);
+    }
+
+    #[actix_web::test]
+    async fn it_parses_pasted_ingredients() {
+        let app = test::init_service(App::new().service(parse_ingredients)).await;
+
+        let request = test::TestRequest::post()
+            .uri("/recipes/parse-ingredients")
+            .set_json(serde_json::json!({
+                "text": "2 1/2 cups all-purpose flour, sifted\n\n3 eggs\nSalt to taste",
+            }))
+            .to_request();
+        let parsed: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+
+        check!(
+            parsed
+                == serde_json::json!({
+                    "ingredients": [
+                        {
+                            "name": "all-purpose flour",
+                            "quantity_type": "Cup",
+                            "quantity": 2.5,
+                            "note": "sifted",
+                        },
+                        { "name": "eggs", "quantity_type": "Count", "quantity": 3.0, "note": null },
+                    ],
+                    "unparsed": ["Salt to taste"],
+                })
+        );
+    }
+}
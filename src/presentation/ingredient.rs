@@ -0,0 +1,357 @@
+use actix_web::{
+    HttpResponse, ResponseError,
+    http::StatusCode,
+    web::{self, Data, Json, Path, ServiceConfig},
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::{
+    IngredientService,
+    core::ingredient::{IngredientDensity, IngredientRecipe, IngredientUsage},
+    persistance::Repository,
+    presentation::{
+        json_body::JsonBody,
+        recipe::{IngredientDto, MealType},
+        unit_system::Units,
+    },
+};
+
+#[derive(Debug, Serialize)]
+pub(crate) struct IngredientUsageDto {
+    pub(crate) name: String,
+    /// Number of recipes using the ingredient
+    pub(crate) recipe_count: u64,
+}
+
+impl From<IngredientUsage> for IngredientUsageDto {
+    fn from(value: IngredientUsage) -> Self {
+        Self {
+            name: value.name,
+            recipe_count: value.recipe_count,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct IngredientRecipeDto {
+    pub(crate) recipe_id: i32,
+    pub(crate) public_id: Uuid,
+    pub(crate) name: String,
+    pub(crate) meal_type: MealType,
+    /// The ingredient as it appears in the recipe
+    pub(crate) ingredient: IngredientDto,
+}
+
+impl From<IngredientRecipe> for IngredientRecipeDto {
+    fn from(value: IngredientRecipe) -> Self {
+        Self {
+            recipe_id: value.recipe_id,
+            public_id: value.public_id,
+            name: value.name,
+            meal_type: value.meal_type.into(),
+            ingredient: value.ingredient.into(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ListIngredientsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::core::ingredient::ListIngredientsError> for ListIngredientsError {
+    fn from(value: crate::core::ingredient::ListIngredientsError) -> Self {
+        match value {
+            crate::core::ingredient::ListIngredientsError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl ResponseError for ListIngredientsError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ListIngredientsError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum IngredientRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::core::ingredient::IngredientRecipesError> for IngredientRecipesError {
+    fn from(value: crate::core::ingredient::IngredientRecipesError) -> Self {
+        match value {
+            crate::core::ingredient::IngredientRecipesError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+impl ResponseError for IngredientRecipesError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            IngredientRecipesError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RenameIngredientDto {
+    pub(crate) from: String,
+    pub(crate) to: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct RenamedIngredientDto {
+    /// Number of recipes the ingredient was renamed in
+    pub(crate) recipes_updated: u64,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum RenameIngredientError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("Ingredient names can't be empty")]
+    EmptyName,
+}
+
+impl From<crate::core::ingredient::RenameIngredientError> for RenameIngredientError {
+    fn from(value: crate::core::ingredient::RenameIngredientError) -> Self {
+        match value {
+            crate::core::ingredient::RenameIngredientError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::core::ingredient::RenameIngredientError::EmptyName => Self::EmptyName,
+        }
+    }
+}
+
+impl ResponseError for RenameIngredientError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RenameIngredientError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            RenameIngredientError::EmptyName => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct IngredientDensityDto {
+    pub(crate) name: String,
+    pub(crate) grams_per_milliliter: f32,
+}
+
+impl From<IngredientDensity> for IngredientDensityDto {
+    fn from(value: IngredientDensity) -> Self {
+        Self {
+            name: value.name,
+            grams_per_milliliter: value.grams_per_milliliter,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct SetDensityDto {
+    pub(crate) grams_per_milliliter: f32,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ListDensitiesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::core::ingredient::ListDensitiesError> for ListDensitiesError {
+    fn from(value: crate::core::ingredient::ListDensitiesError) -> Self {
+        match value {
+            crate::core::ingredient::ListDensitiesError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl ResponseError for ListDensitiesError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ListDensitiesError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum SetDensityError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("Ingredient names can't be empty")]
+    EmptyName,
+    #[error("Densities have to be a positive number of grams per milliliter")]
+    InvalidDensity,
+}
+
+impl From<crate::core::ingredient::SetDensityError> for SetDensityError {
+    fn from(value: crate::core::ingredient::SetDensityError) -> Self {
+        match value {
+            crate::core::ingredient::SetDensityError::Unknown(report) => Self::Unknown(report),
+            crate::core::ingredient::SetDensityError::EmptyName => Self::EmptyName,
+            crate::core::ingredient::SetDensityError::InvalidDensity => Self::InvalidDensity,
+        }
+    }
+}
+
+impl ResponseError for SetDensityError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SetDensityError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SetDensityError::EmptyName | SetDensityError::InvalidDensity => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum DeleteDensityError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("No density is known for the ingredient")]
+    NotFound,
+}
+
+impl From<crate::core::ingredient::DeleteDensityError> for DeleteDensityError {
+    fn from(value: crate::core::ingredient::DeleteDensityError) -> Self {
+        match value {
+            crate::core::ingredient::DeleteDensityError::Unknown(report) => Self::Unknown(report),
+            crate::core::ingredient::DeleteDensityError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for DeleteDensityError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            DeleteDensityError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            DeleteDensityError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+/// Distinct ingredient names with the number of recipes using them
+pub(crate) async fn list_ingredients<R: Repository>(
+    svc: Data<IngredientService<R>>,
+) -> Result<Json<Vec<IngredientUsageDto>>, ListIngredientsError> {
+    let ingredients = svc.list_ingredients().await?;
+    Ok(Json(
+        ingredients
+            .into_iter()
+            .map(IngredientUsageDto::from)
+            .collect(),
+    ))
+}
+
+/// Recipes using an ingredient, an unknown ingredient has no recipes
+pub(crate) async fn ingredient_recipes<R: Repository>(
+    svc: Data<IngredientService<R>>,
+    name: Path<String>,
+    Units(units): Units,
+) -> Result<Json<Vec<IngredientRecipeDto>>, IngredientRecipesError> {
+    let recipes = svc.ingredient_recipes(&name).await?;
+    Ok(Json(
+        recipes
+            .into_iter()
+            .map(|recipe| {
+                let mut dto = IngredientRecipeDto::from(recipe);
+                dto.ingredient = dto.ingredient.into_system(units);
+                dto
+            })
+            .collect(),
+    ))
+}
+
+/// Renames an ingredient across all recipes
+pub(crate) async fn rename_ingredient<R: Repository>(
+    svc: Data<IngredientService<R>>,
+    JsonBody(data): JsonBody<RenameIngredientDto>,
+) -> Result<Json<RenamedIngredientDto>, RenameIngredientError> {
+    let recipes_updated = svc.rename_ingredient(&data.from, &data.to).await?;
+    Ok(Json(RenamedIngredientDto { recipes_updated }))
+}
+
+/// Densities used to convert between volume and mass
+pub(crate) async fn list_densities<R: Repository>(
+    svc: Data<IngredientService<R>>,
+) -> Result<Json<Vec<IngredientDensityDto>>, ListDensitiesError> {
+    let densities = svc.list_densities().await?;
+    Ok(Json(
+        densities
+            .into_iter()
+            .map(IngredientDensityDto::from)
+            .collect(),
+    ))
+}
+
+/// Adds or replaces the density of an ingredient
+pub(crate) async fn set_density<R: Repository>(
+    svc: Data<IngredientService<R>>,
+    name: Path<String>,
+    JsonBody(data): JsonBody<SetDensityDto>,
+) -> Result<Json<IngredientDensityDto>, SetDensityError> {
+    let density = svc.set_density(&name, data.grams_per_milliliter).await?;
+    Ok(Json(density.into()))
+}
+
+pub(crate) async fn delete_density<R: Repository>(
+    svc: Data<IngredientService<R>>,
+    name: Path<String>,
+) -> Result<HttpResponse, DeleteDensityError> {
+    svc.delete_density(&name).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route("/ingredients", web::get().to(list_ingredients::<R>))
+        .route(
+            "/ingredients/{name}/recipes",
+            web::get().to(ingredient_recipes::<R>),
+        )
+        .route(
+            "/ingredients/rename",
+            web::post().to(rename_ingredient::<R>),
+        )
+        .route("/ingredients/densities", web::get().to(list_densities::<R>))
+        .route(
+            "/ingredients/densities/{name}",
+            web::put().to(set_density::<R>),
+        )
+        .route(
+            "/ingredients/densities/{name}",
+            web::delete().to(delete_density::<R>),
+        );
+}
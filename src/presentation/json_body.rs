@@ -0,0 +1,365 @@
+use std::{future::Future, pin::Pin};
+
+use actix_web::{
+    FromRequest, HttpMessage, HttpRequest, HttpResponse, ResponseError,
+    dev::Payload,
+    http::StatusCode,
+    web::{self, Bytes},
+};
+use serde::{Serialize, de::DeserializeOwned};
+use thiserror::Error;
+
+/// Largest JSON body accepted, the same limit `web::Json` has
+const MAX_JSON_SIZE: usize = 2 * 1024 * 1024;
+
+/// Extracts a JSON request body like `web::Json`, but answers bodies which don't match `T` with a
+/// [`JsonErrorDto`] pointing at the offending field instead of a terse message
+#[derive(Debug)]
+pub(crate) struct JsonBody<T>(pub(crate) T);
+
+/// Explains why a JSON body was rejected
+#[derive(Debug, Serialize)]
+pub(crate) struct JsonErrorDto {
+    pub(crate) error: String,
+    /// Field the error is about, like `ingredients[1].quantity_type`. Missing when the error is
+    /// about the body as a whole.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) path: Option<String>,
+    /// What the field should have been, like `u32` or ``one of `Breakfast`, `Lunch`, `Dinner` ``
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) expected: Option<String>,
+    /// Values the field can take, for fields holding an enum
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) allowed_values: Vec<String>,
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum JsonBodyError {
+    #[error("Expected a body with content type `application/json`")]
+    ContentType,
+    #[error("The body is larger than {MAX_JSON_SIZE} bytes")]
+    Overflow,
+    #[error("Failed to read the body: {0}")]
+    Payload(actix_web::Error),
+    #[error("{}", .0.error)]
+    Invalid(JsonErrorDto),
+}
+
+impl ResponseError for JsonBodyError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            JsonBodyError::ContentType => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            JsonBodyError::Overflow => StatusCode::PAYLOAD_TOO_LARGE,
+            JsonBodyError::Payload(_) | JsonBodyError::Invalid(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            JsonBodyError::Invalid(dto) => HttpResponse::build(self.status_code()).json(dto),
+            _ => HttpResponse::build(self.status_code()).body(self.to_string()),
+        }
+    }
+}
+
+impl<T: DeserializeOwned + 'static> FromRequest for JsonBody<T> {
+    type Error = JsonBodyError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let is_json = matches!(
+            req.mime_type(),
+            Ok(Some(mime)) if mime.subtype() == "json" || mime.suffix().is_some_and(|suffix| suffix == "json")
+        );
+        let payload = web::Payload::from_request(req, payload);
+
+        Box::pin(async move {
+            if !is_json {
+                return Err(JsonBodyError::ContentType);
+            }
+
+            let body = payload
+                .await
+                .map_err(JsonBodyError::Payload)?
+                .to_bytes_limited(MAX_JSON_SIZE)
+                .await
+                .map_err(|_| JsonBodyError::Overflow)?
+                .map_err(JsonBodyError::Payload)?;
+
+            parse(&body).map(Self)
+        })
+    }
+}
+
+fn parse<T: DeserializeOwned>(body: &Bytes) -> Result<T, JsonBodyError> {
+    serde_json::from_slice(body).map_err(|error| JsonBodyError::Invalid(explain(body, &error)))
+}
+
+fn explain(body: &[u8], error: &serde_json::Error) -> JsonErrorDto {
+    let position = format!(" at line {} column {}", error.line(), error.column());
+    let message = error.to_string();
+    let message = message
+        .strip_suffix(&position)
+        .unwrap_or(&message)
+        .to_string();
+
+    let missing_field = message
+        .strip_prefix("missing field ")
+        .and_then(|field| backticked(field).into_iter().next());
+    // Syntax errors are about the body as a whole rather than a field
+    let path = error
+        .is_data()
+        .then(|| path_at(body, error.line(), error.column(), missing_field))
+        .filter(|path| !path.is_empty());
+
+    let expected = message
+        .rsplit_once(", expected ")
+        .map(|(_, expected)| expected.to_string());
+    let allowed_values = match &expected {
+        Some(expected) if message.starts_with("unknown variant ") => backticked(expected),
+        _ => Vec::new(),
+    };
+
+    JsonErrorDto {
+        error: message,
+        path: path.map(|path| format_path(&path)),
+        expected,
+        allowed_values,
+        line: error.line(),
+        column: error.column(),
+    }
+}
+
+/// Values between backticks, like `Gram` and `Liter` in ``one of `Gram`, `Liter` ``
+fn backticked(value: &str) -> Vec<String> {
+    value
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .map(str::to_string)
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+fn format_path(path: &[PathSegment]) -> String {
+    let mut formatted = String::new();
+
+    for segment in path {
+        match segment {
+            PathSegment::Key(key) if formatted.is_empty() => formatted.push_str(key),
+            PathSegment::Key(key) => {
+                formatted.push('.');
+                formatted.push_str(key);
+            }
+            PathSegment::Index(index) => formatted.push_str(&format!("[{index}]")),
+        }
+    }
+
+    formatted
+}
+
+/// Works out which field the parser was in at the given position, which serde reports right
+/// after the character it stopped at. That character is left out so a closing bracket doesn't
+/// leave the object the error is about.
+fn path_at(
+    body: &[u8],
+    line: usize,
+    column: usize,
+    missing_field: Option<String>,
+) -> Vec<PathSegment> {
+    let line_start = body
+        .split_inclusive(|byte| *byte == b'\n')
+        .take(line.saturating_sub(1))
+        .map(<[u8]>::len)
+        .sum::<usize>();
+    let end = (line_start + column).saturating_sub(1).min(body.len());
+
+    enum Frame {
+        Object { key: Option<String>, in_key: bool },
+        Array { index: usize },
+    }
+
+    let mut frames = Vec::new();
+    let mut bytes = body[..end].iter().copied();
+
+    while let Some(byte) = bytes.next() {
+        match byte {
+            b'{' => frames.push(Frame::Object {
+                key: None,
+                in_key: true,
+            }),
+            b'[' => frames.push(Frame::Array { index: 0 }),
+            b'}' | b']' => {
+                frames.pop();
+            }
+            b':' => {
+                if let Some(Frame::Object { in_key, .. }) = frames.last_mut() {
+                    *in_key = false;
+                }
+            }
+            b',' => match frames.last_mut() {
+                Some(Frame::Object { key, in_key }) => {
+                    *key = None;
+                    *in_key = true;
+                }
+                Some(Frame::Array { index }) => *index += 1,
+                None => {}
+            },
+            b'"' => {
+                let mut string = Vec::new();
+                while let Some(byte) = bytes.next() {
+                    match byte {
+                        b'"' => break,
+                        b'\\' => string.extend(bytes.next()),
+                        byte => string.push(byte),
+                    }
+                }
+
+                if let Some(Frame::Object { key, in_key: true }) = frames.last_mut() {
+                    *key = Some(String::from_utf8_lossy(&string).into_owned());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Missing fields are reported at the end of their object, past the last key in it
+    if let (Some(field), Some(Frame::Object { key, .. })) = (missing_field, frames.last_mut()) {
+        *key = Some(field);
+    }
+
+    frames
+        .into_iter()
+        .filter_map(|frame| match frame {
+            Frame::Object { key, .. } => key.map(PathSegment::Key),
+            Frame::Array { index } => Some(PathSegment::Index(index)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{
+        App,
+        test::{TestRequest, call_service, init_service, read_body_json},
+        web,
+    };
+    use assert2::{check, let_assert};
+    use serde::Deserialize;
+
+    use crate::presentation::recipe::NewRecipeDto;
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    enum Unit {
+        Gram,
+        Liter,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Ingredient {
+        name: String,
+        unit: Unit,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Recipe {
+        name: String,
+        minutes: Option<u32>,
+        ingredients: Vec<Ingredient>,
+    }
+
+    fn explain_body(body: &str) -> JsonErrorDto {
+        let_assert!(
+            Err(JsonBodyError::Invalid(dto)) = parse::<Recipe>(&Bytes::from(body.to_string()))
+        );
+        dto
+    }
+
+    #[test]
+    fn it_lists_the_allowed_values_of_an_enum() {
+        let dto = explain_body(
+            r#"{"name": "Soup", "ingredients": [{"name": "Salt", "unit": "Gram"}, {"name": "Water", "unit": "Litre"}]}"#,
+        );
+
+        check!(dto.path.as_deref() == Some("ingredients[1].unit"));
+        check!(dto.expected.as_deref() == Some("`Gram` or `Liter`"));
+        check!(dto.allowed_values == ["Gram", "Liter"]);
+    }
+
+    #[test]
+    fn it_names_the_expected_type() {
+        let dto = explain_body(
+            "{\n  \"name\": \"Soup\",\n  \"minutes\": \"twenty\",\n  \"ingredients\": []\n}",
+        );
+
+        check!(dto.path.as_deref() == Some("minutes"));
+        check!(dto.expected.as_deref() == Some("u32"));
+        check!(dto.allowed_values.is_empty());
+        check!(dto.line == 3);
+        check!(dto.error == r#"invalid type: string "twenty", expected u32"#);
+    }
+
+    #[test]
+    fn it_points_at_missing_fields() {
+        let dto = explain_body(r#"{"name": "Soup", "ingredients": [{"unit": "Gram"}]}"#);
+
+        check!(dto.path.as_deref() == Some("ingredients[0].name"));
+        check!(dto.error == "missing field `name`");
+    }
+
+    #[test]
+    fn it_leaves_the_path_out_for_broken_json() {
+        let dto = explain_body(r#"{"name": "Soup""#);
+
+        check!(dto.path.is_none());
+        check!(dto.error == "EOF while parsing an object");
+    }
+
+    async fn echo(JsonBody(recipe): JsonBody<NewRecipeDto>) -> String {
+        recipe.name
+    }
+
+    #[actix_web::test]
+    async fn it_answers_with_a_structured_error() {
+        let app = init_service(App::new().route("/", web::post().to(echo))).await;
+
+        let request = TestRequest::post()
+            .set_json(serde_json::json!({
+                "name": "Pancakes",
+                "description": null,
+                "ingredients": [],
+                "meal_type": "Brunch",
+            }))
+            .to_request();
+        let response = call_service(&app, request).await;
+        check!(response.status() == StatusCode::BAD_REQUEST);
+
+        let body: serde_json::Value = read_body_json(response).await;
+        check!(body["path"] == "meal_type");
+        check!(body["allowed_values"] == serde_json::json!(["Breakfast", "Lunch", "Dinner"]));
+    }
+
+    #[actix_web::test]
+    async fn it_requires_a_json_content_type() {
+        let app = init_service(App::new().route("/", web::post().to(echo))).await;
+
+        let request = TestRequest::post()
+            .set_payload(r#"{"name": "Soup", "ingredients": []}"#)
+            .to_request();
+        let response = call_service(&app, request).await;
+
+        check!(response.status() == StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    }
+}
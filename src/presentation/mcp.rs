@@ -0,0 +1,446 @@
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use uuid::Uuid;
+
+use crate::{
+    RecipeService,
+    core::recipe::SearchCriteria,
+    persistance::Repository,
+    presentation::recipe::{IngredientMatchDto, MealType, NewRecipeDto, RecipeDto},
+};
+
+const PROTOCOL_VERSION: &str = "2024-11-05";
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+const DEFAULT_SEARCH_BY_INGREDIENTS_LIMIT: u32 = 10;
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    /// Missing for notifications, which never get a response
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcResponse {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolCall {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchRecipesArguments {
+    recipe_name: Option<String>,
+    ingredient_name: Option<String>,
+    meal_type: Option<MealType>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchByIngredientsArguments {
+    ingredients: Vec<String>,
+    limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GetRecipeArguments {
+    /// Either the sequential id or the public id of the recipe
+    recipe_id: RecipeIdArgument,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RecipeIdArgument {
+    RecipeId(i32),
+    PublicId(Uuid),
+}
+
+/// Minimal [Model Context Protocol](https://modelcontextprotocol.io) server, exposing recipe
+/// search, retrieval and creation as tools to LLM assistants. Answers requests read line by line
+/// from stdin until it is closed.
+pub(crate) async fn serve_stdio<R: Repository>(svc: RecipeService<R>) -> eyre::Result<()> {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdout = tokio::io::stdout();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(response) = handle_message(&svc, &line).await {
+            stdout.write_all(response.as_bytes()).await?;
+            stdout.write_all(b"\n").await?;
+            stdout.flush().await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Handles a single JSON-RPC message, returning the serialized response if one is expected
+async fn handle_message<R: Repository>(svc: &RecipeService<R>, message: &str) -> Option<String> {
+    let request = match serde_json::from_str::<RpcRequest>(message) {
+        Ok(request) => request,
+        Err(error) => {
+            return Some(error_response(
+                Value::Null,
+                PARSE_ERROR,
+                format!("Invalid request: {error}"),
+            ));
+        }
+    };
+
+    let id = request.id?;
+
+    let result = match request.method.as_str() {
+        "initialize" => Ok(json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": {} },
+            "serverInfo": {
+                "name": env!("CARGO_PKG_NAME"),
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools() })),
+        "tools/call" => match serde_json::from_value::<ToolCall>(request.params) {
+            Ok(call) => call_tool(svc, call).await,
+            Err(error) => Err((INVALID_PARAMS, format!("Invalid tool call: {error}"))),
+        },
+        method => Err((METHOD_NOT_FOUND, format!("Unknown method `{method}`"))),
+    };
+
+    Some(match result {
+        Ok(result) => serialize(RpcResponse {
+            jsonrpc: "2.0",
+            id,
+            result: Some(result),
+            error: None,
+        }),
+        Err((code, message)) => error_response(id, code, message),
+    })
+}
+
+fn error_response(id: Value, code: i64, message: String) -> String {
+    serialize(RpcResponse {
+        jsonrpc: "2.0",
+        id,
+        result: None,
+        error: Some(RpcError { code, message }),
+    })
+}
+
+fn serialize(response: RpcResponse) -> String {
+    serde_json::to_string(&response).expect("Responses always serialize")
+}
+
+fn tools() -> Value {
+    let meal_type = json!({ "type": "string", "enum": ["Breakfast", "Lunch", "Dinner"] });
+
+    json!([
+        {
+            "name": "search_recipes",
+            "description": "Searches recipes by (part of) their name, an ingredient or meal type",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "recipe_name": { "type": "string" },
+                    "ingredient_name": { "type": "string" },
+                    "meal_type": meal_type,
+                },
+            },
+        },
+        {
+            "name": "search_by_ingredients",
+            "description": "Finds the recipes which can be cooked with the ingredients at hand, the recipes missing the fewest ingredients first",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "ingredients": { "type": "array", "items": { "type": "string" } },
+                    "limit": { "type": "integer", "minimum": 1 },
+                },
+                "required": ["ingredients"],
+            },
+        },
+        {
+            "name": "get_recipe",
+            "description": "Gets a recipe with its ingredients and description",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "recipe_id": {
+                        "description": "Id or public id of the recipe",
+                        "type": ["integer", "string"],
+                    },
+                },
+                "required": ["recipe_id"],
+            },
+        },
+        {
+            "name": "create_recipe",
+            "description": "Saves a new recipe",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string" },
+                    "description": { "type": "string" },
+                    "ingredients": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "name": { "type": "string" },
+                                "quantity_type": {
+                                    "type": "string",
+                                    "enum": [
+                                        "Count", "Kilo", "Gram", "Liter", "Milliliter", "Ounce",
+                                        "Pound", "FluidOunce", "Cup", "Tablespoon", "Teaspoon",
+                                    ],
+                                },
+                                "quantity": { "type": "number" },
+                            },
+                            "required": ["name", "quantity_type", "quantity"],
+                        },
+                    },
+                    "cooking_time_minutes": { "type": "integer", "minimum": 0 },
+                    "meal_type": meal_type,
+                },
+                "required": ["name", "ingredients", "meal_type"],
+            },
+        },
+    ])
+}
+
+/// Runs a tool. Failures of the tool itself are reported in the result, so the assistant can
+/// read them, only malformed calls are protocol errors.
+async fn call_tool<R: Repository>(
+    svc: &RecipeService<R>,
+    call: ToolCall,
+) -> Result<Value, (i64, String)> {
+    let output = match call.name.as_str() {
+        "search_recipes" => {
+            let arguments = arguments::<SearchRecipesArguments>(call.arguments)?;
+            svc.search_recipes(SearchCriteria {
+                recipe_name: arguments.recipe_name,
+                ingredient_name: arguments.ingredient_name,
+                meal_type: arguments.meal_type.map(|mt| mt.into()),
+                include_ingredients: false,
+                archived: false,
+                suggest: false,
+                tags: vec![],
+                sort: vec![],
+                created_after: None,
+                created_before: None,
+                updated_after: None,
+                keywords: None,
+            })
+            .await
+            .map(|results| {
+                json!(
+                    results
+                        .recipes
+                        .into_iter()
+                        .map(RecipeDto::from)
+                        .collect::<Vec<_>>()
+                )
+            })
+            .map_err(|error| error.to_string())
+        }
+        "search_by_ingredients" => {
+            let arguments = arguments::<SearchByIngredientsArguments>(call.arguments)?;
+            svc.search_by_ingredients(
+                arguments.ingredients,
+                arguments
+                    .limit
+                    .unwrap_or(DEFAULT_SEARCH_BY_INGREDIENTS_LIMIT),
+            )
+            .await
+            .map(|matches| {
+                json!(
+                    matches
+                        .into_iter()
+                        .map(IngredientMatchDto::from)
+                        .collect::<Vec<_>>()
+                )
+            })
+            .map_err(|error| error.to_string())
+        }
+        "get_recipe" => {
+            let arguments = arguments::<GetRecipeArguments>(call.arguments)?;
+            get_recipe(svc, arguments.recipe_id).await
+        }
+        "create_recipe" => {
+            let arguments = arguments::<NewRecipeDto>(call.arguments)?;
+            svc.create_recipe(arguments.into())
+                .await
+                .map(|recipe| json!(RecipeDto::from(recipe)))
+                .map_err(|error| error.to_string())
+        }
+        name => return Err((INVALID_PARAMS, format!("Unknown tool `{name}`"))),
+    };
+
+    Ok(match output {
+        Ok(output) => json!({
+            "content": [{ "type": "text", "text": output.to_string() }],
+            "isError": false,
+        }),
+        Err(error) => json!({
+            "content": [{ "type": "text", "text": error }],
+            "isError": true,
+        }),
+    })
+}
+
+async fn get_recipe<R: Repository>(
+    svc: &RecipeService<R>,
+    recipe_id: RecipeIdArgument,
+) -> Result<Value, String> {
+    let recipe_id = match recipe_id {
+        RecipeIdArgument::RecipeId(recipe_id) => recipe_id,
+        RecipeIdArgument::PublicId(public_id) => svc
+            .resolve_public_id(public_id)
+            .await
+            .map_err(|error| error.to_string())?,
+    };
+
+    svc.get_recipe(recipe_id)
+        .await
+        .map(|recipe| json!(RecipeDto::from(recipe)))
+        .map_err(|error| error.to_string())
+}
+
+fn arguments<T: DeserializeOwned>(arguments: Value) -> Result<T, (i64, String)> {
+    serde_json::from_value(arguments)
+        .map_err(|error| (INVALID_PARAMS, format!("Invalid arguments: {error}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+    use sqlx::PgPool;
+
+    use crate::Postgres;
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    async fn call(svc: &RecipeService, message: Value) -> Value {
+        let_assert!(Some(response) = handle_message(svc, &message.to_string()).await);
+        let_assert!(Ok(response) = serde_json::from_str(&response));
+        response
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_lists_the_tools(pool: PgPool) {
+        let svc = RecipeService::new(Postgres::new(pool), None);
+
+        let response = call(
+            &svc,
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" }),
+        )
+        .await;
+
+        let_assert!(Some(tools) = response["result"]["tools"].as_array());
+        check!(tools.len() == 4);
+        check!(tools[0]["name"] == "search_recipes");
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_creates_and_gets_recipes(pool: PgPool) {
+        let svc = RecipeService::new(Postgres::new(pool), None);
+
+        let response = call(
+            &svc,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": {
+                    "name": "create_recipe",
+                    "arguments": {
+                        "name": "Pancakes",
+                        "ingredients": [{ "name": "Flour", "quantity_type": "Gram", "quantity": 250.0 }],
+                        "meal_type": "Breakfast",
+                    },
+                },
+            }),
+        )
+        .await;
+        check!(response["result"]["isError"] == false);
+
+        let response = call(
+            &svc,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "tools/call",
+                "params": { "name": "search_by_ingredients", "arguments": { "ingredients": ["flour"] } },
+            }),
+        )
+        .await;
+
+        let_assert!(Some(text) = response["result"]["content"][0]["text"].as_str());
+        let_assert!(Ok(matches) = serde_json::from_str::<Value>(text));
+        check!(matches[0]["name"] == "Pancakes");
+        check!(matches[0]["missing_ingredients"] == 0);
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_reports_tool_failures_in_the_result(pool: PgPool) {
+        let svc = RecipeService::new(Postgres::new(pool), None);
+
+        let response = call(
+            &svc,
+            json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "tools/call",
+                "params": { "name": "get_recipe", "arguments": { "recipe_id": 42 } },
+            }),
+        )
+        .await;
+
+        check!(response["result"]["isError"] == true);
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_ignores_notifications_and_rejects_unknown_methods(pool: PgPool) {
+        let svc = RecipeService::new(Postgres::new(pool), None);
+
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        check!(
+            handle_message(&svc, &notification.to_string())
+                .await
+                .is_none()
+        );
+
+        let response = call(
+            &svc,
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "resources/list" }),
+        )
+        .await;
+        check!(response["error"]["code"] == METHOD_NOT_FOUND);
+    }
+}
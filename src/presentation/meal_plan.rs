@@ -0,0 +1,202 @@
+use std::time::Duration;
+
+use actix_web::{
+    ResponseError,
+    http::StatusCode,
+    web::{self, Data, Json, ServiceConfig},
+};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    MealPlanService,
+    core::meal_plan::{PlanConstraints, PlannedMeal},
+    persistance::Repository,
+    presentation::{
+        json_body::JsonBody,
+        recipe::{MealType, RecipeNameDto},
+    },
+};
+
+const DEFAULT_PLAN_DAYS: u32 = 7;
+
+fn default_plan_days() -> u32 {
+    DEFAULT_PLAN_DAYS
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct PlanConstraintsDto {
+    /// First day of the plan, defaults to today
+    start: Option<NaiveDate>,
+    #[serde(default = "default_plan_days")]
+    days: u32,
+    /// Meals to plan every day
+    meal_types: Vec<MealType>,
+    /// Upper bound for the cooking time from Monday to Friday, like `30m` or `PT30M`
+    #[serde(with = "super::duration::option", default)]
+    max_weekday_cooking_time: Option<Duration>,
+    /// Only recipes with every one of these tags, for dietary restrictions like `vegetarian`
+    #[serde(default)]
+    tags: Vec<String>,
+    /// Days to wait before planning a recipe again, counting from when it was last cooked too
+    #[serde(default)]
+    avoid_repeats_days: u32,
+}
+
+impl From<PlanConstraintsDto> for PlanConstraints {
+    fn from(value: PlanConstraintsDto) -> Self {
+        Self {
+            start: value.start,
+            days: value.days,
+            meal_types: value.meal_types.into_iter().map(Into::into).collect(),
+            max_weekday_cooking_time: value.max_weekday_cooking_time,
+            tags: value.tags,
+            avoid_repeats_days: value.avoid_repeats_days,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct PlannedMealDto {
+    pub(crate) meal_type: MealType,
+    /// Missing when no recipe fits the constraints
+    pub(crate) recipe: Option<RecipeNameDto>,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct PlannedDayDto {
+    pub(crate) date: NaiveDate,
+    pub(crate) meals: Vec<PlannedMealDto>,
+}
+
+/// Groups the meals by day, meals are expected to be ordered by day
+fn group_by_day(meals: Vec<PlannedMeal>) -> Vec<PlannedDayDto> {
+    let mut days = Vec::<PlannedDayDto>::new();
+
+    for meal in meals {
+        let dto = PlannedMealDto {
+            meal_type: meal.meal_type.into(),
+            recipe: meal.recipe.map(RecipeNameDto::from),
+        };
+
+        match days.last_mut() {
+            Some(day) if day.date == meal.date => day.meals.push(dto),
+            _ => days.push(PlannedDayDto {
+                date: meal.date,
+                meals: vec![dto],
+            }),
+        }
+    }
+
+    days
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum GeneratePlanError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error(
+        "A plan covers between 1 and {} days",
+        crate::core::meal_plan::MAX_PLAN_DAYS
+    )]
+    InvalidDays,
+    #[error("At least one meal type is required")]
+    NoMealTypes,
+}
+
+impl From<crate::core::meal_plan::GeneratePlanError> for GeneratePlanError {
+    fn from(value: crate::core::meal_plan::GeneratePlanError) -> Self {
+        match value {
+            crate::core::meal_plan::GeneratePlanError::Unknown(report) => Self::Unknown(report),
+            crate::core::meal_plan::GeneratePlanError::InvalidDays => Self::InvalidDays,
+            crate::core::meal_plan::GeneratePlanError::NoMealTypes => Self::NoMealTypes,
+        }
+    }
+}
+
+impl ResponseError for GeneratePlanError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            GeneratePlanError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            GeneratePlanError::InvalidDays | GeneratePlanError::NoMealTypes => {
+                StatusCode::BAD_REQUEST
+            }
+        }
+    }
+}
+
+/// Fills the requested days with recipes, the plan is not stored
+pub(crate) async fn generate_plan<R: Repository>(
+    svc: Data<MealPlanService<R>>,
+    JsonBody(constraints): JsonBody<PlanConstraintsDto>,
+) -> Result<Json<Vec<PlannedDayDto>>, GeneratePlanError> {
+    let meals = svc.generate(constraints.into()).await?;
+    Ok(Json(group_by_day(meals)))
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route("/meal-plans/generate", web::post().to(generate_plan::<R>));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{App, test};
+    use assert2::check;
+
+    use crate::test_utils::{MockRepository, recipe_entity};
+
+    #[actix_web::test]
+    async fn it_groups_the_plan_by_day() {
+        let repository = MockRepository::new();
+        repository.on_search_recipes(Ok(vec![recipe_entity(1, "Porridge")]));
+        repository.on_search_recipes(Ok(vec![]));
+
+        let app = test::init_service(
+            App::new()
+                .configure(configure::<MockRepository>)
+                .app_data(Data::new(MealPlanService::new(repository))),
+        )
+        .await;
+
+        let request = test::TestRequest::post()
+            .uri("/meal-plans/generate")
+            .set_json(serde_json::json!({
+                "start": "2025-10-20",
+                "days": 2,
+                "meal_types": ["Breakfast", "Dinner"],
+            }))
+            .to_request();
+        let plan: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+
+        check!(plan.as_array().map(Vec::len) == Some(2));
+        check!(plan[1]["date"] == "2025-10-21");
+        check!(plan[0]["meals"][0]["meal_type"] == "Breakfast");
+        check!(plan[0]["meals"][0]["recipe"]["name"] == "Porridge");
+        check!(plan[0]["meals"][1]["recipe"].is_null());
+    }
+
+    #[actix_web::test]
+    async fn it_rejects_plans_without_meal_types() {
+        let app = test::init_service(
+            App::new()
+                .configure(configure::<MockRepository>)
+                .app_data(Data::new(MealPlanService::new(MockRepository::new()))),
+        )
+        .await;
+
+        let request = test::TestRequest::post()
+            .uri("/meal-plans/generate")
+            .set_json(serde_json::json!({ "meal_types": [] }))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+
+        check!(response.status() == StatusCode::BAD_REQUEST);
+    }
+}
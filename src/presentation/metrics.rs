@@ -0,0 +1,124 @@
+use std::time::Instant;
+
+use actix_web::{
+    Error,
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    middleware::Next,
+    web::{self, Data, Json, ServiceConfig},
+};
+use serde::Serialize;
+
+use crate::persistance::metrics::{Latencies, LatencySummary};
+
+/// Latencies served on `/metrics`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Metrics {
+    /// Per route, from receiving the request until the response is ready to be sent
+    pub(crate) routes: Latencies,
+    /// Per repository method
+    pub(crate) queries: Latencies,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct LatencyDto {
+    pub(crate) operation: String,
+    pub(crate) count: u64,
+    pub(crate) p50_ms: f64,
+    pub(crate) p95_ms: f64,
+    pub(crate) p99_ms: f64,
+}
+
+impl From<LatencySummary> for LatencyDto {
+    fn from(value: LatencySummary) -> Self {
+        Self {
+            operation: value.operation,
+            count: value.count,
+            p50_ms: value.p50.as_secs_f64() * 1000.0,
+            p95_ms: value.p95.as_secs_f64() * 1000.0,
+            p99_ms: value.p99.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct MetricsDto {
+    pub(crate) routes: Vec<LatencyDto>,
+    pub(crate) queries: Vec<LatencyDto>,
+}
+
+/// Middleware recording how long requests take per route, like `GET /recipes/{id}`, in the
+/// registered [`Metrics`]. Requests which didn't match a route, like static files, are skipped.
+pub(crate) async fn record_route_latency(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let metrics = req.app_data::<Data<Metrics>>().cloned();
+    let started = Instant::now();
+    let res = next.call(req).await?;
+
+    if let Some(metrics) = metrics
+        && let Some(pattern) = res.request().match_pattern()
+    {
+        let route = format!("{} {pattern}", res.request().method());
+        metrics.routes.record(&route, started.elapsed());
+    }
+
+    Ok(res)
+}
+
+/// Latency percentiles per route and per repository method
+pub(crate) async fn metrics(metrics: Data<Metrics>) -> Json<MetricsDto> {
+    Json(MetricsDto {
+        routes: metrics
+            .routes
+            .summaries()
+            .into_iter()
+            .map(LatencyDto::from)
+            .collect(),
+        queries: metrics
+            .queries
+            .summaries()
+            .into_iter()
+            .map(LatencyDto::from)
+            .collect(),
+    })
+}
+
+pub(crate) fn configure(cfg: &mut ServiceConfig) {
+    cfg.route("/metrics", web::get().to(metrics));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{App, HttpResponse, middleware::from_fn, test};
+    use assert2::{check, let_assert};
+
+    async fn ok() -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    #[actix_web::test]
+    async fn it_records_latencies_by_route_pattern() {
+        let metrics = Metrics::default();
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(record_route_latency))
+                .app_data(Data::new(metrics.clone()))
+                .route("/recipes/{id}", web::get().to(ok)),
+        )
+        .await;
+
+        for uri in ["/recipes/1", "/recipes/2", "/unknown"] {
+            let request = test::TestRequest::get().uri(uri).to_request();
+            test::call_service(&app, request).await;
+        }
+
+        let summaries = metrics.routes.summaries();
+        let_assert!([route] = summaries.as_slice());
+        check!(route.operation == "GET /recipes/{id}");
+        check!(route.count == 2);
+    }
+}
@@ -1 +1,34 @@
+pub(crate) mod admin;
+pub(crate) mod analytics;
+pub(crate) mod captcha;
+pub(crate) mod circuit_breaker;
+pub(crate) mod cooking_log;
+pub(crate) mod data_quality;
+pub(crate) mod deletion;
+pub(crate) mod duplicate;
+pub(crate) mod duration;
+pub(crate) mod export;
+pub(crate) mod federation;
+pub(crate) mod fork;
+pub(crate) mod frontend;
+pub(crate) mod generate;
+pub(crate) mod import;
+pub(crate) mod ingredient;
+pub(crate) mod json_body;
+pub(crate) mod mcp;
+pub(crate) mod meal_plan;
+pub(crate) mod metrics;
+pub(crate) mod normalize_path;
+pub(crate) mod print;
+pub(crate) mod qr_code;
 pub(crate) mod recipe;
+pub(crate) mod recipe_id;
+pub(crate) mod retention;
+pub(crate) mod saved_search;
+pub(crate) mod share;
+pub(crate) mod share_link;
+pub(crate) mod snapshot;
+pub(crate) mod sync;
+pub(crate) mod tag;
+pub(crate) mod timer;
+pub(crate) mod unit_system;
@@ -0,0 +1,107 @@
+use actix_web::{
+    Error, HttpResponse,
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::{Method, Uri, header},
+    middleware::Next,
+};
+
+/// Path with repeated slashes merged and the trailing slash removed, like `/recipes` for
+/// `//recipes/`. The root path is kept as it is.
+fn normalize(path: &str) -> String {
+    let mut normalized = String::with_capacity(path.len());
+
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        normalized.push('/');
+        normalized.push_str(segment);
+    }
+
+    if normalized.is_empty() {
+        normalized.push('/');
+    }
+
+    normalized
+}
+
+/// Middleware normalizing paths before they are routed, so `/recipes/` and `/recipes//1` reach the
+/// same routes as `/recipes` and `/recipes/1`. Reads are redirected permanently to the normalized
+/// path, so clients and caches learn the canonical url. Writes are rewritten in place instead, as
+/// clients don't reliably resend their body after a redirect.
+pub(crate) async fn normalize_path(
+    mut req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    let normalized = normalize(req.path());
+
+    if normalized == req.path() {
+        return Ok(next.call(req).await?.map_into_left_body());
+    }
+
+    let path_and_query = match req.query_string() {
+        "" => normalized,
+        query => format!("{normalized}?{query}"),
+    };
+
+    if matches!(*req.method(), Method::GET | Method::HEAD) {
+        let response = HttpResponse::PermanentRedirect()
+            .insert_header((header::LOCATION, path_and_query))
+            .finish();
+        return Ok(req.into_response(response).map_into_right_body());
+    }
+
+    let mut parts = req.uri().clone().into_parts();
+    parts.path_and_query = Some(
+        path_and_query
+            .parse()
+            .expect("Normalizing keeps the path valid"),
+    );
+    let uri = Uri::from_parts(parts).expect("Only the path was changed");
+
+    req.match_info_mut().get_mut().update(&uri);
+    req.head_mut().uri = uri;
+
+    Ok(next.call(req).await?.map_into_left_body())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{App, http::StatusCode, middleware::from_fn, test, web};
+    use assert2::{check, let_assert};
+
+    async fn ok() -> HttpResponse {
+        HttpResponse::Ok().finish()
+    }
+
+    #[actix_web::test]
+    async fn it_merges_slashes_and_strips_the_trailing_slash() {
+        check!(normalize("/recipes/") == "/recipes");
+        check!(normalize("//recipes///1") == "/recipes/1");
+        check!(normalize("/") == "/");
+        check!(normalize("//") == "/");
+    }
+
+    #[actix_web::test]
+    async fn it_redirects_reads_and_rewrites_writes() {
+        let app = test::init_service(
+            App::new()
+                .wrap(from_fn(normalize_path))
+                .route("/recipes", web::get().to(ok))
+                .route("/recipes", web::post().to(ok)),
+        )
+        .await;
+
+        let request = test::TestRequest::get()
+            .uri("/recipes/?recipe_name=soup")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        check!(response.status() == StatusCode::PERMANENT_REDIRECT);
+        let_assert!(Some(location) = response.headers().get(header::LOCATION));
+        check!(location == "/recipes?recipe_name=soup");
+
+        let request = test::TestRequest::post().uri("//recipes/").to_request();
+        let response = test::call_service(&app, request).await;
+        check!(response.status() == StatusCode::OK);
+    }
+}
@@ -0,0 +1,164 @@
+use actix_web::{
+    HttpResponse, ResponseError,
+    http::{StatusCode, header::ContentType},
+    web::{self, Data, ServiceConfig},
+};
+use maud::{DOCTYPE, Markup, PreEscaped, html};
+use thiserror::Error;
+
+use crate::{
+    RecipeService,
+    core::recipe::{Recipe, format_cooking_time},
+    persistance::Repository,
+    presentation::recipe_id::RecipeId,
+};
+
+const PRINT_STYLESHEET: &str = r#"
+    body { font-family: Georgia, serif; max-width: 42em; margin: 2em auto; line-height: 1.5; color: #000; }
+    header { display: flex; justify-content: space-between; align-items: flex-start; gap: 2em; }
+    h1 { margin: 0 0 .25em; }
+    .meta { color: #444; margin: 0; }
+    .qr { width: 6em; height: 6em; }
+    h2 { border-bottom: 1px solid #000; padding-bottom: .25em; }
+    ul.ingredients { padding-left: 1.25em; }
+    ul.ingredients li { break-inside: avoid; }
+    @page { margin: 1.5cm; }
+    @media print {
+        body { margin: 0; max-width: none; font-size: 11pt; }
+        a { color: inherit; text-decoration: none; }
+    }
+"#;
+
+#[derive(Debug, Error)]
+pub(crate) enum PrintRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::recipe::GetRecipeError> for PrintRecipeError {
+    fn from(value: crate::core::recipe::GetRecipeError) -> Self {
+        match value {
+            crate::core::recipe::GetRecipeError::Unknown(report) => Self::Unknown(report),
+            crate::core::recipe::GetRecipeError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for PrintRecipeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            PrintRecipeError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            PrintRecipeError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+fn render(recipe: &Recipe) -> Markup {
+    html! {
+        (DOCTYPE)
+        html lang="en" {
+            head {
+                meta charset="utf-8";
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { (recipe.name) }
+                style { (PreEscaped(PRINT_STYLESHEET)) }
+            }
+            body {
+                header {
+                    div {
+                        h1 { (recipe.name) }
+                        p.meta {
+                            (recipe.meal_type)
+                            @if let Some(cooking_time) = recipe.cooking_time {
+                                " · " (format_cooking_time(cooking_time))
+                            }
+                        }
+                    }
+                    img.qr
+                        // Relative, so it keeps working when the API is mounted under a prefix
+                        src="qr.png"
+                        alt="QR code linking to this recipe";
+                }
+                @if let Some(description) = &recipe.description {
+                    p { (description) }
+                }
+                @if let Some(pairing) = &recipe.pairing {
+                    p { strong { "Serve with: " } (pairing) }
+                }
+                @if !recipe.ingredients.is_empty() {
+                    h2 { "Ingredients" }
+                    ul.ingredients {
+                        @for ingredient in &recipe.ingredients {
+                            li { (ingredient) }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub(crate) async fn print_recipe<R: Repository>(
+    svc: Data<RecipeService<R>>,
+    RecipeId(recipe_id): RecipeId,
+) -> Result<HttpResponse, PrintRecipeError> {
+    let recipe = svc.get_recipe(recipe_id).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(render(&recipe).into_string()))
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route(
+        "/recipes/{recipe_id}/print",
+        web::get().to(print_recipe::<R>),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{App, test};
+    use sqlx::PgPool;
+
+    use crate::Postgres;
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_should_render_the_recipe_as_escaped_html(pool: PgPool) {
+        let recipe_id = sqlx::query_scalar!(
+            "INSERT INTO recipe (name, meal_type) VALUES ('Mac & Cheese', 'Dinner') RETURNING recipe_id"
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to insert recipe");
+
+        let app = test::init_service(
+            App::new()
+                .configure(configure::<Postgres>)
+                .app_data(Data::new(RecipeService::new(Postgres::new(pool), None))),
+        )
+        .await;
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/recipes/{recipe_id}/print"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+
+        assert2::check!(response.status() == StatusCode::OK);
+
+        let body = test::read_body(response).await;
+        let body = String::from_utf8_lossy(&body);
+        assert2::check!(body.contains("<h1>Mac &amp; Cheese</h1>"));
+        assert2::check!(body.contains(r#"<img class="qr" src="qr.png""#));
+    }
+}
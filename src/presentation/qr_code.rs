@@ -0,0 +1,138 @@
+use actix_web::{
+    HttpResponse, ResponseError,
+    http::{StatusCode, header::ContentType},
+    web::{self, Data, Query, ServiceConfig},
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{QrCodeService, persistance::Repository, presentation::recipe_id::RecipeId};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct QrCodeQuery {
+    /// Share link token to link to instead of the recipe itself
+    token: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum RecipeQrCodeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+    #[error("The share link could not be found")]
+    ShareLinkNotFound,
+}
+
+impl From<crate::core::qr_code::RecipeQrCodeError> for RecipeQrCodeError {
+    fn from(value: crate::core::qr_code::RecipeQrCodeError) -> Self {
+        match value {
+            crate::core::qr_code::RecipeQrCodeError::Unknown(report) => Self::Unknown(report),
+            crate::core::qr_code::RecipeQrCodeError::NotFound => Self::NotFound,
+            crate::core::qr_code::RecipeQrCodeError::ShareLinkNotFound => Self::ShareLinkNotFound,
+        }
+    }
+}
+
+impl ResponseError for RecipeQrCodeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RecipeQrCodeError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            RecipeQrCodeError::NotFound => StatusCode::NOT_FOUND,
+            RecipeQrCodeError::ShareLinkNotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+pub(crate) async fn recipe_qr_code<R: Repository>(
+    svc: Data<QrCodeService<R>>,
+    RecipeId(recipe_id): RecipeId,
+    Query(query): Query<QrCodeQuery>,
+) -> Result<HttpResponse, RecipeQrCodeError> {
+    let png = svc
+        .recipe_qr_code(recipe_id, query.token.as_deref())
+        .await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::png())
+        .body(png))
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route(
+        "/recipes/{recipe_id}/qr.png",
+        web::get().to(recipe_qr_code::<R>),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{App, test};
+    use sqlx::PgPool;
+
+    use crate::Postgres;
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    macro_rules! setup_app {
+        ($pool:expr) => {{
+            let postgres = Postgres::new($pool);
+            let qr_code_service =
+                QrCodeService::new(postgres.clone(), postgres, "http://recipes.example.com/");
+
+            test::init_service(
+                App::new()
+                    .configure(configure::<Postgres>)
+                    .app_data(Data::new(qr_code_service)),
+            )
+            .await
+        }};
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_should_return_a_png_for_an_existing_recipe(pool: PgPool) {
+        let recipe_id = sqlx::query_scalar!(
+            "INSERT INTO recipe (name, meal_type) VALUES ('Pancakes', 'Breakfast') RETURNING recipe_id"
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to insert recipe");
+
+        let app = setup_app!(pool);
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/recipes/{recipe_id}/qr.png"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+
+        assert2::check!(response.status() == StatusCode::OK);
+
+        let body = test::read_body(response).await;
+        assert2::check!(body.starts_with(b"\x89PNG"));
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_should_return_404_for_an_unknown_share_link(pool: PgPool) {
+        let recipe_id = sqlx::query_scalar!(
+            "INSERT INTO recipe (name, meal_type) VALUES ('Pancakes', 'Breakfast') RETURNING recipe_id"
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to insert recipe");
+
+        let app = setup_app!(pool);
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/recipes/{recipe_id}/qr.png?token=unknown"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+
+        assert2::check!(response.status() == StatusCode::NOT_FOUND);
+    }
+}
@@ -1,17 +1,31 @@
 use std::time::Duration;
 
 use actix_web::{
-    HttpResponse, ResponseError, delete, get,
-    http::header::ContentType,
-    post, put,
-    web::{Data, Json, Path, Query},
+    HttpRequest, HttpResponse, ResponseError,
+    http::{
+        Method, StatusCode,
+        header::{self, ContentType},
+    },
+    web::{self, Data, Json, Query, ServiceConfig},
 };
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize, Serializer, ser::SerializeMap};
 use thiserror::Error;
+use uuid::Uuid;
 
 use crate::{
     RecipeService,
-    core::recipe::{Ingredient, NewRecipe, Recipe, SearchCriteria},
+    core::{
+        deletion::Deletion,
+        recipe::{
+            FieldError, Ingredient, IngredientMatch, MetadataChanges, MetadataOutcome,
+            MetadataUpdate, NewRecipe, Pairing, PopularRecipe, RandomCriteria, Recipe, RecipeName,
+            SearchCriteria, SearchSort, Suggestion, Video,
+        },
+        unit::{Quantity, Unit, UnitSystem},
+    },
+    persistance::Repository,
+    presentation::{json_body::JsonBody, recipe_id::RecipeId, tag::Tags, unit_system::Units},
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -20,12 +34,41 @@ pub(crate) struct RecipeDto {
     pub(crate) name: String,
     pub(crate) description: Option<String>,
     pub(crate) ingredients: Vec<IngredientDto>,
+    #[serde(with = "super::duration::option", default)]
     pub(crate) cooking_time: Option<Duration>,
+    /// Cooking time rounded up to whole minutes, `cooking_time` takes precedence when both are
+    /// given
+    #[serde(default)]
+    pub(crate) cooking_time_minutes: Option<u32>,
     pub(crate) meal_type: MealType,
+    /// Day this recipe was last cooked on, ignored when updating a recipe
+    pub(crate) last_cooked_at: Option<NaiveDate>,
+    #[serde(default)]
+    pub(crate) video_url: Option<String>,
+    /// Title of the video as reported by its provider, ignored when updating a recipe
+    #[serde(default)]
+    pub(crate) video_title: Option<String>,
+    /// Thumbnail of the video as reported by its provider, ignored when updating a recipe
+    #[serde(default)]
+    pub(crate) video_thumbnail_url: Option<String>,
+    #[serde(default)]
+    pub(crate) pairing: Option<PairingDto>,
+    /// Recipe this recipe was forked from, ignored when updating a recipe
+    #[serde(default)]
+    pub(crate) forked_from: Option<i32>,
+    /// Random id for external clients, accepted in place of `recipe_id` by all recipe routes.
+    /// Ignored when updating a recipe.
+    #[serde(default)]
+    pub(crate) public_id: Uuid,
 }
 
 impl From<Recipe> for RecipeDto {
     fn from(value: Recipe) -> Self {
+        let (video_url, video_title, video_thumbnail_url) = match value.video {
+            Some(video) => (Some(video.url), video.title, video.thumbnail_url),
+            None => (None, None, None),
+        };
+
         Self {
             recipe_id: value.recipe_id,
             name: value.name,
@@ -36,7 +79,287 @@ impl From<Recipe> for RecipeDto {
                 .map(IngredientDto::from)
                 .collect(),
             cooking_time: value.cooking_time,
+            cooking_time_minutes: value.cooking_time.map(duration_to_minutes),
+            meal_type: value.meal_type.into(),
+            last_cooked_at: value.last_cooked_at,
+            video_url,
+            video_title,
+            video_thumbnail_url,
+            pairing: value.pairing.map(PairingDto::from),
+            forked_from: value.forked_from,
+            public_id: value.public_id,
+        }
+    }
+}
+
+/// Keeps the video metadata and lineage as given, for recipes received from other instances
+impl From<RecipeDto> for Recipe {
+    fn from(value: RecipeDto) -> Self {
+        Self {
+            recipe_id: value.recipe_id,
+            name: value.name,
+            description: value.description,
+            ingredients: value
+                .ingredients
+                .into_iter()
+                .map(Ingredient::from)
+                .collect(),
+            cooking_time: cooking_time_or_minutes(value.cooking_time, value.cooking_time_minutes),
             meal_type: value.meal_type.into(),
+            last_cooked_at: value.last_cooked_at,
+            video: value.video_url.map(|url| Video {
+                url,
+                title: value.video_title,
+                thumbnail_url: value.video_thumbnail_url,
+            }),
+            pairing: value.pairing.map(Pairing::from),
+            forked_from: value.forked_from,
+            public_id: value.public_id,
+        }
+    }
+}
+
+/// Links to related resources, relative to the root of the instance
+#[derive(Debug, Serialize)]
+pub(crate) struct LinksDto {
+    /// Canonical url of the resource itself
+    #[serde(rename = "self")]
+    pub(crate) self_link: String,
+}
+
+/// Newly created recipe, along with where it can be found from now on
+#[derive(Debug, Serialize)]
+pub(crate) struct CreatedRecipeDto {
+    #[serde(flatten)]
+    pub(crate) recipe: RecipeDto,
+    pub(crate) links: LinksDto,
+}
+
+impl RecipeDto {
+    /// Converts the quantities of the ingredients to the given system
+    pub(crate) fn into_system(mut self, system: UnitSystem) -> Self {
+        self.ingredients = self
+            .ingredients
+            .into_iter()
+            .map(|ingredient| ingredient.into_system(system))
+            .collect();
+        self
+    }
+}
+
+fn duration_to_minutes(duration: Duration) -> u32 {
+    u32::try_from(duration.as_secs().div_ceil(60)).unwrap_or(u32::MAX)
+}
+
+/// Picks the structured cooking time if given, falling back to the plain number of minutes
+fn cooking_time_or_minutes(
+    cooking_time: Option<Duration>,
+    minutes: Option<u32>,
+) -> Option<Duration> {
+    cooking_time.or_else(|| minutes.map(|minutes| Duration::from_secs(u64::from(minutes) * 60)))
+}
+
+/// Field of a [`RecipeDto`] which can be selected with the `fields` query parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RecipeField {
+    RecipeId,
+    Name,
+    Description,
+    Ingredients,
+    CookingTime,
+    CookingTimeMinutes,
+    MealType,
+    LastCookedAt,
+    VideoUrl,
+    VideoTitle,
+    VideoThumbnailUrl,
+    Pairing,
+    ForkedFrom,
+    PublicId,
+}
+
+impl RecipeField {
+    fn parse(field: &str) -> Option<Self> {
+        match field {
+            "recipe_id" => Some(Self::RecipeId),
+            "name" => Some(Self::Name),
+            "description" => Some(Self::Description),
+            "ingredients" => Some(Self::Ingredients),
+            "cooking_time" => Some(Self::CookingTime),
+            "cooking_time_minutes" => Some(Self::CookingTimeMinutes),
+            "meal_type" => Some(Self::MealType),
+            "last_cooked_at" => Some(Self::LastCookedAt),
+            "video_url" => Some(Self::VideoUrl),
+            "video_title" => Some(Self::VideoTitle),
+            "video_thumbnail_url" => Some(Self::VideoThumbnailUrl),
+            "pairing" => Some(Self::Pairing),
+            "forked_from" => Some(Self::ForkedFrom),
+            "public_id" => Some(Self::PublicId),
+            _ => None,
+        }
+    }
+
+    /// Parses a comma separated list of field names, returning the first unknown field on failure
+    fn parse_list(fields: &str) -> Result<Vec<Self>, String> {
+        fields
+            .split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+            .map(|field| Self::parse(field).ok_or_else(|| field.to_string()))
+            .collect()
+    }
+}
+
+/// Parses the `include` query parameter into whether ingredients should be embedded. Ingredients
+/// are embedded when the parameter is missing, `include=` leaves them out. Returns the first
+/// unknown expansion on failure.
+pub(crate) fn parse_include(include: Option<&str>) -> Result<bool, String> {
+    let Some(include) = include else {
+        return Ok(true);
+    };
+
+    let mut include_ingredients = false;
+
+    for expansion in include.split(',').map(str::trim) {
+        match expansion {
+            "" => {}
+            "ingredients" => include_ingredients = true,
+            unknown => return Err(unknown.to_string()),
+        }
+    }
+
+    Ok(include_ingredients)
+}
+
+/// Serialization view over a [`RecipeDto`] which only writes the selected fields. All fields are
+/// written when no selection is given.
+#[derive(Debug)]
+pub(crate) struct RecipeView {
+    recipe: RecipeDto,
+    fields: Option<Vec<RecipeField>>,
+}
+
+impl RecipeView {
+    fn includes(&self, field: RecipeField) -> bool {
+        self.fields
+            .as_ref()
+            .is_none_or(|fields| fields.contains(&field))
+    }
+}
+
+impl Serialize for RecipeView {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let recipe = &self.recipe;
+        let mut map = serializer.serialize_map(None)?;
+
+        if self.includes(RecipeField::RecipeId) {
+            map.serialize_entry("recipe_id", &recipe.recipe_id)?;
+        }
+        if self.includes(RecipeField::Name) {
+            map.serialize_entry("name", &recipe.name)?;
+        }
+        if self.includes(RecipeField::Description) {
+            map.serialize_entry("description", &recipe.description)?;
+        }
+        if self.includes(RecipeField::Ingredients) {
+            map.serialize_entry("ingredients", &recipe.ingredients)?;
+        }
+        if self.includes(RecipeField::CookingTime) {
+            map.serialize_entry(
+                "cooking_time",
+                &recipe.cooking_time.map(super::duration::to_iso8601),
+            )?;
+        }
+        if self.includes(RecipeField::CookingTimeMinutes) {
+            map.serialize_entry("cooking_time_minutes", &recipe.cooking_time_minutes)?;
+        }
+        if self.includes(RecipeField::MealType) {
+            map.serialize_entry("meal_type", &recipe.meal_type)?;
+        }
+        if self.includes(RecipeField::LastCookedAt) {
+            map.serialize_entry("last_cooked_at", &recipe.last_cooked_at)?;
+        }
+        if self.includes(RecipeField::VideoUrl) {
+            map.serialize_entry("video_url", &recipe.video_url)?;
+        }
+        if self.includes(RecipeField::VideoTitle) {
+            map.serialize_entry("video_title", &recipe.video_title)?;
+        }
+        if self.includes(RecipeField::VideoThumbnailUrl) {
+            map.serialize_entry("video_thumbnail_url", &recipe.video_thumbnail_url)?;
+        }
+        if self.includes(RecipeField::Pairing) {
+            map.serialize_entry("pairing", &recipe.pairing)?;
+        }
+        if self.includes(RecipeField::ForkedFrom) {
+            map.serialize_entry("forked_from", &recipe.forked_from)?;
+        }
+        if self.includes(RecipeField::PublicId) {
+            map.serialize_entry("public_id", &recipe.public_id)?;
+        }
+
+        map.end()
+    }
+}
+
+/// Beverage to serve with a recipe, the kind is optional so a pairing can also be plain text
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct PairingDto {
+    #[serde(default)]
+    pub(crate) kind: Option<PairingKind>,
+    pub(crate) description: String,
+}
+
+impl From<Pairing> for PairingDto {
+    fn from(value: Pairing) -> Self {
+        Self {
+            kind: value.kind.map(PairingKind::from),
+            description: value.description,
+        }
+    }
+}
+
+impl From<PairingDto> for Pairing {
+    fn from(value: PairingDto) -> Self {
+        Self {
+            kind: value.kind.map(crate::core::recipe::PairingKind::from),
+            description: value.description,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct PopularRecipeDto {
+    #[serde(flatten)]
+    pub(crate) recipe: RecipeDto,
+    pub(crate) views: i64,
+}
+
+impl From<PopularRecipe> for PopularRecipeDto {
+    fn from(value: PopularRecipe) -> Self {
+        Self {
+            recipe: value.recipe.into(),
+            views: value.views,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct IngredientMatchDto {
+    #[serde(flatten)]
+    pub(crate) recipe: RecipeDto,
+    /// Number of ingredients of the recipe which are available
+    pub(crate) matched_ingredients: i64,
+    /// Number of ingredients of the recipe which are still missing
+    pub(crate) missing_ingredients: i64,
+}
+
+impl From<IngredientMatch> for IngredientMatchDto {
+    fn from(value: IngredientMatch) -> Self {
+        Self {
+            recipe: value.recipe.into(),
+            matched_ingredients: value.matched,
+            missing_ingredients: value.missing,
         }
     }
 }
@@ -52,29 +375,71 @@ impl From<Ingredient> for IngredientDto {
     fn from(value: Ingredient) -> Self {
         Self {
             name: value.name,
-            quantity_type: value.quantity_type.into(),
+            quantity_type: Unit::from(&value.quantity_type).into(),
             quantity: value.quantity,
         }
     }
 }
 
+/// Imperial quantities are converted to metric, which is what recipes are stored in
 impl From<IngredientDto> for Ingredient {
     fn from(value: IngredientDto) -> Self {
+        let (quantity_type, quantity) =
+            Quantity::new(value.quantity_type.into(), value.quantity).to_stored();
+
         Self {
             name: value.name,
-            quantity_type: value.quantity_type.into(),
-            quantity: value.quantity,
+            quantity_type,
+            quantity,
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
+impl IngredientDto {
+    pub(crate) fn into_system(self, system: UnitSystem) -> Self {
+        let quantity = Quantity::new(self.quantity_type.into(), self.quantity).to_system(system);
+
+        Self {
+            name: self.name,
+            quantity_type: quantity.unit.into(),
+            quantity: quantity.amount,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct NewRecipeDto {
     pub(crate) name: String,
     pub(crate) description: Option<String>,
     pub(crate) ingredients: Vec<IngredientDto>,
+    #[serde(with = "super::duration::option", default)]
     pub(crate) cooking_time: Option<Duration>,
+    #[serde(default)]
+    pub(crate) cooking_time_minutes: Option<u32>,
     pub(crate) meal_type: MealType,
+    #[serde(default)]
+    pub(crate) video_url: Option<String>,
+    #[serde(default)]
+    pub(crate) pairing: Option<PairingDto>,
+}
+
+impl From<NewRecipe> for NewRecipeDto {
+    fn from(value: NewRecipe) -> Self {
+        Self {
+            name: value.name,
+            description: value.description,
+            ingredients: value
+                .ingredients
+                .into_iter()
+                .map(IngredientDto::from)
+                .collect(),
+            cooking_time: value.cooking_time,
+            cooking_time_minutes: value.cooking_time.map(duration_to_minutes),
+            meal_type: value.meal_type.into(),
+            video_url: value.video_url,
+            pairing: value.pairing.map(PairingDto::from),
+        }
+    }
 }
 
 impl From<NewRecipeDto> for NewRecipe {
@@ -87,8 +452,10 @@ impl From<NewRecipeDto> for NewRecipe {
                 .into_iter()
                 .map(Ingredient::from)
                 .collect(),
-            cooking_time: value.cooking_time,
+            cooking_time: cooking_time_or_minutes(value.cooking_time, value.cooking_time_minutes),
             meal_type: value.meal_type.into(),
+            video_url: value.video_url,
+            pairing: value.pairing.map(Pairing::from),
         }
     }
 }
@@ -100,21 +467,33 @@ pub(crate) enum QuantityType {
     Gram,
     Liter,
     Milliliter,
+    Ounce,
+    Pound,
+    FluidOunce,
+    Cup,
+    Tablespoon,
+    Teaspoon,
 }
 
-impl From<crate::core::recipe::QuantityType> for QuantityType {
-    fn from(value: crate::core::recipe::QuantityType) -> Self {
+impl From<Unit> for QuantityType {
+    fn from(value: Unit) -> Self {
         match value {
-            crate::core::recipe::QuantityType::Count => Self::Count,
-            crate::core::recipe::QuantityType::Kilo => Self::Kilo,
-            crate::core::recipe::QuantityType::Gram => Self::Gram,
-            crate::core::recipe::QuantityType::Liter => Self::Liter,
-            crate::core::recipe::QuantityType::Milliliter => Self::Milliliter,
+            Unit::Count => Self::Count,
+            Unit::Kilo => Self::Kilo,
+            Unit::Gram => Self::Gram,
+            Unit::Liter => Self::Liter,
+            Unit::Milliliter => Self::Milliliter,
+            Unit::Ounce => Self::Ounce,
+            Unit::Pound => Self::Pound,
+            Unit::FluidOunce => Self::FluidOunce,
+            Unit::Cup => Self::Cup,
+            Unit::Tablespoon => Self::Tablespoon,
+            Unit::Teaspoon => Self::Teaspoon,
         }
     }
 }
 
-impl From<QuantityType> for crate::core::recipe::QuantityType {
+impl From<QuantityType> for Unit {
     fn from(value: QuantityType) -> Self {
         match value {
             QuantityType::Count => Self::Count,
@@ -122,11 +501,17 @@ impl From<QuantityType> for crate::core::recipe::QuantityType {
             QuantityType::Gram => Self::Gram,
             QuantityType::Liter => Self::Liter,
             QuantityType::Milliliter => Self::Milliliter,
+            QuantityType::Ounce => Self::Ounce,
+            QuantityType::Pound => Self::Pound,
+            QuantityType::FluidOunce => Self::FluidOunce,
+            QuantityType::Cup => Self::Cup,
+            QuantityType::Tablespoon => Self::Tablespoon,
+            QuantityType::Teaspoon => Self::Teaspoon,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub(crate) enum MealType {
     Breakfast,
     Lunch,
@@ -153,6 +538,33 @@ impl From<MealType> for crate::core::recipe::MealType {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) enum PairingKind {
+    Wine,
+    Beer,
+    NonAlcoholic,
+}
+
+impl From<crate::core::recipe::PairingKind> for PairingKind {
+    fn from(value: crate::core::recipe::PairingKind) -> Self {
+        match value {
+            crate::core::recipe::PairingKind::Wine => Self::Wine,
+            crate::core::recipe::PairingKind::Beer => Self::Beer,
+            crate::core::recipe::PairingKind::NonAlcoholic => Self::NonAlcoholic,
+        }
+    }
+}
+
+impl From<PairingKind> for crate::core::recipe::PairingKind {
+    fn from(value: PairingKind) -> Self {
+        match value {
+            PairingKind::Wine => Self::Wine,
+            PairingKind::Beer => Self::Beer,
+            PairingKind::NonAlcoholic => Self::NonAlcoholic,
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub(crate) enum ListRecipeError {
     #[error("An unknown error occured: {0:}")]
@@ -161,6 +573,14 @@ pub(crate) enum ListRecipeError {
         #[source]
         eyre::Report,
     ),
+    #[error("Unknown recipe field `{0}`")]
+    InvalidField(String),
+    #[error("Unknown expansion `{0}`")]
+    InvalidInclude(String),
+    #[error("Invalid recipe id `{0}`")]
+    InvalidId(String),
+    #[error("At most {MAX_BATCH_IDS} recipes can be requested at once")]
+    TooManyIds,
 }
 
 impl From<crate::core::recipe::ListRecipeError> for ListRecipeError {
@@ -171,7 +591,54 @@ impl From<crate::core::recipe::ListRecipeError> for ListRecipeError {
     }
 }
 
-impl ResponseError for ListRecipeError {}
+impl From<crate::core::recipe::GetRecipesError> for ListRecipeError {
+    fn from(value: crate::core::recipe::GetRecipesError) -> Self {
+        match value {
+            crate::core::recipe::GetRecipesError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl ResponseError for ListRecipeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ListRecipeError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ListRecipeError::InvalidField(_)
+            | ListRecipeError::InvalidInclude(_)
+            | ListRecipeError::InvalidId(_)
+            | ListRecipeError::TooManyIds => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct FieldErrorDto {
+    pub(crate) field: String,
+    pub(crate) message: String,
+}
+
+impl From<FieldError> for FieldErrorDto {
+    fn from(value: FieldError) -> Self {
+        Self {
+            field: value.field,
+            message: value.message,
+        }
+    }
+}
+
+/// Body of a 422 response, listing every field which was rejected
+#[derive(Debug, Serialize)]
+pub(crate) struct InvalidFieldsDto {
+    pub(crate) error: String,
+    pub(crate) fields: Vec<FieldErrorDto>,
+}
+
+fn invalid_fields_response(error: String, fields: &[FieldError]) -> HttpResponse {
+    HttpResponse::UnprocessableEntity().json(InvalidFieldsDto {
+        error,
+        fields: fields.iter().cloned().map(FieldErrorDto::from).collect(),
+    })
+}
 
 #[derive(Debug, Error)]
 pub(crate) enum CreateRecipeError {
@@ -181,17 +648,46 @@ pub(crate) enum CreateRecipeError {
         #[source]
         eyre::Report,
     ),
+    #[error("`{0}` is not a valid video url")]
+    InvalidVideoUrl(String),
+    #[error("The recipe has invalid fields")]
+    InvalidFields(Vec<FieldError>),
 }
 
 impl From<crate::core::recipe::CreateRecipeError> for CreateRecipeError {
     fn from(value: crate::core::recipe::CreateRecipeError) -> Self {
         match value {
             crate::core::recipe::CreateRecipeError::Unknown(report) => Self::Unknown(report),
+            crate::core::recipe::CreateRecipeError::InvalidVideoUrl(url) => {
+                Self::InvalidVideoUrl(url)
+            }
+            crate::core::recipe::CreateRecipeError::InvalidFields(errors) => {
+                Self::InvalidFields(errors)
+            }
         }
     }
 }
 
-impl ResponseError for CreateRecipeError {}
+impl ResponseError for CreateRecipeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            CreateRecipeError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            CreateRecipeError::InvalidVideoUrl(_) => StatusCode::BAD_REQUEST,
+            CreateRecipeError::InvalidFields(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            CreateRecipeError::InvalidFields(fields) => {
+                invalid_fields_response(self.to_string(), fields)
+            }
+            _ => HttpResponse::build(self.status_code())
+                .content_type(ContentType::plaintext())
+                .body(self.to_string()),
+        }
+    }
+}
 
 #[derive(Debug, Error)]
 pub(crate) enum UpdateRecipeError {
@@ -203,6 +699,10 @@ pub(crate) enum UpdateRecipeError {
     ),
     #[error("The recipe could not be found")]
     NotFound,
+    #[error("`{0}` is not a valid video url")]
+    InvalidVideoUrl(String),
+    #[error("The recipe has invalid fields")]
+    InvalidFields(Vec<FieldError>),
 }
 
 impl From<crate::core::recipe::UpdateRecipeError> for UpdateRecipeError {
@@ -210,11 +710,37 @@ impl From<crate::core::recipe::UpdateRecipeError> for UpdateRecipeError {
         match value {
             crate::core::recipe::UpdateRecipeError::Unknown(report) => Self::Unknown(report),
             crate::core::recipe::UpdateRecipeError::NotFound => Self::NotFound,
+            crate::core::recipe::UpdateRecipeError::InvalidVideoUrl(url) => {
+                Self::InvalidVideoUrl(url)
+            }
+            crate::core::recipe::UpdateRecipeError::InvalidFields(errors) => {
+                Self::InvalidFields(errors)
+            }
         }
     }
 }
 
-impl ResponseError for UpdateRecipeError {}
+impl ResponseError for UpdateRecipeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            UpdateRecipeError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            UpdateRecipeError::NotFound => StatusCode::NOT_FOUND,
+            UpdateRecipeError::InvalidVideoUrl(_) => StatusCode::BAD_REQUEST,
+            UpdateRecipeError::InvalidFields(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            UpdateRecipeError::InvalidFields(fields) => {
+                invalid_fields_response(self.to_string(), fields)
+            }
+            _ => HttpResponse::build(self.status_code())
+                .content_type(ContentType::plaintext())
+                .body(self.to_string()),
+        }
+    }
+}
 
 #[derive(Debug, Error)]
 pub(crate) enum DeleteRecipeError {
@@ -226,6 +752,8 @@ pub(crate) enum DeleteRecipeError {
     ),
     #[error("The recipe could not be found")]
     NotFound,
+    #[error("The deletion has invalid fields")]
+    InvalidFields(Vec<FieldError>),
 }
 
 impl From<crate::core::recipe::DeleteRecipeError> for DeleteRecipeError {
@@ -233,119 +761,923 @@ impl From<crate::core::recipe::DeleteRecipeError> for DeleteRecipeError {
         match value {
             crate::core::recipe::DeleteRecipeError::Unknown(report) => Self::Unknown(report),
             crate::core::recipe::DeleteRecipeError::NotFound => Self::NotFound,
+            crate::core::recipe::DeleteRecipeError::InvalidFields(errors) => {
+                Self::InvalidFields(errors)
+            }
+        }
+    }
+}
+
+impl ResponseError for DeleteRecipeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            DeleteRecipeError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            DeleteRecipeError::NotFound => StatusCode::NOT_FOUND,
+            DeleteRecipeError::InvalidFields(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            DeleteRecipeError::InvalidFields(fields) => {
+                invalid_fields_response(self.to_string(), fields)
+            }
+            _ => HttpResponse::build(self.status_code())
+                .content_type(ContentType::plaintext())
+                .body(self.to_string()),
+        }
+    }
+}
+
+/// Header authenticating proxies use to pass on the user making a request
+pub(crate) const REMOTE_USER_HEADER: &str = "Remote-User";
+
+/// Registered as app data when the server sits behind a reverse proxy. Only then are the
+/// forwarded client address and [`REMOTE_USER_HEADER`] believed, as anyone can send those headers
+/// when connecting directly.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TrustedProxy;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct DeleteQuery {
+    /// Why the recipe is deleted, kept in the deletion audit
+    reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ListQuery {
+    /// Comma separated list of fields to include, like `recipe_id,name`
+    fields: Option<String>,
+    /// Comma separated list of relations to embed, only `ingredients` is supported
+    include: Option<String>,
+    /// Comma separated list of recipe ids to fetch, like `1,2,3`
+    ids: Option<String>,
+    /// Lists the archived recipes instead of the others, ignored when fetching by ids
+    #[serde(default)]
+    archived: bool,
+}
+
+const MAX_BATCH_IDS: usize = 100;
+
+/// Header holding the total number of items in a collection
+const TOTAL_COUNT_HEADER: &str = "X-Total-Count";
+
+/// Parses a comma separated list of recipe ids, returning the first invalid id on failure
+fn parse_ids(ids: &str) -> Result<Vec<i32>, String> {
+    ids.split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| id.parse().map_err(|_| id.to_string()))
+        .collect()
+}
+
+pub(crate) async fn list_recipes<R: Repository>(
+    svc: Data<RecipeService<R>>,
+    request: HttpRequest,
+    Query(query): Query<ListQuery>,
+    Units(units): Units,
+) -> Result<HttpResponse, ListRecipeError> {
+    let fields = query
+        .fields
+        .map(|fields| RecipeField::parse_list(&fields))
+        .transpose()
+        .map_err(ListRecipeError::InvalidField)?;
+
+    // Ingredients are also skipped when they are not part of the selected fields, or when only
+    // the headers are requested
+    let include_ingredients = parse_include(query.include.as_deref())
+        .map_err(ListRecipeError::InvalidInclude)?
+        && fields
+            .as_ref()
+            .is_none_or(|fields| fields.contains(&RecipeField::Ingredients))
+        && request.method() != Method::HEAD;
+
+    let recipes = match query.ids {
+        Some(ids) => {
+            let ids = parse_ids(&ids).map_err(ListRecipeError::InvalidId)?;
+
+            if ids.len() > MAX_BATCH_IDS {
+                return Err(ListRecipeError::TooManyIds);
+            }
+
+            svc.get_recipes(ids, include_ingredients).await?
+        }
+        None => {
+            svc.list_recipes(include_ingredients, query.archived)
+                .await?
+        }
+    };
+
+    Ok(HttpResponse::Ok()
+        .insert_header((TOTAL_COUNT_HEADER, recipes.len()))
+        .json(
+            recipes
+                .into_iter()
+                .map(|recipe| RecipeView {
+                    recipe: RecipeDto::from(recipe).into_system(units),
+                    fields: fields.clone(),
+                })
+                .collect::<Vec<_>>(),
+        ))
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum GetRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::recipe::GetRecipeError> for GetRecipeError {
+    fn from(value: crate::core::recipe::GetRecipeError) -> Self {
+        match value {
+            crate::core::recipe::GetRecipeError::Unknown(report) => Self::Unknown(report),
+            crate::core::recipe::GetRecipeError::NotFound => Self::NotFound,
         }
     }
 }
 
-impl ResponseError for DeleteRecipeError {}
+impl ResponseError for GetRecipeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            GetRecipeError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            GetRecipeError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
 
-#[get("/recipes")]
-pub(crate) async fn list_recipes(
-    svc: Data<RecipeService>,
-) -> Result<Json<Vec<RecipeDto>>, ListRecipeError> {
-    let recipes = svc.list_recipes().await?;
-    Ok(Json(recipes.into_iter().map(RecipeDto::from).collect()))
+pub(crate) async fn get_recipe<R: Repository>(
+    svc: Data<RecipeService<R>>,
+    RecipeId(recipe_id): RecipeId,
+    Units(units): Units,
+) -> Result<Json<RecipeDto>, GetRecipeError> {
+    let recipe = svc.get_recipe(recipe_id).await?;
+    Ok(Json(RecipeDto::from(recipe).into_system(units)))
 }
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct SearchQuery {
+    /// Words to look for in names, descriptions, ingredients and tags at once
+    q: Option<String>,
     recipe_name: Option<String>,
     ingredient_name: Option<String>,
     meal_type: Option<MealType>,
+    /// Comma separated list of relations to embed, only `ingredients` is supported
+    include: Option<String>,
+    /// Searches the archived recipes instead of the others
+    #[serde(default)]
+    archived: bool,
+    /// Answers with an object holding the recipes and, when there are none, names to try instead
+    #[serde(default)]
+    suggest: bool,
+    /// Comma separated list of orderings, applied in turn
+    sort: Option<String>,
+    /// Only recipes created after this moment, as an RFC 3339 timestamp
+    created_after: Option<DateTime<Utc>>,
+    /// Only recipes created before this moment
+    created_before: Option<DateTime<Utc>>,
+    /// Only recipes changed after this moment, changes to ingredients and tags included
+    updated_after: Option<DateTime<Utc>>,
 }
 
-impl From<SearchQuery> for SearchCriteria {
-    fn from(value: SearchQuery) -> Self {
-        Self {
-            recipe_name: value.recipe_name,
-            ingredient_name: value.ingredient_name,
-            meal_type: value.meal_type.map(|mt| mt.into()),
+/// Parses the `sort` query parameter, returning the first unknown ordering on failure
+fn parse_sort(sort: Option<&str>) -> Result<Vec<SearchSort>, String> {
+    let Some(sort) = sort else {
+        return Ok(vec![]);
+    };
+
+    sort.split(',')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(|key| match key {
+            "cooking_time" => Ok(SearchSort::CookingTime),
+            "last_cooked" => Ok(SearchSort::LastCooked),
+            unknown => Err(unknown.to_string()),
+        })
+        .collect()
+}
+
+/// Name to search for instead, `field` is the query parameter to put it in
+#[derive(Debug, Serialize)]
+pub(crate) struct SuggestionDto {
+    pub(crate) field: &'static str,
+    pub(crate) name: String,
+}
+
+impl From<Suggestion> for SuggestionDto {
+    fn from(value: Suggestion) -> Self {
+        match value {
+            Suggestion::RecipeName(name) => Self {
+                field: "recipe_name",
+                name,
+            },
+            Suggestion::IngredientName(name) => Self {
+                field: "ingredient_name",
+                name,
+            },
         }
     }
 }
 
+#[derive(Debug, Serialize)]
+pub(crate) struct SearchResultsDto {
+    pub(crate) recipes: Vec<RecipeDto>,
+    pub(crate) suggestions: Vec<SuggestionDto>,
+}
+
 #[derive(Debug, Error)]
 pub(crate) enum SearchRecipeError {
     #[error("Failed to search recipes: {0}")]
     Unknown(#[from] crate::core::recipe::SearchRecipeError),
+    #[error("Unknown expansion `{0}`")]
+    InvalidInclude(String),
+    #[error("Unknown sort `{0}`")]
+    InvalidSort(String),
 }
 
-impl ResponseError for SearchRecipeError {}
-
-#[get("/recipes/search")]
-pub(crate) async fn search_recipes(
-    svc: Data<RecipeService>,
-    Query(query): Query<SearchQuery>,
-) -> Result<Json<Vec<RecipeDto>>, SearchRecipeError> {
-    let recipes = svc.search_recipes(query.into()).await?;
-    Ok(Json(recipes.into_iter().map(RecipeDto::from).collect()))
+impl ResponseError for SearchRecipeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SearchRecipeError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SearchRecipeError::InvalidInclude(_) | SearchRecipeError::InvalidSort(_) => {
+                StatusCode::BAD_REQUEST
+            }
+        }
+    }
 }
 
-#[post("/recipes")]
-pub(crate) async fn create_recipe(
-    svc: Data<RecipeService>,
-    Json(data): Json<NewRecipeDto>,
-) -> Result<HttpResponse, CreateRecipeError> {
-    let recipe = svc.create_recipe(data.into()).await?;
-
-    Ok(HttpResponse::Created()
-        .content_type(ContentType::json())
-        .json(RecipeDto::from(recipe)))
-}
+pub(crate) async fn search_recipes<R: Repository>(
+    svc: Data<RecipeService<R>>,
+    request: HttpRequest,
+    Query(query): Query<SearchQuery>,
+    Tags(tags): Tags,
+    Units(units): Units,
+) -> Result<HttpResponse, SearchRecipeError> {
+    let include_ingredients = parse_include(query.include.as_deref())
+        .map_err(SearchRecipeError::InvalidInclude)?
+        && request.method() != Method::HEAD;
+    let sort = parse_sort(query.sort.as_deref()).map_err(SearchRecipeError::InvalidSort)?;
 
-#[put("/recipes/{recipe_id}")]
-pub(crate) async fn update_recipe(
-    svc: Data<RecipeService>,
-    path: Path<i32>,
-    Json(data): Json<RecipeDto>,
-) -> Result<Json<RecipeDto>, UpdateRecipeError> {
-    let recipe = svc
-        .update_recipe(Recipe {
-            recipe_id: path.into_inner(),
-            name: data.name,
-            description: data.description,
-            ingredients: data.ingredients.into_iter().map(Ingredient::from).collect(),
-            cooking_time: data.cooking_time,
-            meal_type: data.meal_type.into(),
+    let results = svc
+        .search_recipes(SearchCriteria {
+            recipe_name: query.recipe_name,
+            ingredient_name: query.ingredient_name,
+            meal_type: query.meal_type.map(|mt| mt.into()),
+            include_ingredients,
+            archived: query.archived,
+            suggest: query.suggest,
+            tags,
+            sort,
+            created_after: query.created_after,
+            created_before: query.created_before,
+            updated_after: query.updated_after,
+            keywords: query.q,
         })
         .await?;
 
-    Ok(Json(recipe.into()))
-}
+    let recipes = results
+        .recipes
+        .into_iter()
+        .map(|recipe| RecipeDto::from(recipe).into_system(units))
+        .collect::<Vec<_>>();
 
-#[delete("/recipes/{recipe_id}")]
-pub(crate) async fn delete_recipe(
-    svc: Data<RecipeService>,
-    path: Path<i32>,
-) -> Result<(), DeleteRecipeError> {
-    svc.delete_recipe(path.into_inner()).await?;
-    Ok(())
-}
+    let mut response = HttpResponse::Ok();
+    response.insert_header((TOTAL_COUNT_HEADER, recipes.len()));
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    // The plain list stays the default, so existing clients keep working
+    if query.suggest {
+        Ok(response.json(SearchResultsDto {
+            recipes,
+            suggestions: results
+                .suggestions
+                .into_iter()
+                .map(SuggestionDto::from)
+                .collect(),
+        }))
+    } else {
+        Ok(response.json(recipes))
+    }
+}
 
-    use actix_web::test;
+#[derive(Debug, Deserialize)]
+pub(crate) struct SearchByIngredientsDto {
+    /// Names of the ingredients at hand, matched partially and case-insensitive
+    ingredients: Vec<String>,
+    limit: Option<u32>,
+}
 
-    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+const DEFAULT_SEARCH_BY_INGREDIENTS_LIMIT: u32 = 20;
 
-    macro_rules! setup_app {
-        ($pool:expr) => {{
+#[derive(Debug, Error)]
+pub(crate) enum SearchByIngredientsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("At least one ingredient is required")]
+    NoIngredients,
+}
+
+impl From<crate::core::recipe::SearchByIngredientsError> for SearchByIngredientsError {
+    fn from(value: crate::core::recipe::SearchByIngredientsError) -> Self {
+        match value {
+            crate::core::recipe::SearchByIngredientsError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl ResponseError for SearchByIngredientsError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SearchByIngredientsError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SearchByIngredientsError::NoIngredients => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+pub(crate) async fn search_by_ingredients<R: Repository>(
+    svc: Data<RecipeService<R>>,
+    JsonBody(data): JsonBody<SearchByIngredientsDto>,
+    Units(units): Units,
+) -> Result<Json<Vec<IngredientMatchDto>>, SearchByIngredientsError> {
+    let ingredients = data
+        .ingredients
+        .into_iter()
+        .map(|ingredient| ingredient.trim().to_string())
+        .filter(|ingredient| !ingredient.is_empty())
+        .collect::<Vec<_>>();
+
+    if ingredients.is_empty() {
+        return Err(SearchByIngredientsError::NoIngredients);
+    }
+
+    let matches = svc
+        .search_by_ingredients(
+            ingredients,
+            data.limit.unwrap_or(DEFAULT_SEARCH_BY_INGREDIENTS_LIMIT),
+        )
+        .await?;
+
+    Ok(Json(
+        matches
+            .into_iter()
+            .map(|ingredient_match| {
+                let mut dto = IngredientMatchDto::from(ingredient_match);
+                dto.recipe = dto.recipe.into_system(units);
+                dto
+            })
+            .collect(),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RandomQuery {
+    meal_type: Option<MealType>,
+    /// Upper bound for the cooking time, in seconds
+    max_cooking_time: Option<u64>,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum RandomRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("No recipe matched the given criteria")]
+    NotFound,
+    #[error("Invalid max_cooking_time `{0}`, expected at most {max} seconds", max = i64::MAX)]
+    InvalidMaxCookingTime(u64),
+}
+
+impl From<crate::core::recipe::RandomRecipeError> for RandomRecipeError {
+    fn from(value: crate::core::recipe::RandomRecipeError) -> Self {
+        match value {
+            crate::core::recipe::RandomRecipeError::Unknown(report) => Self::Unknown(report),
+            crate::core::recipe::RandomRecipeError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for RandomRecipeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RandomRecipeError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            RandomRecipeError::NotFound => StatusCode::NOT_FOUND,
+            RandomRecipeError::InvalidMaxCookingTime(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+pub(crate) async fn random_recipe<R: Repository>(
+    svc: Data<RecipeService<R>>,
+    Query(query): Query<RandomQuery>,
+    Tags(tags): Tags,
+    Units(units): Units,
+) -> Result<Json<RecipeDto>, RandomRecipeError> {
+    // Cooking times are stored as signed seconds, larger bounds can't be compared against them
+    let max_cooking_time = query
+        .max_cooking_time
+        .map(|secs| {
+            i64::try_from(secs)
+                .map(|_| Duration::from_secs(secs))
+                .map_err(|_| RandomRecipeError::InvalidMaxCookingTime(secs))
+        })
+        .transpose()?;
+
+    let recipe = svc
+        .random_recipe(RandomCriteria {
+            meal_type: query.meal_type.map(|mt| mt.into()),
+            max_cooking_time,
+            tags,
+        })
+        .await?;
+    Ok(Json(RecipeDto::from(recipe).into_system(units)))
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct AutocompleteQuery {
+    q: String,
+    limit: Option<u32>,
+}
+
+const DEFAULT_AUTOCOMPLETE_LIMIT: u32 = 10;
+const MAX_AUTOCOMPLETE_LIMIT: u32 = 50;
+
+#[derive(Debug, Serialize)]
+pub(crate) struct RecipeNameDto {
+    pub(crate) recipe_id: i32,
+    pub(crate) name: String,
+}
+
+impl From<RecipeName> for RecipeNameDto {
+    fn from(value: RecipeName) -> Self {
+        Self {
+            recipe_id: value.recipe_id,
+            name: value.name,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum AutocompleteError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::core::recipe::AutocompleteError> for AutocompleteError {
+    fn from(value: crate::core::recipe::AutocompleteError) -> Self {
+        match value {
+            crate::core::recipe::AutocompleteError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl ResponseError for AutocompleteError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AutocompleteError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Ids and names of the recipes starting with `q`, for search-as-you-type. The limit is capped at
+/// [`MAX_AUTOCOMPLETE_LIMIT`].
+pub(crate) async fn autocomplete<R: Repository>(
+    svc: Data<RecipeService<R>>,
+    Query(query): Query<AutocompleteQuery>,
+) -> Result<Json<Vec<RecipeNameDto>>, AutocompleteError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_AUTOCOMPLETE_LIMIT)
+        .min(MAX_AUTOCOMPLETE_LIMIT);
+    let recipes = svc.autocomplete(&query.q, limit).await?;
+
+    Ok(Json(recipes.into_iter().map(RecipeNameDto::from).collect()))
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct PopularQuery {
+    /// Period to count views over, like `30d` or `2w`
+    window: Option<String>,
+    limit: Option<u32>,
+}
+
+const DEFAULT_POPULAR_WINDOW_DAYS: u32 = 30;
+const MAX_POPULAR_WINDOW_DAYS: u32 = 3650;
+const DEFAULT_POPULAR_LIMIT: u32 = 10;
+
+/// Parses a window like `30d` or `2w` into a number of days, up to [`MAX_POPULAR_WINDOW_DAYS`]
+fn parse_window_days(window: &str) -> Option<u32> {
+    let (amount, days_per_unit) = if let Some(amount) = window.strip_suffix('d') {
+        (amount, 1)
+    } else if let Some(amount) = window.strip_suffix('w') {
+        (amount, 7)
+    } else {
+        return None;
+    };
+
+    amount
+        .parse::<u32>()
+        .ok()?
+        .checked_mul(days_per_unit)
+        .filter(|days| (1..=MAX_POPULAR_WINDOW_DAYS).contains(days))
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum PopularRecipesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error(
+        "Invalid window `{0}`, expected a number of days or weeks like `30d` or `2w`, up to {MAX_POPULAR_WINDOW_DAYS} days"
+    )]
+    InvalidWindow(String),
+}
+
+impl From<crate::core::recipe::PopularRecipesError> for PopularRecipesError {
+    fn from(value: crate::core::recipe::PopularRecipesError) -> Self {
+        match value {
+            crate::core::recipe::PopularRecipesError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl ResponseError for PopularRecipesError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            PopularRecipesError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            PopularRecipesError::InvalidWindow(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+pub(crate) async fn popular_recipes<R: Repository>(
+    svc: Data<RecipeService<R>>,
+    Query(query): Query<PopularQuery>,
+    Units(units): Units,
+) -> Result<Json<Vec<PopularRecipeDto>>, PopularRecipesError> {
+    let window_days = match query.window {
+        Some(window) => {
+            parse_window_days(&window).ok_or(PopularRecipesError::InvalidWindow(window))?
+        }
+        None => DEFAULT_POPULAR_WINDOW_DAYS,
+    };
+
+    let recipes = svc
+        .popular_recipes(window_days, query.limit.unwrap_or(DEFAULT_POPULAR_LIMIT))
+        .await?;
+
+    Ok(Json(
+        recipes
+            .into_iter()
+            .map(|recipe| {
+                let mut dto = PopularRecipeDto::from(recipe);
+                dto.recipe = dto.recipe.into_system(units);
+                dto
+            })
+            .collect(),
+    ))
+}
+
+/// Creates a recipe, pointing to it in the `Location` header and the `self` link of the body
+pub(crate) async fn create_recipe<R: Repository>(
+    svc: Data<RecipeService<R>>,
+    JsonBody(data): JsonBody<NewRecipeDto>,
+    Units(units): Units,
+    req: HttpRequest,
+) -> Result<HttpResponse, CreateRecipeError> {
+    let recipe = svc.create_recipe(data.into()).await?;
+
+    // Built from the request path, so the link keeps the prefix of a scope the API is mounted in
+    let location = format!("{}/{}", req.path().trim_end_matches('/'), recipe.recipe_id);
+
+    Ok(HttpResponse::Created()
+        .content_type(ContentType::json())
+        .insert_header((header::LOCATION, location.clone()))
+        .json(CreatedRecipeDto {
+            recipe: RecipeDto::from(recipe).into_system(units),
+            links: LinksDto {
+                self_link: location,
+            },
+        }))
+}
+
+pub(crate) async fn update_recipe<R: Repository>(
+    svc: Data<RecipeService<R>>,
+    RecipeId(recipe_id): RecipeId,
+    JsonBody(data): JsonBody<RecipeDto>,
+    Units(units): Units,
+) -> Result<Json<RecipeDto>, UpdateRecipeError> {
+    let recipe = svc
+        .update_recipe(Recipe {
+            recipe_id,
+            name: data.name,
+            description: data.description,
+            ingredients: data.ingredients.into_iter().map(Ingredient::from).collect(),
+            cooking_time: cooking_time_or_minutes(data.cooking_time, data.cooking_time_minutes),
+            meal_type: data.meal_type.into(),
+            last_cooked_at: None,
+            video: data.video_url.map(|url| Video {
+                url,
+                title: None,
+                thumbnail_url: None,
+            }),
+            pairing: data.pairing.map(Pairing::from),
+            forked_from: None,
+            public_id: data.public_id,
+        })
+        .await?;
+
+    Ok(Json(RecipeDto::from(recipe).into_system(units)))
+}
+
+/// Metadata fields to change, fields which are left out keep their current value
+#[derive(Debug, Deserialize)]
+pub(crate) struct MetadataChangesDto {
+    #[serde(default)]
+    pub(crate) name: Option<String>,
+    #[serde(default)]
+    pub(crate) description: Option<String>,
+    #[serde(with = "super::duration::option", default)]
+    pub(crate) cooking_time: Option<Duration>,
+    #[serde(default)]
+    pub(crate) cooking_time_minutes: Option<u32>,
+    #[serde(default)]
+    pub(crate) meal_type: Option<MealType>,
+    /// Replaces the tags of the recipe, an empty list removes them all
+    #[serde(default)]
+    pub(crate) tags: Option<Vec<String>>,
+}
+
+impl From<MetadataChangesDto> for MetadataChanges {
+    fn from(value: MetadataChangesDto) -> Self {
+        Self {
+            name: value.name,
+            description: value.description,
+            cooking_time: cooking_time_or_minutes(value.cooking_time, value.cooking_time_minutes),
+            meal_type: value.meal_type.map(Into::into),
+            tags: value.tags,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct MetadataUpdateDto {
+    pub(crate) recipe_id: i32,
+    pub(crate) changes: MetadataChangesDto,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum MetadataStatusDto {
+    Updated,
+    NotFound,
+    Rejected,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct MetadataResultDto {
+    pub(crate) recipe_id: i32,
+    pub(crate) status: MetadataStatusDto,
+    /// Why the changes were rejected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) error: Option<String>,
+}
+
+impl MetadataResultDto {
+    fn new(recipe_id: i32, outcome: MetadataOutcome) -> Self {
+        let (status, error) = match outcome {
+            MetadataOutcome::Updated => (MetadataStatusDto::Updated, None),
+            MetadataOutcome::NotFound => (MetadataStatusDto::NotFound, None),
+            MetadataOutcome::Rejected(reason) => (MetadataStatusDto::Rejected, Some(reason)),
+        };
+
+        Self {
+            recipe_id,
+            status,
+            error,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct MetadataResultsDto {
+    pub(crate) results: Vec<MetadataResultDto>,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum UpdateMetadataError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("At most {MAX_BATCH_IDS} recipes can be updated at once")]
+    TooManyUpdates,
+}
+
+impl From<crate::core::recipe::UpdateMetadataError> for UpdateMetadataError {
+    fn from(value: crate::core::recipe::UpdateMetadataError) -> Self {
+        match value {
+            crate::core::recipe::UpdateMetadataError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl ResponseError for UpdateMetadataError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            UpdateMetadataError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            UpdateMetadataError::TooManyUpdates => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+/// Changes the metadata of many recipes in one transaction, reporting a result per recipe in the
+/// order they were given
+pub(crate) async fn update_metadata<R: Repository>(
+    svc: Data<RecipeService<R>>,
+    JsonBody(updates): JsonBody<Vec<MetadataUpdateDto>>,
+) -> Result<Json<MetadataResultsDto>, UpdateMetadataError> {
+    if updates.len() > MAX_BATCH_IDS {
+        return Err(UpdateMetadataError::TooManyUpdates);
+    }
+
+    let recipe_ids = updates
+        .iter()
+        .map(|update| update.recipe_id)
+        .collect::<Vec<_>>();
+    let outcomes = svc
+        .update_metadata(
+            updates
+                .into_iter()
+                .map(|update| MetadataUpdate {
+                    recipe_id: update.recipe_id,
+                    changes: update.changes.into(),
+                })
+                .collect(),
+        )
+        .await?;
+
+    Ok(Json(MetadataResultsDto {
+        results: recipe_ids
+            .into_iter()
+            .zip(outcomes)
+            .map(|(recipe_id, outcome)| MetadataResultDto::new(recipe_id, outcome))
+            .collect(),
+    }))
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum SetArchivedError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::recipe::SetArchivedError> for SetArchivedError {
+    fn from(value: crate::core::recipe::SetArchivedError) -> Self {
+        match value {
+            crate::core::recipe::SetArchivedError::Unknown(report) => Self::Unknown(report),
+            crate::core::recipe::SetArchivedError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for SetArchivedError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SetArchivedError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SetArchivedError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+/// Moves a recipe to the archive, out of listings and searches
+pub(crate) async fn archive_recipe<R: Repository>(
+    svc: Data<RecipeService<R>>,
+    RecipeId(recipe_id): RecipeId,
+) -> Result<HttpResponse, SetArchivedError> {
+    svc.set_archived(recipe_id, true).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub(crate) async fn unarchive_recipe<R: Repository>(
+    svc: Data<RecipeService<R>>,
+    RecipeId(recipe_id): RecipeId,
+) -> Result<HttpResponse, SetArchivedError> {
+    svc.set_archived(recipe_id, false).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Deletes a recipe, recording the address of the client and the reason given. Behind a
+/// [`TrustedProxy`] the user it reports is recorded too, and the address it forwarded instead of
+/// its own. Responds with the deleted recipe, so clients can offer to undo the deletion by
+/// creating it again.
+pub(crate) async fn delete_recipe<R: Repository>(
+    svc: Data<RecipeService<R>>,
+    RecipeId(recipe_id): RecipeId,
+    Query(query): Query<DeleteQuery>,
+    Units(units): Units,
+    req: HttpRequest,
+) -> Result<Json<RecipeDto>, DeleteRecipeError> {
+    let deletion = if req.app_data::<Data<TrustedProxy>>().is_some() {
+        Deletion {
+            actor: req
+                .headers()
+                .get(REMOTE_USER_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string),
+            ip_address: req
+                .connection_info()
+                .realip_remote_addr()
+                .map(str::to_string),
+            reason: query.reason,
+        }
+    } else {
+        Deletion {
+            actor: None,
+            ip_address: req.peer_addr().map(|addr| addr.ip().to_string()),
+            reason: query.reason,
+        }
+    };
+
+    let recipe = svc.delete_recipe(recipe_id, deletion).await?;
+    Ok(Json(RecipeDto::from(recipe).into_system(units)))
+}
+
+/// Registers the routes of this module, generic over the repository the services use. Needs to
+/// be registered after all other `/recipes/...` routes, otherwise the `recipe_id` segment would
+/// swallow paths like `/recipes/duplicates`.
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route("/recipes", web::get().to(list_recipes::<R>))
+        .route("/recipes", web::head().to(list_recipes::<R>))
+        .route("/recipes/search", web::get().to(search_recipes::<R>))
+        .route("/recipes/search", web::head().to(search_recipes::<R>))
+        .route(
+            "/recipes/search/by-ingredients",
+            web::post().to(search_by_ingredients::<R>),
+        )
+        .route("/recipes/random", web::get().to(random_recipe::<R>))
+        .route("/recipes/autocomplete", web::get().to(autocomplete::<R>))
+        .route("/recipes/popular", web::get().to(popular_recipes::<R>))
+        .route("/recipes", web::post().to(create_recipe::<R>))
+        .route("/recipes", web::patch().to(update_metadata::<R>))
+        .route("/recipes/{recipe_id}", web::put().to(update_recipe::<R>))
+        .route("/recipes/{recipe_id}", web::delete().to(delete_recipe::<R>))
+        .route(
+            "/recipes/{recipe_id}/archive",
+            web::post().to(archive_recipe::<R>),
+        )
+        .route(
+            "/recipes/{recipe_id}/unarchive",
+            web::post().to(unarchive_recipe::<R>),
+        )
+        .route("/recipes/{recipe_id}", web::get().to(get_recipe::<R>));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::test;
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    macro_rules! setup_app {
+        ($pool:expr) => {{
             use crate::Postgres;
             use actix_web::App;
 
             let postgres = Postgres::new($pool);
 
-            let recipe_service = RecipeService::new(postgres);
+            let recipe_service = RecipeService::new(postgres, None);
 
             test::init_service(
                 App::new()
-                    .service(list_recipes)
-                    .service(search_recipes)
-                    .service(create_recipe)
-                    .service(update_recipe)
-                    .service(delete_recipe)
+                    .configure(configure::<Postgres>)
                     .app_data(Data::new(recipe_service.clone())),
             )
             .await
@@ -367,6 +1699,598 @@ mod tests {
 
             assert2::check!(response.status() == StatusCode::OK);
         }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_include_the_total_count_on_head(pool: PgPool) {
+            sqlx::query!(
+                "INSERT INTO recipe (name, meal_type) VALUES ('Pancakes', 'Breakfast'), ('Soup', 'Lunch')"
+            )
+            .execute(&pool)
+            .await
+            .expect("Failed to insert recipes");
+
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::default()
+                .method(Method::HEAD)
+                .uri("/recipes")
+                .to_request();
+            let response = test::call_service(&app, request).await;
+
+            assert2::check!(response.status() == StatusCode::OK);
+            assert2::check!(
+                response
+                    .headers()
+                    .get(TOTAL_COUNT_HEADER)
+                    .and_then(|value| value.to_str().ok())
+                    == Some("2")
+            );
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_only_include_the_requested_fields(pool: PgPool) {
+            sqlx::query!("INSERT INTO recipe (name, meal_type) VALUES ('Pancakes', 'Breakfast')")
+                .execute(&pool)
+                .await
+                .expect("Failed to insert recipe");
+
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::get()
+                .uri("/recipes?fields=name,meal_type")
+                .to_request();
+            let body = test::call_and_read_body(&app, request).await;
+
+            assert2::check!(body == r#"[{"name":"Pancakes","meal_type":"Breakfast"}]"#);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_only_return_the_requested_ids(pool: PgPool) {
+            let recipe_id = sqlx::query_scalar!(
+                "INSERT INTO recipe (name, meal_type) VALUES ('Pancakes', 'Breakfast') RETURNING recipe_id"
+            )
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to insert recipe");
+
+            sqlx::query!("INSERT INTO recipe (name, meal_type) VALUES ('Soup', 'Lunch')")
+                .execute(&pool)
+                .await
+                .expect("Failed to insert recipe");
+
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::get()
+                .uri(&format!("/recipes?ids={recipe_id}&fields=name"))
+                .to_request();
+            let body = test::call_and_read_body(&app, request).await;
+
+            assert2::check!(body == r#"[{"name":"Pancakes"}]"#);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_list_archived_recipes_apart(pool: PgPool) {
+            let recipe_id = sqlx::query_scalar!(
+                "INSERT INTO recipe (name, meal_type) VALUES ('Pancakes', 'Breakfast') RETURNING recipe_id"
+            )
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to insert recipe");
+
+            sqlx::query!("INSERT INTO recipe (name, meal_type) VALUES ('Soup', 'Lunch')")
+                .execute(&pool)
+                .await
+                .expect("Failed to insert recipe");
+
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::post()
+                .uri(&format!("/recipes/{recipe_id}/archive"))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert2::check!(response.status() == actix_web::http::StatusCode::NO_CONTENT);
+
+            let request = test::TestRequest::get()
+                .uri("/recipes?fields=name")
+                .to_request();
+            let body = test::call_and_read_body(&app, request).await;
+            assert2::check!(body == r#"[{"name":"Soup"}]"#);
+
+            let request = test::TestRequest::get()
+                .uri("/recipes?fields=name&archived=true")
+                .to_request();
+            let body = test::call_and_read_body(&app, request).await;
+            assert2::check!(body == r#"[{"name":"Pancakes"}]"#);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_return_400_for_an_invalid_id(pool: PgPool) {
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::get()
+                .uri("/recipes?ids=1,two")
+                .to_request();
+            let response = test::call_service(&app, request).await;
+
+            assert2::check!(response.status() == StatusCode::BAD_REQUEST);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_return_400_for_an_unknown_expansion(pool: PgPool) {
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::get()
+                .uri("/recipes?include=steps")
+                .to_request();
+            let response = test::call_service(&app, request).await;
+
+            assert2::check!(response.status() == StatusCode::BAD_REQUEST);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_return_400_for_an_unknown_field(pool: PgPool) {
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::get()
+                .uri("/recipes?fields=name,secret")
+                .to_request();
+            let response = test::call_service(&app, request).await;
+
+            assert2::check!(response.status() == StatusCode::BAD_REQUEST);
+        }
+    }
+
+    mod create_recipe {
+        use actix_web::http::StatusCode;
+        use sqlx::PgPool;
+
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_return_400_for_an_invalid_video_url(pool: PgPool) {
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::post()
+                .uri("/recipes")
+                .insert_header(ContentType::json())
+                .set_payload(
+                    r#"{"name":"Soup","ingredients":[],"meal_type":"Lunch","video_url":"javascript:alert(1)"}"#,
+                )
+                .to_request();
+            let response = test::call_service(&app, request).await;
+
+            assert2::check!(response.status() == StatusCode::BAD_REQUEST);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_return_422_with_the_fields_which_are_too_long(pool: PgPool) {
+            let app = setup_app!(pool);
+
+            let payload = serde_json::json!({
+                "name": "a".repeat(201),
+                "ingredients": [],
+                "meal_type": "Lunch",
+            });
+            let request = test::TestRequest::post()
+                .uri("/recipes")
+                .set_json(payload)
+                .to_request();
+            let response = test::call_service(&app, request).await;
+
+            assert2::check!(response.status() == StatusCode::UNPROCESSABLE_ENTITY);
+            let body: serde_json::Value = test::read_body_json(response).await;
+            assert2::check!(body["fields"][0]["field"] == "name");
+            assert2::check!(body["fields"][0]["message"] == "can be at most 200 characters long");
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_accept_the_cooking_time_in_minutes(pool: PgPool) {
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::post()
+                .uri("/recipes")
+                .insert_header(ContentType::json())
+                .set_payload(
+                    r#"{"name":"Soup","ingredients":[],"cooking_time_minutes":45,"meal_type":"Lunch"}"#,
+                )
+                .to_request();
+            let response = test::call_service(&app, request).await;
+
+            assert2::check!(response.status() == StatusCode::CREATED);
+
+            let body = test::read_body(response).await;
+            let body = std::str::from_utf8(&body).expect("Body should be valid UTF-8");
+
+            assert2::check!(body.contains(r#""cooking_time":"PT45M""#));
+            assert2::check!(body.contains(r#""cooking_time_minutes":45"#));
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_point_to_the_created_recipe(pool: PgPool) {
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::post()
+                .uri("/recipes")
+                .set_json(serde_json::json!({
+                    "name": "Soup",
+                    "ingredients": [],
+                    "meal_type": "Lunch",
+                }))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+
+            assert2::check!(response.status() == StatusCode::CREATED);
+            let location = response
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+
+            let body: serde_json::Value = test::read_body_json(response).await;
+            let expected = format!("/recipes/{}", body["recipe_id"]);
+            assert2::check!(location.as_deref() == Some(expected.as_str()));
+            assert2::check!(body["links"]["self"] == expected);
+
+            let request = test::TestRequest::get().uri(&expected).to_request();
+            let response = test::call_service(&app, request).await;
+            assert2::check!(response.status() == StatusCode::OK);
+        }
+    }
+
+    mod update_metadata {
+        use actix_web::http::StatusCode;
+        use sqlx::PgPool;
+
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_report_a_result_per_recipe(pool: PgPool) {
+            let recipe_id = sqlx::query_scalar!(
+                "INSERT INTO recipe (name, meal_type) VALUES ('Pancakes', 'Breakfast') RETURNING recipe_id"
+            )
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to insert recipe");
+
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::patch()
+                .uri("/recipes")
+                .insert_header(ContentType::json())
+                .set_payload(format!(
+                    r#"[{{"recipe_id":{recipe_id},"changes":{{"name":"Crêpes","meal_type":"Dinner"}}}},{{"recipe_id":{},"changes":{{"name":"Soup"}}}},{{"recipe_id":{recipe_id},"changes":{{"name":" "}}}}]"#,
+                    recipe_id + 1
+                ))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+
+            assert2::check!(response.status() == StatusCode::OK);
+
+            let body = test::read_body(response).await;
+            let body = std::str::from_utf8(&body).expect("Body should be valid UTF-8");
+
+            assert2::check!(body.contains(&format!(
+                r#"{{"recipe_id":{recipe_id},"status":"updated"}},{{"recipe_id":{},"status":"not_found"}},{{"recipe_id":{recipe_id},"status":"rejected","#,
+                recipe_id + 1
+            )));
+
+            let request = test::TestRequest::get()
+                .uri(&format!("/recipes?ids={recipe_id}&fields=name,meal_type"))
+                .to_request();
+            let body = test::call_and_read_body(&app, request).await;
+
+            assert2::check!(body == r#"[{"name":"Crêpes","meal_type":"Dinner"}]"#);
+        }
+    }
+
+    mod get_recipe {
+        use actix_web::http::StatusCode;
+        use sqlx::PgPool;
+
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_return_404_for_nonexistent_recipe(pool: PgPool) {
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::get().uri("/recipes/99999").to_request();
+            let response = test::call_service(&app, request).await;
+
+            assert2::check!(response.status() == StatusCode::NOT_FOUND);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_convert_to_the_requested_unit_system(pool: PgPool) {
+            let recipe_id: i32 = sqlx::query_scalar(
+                "INSERT INTO recipe (name, meal_type) VALUES ('Pancakes', 'Breakfast') RETURNING recipe_id",
+            )
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to insert recipe");
+            sqlx::query(
+                "INSERT INTO ingredient (recipe_id, ingredient_order, name, quantity_type, quantity)
+                VALUES ($1, 0, 'Flour', 'Kilo', 1), ($1, 1, 'Milk', 'Milliliter', 30)",
+            )
+            .bind(recipe_id)
+            .execute(&pool)
+            .await
+            .expect("Failed to insert ingredients");
+
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::get()
+                .uri(&format!("/recipes/{recipe_id}?unit_system=imperial"))
+                .to_request();
+            let recipe: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+
+            assert2::check!(
+                recipe["ingredients"]
+                    == serde_json::json!([
+                        { "name": "Flour", "quantity_type": "Pound", "quantity": 2.2 },
+                        { "name": "Milk", "quantity_type": "FluidOunce", "quantity": 1.01 },
+                    ])
+            );
+        }
+    }
+
+    mod search_by_ingredients {
+        use actix_web::http::StatusCode;
+        use sqlx::PgPool;
+
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_return_400_without_ingredients(pool: PgPool) {
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::post()
+                .uri("/recipes/search/by-ingredients")
+                .insert_header(ContentType::json())
+                .set_payload(r#"{"ingredients":[" "]}"#)
+                .to_request();
+            let response = test::call_service(&app, request).await;
+
+            assert2::check!(response.status() == StatusCode::BAD_REQUEST);
+        }
+    }
+
+    mod search_recipes {
+        use actix_web::http::StatusCode;
+        use sqlx::PgPool;
+
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_suggest_names_when_nothing_matches(pool: PgPool) {
+            sqlx::query!("INSERT INTO recipe (name, meal_type) VALUES ('Pancakes', 'Breakfast')")
+                .execute(&pool)
+                .await
+                .expect("Failed to insert recipe");
+
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::get()
+                .uri("/recipes/search?recipe_name=pancaeks&suggest=true")
+                .to_request();
+            let body = test::call_and_read_body(&app, request).await;
+
+            assert2::check!(
+                body == r#"{"recipes":[],"suggestions":[{"field":"recipe_name","name":"Pancakes"}]}"#
+            );
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_reject_unknown_sorts(pool: PgPool) {
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::get()
+                .uri("/recipes/search?sort=cooking_time,rating")
+                .to_request();
+            let response = test::call_service(&app, request).await;
+
+            assert2::check!(response.status() == StatusCode::BAD_REQUEST);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_combine_tag_filters(pool: PgPool) {
+            let recipe_ids = sqlx::query_scalar!(
+                "INSERT INTO recipe (name, meal_type) VALUES ('Pancakes', 'Breakfast'), ('Porridge', 'Breakfast') RETURNING recipe_id"
+            )
+            .fetch_all(&pool)
+            .await
+            .expect("Failed to insert recipes");
+
+            sqlx::query!(
+                "INSERT INTO recipe_tag (recipe_id, name) VALUES ($1, 'sweet'), ($1, 'quick'), ($2, 'quick')",
+                recipe_ids[0],
+                recipe_ids[1]
+            )
+            .execute(&pool)
+            .await
+            .expect("Failed to insert tags");
+
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::get()
+                .uri("/recipes/search?recipe_name=p&tag=Quick&tag=sweet")
+                .to_request();
+            let body = test::call_and_read_body(&app, request).await;
+            let body = std::str::from_utf8(&body).expect("Body should be valid UTF-8");
+
+            assert2::check!(body.contains("Pancakes"));
+            assert2::check!(!body.contains("Porridge"));
+        }
+    }
+
+    mod autocomplete {
+        use actix_web::http::StatusCode;
+        use sqlx::PgPool;
+
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_return_ids_and_names_only(pool: PgPool) {
+            let recipe_id = sqlx::query_scalar!(
+                "INSERT INTO recipe (name, meal_type) VALUES ('Pancakes', 'Breakfast') RETURNING recipe_id"
+            )
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to insert recipe");
+
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::get()
+                .uri("/recipes/autocomplete?q=pan")
+                .to_request();
+            let response = test::call_service(&app, request).await;
+
+            assert2::check!(response.status() == StatusCode::OK);
+
+            let body = test::read_body(response).await;
+            assert2::check!(body == format!(r#"[{{"recipe_id":{recipe_id},"name":"Pancakes"}}]"#));
+        }
+    }
+
+    mod popular_recipes {
+        use actix_web::http::StatusCode;
+        use sqlx::PgPool;
+
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_return_200(pool: PgPool) {
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::get()
+                .uri("/recipes/popular?window=2w")
+                .to_request();
+            let response = test::call_service(&app, request).await;
+
+            assert2::check!(response.status() == StatusCode::OK);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_return_400_for_an_invalid_window(pool: PgPool) {
+            let app = setup_app!(pool);
+
+            for window in ["thirty", "0d", "3%C3%A9", "3651d", "4294967295w", ""] {
+                let request = test::TestRequest::get()
+                    .uri(&format!("/recipes/popular?window={window}"))
+                    .to_request();
+                let response = test::call_service(&app, request).await;
+
+                assert2::check!(response.status() == StatusCode::BAD_REQUEST, "for {window}");
+            }
+        }
+
+        #[actix_web::test]
+        async fn it_parses_windows_in_days_and_weeks() {
+            assert2::check!(parse_window_days("30d") == Some(30));
+            assert2::check!(parse_window_days("2w") == Some(14));
+            assert2::check!(parse_window_days("3650d") == Some(3650));
+            assert2::check!(parse_window_days("é") == None);
+        }
+    }
+
+    mod random_recipe {
+        use actix_web::http::StatusCode;
+        use sqlx::PgPool;
+
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_return_404_when_nothing_matches(pool: PgPool) {
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::get()
+                .uri("/recipes/random?meal_type=Dinner")
+                .to_request();
+            let response = test::call_service(&app, request).await;
+
+            assert2::check!(response.status() == StatusCode::NOT_FOUND);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_reject_a_max_cooking_time_too_large_to_compare(pool: PgPool) {
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::get()
+                .uri(&format!("/recipes/random?max_cooking_time={}", u64::MAX))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+
+            assert2::check!(response.status() == StatusCode::BAD_REQUEST);
+        }
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_only_pick_recipes_with_the_tag(pool: PgPool) {
+            let recipe_id: i32 = sqlx::query_scalar(
+                "INSERT INTO recipe (name, meal_type) VALUES ('Stew', 'Dinner') RETURNING recipe_id",
+            )
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to insert recipe");
+            sqlx::query("INSERT INTO recipe (name, meal_type) VALUES ('Soup', 'Dinner')")
+                .execute(&pool)
+                .await
+                .expect("Failed to insert recipe");
+            sqlx::query("INSERT INTO recipe_tag (recipe_id, name) VALUES ($1, 'winter')")
+                .bind(recipe_id)
+                .execute(&pool)
+                .await
+                .expect("Failed to insert tag");
+
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::get()
+                .uri("/recipes/random?tag=Winter")
+                .to_request();
+            let recipe: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+
+            assert2::check!(recipe["name"] == "Stew");
+        }
+    }
+
+    mod delete_recipe {
+        use actix_web::http::StatusCode;
+        use sqlx::PgPool;
+
+        use super::*;
+
+        #[sqlx::test(migrator = "super::MIGRATOR")]
+        async fn it_should_return_the_deleted_recipe(pool: PgPool) {
+            let recipe_id: i32 = sqlx::query_scalar(
+                "INSERT INTO recipe (name, meal_type) VALUES ('Pancakes', 'Breakfast') RETURNING recipe_id",
+            )
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to insert recipe");
+            sqlx::query(
+                "INSERT INTO ingredient (recipe_id, ingredient_order, name, quantity_type, quantity)
+                VALUES ($1, 0, 'Flour', 'Gram', 200)",
+            )
+            .bind(recipe_id)
+            .execute(&pool)
+            .await
+            .expect("Failed to insert ingredients");
+
+            let app = setup_app!(pool);
+
+            let request = test::TestRequest::delete()
+                .uri(&format!("/recipes/{recipe_id}"))
+                .to_request();
+            let recipe: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+
+            assert2::check!(recipe["name"] == "Pancakes");
+            assert2::check!(recipe["ingredients"][0]["name"] == "Flour");
+
+            let request = test::TestRequest::get()
+                .uri(&format!("/recipes/{recipe_id}"))
+                .to_request();
+            let response = test::call_service(&app, request).await;
+
+            assert2::check!(response.status() == StatusCode::NOT_FOUND);
+        }
     }
 
     // TODO: I'd add other tests here checking more for http specific properties like the status
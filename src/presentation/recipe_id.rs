@@ -0,0 +1,144 @@
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use actix_web::{
+    FromRequest, HttpRequest, ResponseError, dev::Payload, http::StatusCode, web::Data,
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::{
+    core::recipe::{RecipeService, ResolvePublicIdError},
+    persistance::recipe::RecipeRepository,
+    video::resolver::VideoResolver,
+};
+
+/// Extracts the `{recipe_id}` path segment, which can either be the sequential `recipe_id` or the
+/// `public_id` of a recipe. Public ids are looked up through the registered
+/// [`PublicIdResolver`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RecipeId(pub(crate) i32);
+
+type ResolveFuture<'a> = Pin<Box<dyn Future<Output = Result<i32, ResolvePublicIdError>> + 'a>>;
+
+/// Resolves public ids without the extractor knowing which repository the [`RecipeService`] uses
+pub(crate) trait PublicIdResolver {
+    fn resolve(&self, public_id: Uuid) -> ResolveFuture<'_>;
+}
+
+impl<RR: RecipeRepository, VR: VideoResolver> PublicIdResolver for RecipeService<RR, VR> {
+    fn resolve(&self, public_id: Uuid) -> ResolveFuture<'_> {
+        Box::pin(self.resolve_public_id(public_id))
+    }
+}
+
+/// Wraps a recipe service to be registered as app data for the [`RecipeId`] extractor
+pub(crate) fn public_id_resolver<RR: RecipeRepository, VR: VideoResolver>(
+    svc: RecipeService<RR, VR>,
+) -> Data<dyn PublicIdResolver> {
+    Data::from(Arc::new(svc) as Arc<dyn PublicIdResolver>)
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum RecipeIdError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<ResolvePublicIdError> for RecipeIdError {
+    fn from(value: ResolvePublicIdError) -> Self {
+        match value {
+            ResolvePublicIdError::Unknown(report) => Self::Unknown(report),
+            ResolvePublicIdError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for RecipeIdError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RecipeIdError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            RecipeIdError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+impl FromRequest for RecipeId {
+    type Error = RecipeIdError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let value = req
+            .match_info()
+            .get("recipe_id")
+            .unwrap_or_default()
+            .to_string();
+        let resolver = req.app_data::<Data<dyn PublicIdResolver>>().cloned();
+
+        Box::pin(async move {
+            if let Ok(recipe_id) = value.parse() {
+                return Ok(Self(recipe_id));
+            }
+
+            let public_id = Uuid::parse_str(&value).map_err(|_| RecipeIdError::NotFound)?;
+            let resolver =
+                resolver.ok_or_else(|| eyre::eyre!("No public id resolver is registered"))?;
+
+            Ok(Self(resolver.resolve(public_id).await?))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{App, HttpResponse, get, test};
+    use sqlx::PgPool;
+
+    use crate::{Postgres, RecipeService};
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    #[get("/recipes/{recipe_id}")]
+    async fn echo(RecipeId(recipe_id): RecipeId) -> HttpResponse {
+        HttpResponse::Ok().body(recipe_id.to_string())
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_should_accept_both_ids(pool: PgPool) {
+        let (recipe_id, public_id) = sqlx::query!(
+            "INSERT INTO recipe (name, meal_type) VALUES ('Soup', 'Dinner') RETURNING recipe_id, public_id"
+        )
+        .fetch_one(&pool)
+        .await
+        .map(|row| (row.recipe_id, row.public_id))
+        .expect("Failed to insert recipe");
+
+        let app = test::init_service(App::new().service(echo).app_data(public_id_resolver(
+            RecipeService::new(Postgres::new(pool), None),
+        )))
+        .await;
+
+        for id in [recipe_id.to_string(), public_id.to_string()] {
+            let request = test::TestRequest::get()
+                .uri(&format!("/recipes/{id}"))
+                .to_request();
+            let body = test::call_and_read_body(&app, request).await;
+
+            assert2::check!(body == recipe_id.to_string());
+        }
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/recipes/{}", Uuid::nil()))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+
+        assert2::check!(response.status() == StatusCode::NOT_FOUND);
+    }
+}
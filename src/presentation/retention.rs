@@ -0,0 +1,166 @@
+use actix_web::{
+    ResponseError,
+    http::StatusCode,
+    web::{self, Data, Json, ServiceConfig},
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    RetentionService,
+    core::retention::{ExpiredRows, RetentionReport},
+    persistance::Repository,
+    presentation::admin::Admin,
+};
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ExpiredRowsDto {
+    pub(crate) cutoff: DateTime<Utc>,
+    pub(crate) count: u64,
+}
+
+impl From<ExpiredRows> for ExpiredRowsDto {
+    fn from(value: ExpiredRows) -> Self {
+        Self {
+            cutoff: value.cutoff,
+            count: value.count,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct RetentionReportDto {
+    pub(crate) dry_run: bool,
+    pub(crate) deletions: Option<ExpiredRowsDto>,
+    pub(crate) revoked_share_links: Option<ExpiredRowsDto>,
+    pub(crate) snapshots: Option<ExpiredRowsDto>,
+}
+
+impl From<RetentionReport> for RetentionReportDto {
+    fn from(value: RetentionReport) -> Self {
+        Self {
+            dry_run: value.dry_run,
+            deletions: value.deletions.map(ExpiredRowsDto::from),
+            revoked_share_links: value.revoked_share_links.map(ExpiredRowsDto::from),
+            snapshots: value.snapshots.map(ExpiredRowsDto::from),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum PreviewRetentionError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::core::retention::PreviewRetentionError> for PreviewRetentionError {
+    fn from(value: crate::core::retention::PreviewRetentionError) -> Self {
+        match value {
+            crate::core::retention::PreviewRetentionError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl ResponseError for PreviewRetentionError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            PreviewRetentionError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Dry run of the retention policy, reporting how many rows the next run would delete
+pub(crate) async fn preview_retention<R: Repository>(
+    _: Admin,
+    svc: Data<RetentionService<R>>,
+) -> Result<Json<RetentionReportDto>, PreviewRetentionError> {
+    Ok(Json(svc.preview().await?.into()))
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route("/admin/retention", web::get().to(preview_retention::<R>));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::time::Duration;
+
+    use actix_web::{App, test};
+    use assert2::check;
+
+    use crate::{
+        core::retention::RetentionPolicy,
+        persistance::retention::ExpiredCountsEntity,
+        presentation::admin::{TEST_ADMIN_AUTHORIZATION, test_admin_token},
+        test_utils::MockRepository,
+    };
+
+    #[actix_web::test]
+    async fn it_should_report_without_purging() {
+        let repository = MockRepository::new();
+        repository.on_count_expired(Ok(ExpiredCountsEntity {
+            deletions: 3,
+            revoked_share_links: 0,
+            snapshots: 0,
+        }));
+        let policy = RetentionPolicy {
+            deletions: Some(Duration::from_secs(24 * 60 * 60)),
+            ..Default::default()
+        };
+
+        let app = test::init_service(
+            App::new()
+                .configure(configure::<MockRepository>)
+                .app_data(Data::new(RetentionService::new(
+                    repository.clone(),
+                    policy,
+                    false,
+                )))
+                .app_data(test_admin_token()),
+        )
+        .await;
+
+        let request = test::TestRequest::get()
+            .uri("/admin/retention")
+            .insert_header(TEST_ADMIN_AUTHORIZATION)
+            .to_request();
+        let report: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+
+        check!(report["dry_run"] == true);
+        check!(report["deletions"]["count"] == 3);
+        check!(report["snapshots"].is_null());
+        check!(repository.calls_to("purge_expired").is_empty());
+    }
+
+    #[actix_web::test]
+    async fn it_should_return_401_without_the_admin_token() {
+        let repository = MockRepository::new();
+
+        let app = test::init_service(
+            App::new()
+                .configure(configure::<MockRepository>)
+                .app_data(Data::new(RetentionService::new(
+                    repository.clone(),
+                    RetentionPolicy::default(),
+                    false,
+                )))
+                .app_data(test_admin_token()),
+        )
+        .await;
+
+        let request = test::TestRequest::get()
+            .uri("/admin/retention")
+            .to_request();
+        let response = test::call_service(&app, request).await;
+
+        check!(response.status() == StatusCode::UNAUTHORIZED);
+        check!(repository.calls().is_empty());
+    }
+}
@@ -0,0 +1,279 @@
+use actix_web::{
+    HttpResponse, ResponseError,
+    http::{StatusCode, header::ContentType},
+    web::{self, Data, Json, Path, Query, ServiceConfig},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    SavedSearchService,
+    core::saved_search::{SavedCriteria, SavedSearch},
+    persistance::Repository,
+    presentation::{
+        json_body::JsonBody,
+        recipe::{MealType, RecipeDto, parse_include},
+        unit_system::Units,
+    },
+};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct SavedCriteriaDto {
+    #[serde(default)]
+    pub(crate) recipe_name: Option<String>,
+    #[serde(default)]
+    pub(crate) ingredient_name: Option<String>,
+    #[serde(default)]
+    pub(crate) meal_type: Option<MealType>,
+    /// Searches the archived recipes instead of the others
+    #[serde(default)]
+    pub(crate) archived: bool,
+    /// Only recipes tagged with every one of these tags are found
+    #[serde(default)]
+    pub(crate) tags: Vec<String>,
+}
+
+impl From<SavedCriteria> for SavedCriteriaDto {
+    fn from(value: SavedCriteria) -> Self {
+        Self {
+            recipe_name: value.recipe_name,
+            ingredient_name: value.ingredient_name,
+            meal_type: value.meal_type.map(Into::into),
+            archived: value.archived,
+            tags: value.tags,
+        }
+    }
+}
+
+impl From<SavedCriteriaDto> for SavedCriteria {
+    fn from(value: SavedCriteriaDto) -> Self {
+        Self {
+            recipe_name: value.recipe_name,
+            ingredient_name: value.ingredient_name,
+            meal_type: value.meal_type.map(Into::into),
+            archived: value.archived,
+            tags: value.tags,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SavedSearchDto {
+    pub(crate) saved_search_id: i32,
+    pub(crate) name: String,
+    pub(crate) criteria: SavedCriteriaDto,
+    pub(crate) created_at: DateTime<Utc>,
+}
+
+impl From<SavedSearch> for SavedSearchDto {
+    fn from(value: SavedSearch) -> Self {
+        Self {
+            saved_search_id: value.saved_search_id,
+            name: value.name,
+            criteria: value.criteria.into(),
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct NewSavedSearchDto {
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) criteria: SavedCriteriaDto,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RunQuery {
+    /// Comma separated list of relations to embed, only `ingredients` is supported
+    include: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum SaveSearchError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The name of a saved search can't be empty")]
+    EmptyName,
+}
+
+impl From<crate::core::saved_search::SaveSearchError> for SaveSearchError {
+    fn from(value: crate::core::saved_search::SaveSearchError) -> Self {
+        match value {
+            crate::core::saved_search::SaveSearchError::Unknown(report) => Self::Unknown(report),
+            crate::core::saved_search::SaveSearchError::EmptyName => Self::EmptyName,
+        }
+    }
+}
+
+impl ResponseError for SaveSearchError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SaveSearchError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SaveSearchError::EmptyName => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ListSavedSearchesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::core::saved_search::ListSavedSearchesError> for ListSavedSearchesError {
+    fn from(value: crate::core::saved_search::ListSavedSearchesError) -> Self {
+        match value {
+            crate::core::saved_search::ListSavedSearchesError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+        }
+    }
+}
+
+impl ResponseError for ListSavedSearchesError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ListSavedSearchesError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum RunSavedSearchError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The saved search could not be found")]
+    NotFound,
+    #[error("Unknown expansion `{0}`")]
+    InvalidInclude(String),
+}
+
+impl From<crate::core::saved_search::RunSavedSearchError> for RunSavedSearchError {
+    fn from(value: crate::core::saved_search::RunSavedSearchError) -> Self {
+        match value {
+            crate::core::saved_search::RunSavedSearchError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::core::saved_search::RunSavedSearchError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for RunSavedSearchError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RunSavedSearchError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            RunSavedSearchError::NotFound => StatusCode::NOT_FOUND,
+            RunSavedSearchError::InvalidInclude(_) => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum DeleteSavedSearchError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The saved search could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::saved_search::DeleteSavedSearchError> for DeleteSavedSearchError {
+    fn from(value: crate::core::saved_search::DeleteSavedSearchError) -> Self {
+        match value {
+            crate::core::saved_search::DeleteSavedSearchError::Unknown(report) => {
+                Self::Unknown(report)
+            }
+            crate::core::saved_search::DeleteSavedSearchError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for DeleteSavedSearchError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            DeleteSavedSearchError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            DeleteSavedSearchError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+pub(crate) async fn save_search<R: Repository>(
+    svc: Data<SavedSearchService<R>>,
+    JsonBody(data): JsonBody<NewSavedSearchDto>,
+) -> Result<HttpResponse, SaveSearchError> {
+    let saved = svc.save_search(&data.name, data.criteria.into()).await?;
+
+    Ok(HttpResponse::Created()
+        .content_type(ContentType::json())
+        .json(SavedSearchDto::from(saved)))
+}
+
+pub(crate) async fn list_saved_searches<R: Repository>(
+    svc: Data<SavedSearchService<R>>,
+) -> Result<Json<Vec<SavedSearchDto>>, ListSavedSearchesError> {
+    let searches = svc.list_saved_searches().await?;
+    Ok(Json(
+        searches.into_iter().map(SavedSearchDto::from).collect(),
+    ))
+}
+
+/// Recipes currently matching a saved search, in the same shape as `/recipes/search`
+pub(crate) async fn run_saved_search<R: Repository>(
+    svc: Data<SavedSearchService<R>>,
+    path: Path<i32>,
+    Query(query): Query<RunQuery>,
+    Units(units): Units,
+) -> Result<Json<Vec<RecipeDto>>, RunSavedSearchError> {
+    let include_ingredients =
+        parse_include(query.include.as_deref()).map_err(RunSavedSearchError::InvalidInclude)?;
+
+    let recipes = svc
+        .run_saved_search(path.into_inner(), include_ingredients)
+        .await?;
+
+    Ok(Json(
+        recipes
+            .into_iter()
+            .map(|recipe| RecipeDto::from(recipe).into_system(units))
+            .collect(),
+    ))
+}
+
+pub(crate) async fn delete_saved_search<R: Repository>(
+    svc: Data<SavedSearchService<R>>,
+    path: Path<i32>,
+) -> Result<HttpResponse, DeleteSavedSearchError> {
+    svc.delete_saved_search(path.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route("/searches", web::get().to(list_saved_searches::<R>))
+        .route("/searches", web::post().to(save_search::<R>))
+        .route(
+            "/searches/{saved_search_id}/run",
+            web::get().to(run_saved_search::<R>),
+        )
+        .route(
+            "/searches/{saved_search_id}",
+            web::delete().to(delete_saved_search::<R>),
+        );
+}
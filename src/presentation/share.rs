@@ -0,0 +1,160 @@
+use actix_web::{
+    HttpResponse, HttpResponseBuilder, ResponseError,
+    http::{
+        StatusCode,
+        header::{ContentType, RETRY_AFTER},
+    },
+    web::{self, Data, ServiceConfig},
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    ShareService,
+    core::share::ShareByEmail,
+    persistance::Repository,
+    presentation::{json_body::JsonBody, recipe_id::RecipeId},
+};
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ShareByEmailDto {
+    pub(crate) to: String,
+    pub(crate) message: Option<String>,
+}
+
+impl From<ShareByEmailDto> for ShareByEmail {
+    fn from(value: ShareByEmailDto) -> Self {
+        Self {
+            to: value.to,
+            message: value.message,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ShareRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+    #[error("`{0}` is not a valid email address")]
+    InvalidRecipient(String),
+    #[error("Too many recipes have been shared recently, try again later")]
+    RateLimited { retry_after_secs: u64 },
+    #[error("Sharing by email is not configured on this instance")]
+    Disabled,
+}
+
+impl From<crate::core::share::ShareRecipeError> for ShareRecipeError {
+    fn from(value: crate::core::share::ShareRecipeError) -> Self {
+        match value {
+            crate::core::share::ShareRecipeError::Unknown(report) => Self::Unknown(report),
+            crate::core::share::ShareRecipeError::NotFound => Self::NotFound,
+            crate::core::share::ShareRecipeError::InvalidRecipient(to) => {
+                Self::InvalidRecipient(to)
+            }
+            crate::core::share::ShareRecipeError::RateLimited { retry_after } => {
+                Self::RateLimited {
+                    retry_after_secs: retry_after.as_secs().max(1),
+                }
+            }
+            crate::core::share::ShareRecipeError::Disabled => Self::Disabled,
+        }
+    }
+}
+
+impl ResponseError for ShareRecipeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ShareRecipeError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ShareRecipeError::NotFound => StatusCode::NOT_FOUND,
+            ShareRecipeError::InvalidRecipient(_) => StatusCode::BAD_REQUEST,
+            ShareRecipeError::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            ShareRecipeError::Disabled => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut response = HttpResponseBuilder::new(self.status_code());
+
+        if let ShareRecipeError::RateLimited { retry_after_secs } = self {
+            response.insert_header((RETRY_AFTER, retry_after_secs.to_string()));
+        }
+
+        response
+            .content_type(ContentType::plaintext())
+            .body(self.to_string())
+    }
+}
+
+pub(crate) async fn share_recipe<R: Repository>(
+    svc: Data<ShareService<R>>,
+    RecipeId(recipe_id): RecipeId,
+    JsonBody(data): JsonBody<ShareByEmailDto>,
+) -> Result<HttpResponse, ShareRecipeError> {
+    svc.share_by_email(recipe_id, data.into()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route(
+        "/recipes/{recipe_id}/share",
+        web::post().to(share_recipe::<R>),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{App, test};
+    use sqlx::PgPool;
+    use std::time::Duration;
+
+    use crate::Postgres;
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_should_return_503_when_mail_is_not_configured(pool: PgPool) {
+        let recipe_id = sqlx::query_scalar!(
+            "INSERT INTO recipe (name, meal_type) VALUES ('Pancakes', 'Breakfast') RETURNING recipe_id"
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to insert recipe");
+
+        let share_service =
+            ShareService::new(Postgres::new(pool), None, 1, Duration::from_secs(60));
+
+        let app = test::init_service(
+            App::new()
+                .configure(configure::<Postgres>)
+                .app_data(Data::new(share_service)),
+        )
+        .await;
+
+        let request = test::TestRequest::post()
+            .uri(&format!("/recipes/{recipe_id}/share"))
+            .insert_header(ContentType::json())
+            .set_payload(r#"{ "to": "friend@example.com" }"#)
+            .to_request();
+        let response = test::call_service(&app, request).await;
+
+        assert2::check!(response.status() == StatusCode::SERVICE_UNAVAILABLE);
+
+        // Nothing was sent, so the rate limit still has room
+        let request = test::TestRequest::post()
+            .uri(&format!("/recipes/{recipe_id}/share"))
+            .insert_header(ContentType::json())
+            .set_payload(r#"{ "to": "friend@example.com" }"#)
+            .to_request();
+        let response = test::call_service(&app, request).await;
+
+        assert2::check!(response.status() == StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
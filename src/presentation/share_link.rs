@@ -0,0 +1,210 @@
+use actix_web::{
+    HttpResponse, ResponseError,
+    http::{StatusCode, header::ContentType},
+    web::{self, Data, Json, Path, ServiceConfig},
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    ShareLinkService,
+    core::share_link::ShareLink,
+    persistance::Repository,
+    presentation::{recipe::RecipeDto, recipe_id::RecipeId},
+};
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ShareLinkDto {
+    pub(crate) share_link_id: i32,
+    pub(crate) recipe_id: i32,
+    pub(crate) token: String,
+    /// Path of the read-only view this link grants access to
+    pub(crate) url: String,
+    pub(crate) created_at: DateTime<Utc>,
+}
+
+impl From<ShareLink> for ShareLinkDto {
+    fn from(value: ShareLink) -> Self {
+        Self {
+            share_link_id: value.share_link_id,
+            recipe_id: value.recipe_id,
+            url: format!("/shared/{}", value.token),
+            token: value.token,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum CreateShareLinkError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::share_link::CreateShareLinkError> for CreateShareLinkError {
+    fn from(value: crate::core::share_link::CreateShareLinkError) -> Self {
+        match value {
+            crate::core::share_link::CreateShareLinkError::Unknown(report) => Self::Unknown(report),
+            crate::core::share_link::CreateShareLinkError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for CreateShareLinkError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            CreateShareLinkError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            CreateShareLinkError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ListShareLinksError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::share_link::ListShareLinksError> for ListShareLinksError {
+    fn from(value: crate::core::share_link::ListShareLinksError) -> Self {
+        match value {
+            crate::core::share_link::ListShareLinksError::Unknown(report) => Self::Unknown(report),
+            crate::core::share_link::ListShareLinksError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for ListShareLinksError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ListShareLinksError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ListShareLinksError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum RevokeShareLinkError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The share link could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::share_link::RevokeShareLinkError> for RevokeShareLinkError {
+    fn from(value: crate::core::share_link::RevokeShareLinkError) -> Self {
+        match value {
+            crate::core::share_link::RevokeShareLinkError::Unknown(report) => Self::Unknown(report),
+            crate::core::share_link::RevokeShareLinkError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for RevokeShareLinkError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RevokeShareLinkError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            RevokeShareLinkError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum GetSharedRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The shared recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::share_link::GetSharedRecipeError> for GetSharedRecipeError {
+    fn from(value: crate::core::share_link::GetSharedRecipeError) -> Self {
+        match value {
+            crate::core::share_link::GetSharedRecipeError::Unknown(report) => Self::Unknown(report),
+            crate::core::share_link::GetSharedRecipeError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for GetSharedRecipeError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            GetSharedRecipeError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            GetSharedRecipeError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+pub(crate) async fn create_share_link<R: Repository>(
+    svc: Data<ShareLinkService<R>>,
+    RecipeId(recipe_id): RecipeId,
+) -> Result<HttpResponse, CreateShareLinkError> {
+    let link = svc.create_share_link(recipe_id).await?;
+
+    Ok(HttpResponse::Created()
+        .content_type(ContentType::json())
+        .json(ShareLinkDto::from(link)))
+}
+
+pub(crate) async fn list_share_links<R: Repository>(
+    svc: Data<ShareLinkService<R>>,
+    RecipeId(recipe_id): RecipeId,
+) -> Result<Json<Vec<ShareLinkDto>>, ListShareLinksError> {
+    let links = svc.list_share_links(recipe_id).await?;
+    Ok(Json(links.into_iter().map(ShareLinkDto::from).collect()))
+}
+
+pub(crate) async fn revoke_share_link<R: Repository>(
+    svc: Data<ShareLinkService<R>>,
+    RecipeId(recipe_id): RecipeId,
+    path: Path<(String, i32)>,
+) -> Result<HttpResponse, RevokeShareLinkError> {
+    let (_, share_link_id) = path.into_inner();
+    svc.revoke_share_link(recipe_id, share_link_id).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub(crate) async fn get_shared_recipe<R: Repository>(
+    svc: Data<ShareLinkService<R>>,
+    path: Path<String>,
+) -> Result<Json<RecipeDto>, GetSharedRecipeError> {
+    let recipe = svc.get_shared_recipe(&path.into_inner()).await?;
+    Ok(Json(recipe.into()))
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route(
+        "/recipes/{recipe_id}/share-link",
+        web::post().to(create_share_link::<R>),
+    )
+    .route(
+        "/recipes/{recipe_id}/share-links",
+        web::get().to(list_share_links::<R>),
+    )
+    .route(
+        "/recipes/{recipe_id}/share-links/{share_link_id}",
+        web::delete().to(revoke_share_link::<R>),
+    )
+    .route("/shared/{token}", web::get().to(get_shared_recipe::<R>));
+}
@@ -0,0 +1,252 @@
+use actix_web::{
+    HttpResponse, ResponseError,
+    http::{
+        StatusCode,
+        header::{ContentDisposition, ContentType, DispositionParam, DispositionType},
+    },
+    web::{self, Data, Json, Path, ServiceConfig},
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    SnapshotService,
+    core::snapshot::Snapshot,
+    persistance::Repository,
+    presentation::{admin::Admin, recipe::NewRecipeDto},
+};
+
+#[derive(Debug, Serialize)]
+pub(crate) struct SnapshotDto {
+    pub(crate) snapshot_id: i32,
+    pub(crate) created_at: DateTime<Utc>,
+    pub(crate) recipe_count: u64,
+}
+
+impl From<Snapshot> for SnapshotDto {
+    fn from(value: Snapshot) -> Self {
+        Self {
+            snapshot_id: value.snapshot_id,
+            created_at: value.created_at,
+            recipe_count: value.recipe_count,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum CreateSnapshotError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::core::snapshot::CreateSnapshotError> for CreateSnapshotError {
+    fn from(value: crate::core::snapshot::CreateSnapshotError) -> Self {
+        match value {
+            crate::core::snapshot::CreateSnapshotError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl ResponseError for CreateSnapshotError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            CreateSnapshotError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ListSnapshotsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::core::snapshot::ListSnapshotsError> for ListSnapshotsError {
+    fn from(value: crate::core::snapshot::ListSnapshotsError) -> Self {
+        match value {
+            crate::core::snapshot::ListSnapshotsError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl ResponseError for ListSnapshotsError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ListSnapshotsError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum DownloadSnapshotError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The snapshot could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::snapshot::SnapshotRecipesError> for DownloadSnapshotError {
+    fn from(value: crate::core::snapshot::SnapshotRecipesError) -> Self {
+        match value {
+            crate::core::snapshot::SnapshotRecipesError::Unknown(report) => Self::Unknown(report),
+            crate::core::snapshot::SnapshotRecipesError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for DownloadSnapshotError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            DownloadSnapshotError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            DownloadSnapshotError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+/// Records a snapshot of all recipes, to fall back on before risky changes like large imports
+pub(crate) async fn create_snapshot<R: Repository>(
+    _: Admin,
+    svc: Data<SnapshotService<R>>,
+) -> Result<HttpResponse, CreateSnapshotError> {
+    let snapshot = svc.create_snapshot().await?;
+
+    Ok(HttpResponse::Created()
+        .content_type(ContentType::json())
+        .json(SnapshotDto::from(snapshot)))
+}
+
+pub(crate) async fn list_snapshots<R: Repository>(
+    _: Admin,
+    svc: Data<SnapshotService<R>>,
+) -> Result<Json<Vec<SnapshotDto>>, ListSnapshotsError> {
+    let snapshots = svc.list_snapshots().await?;
+    Ok(Json(snapshots.into_iter().map(SnapshotDto::from).collect()))
+}
+
+/// Downloads the recipes of a snapshot as a JSON array, which `gecko-recipes import` accepts
+pub(crate) async fn download_snapshot<R: Repository>(
+    _: Admin,
+    svc: Data<SnapshotService<R>>,
+    path: Path<i32>,
+) -> Result<HttpResponse, DownloadSnapshotError> {
+    let snapshot_id = path.into_inner();
+    let recipes = svc.snapshot_recipes(snapshot_id).await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::json())
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(format!(
+                "snapshot-{snapshot_id}.json"
+            ))],
+        })
+        .json(
+            recipes
+                .into_iter()
+                .map(NewRecipeDto::from)
+                .collect::<Vec<_>>(),
+        ))
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route("/admin/snapshots", web::get().to(list_snapshots::<R>))
+        .route("/admin/snapshots", web::post().to(create_snapshot::<R>))
+        .route(
+            "/admin/snapshots/{snapshot_id}",
+            web::get().to(download_snapshot::<R>),
+        );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{App, http::header::CONTENT_DISPOSITION, test};
+    use assert2::{check, let_assert};
+    use sqlx::PgPool;
+
+    use crate::{
+        Postgres,
+        presentation::admin::{TEST_ADMIN_AUTHORIZATION, test_admin_token},
+    };
+
+    static MIGRATOR: sqlx::migrate::Migrator = sqlx::migrate!();
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_should_download_a_created_snapshot(pool: PgPool) {
+        sqlx::query!(
+            "INSERT INTO recipe (name, description, meal_type) VALUES ('Toast', 'Toast the bread.', 'Breakfast')"
+        )
+        .execute(&pool)
+        .await
+        .expect("Failed to insert recipe");
+
+        let app = test::init_service(
+            App::new()
+                .configure(configure::<Postgres>)
+                .app_data(Data::new(SnapshotService::new(Postgres::new(pool))))
+                .app_data(test_admin_token()),
+        )
+        .await;
+
+        let request = test::TestRequest::post()
+            .uri("/admin/snapshots")
+            .insert_header(TEST_ADMIN_AUTHORIZATION)
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        check!(response.status() == StatusCode::CREATED);
+        let snapshot: serde_json::Value = test::read_body_json(response).await;
+        check!(snapshot["recipe_count"] == 1);
+
+        let request = test::TestRequest::get()
+            .uri(&format!("/admin/snapshots/{}", snapshot["snapshot_id"]))
+            .insert_header(TEST_ADMIN_AUTHORIZATION)
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        check!(response.status() == StatusCode::OK);
+        let_assert!(Some(disposition) = response.headers().get(CONTENT_DISPOSITION));
+        check!(
+            disposition
+                .to_str()
+                .unwrap_or_default()
+                .starts_with("attachment")
+        );
+
+        let recipes: serde_json::Value = test::read_body_json(response).await;
+        check!(recipes[0]["name"] == "Toast");
+        check!(recipes[0]["meal_type"] == "Breakfast");
+    }
+
+    #[sqlx::test(migrator = "MIGRATOR")]
+    async fn it_should_return_401_without_the_admin_token(pool: PgPool) {
+        let app = test::init_service(
+            App::new()
+                .configure(configure::<Postgres>)
+                .app_data(Data::new(SnapshotService::new(Postgres::new(pool))))
+                .app_data(test_admin_token()),
+        )
+        .await;
+
+        for request in [
+            test::TestRequest::get().uri("/admin/snapshots"),
+            test::TestRequest::post().uri("/admin/snapshots"),
+            test::TestRequest::get().uri("/admin/snapshots/1"),
+        ] {
+            let response = test::call_service(&app, request.to_request()).await;
+            check!(response.status() == StatusCode::UNAUTHORIZED);
+        }
+    }
+}
@@ -0,0 +1,337 @@
+use actix_web::{
+    ResponseError,
+    http::StatusCode,
+    web::{self, Data, Json, Query, ServiceConfig},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::{
+    SyncService,
+    core::{
+        sync::{ChangeSet, Conflict, ConflictStrategy, RecipeChange, Upload, UploadOutcome},
+        unit::UnitSystem,
+    },
+    persistance::Repository,
+    presentation::{
+        duplicate::MergeFieldDto,
+        json_body::JsonBody,
+        recipe::{NewRecipeDto, RecipeDto},
+        unit_system::Units,
+    },
+};
+
+const DEFAULT_SYNC_LIMIT: i64 = 100;
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct SyncQuery {
+    /// Cursor returned by the previous pull, everything is returned when missing
+    since: Option<i64>,
+    /// Maximum number of recipe events to go through, capped at 1000
+    limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum RecipeChangeDto {
+    Created {
+        recipe: RecipeDto,
+    },
+    Updated {
+        recipe: RecipeDto,
+    },
+    Deleted {
+        public_id: Uuid,
+        deleted_at: DateTime<Utc>,
+    },
+    Archived {
+        public_id: Uuid,
+        archived_at: DateTime<Utc>,
+    },
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ChangeSetDto {
+    pub(crate) changes: Vec<RecipeChangeDto>,
+    /// Passed as `since` on the next pull
+    pub(crate) cursor: i64,
+    /// Whether another pull is needed to catch up
+    pub(crate) has_more: bool,
+}
+
+impl From<ChangeSet> for ChangeSetDto {
+    fn from(value: ChangeSet) -> Self {
+        Self {
+            changes: value
+                .changes
+                .into_iter()
+                .map(|change| match change {
+                    RecipeChange::Created(recipe) => RecipeChangeDto::Created {
+                        recipe: recipe.into(),
+                    },
+                    RecipeChange::Updated(recipe) => RecipeChangeDto::Updated {
+                        recipe: recipe.into(),
+                    },
+                    RecipeChange::Deleted {
+                        public_id,
+                        deleted_at,
+                    } => RecipeChangeDto::Deleted {
+                        public_id,
+                        deleted_at,
+                    },
+                    RecipeChange::Archived {
+                        public_id,
+                        archived_at,
+                    } => RecipeChangeDto::Archived {
+                        public_id,
+                        archived_at,
+                    },
+                })
+                .collect(),
+            cursor: value.cursor,
+            has_more: value.has_more,
+        }
+    }
+}
+
+impl ChangeSetDto {
+    /// Converts the quantities of the changed recipes to the given system
+    fn into_system(mut self, system: UnitSystem) -> Self {
+        self.changes = self
+            .changes
+            .into_iter()
+            .map(|change| match change {
+                RecipeChangeDto::Created { recipe } => RecipeChangeDto::Created {
+                    recipe: recipe.into_system(system),
+                },
+                RecipeChangeDto::Updated { recipe } => RecipeChangeDto::Updated {
+                    recipe: recipe.into_system(system),
+                },
+                tombstone
+                @ (RecipeChangeDto::Deleted { .. } | RecipeChangeDto::Archived { .. }) => tombstone,
+            })
+            .collect();
+        self
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub(crate) enum UploadDto {
+    Create {
+        recipe: NewRecipeDto,
+    },
+    Update {
+        public_id: Uuid,
+        /// Cursor of the pull the client's copy is from, conflicts are only detected with it
+        #[serde(default)]
+        base_cursor: Option<i64>,
+        recipe: NewRecipeDto,
+    },
+    Delete {
+        public_id: Uuid,
+        #[serde(default)]
+        base_cursor: Option<i64>,
+    },
+}
+
+impl From<UploadDto> for Upload {
+    fn from(value: UploadDto) -> Self {
+        match value {
+            UploadDto::Create { recipe } => Self::Create(recipe.into()),
+            UploadDto::Update {
+                public_id,
+                base_cursor,
+                recipe,
+            } => Self::Update {
+                public_id,
+                base_cursor,
+                recipe: recipe.into(),
+            },
+            UploadDto::Delete {
+                public_id,
+                base_cursor,
+            } => Self::Delete {
+                public_id,
+                base_cursor,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ConflictStrategyDto {
+    ServerWins,
+    ClientWins,
+    #[default]
+    Manual,
+}
+
+impl From<ConflictStrategyDto> for ConflictStrategy {
+    fn from(value: ConflictStrategyDto) -> Self {
+        match value {
+            ConflictStrategyDto::ServerWins => Self::ServerWins,
+            ConflictStrategyDto::ClientWins => Self::ClientWins,
+            ConflictStrategyDto::Manual => Self::Manual,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct UploadQuery {
+    /// How to settle conflicts, they are handed back to the client when missing
+    #[serde(default)]
+    strategy: ConflictStrategyDto,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct UploadChangesDto {
+    pub(crate) changes: Vec<UploadDto>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub(crate) enum UploadOutcomeDto {
+    Applied {
+        public_id: Uuid,
+    },
+    NotFound,
+    Rejected {
+        error: String,
+    },
+    Conflict {
+        /// Recipe as it is here
+        server: Box<RecipeDto>,
+        /// Recipe as the client uploaded it, `null` when the client deleted it
+        client: Option<NewRecipeDto>,
+        /// Fields which differ between both versions
+        fields: Vec<MergeFieldDto>,
+    },
+    Discarded {
+        public_id: Uuid,
+    },
+}
+
+impl From<UploadOutcome> for UploadOutcomeDto {
+    fn from(value: UploadOutcome) -> Self {
+        match value {
+            UploadOutcome::Applied { public_id } => Self::Applied { public_id },
+            UploadOutcome::NotFound => Self::NotFound,
+            UploadOutcome::Rejected(error) => Self::Rejected { error },
+            UploadOutcome::Conflict(conflict) => {
+                let Conflict {
+                    server,
+                    client,
+                    fields,
+                } = *conflict;
+
+                Self::Conflict {
+                    server: Box::new(server.into()),
+                    client: client.map(NewRecipeDto::from),
+                    fields: fields.into_iter().map(MergeFieldDto::from).collect(),
+                }
+            }
+            UploadOutcome::Discarded { public_id } => Self::Discarded { public_id },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct UploadResultsDto {
+    /// Outcome of every uploaded change, in the order they were uploaded
+    pub(crate) results: Vec<UploadOutcomeDto>,
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ChangesSinceError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::core::sync::ChangesSinceError> for ChangesSinceError {
+    fn from(value: crate::core::sync::ChangesSinceError) -> Self {
+        match value {
+            crate::core::sync::ChangesSinceError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl ResponseError for ChangesSinceError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ChangesSinceError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum UploadChangesError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::core::sync::UploadChangesError> for UploadChangesError {
+    fn from(value: crate::core::sync::UploadChangesError) -> Self {
+        match value {
+            crate::core::sync::UploadChangesError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl ResponseError for UploadChangesError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            UploadChangesError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Recipes created, updated and deleted since the cursor
+pub(crate) async fn changes_since<R: Repository>(
+    svc: Data<SyncService<R>>,
+    Query(query): Query<SyncQuery>,
+    Units(units): Units,
+) -> Result<Json<ChangeSetDto>, ChangesSinceError> {
+    let change_set = svc
+        .changes_since(
+            query.since.unwrap_or(0),
+            query.limit.unwrap_or(DEFAULT_SYNC_LIMIT),
+        )
+        .await?;
+
+    Ok(Json(ChangeSetDto::from(change_set).into_system(units)))
+}
+
+/// Applies the changes a client made while offline
+pub(crate) async fn upload_changes<R: Repository>(
+    svc: Data<SyncService<R>>,
+    Query(query): Query<UploadQuery>,
+    JsonBody(data): JsonBody<UploadChangesDto>,
+) -> Result<Json<UploadResultsDto>, UploadChangesError> {
+    let outcomes = svc
+        .upload_changes(
+            data.changes.into_iter().map(Upload::from).collect(),
+            query.strategy.into(),
+        )
+        .await?;
+
+    Ok(Json(UploadResultsDto {
+        results: outcomes.into_iter().map(UploadOutcomeDto::from).collect(),
+    }))
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route("/sync", web::get().to(changes_since::<R>))
+        .route("/sync", web::post().to(upload_changes::<R>));
+}
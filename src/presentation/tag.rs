@@ -0,0 +1,181 @@
+use std::future::{Ready, ready};
+
+use actix_web::{
+    FromRequest, HttpRequest, ResponseError,
+    dev::Payload,
+    http::StatusCode,
+    web::{self, Data, Json, Query, ServiceConfig},
+};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    TagService,
+    core::tag::TagUsage,
+    persistance::Repository,
+    presentation::{json_body::JsonBody, recipe_id::RecipeId},
+};
+
+/// Extracts every `tag` query parameter, so `?tag=quick&tag=vegetarian` filters on both tags
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Tags(pub(crate) Vec<String>);
+
+impl FromRequest for Tags {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(
+            Query::<Vec<(String, String)>>::from_query(req.query_string())
+                .map(|Query(pairs)| {
+                    Self(
+                        pairs
+                            .into_iter()
+                            .filter(|(key, _)| key == "tag")
+                            .map(|(_, value)| value)
+                            .collect(),
+                    )
+                })
+                .map_err(Into::into),
+        )
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct TagUsageDto {
+    pub(crate) name: String,
+    /// Number of recipes tagged with the tag
+    pub(crate) recipe_count: u64,
+}
+
+impl From<TagUsage> for TagUsageDto {
+    fn from(value: TagUsage) -> Self {
+        Self {
+            name: value.name,
+            recipe_count: value.recipe_count,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum ListTagsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+}
+
+impl From<crate::core::tag::ListTagsError> for ListTagsError {
+    fn from(value: crate::core::tag::ListTagsError) -> Self {
+        match value {
+            crate::core::tag::ListTagsError::Unknown(report) => Self::Unknown(report),
+        }
+    }
+}
+
+impl ResponseError for ListTagsError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            ListTagsError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum RecipeTagsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::tag::RecipeTagsError> for RecipeTagsError {
+    fn from(value: crate::core::tag::RecipeTagsError) -> Self {
+        match value {
+            crate::core::tag::RecipeTagsError::Unknown(report) => Self::Unknown(report),
+            crate::core::tag::RecipeTagsError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for RecipeTagsError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RecipeTagsError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            RecipeTagsError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum SetRecipeTagsError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+    #[error("Tags can't be empty")]
+    EmptyTag,
+}
+
+impl From<crate::core::tag::SetRecipeTagsError> for SetRecipeTagsError {
+    fn from(value: crate::core::tag::SetRecipeTagsError) -> Self {
+        match value {
+            crate::core::tag::SetRecipeTagsError::Unknown(report) => Self::Unknown(report),
+            crate::core::tag::SetRecipeTagsError::NotFound => Self::NotFound,
+            crate::core::tag::SetRecipeTagsError::EmptyTag => Self::EmptyTag,
+        }
+    }
+}
+
+impl ResponseError for SetRecipeTagsError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SetRecipeTagsError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            SetRecipeTagsError::NotFound => StatusCode::NOT_FOUND,
+            SetRecipeTagsError::EmptyTag => StatusCode::BAD_REQUEST,
+        }
+    }
+}
+
+/// Tag cloud, every tag in use with the number of recipes tagged with it
+pub(crate) async fn list_tags<R: Repository>(
+    svc: Data<TagService<R>>,
+) -> Result<Json<Vec<TagUsageDto>>, ListTagsError> {
+    let tags = svc.list_tags().await?;
+    Ok(Json(tags.into_iter().map(TagUsageDto::from).collect()))
+}
+
+pub(crate) async fn recipe_tags<R: Repository>(
+    svc: Data<TagService<R>>,
+    RecipeId(recipe_id): RecipeId,
+) -> Result<Json<Vec<String>>, RecipeTagsError> {
+    Ok(Json(svc.recipe_tags(recipe_id).await?))
+}
+
+/// Replaces the tags of a recipe with the tags in the body
+pub(crate) async fn set_recipe_tags<R: Repository>(
+    svc: Data<TagService<R>>,
+    RecipeId(recipe_id): RecipeId,
+    JsonBody(tags): JsonBody<Vec<String>>,
+) -> Result<Json<Vec<String>>, SetRecipeTagsError> {
+    Ok(Json(svc.set_recipe_tags(recipe_id, &tags).await?))
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route("/tags", web::get().to(list_tags::<R>))
+        .route("/recipes/{recipe_id}/tags", web::get().to(recipe_tags::<R>))
+        .route(
+            "/recipes/{recipe_id}/tags",
+            web::put().to(set_recipe_tags::<R>),
+        );
+}
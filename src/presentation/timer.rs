@@ -0,0 +1,125 @@
+use actix_web::{
+    self, ResponseError,
+    http::StatusCode,
+    web::{self, Data, Json, ServiceConfig},
+};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    RecipeService, core::timer::Timer, persistance::Repository, presentation::recipe_id::RecipeId,
+};
+
+#[derive(Debug, Serialize)]
+pub(crate) struct TimerDto {
+    pub(crate) name: String,
+    /// ISO-8601 duration, like `PT20M`
+    pub(crate) duration: String,
+    pub(crate) duration_seconds: u64,
+}
+
+impl From<Timer> for TimerDto {
+    fn from(value: Timer) -> Self {
+        Self {
+            name: value.name,
+            duration: super::duration::to_iso8601(value.duration),
+            duration_seconds: value.duration.as_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub(crate) enum RecipeTimersError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("The recipe could not be found")]
+    NotFound,
+}
+
+impl From<crate::core::recipe::GetRecipeError> for RecipeTimersError {
+    fn from(value: crate::core::recipe::GetRecipeError) -> Self {
+        match value {
+            crate::core::recipe::GetRecipeError::Unknown(report) => Self::Unknown(report),
+            crate::core::recipe::GetRecipeError::NotFound => Self::NotFound,
+        }
+    }
+}
+
+impl ResponseError for RecipeTimersError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RecipeTimersError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            RecipeTimersError::NotFound => StatusCode::NOT_FOUND,
+        }
+    }
+}
+
+pub(crate) async fn recipe_timers<R: Repository>(
+    svc: Data<RecipeService<R>>,
+    RecipeId(recipe_id): RecipeId,
+) -> Result<Json<Vec<TimerDto>>, RecipeTimersError> {
+    let timers = svc.recipe_timers(recipe_id).await?;
+    Ok(Json(timers.into_iter().map(TimerDto::from).collect()))
+}
+
+pub(crate) fn configure<R: Repository>(cfg: &mut ServiceConfig) {
+    cfg.route(
+        "/recipes/{recipe_id}/timers",
+        web::get().to(recipe_timers::<R>),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web::{App, test};
+    use uuid::Uuid;
+
+    use crate::{
+        persistance::recipe::{MealType, RecipeEntity},
+        test_utils::MockRepository,
+    };
+
+    #[actix_web::test]
+    async fn it_should_suggest_timers_from_the_description() {
+        let repository = MockRepository::new();
+        repository.on_get_recipe(Ok(RecipeEntity {
+            recipe_id: 1,
+            name: "Soup".to_string(),
+            description: Some("Simmer for 20 minutes.".to_string()),
+            ingredients: vec![],
+            cooking_time: None,
+            meal_type: MealType::Dinner,
+            last_cooked_at: None,
+            video_url: None,
+            video_title: None,
+            video_thumbnail_url: None,
+            pairing: None,
+            pairing_kind: None,
+            forked_from: None,
+            public_id: Uuid::nil(),
+        }));
+
+        let app = test::init_service(
+            App::new()
+                .configure(configure::<MockRepository>)
+                .app_data(Data::new(RecipeService::new(repository.clone(), None))),
+        )
+        .await;
+
+        let request = test::TestRequest::get()
+            .uri("/recipes/1/timers")
+            .to_request();
+        let body = test::call_and_read_body(&app, request).await;
+
+        assert2::check!(
+            body == r#"[{"name":"Simmer for 20 minutes","duration":"PT20M","duration_seconds":1200}]"#
+        );
+        assert2::check!(repository.calls_to("get_recipe")[0].arguments == "(1,)");
+    }
+}
@@ -0,0 +1,44 @@
+use std::future::{Ready, ready};
+
+use actix_web::{FromRequest, HttpRequest, dev::Payload, web::Query};
+use serde::Deserialize;
+
+use crate::core::unit::UnitSystem;
+
+/// Extracts the `unit_system` query parameter, recipes are shown in metric when it is missing
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Units(pub(crate) UnitSystem);
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum UnitSystemDto {
+    Metric,
+    Imperial,
+}
+
+impl From<UnitSystemDto> for UnitSystem {
+    fn from(value: UnitSystemDto) -> Self {
+        match value {
+            UnitSystemDto::Metric => Self::Metric,
+            UnitSystemDto::Imperial => Self::Imperial,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct UnitsQuery {
+    unit_system: Option<UnitSystemDto>,
+}
+
+impl FromRequest for Units {
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        ready(
+            Query::<UnitsQuery>::from_query(req.query_string())
+                .map(|Query(query)| Self(query.unit_system.map(Into::into).unwrap_or_default()))
+                .map_err(Into::into),
+        )
+    }
+}
@@ -0,0 +1,119 @@
+use std::{sync::Arc, time::Duration};
+
+use eyre::{Context, OptionExt};
+use reqwest::{Client, Url};
+use secrecy::{ExposeSecret, SecretBox};
+use serde::{Deserialize, Serialize};
+
+use crate::suggest::suggester::{RecipeSuggester, SuggestRecipeError};
+
+/// Language models can take a while to write a whole recipe
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+const SYSTEM_PROMPT: &str = "You write cooking recipes. Answer with plain text only, no markdown. \
+Put the name of the recipe on the first line. Then list one ingredient per line as \
+`<quantity> <unit> <name>`, using g, kg, ml or l as the unit or leaving it out for countable \
+ingredients. Finish with the instructions as short sentences, mentioning breakfast or lunch when \
+the recipe is meant for it.";
+
+/// Suggests recipes through any API compatible with the OpenAI chat completions endpoint
+#[derive(Debug, Clone)]
+pub struct OpenAi {
+    client: Client,
+    endpoint: Url,
+    api_key: Option<Arc<SecretBox<str>>>,
+    model: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: [ChatMessage<'a>; 2],
+}
+
+#[derive(Debug, Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatResponseMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponseMessage {
+    content: Option<String>,
+}
+
+impl OpenAi {
+    /// Creates a suggester for an API like `https://api.openai.com/v1`
+    pub fn new(
+        base_url: &str,
+        api_key: Option<SecretBox<str>>,
+        model: String,
+    ) -> eyre::Result<Self> {
+        let endpoint = format!("{}/chat/completions", base_url.trim_end_matches('/'))
+            .parse()
+            .wrap_err("Failed to parse LLM url")?;
+
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .wrap_err("Failed to build HTTP client")?;
+
+        Ok(Self {
+            client,
+            endpoint,
+            api_key: api_key.map(Arc::new),
+            model,
+        })
+    }
+}
+
+impl RecipeSuggester for OpenAi {
+    async fn suggest(&self, prompt: &str) -> Result<String, SuggestRecipeError> {
+        let mut request = self.client.post(self.endpoint.clone()).json(&ChatRequest {
+            model: &self.model,
+            messages: [
+                ChatMessage {
+                    role: "system",
+                    content: SYSTEM_PROMPT,
+                },
+                ChatMessage {
+                    role: "user",
+                    content: prompt,
+                },
+            ],
+        });
+
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key.expose_secret());
+        }
+
+        let response = request
+            .send()
+            .await
+            .wrap_err("Failed to reach LLM provider")?
+            .error_for_status()
+            .wrap_err("LLM provider returned an error")?
+            .json::<ChatResponse>()
+            .await
+            .wrap_err("Failed to parse LLM response")?;
+
+        let content = response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .ok_or_eyre("LLM response did not contain a message")?;
+
+        Ok(content)
+    }
+}
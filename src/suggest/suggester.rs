@@ -0,0 +1,33 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SuggestRecipeError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("Generating recipes is not configured on this instance")]
+    Disabled,
+}
+
+pub trait RecipeSuggester: std::fmt::Debug + Clone + Send + Sync + 'static {
+    /// Writes a recipe matching the prompt as plain text. The name goes on the first line,
+    /// followed by one ingredient per line like `200 g rice` and finally the instructions.
+    fn suggest(
+        &self,
+        prompt: &str,
+    ) -> impl Future<Output = Result<String, SuggestRecipeError>> + Send;
+}
+
+/// Allows generating recipes to be optional, an unconfigured suggester fails every prompt with
+/// [`SuggestRecipeError::Disabled`]
+impl<S: RecipeSuggester> RecipeSuggester for Option<S> {
+    async fn suggest(&self, prompt: &str) -> Result<String, SuggestRecipeError> {
+        match self {
+            Some(suggester) => suggester.suggest(prompt).await,
+            None => Err(SuggestRecipeError::Disabled),
+        }
+    }
+}
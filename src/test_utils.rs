@@ -0,0 +1,364 @@
+pub mod contract;
+
+use std::{
+    any::Any,
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::persistance::{
+    analytics::{AnalyticsRepository, GetUsageError, RecordUsageError, UsageEntity},
+    cooking_log::{
+        CookingHistoryError, CookingLogEntity, CookingLogRepository, LogCookingError,
+        NewCookingLogEntity,
+    },
+    deletion::{DeletionEntity, DeletionRepository, ListDeletionsError, RecipeDeletionEntity},
+    federation::{
+        FederatedRecipesError, FederationRepository, ListInstancesError, MirrorRecipesError,
+        MirrorSummaryEntity, RecipeOriginEntity, RecipeOriginError, RegisterInstanceError,
+        RemoteInstanceEntity, RemoteRecipeEntity, RemoveInstanceError, SetFederationOptOutError,
+    },
+    ingredient::{
+        DeleteDensityError, IngredientDensityEntity, IngredientRecipeEntity,
+        IngredientRecipesError, IngredientRepository, IngredientUsageEntity, ListDensitiesError,
+        ListIngredientsError, RenameIngredientError, SetDensityError,
+    },
+    integrity::{
+        CheckIntegrityError, IntegrityIssuesEntity, IntegrityRepository, RepairIntegrityError,
+    },
+    outbox::{
+        EventsSinceError, LatestEventIdError, MarkPublishedError, OutboxEventEntity,
+        OutboxRepository, UnpublishedEventsError,
+    },
+    recipe::{
+        AutocompleteError, CreateRecipeError, DeleteRecipeError, ForkRecipeError, GetRecipeError,
+        GetRecipesError, ImportRecipesError, IngredientMatchEntity, ListForksError,
+        ListRecipeError, MealType, MergeRecipesError, MutableRecipeEntity, NameKind,
+        PopularRecipeEntity, PopularRecipesError, RandomRecipeArguments, RandomRecipeError,
+        RecipeEntity, RecipeForkEntity, RecipeMetadataEntity, RecipeNameEntity, RecipeRepository,
+        RecordViewsError, ResolvePublicIdError, SearchByIngredientsError, SearchRecipeError,
+        SearchRecipesArguments, SetArchivedError, SimilarNamesError, UpdateMetadataError,
+        UpdateRecipeError,
+    },
+    retention::{
+        CountExpiredError, ExpiredCountsEntity, PurgeExpiredError, RetentionCutoffsEntity,
+        RetentionRepository,
+    },
+    saved_search::{
+        DeleteSavedSearchError, GetSavedSearchError, ListSavedSearchesError, SaveSearchError,
+        SavedCriteriaEntity, SavedSearchEntity, SavedSearchRepository,
+    },
+    share_link::{
+        CreateShareLinkError, ListShareLinksError, ResolveShareLinkError, RevokeShareLinkError,
+        ShareLinkEntity, ShareLinkRepository,
+    },
+    snapshot::{
+        CreateSnapshotError, ListSnapshotsError, SnapshotEntity, SnapshotRecipeEntity,
+        SnapshotRecipesError, SnapshotRepository,
+    },
+    tag::{ListTagsError, RecipeTagsError, SetRecipeTagsError, TagRepository, TagUsageEntity},
+};
+
+/// Call made to a [`MockRepository`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockCall {
+    /// Name of the repository method, like `get_recipe`
+    pub method: &'static str,
+    /// Debug representation of the arguments as a tuple, like `(1,)`
+    pub arguments: String,
+}
+
+/// Repository implementing every repository trait with scripted responses, for tests which don't
+/// want to depend on a database. Responses are queued per method with the `on_*` methods and
+/// handed out in order, a call without a queued response panics. Clones share their responses
+/// and recorded calls.
+#[derive(Debug, Clone, Default)]
+pub struct MockRepository {
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    calls: Vec<MockCall>,
+    responses: HashMap<&'static str, VecDeque<Box<dyn Any + Send>>>,
+}
+
+impl MockRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every call made so far, oldest first
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.state().calls.clone()
+    }
+
+    /// Calls made to a single method so far, oldest first
+    pub fn calls_to(&self, method: &str) -> Vec<MockCall> {
+        self.state()
+            .calls
+            .iter()
+            .filter(|call| call.method == method)
+            .cloned()
+            .collect()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().expect("Mock state lock poisoned")
+    }
+
+    fn push<T: Send + 'static>(&self, method: &'static str, response: T) {
+        self.state()
+            .responses
+            .entry(method)
+            .or_default()
+            .push_back(Box::new(response));
+    }
+
+    fn respond<T: 'static>(&self, method: &'static str, arguments: String) -> T {
+        let mut state = self.state();
+        state.calls.push(MockCall { method, arguments });
+
+        let response = state
+            .responses
+            .get_mut(method)
+            .and_then(VecDeque::pop_front)
+            .unwrap_or_else(|| panic!("No response scripted for `{method}`"));
+
+        *response
+            .downcast()
+            .expect("Scripted responses always match their method")
+    }
+}
+
+/// Dinner recipe without ingredients or any of the optional details, for scripting responses
+pub fn recipe_entity(recipe_id: i32, name: &str) -> RecipeEntity {
+    RecipeEntity {
+        recipe_id,
+        name: name.to_string(),
+        description: None,
+        ingredients: vec![],
+        cooking_time: None,
+        meal_type: MealType::Dinner,
+        video_url: None,
+        video_title: None,
+        video_thumbnail_url: None,
+        pairing: None,
+        pairing_kind: None,
+        forked_from: None,
+        public_id: Uuid::from_u128(recipe_id as u128),
+        last_cooked_at: None,
+    }
+}
+
+/// Implements a repository trait for [`MockRepository`], next to an `on_*` method per repository
+/// method to script its responses
+macro_rules! mock_repository {
+    ($trait:ident { $(fn $method:ident / $on:ident($($arg:ident: $ty:ty),*) -> $ret:ty;)* }) => {
+        impl MockRepository {
+            $(
+                #[doc = concat!("Queues a response for the next call to `", stringify!($method), "`")]
+                pub fn $on(&self, response: $ret) -> &Self {
+                    self.push(stringify!($method), response);
+                    self
+                }
+            )*
+        }
+
+        impl $trait for MockRepository {
+            $(
+                async fn $method(&self, $($arg: $ty),*) -> $ret {
+                    self.respond(stringify!($method), format!("{:?}", ($(&$arg,)*)))
+                }
+            )*
+        }
+    };
+}
+
+mock_repository!(RecipeRepository {
+    fn list_recipes / on_list_recipes(include_ingredients: bool, archived: bool)
+        -> Result<Vec<RecipeEntity>, ListRecipeError>;
+    fn get_recipe / on_get_recipe(recipe_id: i32) -> Result<RecipeEntity, GetRecipeError>;
+    fn resolve_public_id / on_resolve_public_id(public_id: Uuid)
+        -> Result<i32, ResolvePublicIdError>;
+    fn get_recipes / on_get_recipes(recipe_ids: Vec<i32>, include_ingredients: bool)
+        -> Result<Vec<RecipeEntity>, GetRecipesError>;
+    fn create_recipe / on_create_recipe(entity: MutableRecipeEntity)
+        -> Result<RecipeEntity, CreateRecipeError>;
+    fn import_recipes / on_import_recipes(entities: Vec<MutableRecipeEntity>)
+        -> Result<Vec<i32>, ImportRecipesError>;
+    fn update_recipe / on_update_recipe(recipe_id: i32, entity: MutableRecipeEntity)
+        -> Result<RecipeEntity, UpdateRecipeError>;
+    fn update_metadata / on_update_metadata(changes: Vec<RecipeMetadataEntity>)
+        -> Result<Vec<i32>, UpdateMetadataError>;
+    fn delete_recipe / on_delete_recipe(recipe_id: i32, deletion: DeletionEntity)
+        -> Result<RecipeEntity, DeleteRecipeError>;
+    fn fork_recipe / on_fork_recipe(recipe_id: i32) -> Result<RecipeEntity, ForkRecipeError>;
+    fn list_forks / on_list_forks(recipe_id: i32)
+        -> Result<Vec<RecipeForkEntity>, ListForksError>;
+    fn merge_recipes / on_merge_recipes(
+        target_id: i32,
+        source_id: i32,
+        entity: MutableRecipeEntity
+    ) -> Result<RecipeEntity, MergeRecipesError>;
+    fn search_recipes / on_search_recipes(args: SearchRecipesArguments)
+        -> Result<Vec<RecipeEntity>, SearchRecipeError>;
+    fn similar_names / on_similar_names(term: String, kind: NameKind, archived: bool, limit: i64)
+        -> Result<Vec<String>, SimilarNamesError>;
+    fn autocomplete / on_autocomplete(prefix: String, limit: i64)
+        -> Result<Vec<RecipeNameEntity>, AutocompleteError>;
+    fn set_archived / on_set_archived(recipe_id: i32, archived: bool)
+        -> Result<(), SetArchivedError>;
+    fn search_by_ingredients / on_search_by_ingredients(ingredient_names: Vec<String>, limit: i64)
+        -> Result<Vec<IngredientMatchEntity>, SearchByIngredientsError>;
+    fn random_recipe / on_random_recipe(args: RandomRecipeArguments)
+        -> Result<RecipeEntity, RandomRecipeError>;
+    fn record_views / on_record_views(views: Vec<(i32, i64)>) -> Result<(), RecordViewsError>;
+    fn popular_recipes / on_popular_recipes(window_days: i32, limit: i64)
+        -> Result<Vec<PopularRecipeEntity>, PopularRecipesError>;
+});
+
+mock_repository!(CookingLogRepository {
+    fn log_cooking / on_log_cooking(recipe_id: i32, entity: NewCookingLogEntity)
+        -> Result<CookingLogEntity, LogCookingError>;
+    fn cooking_history / on_cooking_history(recipe_id: i32)
+        -> Result<Vec<CookingLogEntity>, CookingHistoryError>;
+});
+
+mock_repository!(ShareLinkRepository {
+    fn create_share_link / on_create_share_link(recipe_id: i32, token: String)
+        -> Result<ShareLinkEntity, CreateShareLinkError>;
+    fn list_share_links / on_list_share_links(recipe_id: i32)
+        -> Result<Vec<ShareLinkEntity>, ListShareLinksError>;
+    fn revoke_share_link / on_revoke_share_link(recipe_id: i32, share_link_id: i32)
+        -> Result<(), RevokeShareLinkError>;
+    fn resolve_share_link / on_resolve_share_link(token: &str)
+        -> Result<i32, ResolveShareLinkError>;
+});
+
+mock_repository!(OutboxRepository {
+    fn unpublished_events / on_unpublished_events(limit: i64)
+        -> Result<Vec<OutboxEventEntity>, UnpublishedEventsError>;
+    fn events_since / on_events_since(outbox_id: i64, limit: i64)
+        -> Result<Vec<OutboxEventEntity>, EventsSinceError>;
+    fn latest_event_id / on_latest_event_id(recipe_id: i32)
+        -> Result<Option<i64>, LatestEventIdError>;
+    fn mark_published / on_mark_published(outbox_ids: Vec<i64>) -> Result<(), MarkPublishedError>;
+});
+
+mock_repository!(FederationRepository {
+    fn register_instance / on_register_instance(url: String, token: Option<Vec<u8>>)
+        -> Result<RemoteInstanceEntity, RegisterInstanceError>;
+    fn list_instances / on_list_instances() -> Result<Vec<RemoteInstanceEntity>, ListInstancesError>;
+    fn remove_instance / on_remove_instance(remote_instance_id: i32)
+        -> Result<(), RemoveInstanceError>;
+    fn federated_recipes / on_federated_recipes()
+        -> Result<Vec<RecipeEntity>, FederatedRecipesError>;
+    fn mirror_recipes / on_mirror_recipes(
+        remote_instance_id: i32,
+        recipes: Vec<RemoteRecipeEntity>
+    ) -> Result<MirrorSummaryEntity, MirrorRecipesError>;
+    fn recipe_origin / on_recipe_origin(recipe_id: i32)
+        -> Result<Option<RecipeOriginEntity>, RecipeOriginError>;
+    fn set_federation_opt_out / on_set_federation_opt_out(recipe_id: i32, opt_out: bool)
+        -> Result<(), SetFederationOptOutError>;
+});
+
+mock_repository!(IngredientRepository {
+    fn list_ingredients / on_list_ingredients()
+        -> Result<Vec<IngredientUsageEntity>, ListIngredientsError>;
+    fn ingredient_recipes / on_ingredient_recipes(name: String)
+        -> Result<Vec<IngredientRecipeEntity>, IngredientRecipesError>;
+    fn rename_ingredient / on_rename_ingredient(from: String, to: String)
+        -> Result<u64, RenameIngredientError>;
+    fn list_densities / on_list_densities()
+        -> Result<Vec<IngredientDensityEntity>, ListDensitiesError>;
+    fn set_density / on_set_density(name: String, grams_per_milliliter: f32)
+        -> Result<IngredientDensityEntity, SetDensityError>;
+    fn delete_density / on_delete_density(name: String) -> Result<(), DeleteDensityError>;
+});
+
+mock_repository!(SavedSearchRepository {
+    fn save_search / on_save_search(name: String, criteria: SavedCriteriaEntity)
+        -> Result<SavedSearchEntity, SaveSearchError>;
+    fn list_saved_searches / on_list_saved_searches()
+        -> Result<Vec<SavedSearchEntity>, ListSavedSearchesError>;
+    fn get_saved_search / on_get_saved_search(saved_search_id: i32)
+        -> Result<SavedSearchEntity, GetSavedSearchError>;
+    fn delete_saved_search / on_delete_saved_search(saved_search_id: i32)
+        -> Result<(), DeleteSavedSearchError>;
+});
+
+mock_repository!(TagRepository {
+    fn list_tags / on_list_tags() -> Result<Vec<TagUsageEntity>, ListTagsError>;
+    fn recipe_tags / on_recipe_tags(recipe_id: i32) -> Result<Vec<String>, RecipeTagsError>;
+    fn set_recipe_tags / on_set_recipe_tags(recipe_id: i32, tags: Vec<String>)
+        -> Result<Vec<String>, SetRecipeTagsError>;
+});
+
+mock_repository!(SnapshotRepository {
+    fn create_snapshot / on_create_snapshot() -> Result<SnapshotEntity, CreateSnapshotError>;
+    fn list_snapshots / on_list_snapshots() -> Result<Vec<SnapshotEntity>, ListSnapshotsError>;
+    fn snapshot_recipes / on_snapshot_recipes(snapshot_id: i32)
+        -> Result<Vec<SnapshotRecipeEntity>, SnapshotRecipesError>;
+});
+
+mock_repository!(DeletionRepository {
+    fn list_deletions / on_list_deletions(recipe_id: Option<i32>, limit: i64)
+        -> Result<Vec<RecipeDeletionEntity>, ListDeletionsError>;
+});
+
+mock_repository!(RetentionRepository {
+    fn count_expired / on_count_expired(cutoffs: RetentionCutoffsEntity)
+        -> Result<ExpiredCountsEntity, CountExpiredError>;
+    fn purge_expired / on_purge_expired(cutoffs: RetentionCutoffsEntity)
+        -> Result<ExpiredCountsEntity, PurgeExpiredError>;
+});
+
+mock_repository!(AnalyticsRepository {
+    fn record_usage / on_record_usage(routes: Vec<(String, i64)>, search_terms: Vec<(String, i64)>)
+        -> Result<(), RecordUsageError>;
+    fn get_usage / on_get_usage(since: NaiveDate, min_searches: i64, limit: i64)
+        -> Result<UsageEntity, GetUsageError>;
+});
+
+mock_repository!(IntegrityRepository {
+    fn check_integrity / on_check_integrity()
+        -> Result<IntegrityIssuesEntity, CheckIntegrityError>;
+    fn repair_integrity / on_repair_integrity(issues: IntegrityIssuesEntity)
+        -> Result<(), RepairIntegrityError>;
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert2::{check, let_assert};
+
+    #[tokio::test]
+    async fn it_hands_out_scripted_responses_in_order() {
+        let repository = MockRepository::new();
+        repository
+            .on_resolve_public_id(Ok(1))
+            .on_resolve_public_id(Err(ResolvePublicIdError::NotFound));
+
+        let_assert!(Ok(1) = repository.resolve_public_id(Uuid::nil()).await);
+        let_assert!(
+            Err(ResolvePublicIdError::NotFound) = repository.resolve_public_id(Uuid::nil()).await
+        );
+
+        check!(repository.calls_to("resolve_public_id").len() == 2);
+        check!(repository.calls()[0].arguments == format!("({},)", Uuid::nil()));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "No response scripted for `delete_recipe`")]
+    async fn it_panics_on_unscripted_calls() {
+        let _ = MockRepository::new()
+            .delete_recipe(1, DeletionEntity::default())
+            .await;
+    }
+}
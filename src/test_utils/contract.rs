@@ -0,0 +1,335 @@
+//! Behaviour every [`RecipeRepository`] is expected to have, independent of where it stores its
+//! recipes. Each check takes a repository without any recipes in it and panics when the contract
+//! is broken. Use [`recipe_repository_contract!`](crate::recipe_repository_contract) to turn all
+//! of them into tests for an implementation.
+
+use std::time::Duration;
+
+use uuid::Uuid;
+
+use crate::persistance::{
+    deletion::DeletionEntity,
+    recipe::{
+        DeleteRecipeError, ForkRecipeError, GetRecipeError, ListForksError, MealType,
+        MergeRecipesError, MutableIngredientEntity, MutableRecipeEntity, QuantityType,
+        RecipeRepository, ResolvePublicIdError, UpdateRecipeError,
+    },
+};
+
+/// Id no repository hands out to a recipe in these checks
+const MISSING_ID: i32 = i32::MAX;
+
+fn recipe(name: &str, ingredients: &[&str]) -> MutableRecipeEntity {
+    MutableRecipeEntity {
+        name: name.to_string(),
+        description: None,
+        ingredients: ingredients
+            .iter()
+            .map(|ingredient| MutableIngredientEntity {
+                name: ingredient.to_string(),
+                quantity_type: QuantityType::Count,
+                quantity: 1.0,
+            })
+            .collect(),
+        cooking_time: Some(Duration::from_secs(30 * 60)),
+        meal_type: MealType::Dinner,
+        video_url: None,
+        video_title: None,
+        video_thumbnail_url: None,
+        pairing: None,
+        pairing_kind: None,
+    }
+}
+
+async fn create<R: RecipeRepository>(repository: &R, name: &str, ingredients: &[&str]) -> i32 {
+    repository
+        .create_recipe(recipe(name, ingredients))
+        .await
+        .expect("Failed to create recipe")
+        .recipe_id
+}
+
+async fn ingredient_names<R: RecipeRepository>(repository: &R, recipe_id: i32) -> Vec<String> {
+    repository
+        .get_recipe(recipe_id)
+        .await
+        .expect("Failed to get recipe")
+        .ingredients
+        .into_iter()
+        .map(|ingredient| ingredient.name)
+        .collect()
+}
+
+/// Created recipes can be read back by both their id and public id, ingredients in the order
+/// they were given
+pub async fn stores_created_recipes<R: RecipeRepository>(repository: R) {
+    let created = repository
+        .create_recipe(recipe("Soup", &["Leek", "Potato", "Stock"]))
+        .await
+        .expect("Failed to create recipe");
+
+    let recipe = repository
+        .get_recipe(created.recipe_id)
+        .await
+        .expect("Failed to get recipe");
+    assert_eq!(recipe.name, "Soup");
+    assert_eq!(recipe.cooking_time, Some(Duration::from_secs(30 * 60)));
+    assert_eq!(
+        ingredient_names(&repository, created.recipe_id).await,
+        ["Leek", "Potato", "Stock"]
+    );
+
+    let resolved = repository
+        .resolve_public_id(created.public_id)
+        .await
+        .expect("Failed to resolve public id");
+    assert_eq!(resolved, created.recipe_id);
+}
+
+/// Every operation on a single recipe reports a missing recipe as `NotFound`
+pub async fn reports_missing_recipes_as_not_found<R: RecipeRepository>(repository: R) {
+    let existing = create(&repository, "Soup", &[]).await;
+
+    assert!(matches!(
+        repository.get_recipe(MISSING_ID).await,
+        Err(GetRecipeError::NotFound)
+    ));
+    assert!(matches!(
+        repository.resolve_public_id(Uuid::nil()).await,
+        Err(ResolvePublicIdError::NotFound)
+    ));
+    assert!(matches!(
+        repository
+            .update_recipe(MISSING_ID, recipe("Stew", &[]))
+            .await,
+        Err(UpdateRecipeError::NotFound)
+    ));
+    assert!(matches!(
+        repository
+            .delete_recipe(MISSING_ID, DeletionEntity::default())
+            .await,
+        Err(DeleteRecipeError::NotFound)
+    ));
+    assert!(matches!(
+        repository.fork_recipe(MISSING_ID).await,
+        Err(ForkRecipeError::NotFound)
+    ));
+    assert!(matches!(
+        repository.list_forks(MISSING_ID).await,
+        Err(ListForksError::NotFound)
+    ));
+    assert!(matches!(
+        repository
+            .merge_recipes(MISSING_ID, existing, recipe("Stew", &[]))
+            .await,
+        Err(MergeRecipesError::NotFound)
+    ));
+    assert!(matches!(
+        repository
+            .merge_recipes(existing, MISSING_ID, recipe("Stew", &[]))
+            .await,
+        Err(MergeRecipesError::NotFound)
+    ));
+}
+
+/// Imported recipes get ids in the order they were given and are stored exactly as given
+pub async fn imports_recipes_in_order<R: RecipeRepository>(repository: R) {
+    let existing = create(&repository, "Soup", &[]).await;
+
+    let mut stew = recipe("Stew", &["Beef", "Carrot"]);
+    stew.description = Some("Brown the beef\tthen simmer.\nSalt \\ pepper".to_string());
+    stew.cooking_time = None;
+
+    let recipe_ids = repository
+        .import_recipes(vec![recipe("Salad", &[]), stew])
+        .await
+        .expect("Failed to import recipes");
+
+    assert_eq!(recipe_ids.len(), 2);
+    assert!(recipe_ids.iter().all(|recipe_id| *recipe_id > existing));
+
+    let salad = repository
+        .get_recipe(recipe_ids[0])
+        .await
+        .expect("Failed to get recipe");
+    assert_eq!(salad.name, "Salad");
+    assert!(salad.ingredients.is_empty());
+
+    let stew = repository
+        .get_recipe(recipe_ids[1])
+        .await
+        .expect("Failed to get recipe");
+    assert_eq!(stew.name, "Stew");
+    assert_eq!(
+        stew.description.as_deref(),
+        Some("Brown the beef\tthen simmer.\nSalt \\ pepper")
+    );
+    assert_eq!(stew.cooking_time, None);
+    assert_eq!(
+        ingredient_names(&repository, recipe_ids[1]).await,
+        ["Beef", "Carrot"]
+    );
+}
+
+/// Recipes fetched in bulk come back in the requested order, missing ids are skipped
+pub async fn gets_recipes_in_the_requested_order<R: RecipeRepository>(repository: R) {
+    let first = create(&repository, "Soup", &[]).await;
+    let second = create(&repository, "Stew", &[]).await;
+    let third = create(&repository, "Salad", &[]).await;
+
+    let recipes = repository
+        .get_recipes(vec![third, MISSING_ID, first, second], false)
+        .await
+        .expect("Failed to get recipes");
+
+    let ids = recipes
+        .iter()
+        .map(|recipe| recipe.recipe_id)
+        .collect::<Vec<_>>();
+    assert_eq!(ids, [third, first, second]);
+}
+
+/// Updating a recipe replaces all of its ingredients rather than adding to them
+pub async fn replaces_ingredients_on_update<R: RecipeRepository>(repository: R) {
+    let recipe_id = create(&repository, "Soup", &["Leek", "Potato"]).await;
+
+    repository
+        .update_recipe(recipe_id, recipe("Soup", &["Carrot"]))
+        .await
+        .expect("Failed to update recipe");
+
+    assert_eq!(ingredient_names(&repository, recipe_id).await, ["Carrot"]);
+}
+
+/// Deleting a recipe returns it and leaves the other recipes and their ingredients alone
+pub async fn deletes_only_the_given_recipe<R: RecipeRepository>(repository: R) {
+    let deleted = create(&repository, "Soup", &["Leek"]).await;
+    let kept = create(&repository, "Stew", &["Beef"]).await;
+
+    let recipe = repository
+        .delete_recipe(deleted, DeletionEntity::default())
+        .await
+        .expect("Failed to delete recipe");
+    assert_eq!(recipe.name, "Soup");
+    assert_eq!(recipe.ingredients[0].name, "Leek");
+
+    assert!(matches!(
+        repository.get_recipe(deleted).await,
+        Err(GetRecipeError::NotFound)
+    ));
+    assert_eq!(ingredient_names(&repository, kept).await, ["Beef"]);
+}
+
+/// Forks, including forks of forks, are listed by recipe id
+pub async fn lists_forks_by_recipe_id<R: RecipeRepository>(repository: R) {
+    let base = create(&repository, "Pizza", &["Dough"]).await;
+    let fork = repository
+        .fork_recipe(base)
+        .await
+        .expect("Failed to fork recipe");
+    let nested = repository
+        .fork_recipe(fork.recipe_id)
+        .await
+        .expect("Failed to fork recipe");
+
+    assert_eq!(fork.forked_from, Some(base));
+    assert_eq!(
+        ingredient_names(&repository, fork.recipe_id).await,
+        ["Dough"]
+    );
+
+    let forks = repository
+        .list_forks(base)
+        .await
+        .expect("Failed to list forks");
+    let forks = forks
+        .iter()
+        .map(|fork| (fork.recipe_id, fork.forked_from))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        forks,
+        [(fork.recipe_id, base), (nested.recipe_id, fork.recipe_id)]
+    );
+}
+
+/// A merge which fails halfway leaves the target recipe exactly as it was
+pub async fn leaves_the_target_untouched_when_a_merge_fails<R: RecipeRepository>(repository: R) {
+    let target = create(&repository, "Lasagne", &["Pasta", "Tomato"]).await;
+
+    let result = repository
+        .merge_recipes(target, MISSING_ID, recipe("Merged", &["Cheese"]))
+        .await;
+    assert!(matches!(result, Err(MergeRecipesError::NotFound)));
+
+    let recipe = repository
+        .get_recipe(target)
+        .await
+        .expect("Failed to get recipe");
+    assert_eq!(recipe.name, "Lasagne");
+    assert_eq!(
+        ingredient_names(&repository, target).await,
+        ["Pasta", "Tomato"]
+    );
+}
+
+/// Popular recipes are ordered by their views, views for missing recipes are dropped
+pub async fn orders_popular_recipes_by_views<R: RecipeRepository>(repository: R) {
+    let soup = create(&repository, "Soup", &[]).await;
+    let stew = create(&repository, "Stew", &[]).await;
+
+    repository
+        .record_views(vec![(soup, 2), (stew, 3), (MISSING_ID, 10)])
+        .await
+        .expect("Failed to record views");
+    repository
+        .record_views(vec![(soup, 2)])
+        .await
+        .expect("Failed to record views");
+
+    let popular = repository
+        .popular_recipes(7, 10)
+        .await
+        .expect("Failed to list popular recipes");
+    let popular = popular
+        .iter()
+        .map(|popular| (popular.recipe.recipe_id, popular.views))
+        .collect::<Vec<_>>();
+    assert_eq!(popular, [(soup, 4), (stew, 3)]);
+}
+
+/// Generates a test per contract check. Takes the attribute each test is marked with and a
+/// closure building an empty repository out of the test's arguments:
+///
+/// ```ignore
+/// recipe_repository_contract!(
+///     #[sqlx::test(migrator = "MIGRATOR")]
+///     |pool: PgPool| Postgres::new(pool)
+/// );
+/// ```
+#[macro_export]
+macro_rules! recipe_repository_contract {
+    (#[$attr:meta] |$($arg:ident: $ty:ty),*| $repository:expr) => {
+        $crate::recipe_repository_contract!(
+            @tests [
+                stores_created_recipes,
+                imports_recipes_in_order,
+                reports_missing_recipes_as_not_found,
+                gets_recipes_in_the_requested_order,
+                replaces_ingredients_on_update,
+                deletes_only_the_given_recipe,
+                lists_forks_by_recipe_id,
+                leaves_the_target_untouched_when_a_merge_fails,
+                orders_popular_recipes_by_views
+            ]
+            #[$attr] ($($arg: $ty),*) $repository
+        );
+    };
+    (@tests [$($check:ident),*] #[$attr:meta] $arguments:tt $repository:expr) => {
+        $(
+            #[$attr]
+            async fn $check $arguments {
+                $crate::test_utils::contract::$check($repository).await;
+            }
+        )*
+    };
+}
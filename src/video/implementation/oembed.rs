@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use eyre::Context;
+use reqwest::{Client, StatusCode, Url};
+use serde::Deserialize;
+
+use crate::video::resolver::{ResolveVideoError, VideoMetadata, VideoResolver};
+
+/// How long to wait on the oEmbed provider before saving a recipe without video metadata
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub struct Oembed {
+    client: Client,
+    endpoint: Url,
+}
+
+#[derive(Debug, Deserialize)]
+struct OembedResponse {
+    title: Option<String>,
+    thumbnail_url: Option<String>,
+    /// Set by aggregating providers like noembed when they don't know the url
+    error: Option<String>,
+}
+
+impl Oembed {
+    /// Creates a resolver for an oEmbed endpoint like `https://noembed.com/embed`
+    pub fn new(endpoint: &str) -> eyre::Result<Self> {
+        let endpoint = endpoint.parse().wrap_err("Failed to parse oEmbed url")?;
+
+        let client = Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .wrap_err("Failed to build HTTP client")?;
+
+        Ok(Self { client, endpoint })
+    }
+}
+
+impl VideoResolver for Oembed {
+    async fn resolve(&self, url: &str) -> Result<VideoMetadata, ResolveVideoError> {
+        let response = self
+            .client
+            .get(self.endpoint.clone())
+            .query(&[("url", url), ("format", "json")])
+            .send()
+            .await
+            .wrap_err("Failed to reach oEmbed provider")?;
+
+        if matches!(
+            response.status(),
+            StatusCode::NOT_FOUND | StatusCode::NOT_IMPLEMENTED
+        ) {
+            return Err(ResolveVideoError::Unsupported(url.to_string()));
+        }
+
+        let body = response
+            .error_for_status()
+            .wrap_err("oEmbed provider returned an error")?
+            .json::<OembedResponse>()
+            .await
+            .wrap_err("Failed to parse oEmbed response")?;
+
+        if body.error.is_some() {
+            return Err(ResolveVideoError::Unsupported(url.to_string()));
+        }
+
+        Ok(VideoMetadata {
+            title: body.title,
+            thumbnail_url: body.thumbnail_url,
+        })
+    }
+}
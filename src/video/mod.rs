@@ -0,0 +1,2 @@
+pub mod implementation;
+pub mod resolver;
@@ -0,0 +1,40 @@
+use thiserror::Error;
+
+/// Metadata of a video, used by clients to render an embedded player
+#[derive(Debug, Default)]
+pub struct VideoMetadata {
+    pub title: Option<String>,
+    pub thumbnail_url: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum ResolveVideoError {
+    #[error("An unknown error occured: {0:}")]
+    Unknown(
+        #[from]
+        #[source]
+        eyre::Report,
+    ),
+    #[error("No metadata is available for `{0}`")]
+    Unsupported(String),
+    #[error("Resolving videos is not configured on this instance")]
+    Disabled,
+}
+
+pub trait VideoResolver: std::fmt::Debug + Clone + Send + Sync + 'static {
+    fn resolve(
+        &self,
+        url: &str,
+    ) -> impl Future<Output = Result<VideoMetadata, ResolveVideoError>> + Send;
+}
+
+/// Allows resolving videos to be optional, an unconfigured resolver fails every lookup with
+/// [`ResolveVideoError::Disabled`]
+impl<R: VideoResolver> VideoResolver for Option<R> {
+    async fn resolve(&self, url: &str) -> Result<VideoMetadata, ResolveVideoError> {
+        match self {
+            Some(resolver) => resolver.resolve(url).await,
+            None => Err(ResolveVideoError::Disabled),
+        }
+    }
+}